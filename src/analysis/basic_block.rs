@@ -1,16 +1,22 @@
 use std::collections::BTreeSet;
 use std::iter::FromIterator;
 
-use crate::model::binder::Binder;
-use crate::mir::branches;
 use crate::mir::InstructionMIR;
-use crate::mir::compiler::InstructionMIRCompiler;
 use crate::mir::InstructionMIRData;
+use crate::analysis;
+
+#[cfg(test)]
+use crate::model::binder::Binder;
+#[cfg(test)]
+use crate::mir::compiler::InstructionMIRCompiler;
+#[cfg(test)]
 use crate::model::function::{Function, FunctionDeclaration};
+#[cfg(test)]
 use crate::model::instruction::Instruction;
+#[cfg(test)]
 use crate::model::typesystem::{TypeId, TypeStorage};
+#[cfg(test)]
 use crate::model::verifier::Verifier;
-use crate::analysis;
 
 pub struct BasicBlock {
     pub start_offset: usize,
@@ -18,6 +24,7 @@ pub struct BasicBlock {
 }
 
 impl BasicBlock {
+    #[allow(dead_code)]
     pub fn first(&self) -> usize {
         *self.instructions.first().unwrap()
     }
@@ -26,7 +33,7 @@ impl BasicBlock {
         *self.instructions.last().unwrap()
     }
 
-    pub fn create_blocks(instructions: &Vec<InstructionMIR>) -> Vec<BasicBlock> {
+    pub fn create_blocks(instructions: &[InstructionMIR]) -> Vec<BasicBlock> {
         let mut blocks = Vec::new();
         let leaders = BasicBlock::find_leaders(instructions);
 
@@ -52,7 +59,7 @@ impl BasicBlock {
         blocks
     }
 
-    fn find_leaders(instructions: &Vec<InstructionMIR>) -> Vec<usize> {
+    fn find_leaders(instructions: &[InstructionMIR]) -> Vec<usize> {
         let branch_label_mapping = analysis::create_label_mapping(instructions);
 
         // A leader is the start of a basic block
@@ -83,7 +90,7 @@ impl BasicBlock {
             }
         }
 
-        Vec::from_iter(leaders.into_iter())
+        Vec::from_iter(leaders)
     }
 
     pub fn linearize(blocks: &Vec<BasicBlock>) -> Vec<usize> {
@@ -97,7 +104,8 @@ impl BasicBlock {
     }
 }
 
-fn get_instructions(instructions: &Vec<InstructionMIR>, indices: &Vec<usize>) -> Vec<InstructionMIR> {
+#[allow(dead_code)]
+fn get_instructions(instructions: &[InstructionMIR], indices: &[usize]) -> Vec<InstructionMIR> {
     indices.iter().map(|index| instructions[*index].clone()).collect()
 }
 
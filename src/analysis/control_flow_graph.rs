@@ -2,17 +2,22 @@ use std::collections::{HashMap, HashSet};
 use std::iter::FromIterator;
 
 use crate::analysis::basic_block::BasicBlock;
-use crate::model::binder::Binder;
-use crate::mir::branches;
-use crate::compiler::ir::BranchLabel;
 use crate::mir::InstructionMIR;
-use crate::mir::compiler::InstructionMIRCompiler;
 use crate::mir::InstructionMIRData;
+use crate::analysis;
+
+#[cfg(test)]
+use crate::model::binder::Binder;
+#[cfg(test)]
+use crate::mir::compiler::InstructionMIRCompiler;
+#[cfg(test)]
 use crate::model::function::{Function, FunctionDeclaration};
+#[cfg(test)]
 use crate::model::instruction::Instruction;
+#[cfg(test)]
 use crate::model::typesystem::{TypeId, TypeStorage};
+#[cfg(test)]
 use crate::model::verifier::Verifier;
-use crate::analysis;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct ControlFlowEdge {
@@ -22,12 +27,13 @@ pub struct ControlFlowEdge {
 
 pub struct ControlFlowGraph {
     pub vertices: Vec<usize>,
+    #[allow(dead_code)]
     pub edges: HashMap<usize, HashSet<ControlFlowEdge>>,
     pub back_edges: HashMap<usize, HashSet<ControlFlowEdge>>
 }
 
 impl ControlFlowGraph {
-    pub fn new(instructions: &Vec<InstructionMIR>, blocks: &Vec<BasicBlock>) -> ControlFlowGraph {
+    pub fn new(instructions: &[InstructionMIR], blocks: &[BasicBlock]) -> ControlFlowGraph {
         let branch_label_mapping = analysis::create_label_mapping(instructions);
 
         let vertices = (0..blocks.len()).collect::<Vec<_>>();
@@ -40,8 +46,8 @@ impl ControlFlowGraph {
         }
 
         let mut add_edge = |from, to| {
-            edges.entry(from).or_insert_with(|| HashSet::new()).insert(ControlFlowEdge { from, to });
-            back_edges.entry(to).or_insert_with(|| HashSet::new()).insert(ControlFlowEdge { from: to, to: from });
+            edges.entry(from).or_insert_with(HashSet::new).insert(ControlFlowEdge { from, to });
+            back_edges.entry(to).or_insert_with(HashSet::new).insert(ControlFlowEdge { from: to, to: from });
         };
 
         for (block_index, block) in blocks.iter().enumerate() {
@@ -69,7 +75,8 @@ impl ControlFlowGraph {
         }
     }
 
-    pub fn print_graph(&self, instructions: &Vec<InstructionMIR>, blocks: &Vec<BasicBlock>) {
+    #[allow(dead_code)]
+    pub fn print_graph(&self, instructions: &[InstructionMIR], blocks: &[BasicBlock]) {
         for vertex_index in &self.vertices {
             let block = &blocks[*vertex_index];
             println!(
@@ -152,6 +159,7 @@ fn test_branches1() {
     );
 }
 
+#[allow(dead_code)]
 fn extract_edges(graph: &ControlFlowGraph, index: usize) -> Vec<ControlFlowEdge> {
     if let Some(edges) = graph.edges.get(&index) {
         let mut edges = Vec::from_iter(edges.iter().cloned());
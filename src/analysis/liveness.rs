@@ -1,15 +1,25 @@
+#![cfg_attr(test, allow(clippy::needless_borrow))]
+
 use std::collections::{HashMap, HashSet};
 use std::iter::FromIterator;
 
 use crate::analysis::basic_block::BasicBlock;
 use crate::analysis::control_flow_graph::ControlFlowGraph;
 use crate::analysis::VirtualRegister;
+use crate::mir::InstructionMIR;
+use crate::mir::compiler::MIRCompilationResult;
+
+#[cfg(test)]
 use crate::model::binder::Binder;
-use crate::mir::{branches, InstructionMIR, RegisterMIR};
-use crate::mir::compiler::{InstructionMIRCompiler, MIRCompilationResult};
+#[cfg(test)]
+use crate::mir::compiler::InstructionMIRCompiler;
+#[cfg(test)]
 use crate::model::function::{Function, FunctionDeclaration};
+#[cfg(test)]
 use crate::model::instruction::Instruction;
+#[cfg(test)]
 use crate::model::typesystem::{TypeId, TypeStorage};
+#[cfg(test)]
 use crate::model::verifier::Verifier;
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
@@ -66,7 +76,7 @@ pub fn compute(compilation_result: &MIRCompilationResult,
 }
 
 fn get_live_interval(register: &VirtualRegister, alive_at: &HashSet<usize>) -> LiveInterval {
-    let mut start = usize::max_value();
+    let mut start = usize::MAX;
     let mut end = 0;
 
     for &instruction_index in alive_at {
@@ -81,8 +91,8 @@ fn get_live_interval(register: &VirtualRegister, alive_at: &HashSet<usize>) -> L
     }
 }
 
-fn get_virtual_registers(instructions: &Vec<InstructionMIR>,
-                         basic_blocks: &Vec<BasicBlock>,
+fn get_virtual_registers(instructions: &[InstructionMIR],
+                         basic_blocks: &[BasicBlock],
                          control_flow_graph: &ControlFlowGraph) -> Vec<VirtualRegister> {
     let mut registers = HashSet::new();
 
@@ -99,7 +109,7 @@ fn get_virtual_registers(instructions: &Vec<InstructionMIR>,
         }
     }
 
-    let mut registers = Vec::from_iter(registers.into_iter());
+    let mut registers = Vec::from_iter(registers);
     registers.sort_by_key(|register| register.number);
     registers
 }
@@ -124,6 +134,7 @@ fn compute_liveness_for_register(instructions: &Vec<InstructionMIR>,
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn compute_liveness_for_register_in_block(instructions: &Vec<InstructionMIR>,
                                           basic_blocks: &Vec<BasicBlock>,
                                           control_flow_graph: &ControlFlowGraph,
@@ -142,7 +153,7 @@ fn compute_liveness_for_register_in_block(instructions: &Vec<InstructionMIR>,
         let instruction = &instructions[basic_blocks[block_index].instructions[i]];
 
         if let Some(assign_register) = instruction.data.assign_virtual_register() {
-            if &assign_register == register && !instruction.data.use_virtual_registers().contains(&register) {
+            if &assign_register == register && !instruction.data.use_virtual_registers().contains(register) {
                 alive_at.insert(basic_blocks[block_index].start_offset + i);
                 terminated = true;
                 break;
@@ -163,7 +174,7 @@ fn compute_liveness_for_register_in_block(instructions: &Vec<InstructionMIR>,
                     edge.to,
                     basic_blocks[edge.to].instructions.len() - 1,
                     visited,
-                    &register,
+                    register,
                     alive_at
                 );
             }
@@ -179,8 +190,8 @@ struct UsageSite {
 type UseSites = HashMap<VirtualRegister, Vec<UsageSite>>;
 type AssignSites = HashMap<VirtualRegister, Vec<UsageSite>>;
 
-fn get_register_usage(instructions: &Vec<InstructionMIR>,
-                      basic_blocks: &Vec<BasicBlock>,
+fn get_register_usage(instructions: &[InstructionMIR],
+                      basic_blocks: &[BasicBlock],
                       control_flow_graph: &ControlFlowGraph) -> (UseSites, AssignSites) {
     let mut use_sites = HashMap::new();
     let mut assign_sites = HashMap::new();
@@ -190,14 +201,14 @@ fn get_register_usage(instructions: &Vec<InstructionMIR>,
             let instruction = &instructions[instruction_index];
 
             if let Some(assign_register) = instruction.data.assign_virtual_register() {
-                assign_sites.entry(assign_register).or_insert_with(|| Vec::new()).push(UsageSite {
+                assign_sites.entry(assign_register).or_insert_with(Vec::new).push(UsageSite {
                     block_index,
                     offset: block_offset
                 });
             }
 
             for use_register in instruction.data.use_virtual_registers() {
-                use_sites.entry(use_register).or_insert_with(|| Vec::new()).push(UsageSite {
+                use_sites.entry(use_register).or_insert_with(Vec::new).push(UsageSite {
                     block_index,
                     offset: block_offset
                 });
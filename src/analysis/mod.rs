@@ -3,13 +3,24 @@ use std::iter::FromIterator;
 
 use crate::compiler::ir::BranchLabel;
 use crate::mir::{InstructionMIR, InstructionMIRData, RegisterMIR};
-use crate::model::typesystem::{TypeId, TypeStorage};
+use crate::model::typesystem::TypeId;
 use crate::optimization::null_check_elision::InstructionsRegisterNullStatus;
-use crate::mir::compiler::{MIRCompilationResult, InstructionMIRCompiler};
+use crate::mir::compiler::MIRCompilationResult;
+
+#[cfg(test)]
+use crate::model::typesystem::TypeStorage;
+#[cfg(test)]
+use crate::mir::compiler::InstructionMIRCompiler;
+#[cfg(test)]
 use crate::model::function::{Function, FunctionDeclaration};
+#[cfg(test)]
 use crate::model::instruction::Instruction;
+#[cfg(test)]
 use crate::model::binder::Binder;
+#[cfg(test)]
 use crate::model::verifier::Verifier;
+#[cfg(test)]
+use crate::model::class::Class;
 
 pub mod basic_block;
 pub mod control_flow_graph;
@@ -22,7 +33,8 @@ pub struct OptimizationResult {
 #[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum VirtualRegisterType {
     Int,
-    Float
+    Float,
+    Struct
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
@@ -34,13 +46,14 @@ pub struct VirtualRegister {
 impl VirtualRegister {
     pub fn from(register: &RegisterMIR) -> VirtualRegister {
         match register.value_type {
-            TypeId::Float32 => VirtualRegister { number: register.number, register_type: VirtualRegisterType::Float },
+            TypeId::Float32 | TypeId::Float64 => VirtualRegister { number: register.number, register_type: VirtualRegisterType::Float },
+            TypeId::Struct(_) => VirtualRegister { number: register.number, register_type: VirtualRegisterType::Struct },
             _ => VirtualRegister { number: register.number, register_type: VirtualRegisterType::Int }
         }
     }
 
 }
-pub fn create_label_mapping(instructions: &Vec<InstructionMIR>) -> HashMap<BranchLabel, usize> {
+pub fn create_label_mapping(instructions: &[InstructionMIR]) -> HashMap<BranchLabel, usize> {
     let mut mapping = HashMap::new();
 
     for (instruction_index, instruction) in instructions.iter().enumerate() {
@@ -57,27 +70,36 @@ pub fn determine_instructions_operand_stack(compilation_result: &MIRCompilationR
     let mut instructions_operands = Vec::new();
     let local_registers = HashSet::<RegisterMIR>::from_iter(compilation_result.local_virtual_registers.iter().cloned());
 
-    let pop_if_not_local = |operand_stack: &mut Vec<RegisterMIR>, register: &RegisterMIR| {
-        if !local_registers.contains(register) {
-            operand_stack.pop();
-        }
-    };
-
-    let push_if_not_local = |operand_stack: &mut Vec<RegisterMIR>, register: &RegisterMIR| {
-        if !local_registers.contains(register) {
-            operand_stack.push(register.clone());
-        }
-    };
+    let is_operand = |register: &RegisterMIR| !local_registers.contains(register);
 
     for instruction in &compilation_result.instructions {
         instructions_operands.push(operand_stack.clone());
 
-        for use_register in instruction.data.use_registers().iter().rev() {
-            pop_if_not_local(&mut operand_stack, use_register);
+        let mut use_registers = instruction.data.use_registers();
+        match &instruction.data {
+            // A move to a register above the source keeps the source on the stack (as for duplicate)
+            InstructionMIRData::Move(destination, source) if is_operand(destination) && destination.number > source.number => {
+                use_registers.clear();
+            }
+            // The constructed object remains on the stack after the constructor has been called
+            InstructionMIRData::CallInstance(signature, _, _) if signature.is_constructor() => {
+                use_registers.remove(0);
+            }
+            _ => {}
+        }
+
+        for use_register in use_registers {
+            if is_operand(&use_register) {
+                operand_stack.retain(|register: &RegisterMIR| register.number != use_register.number);
+            }
         }
 
         if let Some(assign_register) = instruction.data.assign_register() {
-            push_if_not_local(&mut operand_stack, &assign_register);
+            if is_operand(&assign_register) {
+                operand_stack.retain(|register: &RegisterMIR| register.number != assign_register.number);
+                operand_stack.push(assign_register);
+                operand_stack.sort_by_key(|register| register.number);
+            }
         }
     }
 
@@ -143,4 +165,38 @@ fn test_determine_instructions_operand_stack2() {
 
     let instructions_operand_stack = determine_instructions_operand_stack(&result);
     assert_eq!(result.instructions_operand_stack, instructions_operand_stack);
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_determine_instructions_operand_stack3() {
+    let mut function = Function::new(
+        FunctionDeclaration::with_managed("test".to_owned(), vec![], TypeId::Int32),
+        vec![TypeId::Class("Point".to_owned())],
+        vec![
+            Instruction::NewObjectWithConstructor("Point".to_owned(), Vec::new()),
+            Instruction::Duplicate,
+            Instruction::LoadInt32(1),
+            Instruction::NewObjectWithConstructor("Point".to_owned(), vec![TypeId::Int32]),
+            Instruction::StoreLocal(0),
+            Instruction::StoreLocal(0),
+            Instruction::StoreLocal(0),
+            Instruction::LoadInt32(0),
+            Instruction::Return
+        ]
+    );
+
+    let mut binder = Binder::new();
+    binder.define(FunctionDeclaration::with_constructor(TypeId::Class("Point".to_owned()), vec![]));
+    binder.define(FunctionDeclaration::with_constructor(TypeId::Class("Point".to_owned()), vec![TypeId::Int32]));
+
+    let mut type_storage = TypeStorage::new();
+    type_storage.add_class(Class::new("Point".to_owned(), vec![])).unwrap();
+    Verifier::new(&binder, &type_storage, &mut function).verify().unwrap();
+
+    let mut compiler = InstructionMIRCompiler::new(&type_storage, &binder, &function);
+    compiler.compile(function.instructions());
+    let result = compiler.done();
+
+    let instructions_operand_stack = determine_instructions_operand_stack(&result);
+    assert_eq!(result.instructions_operand_stack, instructions_operand_stack);
+}
@@ -18,7 +18,7 @@ impl ExecutableMemoryAllocator {
 
         // No page with enough room, allocate new
         let page_size = 4096;
-        let mut page = ExecutablePage::new(((size + page_size - 1) / page_size) * page_size).unwrap(); //Align to page size
+        let mut page = ExecutablePage::new(size.div_ceil(page_size) * page_size).unwrap(); //Align to page size
         let address = page.try_allocate(size).unwrap();
         self.pages.push(page);
         address
@@ -44,7 +44,7 @@ impl ExecutablePage {
             )
         };
 
-        if page_ptr != std::ptr::null_mut() {
+        if !page_ptr.is_null() {
             Some(
                 ExecutablePage {
                     address: page_ptr,
@@ -1,9 +1,9 @@
 use iced_x86::Register;
 
-use crate::compiler::ir::{HardwareRegister, HardwareRegisterExplicit, InstructionIR, Variable};
+use crate::compiler::ir::{HardwareRegisterExplicit, InstructionIR, Variable};
 use crate::compiler::stack_layout;
 use crate::compiler::stack_layout::{STACK_ENTRY_SIZE, STACK_OFFSET};
-use crate::model::function::{Function, FunctionDeclaration, FunctionSignature};
+use crate::model::function::{Function, FunctionDeclaration};
 use crate::model::typesystem::TypeId;
 
 pub struct CallingConventions {
@@ -19,7 +19,7 @@ impl CallingConventions {
 
     pub fn call_function_arguments(&self,
                                    function_to_call: &FunctionDeclaration,
-                                   arguments: &Vec<Variable>,
+                                   arguments: &[Variable],
                                    instructions: &mut Vec<InstructionIR>) {
         for argument_index in (0..function_to_call.parameters().len()).rev() {
             self.call_function_argument(
@@ -33,14 +33,14 @@ impl CallingConventions {
 
     pub fn call_function_argument(&self,
                                   function_to_call: &FunctionDeclaration,
-                                  arguments: &Vec<Variable>,
+                                  arguments: &[Variable],
                                   argument_index: usize,
                                   instructions: &mut Vec<InstructionIR>) {
         let argument_source = &arguments[argument_index];
 
         match &function_to_call.parameters()[argument_index] {
-            TypeId::Float32 => {
-                let relative_index = float_register_call_arguments::get_relative_index(&function_to_call.parameters(), argument_index);
+            TypeId::Float32 | TypeId::Float64 => {
+                let relative_index = float_register_call_arguments::get_relative_index(function_to_call.parameters(), argument_index);
                 if relative_index >= float_register_call_arguments::NUM_ARGUMENTS {
                     argument_source.move_to_stack(instructions);
                 } else {
@@ -51,7 +51,7 @@ impl CallingConventions {
                 }
             }
             _ => {
-                let relative_index = register_call_arguments::get_relative_index(&function_to_call.parameters(), argument_index);
+                let relative_index = register_call_arguments::get_relative_index(function_to_call.parameters(), argument_index);
                 if relative_index >= register_call_arguments::NUM_ARGUMENTS {
                     argument_source.move_to_stack(instructions);
                 } else {
@@ -69,7 +69,7 @@ impl CallingConventions {
 
         for argument_index in (0..function.declaration().parameters().len()).rev() {
             let argument_source = match function.declaration().parameters()[argument_index] {
-                TypeId::Float32 => {
+                TypeId::Float32 | TypeId::Float64 => {
                     self.move_float_arguments_to_stack(
                         function,
                         argument_index,
@@ -104,7 +104,7 @@ impl CallingConventions {
                                instructions: &mut Vec<InstructionIR>) {
         match func_to_call.return_type() {
             TypeId::Void => {}
-            TypeId::Float32 => {
+            TypeId::Float32 | TypeId::Float64 => {
                 variable.move_from_explicit(
                     HardwareRegisterExplicit(float_register_call_arguments::RETURN_VALUE),
                     instructions
@@ -125,7 +125,7 @@ impl CallingConventions {
                              instructions: &mut Vec<InstructionIR>) {
         match function.declaration().return_type() {
             TypeId::Void => {}
-            TypeId::Float32 => {
+            TypeId::Float32 | TypeId::Float64 => {
                 variable.move_to_explicit(
                     HardwareRegisterExplicit(float_register_call_arguments::RETURN_VALUE),
                     instructions
@@ -180,7 +180,7 @@ impl CallingConventions {
             }
 
             match parameter {
-                TypeId::Float32 => {
+                TypeId::Float32 | TypeId::Float64 => {
                     if float_register_call_arguments::get_relative_index(parameters, index) >= float_register_call_arguments::NUM_ARGUMENTS {
                         stack_argument_index += 1;
                     }
@@ -196,12 +196,12 @@ impl CallingConventions {
         stack_argument_index
     }
 
-    pub fn num_stack_arguments(&self, parameters: &Vec<TypeId>) -> usize {
+    pub fn num_stack_arguments(&self, parameters: &[TypeId]) -> usize {
         let mut num_stack_arguments = 0;
 
         for (parameter_index, parameter) in parameters.iter().enumerate() {
             match parameter {
-                TypeId::Float32 => {
+                TypeId::Float32 | TypeId::Float64 => {
                     if float_register_call_arguments::get_relative_index(parameters, parameter_index) >= float_register_call_arguments::NUM_ARGUMENTS {
                         num_stack_arguments += 1;
                     }
@@ -224,7 +224,7 @@ impl CallingConventions {
 
 pub fn get_call_register(func_to_call: &FunctionDeclaration, index: usize, argument_type: &TypeId) -> Option<Register> {
     match argument_type {
-        TypeId::Float32 => {
+        TypeId::Float32 | TypeId::Float64 => {
             let relative_index = float_register_call_arguments::get_relative_index(func_to_call.parameters(), index);
             if relative_index < float_register_call_arguments::NUM_ARGUMENTS {
                 return Some(float_register_call_arguments::get_argument(relative_index));
@@ -266,7 +266,7 @@ pub mod register_call_arguments {
         }
     }
 
-    pub fn get_relative_index(parameters: &Vec<TypeId>, argument_index: usize) -> usize {
+    pub fn get_relative_index(parameters: &[TypeId], argument_index: usize) -> usize {
         let mut relative_argument_index = 0;
         for (index, parameter) in parameters.iter().enumerate() {
             if index == argument_index {
@@ -274,7 +274,7 @@ pub mod register_call_arguments {
             }
 
             match parameter {
-                TypeId::Float32 => {},
+                TypeId::Float32 | TypeId::Float64 => {},
                 _ => {
                     relative_argument_index += 1;
                 }
@@ -285,6 +285,7 @@ pub mod register_call_arguments {
     }
 
     pub const RETURN_VALUE: Register = Register::RAX;
+    #[allow(dead_code)]
     pub const RETURN_VALUE_32: Register = Register::EAX;
 }
 
@@ -317,14 +318,14 @@ pub mod float_register_call_arguments {
         }
     }
 
-    pub fn get_relative_index(parameters: &Vec<TypeId>, argument_index: usize) -> usize {
+    pub fn get_relative_index(parameters: &[TypeId], argument_index: usize) -> usize {
         let mut float_argument_index = 0;
         for (index, parameter) in parameters.iter().enumerate() {
             if index == argument_index {
                 break;
             }
 
-            if let TypeId::Float32 = parameter {
+            if parameter.is_float() {
                 float_argument_index += 1;
             }
         }
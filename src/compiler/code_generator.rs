@@ -2,39 +2,59 @@ use iced_x86::{Code, Encoder, MemoryOperand, Register};
 use iced_x86::Instruction as X86Instruction;
 
 use crate::compiler::{FunctionCallType, FunctionCompilationData, stack_layout, UnresolvedFunctionCall};
-use crate::compiler::calling_conventions::{CallingConventions, float_register_call_arguments, register_call_arguments};
+use crate::compiler::calling_conventions::{CallingConventions, register_call_arguments};
 use crate::compiler::error_handling::ErrorHandling;
-use crate::compiler::ir::{Condition, HardwareRegisterExplicit, InstructionIR};
+use crate::compiler::ir::{Condition, HardwareRegister, InstructionIR, RoundingMode, Variable};
 use crate::model::binder::Binder;
-use crate::model::function::{Function, FunctionType};
-use crate::model::typesystem::{TypeId, Type, TypeStorage};
+use crate::model::function::{Function, FunctionAddress, FunctionDeclaration, FunctionType};
+use crate::model::typesystem::{TypeId, Type, TypeStorage, VIRTUAL_TABLE_OFFSET, SUPERTYPES_OFFSET, INTERFACE_TABLES_OFFSET, INTERFACE_TABLE_SIZE, INTERFACE_TABLE_FUNCTIONS_OFFSET};
 use crate::runtime::{array, runtime_interface};
+use crate::runtime::object::HEADER_SIZE;
 use crate::compiler::code_generator::register_mapping::DataSize;
+use crate::runtime::memory::manager::MemoryManager;
+use crate::engine::execution::{StackLimit, RuntimeErrorManager, ExternalCallerFrame};
 
 pub struct CodeGeneratorResult {
     pub code_bytes: Vec<u8>,
     pub instructions_offsets: Vec<(usize, usize)>
 }
 
+/// How the address of a called function is determined.
+enum CallDispatch<'v> {
+    Direct,
+    Virtual(usize),
+    Interface(usize),
+    Indirect(&'v Variable)
+}
+
 pub struct CodeGenerator<'a> {
     encoder: Encoder,
     encoder_offset: usize,
     binder: &'a Binder,
     error_handling: &'a ErrorHandling,
     type_storage: &'a mut TypeStorage,
+    memory_manager: &'a mut MemoryManager,
+    stack_limit: &'a StackLimit,
+    runtime_error: &'a RuntimeErrorManager,
     instructions_offsets: Vec<(usize, usize)>
 }
 
 impl<'a> CodeGenerator<'a> {
     pub fn new(binder: &'a Binder,
                error_handling: &'a ErrorHandling,
-               type_storage: &'a mut TypeStorage) -> CodeGenerator<'a> {
+               type_storage: &'a mut TypeStorage,
+               memory_manager: &'a mut MemoryManager,
+               stack_limit: &'a StackLimit,
+               runtime_error: &'a RuntimeErrorManager) -> CodeGenerator<'a> {
         CodeGenerator {
             encoder: Encoder::new(64),
             encoder_offset: 0,
             binder,
             error_handling,
             type_storage,
+            memory_manager,
+            stack_limit,
+            runtime_error,
             instructions_offsets: Vec::new()
         }
     }
@@ -58,7 +78,7 @@ impl<'a> CodeGenerator<'a> {
                 }
             }
 
-            self.generate_instruction(&function, compilation_data, instruction);
+            self.generate_instruction(function, compilation_data, instruction);
         }
     }
 
@@ -106,7 +126,7 @@ impl<'a> CodeGenerator<'a> {
                 if is_entry_point {
                     call_direct(
                         |instruction| self.encode_x86_instruction(instruction),
-                        runtime_interface::set_error_return as u64
+                        runtime_interface::set_error_return as *const () as u64
                     );
                 }
 
@@ -119,6 +139,9 @@ impl<'a> CodeGenerator<'a> {
                     Register::RAX
                 ));
             },
+            InstructionIR::StackOverflowCheck => {
+                self.generate_stack_overflow_check(compilation_data);
+            }
             InstructionIR::LoadZeroToRegister(register) => {
                 let register = register_mapping::get(*register, DataSize::Bytes8);
 
@@ -200,7 +223,7 @@ impl<'a> CodeGenerator<'a> {
 
                 if destination.is_xmm() {
                     self.encode_x86_instruction(X86Instruction::with_reg_mem(
-                        Code::Movss_xmm_xmmm32,
+                        Code::Movsd_xmm_xmmm64,
                         destination,
                         MemoryOperand::with_base_displ(Register::RBP, *offset)
                     ));
@@ -217,7 +240,7 @@ impl<'a> CodeGenerator<'a> {
 
                 if source.is_xmm() {
                     self.encode_x86_instruction(X86Instruction::with_mem_reg(
-                        Code::Movss_xmmm32_xmm,
+                        Code::Movsd_xmmm64_xmm,
                         MemoryOperand::with_base_displ(Register::RBP, *offset),
                         source
                     ));
@@ -232,7 +255,7 @@ impl<'a> CodeGenerator<'a> {
             InstructionIR::StoreFrameMemoryExplicit(offset, register) => {
                 if register.0.is_xmm() {
                     self.encode_x86_instruction(X86Instruction::with_mem_reg(
-                        Code::Movss_xmmm32_xmm,
+                        Code::Movsd_xmmm64_xmm,
                         MemoryOperand::with_base_displ(Register::RBP, *offset),
                         register.0
                     ));
@@ -247,7 +270,7 @@ impl<'a> CodeGenerator<'a> {
             InstructionIR::LoadFrameMemoryExplicit(register, offset) => {
                 if register.0.is_xmm() {
                     self.encode_x86_instruction(X86Instruction::with_reg_mem(
-                        Code::Movss_xmm_xmmm32,
+                        Code::Movsd_xmm_xmmm64,
                         register.0,
                         MemoryOperand::with_base_displ(Register::RBP, *offset)
                     ));
@@ -259,6 +282,24 @@ impl<'a> CodeGenerator<'a> {
                     ));
                 }
             }
+            InstructionIR::CopyFrameMemory(destination_offset, source_offset, size) => {
+                self.generate_copy_memory(Register::RBP, *destination_offset, Register::RBP, *source_offset, *size);
+            }
+            InstructionIR::ZeroFrameMemory(offset, size) => {
+                for chunk_offset in (0..*size).step_by(8) {
+                    self.encode_x86_instruction(X86Instruction::try_with_mem_i32(
+                        Code::Mov_rm64_imm32,
+                        MemoryOperand::with_base_displ(Register::RBP, *offset + chunk_offset as i32),
+                        0
+                    ).unwrap());
+                }
+            }
+            InstructionIR::LoadFrameField(field_type, destination_register, offset) => {
+                self.generate_load_field(field_type, MemoryOperand::with_base_displ(Register::RBP, *offset), *destination_register);
+            }
+            InstructionIR::StoreFrameField(field_type, offset, value_register) => {
+                self.generate_store_field(field_type, MemoryOperand::with_base_displ(Register::RBP, *offset), *value_register);
+            }
             InstructionIR::MoveInt32ToFrameMemory(offset, value) => {
                 self.encode_x86_instruction(X86Instruction::try_with_mem_i32(
                     Code::Mov_rm64_imm32,
@@ -275,13 +316,40 @@ impl<'a> CodeGenerator<'a> {
                     *value
                 ).unwrap());
             }
+            InstructionIR::MoveInt64ToRegister(destination, value) => {
+                let destination = register_mapping::get(*destination, DataSize::Bytes8);
+
+                self.encode_x86_instruction(X86Instruction::try_with_reg_i64(
+                    Code::Mov_r64_imm64,
+                    destination,
+                    *value
+                ).unwrap());
+            }
+            InstructionIR::LoadString(destination, value) => {
+                let destination = register_mapping::get(*destination, DataSize::Bytes8);
+
+                // Literals are interned before being compiled, and loaded through their root as the string can be moved
+                let string_slot = self.memory_manager.interned_string(value).unwrap();
+
+                self.encode_x86_instruction(X86Instruction::try_with_reg_u64(
+                    Code::Mov_r64_imm64,
+                    destination,
+                    string_slot as u64
+                ).unwrap());
+
+                self.encode_x86_instruction(X86Instruction::with_reg_mem(
+                    Code::Mov_r64_rm64,
+                    destination,
+                    MemoryOperand::with_base(destination)
+                ));
+            }
             InstructionIR::Move(destination, source) => {
                 let destination = register_mapping::get(*destination, DataSize::Bytes8);
                 let source = register_mapping::get(*source, DataSize::Bytes8);
 
                 if source.is_xmm() {
                     self.encode_x86_instruction(X86Instruction::with_reg_reg(
-                        Code::Movss_xmm_xmmm32,
+                        Code::Movsd_xmm_xmmm64,
                         destination,
                         source
                     ));
@@ -296,7 +364,7 @@ impl<'a> CodeGenerator<'a> {
             InstructionIR::MoveExplicit(destination, source) => {
                 if source.0.is_xmm() {
                     self.encode_x86_instruction(X86Instruction::with_reg_reg(
-                        Code::Movss_xmm_xmmm32,
+                        Code::Movsd_xmm_xmmm64,
                         destination.0,
                         source.0
                     ));
@@ -311,7 +379,7 @@ impl<'a> CodeGenerator<'a> {
             InstructionIR::MoveImplicitToExplicit(destination, source) => {
                 if destination.0.is_xmm() {
                     self.encode_x86_instruction(X86Instruction::with_reg_reg(
-                        Code::Movss_xmm_xmmm32,
+                        Code::Movsd_xmm_xmmm64,
                         destination.0,
                         register_mapping::get(*source, DataSize::Bytes8)
                     ));
@@ -328,7 +396,7 @@ impl<'a> CodeGenerator<'a> {
 
                 if destination.is_xmm() {
                     self.encode_x86_instruction(X86Instruction::with_reg_reg(
-                        Code::Movss_xmm_xmmm32,
+                        Code::Movsd_xmm_xmmm64,
                         destination,
                         source.0,
                     ));
@@ -435,6 +503,13 @@ impl<'a> CodeGenerator<'a> {
                     ));
                 }
 
+                let divisor_register = register_mapping::get(*source, DataSize::Bytes4);
+                self.generate_division_check(
+                    compilation_data,
+                    DataSize::Bytes4,
+                    |value| X86Instruction::try_with_reg_i32(Code::Cmp_rm32_imm8, divisor_register, value).unwrap()
+                );
+
                 self.encode_x86_instruction(X86Instruction::with(Code::Cdq));
 
                 self.encode_x86_instruction(X86Instruction::with_reg(
@@ -461,6 +536,17 @@ impl<'a> CodeGenerator<'a> {
                     ));
                 }
 
+                let divisor_offset = *source_offset;
+                self.generate_division_check(
+                    compilation_data,
+                    DataSize::Bytes4,
+                    |value| X86Instruction::try_with_mem_i32(
+                        Code::Cmp_rm32_imm8,
+                        MemoryOperand::with_base_displ(Register::RBP, divisor_offset),
+                        value
+                    ).unwrap()
+                );
+
                 self.encode_x86_instruction(X86Instruction::with(Code::Cdq));
 
                 self.encode_x86_instruction(X86Instruction::with_mem(
@@ -476,6 +562,52 @@ impl<'a> CodeGenerator<'a> {
                     ));
                 }
             }
+            InstructionIR::RemainderInt32(destination, source) => {
+                let destination_register = register_mapping::get(*destination, DataSize::Bytes8);
+
+                if destination_register != Register::RAX {
+                    self.encode_x86_instruction(X86Instruction::with_reg_reg(
+                        Code::Mov_rm64_r64,
+                        Register::RAX,
+                        destination_register
+                    ));
+                }
+
+                let divisor_register = register_mapping::get(*source, DataSize::Bytes4);
+                self.generate_division_check(
+                    compilation_data,
+                    DataSize::Bytes4,
+                    |value| X86Instruction::try_with_reg_i32(Code::Cmp_rm32_imm8, divisor_register, value).unwrap()
+                );
+
+                self.encode_x86_instruction(X86Instruction::with(Code::Cdq));
+
+                self.encode_x86_instruction(X86Instruction::with_reg(
+                    Code::Idiv_rm32,
+                    register_mapping::get(*source, DataSize::Bytes4)
+                ));
+
+                // The remainder is placed in RDX
+                if destination_register != Register::RDX {
+                    self.encode_x86_instruction(X86Instruction::with_reg_reg(
+                        Code::Mov_rm64_r64,
+                        destination_register,
+                        Register::RDX,
+                    ));
+                }
+            }
+            InstructionIR::NegateInt32(register) => {
+                self.encode_x86_instruction(X86Instruction::with_reg(
+                    Code::Neg_rm32,
+                    register_mapping::get(*register, DataSize::Bytes4)
+                ));
+            }
+            InstructionIR::NegateInt32FrameMemory(offset) => {
+                self.encode_x86_instruction(X86Instruction::with_mem(
+                    Code::Neg_rm32,
+                    MemoryOperand::with_base_displ(Register::RBP, *offset)
+                ));
+            }
             InstructionIR::AndInt32(destination, source) => {
                 self.encode_x86_instruction(X86Instruction::with_reg_reg(
                     Code::And_r32_rm32,
@@ -546,6 +678,27 @@ impl<'a> CodeGenerator<'a> {
                     *value
                 ).unwrap());
             }
+            InstructionIR::XorInt32(destination, source) => {
+                self.encode_x86_instruction(X86Instruction::with_reg_reg(
+                    Code::Xor_r32_rm32,
+                    register_mapping::get(*destination, DataSize::Bytes4),
+                    register_mapping::get(*source, DataSize::Bytes4)
+                ));
+            }
+            InstructionIR::XorInt32FromFrameMemory(destination, source_offset) => {
+                self.encode_x86_instruction(X86Instruction::with_reg_mem(
+                    Code::Xor_r32_rm32,
+                    register_mapping::get(*destination, DataSize::Bytes4),
+                    MemoryOperand::with_base_displ(Register::RBP, *source_offset)
+                ));
+            }
+            InstructionIR::XorInt32ToFrameMemory(destination_offset, source) => {
+                self.encode_x86_instruction(X86Instruction::with_mem_reg(
+                    Code::Xor_rm32_r32,
+                    MemoryOperand::with_base_displ(Register::RBP, *destination_offset),
+                    register_mapping::get(*source, DataSize::Bytes4)
+                ));
+            }
             InstructionIR::NotInt32(register) => {
                 self.encode_x86_instruction(X86Instruction::with_reg(
                     Code::Not_rm32,
@@ -572,6 +725,119 @@ impl<'a> CodeGenerator<'a> {
                     1
                 ).unwrap());
             }
+            InstructionIR::BitwiseNotInt32(register) => {
+                self.encode_x86_instruction(X86Instruction::with_reg(
+                    Code::Not_rm32,
+                    register_mapping::get(*register, DataSize::Bytes4)
+                ));
+            }
+            InstructionIR::BitwiseNotInt32FrameMemory(offset) => {
+                self.encode_x86_instruction(X86Instruction::with_mem(
+                    Code::Not_rm32,
+                    MemoryOperand::with_base_displ(Register::RBP, *offset)
+                ));
+            }
+            InstructionIR::ShiftLeftInt32(destination, source) => {
+                let count_register = register_mapping::get(*source, DataSize::Bytes8);
+                if count_register != Register::RCX {
+                    self.encode_x86_instruction(X86Instruction::with_reg_reg(
+                        Code::Mov_rm64_r64,
+                        Register::RCX,
+                        count_register
+                    ));
+                }
+
+                self.encode_x86_instruction(X86Instruction::with_reg_reg(
+                    Code::Shl_rm32_CL,
+                    register_mapping::get(*destination, DataSize::Bytes4),
+                    Register::CL
+                ));
+            }
+            InstructionIR::ShiftRightInt32(destination, source) => {
+                let count_register = register_mapping::get(*source, DataSize::Bytes8);
+                if count_register != Register::RCX {
+                    self.encode_x86_instruction(X86Instruction::with_reg_reg(
+                        Code::Mov_rm64_r64,
+                        Register::RCX,
+                        count_register
+                    ));
+                }
+
+                // Int32 is signed, so the sign bit is preserved
+                self.encode_x86_instruction(X86Instruction::with_reg_reg(
+                    Code::Sar_rm32_CL,
+                    register_mapping::get(*destination, DataSize::Bytes4),
+                    Register::CL
+                ));
+            }
+            InstructionIR::ConvertInt32ToFloat32(destination, source) => {
+                self.encode_x86_instruction(X86Instruction::with_reg_reg(
+                    Code::Cvtsi2ss_xmm_rm32,
+                    register_mapping::get(*destination, DataSize::Bytes4),
+                    register_mapping::get(*source, DataSize::Bytes4)
+                ));
+            }
+            InstructionIR::ConvertFloat32ToInt32(destination, source) => {
+                // Truncates towards zero
+                self.encode_x86_instruction(X86Instruction::with_reg_reg(
+                    Code::Cvttss2si_r32_xmmm32,
+                    register_mapping::get(*destination, DataSize::Bytes4),
+                    register_mapping::get(*source, DataSize::Bytes4)
+                ));
+            }
+            InstructionIR::ConvertBoolToInt32(destination, source) => {
+                // Only the lowest byte of a bool is defined
+                self.encode_x86_instruction(X86Instruction::with_reg_reg(
+                    Code::Movzx_r32_rm8,
+                    register_mapping::get(*destination, DataSize::Bytes4),
+                    register_mapping::get(*source, DataSize::Bytes1)
+                ));
+            }
+            InstructionIR::ConvertInt32ToInt64(destination, source) => {
+                // Sign extends
+                self.encode_x86_instruction(X86Instruction::with_reg_reg(
+                    Code::Movsxd_r64_rm32,
+                    register_mapping::get(*destination, DataSize::Bytes8),
+                    register_mapping::get(*source, DataSize::Bytes4)
+                ));
+            }
+            InstructionIR::ConvertInt64ToInt32(destination, source) => {
+                // Keeps the lower 32 bits
+                self.encode_x86_instruction(X86Instruction::with_reg_reg(
+                    Code::Mov_r32_rm32,
+                    register_mapping::get(*destination, DataSize::Bytes4),
+                    register_mapping::get(*source, DataSize::Bytes4)
+                ));
+            }
+            InstructionIR::ConvertFloat32ToFloat64(destination, source) => {
+                self.encode_x86_instruction(X86Instruction::with_reg_reg(
+                    Code::Cvtss2sd_xmm_xmmm32,
+                    register_mapping::get(*destination, DataSize::Bytes8),
+                    register_mapping::get(*source, DataSize::Bytes4)
+                ));
+            }
+            InstructionIR::ConvertFloat64ToFloat32(destination, source) => {
+                self.encode_x86_instruction(X86Instruction::with_reg_reg(
+                    Code::Cvtsd2ss_xmm_xmmm64,
+                    register_mapping::get(*destination, DataSize::Bytes4),
+                    register_mapping::get(*source, DataSize::Bytes8)
+                ));
+            }
+            InstructionIR::ConvertInt64ToFloat64(destination, source) => {
+                self.encode_x86_instruction(X86Instruction::with_reg_reg(
+                    Code::Cvtsi2sd_xmm_rm64,
+                    register_mapping::get(*destination, DataSize::Bytes8),
+                    register_mapping::get(*source, DataSize::Bytes8)
+                ));
+            }
+            InstructionIR::ConvertFloat64ToInt64(destination, source) => {
+                // Truncates towards zero
+                self.encode_x86_instruction(X86Instruction::with_reg_reg(
+                    Code::Cvttsd2si_r64_xmmm64,
+                    register_mapping::get(*destination, DataSize::Bytes8),
+                    register_mapping::get(*source, DataSize::Bytes8)
+                ));
+            }
             InstructionIR::AddFloat32(destination, source) => {
                 self.encode_x86_instruction(X86Instruction::with_reg_reg(
                     Code::Addss_xmm_xmmm32,
@@ -628,78 +894,468 @@ impl<'a> CodeGenerator<'a> {
                     MemoryOperand::with_base_displ(Register::RBP, *source_offset)
                 ));
             }
-            InstructionIR::Call(signature, arguments, num_saved) => {
-                let func_to_call = self.binder.get(signature).unwrap();
-                let calling_conventions = CallingConventions::new();
+            InstructionIR::MinFloat32(destination, source) => {
+                self.encode_x86_instruction(X86Instruction::with_reg_reg(
+                    Code::Minss_xmm_xmmm32,
+                    register_mapping::get(*destination, DataSize::Bytes4),
+                    register_mapping::get(*source, DataSize::Bytes4)
+                ));
+            }
+            InstructionIR::MinFloat32FromFrameMemory(destination, source_offset) => {
+                self.encode_x86_instruction(X86Instruction::with_reg_mem(
+                    Code::Minss_xmm_xmmm32,
+                    register_mapping::get(*destination, DataSize::Bytes4),
+                    MemoryOperand::with_base_displ(Register::RBP, *source_offset)
+                ));
+            }
+            InstructionIR::MaxFloat32(destination, source) => {
+                self.encode_x86_instruction(X86Instruction::with_reg_reg(
+                    Code::Maxss_xmm_xmmm32,
+                    register_mapping::get(*destination, DataSize::Bytes4),
+                    register_mapping::get(*source, DataSize::Bytes4)
+                ));
+            }
+            InstructionIR::MaxFloat32FromFrameMemory(destination, source_offset) => {
+                self.encode_x86_instruction(X86Instruction::with_reg_mem(
+                    Code::Maxss_xmm_xmmm32,
+                    register_mapping::get(*destination, DataSize::Bytes4),
+                    MemoryOperand::with_base_displ(Register::RBP, *source_offset)
+                ));
+            }
+            InstructionIR::SquareRootFloat32(destination, source) => {
+                self.encode_x86_instruction(X86Instruction::with_reg_reg(
+                    Code::Sqrtss_xmm_xmmm32,
+                    register_mapping::get(*destination, DataSize::Bytes4),
+                    register_mapping::get(*source, DataSize::Bytes4)
+                ));
+            }
+            InstructionIR::RoundFloat32(destination, source, mode) => {
+                let rounding_mode = match mode {
+                    RoundingMode::Floor => 1,
+                    RoundingMode::Ceil => 2
+                };
 
-                //Align the stack
-                let stack_alignment = calling_conventions.stack_alignment(func_to_call, *num_saved);
-                if stack_alignment > 0 {
-                    self.encode_x86_instruction(X86Instruction::try_with_reg_i32(Code::Sub_rm64_imm32, Register::RSP, stack_alignment).unwrap());
+                self.encode_x86_instruction(X86Instruction::try_with_reg_reg_u32(
+                    Code::Roundss_xmm_xmmm32_imm8,
+                    register_mapping::get(*destination, DataSize::Bytes4),
+                    register_mapping::get(*source, DataSize::Bytes4),
+                    rounding_mode
+                ).unwrap());
+            }
+            InstructionIR::AddInt64(destination, source) => {
+                self.encode_x86_instruction(X86Instruction::with_reg_reg(
+                    Code::Add_r64_rm64,
+                    register_mapping::get(*destination, DataSize::Bytes8),
+                    register_mapping::get(*source, DataSize::Bytes8)
+                ));
+            }
+            InstructionIR::AddInt64FromFrameMemory(destination, source_offset) => {
+                self.encode_x86_instruction(X86Instruction::with_reg_mem(
+                    Code::Add_r64_rm64,
+                    register_mapping::get(*destination, DataSize::Bytes8),
+                    MemoryOperand::with_base_displ(Register::RBP, *source_offset)
+                ));
+            }
+            InstructionIR::AddInt64ToFrameMemory(destination_offset, source) => {
+                self.encode_x86_instruction(X86Instruction::with_mem_reg(
+                    Code::Add_rm64_r64,
+                    MemoryOperand::with_base_displ(Register::RBP, *destination_offset),
+                    register_mapping::get(*source, DataSize::Bytes8)
+                ));
+            }
+            InstructionIR::SubInt64(destination, source) => {
+                self.encode_x86_instruction(X86Instruction::with_reg_reg(
+                    Code::Sub_r64_rm64,
+                    register_mapping::get(*destination, DataSize::Bytes8),
+                    register_mapping::get(*source, DataSize::Bytes8)
+                ));
+            }
+            InstructionIR::SubInt64FromFrameMemory(destination, source_offset) => {
+                self.encode_x86_instruction(X86Instruction::with_reg_mem(
+                    Code::Sub_r64_rm64,
+                    register_mapping::get(*destination, DataSize::Bytes8),
+                    MemoryOperand::with_base_displ(Register::RBP, *source_offset)
+                ));
+            }
+            InstructionIR::SubInt64ToFrameMemory(destination_offset, source) => {
+                self.encode_x86_instruction(X86Instruction::with_mem_reg(
+                    Code::Sub_rm64_r64,
+                    MemoryOperand::with_base_displ(Register::RBP, *destination_offset),
+                    register_mapping::get(*source, DataSize::Bytes8)
+                ));
+            }
+            InstructionIR::MultiplyInt64(destination, source) => {
+                self.encode_x86_instruction(X86Instruction::with_reg_reg(
+                    Code::Imul_r64_rm64,
+                    register_mapping::get(*destination, DataSize::Bytes8),
+                    register_mapping::get(*source, DataSize::Bytes8)
+                ));
+            }
+            InstructionIR::MultiplyInt64FromFrameMemory(destination, source_offset) => {
+                self.encode_x86_instruction(X86Instruction::with_reg_mem(
+                    Code::Imul_r64_rm64,
+                    register_mapping::get(*destination, DataSize::Bytes8),
+                    MemoryOperand::with_base_displ(Register::RBP, *source_offset)
+                ));
+            }
+            InstructionIR::DivideInt64(destination, source) => {
+                let destination_register = register_mapping::get(*destination, DataSize::Bytes8);
+
+                if destination_register != Register::RAX {
+                    self.encode_x86_instruction(X86Instruction::with_reg_reg(
+                        Code::Mov_rm64_r64,
+                        Register::RAX,
+                        destination_register
+                    ));
                 }
 
-                let mut call_argument_instructions = Vec::new();
-                calling_conventions.call_function_arguments(
-                    func_to_call,
-                    &arguments,
-                    &mut call_argument_instructions
+                let divisor_register = register_mapping::get(*source, DataSize::Bytes8);
+                self.generate_division_check(
+                    compilation_data,
+                    DataSize::Bytes8,
+                    |value| X86Instruction::try_with_reg_i32(Code::Cmp_rm64_imm8, divisor_register, value).unwrap()
                 );
-                self.generate_instructions(function, compilation_data, &call_argument_instructions);
-
-                match func_to_call.function_type() {
-                    FunctionType::External => {
-                        call_direct(
-                            |instruction| self.encode_x86_instruction(instruction),
-                            func_to_call.address().unwrap() as u64
-                        )
-                    }
-                    FunctionType::Managed => {
-                        compilation_data.unresolved_function_calls.push(UnresolvedFunctionCall {
-                            call_type: FunctionCallType::Relative,
-                            call_offset: self.encoder_offset,
-                            signature: signature.clone()
-                        });
 
-                        self.encode_x86_instruction(X86Instruction::try_with_branch(
-                            Code::Call_rel32_64,
-                            0
-                        ).unwrap());
-                    }
-                }
+                self.encode_x86_instruction(X86Instruction::with(Code::Cqo));
 
-                //If we have passed arguments via the stack, adjust the stack pointer.
-                let num_stack_arguments = calling_conventions.num_stack_arguments(func_to_call.parameters());
-                if num_stack_arguments > 0 {
-                    self.encode_x86_instruction(X86Instruction::try_with_reg_i32(
-                        Code::Add_rm64_imm32,
-                        Register::RSP,
-                        num_stack_arguments as i32 * stack_layout::STACK_ENTRY_SIZE
-                    ).unwrap());
-                }
+                self.encode_x86_instruction(X86Instruction::with_reg(
+                    Code::Idiv_rm64,
+                    divisor_register
+                ));
 
-                //Unalign the stack
-                if stack_alignment > 0 {
-                    self.encode_x86_instruction(X86Instruction::try_with_reg_i32(Code::Add_rm64_imm32, Register::RSP, stack_alignment).unwrap());
+                if destination_register != Register::RAX {
+                    self.encode_x86_instruction(X86Instruction::with_reg_reg(
+                        Code::Mov_rm64_r64,
+                        destination_register,
+                        Register::RAX,
+                    ));
                 }
             }
-            InstructionIR::Return => {
-                //Restore the base pointer
-                self.encode_x86_instruction(X86Instruction::with_reg_reg(Code::Mov_r64_rm64, Register::RSP, Register::RBP));
-                self.encode_x86_instruction(X86Instruction::with_reg(Code::Pop_rm64, Register::RBP));
-
-                self.encode_x86_instruction(X86Instruction::with(Code::Retnq));
-            }
-            InstructionIR::NullReferenceCheck(reference_register) => {
-                let reference_register = register_mapping::get(*reference_register, DataSize::Bytes8);
+            InstructionIR::DivideInt64FromFrameMemory(destination, source_offset) => {
+                let destination_register = register_mapping::get(*destination, DataSize::Bytes8);
 
-                self.encode_x86_instruction(X86Instruction::with_reg_reg(Code::Xor_r64_rm64, Register::RAX, Register::RAX));
-                self.encode_x86_instruction(X86Instruction::with_reg_reg(Code::Cmp_r64_rm64, reference_register, Register::RAX));
+                if destination_register != Register::RAX {
+                    self.encode_x86_instruction(X86Instruction::with_reg_reg(
+                        Code::Mov_rm64_r64,
+                        Register::RAX,
+                        destination_register
+                    ));
+                }
 
-                let instruction_size = self.encode_x86_instruction_with_size(X86Instruction::try_with_branch(Code::Je_rel32_64, 0).unwrap());
-                compilation_data.unresolved_native_branches.insert(
-                    self.encoder_offset - instruction_size,
-                    self.error_handling.null_check_handler as usize
+                let divisor_offset = *source_offset;
+                self.generate_division_check(
+                    compilation_data,
+                    DataSize::Bytes8,
+                    |value| X86Instruction::try_with_mem_i32(
+                        Code::Cmp_rm64_imm8,
+                        MemoryOperand::with_base_displ(Register::RBP, divisor_offset),
+                        value
+                    ).unwrap()
                 );
+
+                self.encode_x86_instruction(X86Instruction::with(Code::Cqo));
+
+                self.encode_x86_instruction(X86Instruction::with_mem(
+                    Code::Idiv_rm64,
+                    MemoryOperand::with_base_displ(Register::RBP, *source_offset)
+                ));
+
+                if destination_register != Register::RAX {
+                    self.encode_x86_instruction(X86Instruction::with_reg_reg(
+                        Code::Mov_rm64_r64,
+                        destination_register,
+                        Register::RAX,
+                    ));
+                }
+            }
+            InstructionIR::RemainderInt64(destination, source) => {
+                let destination_register = register_mapping::get(*destination, DataSize::Bytes8);
+
+                if destination_register != Register::RAX {
+                    self.encode_x86_instruction(X86Instruction::with_reg_reg(
+                        Code::Mov_rm64_r64,
+                        Register::RAX,
+                        destination_register
+                    ));
+                }
+
+                let divisor_register = register_mapping::get(*source, DataSize::Bytes8);
+                self.generate_division_check(
+                    compilation_data,
+                    DataSize::Bytes8,
+                    |value| X86Instruction::try_with_reg_i32(Code::Cmp_rm64_imm8, divisor_register, value).unwrap()
+                );
+
+                self.encode_x86_instruction(X86Instruction::with(Code::Cqo));
+
+                self.encode_x86_instruction(X86Instruction::with_reg(
+                    Code::Idiv_rm64,
+                    divisor_register
+                ));
+
+                // The remainder is placed in RDX
+                if destination_register != Register::RDX {
+                    self.encode_x86_instruction(X86Instruction::with_reg_reg(
+                        Code::Mov_rm64_r64,
+                        destination_register,
+                        Register::RDX,
+                    ));
+                }
+            }
+            InstructionIR::NegateInt64(register) => {
+                self.encode_x86_instruction(X86Instruction::with_reg(
+                    Code::Neg_rm64,
+                    register_mapping::get(*register, DataSize::Bytes8)
+                ));
+            }
+            InstructionIR::NegateInt64FrameMemory(offset) => {
+                self.encode_x86_instruction(X86Instruction::with_mem(
+                    Code::Neg_rm64,
+                    MemoryOperand::with_base_displ(Register::RBP, *offset)
+                ));
+            }
+            InstructionIR::AndInt64(destination, source) => {
+                self.encode_x86_instruction(X86Instruction::with_reg_reg(
+                    Code::And_r64_rm64,
+                    register_mapping::get(*destination, DataSize::Bytes8),
+                    register_mapping::get(*source, DataSize::Bytes8)
+                ));
+            }
+            InstructionIR::AndInt64FromFrameMemory(destination, source_offset) => {
+                self.encode_x86_instruction(X86Instruction::with_reg_mem(
+                    Code::And_r64_rm64,
+                    register_mapping::get(*destination, DataSize::Bytes8),
+                    MemoryOperand::with_base_displ(Register::RBP, *source_offset)
+                ));
+            }
+            InstructionIR::AndInt64ToFrameMemory(destination_offset, source) => {
+                self.encode_x86_instruction(X86Instruction::with_mem_reg(
+                    Code::And_rm64_r64,
+                    MemoryOperand::with_base_displ(Register::RBP, *destination_offset),
+                    register_mapping::get(*source, DataSize::Bytes8)
+                ));
+            }
+            InstructionIR::OrInt64(destination, source) => {
+                self.encode_x86_instruction(X86Instruction::with_reg_reg(
+                    Code::Or_r64_rm64,
+                    register_mapping::get(*destination, DataSize::Bytes8),
+                    register_mapping::get(*source, DataSize::Bytes8)
+                ));
+            }
+            InstructionIR::OrInt64FromFrameMemory(destination, source_offset) => {
+                self.encode_x86_instruction(X86Instruction::with_reg_mem(
+                    Code::Or_r64_rm64,
+                    register_mapping::get(*destination, DataSize::Bytes8),
+                    MemoryOperand::with_base_displ(Register::RBP, *source_offset)
+                ));
+            }
+            InstructionIR::OrInt64ToFrameMemory(destination_offset, source) => {
+                self.encode_x86_instruction(X86Instruction::with_mem_reg(
+                    Code::Or_rm64_r64,
+                    MemoryOperand::with_base_displ(Register::RBP, *destination_offset),
+                    register_mapping::get(*source, DataSize::Bytes8)
+                ));
+            }
+            InstructionIR::XorInt64(destination, source) => {
+                self.encode_x86_instruction(X86Instruction::with_reg_reg(
+                    Code::Xor_r64_rm64,
+                    register_mapping::get(*destination, DataSize::Bytes8),
+                    register_mapping::get(*source, DataSize::Bytes8)
+                ));
+            }
+            InstructionIR::XorInt64FromFrameMemory(destination, source_offset) => {
+                self.encode_x86_instruction(X86Instruction::with_reg_mem(
+                    Code::Xor_r64_rm64,
+                    register_mapping::get(*destination, DataSize::Bytes8),
+                    MemoryOperand::with_base_displ(Register::RBP, *source_offset)
+                ));
+            }
+            InstructionIR::XorInt64ToFrameMemory(destination_offset, source) => {
+                self.encode_x86_instruction(X86Instruction::with_mem_reg(
+                    Code::Xor_rm64_r64,
+                    MemoryOperand::with_base_displ(Register::RBP, *destination_offset),
+                    register_mapping::get(*source, DataSize::Bytes8)
+                ));
+            }
+            InstructionIR::BitwiseNotInt64(register) => {
+                self.encode_x86_instruction(X86Instruction::with_reg(
+                    Code::Not_rm64,
+                    register_mapping::get(*register, DataSize::Bytes8)
+                ));
+            }
+            InstructionIR::BitwiseNotInt64FrameMemory(offset) => {
+                self.encode_x86_instruction(X86Instruction::with_mem(
+                    Code::Not_rm64,
+                    MemoryOperand::with_base_displ(Register::RBP, *offset)
+                ));
+            }
+            InstructionIR::ShiftLeftInt64(destination, source) => {
+                let count_register = register_mapping::get(*source, DataSize::Bytes8);
+                if count_register != Register::RCX {
+                    self.encode_x86_instruction(X86Instruction::with_reg_reg(
+                        Code::Mov_rm64_r64,
+                        Register::RCX,
+                        count_register
+                    ));
+                }
+
+                self.encode_x86_instruction(X86Instruction::with_reg_reg(
+                    Code::Shl_rm64_CL,
+                    register_mapping::get(*destination, DataSize::Bytes8),
+                    Register::CL
+                ));
+            }
+            InstructionIR::ShiftRightInt64(destination, source) => {
+                let count_register = register_mapping::get(*source, DataSize::Bytes8);
+                if count_register != Register::RCX {
+                    self.encode_x86_instruction(X86Instruction::with_reg_reg(
+                        Code::Mov_rm64_r64,
+                        Register::RCX,
+                        count_register
+                    ));
+                }
+
+                // Int64 is signed, so the sign bit is preserved
+                self.encode_x86_instruction(X86Instruction::with_reg_reg(
+                    Code::Sar_rm64_CL,
+                    register_mapping::get(*destination, DataSize::Bytes8),
+                    Register::CL
+                ));
+            }
+            InstructionIR::AddFloat64(destination, source) => {
+                self.encode_x86_instruction(X86Instruction::with_reg_reg(
+                    Code::Addsd_xmm_xmmm64,
+                    register_mapping::get(*destination, DataSize::Bytes8),
+                    register_mapping::get(*source, DataSize::Bytes8)
+                ));
+            }
+            InstructionIR::AddFloat64FromFrameMemory(destination, source_offset) => {
+                self.encode_x86_instruction(X86Instruction::with_reg_mem(
+                    Code::Addsd_xmm_xmmm64,
+                    register_mapping::get(*destination, DataSize::Bytes8),
+                    MemoryOperand::with_base_displ(Register::RBP, *source_offset)
+                ));
+            }
+            InstructionIR::SubFloat64(destination, source) => {
+                self.encode_x86_instruction(X86Instruction::with_reg_reg(
+                    Code::Subsd_xmm_xmmm64,
+                    register_mapping::get(*destination, DataSize::Bytes8),
+                    register_mapping::get(*source, DataSize::Bytes8)
+                ));
+            }
+            InstructionIR::SubFloat64FromFrameMemory(destination, source_offset) => {
+                self.encode_x86_instruction(X86Instruction::with_reg_mem(
+                    Code::Subsd_xmm_xmmm64,
+                    register_mapping::get(*destination, DataSize::Bytes8),
+                    MemoryOperand::with_base_displ(Register::RBP, *source_offset)
+                ));
+            }
+            InstructionIR::MultiplyFloat64(destination, source) => {
+                self.encode_x86_instruction(X86Instruction::with_reg_reg(
+                    Code::Mulsd_xmm_xmmm64,
+                    register_mapping::get(*destination, DataSize::Bytes8),
+                    register_mapping::get(*source, DataSize::Bytes8)
+                ));
+            }
+            InstructionIR::MultiplyFloat64FromFrameMemory(destination, source_offset) => {
+                self.encode_x86_instruction(X86Instruction::with_reg_mem(
+                    Code::Mulsd_xmm_xmmm64,
+                    register_mapping::get(*destination, DataSize::Bytes8),
+                    MemoryOperand::with_base_displ(Register::RBP, *source_offset)
+                ));
+            }
+            InstructionIR::DivideFloat64(destination, source) => {
+                self.encode_x86_instruction(X86Instruction::with_reg_reg(
+                    Code::Divsd_xmm_xmmm64,
+                    register_mapping::get(*destination, DataSize::Bytes8),
+                    register_mapping::get(*source, DataSize::Bytes8)
+                ));
+            }
+            InstructionIR::DivideFloat64FromFrameMemory(destination, source_offset) => {
+                self.encode_x86_instruction(X86Instruction::with_reg_mem(
+                    Code::Divsd_xmm_xmmm64,
+                    register_mapping::get(*destination, DataSize::Bytes8),
+                    MemoryOperand::with_base_displ(Register::RBP, *source_offset)
+                ));
+            }
+            InstructionIR::Call(signature, arguments, num_saved, instruction_index) => {
+                let func_to_call = self.binder.get(signature).unwrap();
+                if func_to_call.function_type() != &FunctionType::Managed {
+                    self.generate_store_external_caller(function, *instruction_index);
+                }
+
+                self.generate_call(function, compilation_data, func_to_call, arguments, *num_saved, CallDispatch::Direct);
+            }
+            InstructionIR::CallVirtual(signature, index, arguments, num_saved) => {
+                let func_to_call = self.binder.get(signature).unwrap();
+                self.generate_call(function, compilation_data, func_to_call, arguments, *num_saved, CallDispatch::Virtual(*index));
+            }
+            InstructionIR::CallInterface(signature, index, arguments, num_saved) => {
+                let func_to_call = self.binder.get(signature).unwrap();
+                self.generate_call(function, compilation_data, func_to_call, arguments, *num_saved, CallDispatch::Interface(*index));
+            }
+            InstructionIR::LoadFunction(signature, destination_register) => {
+                let destination_register = register_mapping::get(*destination_register, DataSize::Bytes8);
+                let func_to_load = self.binder.get(signature).unwrap();
+
+                match func_to_load.function_type() {
+                    FunctionType::Managed => {
+                        // The function might not have been compiled yet, so the address is resolved later
+                        compilation_data.unresolved_function_calls.push(UnresolvedFunctionCall {
+                            call_type: FunctionCallType::Absolute,
+                            call_offset: self.encoder_offset,
+                            signature: signature.clone()
+                        });
+
+                        self.encode_x86_instruction(X86Instruction::try_with_reg_u64(Code::Mov_r64_imm64, destination_register, 0).unwrap());
+                    }
+                    FunctionType::External | FunctionType::Native => {
+                        self.encode_x86_instruction(X86Instruction::try_with_reg_u64(
+                            Code::Mov_r64_imm64,
+                            destination_register,
+                            func_to_load.address().unwrap() as u64
+                        ).unwrap());
+                    }
+                }
+            }
+            InstructionIR::CallIndirect(function_type, function_source, arguments, num_saved, instruction_index) => {
+                // The reference might be to an external or native function
+                let func_to_call = FunctionDeclaration::with_function_reference(function_type).unwrap();
+                self.generate_store_external_caller(function, *instruction_index);
+                self.generate_call(function, compilation_data, &func_to_call, arguments, *num_saved, CallDispatch::Indirect(function_source));
+            }
+            InstructionIR::Return => {
+                //Restore the base pointer
+                self.encode_x86_instruction(X86Instruction::with_reg_reg(Code::Mov_r64_rm64, Register::RSP, Register::RBP));
+                self.encode_x86_instruction(X86Instruction::with_reg(Code::Pop_rm64, Register::RBP));
+
+                self.encode_x86_instruction(X86Instruction::with(Code::Retnq));
+            }
+            InstructionIR::NullReferenceCheck(reference_register) => {
+                let reference_register = register_mapping::get(*reference_register, DataSize::Bytes8);
+
+                self.encode_x86_instruction(X86Instruction::with_reg_reg(Code::Test_rm64_r64, reference_register, reference_register));
+
+                self.generate_error_check(compilation_data, Code::Je_rel32_64, self.error_handling.null_check_handler);
+            }
+            InstructionIR::Throw(exception_register) => {
+                let exception_register = register_mapping::get(*exception_register, DataSize::Bytes8);
+                if exception_register != Register::RAX {
+                    self.encode_x86_instruction(X86Instruction::with_reg_reg(Code::Mov_r64_rm64, Register::RAX, exception_register));
+                }
+
+                // The handler is called such that the return address tells where the exception was thrown
+                let instruction_size = self.encode_x86_instruction_with_size(X86Instruction::try_with_branch(Code::Call_rel32_64, 0).unwrap());
+                compilation_data.unresolved_native_branches.insert(self.encoder_offset - instruction_size, self.error_handling.throw_handler as usize);
+            }
+            InstructionIR::ThrowIfNotNull(exception_register) => {
+                let exception_register = register_mapping::get(*exception_register, DataSize::Bytes8);
+                if exception_register != Register::RAX {
+                    self.encode_x86_instruction(X86Instruction::with_reg_reg(Code::Mov_r64_rm64, Register::RAX, exception_register));
+                }
+
+                self.encode_x86_instruction(X86Instruction::with_reg_reg(Code::Test_rm64_r64, Register::RAX, Register::RAX));
+                self.generate_error_check(compilation_data, Code::Jne_rel32_64, self.error_handling.throw_handler);
             }
             InstructionIR::ArrayBoundsCheck(reference_register, index_register) => {
                 let reference_register = register_mapping::get(*reference_register, DataSize::Bytes8);
@@ -709,13 +1365,9 @@ impl<'a> CodeGenerator<'a> {
                 self.encode_x86_instruction(X86Instruction::with_reg_reg(Code::Cmp_r64_rm64, index_register, Register::RAX));
 
                 // By using an unsigned comparison, we only need one check.
-                let instruction_size = self.encode_x86_instruction_with_size(X86Instruction::try_with_branch(Code::Jae_rel32_64, 0).unwrap());
-                compilation_data.unresolved_native_branches.insert(
-                    self.encoder_offset - instruction_size,
-                    self.error_handling.array_bounds_check_handler as usize
-                );
+                self.generate_error_check(compilation_data, Code::Jae_rel32_64, self.error_handling.array_bounds_check_handler);
             }
-            InstructionIR::NewArray(element, size_register, num_saved) => {
+            InstructionIR::NewArray(element, size_register, num_saved, instruction_index) => {
                 let stack_alignment = (*num_saved as i32 % 2) * stack_layout::STACK_ENTRY_SIZE;
                 if stack_alignment > 0 {
                     self.encode_x86_instruction(X86Instruction::try_with_reg_i32(Code::Sub_rm64_imm32, Register::RSP, stack_alignment).unwrap());
@@ -732,11 +1384,7 @@ impl<'a> CodeGenerator<'a> {
                 self.encode_x86_instruction(X86Instruction::with_reg_reg(Code::Xor_r32_rm32, Register::EAX, Register::EAX));
                 self.encode_x86_instruction(X86Instruction::with_reg_reg(Code::Cmp_r32_rm32, Register::EAX, register_call_arguments::ARG1.full_register32()));
 
-                let instruction_size = self.encode_x86_instruction_with_size(X86Instruction::try_with_branch(Code::Jg_rel32_64, 0).unwrap());
-                compilation_data.unresolved_native_branches.insert(
-                    self.encoder_offset - instruction_size,
-                    self.error_handling.array_create_check_handler as usize
-                );
+                self.generate_error_check(compilation_data, Code::Jg_rel32_64, self.error_handling.array_create_check_handler);
 
                 self.encode_x86_instruction(X86Instruction::try_with_reg_u64(
                     Code::Mov_r64_imm64,
@@ -744,11 +1392,18 @@ impl<'a> CodeGenerator<'a> {
                     array_type
                 ).unwrap());
 
+                // Needed if a garbage collection is triggered
+                self.encode_x86_instruction(X86Instruction::with_reg_reg(Code::Mov_r64_rm64, register_call_arguments::ARG2, Register::RBP));
+                self.encode_x86_instruction(X86Instruction::try_with_reg_u64(Code::Mov_r64_imm64, register_call_arguments::ARG3, function as *const _ as u64).unwrap());
+                self.encode_x86_instruction(X86Instruction::try_with_reg_i32(Code::Mov_rm64_imm32, register_call_arguments::ARG4, *instruction_index as i32).unwrap());
+
                 call_direct(
                     |instruction| self.encode_x86_instruction(instruction),
-                    runtime_interface::new_array as u64
+                    runtime_interface::new_array as *const () as u64
                 );
 
+                self.generate_out_of_memory_check(compilation_data);
+
                 if stack_alignment > 0 {
                     self.encode_x86_instruction(X86Instruction::try_with_reg_i32(Code::Add_rm64_imm32, Register::RSP, stack_alignment).unwrap());
                 }
@@ -758,95 +1413,32 @@ impl<'a> CodeGenerator<'a> {
                 let index_register = register_mapping::get(*index_register, DataSize::Bytes8);
 
                 let memory_operand = self.compute_array_element_address(element, reference_register, index_register);
-
-                // Load the element
-                match element.size() {
-                    8 => {
-                        let destination_register = register_mapping::get(*destination_register, DataSize::Bytes8);
-                        self.encode_x86_instruction(X86Instruction::with_reg_mem(
-                            Code::Mov_r64_rm64,
-                            destination_register,
-                            memory_operand,
-                        ));
-                    }
-                    4 => {
-                        let destination_register = register_mapping::get(*destination_register, DataSize::Bytes4);
-
-                        match element {
-                            TypeId::Float32 => {
-                                self.encode_x86_instruction(X86Instruction::with_reg_mem(
-                                    Code::Movss_xmm_xmmm32,
-                                    destination_register,
-                                    memory_operand,
-                                ));
-                            }
-                            _ => {
-                                self.encode_x86_instruction(X86Instruction::with_reg_mem(
-                                    Code::Mov_r32_rm32,
-                                    destination_register,
-                                    memory_operand,
-                                ));
-                            }
-                        }
-                    }
-                    1 => {
-                        let destination_register = register_mapping::get(*destination_register, DataSize::Bytes1);
-                        self.encode_x86_instruction(X86Instruction::with_reg_mem(
-                            Code::Mov_r8_rm8,
-                            destination_register,
-                            memory_operand,
-                        ));
-                    }
-                    _ => { panic!("unexpected."); }
-                }
+                self.generate_load_element(element, *destination_register, memory_operand);
             }
             InstructionIR::StoreElement(element, reference_register, index_register, value_register) => {
                 let reference_register = register_mapping::get(*reference_register, DataSize::Bytes8);
                 let index_register = register_mapping::get(*index_register, DataSize::Bytes8);
 
                 let memory_operand = self.compute_array_element_address(element, reference_register, index_register);
+                self.generate_store_element(element, memory_operand, *value_register);
 
-                //Store the element
-                match element.size() {
-                    8 => {
-                        let value_register = register_mapping::get(*value_register, DataSize::Bytes8);
-                        self.encode_x86_instruction(X86Instruction::with_mem_reg(
-                            Code::Mov_rm64_r64,
-                            memory_operand,
-                            value_register,
-                        ));
-                    }
-                    4 => {
-                        match element {
-                            TypeId::Float32 => {
-                                let value_register = register_mapping::get(*value_register, DataSize::Bytes4);
-                                self.encode_x86_instruction(X86Instruction::with_mem_reg(
-                                    Code::Movss_xmmm32_xmm,
-                                    memory_operand,
-                                    value_register,
-                                ));
-                            }
-                            _ => {
-                                let value_register_32 = register_mapping::get(*value_register, DataSize::Bytes4);
-                                self.encode_x86_instruction(X86Instruction::with_mem_reg(
-                                    Code::Mov_rm32_r32,
-                                    memory_operand,
-                                    value_register_32,
-                                ));
-                            }
-                        }
-                    }
-                    1 => {
-                        let value_register = register_mapping::get(*value_register, DataSize::Bytes1);
-                        self.encode_x86_instruction(X86Instruction::with_mem_reg(
-                            Code::Mov_rm8_r8,
-                            memory_operand,
-                            value_register,
-                        ));
-                    }
-                    _ => { panic!("unexpected."); }
+                if element.is_reference() {
+                    self.generate_write_barrier(reference_register, register_mapping::get(*value_register, DataSize::Bytes8));
                 }
             }
+            InstructionIR::LoadStructElement(element, destination_offset, reference_register, index_register) => {
+                let reference_register = register_mapping::get(*reference_register, DataSize::Bytes8);
+                let index_register = register_mapping::get(*index_register, DataSize::Bytes8);
+                let size = self.compute_struct_element_address(element, reference_register, index_register);
+                self.generate_copy_memory(Register::RBP, *destination_offset, Register::RDI, array::LENGTH_SIZE as i32, size);
+            }
+            InstructionIR::StoreStructElement(element, reference_register, index_register, source_offset) => {
+                let reference_register = register_mapping::get(*reference_register, DataSize::Bytes8);
+                let index_register = register_mapping::get(*index_register, DataSize::Bytes8);
+                let size = self.compute_struct_element_address(element, reference_register, index_register);
+                self.generate_copy_memory(Register::RDI, array::LENGTH_SIZE as i32, Register::RBP, *source_offset, size);
+                self.generate_struct_write_barrier(element, reference_register, *source_offset);
+            }
             InstructionIR::LoadArrayLength(destination_register, reference_register) => {
                 let destination_register = register_mapping::get(*destination_register, DataSize::Bytes4);
                 let reference_register = register_mapping::get(*reference_register, DataSize::Bytes8);
@@ -856,7 +1448,222 @@ impl<'a> CodeGenerator<'a> {
                     MemoryOperand::with_base(reference_register)
                 ));
             },
-            InstructionIR::NewObject(class_type) => {
+            InstructionIR::ArrayCopy(element) => {
+                // The operands are on the stack, with the count at the top. RCX is saved as it holds the number of elements to copy.
+                push_r64(|instruction| self.encode_x86_instruction(instruction), Register::RCX);
+                let (source_offset, source_index_offset, destination_offset, destination_index_offset, count_offset) = (40, 32, 24, 16, 8);
+
+                self.generate_array_range_check(compilation_data, source_offset, source_index_offset, count_offset);
+                self.generate_array_range_check(compilation_data, destination_offset, destination_index_offset, count_offset);
+
+                let size = element.size() as i32;
+                self.generate_array_range_address(Register::RSI, source_offset, source_index_offset, size);
+                self.generate_array_range_address(Register::RDI, destination_offset, destination_index_offset, size);
+                self.encode_x86_instruction(X86Instruction::with_reg_mem(
+                    Code::Mov_r32_rm32,
+                    Register::ECX,
+                    MemoryOperand::with_base_displ(Register::RSP, count_offset)
+                ));
+
+                // If the destination is after the source, they might overlap and the copy is done backwards
+                self.encode_x86_instruction(X86Instruction::with_reg_reg(Code::Cmp_r64_rm64, Register::RDI, Register::RSI));
+                let forward_jump = self.encoder_offset;
+                let forward_jump_size = self.encode_x86_instruction_with_size(X86Instruction::try_with_branch(Code::Jbe_rel32_64, 0).unwrap());
+
+                self.encode_x86_instruction(X86Instruction::with_reg_mem(
+                    Code::Lea_r64_m,
+                    Register::RAX,
+                    MemoryOperand::with_index_scale_displ_size(Register::RCX, size as u32, -size, 4)
+                ));
+                self.encode_x86_instruction(X86Instruction::with_reg_reg(Code::Add_r64_rm64, Register::RSI, Register::RAX));
+                self.encode_x86_instruction(X86Instruction::with_reg_reg(Code::Add_r64_rm64, Register::RDI, Register::RAX));
+                self.encode_x86_instruction(X86Instruction::with(Code::Std));
+                self.encode_x86_instruction(rep_movs(size));
+                self.encode_x86_instruction(X86Instruction::with(Code::Cld));
+                let end_jump = self.encoder_offset;
+                let end_jump_size = self.encode_x86_instruction_with_size(X86Instruction::try_with_branch(Code::Jmp_rel32_64, 0).unwrap());
+
+                self.set_jump_target(forward_jump, forward_jump_size);
+                self.encode_x86_instruction(rep_movs(size));
+                self.set_jump_target(end_jump, end_jump_size);
+
+                pop_r64(|instruction| self.encode_x86_instruction(instruction), Register::RCX);
+                self.encode_x86_instruction(X86Instruction::try_with_reg_i32(Code::Add_rm64_imm32, Register::RSP, 5 * stack_layout::STACK_ENTRY_SIZE).unwrap());
+            }
+            InstructionIR::ArrayFill(element) => {
+                // The operands are on the stack, with the value at the top
+                push_r64(|instruction| self.encode_x86_instruction(instruction), Register::RCX);
+
+                self.encode_x86_instruction(X86Instruction::with_reg_mem(Code::Mov_r64_rm64, Register::RDI, MemoryOperand::with_base_displ(Register::RSP, 16)));
+                self.encode_x86_instruction(X86Instruction::with_reg_mem(Code::Mov_r32_rm32, Register::ECX, MemoryOperand::with_base(Register::RDI)));
+                self.encode_x86_instruction(X86Instruction::try_with_reg_i32(Code::Add_rm64_imm32, Register::RDI, array::LENGTH_SIZE as i32).unwrap());
+                self.encode_x86_instruction(X86Instruction::with_reg_mem(Code::Mov_r64_rm64, Register::RAX, MemoryOperand::with_base_displ(Register::RSP, 8)));
+
+                let rep_stos = match element.size() {
+                    8 => X86Instruction::try_with_rep_stosq(64).unwrap(),
+                    4 => X86Instruction::try_with_rep_stosd(64).unwrap(),
+                    1 => X86Instruction::try_with_rep_stosb(64).unwrap(),
+                    _ => { panic!("unexpected."); }
+                };
+                self.encode_x86_instruction(rep_stos);
+
+                pop_r64(|instruction| self.encode_x86_instruction(instruction), Register::RCX);
+                self.encode_x86_instruction(X86Instruction::try_with_reg_i32(Code::Add_rm64_imm32, Register::RSP, 2 * stack_layout::STACK_ENTRY_SIZE).unwrap());
+            }
+            InstructionIR::ArrayEquals(element, destination_register) => {
+                // The arrays are compared bitwise, which means that floats are not compared by value
+                push_r64(|instruction| self.encode_x86_instruction(instruction), Register::RCX);
+
+                self.encode_x86_instruction(X86Instruction::with_reg_mem(Code::Mov_r64_rm64, Register::RSI, MemoryOperand::with_base_displ(Register::RSP, 16)));
+                self.encode_x86_instruction(X86Instruction::with_reg_mem(Code::Mov_r64_rm64, Register::RDI, MemoryOperand::with_base_displ(Register::RSP, 8)));
+                self.encode_x86_instruction(X86Instruction::with_reg_mem(Code::Mov_r32_rm32, Register::ECX, MemoryOperand::with_base(Register::RSI)));
+                self.encode_x86_instruction(X86Instruction::with_reg_mem(Code::Lea_r64_m, Register::RSI, MemoryOperand::with_base_displ(Register::RSI, array::LENGTH_SIZE as i32)));
+                self.encode_x86_instruction(X86Instruction::with_reg_mem(Code::Lea_r64_m, Register::RDI, MemoryOperand::with_base_displ(Register::RDI, array::LENGTH_SIZE as i32)));
+
+                // The flags of the length comparison are kept if there are no elements to compare
+                self.encode_x86_instruction(X86Instruction::with_reg_mem(
+                    Code::Cmp_r32_rm32,
+                    Register::ECX,
+                    MemoryOperand::with_base_displ(Register::RDI, -(array::LENGTH_SIZE as i32))
+                ));
+                let not_equal_jump = self.encoder_offset;
+                let not_equal_jump_size = self.encode_x86_instruction_with_size(X86Instruction::try_with_branch(Code::Jne_rel32_64, 0).unwrap());
+
+                let repe_cmps = match element.size() {
+                    8 => X86Instruction::try_with_repe_cmpsq(64).unwrap(),
+                    4 => X86Instruction::try_with_repe_cmpsd(64).unwrap(),
+                    1 => X86Instruction::try_with_repe_cmpsb(64).unwrap(),
+                    _ => { panic!("unexpected."); }
+                };
+                self.encode_x86_instruction(repe_cmps);
+                self.set_jump_target(not_equal_jump, not_equal_jump_size);
+
+                self.encode_x86_instruction(X86Instruction::with_reg(Code::Sete_rm8, Register::AL));
+                self.encode_x86_instruction(X86Instruction::with_reg_reg(Code::Movzx_r32_rm8, Register::EAX, Register::AL));
+
+                pop_r64(|instruction| self.encode_x86_instruction(instruction), Register::RCX);
+                self.encode_x86_instruction(X86Instruction::try_with_reg_i32(Code::Add_rm64_imm32, Register::RSP, 2 * stack_layout::STACK_ENTRY_SIZE).unwrap());
+
+                let destination_register = register_mapping::get(*destination_register, DataSize::Bytes4);
+                if destination_register != Register::EAX {
+                    self.encode_x86_instruction(X86Instruction::with_reg_reg(Code::Mov_r32_rm32, destination_register, Register::EAX));
+                }
+            }
+            InstructionIR::Array2DBoundsCheck(reference_register, row_register, column_register) => {
+                let reference_register = register_mapping::get(*reference_register, DataSize::Bytes8);
+                let row_register = register_mapping::get(*row_register, DataSize::Bytes8);
+                let column_register = register_mapping::get(*column_register, DataSize::Bytes8);
+
+                for (dimension_offset, index_register) in [(array::ROWS_OFFSET, row_register), (array::COLUMNS_OFFSET, column_register)] {
+                    self.encode_x86_instruction(X86Instruction::with_reg_mem(
+                        Code::Mov_r32_rm32,
+                        Register::EAX,
+                        MemoryOperand::with_base_displ(reference_register, dimension_offset as i32)
+                    ));
+                    self.encode_x86_instruction(X86Instruction::with_reg_reg(Code::Cmp_r64_rm64, index_register, Register::RAX));
+
+                    self.generate_error_check(compilation_data, Code::Jae_rel32_64, self.error_handling.array_bounds_check_handler);
+                }
+            }
+            InstructionIR::NewArray2D(element, rows_register, columns_register, num_saved, instruction_index) => {
+                let stack_alignment = (*num_saved as i32 % 2) * stack_layout::STACK_ENTRY_SIZE;
+                if stack_alignment > 0 {
+                    self.encode_x86_instruction(X86Instruction::try_with_reg_i32(Code::Sub_rm64_imm32, Register::RSP, stack_alignment).unwrap());
+                }
+
+                let array_type_id = TypeId::Array2D(Box::new(element.clone()));
+                let array_type = self.type_storage.entry(array_type_id);
+                let array_type = array_type as *const Type as *const u64 as u64;
+
+                // The rows are moved first, as the register of the columns argument might hold the rows
+                let rows_register = register_mapping::get(*rows_register, DataSize::Bytes8);
+                let columns_register = register_mapping::get(*columns_register, DataSize::Bytes8);
+                self.encode_x86_instruction(X86Instruction::with_reg_reg(Code::Mov_r64_rm64, register_call_arguments::ARG1, rows_register));
+                self.encode_x86_instruction(X86Instruction::with_reg_reg(Code::Mov_r64_rm64, register_call_arguments::ARG2, columns_register));
+
+                // Check that the dimensions are valid
+                self.encode_x86_instruction(X86Instruction::with_reg_reg(Code::Xor_r32_rm32, Register::EAX, Register::EAX));
+                for dimension_register in [register_call_arguments::ARG1, register_call_arguments::ARG2] {
+                    self.encode_x86_instruction(X86Instruction::with_reg_reg(Code::Cmp_r32_rm32, Register::EAX, dimension_register.full_register32()));
+                    self.generate_error_check(compilation_data, Code::Jg_rel32_64, self.error_handling.array_create_check_handler);
+                }
+
+                self.encode_x86_instruction(X86Instruction::try_with_reg_u64(
+                    Code::Mov_r64_imm64,
+                    register_call_arguments::ARG0,
+                    array_type
+                ).unwrap());
+
+                // Needed if a garbage collection is triggered
+                self.encode_x86_instruction(X86Instruction::with_reg_reg(Code::Mov_r64_rm64, register_call_arguments::ARG3, Register::RBP));
+                self.encode_x86_instruction(X86Instruction::try_with_reg_u64(Code::Mov_r64_imm64, register_call_arguments::ARG4, function as *const _ as u64).unwrap());
+                self.encode_x86_instruction(X86Instruction::try_with_reg_i32(Code::Mov_rm64_imm32, register_call_arguments::ARG5, *instruction_index as i32).unwrap());
+
+                call_direct(
+                    |instruction| self.encode_x86_instruction(instruction),
+                    runtime_interface::new_array2d as *const () as u64
+                );
+
+                self.generate_out_of_memory_check(compilation_data);
+
+                if stack_alignment > 0 {
+                    self.encode_x86_instruction(X86Instruction::try_with_reg_i32(Code::Add_rm64_imm32, Register::RSP, stack_alignment).unwrap());
+                }
+            }
+            InstructionIR::LoadElement2D(element, destination_register, reference_register, row_register, column_register) => {
+                let reference_register = register_mapping::get(*reference_register, DataSize::Bytes8);
+                let row_register = register_mapping::get(*row_register, DataSize::Bytes8);
+                let column_register = register_mapping::get(*column_register, DataSize::Bytes8);
+
+                let memory_operand = self.compute_array2d_element_address(element, reference_register, row_register, column_register);
+                self.generate_load_element(element, *destination_register, memory_operand);
+            }
+            InstructionIR::StoreElement2D(element, reference_register, row_register, column_register, value_register) => {
+                let reference_register = register_mapping::get(*reference_register, DataSize::Bytes8);
+                let row_register = register_mapping::get(*row_register, DataSize::Bytes8);
+                let column_register = register_mapping::get(*column_register, DataSize::Bytes8);
+
+                let memory_operand = self.compute_array2d_element_address(element, reference_register, row_register, column_register);
+                self.generate_store_element(element, memory_operand, *value_register);
+
+                if element.is_reference() {
+                    self.generate_write_barrier(reference_register, register_mapping::get(*value_register, DataSize::Bytes8));
+                }
+            }
+            InstructionIR::LoadArrayDimension(dimension, destination_register, reference_register) => {
+                let destination_register = register_mapping::get(*destination_register, DataSize::Bytes4);
+                let reference_register = register_mapping::get(*reference_register, DataSize::Bytes8);
+                let dimension_offset = if *dimension == 0 { array::ROWS_OFFSET } else { array::COLUMNS_OFFSET };
+                self.encode_x86_instruction(X86Instruction::with_reg_mem(
+                    Code::Mov_r32_rm32,
+                    destination_register,
+                    MemoryOperand::with_base_displ(reference_register, dimension_offset as i32)
+                ));
+            }
+            InstructionIR::IsInstance(class_type, destination_register, reference_register) => {
+                let reference_register = register_mapping::get(*reference_register, DataSize::Bytes8);
+                self.generate_subtype_test(reference_register, class_type);
+
+                // The moves do not change the flags
+                let destination_register_8 = register_mapping::get(*destination_register, DataSize::Bytes1);
+                let destination_register_32 = register_mapping::get(*destination_register, DataSize::Bytes4);
+                self.encode_x86_instruction(X86Instruction::with_reg(Code::Sete_rm8, destination_register_8));
+                self.encode_x86_instruction(X86Instruction::with_reg_reg(Code::Movzx_r32_rm8, destination_register_32, destination_register_8));
+            }
+            InstructionIR::CastClass(class_type, reference_register) => {
+                let reference_register = register_mapping::get(*reference_register, DataSize::Bytes8);
+
+                // Null can be cast to any class
+                self.encode_x86_instruction(X86Instruction::with_reg_reg(Code::Test_rm64_r64, reference_register, reference_register));
+                let null_branch_offset = self.encoder_offset;
+                let null_branch_size = self.encode_x86_instruction_with_size(X86Instruction::try_with_branch(Code::Je_rel32_64, 0).unwrap());
+
+                self.generate_subtype_test(reference_register, class_type);
+                self.generate_error_check(compilation_data, Code::Jne_rel32_64, self.error_handling.invalid_cast_check_handler);
+
+                self.set_jump_target(null_branch_offset, null_branch_size);
+            }
+            InstructionIR::NewObject(class_type, instruction_index) => {
                 let class_type = self.type_storage.entry(class_type.clone());
                 let class_type = class_type as *const Type as *const u64 as u64;
 
@@ -866,103 +1673,63 @@ impl<'a> CodeGenerator<'a> {
                     class_type
                 ).unwrap());
 
+                // Needed if a garbage collection is triggered
+                self.encode_x86_instruction(X86Instruction::with_reg_reg(Code::Mov_r64_rm64, register_call_arguments::ARG1, Register::RBP));
+                self.encode_x86_instruction(X86Instruction::try_with_reg_u64(Code::Mov_r64_imm64, register_call_arguments::ARG2, function as *const _ as u64).unwrap());
+                self.encode_x86_instruction(X86Instruction::try_with_reg_i32(Code::Mov_rm64_imm32, register_call_arguments::ARG3, *instruction_index as i32).unwrap());
+
                 call_direct(
                     |instruction| self.encode_x86_instruction(instruction),
-                    runtime_interface::new_class as u64
+                    runtime_interface::new_class as *const () as u64
                 );
+
+                self.generate_out_of_memory_check(compilation_data);
             },
             InstructionIR::LoadField(field_type, field_offset, destination_register, reference_register) => {
                 let reference_register = register_mapping::get(*reference_register, DataSize::Bytes8);
-
                 let memory_operand = MemoryOperand::with_base_displ(reference_register, *field_offset as i32);
-
-                // Load the field
-                match field_type.size() {
-                    8 => {
-                        let destination_register = register_mapping::get(*destination_register, DataSize::Bytes8);
-                        self.encode_x86_instruction(X86Instruction::with_reg_mem(
-                            Code::Mov_r64_rm64,
-                            destination_register,
-                            memory_operand,
-                        ));
-                    }
-                    4 => {
-                        let destination_register = register_mapping::get(*destination_register, DataSize::Bytes4);
-
-                        match field_type {
-                            TypeId::Float32 => {
-                                self.encode_x86_instruction(X86Instruction::with_reg_mem(
-                                    Code::Movss_xmm_xmmm32,
-                                    destination_register,
-                                    memory_operand,
-                                ));
-                            }
-                            _ => {
-                                self.encode_x86_instruction(X86Instruction::with_reg_mem(
-                                    Code::Mov_r32_rm32,
-                                    destination_register,
-                                    memory_operand,
-                                ));
-                            }
-                        }
-                    }
-                    1 => {
-                        let destination_register = register_mapping::get(*destination_register, DataSize::Bytes1);
-                        self.encode_x86_instruction(X86Instruction::with_reg_mem(
-                            Code::Mov_r8_rm8,
-                            destination_register,
-                            memory_operand,
-                        ));
-                    }
-                    _ => { panic!("unexpected."); }
-                }
+                self.generate_load_field(field_type, memory_operand, *destination_register);
             }
             InstructionIR::StoreField(field_type, field_offset, reference_register, value_register) => {
                 let reference_register = register_mapping::get(*reference_register, DataSize::Bytes8);
-
                 let memory_operand = MemoryOperand::with_base_displ(reference_register, *field_offset as i32);
+                self.generate_store_field(field_type, memory_operand, *value_register);
 
-                //Store the field
-                match field_type.size() {
-                    8 => {
-                        let value_register = register_mapping::get(*value_register, DataSize::Bytes8);
-                        self.encode_x86_instruction(X86Instruction::with_mem_reg(
-                            Code::Mov_rm64_r64,
-                            memory_operand,
-                            value_register,
-                        ));
-                    }
-                    4 => {
-                        match field_type {
-                            TypeId::Float32 => {
-                                let value_register = register_mapping::get(*value_register, DataSize::Bytes4);
-                                self.encode_x86_instruction(X86Instruction::with_mem_reg(
-                                    Code::Movss_xmmm32_xmm,
-                                    memory_operand,
-                                    value_register,
-                                ));
-                            }
-                            _ => {
-                                let value_register_32 = register_mapping::get(*value_register, DataSize::Bytes4);
-                                self.encode_x86_instruction(X86Instruction::with_mem_reg(
-                                    Code::Mov_rm32_r32,
-                                    memory_operand,
-                                    value_register_32,
-                                ));
-                            }
-                        }
-                    }
-                    1 => {
-                        let value_register = register_mapping::get(*value_register, DataSize::Bytes1);
-                        self.encode_x86_instruction(X86Instruction::with_mem_reg(
-                            Code::Mov_rm8_r8,
-                            memory_operand,
-                            value_register,
-                        ));
-                    }
-                    _ => { panic!("unexpected."); }
+                if field_type.is_reference() {
+                    self.generate_write_barrier(reference_register, register_mapping::get(*value_register, DataSize::Bytes8));
                 }
             }
+            InstructionIR::LoadStaticField(class_type, field_name, destination_register) => {
+                let field_type = self.generate_static_field_address(class_type, field_name);
+                self.generate_load_field(&field_type, MemoryOperand::with_base(Register::RDI), *destination_register);
+            }
+            InstructionIR::StoreStaticField(class_type, field_name, value_register) => {
+                // Static fields are roots, so no write barrier is needed
+                let field_type = self.generate_static_field_address(class_type, field_name);
+                self.generate_store_field(&field_type, MemoryOperand::with_base(Register::RDI), *value_register);
+            }
+            InstructionIR::LoadStructField(field_type, field_offset, destination_offset, reference_register) => {
+                let reference_register = register_mapping::get(*reference_register, DataSize::Bytes8);
+                let size = self.type_storage.size_of(field_type);
+                self.generate_copy_memory(Register::RBP, *destination_offset, reference_register, *field_offset as i32, size);
+            }
+            InstructionIR::StoreStructField(field_type, field_offset, reference_register, source_offset) => {
+                let reference_register = register_mapping::get(*reference_register, DataSize::Bytes8);
+                let size = self.type_storage.size_of(field_type);
+                self.generate_copy_memory(reference_register, *field_offset as i32, Register::RBP, *source_offset, size);
+                self.generate_struct_write_barrier(field_type, reference_register, *source_offset);
+            }
+            InstructionIR::LoadStaticStructField(class_type, field_name, destination_offset) => {
+                let field_type = self.generate_static_field_address(class_type, field_name);
+                let size = self.type_storage.size_of(&field_type);
+                self.generate_copy_memory(Register::RBP, *destination_offset, Register::RDI, 0, size);
+            }
+            InstructionIR::StoreStaticStructField(class_type, field_name, source_offset) => {
+                // Static fields are roots, so no write barrier is needed
+                let field_type = self.generate_static_field_address(class_type, field_name);
+                let size = self.type_storage.size_of(&field_type);
+                self.generate_copy_memory(Register::RDI, 0, Register::RBP, *source_offset, size);
+            }
             InstructionIR::BranchLabel(label) => {
                 compilation_data.branch_targets.insert(*label, self.encoder_offset);
             }
@@ -971,21 +1738,31 @@ impl<'a> CodeGenerator<'a> {
                 let instruction_size = self.encode_x86_instruction_with_size(X86Instruction::try_with_branch(Code::Jmp_rel32_64, 0).unwrap());
                 compilation_data.unresolved_branches.insert(self.encoder_offset - instruction_size, (*target, instruction_size));
             }
-            InstructionIR::Compare(op_type, op1, op2) => {
-                let op1 = register_mapping::get(*op1, DataSize::Bytes4);
-                let op2 = register_mapping::get(*op2, DataSize::Bytes4);
+            InstructionIR::Compare(op_type, op1_register, op2_register) => {
+                let op1 = register_mapping::get(*op1_register, DataSize::Bytes4);
+                let op2 = register_mapping::get(*op2_register, DataSize::Bytes4);
 
                 match op_type {
                     TypeId::Float32 => {
                         self.encode_x86_instruction(X86Instruction::with_reg_reg(Code::Ucomiss_xmm_xmmm32, op1, op2));
                     }
+                    TypeId::Float64 => {
+                        self.encode_x86_instruction(X86Instruction::with_reg_reg(Code::Ucomisd_xmm_xmmm64, op1, op2));
+                    }
+                    TypeId::Int64 => {
+                        self.encode_x86_instruction(X86Instruction::with_reg_reg(
+                            Code::Cmp_r64_rm64,
+                            register_mapping::get(*op1_register, DataSize::Bytes8),
+                            register_mapping::get(*op2_register, DataSize::Bytes8)
+                        ));
+                    }
                     _ => {
                         self.encode_x86_instruction(X86Instruction::with_reg_reg(Code::Cmp_r32_rm32, op1, op2));
                     }
                 }
             }
-            InstructionIR::CompareFromFrameMemory(op_type, op1, op2_offset) => {
-                let op1 = register_mapping::get(*op1, DataSize::Bytes4);
+            InstructionIR::CompareFromFrameMemory(op_type, op1_register, op2_offset) => {
+                let op1 = register_mapping::get(*op1_register, DataSize::Bytes4);
 
                 match op_type {
                     TypeId::Float32 => {
@@ -995,6 +1772,20 @@ impl<'a> CodeGenerator<'a> {
                             MemoryOperand::with_base_displ(Register::RBP, *op2_offset)
                         ));
                     }
+                    TypeId::Float64 => {
+                        self.encode_x86_instruction(X86Instruction::with_reg_mem(
+                            Code::Ucomisd_xmm_xmmm64,
+                            op1,
+                            MemoryOperand::with_base_displ(Register::RBP, *op2_offset)
+                        ));
+                    }
+                    TypeId::Int64 => {
+                        self.encode_x86_instruction(X86Instruction::with_reg_mem(
+                            Code::Cmp_r64_rm64,
+                            register_mapping::get(*op1_register, DataSize::Bytes8),
+                            MemoryOperand::with_base_displ(Register::RBP, *op2_offset)
+                        ));
+                    }
                     _ => {
                         self.encode_x86_instruction(X86Instruction::with_reg_mem(
                             Code::Cmp_r32_rm32,
@@ -1004,13 +1795,20 @@ impl<'a> CodeGenerator<'a> {
                     }
                 }
             }
-            InstructionIR::CompareToFrameMemory(op_type, op1_offset, op2) => {
-                let op2 = register_mapping::get(*op2, DataSize::Bytes4);
+            InstructionIR::CompareToFrameMemory(op_type, op1_offset, op2_register) => {
+                let op2 = register_mapping::get(*op2_register, DataSize::Bytes4);
 
                 match op_type {
-                    TypeId::Float32 => {
+                    TypeId::Float32 | TypeId::Float64 => {
                         unimplemented!();
                     }
+                    TypeId::Int64 => {
+                        self.encode_x86_instruction(X86Instruction::with_mem_reg(
+                            Code::Cmp_rm64_r64,
+                            MemoryOperand::with_base_displ(Register::RBP, *op1_offset),
+                            register_mapping::get(*op2_register, DataSize::Bytes8)
+                        ));
+                    }
                     _ => {
                         self.encode_x86_instruction(X86Instruction::with_mem_reg(
                             Code::Cmp_rm32_r32,
@@ -1059,8 +1857,8 @@ impl<'a> CodeGenerator<'a> {
                 let mut buffer = self.encoder.take_buffer();
 
                 unsafe {
-                    *(buffer.as_mut_ptr().add(jump_start + 1) as *mut i32) = (self.encoder_offset as isize - true_branch_start as isize) as i32;
-                    *(buffer.as_mut_ptr().add(compare_jump_start + 2) as *mut i32) = (true_branch_start as isize - false_branch_start as isize) as i32;
+                    (buffer.as_mut_ptr().add(jump_start + 1) as *mut i32).write_unaligned((self.encoder_offset as isize - true_branch_start as isize) as i32);
+                    (buffer.as_mut_ptr().add(compare_jump_start + 2) as *mut i32).write_unaligned((true_branch_start as isize - false_branch_start as isize) as i32);
                 }
 
                 self.encoder.set_buffer(buffer);
@@ -1072,7 +1870,7 @@ impl<'a> CodeGenerator<'a> {
 
                 call_direct(
                     |instruction| self.encode_x86_instruction(instruction),
-                    runtime_interface::print_stack_frame as u64
+                    runtime_interface::print_stack_frame as *const () as u64
                 );
             }
             InstructionIR::GarbageCollect(instruction_index) => {
@@ -1082,7 +1880,7 @@ impl<'a> CodeGenerator<'a> {
 
                 call_direct(
                     |instruction| self.encode_x86_instruction(instruction),
-                    runtime_interface::garbage_collect as u64
+                    runtime_interface::garbage_collect as *const () as u64
                 );
             }
         }
@@ -1097,6 +1895,614 @@ impl<'a> CodeGenerator<'a> {
         }
     }
 
+    fn generate_call(&mut self,
+                     function: &Function,
+                     compilation_data: &mut FunctionCompilationData,
+                     func_to_call: &FunctionDeclaration,
+                     arguments: &[Variable],
+                     num_saved: usize,
+                     dispatch: CallDispatch) {
+        let calling_conventions = CallingConventions::new();
+        let check_native_error = func_to_call.function_type() != &FunctionType::Managed || matches!(dispatch, CallDispatch::Indirect(_));
+
+        //Align the stack
+        let stack_alignment = calling_conventions.stack_alignment(func_to_call, num_saved);
+        if stack_alignment > 0 {
+            self.encode_x86_instruction(X86Instruction::try_with_reg_i32(Code::Sub_rm64_imm32, Register::RSP, stack_alignment).unwrap());
+        }
+
+        let mut call_argument_instructions = Vec::new();
+        calling_conventions.call_function_arguments(
+            func_to_call,
+            arguments,
+            &mut call_argument_instructions
+        );
+        self.generate_instructions(function, compilation_data, &call_argument_instructions);
+
+        match (func_to_call.function_type(), dispatch) {
+            (_, CallDispatch::Indirect(function_source)) => {
+                let mut load_instructions = Vec::new();
+                function_source.move_to_register(HardwareRegister::IntSpill, &mut load_instructions);
+                self.generate_instructions(function, compilation_data, &load_instructions);
+
+                // Function references that have not been assigned are null
+                self.encode_x86_instruction(X86Instruction::with_reg_reg(Code::Test_rm64_r64, Register::RAX, Register::RAX));
+                self.generate_error_check(compilation_data, Code::Je_rel32_64, self.error_handling.null_check_handler);
+
+                self.encode_x86_instruction(X86Instruction::with_reg(Code::Call_rm64, Register::RAX));
+            }
+            (FunctionType::Managed, CallDispatch::Virtual(virtual_index)) => {
+                // The object is passed as the first argument, and the virtual table is the first field of the type
+                self.encode_x86_instruction(X86Instruction::with_reg_mem(
+                    Code::Mov_r64_rm64,
+                    Register::RAX,
+                    MemoryOperand::with_base_displ(register_call_arguments::ARG0, -(HEADER_SIZE as i32))
+                ));
+                self.encode_x86_instruction(X86Instruction::with_reg_mem(
+                    Code::Mov_r64_rm64,
+                    Register::RAX,
+                    MemoryOperand::with_base_displ(Register::RAX, VIRTUAL_TABLE_OFFSET)
+                ));
+                self.encode_x86_instruction(X86Instruction::with_mem(
+                    Code::Call_rm64,
+                    MemoryOperand::with_base_displ(Register::RAX, (virtual_index * std::mem::size_of::<FunctionAddress>()) as i32)
+                ));
+            }
+            (FunctionType::Managed, CallDispatch::Interface(interface_index)) => {
+                let interface_type = self.type_storage.get(func_to_call.class().as_ref().unwrap()).unwrap() as *const Type as u64;
+
+                // Search for the table of the interface, which exists as the verifier has checked that the object implements it
+                self.encode_x86_instruction(X86Instruction::with_reg_mem(
+                    Code::Mov_r64_rm64,
+                    Register::RAX,
+                    MemoryOperand::with_base_displ(register_call_arguments::ARG0, -(HEADER_SIZE as i32))
+                ));
+                self.encode_x86_instruction(X86Instruction::with_reg_mem(
+                    Code::Mov_r64_rm64,
+                    Register::RAX,
+                    MemoryOperand::with_base_displ(Register::RAX, INTERFACE_TABLES_OFFSET)
+                ));
+                self.encode_x86_instruction(X86Instruction::try_with_reg_u64(Code::Mov_r64_imm64, Register::R11, interface_type).unwrap());
+
+                let loop_start = self.encoder_offset;
+                self.encode_x86_instruction(X86Instruction::with_mem_reg(Code::Cmp_rm64_r64, MemoryOperand::with_base(Register::RAX), Register::R11));
+                let found_branch_offset = self.encoder_offset;
+                let found_branch_size = self.encode_x86_instruction_with_size(X86Instruction::try_with_branch(Code::Je_rel32_64, 0).unwrap());
+                self.encode_x86_instruction(X86Instruction::try_with_reg_i32(Code::Add_rm64_imm32, Register::RAX, INTERFACE_TABLE_SIZE).unwrap());
+                let loop_branch_offset = self.encoder_offset;
+                let loop_branch_size = self.encode_x86_instruction_with_size(X86Instruction::try_with_branch(Code::Jmp_rel32_64, 0).unwrap());
+                self.set_jump_target_to(loop_branch_offset, loop_branch_size, loop_start);
+                self.set_jump_target(found_branch_offset, found_branch_size);
+
+                self.encode_x86_instruction(X86Instruction::with_reg_mem(
+                    Code::Mov_r64_rm64,
+                    Register::RAX,
+                    MemoryOperand::with_base_displ(Register::RAX, INTERFACE_TABLE_FUNCTIONS_OFFSET)
+                ));
+                self.encode_x86_instruction(X86Instruction::with_mem(
+                    Code::Call_rm64,
+                    MemoryOperand::with_base_displ(Register::RAX, (interface_index * std::mem::size_of::<FunctionAddress>()) as i32)
+                ));
+            }
+            (FunctionType::External, _) | (FunctionType::Native, _) => {
+                call_direct(
+                    |instruction| self.encode_x86_instruction(instruction),
+                    func_to_call.address().unwrap() as u64
+                )
+            }
+            (FunctionType::Managed, CallDispatch::Direct) => {
+                compilation_data.unresolved_function_calls.push(UnresolvedFunctionCall {
+                    call_type: FunctionCallType::Relative,
+                    call_offset: self.encoder_offset,
+                    signature: func_to_call.signature()
+                });
+
+                self.encode_x86_instruction(X86Instruction::try_with_branch(
+                    Code::Call_rel32_64,
+                    0
+                ).unwrap());
+            }
+        }
+
+        //If we have passed arguments via the stack, adjust the stack pointer.
+        let num_stack_arguments = calling_conventions.num_stack_arguments(func_to_call.parameters());
+        if num_stack_arguments > 0 {
+            self.encode_x86_instruction(X86Instruction::try_with_reg_i32(
+                Code::Add_rm64_imm32,
+                Register::RSP,
+                num_stack_arguments as i32 * stack_layout::STACK_ENTRY_SIZE
+            ).unwrap());
+        }
+
+        //Unalign the stack
+        if stack_alignment > 0 {
+            self.encode_x86_instruction(X86Instruction::try_with_reg_i32(Code::Add_rm64_imm32, Register::RSP, stack_alignment).unwrap());
+        }
+
+        if check_native_error {
+            self.generate_native_error_check(compilation_data);
+        }
+    }
+
+    // External functions use the frame to collect garbage when allocating
+    fn generate_store_external_caller(&mut self, function: &Function, instruction_index: usize) {
+        // RSI & RDI are only used for calls, and the arguments have not been moved into them yet
+        self.encode_x86_instruction(X86Instruction::try_with_reg_u64(
+            Code::Mov_r64_imm64,
+            Register::RDI,
+            self.runtime_error.external_caller_address() as u64
+        ).unwrap());
+        self.encode_x86_instruction(X86Instruction::with_mem_reg(
+            Code::Mov_rm64_r64,
+            MemoryOperand::with_base_displ(Register::RDI, std::mem::offset_of!(ExternalCallerFrame, base_pointer) as i32),
+            Register::RBP
+        ));
+        self.encode_x86_instruction(X86Instruction::try_with_reg_u64(Code::Mov_r64_imm64, Register::RSI, function as *const _ as u64).unwrap());
+        self.encode_x86_instruction(X86Instruction::with_mem_reg(
+            Code::Mov_rm64_r64,
+            MemoryOperand::with_base_displ(Register::RDI, std::mem::offset_of!(ExternalCallerFrame, function) as i32),
+            Register::RSI
+        ));
+        self.encode_x86_instruction(X86Instruction::try_with_mem_i32(
+            Code::Mov_rm64_imm32,
+            MemoryOperand::with_base_displ(Register::RDI, std::mem::offset_of!(ExternalCallerFrame, instruction_index) as i32),
+            instruction_index as i32
+        ).unwrap());
+    }
+
+    // Sets the zero flag if the type of the object is the given type or a subtype of it. Null is not a subtype of any type.
+    fn generate_subtype_test(&mut self, object_register: Register, type_id: &TypeId) {
+        // RSI & RDI are only used for calls, so they are free to use here.
+        let target_type = self.type_storage.get(type_id).unwrap() as *const Type as u64;
+        self.encode_x86_instruction(X86Instruction::try_with_reg_u64(Code::Mov_r64_imm64, Register::RDI, target_type).unwrap());
+
+        self.encode_x86_instruction(X86Instruction::with_reg_reg(Code::Test_rm64_r64, object_register, object_register));
+        let null_branch_offset = self.encoder_offset;
+        let null_branch_size = self.encode_x86_instruction_with_size(X86Instruction::try_with_branch(Code::Je_rel32_64, 0).unwrap());
+
+        self.encode_x86_instruction(X86Instruction::with_reg_mem(
+            Code::Mov_r64_rm64,
+            Register::RSI,
+            MemoryOperand::with_base_displ(object_register, -(HEADER_SIZE as i32))
+        ));
+        self.encode_x86_instruction(X86Instruction::with_reg_mem(
+            Code::Mov_r64_rm64,
+            Register::RSI,
+            MemoryOperand::with_base_displ(Register::RSI, SUPERTYPES_OFFSET)
+        ));
+
+        // The supertypes are null terminated
+        let loop_start = self.encoder_offset;
+        self.encode_x86_instruction(X86Instruction::with_mem_reg(Code::Cmp_rm64_r64, MemoryOperand::with_base(Register::RSI), Register::RDI));
+        let found_branch_offset = self.encoder_offset;
+        let found_branch_size = self.encode_x86_instruction_with_size(X86Instruction::try_with_branch(Code::Je_rel32_64, 0).unwrap());
+        self.encode_x86_instruction(X86Instruction::try_with_reg_i32(Code::Add_rm64_imm32, Register::RSI, 8).unwrap());
+        self.encode_x86_instruction(X86Instruction::try_with_mem_i32(
+            Code::Cmp_rm64_imm8,
+            MemoryOperand::with_base_displ(Register::RSI, -8),
+            0
+        ).unwrap());
+        let loop_branch_offset = self.encoder_offset;
+        let loop_branch_size = self.encode_x86_instruction_with_size(X86Instruction::try_with_branch(Code::Jne_rel32_64, 0).unwrap());
+        self.set_jump_target_to(loop_branch_offset, loop_branch_size, loop_start);
+
+        // Not found, the target type is never null
+        self.set_jump_target(null_branch_offset, null_branch_size);
+        self.encode_x86_instruction(X86Instruction::with_reg_reg(Code::Test_rm64_r64, Register::RDI, Register::RDI));
+
+        self.set_jump_target(found_branch_offset, found_branch_size);
+    }
+
+    fn generate_out_of_memory_check(&mut self, compilation_data: &mut FunctionCompilationData) {
+        // The allocation returns null if there is no memory left
+        self.encode_x86_instruction(X86Instruction::with_reg_reg(Code::Test_rm64_r64, Register::RAX, Register::RAX));
+
+        self.generate_error_check(compilation_data, Code::Je_rel32_64, self.error_handling.out_of_memory_handler);
+    }
+
+    fn generate_error_check(&mut self,
+                            compilation_data: &mut FunctionCompilationData,
+                            error_condition: Code,
+                            handler: *const std::ffi::c_void) {
+        let skip_condition = match error_condition {
+            Code::Je_rel32_64 => Code::Jne_rel32_64,
+            Code::Jne_rel32_64 => Code::Je_rel32_64,
+            Code::Jae_rel32_64 => Code::Jb_rel32_64,
+            Code::Jb_rel32_64 => Code::Jae_rel32_64,
+            Code::Jg_rel32_64 => Code::Jle_rel32_64,
+            _ => panic!("Unsupported error condition: {:?}.", error_condition)
+        };
+
+        let skip_branch_offset = self.encoder_offset;
+        let skip_branch_size = self.encode_x86_instruction_with_size(X86Instruction::try_with_branch(skip_condition, 0).unwrap());
+
+        // The handler is called such that the return address tells where the error happened
+        let instruction_size = self.encode_x86_instruction_with_size(X86Instruction::try_with_branch(Code::Call_rel32_64, 0).unwrap());
+        compilation_data.unresolved_native_branches.insert(self.encoder_offset - instruction_size, handler as usize);
+
+        self.set_jump_target(skip_branch_offset, skip_branch_size);
+    }
+
+    fn generate_stack_overflow_check(&mut self, compilation_data: &mut FunctionCompilationData) {
+        self.encode_x86_instruction(X86Instruction::try_with_reg_u64(
+            Code::Mov_r64_imm64,
+            Register::RAX,
+            self.stack_limit.limit_address() as u64
+        ).unwrap());
+        self.encode_x86_instruction(X86Instruction::with_reg_mem(Code::Cmp_r64_rm64, Register::RSP, MemoryOperand::with_base(Register::RAX)));
+
+        self.generate_error_check(compilation_data, Code::Jb_rel32_64, self.error_handling.stack_overflow_check_handler);
+    }
+
+    fn generate_native_error_check(&mut self, compilation_data: &mut FunctionCompilationData) {
+        // RDI is only used for calls, so the return value is kept
+        self.encode_x86_instruction(X86Instruction::try_with_reg_u64(
+            Code::Mov_r64_imm64,
+            Register::RDI,
+            self.runtime_error.native_error_flag_address() as u64
+        ).unwrap());
+        self.encode_x86_instruction(X86Instruction::try_with_mem_i32(Code::Cmp_rm64_imm8, MemoryOperand::with_base(Register::RDI), 0).unwrap());
+
+        self.generate_error_check(compilation_data, Code::Jne_rel32_64, self.error_handling.native_error_handler);
+    }
+
+    fn generate_division_check<F: Fn(i32) -> X86Instruction>(&mut self,
+                                                              compilation_data: &mut FunctionCompilationData,
+                                                              data_size: DataSize,
+                                                              compare_divisor: F) {
+        // Assumes that the dividend is in RAX
+        self.encode_x86_instruction(compare_divisor(0));
+        self.generate_error_check(compilation_data, Code::Je_rel32_64, self.error_handling.division_by_zero_check_handler);
+
+        // MIN / -1 does not fit in the data size
+        self.encode_x86_instruction(compare_divisor(-1));
+        let not_minus_one_branch_offset = self.encoder_offset;
+        let not_minus_one_branch_size = self.encode_x86_instruction_with_size(X86Instruction::try_with_branch(Code::Jne_rel32_64, 0).unwrap());
+
+        match data_size {
+            DataSize::Bytes8 => {
+                // RDX is overwritten by the sign extension anyway
+                self.encode_x86_instruction(X86Instruction::try_with_reg_i64(Code::Mov_r64_imm64, Register::RDX, i64::MIN).unwrap());
+                self.encode_x86_instruction(X86Instruction::with_reg_reg(Code::Cmp_r64_rm64, Register::RAX, Register::RDX));
+            }
+            _ => {
+                self.encode_x86_instruction(X86Instruction::try_with_reg_i32(Code::Cmp_rm32_imm32, Register::EAX, i32::MIN).unwrap());
+            }
+        }
+
+        self.generate_error_check(compilation_data, Code::Je_rel32_64, self.error_handling.division_overflow_check_handler);
+
+        self.set_jump_target(not_minus_one_branch_offset, not_minus_one_branch_size);
+    }
+
+    fn generate_load_field(&mut self, field_type: &TypeId, memory_operand: MemoryOperand, destination_register: HardwareRegister) {
+        match field_type.size() {
+            8 => {
+                let destination_register = register_mapping::get(destination_register, DataSize::Bytes8);
+
+                match field_type {
+                    TypeId::Float64 => {
+                        self.encode_x86_instruction(X86Instruction::with_reg_mem(
+                            Code::Movsd_xmm_xmmm64,
+                            destination_register,
+                            memory_operand,
+                        ));
+                    }
+                    _ => {
+                        self.encode_x86_instruction(X86Instruction::with_reg_mem(
+                            Code::Mov_r64_rm64,
+                            destination_register,
+                            memory_operand,
+                        ));
+                    }
+                }
+            }
+            4 => {
+                let destination_register = register_mapping::get(destination_register, DataSize::Bytes4);
+
+                match field_type {
+                    TypeId::Float32 => {
+                        self.encode_x86_instruction(X86Instruction::with_reg_mem(
+                            Code::Movss_xmm_xmmm32,
+                            destination_register,
+                            memory_operand,
+                        ));
+                    }
+                    _ => {
+                        self.encode_x86_instruction(X86Instruction::with_reg_mem(
+                            Code::Mov_r32_rm32,
+                            destination_register,
+                            memory_operand,
+                        ));
+                    }
+                }
+            }
+            1 => {
+                // Zero extended, as the full register is used when comparing
+                let destination_register = register_mapping::get(destination_register, DataSize::Bytes4);
+                self.encode_x86_instruction(X86Instruction::with_reg_mem(
+                    Code::Movzx_r32_rm8,
+                    destination_register,
+                    memory_operand,
+                ));
+            }
+            _ => { panic!("unexpected."); }
+        }
+    }
+
+    fn generate_store_field(&mut self, field_type: &TypeId, memory_operand: MemoryOperand, value_register: HardwareRegister) {
+        match field_type.size() {
+            8 => {
+                let value_register = register_mapping::get(value_register, DataSize::Bytes8);
+
+                match field_type {
+                    TypeId::Float64 => {
+                        self.encode_x86_instruction(X86Instruction::with_mem_reg(
+                            Code::Movsd_xmmm64_xmm,
+                            memory_operand,
+                            value_register,
+                        ));
+                    }
+                    _ => {
+                        self.encode_x86_instruction(X86Instruction::with_mem_reg(
+                            Code::Mov_rm64_r64,
+                            memory_operand,
+                            value_register,
+                        ));
+                    }
+                }
+            }
+            4 => {
+                match field_type {
+                    TypeId::Float32 => {
+                        let value_register = register_mapping::get(value_register, DataSize::Bytes4);
+                        self.encode_x86_instruction(X86Instruction::with_mem_reg(
+                            Code::Movss_xmmm32_xmm,
+                            memory_operand,
+                            value_register,
+                        ));
+                    }
+                    _ => {
+                        let value_register_32 = register_mapping::get(value_register, DataSize::Bytes4);
+                        self.encode_x86_instruction(X86Instruction::with_mem_reg(
+                            Code::Mov_rm32_r32,
+                            memory_operand,
+                            value_register_32,
+                        ));
+                    }
+                }
+            }
+            1 => {
+                let value_register = register_mapping::get(value_register, DataSize::Bytes1);
+                self.encode_x86_instruction(X86Instruction::with_mem_reg(
+                    Code::Mov_rm8_r8,
+                    memory_operand,
+                    value_register,
+                ));
+            }
+            _ => { panic!("unexpected."); }
+        }
+    }
+
+    fn generate_copy_memory(&mut self,
+                            destination_base: Register,
+                            destination_offset: i32,
+                            source_base: Register,
+                            source_offset: i32,
+                            size: usize) {
+        // Struct sizes are padded to 8 bytes. RSI is only used for calls, so it is free to use here.
+        for chunk_offset in (0..size as i32).step_by(8) {
+            self.encode_x86_instruction(X86Instruction::with_reg_mem(
+                Code::Mov_r64_rm64,
+                Register::RSI,
+                MemoryOperand::with_base_displ(source_base, source_offset + chunk_offset)
+            ));
+
+            self.encode_x86_instruction(X86Instruction::with_mem_reg(
+                Code::Mov_rm64_r64,
+                MemoryOperand::with_base_displ(destination_base, destination_offset + chunk_offset),
+                Register::RSI
+            ));
+        }
+    }
+
+    fn generate_struct_write_barrier(&mut self, struct_type: &TypeId, object_register: Register, source_offset: i32) {
+        for reference_offset in self.type_storage.reference_offsets(struct_type) {
+            self.encode_x86_instruction(X86Instruction::with_reg_mem(
+                Code::Mov_r64_rm64,
+                Register::RSI,
+                MemoryOperand::with_base_displ(Register::RBP, source_offset + reference_offset as i32)
+            ));
+
+            self.generate_write_barrier(object_register, Register::RSI);
+        }
+    }
+
+    fn generate_static_field_address(&mut self, class_type: &TypeId, field_name: &str) -> TypeId {
+        // The static fields are never moved, so the address is embedded. RDI is only used for calls, so it is free to use here.
+        let class = self.type_storage.get(class_type).unwrap().class.as_ref().unwrap();
+        let field_type = class.get_static_field(field_name).unwrap().type_id().clone();
+        let field_address = self.memory_manager.static_field_address(class, field_name).unwrap();
+
+        self.encode_x86_instruction(X86Instruction::try_with_reg_u64(
+            Code::Mov_r64_imm64,
+            Register::RDI,
+            field_address as u64
+        ).unwrap());
+
+        field_type
+    }
+
+    fn generate_write_barrier(&mut self, object_register: Register, value_register: Register) {
+        // Only stores of young objects into old objects needs to be remembered.
+        // RSI & RDI are only used for calls, so they are free to use here.
+        let young_generation = &self.memory_manager.young_generation.segments()[0];
+        let young_generation_start = young_generation.data().as_ptr() as u64;
+        let young_generation_size = young_generation.size() as i32;
+
+        self.encode_x86_instruction(X86Instruction::try_with_reg_u64(Code::Mov_r64_imm64, Register::RDI, young_generation_start).unwrap());
+
+        // By using an unsigned comparison, null is treated as not being young
+        self.encode_x86_instruction(X86Instruction::with_reg_reg(Code::Mov_r64_rm64, Register::RSI, value_register));
+        self.encode_x86_instruction(X86Instruction::with_reg_reg(Code::Sub_r64_rm64, Register::RSI, Register::RDI));
+        self.encode_x86_instruction(X86Instruction::try_with_reg_i32(Code::Cmp_rm64_imm32, Register::RSI, young_generation_size).unwrap());
+        let value_not_young_jump = self.encoder_offset;
+        let value_not_young_jump_size = self.encode_x86_instruction_with_size(X86Instruction::try_with_branch(Code::Jae_rel32_64, 0).unwrap());
+
+        self.encode_x86_instruction(X86Instruction::with_reg_reg(Code::Mov_r64_rm64, Register::RSI, object_register));
+        self.encode_x86_instruction(X86Instruction::with_reg_reg(Code::Sub_r64_rm64, Register::RSI, Register::RDI));
+        self.encode_x86_instruction(X86Instruction::try_with_reg_i32(Code::Cmp_rm64_imm32, Register::RSI, young_generation_size).unwrap());
+        let object_young_jump = self.encoder_offset;
+        let object_young_jump_size = self.encode_x86_instruction_with_size(X86Instruction::try_with_branch(Code::Jb_rel32_64, 0).unwrap());
+
+        // As we don't know which registers are alive, save all that can be used
+        let int_registers = [Register::RAX, Register::RCX, Register::RDX, Register::R8, Register::R9, Register::R10, Register::R11];
+        let float_registers = [Register::XMM0, Register::XMM1, Register::XMM2, Register::XMM3, Register::XMM4, Register::XMM5];
+
+        for register in &int_registers {
+            push_r64(|instruction| self.encode_x86_instruction(instruction), *register);
+        }
+
+        for register in &float_registers {
+            push_xmm(|instruction| self.encode_x86_instruction(instruction), *register);
+        }
+
+        self.encode_x86_instruction(X86Instruction::with_reg_reg(Code::Mov_r64_rm64, register_call_arguments::ARG0, object_register));
+
+        // Align the stack, the original stack pointer is saved on the stack
+        self.encode_x86_instruction(X86Instruction::with_reg_reg(Code::Mov_r64_rm64, Register::RSI, Register::RSP));
+        self.encode_x86_instruction(X86Instruction::try_with_reg_i32(Code::And_rm64_imm32, Register::RSP, -16).unwrap());
+        self.encode_x86_instruction(X86Instruction::with_reg(Code::Push_r64, Register::RSI));
+        self.encode_x86_instruction(X86Instruction::with_reg(Code::Push_r64, Register::RSI));
+
+        call_direct(
+            |instruction| self.encode_x86_instruction(instruction),
+            runtime_interface::write_barrier as *const () as u64
+        );
+
+        self.encode_x86_instruction(X86Instruction::with_reg_mem(Code::Mov_r64_rm64, Register::RSP, MemoryOperand::with_base(Register::RSP)));
+
+        for register in float_registers.iter().rev() {
+            pop_xmm(|instruction| self.encode_x86_instruction(instruction), *register);
+        }
+
+        for register in int_registers.iter().rev() {
+            pop_r64(|instruction| self.encode_x86_instruction(instruction), *register);
+        }
+
+        self.set_jump_target(value_not_young_jump, value_not_young_jump_size);
+        self.set_jump_target(object_young_jump, object_young_jump_size);
+    }
+
+    fn generate_load_element(&mut self, element: &TypeId, destination_register: HardwareRegister, memory_operand: MemoryOperand) {
+        match element.size() {
+            8 => {
+                let destination_register = register_mapping::get(destination_register, DataSize::Bytes8);
+
+                match element {
+                    TypeId::Float64 => {
+                        self.encode_x86_instruction(X86Instruction::with_reg_mem(
+                            Code::Movsd_xmm_xmmm64,
+                            destination_register,
+                            memory_operand,
+                        ));
+                    }
+                    _ => {
+                        self.encode_x86_instruction(X86Instruction::with_reg_mem(
+                            Code::Mov_r64_rm64,
+                            destination_register,
+                            memory_operand,
+                        ));
+                    }
+                }
+            }
+            4 => {
+                let destination_register = register_mapping::get(destination_register, DataSize::Bytes4);
+
+                match element {
+                    TypeId::Float32 => {
+                        self.encode_x86_instruction(X86Instruction::with_reg_mem(
+                            Code::Movss_xmm_xmmm32,
+                            destination_register,
+                            memory_operand,
+                        ));
+                    }
+                    _ => {
+                        self.encode_x86_instruction(X86Instruction::with_reg_mem(
+                            Code::Mov_r32_rm32,
+                            destination_register,
+                            memory_operand,
+                        ));
+                    }
+                }
+            }
+            1 => {
+                // Zero extended, as the full register is used when comparing
+                let destination_register = register_mapping::get(destination_register, DataSize::Bytes4);
+                self.encode_x86_instruction(X86Instruction::with_reg_mem(
+                    Code::Movzx_r32_rm8,
+                    destination_register,
+                    memory_operand,
+                ));
+            }
+            _ => { panic!("unexpected."); }
+        }
+    }
+
+    fn generate_store_element(&mut self, element: &TypeId, memory_operand: MemoryOperand, value_register: HardwareRegister) {
+        match element.size() {
+            8 => {
+                let value_register = register_mapping::get(value_register, DataSize::Bytes8);
+
+                match element {
+                    TypeId::Float64 => {
+                        self.encode_x86_instruction(X86Instruction::with_mem_reg(
+                            Code::Movsd_xmmm64_xmm,
+                            memory_operand,
+                            value_register,
+                        ));
+                    }
+                    _ => {
+                        self.encode_x86_instruction(X86Instruction::with_mem_reg(
+                            Code::Mov_rm64_r64,
+                            memory_operand,
+                            value_register,
+                        ));
+                    }
+                }
+            }
+            4 => {
+                match element {
+                    TypeId::Float32 => {
+                        let value_register = register_mapping::get(value_register, DataSize::Bytes4);
+                        self.encode_x86_instruction(X86Instruction::with_mem_reg(
+                            Code::Movss_xmmm32_xmm,
+                            memory_operand,
+                            value_register,
+                        ));
+                    }
+                    _ => {
+                        let value_register_32 = register_mapping::get(value_register, DataSize::Bytes4);
+                        self.encode_x86_instruction(X86Instruction::with_mem_reg(
+                            Code::Mov_rm32_r32,
+                            memory_operand,
+                            value_register_32,
+                        ));
+                    }
+                }
+            }
+            1 => {
+                let value_register = register_mapping::get(value_register, DataSize::Bytes1);
+                self.encode_x86_instruction(X86Instruction::with_mem_reg(
+                    Code::Mov_rm8_r8,
+                    memory_operand,
+                    value_register,
+                ));
+            }
+            _ => { panic!("unexpected."); }
+        }
+    }
+
     fn compute_array_element_address(&mut self,
                                      element: &TypeId,
                                      reference_register: Register,
@@ -1107,8 +2513,70 @@ impl<'a> CodeGenerator<'a> {
         )
     }
 
+    fn compute_array2d_element_address(&mut self,
+                                       element: &TypeId,
+                                       reference_register: Register,
+                                       row_register: Register,
+                                       column_register: Register) -> MemoryOperand {
+        // The index of the element is computed in RSI, which can't overflow as the bounds have been checked
+        self.encode_x86_instruction(X86Instruction::with_reg_mem(
+            Code::Mov_r32_rm32,
+            Register::ESI,
+            MemoryOperand::with_base_displ(reference_register, array::COLUMNS_OFFSET as i32)
+        ));
+        self.encode_x86_instruction(X86Instruction::with_reg_reg(Code::Imul_r64_rm64, Register::RSI, row_register));
+        self.encode_x86_instruction(X86Instruction::with_reg_reg(Code::Add_r64_rm64, Register::RSI, column_register));
+
+        MemoryOperand::with_base_index_scale_displ_size(
+            reference_register,
+            Register::RSI, element.size() as u32, array::DIMENSIONS_SIZE as i32, 1
+        )
+    }
+
+    fn generate_array_range_check(&mut self,
+                                  compilation_data: &mut FunctionCompilationData,
+                                  array_offset: i32,
+                                  index_offset: i32,
+                                  count_offset: i32) {
+        // The end of the range is computed with 64 bits, where negative values become larger than any length
+        self.encode_x86_instruction(X86Instruction::with_reg_mem(Code::Mov_r32_rm32, Register::ESI, MemoryOperand::with_base_displ(Register::RSP, index_offset)));
+        self.encode_x86_instruction(X86Instruction::with_reg_mem(Code::Mov_r32_rm32, Register::EAX, MemoryOperand::with_base_displ(Register::RSP, count_offset)));
+        self.encode_x86_instruction(X86Instruction::with_reg_reg(Code::Add_r64_rm64, Register::RSI, Register::RAX));
+
+        self.encode_x86_instruction(X86Instruction::with_reg_mem(Code::Mov_r64_rm64, Register::RDI, MemoryOperand::with_base_displ(Register::RSP, array_offset)));
+        self.encode_x86_instruction(X86Instruction::with_reg_mem(Code::Mov_r32_rm32, Register::EDI, MemoryOperand::with_base(Register::RDI)));
+        self.encode_x86_instruction(X86Instruction::with_reg_reg(Code::Cmp_r64_rm64, Register::RDI, Register::RSI));
+
+        self.generate_error_check(compilation_data, Code::Jb_rel32_64, self.error_handling.array_bounds_check_handler);
+    }
+
+    fn generate_array_range_address(&mut self, destination: Register, array_offset: i32, index_offset: i32, size: i32) {
+        self.encode_x86_instruction(X86Instruction::with_reg_mem(Code::Mov_r64_rm64, destination, MemoryOperand::with_base_displ(Register::RSP, array_offset)));
+        self.encode_x86_instruction(X86Instruction::with_reg_mem(Code::Mov_r32_rm32, Register::EAX, MemoryOperand::with_base_displ(Register::RSP, index_offset)));
+        self.encode_x86_instruction(X86Instruction::with_reg_mem(
+            Code::Lea_r64_m,
+            destination,
+            MemoryOperand::with_base_index_scale_displ_size(destination, Register::RAX, size as u32, array::LENGTH_SIZE as i32, 1)
+        ));
+    }
+
+    fn compute_struct_element_address(&mut self,
+                                      element: &TypeId,
+                                      reference_register: Register,
+                                      index_register: Register) -> usize {
+        // The address (without the length) is placed in RDI
+        let size = self.type_storage.size_of(element);
+        self.encode_x86_instruction(X86Instruction::try_with_reg_reg_i32(Code::Imul_r64_rm64_imm32, Register::RDI, index_register, size as i32).unwrap());
+        self.encode_x86_instruction(X86Instruction::with_reg_reg(Code::Add_r64_rm64, Register::RDI, reference_register));
+        size
+    }
+
     fn set_jump_target(&mut self, branch_offset: usize, branch_instruction_size: usize) {
-        let jump_amount = (self.encoder_offset - branch_offset) as i32 - branch_instruction_size as i32;
+        self.set_jump_target_to(branch_offset, branch_instruction_size, self.encoder_offset);
+    }
+
+    fn set_jump_target_to(&mut self, branch_offset: usize, branch_instruction_size: usize, target_offset: usize) {
+        let jump_amount = target_offset as i32 - (branch_offset + branch_instruction_size) as i32;
         let mut buffer = self.encoder.take_buffer();
 
         let source_offset = branch_offset as i32 + branch_instruction_size as i32 - std::mem::size_of::<i32>() as i32;
@@ -1134,8 +2602,6 @@ impl<'a> CodeGenerator<'a> {
 pub mod register_mapping {
     use iced_x86::Register;
 
-    use crate::compiler::calling_conventions::{float_register_call_arguments, register_call_arguments};
-    use crate::compiler::FunctionCallType::Relative;
     use crate::compiler::ir::HardwareRegister;
 
     pub enum DataSize {
@@ -1242,6 +2708,16 @@ fn call_direct<F: FnMut(X86Instruction)>(mut encode_instruction: F, address: u64
     encode_instruction(X86Instruction::with_reg(Code::Call_rm64, Register::RAX));
 }
 
+fn rep_movs(size: i32) -> X86Instruction {
+    match size {
+        8 => X86Instruction::try_with_rep_movsq(64).unwrap(),
+        4 => X86Instruction::try_with_rep_movsd(64).unwrap(),
+        1 => X86Instruction::try_with_rep_movsb(64).unwrap(),
+        _ => { panic!("unexpected."); }
+    }
+}
+
+#[allow(dead_code)]
 pub fn push_r32<F: FnMut(X86Instruction)>(mut encode_instruction: F, register: Register) {
     encode_instruction(X86Instruction::try_with_reg_i32(Code::Sub_rm64_imm32, Register::RSP, register.size() as i32).unwrap());
     encode_instruction(X86Instruction::with_mem_reg(Code::Mov_rm32_r32, MemoryOperand::with_base(Register::RSP), register));
@@ -1254,9 +2730,10 @@ pub fn push_r64<F: FnMut(X86Instruction)>(mut encode_instruction: F, register: R
 
 pub fn push_xmm<F: FnMut(X86Instruction)>(mut encode_instruction: F, register: Register) {
     encode_instruction(X86Instruction::try_with_reg_i32(Code::Sub_rm64_imm32, Register::RSP, Register::RAX.size() as i32).unwrap());
-    encode_instruction(X86Instruction::with_mem_reg(Code::Movss_xmmm32_xmm, MemoryOperand::with_base(Register::RSP), register));
+    encode_instruction(X86Instruction::with_mem_reg(Code::Movsd_xmmm64_xmm, MemoryOperand::with_base(Register::RSP), register));
 }
 
+#[allow(dead_code)]
 pub fn pop_r32<F: FnMut(X86Instruction)>(mut encode_instruction: F, register: Register) {
     encode_instruction(X86Instruction::with_reg_mem(Code::Mov_r32_rm32, register, MemoryOperand::with_base(Register::RSP)));
     encode_instruction(X86Instruction::try_with_reg_i32(Code::Add_rm64_imm32, Register::RSP, register.size() as i32).unwrap());
@@ -1268,6 +2745,6 @@ pub fn pop_r64<F: FnMut(X86Instruction)>(mut encode_instruction: F, register: Re
 }
 
 pub fn pop_xmm<F: FnMut(X86Instruction)>(mut encode_instruction: F, register: Register) {
-    encode_instruction(X86Instruction::with_reg_mem(Code::Movss_xmm_xmmm32, register, MemoryOperand::with_base(Register::RSP)));
+    encode_instruction(X86Instruction::with_reg_mem(Code::Movsd_xmm_xmmm64, register, MemoryOperand::with_base(Register::RSP)));
     encode_instruction(X86Instruction::try_with_reg_i32(Code::Add_rm64_imm32, Register::RSP, Register::RAX.size() as i32).unwrap());
 }
\ No newline at end of file
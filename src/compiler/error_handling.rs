@@ -8,16 +8,30 @@ use crate::compiler::allocator::ExecutableMemoryAllocator;
 pub struct ErrorHandling {
     pub null_check_handler: *const std::ffi::c_void,
     pub array_create_check_handler: *const std::ffi::c_void,
-    pub array_bounds_check_handler: *const std::ffi::c_void
+    pub array_bounds_check_handler: *const std::ffi::c_void,
+    pub out_of_memory_handler: *const std::ffi::c_void,
+    pub division_by_zero_check_handler: *const std::ffi::c_void,
+    pub division_overflow_check_handler: *const std::ffi::c_void,
+    pub invalid_cast_check_handler: *const std::ffi::c_void,
+    pub stack_overflow_check_handler: *const std::ffi::c_void,
+    pub native_error_handler: *const std::ffi::c_void,
+    pub throw_handler: *const std::ffi::c_void
 }
 
 impl ErrorHandling {
     pub fn new(memory_allocator: &mut ExecutableMemoryAllocator) -> ErrorHandling {
         // Create handler calls
         let mut encoder = Encoder::new(64);
-        let null_check_handler_offset = ErrorHandling::generate_handler(&mut encoder, runtime_interface::null_error as u64);
-        let array_create_check_handler_offset = ErrorHandling::generate_handler(&mut encoder, runtime_interface::array_create_error as u64);
-        let array_bounds_check_handler_offset = ErrorHandling::generate_handler(&mut encoder, runtime_interface::array_bounds_error as u64);
+        let null_check_handler_offset = ErrorHandling::generate_handler(&mut encoder, runtime_interface::null_error as *const () as u64);
+        let array_create_check_handler_offset = ErrorHandling::generate_handler(&mut encoder, runtime_interface::array_create_error as *const () as u64);
+        let array_bounds_check_handler_offset = ErrorHandling::generate_handler(&mut encoder, runtime_interface::array_bounds_error as *const () as u64);
+        let out_of_memory_handler_offset = ErrorHandling::generate_handler(&mut encoder, runtime_interface::out_of_memory_error as *const () as u64);
+        let division_by_zero_check_handler_offset = ErrorHandling::generate_handler(&mut encoder, runtime_interface::division_by_zero_error as *const () as u64);
+        let division_overflow_check_handler_offset = ErrorHandling::generate_handler(&mut encoder, runtime_interface::division_overflow_error as *const () as u64);
+        let invalid_cast_check_handler_offset = ErrorHandling::generate_handler(&mut encoder, runtime_interface::invalid_cast_error as *const () as u64);
+        let stack_overflow_check_handler_offset = ErrorHandling::generate_handler(&mut encoder, runtime_interface::stack_overflow_error as *const () as u64);
+        let native_error_handler_offset = ErrorHandling::generate_handler(&mut encoder, runtime_interface::native_error as *const () as u64);
+        let throw_handler_offset = ErrorHandling::generate_handler(&mut encoder, runtime_interface::throw_exception as *const () as u64);
 
         // Allocate and copy memory
         let handler_buffer = encoder.take_buffer();
@@ -29,7 +43,14 @@ impl ErrorHandling {
         ErrorHandling {
             null_check_handler: unsafe { handler_ptr.add(null_check_handler_offset) },
             array_create_check_handler: unsafe { handler_ptr.add(array_create_check_handler_offset) },
-            array_bounds_check_handler: unsafe { handler_ptr.add(array_bounds_check_handler_offset) }
+            array_bounds_check_handler: unsafe { handler_ptr.add(array_bounds_check_handler_offset) },
+            out_of_memory_handler: unsafe { handler_ptr.add(out_of_memory_handler_offset) },
+            division_by_zero_check_handler: unsafe { handler_ptr.add(division_by_zero_check_handler_offset) },
+            division_overflow_check_handler: unsafe { handler_ptr.add(division_overflow_check_handler_offset) },
+            invalid_cast_check_handler: unsafe { handler_ptr.add(invalid_cast_check_handler_offset) },
+            stack_overflow_check_handler: unsafe { handler_ptr.add(stack_overflow_check_handler_offset) },
+            native_error_handler: unsafe { handler_ptr.add(native_error_handler_offset) },
+            throw_handler: unsafe { handler_ptr.add(throw_handler_offset) }
         }
     }
 
@@ -42,21 +63,40 @@ impl ErrorHandling {
             encoder.encode(&instruction, 0).unwrap();
         };
 
+        // The return address tells where in the managed code that the error happened
+        encode_x86_instruction(X86Instruction::with_reg_mem(
+            Code::Mov_r64_rm64,
+            register_call_arguments::ARG1,
+            MemoryOperand::with_base(Register::RSP)
+        ));
+        encode_x86_instruction(X86Instruction::with_reg_reg(Code::Mov_r64_rm64, register_call_arguments::ARG2, Register::RBP));
+
+        // The thrown exception (if any)
+        encode_x86_instruction(X86Instruction::with_reg_reg(Code::Mov_r64_rm64, register_call_arguments::ARG3, Register::RAX));
+
         // Invoke the handler with a pointer to current stack that will have the return values
+        encode_x86_instruction(X86Instruction::try_with_reg_i32(Code::And_rm64_imm8, Register::RSP, -16).unwrap());
         encode_x86_instruction(X86Instruction::try_with_reg_i32(Code::Sub_rm64_imm32, Register::RSP, 32).unwrap());
         encode_x86_instruction(X86Instruction::with_reg_reg(Code::Mov_rm64_r64, register_call_arguments::ARG0, Register::RSP));
 
         encode_x86_instruction(X86Instruction::try_with_reg_u64(Code::Mov_r64_imm64, Register::RAX, handler_function_address).unwrap());
         encode_x86_instruction(X86Instruction::with_reg(Code::Call_rm64, Register::RAX));
 
-        // Return address (entrypoint invoker)
+        // Where to continue, either the entrypoint invoker or an exception handler
         encode_x86_instruction(X86Instruction::with_reg_mem(
             Code::Mov_r64_rm64,
             Register::RDI,
             MemoryOperand::with_base_displ(Register::RSP, 0)
         ));
 
-        // Base & stack pointer when entrypoint was called
+        // The exception passed to the handler
+        encode_x86_instruction(X86Instruction::with_reg_mem(
+            Code::Mov_r64_rm64,
+            Register::RAX,
+            MemoryOperand::with_base_displ(Register::RSP, 24)
+        ));
+
+        // Base & stack pointer to continue with
         encode_x86_instruction(X86Instruction::with_reg_mem(
             Code::Mov_r64_rm64,
             Register::RBP,
@@ -69,8 +109,6 @@ impl ErrorHandling {
             MemoryOperand::with_base_displ(Register::RSP, 16)
         ));
 
-        // Simulate return instruction with custom address
-        encode_x86_instruction(X86Instruction::try_with_reg_i32(Code::Add_rm64_imm32, Register::RSP, 8).unwrap());
         encode_x86_instruction(X86Instruction::with_reg(Code::Jmp_rm64, Register::RDI));
 
         handler_offset
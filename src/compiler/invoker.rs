@@ -0,0 +1,100 @@
+use iced_x86::{Encoder, Code, Register, MemoryOperand};
+use iced_x86::Instruction as X86Instruction;
+
+use crate::compiler::allocator::ExecutableMemoryAllocator;
+use crate::compiler::calling_conventions::{CallingConventions, get_call_register, register_call_arguments};
+use crate::compiler::stack_layout;
+use crate::model::function::FunctionDeclaration;
+use crate::model::typesystem::TypeId;
+use crate::runtime::runtime_interface;
+
+/// Calls a function with the arguments given as an array of raw values, and returns the raw return value.
+pub type InvokerFunction = extern "C" fn(*const u64) -> u64;
+
+#[allow(dead_code)]
+pub fn generate_invoker(memory_allocator: &mut ExecutableMemoryAllocator, function: &FunctionDeclaration) -> InvokerFunction {
+    let mut encoder = Encoder::new(64);
+    let mut encode_x86_instruction = |instruction: X86Instruction| {
+        encoder.encode(&instruction, 0).unwrap();
+    };
+
+    let arguments_register = Register::RAX;
+    encode_x86_instruction(X86Instruction::with_reg_reg(Code::Mov_r64_rm64, arguments_register, register_call_arguments::ARG0));
+
+    // The invoker acts as an entry point, such that unhandled errors returns from it
+    encode_x86_instruction(X86Instruction::with_reg_mem(
+        Code::Mov_r64_rm64,
+        register_call_arguments::ARG0,
+        MemoryOperand::with_base_displ(Register::RSP, 0)
+    ));
+    encode_x86_instruction(X86Instruction::with_reg_reg(Code::Mov_r64_rm64, register_call_arguments::ARG1, Register::RBP));
+    encode_x86_instruction(X86Instruction::with_reg_reg(Code::Mov_r64_rm64, register_call_arguments::ARG2, Register::RSP));
+
+    encode_x86_instruction(X86Instruction::with_reg(Code::Push_r64, Register::RBP));
+    encode_x86_instruction(X86Instruction::with_reg_reg(Code::Mov_r64_rm64, Register::RBP, Register::RSP));
+
+    // A null function marks the end of the managed frames for the stack walker
+    encode_x86_instruction(X86Instruction::try_with_i32(Code::Pushq_imm32, 0).unwrap());
+    encode_x86_instruction(X86Instruction::with_reg(Code::Push_r64, arguments_register));
+
+    encode_x86_instruction(X86Instruction::try_with_reg_u64(Code::Mov_r64_imm64, Register::R11, runtime_interface::set_error_return as *const () as u64).unwrap());
+    encode_x86_instruction(X86Instruction::with_reg(Code::Call_rm64, Register::R11));
+
+    encode_x86_instruction(X86Instruction::with_reg_mem(
+        Code::Mov_r64_rm64,
+        arguments_register,
+        MemoryOperand::with_base_displ(Register::RBP, -2 * stack_layout::STACK_ENTRY_SIZE)
+    ));
+
+    let argument_operand = |argument_index: usize| {
+        MemoryOperand::with_base_displ(arguments_register, argument_index as i32 * stack_layout::STACK_ENTRY_SIZE)
+    };
+
+    let calling_conventions = CallingConventions::new();
+    let num_stack_arguments = calling_conventions.num_stack_arguments(function.parameters());
+    if num_stack_arguments % 2 == 1 {
+        encode_x86_instruction(X86Instruction::try_with_reg_i32(Code::Sub_rm64_imm32, Register::RSP, stack_layout::STACK_ENTRY_SIZE).unwrap());
+    }
+
+    for (argument_index, argument_type) in function.parameters().iter().enumerate().rev() {
+        if get_call_register(function, argument_index, argument_type).is_none() {
+            encode_x86_instruction(X86Instruction::with_mem(Code::Push_rm64, argument_operand(argument_index)));
+        }
+    }
+
+    for (argument_index, argument_type) in function.parameters().iter().enumerate() {
+        if let Some(register) = get_call_register(function, argument_index, argument_type) {
+            let code = match argument_type {
+                TypeId::Float32 => Code::Movss_xmm_xmmm32,
+                TypeId::Float64 => Code::Movsd_xmm_xmmm64,
+                _ => Code::Mov_r64_rm64
+            };
+
+            encode_x86_instruction(X86Instruction::with_reg_mem(code, register, argument_operand(argument_index)));
+        }
+    }
+
+    encode_x86_instruction(X86Instruction::try_with_reg_u64(Code::Mov_r64_imm64, Register::R11, function.address().unwrap() as u64).unwrap());
+    encode_x86_instruction(X86Instruction::with_reg(Code::Call_rm64, Register::R11));
+
+    match function.return_type() {
+        TypeId::Float32 => {
+            encode_x86_instruction(X86Instruction::with_reg_reg(Code::Movd_rm32_xmm, Register::EAX, Register::XMM0));
+        }
+        TypeId::Float64 => {
+            encode_x86_instruction(X86Instruction::with_reg_reg(Code::Movq_rm64_xmm, Register::RAX, Register::XMM0));
+        }
+        _ => {}
+    }
+
+    encode_x86_instruction(X86Instruction::with_reg_reg(Code::Mov_r64_rm64, Register::RSP, Register::RBP));
+    encode_x86_instruction(X86Instruction::with_reg(Code::Pop_rm64, Register::RBP));
+    encode_x86_instruction(X86Instruction::with(Code::Retnq));
+
+    let invoker_buffer = encoder.take_buffer();
+    let invoker_ptr = memory_allocator.allocate(invoker_buffer.len());
+    unsafe {
+        invoker_ptr.copy_from(invoker_buffer.as_ptr() as *const _, invoker_buffer.len());
+        std::mem::transmute(invoker_ptr)
+    }
+}
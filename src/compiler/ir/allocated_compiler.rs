@@ -1,26 +1,22 @@
-use std::collections::{BTreeSet, HashMap, HashSet};
-use std::iter::FromIterator;
+use std::collections::HashSet;
 
-use crate::analysis::{OptimizationResult, liveness, VirtualRegister};
+use crate::analysis::{OptimizationResult, liveness};
 use crate::analysis::basic_block::BasicBlock;
 use crate::analysis::control_flow_graph::ControlFlowGraph;
-use crate::compiler::calling_conventions::{CallingConventions, float_register_call_arguments, get_call_register, register_call_arguments};
+use crate::compiler::calling_conventions::{CallingConventions, get_call_register, register_call_arguments};
 use crate::compiler::code_generator::register_mapping;
 use crate::compiler::ir::{HardwareRegister, HardwareRegisterExplicit, InstructionIR, Variable};
 use crate::compiler::stack_layout;
 use crate::model::binder::Binder;
-use crate::mir::{branches, InstructionMIR, RegisterMIR};
-use crate::mir::compiler::{InstructionMIRCompiler, MIRCompilationResult};
+use crate::mir::{InstructionMIR, RegisterMIR};
+use crate::mir::compiler::MIRCompilationResult;
 use crate::mir::InstructionMIRData;
-use crate::model::function::{Function, FunctionDeclaration, FunctionSignature};
-use crate::model::instruction::Instruction;
+use crate::model::function::{Function, FunctionDeclaration, VirtualModifier};
 use crate::model::typesystem::{TypeId, TypeStorage};
-use crate::model::verifier::Verifier;
 use crate::optimization::register_allocation;
 use crate::optimization::register_allocation::{AllocatedRegister, RegisterAllocation, RegisterAllocationSettings};
 use crate::compiler::code_generator::register_mapping::DataSize;
 use crate::compiler::ir::helpers::{AllocatedCompilerHelpers, TempRegisters};
-use iced_x86::Register;
 
 pub struct AllocatedInstructionIRCompiler<'a> {
     binder: &'a Binder,
@@ -66,7 +62,7 @@ impl<'a> AllocatedInstructionIRCompiler<'a> {
                          compilation_result: &MIRCompilationResult) -> RegisterAllocation {
         let instructions = &compilation_result.instructions;
         let basic_blocks = BasicBlock::create_blocks(instructions);
-        let control_flow_graph = ControlFlowGraph::new(&instructions, &basic_blocks);
+        let control_flow_graph = ControlFlowGraph::new(instructions, &basic_blocks);
         let live_intervals = liveness::compute(compilation_result, &basic_blocks, &control_flow_graph);
         register_allocation::linear_scan::allocate(
             &live_intervals,
@@ -82,6 +78,8 @@ impl<'a> AllocatedInstructionIRCompiler<'a> {
             self.instructions.push(InstructionIR::SubFromStackPointer(stack_size));
         }
 
+        self.instructions.push(InstructionIR::StackOverflowCheck);
+
         let argument_sources = CallingConventions::new().move_arguments_to_stack(self.function, &mut self.instructions);
         if let Some(this_reference) = self.compilation_result.member_this_register(self.function) {
             if self.register_allocation.is_used(this_reference) {
@@ -94,11 +92,17 @@ impl<'a> AllocatedInstructionIRCompiler<'a> {
             let mut float_initialized = false;
 
             for register in &self.compilation_result.need_zero_initialize_registers {
-                if self.register_allocation.is_used(register) {
+                if register.value_type.is_struct() {
+                    // Struct locals are always scanned by the GC, even if unused
+                    self.instructions.push(InstructionIR::ZeroFrameMemory(
+                        self.get_register_stack_offset(register),
+                        self.type_storage.size_of(&register.value_type)
+                    ));
+                } else if self.register_allocation.is_used(register) {
                     let allocated_register = self.register_allocation.get_register(register).hardware_register();
 
                     match register.value_type {
-                        TypeId::Float32 => {
+                        TypeId::Float32 | TypeId::Float64 => {
                             if !float_initialized {
                                 self.instructions.push(InstructionIR::LoadZeroToRegister(HardwareRegister::FloatSpill));
                                 float_initialized = true;
@@ -143,7 +147,7 @@ impl<'a> AllocatedInstructionIRCompiler<'a> {
                 }
             }
             InstructionMIRData::LoadFloat32(destination, value) => {
-                let value: i32 = unsafe { std::mem::transmute(*value) };
+                let value: i32 = value.to_bits() as i32;
 
                 match self.register_allocation.get_register(destination).hardware_register() {
                     Some(register) => {
@@ -155,6 +159,31 @@ impl<'a> AllocatedInstructionIRCompiler<'a> {
                     }
                 }
             }
+            InstructionMIRData::LoadInt64(destination, value) => {
+                match self.register_allocation.get_register(destination).hardware_register() {
+                    Some(register) => {
+                        self.instructions.push(InstructionIR::MoveInt64ToRegister(register, *value));
+                    }
+                    None => {
+                        self.instructions.push(InstructionIR::MoveInt64ToRegister(HardwareRegister::IntSpill, *value));
+                        self.instructions.push(InstructionIR::StoreFrameMemory(self.get_register_stack_offset(destination), HardwareRegister::IntSpill));
+                    }
+                }
+            }
+            InstructionMIRData::LoadFloat64(destination, value) => {
+                let value: i64 = value.to_bits() as i64;
+                self.instructions.push(InstructionIR::MoveInt64ToRegister(HardwareRegister::IntSpill, value));
+
+                match self.register_allocation.get_register(destination).hardware_register() {
+                    Some(register) => {
+                        self.instructions.push(InstructionIR::Push(HardwareRegister::IntSpill));
+                        self.instructions.push(InstructionIR::Pop(register));
+                    }
+                    None => {
+                        self.instructions.push(InstructionIR::StoreFrameMemory(self.get_register_stack_offset(destination), HardwareRegister::IntSpill));
+                    }
+                }
+            }
             InstructionMIRData::LoadBool(destination, value) => {
                 let value = if *value {1} else {0};
                 match self.register_allocation.get_register(destination).hardware_register() {
@@ -166,6 +195,16 @@ impl<'a> AllocatedInstructionIRCompiler<'a> {
                     }
                 }
             }
+            InstructionMIRData::Move(destination, source) if destination.value_type.is_struct() => {
+                // Structs are always on the stack
+                if destination != source {
+                    self.instructions.push(InstructionIR::CopyFrameMemory(
+                        self.get_register_stack_offset(destination),
+                        self.get_register_stack_offset(source),
+                        self.type_storage.size_of(&destination.value_type)
+                    ));
+                }
+            }
             InstructionMIRData::Move(destination, source) => {
                 self.move_register(destination, source);
             }
@@ -250,6 +289,32 @@ impl<'a> AllocatedInstructionIRCompiler<'a> {
                 self.pop_alive_registers(&alive_registers, Some(HardwareRegister::IntSpill));
                 self.move_from_hardware_register(destination, HardwareRegister::IntSpill);
             }
+            InstructionMIRData::RemainderInt32(destination, operand1, operand2) => {
+                let alive_registers = self.push_alive_registers(instruction_index);
+
+                self.move_to_hardware_register(HardwareRegister::IntSpill, operand1);
+                self.move_to_hardware_register(HardwareRegister::Int(5), operand2);
+                self.instructions.push(InstructionIR::RemainderInt32(HardwareRegister::IntSpill, HardwareRegister::Int(5)));
+
+                self.pop_alive_registers(&alive_registers, Some(HardwareRegister::IntSpill));
+                self.move_from_hardware_register(destination, HardwareRegister::IntSpill);
+            }
+            InstructionMIRData::NegateInt32(destination, operand) => {
+                if destination == operand {
+                    match self.register_allocation().get_register(destination).hardware_register() {
+                        Some(register) => {
+                            self.instructions.push(InstructionIR::NegateInt32(register));
+                        }
+                        None => {
+                            self.instructions.push(InstructionIR::NegateInt32FrameMemory(self.get_register_stack_offset(destination)));
+                        }
+                    }
+                } else {
+                    self.move_to_hardware_register(HardwareRegister::IntSpill, operand);
+                    self.instructions.push(InstructionIR::NegateInt32(HardwareRegister::IntSpill));
+                    self.move_from_hardware_register(destination, HardwareRegister::IntSpill);
+                }
+            }
             InstructionMIRData::AndBool(destination, operand1, operand2) => {
                 self.binary_operator_with_destination(
                     destination,
@@ -324,6 +389,137 @@ impl<'a> AllocatedInstructionIRCompiler<'a> {
                     self.move_from_hardware_register(destination, HardwareRegister::IntSpill);
                 }
             }
+            InstructionMIRData::AndInt32(destination, operand1, operand2) => {
+                self.binary_operator_with_destination(
+                    destination,
+                    operand1,
+                    operand2,
+                    |instructions, op1, op2| {
+                        instructions.push(InstructionIR::AndInt32(op1, op2));
+                    },
+                    |instructions, op1, op2| {
+                        instructions.push(InstructionIR::AndInt32FromFrameMemory(op1, op2));
+                    },
+                    |instructions, op1, op2| {
+                        instructions.push(InstructionIR::AndInt32ToFrameMemory(op1, op2));
+                    }
+                );
+            }
+            InstructionMIRData::OrInt32(destination, operand1, operand2) => {
+                self.binary_operator_with_destination(
+                    destination,
+                    operand1,
+                    operand2,
+                    |instructions, op1, op2| {
+                        instructions.push(InstructionIR::OrInt32(op1, op2));
+                    },
+                    |instructions, op1, op2| {
+                        instructions.push(InstructionIR::OrInt32FromFrameMemory(op1, op2));
+                    },
+                    |instructions, op1, op2| {
+                        instructions.push(InstructionIR::OrInt32ToFrameMemory(op1, op2));
+                    }
+                );
+            }
+            InstructionMIRData::XorInt32(destination, operand1, operand2) => {
+                self.binary_operator_with_destination(
+                    destination,
+                    operand1,
+                    operand2,
+                    |instructions, op1, op2| {
+                        instructions.push(InstructionIR::XorInt32(op1, op2));
+                    },
+                    |instructions, op1, op2| {
+                        instructions.push(InstructionIR::XorInt32FromFrameMemory(op1, op2));
+                    },
+                    |instructions, op1, op2| {
+                        instructions.push(InstructionIR::XorInt32ToFrameMemory(op1, op2));
+                    }
+                );
+            }
+            InstructionMIRData::NotInt32(destination, operand) => {
+                if destination == operand {
+                    match self.register_allocation().get_register(destination).hardware_register() {
+                        Some(register) => {
+                            self.instructions.push(InstructionIR::BitwiseNotInt32(register));
+                        }
+                        None => {
+                            self.instructions.push(InstructionIR::BitwiseNotInt32FrameMemory(self.get_register_stack_offset(destination)));
+                        }
+                    }
+                } else {
+                    self.move_to_hardware_register(HardwareRegister::IntSpill, operand);
+                    self.instructions.push(InstructionIR::BitwiseNotInt32(HardwareRegister::IntSpill));
+                    self.move_from_hardware_register(destination, HardwareRegister::IntSpill);
+                }
+            }
+            InstructionMIRData::ShiftLeftInt32(destination, operand1, operand2) => {
+                let alive_registers = self.push_alive_registers(instruction_index);
+
+                // The shift amount must be in CL
+                self.move_to_hardware_register(HardwareRegister::IntSpill, operand1);
+                self.move_to_hardware_register(HardwareRegister::Int(1), operand2);
+                self.instructions.push(InstructionIR::ShiftLeftInt32(HardwareRegister::IntSpill, HardwareRegister::Int(1)));
+
+                self.pop_alive_registers(&alive_registers, Some(HardwareRegister::IntSpill));
+                self.move_from_hardware_register(destination, HardwareRegister::IntSpill);
+            }
+            InstructionMIRData::ShiftRightInt32(destination, operand1, operand2) => {
+                let alive_registers = self.push_alive_registers(instruction_index);
+
+                // The shift amount must be in CL
+                self.move_to_hardware_register(HardwareRegister::IntSpill, operand1);
+                self.move_to_hardware_register(HardwareRegister::Int(1), operand2);
+                self.instructions.push(InstructionIR::ShiftRightInt32(HardwareRegister::IntSpill, HardwareRegister::Int(1)));
+
+                self.pop_alive_registers(&alive_registers, Some(HardwareRegister::IntSpill));
+                self.move_from_hardware_register(destination, HardwareRegister::IntSpill);
+            }
+            InstructionMIRData::ConvertInt32ToFloat32(destination, operand) => {
+                self.move_to_hardware_register(HardwareRegister::IntSpill, operand);
+                self.instructions.push(InstructionIR::ConvertInt32ToFloat32(HardwareRegister::FloatSpill, HardwareRegister::IntSpill));
+                self.move_from_hardware_register(destination, HardwareRegister::FloatSpill);
+            }
+            InstructionMIRData::ConvertFloat32ToInt32(destination, operand) => {
+                self.move_to_hardware_register(HardwareRegister::FloatSpill, operand);
+                self.instructions.push(InstructionIR::ConvertFloat32ToInt32(HardwareRegister::IntSpill, HardwareRegister::FloatSpill));
+                self.move_from_hardware_register(destination, HardwareRegister::IntSpill);
+            }
+            InstructionMIRData::ConvertBoolToInt32(destination, operand) => {
+                self.move_to_hardware_register(HardwareRegister::IntSpill, operand);
+                self.instructions.push(InstructionIR::ConvertBoolToInt32(HardwareRegister::IntSpill, HardwareRegister::IntSpill));
+                self.move_from_hardware_register(destination, HardwareRegister::IntSpill);
+            }
+            InstructionMIRData::ConvertInt32ToInt64(destination, operand) => {
+                self.move_to_hardware_register(HardwareRegister::IntSpill, operand);
+                self.instructions.push(InstructionIR::ConvertInt32ToInt64(HardwareRegister::IntSpill, HardwareRegister::IntSpill));
+                self.move_from_hardware_register(destination, HardwareRegister::IntSpill);
+            }
+            InstructionMIRData::ConvertInt64ToInt32(destination, operand) => {
+                self.move_to_hardware_register(HardwareRegister::IntSpill, operand);
+                self.instructions.push(InstructionIR::ConvertInt64ToInt32(HardwareRegister::IntSpill, HardwareRegister::IntSpill));
+                self.move_from_hardware_register(destination, HardwareRegister::IntSpill);
+            }
+            InstructionMIRData::ConvertFloat32ToFloat64(destination, operand) => {
+                self.move_to_hardware_register(HardwareRegister::FloatSpill, operand);
+                self.instructions.push(InstructionIR::ConvertFloat32ToFloat64(HardwareRegister::FloatSpill, HardwareRegister::FloatSpill));
+                self.move_from_hardware_register(destination, HardwareRegister::FloatSpill);
+            }
+            InstructionMIRData::ConvertFloat64ToFloat32(destination, operand) => {
+                self.move_to_hardware_register(HardwareRegister::FloatSpill, operand);
+                self.instructions.push(InstructionIR::ConvertFloat64ToFloat32(HardwareRegister::FloatSpill, HardwareRegister::FloatSpill));
+                self.move_from_hardware_register(destination, HardwareRegister::FloatSpill);
+            }
+            InstructionMIRData::ConvertInt64ToFloat64(destination, operand) => {
+                self.move_to_hardware_register(HardwareRegister::IntSpill, operand);
+                self.instructions.push(InstructionIR::ConvertInt64ToFloat64(HardwareRegister::FloatSpill, HardwareRegister::IntSpill));
+                self.move_from_hardware_register(destination, HardwareRegister::FloatSpill);
+            }
+            InstructionMIRData::ConvertFloat64ToInt64(destination, operand) => {
+                self.move_to_hardware_register(HardwareRegister::FloatSpill, operand);
+                self.instructions.push(InstructionIR::ConvertFloat64ToInt64(HardwareRegister::IntSpill, HardwareRegister::FloatSpill));
+                self.move_from_hardware_register(destination, HardwareRegister::IntSpill);
+            }
             InstructionMIRData::AddFloat32(destination, operand1, operand2) => {
                 self.binary_operator_with_destination_f32(
                     destination,
@@ -376,78 +572,615 @@ impl<'a> AllocatedInstructionIRCompiler<'a> {
                     }
                 );
             }
-            InstructionMIRData::Return(source) => {
-                if let Some(source) = source {
-                    CallingConventions::new().make_return_value(
-                        self.function,
-                        &match self.register_allocation.get_register(source).hardware_register() {
-                            Some(register) => Variable::Register(register.clone()),
-                            None => Variable::FrameMemory(self.get_register_stack_offset(source))
-                        },
-                        &mut self.instructions
-                    );
-                }
-
-                self.instructions.push(InstructionIR::Return);
+            InstructionMIRData::MinFloat32(destination, operand1, operand2) => {
+                self.binary_operator_with_destination_f32(
+                    destination,
+                    operand1,
+                    operand2,
+                    |instructions, op1, op2| {
+                        instructions.push(InstructionIR::MinFloat32(op1, op2));
+                    },
+                    |instructions, op1, op2| {
+                        instructions.push(InstructionIR::MinFloat32FromFrameMemory(op1, op2));
+                    }
+                );
             }
-            InstructionMIRData::Call(signature, return_value, arguments) => {
-                let func_to_call = self.binder.get(signature).unwrap();
+            InstructionMIRData::MaxFloat32(destination, operand1, operand2) => {
+                self.binary_operator_with_destination_f32(
+                    destination,
+                    operand1,
+                    operand2,
+                    |instructions, op1, op2| {
+                        instructions.push(InstructionIR::MaxFloat32(op1, op2));
+                    },
+                    |instructions, op1, op2| {
+                        instructions.push(InstructionIR::MaxFloat32FromFrameMemory(op1, op2));
+                    }
+                );
+            }
+            InstructionMIRData::SquareRootFloat32(destination, operand) => {
+                self.move_to_hardware_register(HardwareRegister::FloatSpill, operand);
+                self.instructions.push(InstructionIR::SquareRootFloat32(HardwareRegister::FloatSpill, HardwareRegister::FloatSpill));
+                self.move_from_hardware_register(destination, HardwareRegister::FloatSpill);
+            }
+            InstructionMIRData::RoundFloat32(destination, operand, mode) => {
+                self.move_to_hardware_register(HardwareRegister::FloatSpill, operand);
+                self.instructions.push(InstructionIR::RoundFloat32(HardwareRegister::FloatSpill, HardwareRegister::FloatSpill, *mode));
+                self.move_from_hardware_register(destination, HardwareRegister::FloatSpill);
+            }
+            InstructionMIRData::AddInt64(destination, operand1, operand2) => {
+                self.binary_operator_with_destination(
+                    destination,
+                    operand1,
+                    operand2,
+                    |instructions, op1, op2| {
+                        instructions.push(InstructionIR::AddInt64(op1, op2));
+                    },
+                    |instructions, op1, op2| {
+                        instructions.push(InstructionIR::AddInt64FromFrameMemory(op1, op2));
+                    },
+                    |instructions, op1, op2| {
+                        instructions.push(InstructionIR::AddInt64ToFrameMemory(op1, op2));
+                    }
+                );
+            }
+            InstructionMIRData::SubInt64(destination, operand1, operand2) => {
+                self.binary_operator_with_destination(
+                    destination,
+                    operand1,
+                    operand2,
+                    |instructions, op1, op2| {
+                        instructions.push(InstructionIR::SubInt64(op1, op2));
+                    },
+                    |instructions, op1, op2| {
+                        instructions.push(InstructionIR::SubInt64FromFrameMemory(op1, op2));
+                    },
+                    |instructions, op1, op2| {
+                        instructions.push(InstructionIR::SubInt64ToFrameMemory(op1, op2));
+                    }
+                );
+            }
+            InstructionMIRData::MultiplyInt64(destination, operand1, operand2) => {
+                self.binary_operator_no_memory_store_with_destination(
+                    destination,
+                    operand1,
+                    operand2,
+                    |instructions, op1, op2| {
+                        instructions.push(InstructionIR::MultiplyInt64(op1, op2));
+                    },
+                    |instructions, op1, op2| {
+                        instructions.push(InstructionIR::MultiplyInt64FromFrameMemory(op1, op2));
+                    }
+                );
+            }
+            InstructionMIRData::DivideInt64(destination, operand1, operand2) => {
+                let alive_registers = self.push_alive_registers(instruction_index);
+
+                self.move_to_hardware_register(HardwareRegister::IntSpill, operand1);
+                self.move_to_hardware_register(HardwareRegister::Int(5), operand2);
+                self.instructions.push(InstructionIR::DivideInt64(HardwareRegister::IntSpill, HardwareRegister::Int(5)));
 
+                self.pop_alive_registers(&alive_registers, Some(HardwareRegister::IntSpill));
+                self.move_from_hardware_register(destination, HardwareRegister::IntSpill);
+            }
+            InstructionMIRData::RemainderInt64(destination, operand1, operand2) => {
                 let alive_registers = self.push_alive_registers(instruction_index);
 
-                let arguments_source = self.get_call_argument_sources(func_to_call, arguments);
-                self.instructions.push(InstructionIR::Call(signature.clone(), arguments_source, 0));
+                self.move_to_hardware_register(HardwareRegister::IntSpill, operand1);
+                self.move_to_hardware_register(HardwareRegister::Int(5), operand2);
+                self.instructions.push(InstructionIR::RemainderInt64(HardwareRegister::IntSpill, HardwareRegister::Int(5)));
 
-                let return_register = if let Some(return_value) = return_value {
-                    CallingConventions::new().handle_return_value(
-                        self.function,
-                        &match self.register_allocation.get_register(return_value).hardware_register() {
-                            Some(register) => Variable::Register(register.clone()),
-                            None => Variable::FrameMemory(self.get_register_stack_offset(return_value))
-                        },
-                        func_to_call,
-                        &mut self.instructions
-                    );
+                self.pop_alive_registers(&alive_registers, Some(HardwareRegister::IntSpill));
+                self.move_from_hardware_register(destination, HardwareRegister::IntSpill);
+            }
+            InstructionMIRData::NegateInt64(destination, operand) => {
+                if destination == operand {
+                    match self.register_allocation().get_register(destination).hardware_register() {
+                        Some(register) => {
+                            self.instructions.push(InstructionIR::NegateInt64(register));
+                        }
+                        None => {
+                            self.instructions.push(InstructionIR::NegateInt64FrameMemory(self.get_register_stack_offset(destination)));
+                        }
+                    }
+                } else {
+                    self.move_to_hardware_register(HardwareRegister::IntSpill, operand);
+                    self.instructions.push(InstructionIR::NegateInt64(HardwareRegister::IntSpill));
+                    self.move_from_hardware_register(destination, HardwareRegister::IntSpill);
+                }
+            }
+            InstructionMIRData::NotInt64(destination, operand) => {
+                if destination == operand {
+                    match self.register_allocation().get_register(destination).hardware_register() {
+                        Some(register) => {
+                            self.instructions.push(InstructionIR::BitwiseNotInt64(register));
+                        }
+                        None => {
+                            self.instructions.push(InstructionIR::BitwiseNotInt64FrameMemory(self.get_register_stack_offset(destination)));
+                        }
+                    }
+                } else {
+                    self.move_to_hardware_register(HardwareRegister::IntSpill, operand);
+                    self.instructions.push(InstructionIR::BitwiseNotInt64(HardwareRegister::IntSpill));
+                    self.move_from_hardware_register(destination, HardwareRegister::IntSpill);
+                }
+            }
+            InstructionMIRData::AndInt64(destination, operand1, operand2) => {
+                self.binary_operator_with_destination(
+                    destination,
+                    operand1,
+                    operand2,
+                    |instructions, op1, op2| {
+                        instructions.push(InstructionIR::AndInt64(op1, op2));
+                    },
+                    |instructions, op1, op2| {
+                        instructions.push(InstructionIR::AndInt64FromFrameMemory(op1, op2));
+                    },
+                    |instructions, op1, op2| {
+                        instructions.push(InstructionIR::AndInt64ToFrameMemory(op1, op2));
+                    }
+                );
+            }
+            InstructionMIRData::OrInt64(destination, operand1, operand2) => {
+                self.binary_operator_with_destination(
+                    destination,
+                    operand1,
+                    operand2,
+                    |instructions, op1, op2| {
+                        instructions.push(InstructionIR::OrInt64(op1, op2));
+                    },
+                    |instructions, op1, op2| {
+                        instructions.push(InstructionIR::OrInt64FromFrameMemory(op1, op2));
+                    },
+                    |instructions, op1, op2| {
+                        instructions.push(InstructionIR::OrInt64ToFrameMemory(op1, op2));
+                    }
+                );
+            }
+            InstructionMIRData::XorInt64(destination, operand1, operand2) => {
+                self.binary_operator_with_destination(
+                    destination,
+                    operand1,
+                    operand2,
+                    |instructions, op1, op2| {
+                        instructions.push(InstructionIR::XorInt64(op1, op2));
+                    },
+                    |instructions, op1, op2| {
+                        instructions.push(InstructionIR::XorInt64FromFrameMemory(op1, op2));
+                    },
+                    |instructions, op1, op2| {
+                        instructions.push(InstructionIR::XorInt64ToFrameMemory(op1, op2));
+                    }
+                );
+            }
+            InstructionMIRData::ShiftLeftInt64(destination, operand1, operand2) => {
+                let alive_registers = self.push_alive_registers(instruction_index);
+
+                // The shift amount must be in CL
+                self.move_to_hardware_register(HardwareRegister::IntSpill, operand1);
+                self.move_to_hardware_register(HardwareRegister::Int(1), operand2);
+                self.instructions.push(InstructionIR::ShiftLeftInt64(HardwareRegister::IntSpill, HardwareRegister::Int(1)));
+
+                self.pop_alive_registers(&alive_registers, Some(HardwareRegister::IntSpill));
+                self.move_from_hardware_register(destination, HardwareRegister::IntSpill);
+            }
+            InstructionMIRData::ShiftRightInt64(destination, operand1, operand2) => {
+                let alive_registers = self.push_alive_registers(instruction_index);
+
+                // The shift amount must be in CL
+                self.move_to_hardware_register(HardwareRegister::IntSpill, operand1);
+                self.move_to_hardware_register(HardwareRegister::Int(1), operand2);
+                self.instructions.push(InstructionIR::ShiftRightInt64(HardwareRegister::IntSpill, HardwareRegister::Int(1)));
+
+                self.pop_alive_registers(&alive_registers, Some(HardwareRegister::IntSpill));
+                self.move_from_hardware_register(destination, HardwareRegister::IntSpill);
+            }
+            InstructionMIRData::AddFloat64(destination, operand1, operand2) => {
+                self.binary_operator_with_destination_f32(
+                    destination,
+                    operand1,
+                    operand2,
+                    |instructions, op1, op2| {
+                        instructions.push(InstructionIR::AddFloat64(op1, op2));
+                    },
+                    |instructions, op1, op2| {
+                        instructions.push(InstructionIR::AddFloat64FromFrameMemory(op1, op2));
+                    }
+                );
+            }
+            InstructionMIRData::SubFloat64(destination, operand1, operand2) => {
+                self.binary_operator_with_destination_f32(
+                    destination,
+                    operand1,
+                    operand2,
+                    |instructions, op1, op2| {
+                        instructions.push(InstructionIR::SubFloat64(op1, op2));
+                    },
+                    |instructions, op1, op2| {
+                        instructions.push(InstructionIR::SubFloat64FromFrameMemory(op1, op2));
+                    }
+                );
+            }
+            InstructionMIRData::MultiplyFloat64(destination, operand1, operand2) => {
+                self.binary_operator_with_destination_f32(
+                    destination,
+                    operand1,
+                    operand2,
+                    |instructions, op1, op2| {
+                        instructions.push(InstructionIR::MultiplyFloat64(op1, op2));
+                    },
+                    |instructions, op1, op2| {
+                        instructions.push(InstructionIR::MultiplyFloat64FromFrameMemory(op1, op2));
+                    }
+                );
+            }
+            InstructionMIRData::DivideFloat64(destination, operand1, operand2) => {
+                self.binary_operator_with_destination_f32(
+                    destination,
+                    operand1,
+                    operand2,
+                    |instructions, op1, op2| {
+                        instructions.push(InstructionIR::DivideFloat64(op1, op2));
+                    },
+                    |instructions, op1, op2| {
+                        instructions.push(InstructionIR::DivideFloat64FromFrameMemory(op1, op2));
+                    }
+                );
+            }
+            InstructionMIRData::Return(source) => {
+                if let Some(source) = source {
+                    CallingConventions::new().make_return_value(
+                        self.function,
+                        &match self.register_allocation.get_register(source).hardware_register() {
+                            Some(register) => Variable::Register(register),
+                            None => Variable::FrameMemory(self.get_register_stack_offset(source))
+                        },
+                        &mut self.instructions
+                    );
+                }
+
+                self.instructions.push(InstructionIR::Return);
+            }
+            InstructionMIRData::Call(signature, return_value, arguments) => {
+                let func_to_call = self.binder.get(signature).unwrap();
+
+                let alive_registers = self.push_alive_registers(instruction_index);
+
+                let arguments_source = self.get_call_argument_sources(func_to_call, arguments);
+                self.instructions.push(InstructionIR::Call(signature.clone(), arguments_source, 0, instruction_index));
+
+                let return_register = if let Some(return_value) = return_value {
+                    CallingConventions::new().handle_return_value(
+                        self.function,
+                        &match self.register_allocation.get_register(return_value).hardware_register() {
+                            Some(register) => Variable::Register(register),
+                            None => Variable::FrameMemory(self.get_register_stack_offset(return_value))
+                        },
+                        func_to_call,
+                        &mut self.instructions
+                    );
+
+                    self.register_allocation.get_register(return_value).hardware_register()
+                } else {
+                    None
+                };
+
+                self.pop_alive_registers(&alive_registers, return_register);
+            }
+            InstructionMIRData::LoadFunction(signature, destination) => {
+                match self.register_allocation.get_register(destination).hardware_register() {
+                    Some(register) => {
+                        self.instructions.push(InstructionIR::LoadFunction(signature.clone(), register));
+                    }
+                    None => {
+                        self.instructions.push(InstructionIR::LoadFunction(signature.clone(), HardwareRegister::IntSpill));
+                        self.instructions.push(InstructionIR::StoreFrameMemory(self.get_register_stack_offset(destination), HardwareRegister::IntSpill));
+                    }
+                }
+            }
+            InstructionMIRData::CallIndirect(function_type, return_value, function_reference, arguments) => {
+                let func_to_call = FunctionDeclaration::with_function_reference(function_type).unwrap();
+
+                let alive_registers = self.push_alive_registers(instruction_index);
+
+                // The function reference is loaded after the arguments have been moved, which might overwrite its register.
+                // As it's alive at the call, the saved value on the stack is used instead.
+                let function_source = Variable::FrameMemory(self.get_register_stack_offset(function_reference));
+                let arguments_source = self.get_call_argument_sources(&func_to_call, arguments);
+                self.instructions.push(InstructionIR::CallIndirect(function_type.clone(), function_source, arguments_source, 0, instruction_index));
+
+                let return_register = if let Some(return_value) = return_value {
+                    CallingConventions::new().handle_return_value(
+                        self.function,
+                        &match self.register_allocation.get_register(return_value).hardware_register() {
+                            Some(register) => Variable::Register(register),
+                            None => Variable::FrameMemory(self.get_register_stack_offset(return_value))
+                        },
+                        &func_to_call,
+                        &mut self.instructions
+                    );
+
+                    self.register_allocation.get_register(return_value).hardware_register()
+                } else {
+                    None
+                };
+
+                self.pop_alive_registers(&alive_registers, return_register);
+            }
+            InstructionMIRData::LoadArgument(argument_index, destination) => {
+                let argument_offset = stack_layout::argument_stack_offset(self.function, *argument_index);
+                if let Some(register) = self.register_allocation.get_register(destination).hardware_register() {
+                    self.instructions.push(InstructionIR::LoadFrameMemory(register, argument_offset));
+                } else {
+                    self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::IntSpill, argument_offset));
+                    self.instructions.push(InstructionIR::StoreFrameMemory(self.get_register_stack_offset(destination), HardwareRegister::IntSpill));
+                }
+            }
+            InstructionMIRData::LoadNull(destination) => {
+                match self.register_allocation.get_register(destination).hardware_register() {
+                    Some(register) => {
+                        self.instructions.push(InstructionIR::MoveInt32ToRegister(register, 0));
+                    }
+                    None => {
+                        self.instructions.push(InstructionIR::MoveInt32ToFrameMemory(self.get_register_stack_offset(destination), 0));
+                    }
+                }
+            }
+            InstructionMIRData::LoadString(destination, value) => {
+                match self.register_allocation.get_register(destination).hardware_register() {
+                    Some(register) => {
+                        self.instructions.push(InstructionIR::LoadString(register, value.clone()));
+                    }
+                    None => {
+                        self.instructions.push(InstructionIR::LoadString(HardwareRegister::IntSpill, value.clone()));
+                        self.instructions.push(InstructionIR::StoreFrameMemory(self.get_register_stack_offset(destination), HardwareRegister::IntSpill));
+                    }
+                }
+            }
+            InstructionMIRData::NewArray(element, destination, size) => {
+                let alive_registers = self.push_alive_registers(instruction_index);
+
+                let mut temp_registers = TempRegisters::new(&self.register_allocation, instruction_index);
+                temp_registers.try_remove(&self.register_allocation, size);
+                let (size_register, _, size_alive) = temp_registers.get_with_status(&self.register_allocation, self.compilation_result, &mut self.instructions, size);
+
+                self.instructions.push(InstructionIR::NewArray(element.clone(), size_register, if size_alive {1} else {0}, instruction_index));
+
+                let destination_register = match self.register_allocation.get_register(destination).hardware_register() {
+                    Some(register) => {
+                        self.instructions.push(InstructionIR::MoveExplicitToImplicit(
+                            register,
+                            HardwareRegisterExplicit(register_call_arguments::RETURN_VALUE)
+                        ));
+
+                        Some(register)
+                    }
+                    None => {
+                        self.instructions.push(InstructionIR::StoreFrameMemoryExplicit(
+                            self.get_register_stack_offset(destination),
+                            HardwareRegisterExplicit(register_call_arguments::RETURN_VALUE)
+                        ));
+
+                        None
+                    }
+                };
+
+                temp_registers.done(&mut self.instructions);
+
+                self.pop_alive_registers(&alive_registers, destination_register);
+            }
+            InstructionMIRData::LoadElement(element, destination, array_ref, index) if element.is_struct() => {
+                let mut temp_registers = TempRegisters::new(&self.register_allocation, instruction_index);
+                let [array_ref_register, index_register] = temp_registers.get_and_try_remove(
+                    &self.register_allocation,
+                    self.compilation_result,
+                    &mut self.instructions,
+                    [array_ref, index]
+                );
+
+                if self.can_be_null(instruction_index, array_ref) {
+                    self.instructions.push(InstructionIR::NullReferenceCheck(array_ref_register));
+                }
+
+                self.instructions.push(InstructionIR::ArrayBoundsCheck(array_ref_register, index_register));
+                self.instructions.push(InstructionIR::LoadStructElement(
+                    element.clone(),
+                    self.get_register_stack_offset(destination),
+                    array_ref_register,
+                    index_register
+                ));
+
+                temp_registers.done(&mut self.instructions);
+            }
+            InstructionMIRData::LoadElement(element, destination, array_ref, index) => {
+                let mut temp_registers = TempRegisters::new(&self.register_allocation, instruction_index);
+                let [array_ref_register, index_register] = temp_registers.get_and_try_remove(
+                    &self.register_allocation,
+                    self.compilation_result,
+                    &mut self.instructions,
+                    [array_ref, index]
+                );
+
+                if self.can_be_null(instruction_index, array_ref) {
+                    self.instructions.push(InstructionIR::NullReferenceCheck(array_ref_register));
+                }
+
+                self.instructions.push(InstructionIR::ArrayBoundsCheck(array_ref_register, index_register));
+
+                let return_value = match self.register_allocation.get_register(destination).hardware_register() {
+                    Some(register) => register,
+                    None => {
+                        match element {
+                            TypeId::Float32 | TypeId::Float64 => HardwareRegister::FloatSpill,
+                            _ => HardwareRegister::IntSpill
+                        }
+                    }
+                };
+
+                self.instructions.push(InstructionIR::LoadElement(
+                    element.clone(),
+                    return_value,
+                    array_ref_register,
+                    index_register
+                ));
+
+                if self.register_allocation.get_register(destination).is_stack() {
+                    self.instructions.push(InstructionIR::StoreFrameMemory(
+                        self.get_register_stack_offset(destination),
+                        return_value
+                    ));
+                }
+
+                temp_registers.done(&mut self.instructions);
+            }
+            InstructionMIRData::StoreElement(element, array_ref, index, value) if element.is_struct() => {
+                let mut temp_registers = TempRegisters::new(&self.register_allocation, instruction_index);
+                let [array_ref_register, index_register] = temp_registers.get_and_try_remove(
+                    &self.register_allocation,
+                    self.compilation_result,
+                    &mut self.instructions,
+                    [array_ref, index]
+                );
+
+                if self.can_be_null(instruction_index, array_ref) {
+                    self.instructions.push(InstructionIR::NullReferenceCheck(array_ref_register));
+                }
+
+                self.instructions.push(InstructionIR::ArrayBoundsCheck(array_ref_register, index_register));
+                self.instructions.push(InstructionIR::StoreStructElement(
+                    element.clone(),
+                    array_ref_register,
+                    index_register,
+                    self.get_register_stack_offset(value)
+                ));
+
+                temp_registers.done(&mut self.instructions);
+            }
+            InstructionMIRData::StoreElement(element, array_ref, index, value) => {
+                let mut temp_registers = TempRegisters::new(&self.register_allocation, instruction_index);
+                let [array_ref_register, index_register, value_register] = temp_registers.get_and_try_remove(
+                    &self.register_allocation,
+                    self.compilation_result,
+                    &mut self.instructions,
+                    [array_ref, index, value]
+                );
+
+                if self.can_be_null(instruction_index, array_ref) {
+                    self.instructions.push(InstructionIR::NullReferenceCheck(array_ref_register));
+                }
+
+                self.instructions.push(InstructionIR::ArrayBoundsCheck(array_ref_register, index_register));
+
+                self.instructions.push(InstructionIR::StoreElement(
+                    element.clone(),
+                    array_ref_register,
+                    index_register,
+                    value_register
+                ));
+
+                temp_registers.done(&mut self.instructions);
+            }
+            InstructionMIRData::LoadArrayLength(destination, array_ref) => {
+                let mut temp_registers = TempRegisters::new(&self.register_allocation, instruction_index);
+                let [array_ref_register] = temp_registers.get_and_try_remove(
+                    &self.register_allocation,
+                    self.compilation_result,
+                    &mut self.instructions,
+                    [array_ref]
+                );
+
+                if self.can_be_null(instruction_index, array_ref) {
+                    self.instructions.push(InstructionIR::NullReferenceCheck(array_ref_register));
+                }
+
+                let return_value = match self.register_allocation.get_register(destination).hardware_register() {
+                    Some(register) => register,
+                    None => HardwareRegister::IntSpill
+                };
+
+                self.instructions.push(InstructionIR::LoadArrayLength(return_value, array_ref_register));
+
+                if self.register_allocation.get_register(destination).is_stack() {
+                    self.instructions.push(InstructionIR::StoreFrameMemory(
+                        self.get_register_stack_offset(destination),
+                        return_value
+                    ));
+                }
+
+                temp_registers.done(&mut self.instructions);
+            }
+            InstructionMIRData::ArrayCopy(element, source, source_index, destination, destination_index, count) => {
+                self.push_operands(instruction_index, &[source, source_index, destination, destination_index, count]);
+                self.instructions.push(InstructionIR::ArrayCopy(element.clone()));
+            }
+            InstructionMIRData::ArrayFill(element, array_ref, value) => {
+                self.push_operands(instruction_index, &[array_ref, value]);
+                self.instructions.push(InstructionIR::ArrayFill(element.clone()));
+            }
+            InstructionMIRData::ArrayEquals(element, destination, array_ref1, array_ref2) => {
+                self.push_operands(instruction_index, &[array_ref1, array_ref2]);
 
-                    self.register_allocation.get_register(return_value).hardware_register()
-                } else {
-                    None
+                let return_value = match self.register_allocation.get_register(destination).hardware_register() {
+                    Some(register) => register,
+                    None => HardwareRegister::IntSpill
                 };
 
-                self.pop_alive_registers(&alive_registers, return_register);
-            }
-            InstructionMIRData::LoadArgument(argument_index, destination) => {
-                let argument_offset = stack_layout::argument_stack_offset(self.function, *argument_index);
-                if let Some(register) = self.register_allocation.get_register(destination).hardware_register() {
-                    self.instructions.push(InstructionIR::LoadFrameMemory(register, argument_offset));
-                } else {
-                    self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::IntSpill, argument_offset));
-                    self.instructions.push(InstructionIR::StoreFrameMemory(self.get_register_stack_offset(destination), HardwareRegister::IntSpill));
+                self.instructions.push(InstructionIR::ArrayEquals(element.clone(), return_value));
+
+                if self.register_allocation.get_register(destination).is_stack() {
+                    self.instructions.push(InstructionIR::StoreFrameMemory(
+                        self.get_register_stack_offset(destination),
+                        return_value
+                    ));
                 }
             }
-            InstructionMIRData::LoadNull(destination) => {
-                match self.register_allocation.get_register(destination).hardware_register() {
-                    Some(register) => {
-                        self.instructions.push(InstructionIR::MoveInt32ToRegister(register, 0));
-                    }
-                    None => {
-                        self.instructions.push(InstructionIR::MoveInt32ToFrameMemory(self.get_register_stack_offset(destination), 0));
-                    }
+            InstructionMIRData::ArrayClone(element, destination, array_ref) => {
+                // All alive registers are saved, so any register can be used
+                let alive_registers = self.push_alive_registers(instruction_index);
+
+                self.move_to_hardware_register(HardwareRegister::Int(0), array_ref);
+
+                if self.can_be_null(instruction_index, array_ref) {
+                    self.instructions.push(InstructionIR::NullReferenceCheck(HardwareRegister::Int(0)));
                 }
+
+                self.instructions.push(InstructionIR::LoadArrayLength(HardwareRegister::Int(0), HardwareRegister::Int(0)));
+                self.instructions.push(InstructionIR::NewArray(element.clone(), HardwareRegister::Int(0), 0, instruction_index));
+                self.instructions.push(InstructionIR::MoveExplicitToImplicit(
+                    HardwareRegister::Int(1),
+                    HardwareRegisterExplicit(register_call_arguments::RETURN_VALUE)
+                ));
+
+                // The allocation might have moved the source array, which is updated in the stack frame
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(0), self.get_register_stack_offset(array_ref)));
+                self.instructions.push(InstructionIR::Push(HardwareRegister::Int(0)));
+                self.instructions.push(InstructionIR::PushInt32(0));
+                self.instructions.push(InstructionIR::Push(HardwareRegister::Int(1)));
+                self.instructions.push(InstructionIR::PushInt32(0));
+                self.instructions.push(InstructionIR::LoadArrayLength(HardwareRegister::Int(0), HardwareRegister::Int(1)));
+                self.instructions.push(InstructionIR::Push(HardwareRegister::Int(0)));
+                self.instructions.push(InstructionIR::ArrayCopy(element.clone()));
+
+                self.move_from_hardware_register(destination, HardwareRegister::Int(1));
+                let destination_register = self.register_allocation.get_register(destination).hardware_register();
+                self.pop_alive_registers(&alive_registers, destination_register);
             }
-            InstructionMIRData::NewArray(element, destination, size) => {
+            InstructionMIRData::NewArray2D(element, destination, rows, columns) => {
                 let alive_registers = self.push_alive_registers(instruction_index);
 
                 let mut temp_registers = TempRegisters::new(&self.register_allocation, instruction_index);
-                temp_registers.try_remove(&self.register_allocation, size);
-                let (size_register, _, size_alive) = temp_registers.get_with_status(&self.register_allocation, self.function, &mut self.instructions, size);
+                temp_registers.try_remove(&self.register_allocation, rows);
+                temp_registers.try_remove(&self.register_allocation, columns);
+                let (rows_register, _, rows_alive) = temp_registers.get_with_status(&self.register_allocation, self.compilation_result, &mut self.instructions, rows);
+                let (columns_register, _, columns_alive) = temp_registers.get_with_status(&self.register_allocation, self.compilation_result, &mut self.instructions, columns);
+                let num_saved = rows_alive as usize + columns_alive as usize;
 
-                self.instructions.push(InstructionIR::NewArray(element.clone(), size_register, if size_alive {1} else {0}));
+                self.instructions.push(InstructionIR::NewArray2D(element.clone(), rows_register, columns_register, num_saved, instruction_index));
 
                 let destination_register = match self.register_allocation.get_register(destination).hardware_register() {
                     Some(register) => {
                         self.instructions.push(InstructionIR::MoveExplicitToImplicit(
-                            register.clone(),
+                            register,
                             HardwareRegisterExplicit(register_call_arguments::RETURN_VALUE)
                         ));
 
@@ -467,36 +1200,37 @@ impl<'a> AllocatedInstructionIRCompiler<'a> {
 
                 self.pop_alive_registers(&alive_registers, destination_register);
             }
-            InstructionMIRData::LoadElement(element, destination, array_ref, index) => {
+            InstructionMIRData::LoadElement2D(element, destination, array_ref, row, column) => {
                 let mut temp_registers = TempRegisters::new(&self.register_allocation, instruction_index);
-                let [array_ref_register, index_register] = temp_registers.get_and_try_remove(
+                let [array_ref_register, row_register, column_register] = temp_registers.get_and_try_remove(
                     &self.register_allocation,
-                    self.function,
+                    self.compilation_result,
                     &mut self.instructions,
-                    [array_ref, index]
+                    [array_ref, row, column]
                 );
 
                 if self.can_be_null(instruction_index, array_ref) {
                     self.instructions.push(InstructionIR::NullReferenceCheck(array_ref_register));
                 }
 
-                self.instructions.push(InstructionIR::ArrayBoundsCheck(array_ref_register, index_register));
+                self.instructions.push(InstructionIR::Array2DBoundsCheck(array_ref_register, row_register, column_register));
 
                 let return_value = match self.register_allocation.get_register(destination).hardware_register() {
                     Some(register) => register,
                     None => {
                         match element {
-                            TypeId::Float32 => HardwareRegister::FloatSpill,
+                            TypeId::Float32 | TypeId::Float64 => HardwareRegister::FloatSpill,
                             _ => HardwareRegister::IntSpill
                         }
                     }
                 };
 
-                self.instructions.push(InstructionIR::LoadElement(
+                self.instructions.push(InstructionIR::LoadElement2D(
                     element.clone(),
                     return_value,
                     array_ref_register,
-                    index_register
+                    row_register,
+                    column_register
                 ));
 
                 if self.register_allocation.get_register(destination).is_stack() {
@@ -508,35 +1242,51 @@ impl<'a> AllocatedInstructionIRCompiler<'a> {
 
                 temp_registers.done(&mut self.instructions);
             }
-            InstructionMIRData::StoreElement(element, array_ref, index, value) => {
+            InstructionMIRData::StoreElement2D(element, array_ref, row, column, value) => {
                 let mut temp_registers = TempRegisters::new(&self.register_allocation, instruction_index);
-                let [array_ref_register, index_register, value_register] = temp_registers.get_and_try_remove(
+                temp_registers.try_remove(&self.register_allocation, value);
+                let [array_ref_register, row_register, column_register] = temp_registers.get_and_try_remove(
                     &self.register_allocation,
-                    self.function,
+                    self.compilation_result,
                     &mut self.instructions,
-                    [array_ref, index, value]
+                    [array_ref, row, column]
                 );
 
                 if self.can_be_null(instruction_index, array_ref) {
                     self.instructions.push(InstructionIR::NullReferenceCheck(array_ref_register));
                 }
 
-                self.instructions.push(InstructionIR::ArrayBoundsCheck(array_ref_register, index_register));
+                self.instructions.push(InstructionIR::Array2DBoundsCheck(array_ref_register, row_register, column_register));
 
-                self.instructions.push(InstructionIR::StoreElement(
+                // There are not enough temporary registers for all operands, so a spilled value is loaded after the bounds check which uses the spill register
+                let value_register = match self.register_allocation.get_register(value).hardware_register() {
+                    Some(register) => register,
+                    None => {
+                        let value_register = match element {
+                            TypeId::Float32 | TypeId::Float64 => HardwareRegister::FloatSpill,
+                            _ => HardwareRegister::IntSpill
+                        };
+
+                        self.instructions.push(InstructionIR::LoadFrameMemory(value_register, self.get_register_stack_offset(value)));
+                        value_register
+                    }
+                };
+
+                self.instructions.push(InstructionIR::StoreElement2D(
                     element.clone(),
                     array_ref_register,
-                    index_register,
+                    row_register,
+                    column_register,
                     value_register
                 ));
 
                 temp_registers.done(&mut self.instructions);
             }
-            InstructionMIRData::LoadArrayLength(destination, array_ref) => {
+            InstructionMIRData::LoadArrayDimension(dimension, destination, array_ref) => {
                 let mut temp_registers = TempRegisters::new(&self.register_allocation, instruction_index);
                 let [array_ref_register] = temp_registers.get_and_try_remove(
                     &self.register_allocation,
-                    self.function,
+                    self.compilation_result,
                     &mut self.instructions,
                     [array_ref]
                 );
@@ -550,7 +1300,7 @@ impl<'a> AllocatedInstructionIRCompiler<'a> {
                     None => HardwareRegister::IntSpill
                 };
 
-                self.instructions.push(InstructionIR::LoadArrayLength(return_value, array_ref_register));
+                self.instructions.push(InstructionIR::LoadArrayDimension(*dimension, return_value, array_ref_register));
 
                 if self.register_allocation.get_register(destination).is_stack() {
                     self.instructions.push(InstructionIR::StoreFrameMemory(
@@ -564,12 +1314,12 @@ impl<'a> AllocatedInstructionIRCompiler<'a> {
             InstructionMIRData::NewObject(class_type, destination) => {
                 let alive_registers = self.push_alive_registers(instruction_index);
 
-                self.instructions.push(InstructionIR::NewObject(class_type.clone()));
+                self.instructions.push(InstructionIR::NewObject(class_type.clone(), instruction_index));
 
                 let destination_register = match self.register_allocation.get_register(destination).hardware_register() {
                     Some(register) => {
                         self.instructions.push(InstructionIR::MoveExplicitToImplicit(
-                            register.clone(),
+                            register,
                             HardwareRegisterExplicit(register_call_arguments::RETURN_VALUE)
                         ));
 
@@ -587,6 +1337,83 @@ impl<'a> AllocatedInstructionIRCompiler<'a> {
 
                 self.pop_alive_registers(&alive_registers, destination_register);
             }
+            InstructionMIRData::IsInstance(class_type, destination, class_ref) => {
+                let mut temp_registers = TempRegisters::new(&self.register_allocation, instruction_index);
+                let [class_ref_register] = temp_registers.get_and_try_remove(
+                    &self.register_allocation,
+                    self.compilation_result,
+                    &mut self.instructions,
+                    [class_ref]
+                );
+
+                let destination_register = match self.register_allocation.get_register(destination).hardware_register() {
+                    Some(register) => register,
+                    None => HardwareRegister::IntSpill
+                };
+
+                self.instructions.push(InstructionIR::IsInstance(class_type.clone(), destination_register, class_ref_register));
+
+                if self.register_allocation.get_register(destination).is_stack() {
+                    self.instructions.push(InstructionIR::StoreFrameMemory(
+                        self.get_register_stack_offset(destination),
+                        destination_register
+                    ));
+                }
+
+                temp_registers.done(&mut self.instructions);
+            }
+            InstructionMIRData::CastClass(class_type, destination, class_ref) => {
+                let mut temp_registers = TempRegisters::new(&self.register_allocation, instruction_index);
+                let [class_ref_register] = temp_registers.get_and_try_remove(
+                    &self.register_allocation,
+                    self.compilation_result,
+                    &mut self.instructions,
+                    [class_ref]
+                );
+
+                self.instructions.push(InstructionIR::CastClass(class_type.clone(), class_ref_register));
+
+                match self.register_allocation.get_register(destination).hardware_register() {
+                    Some(destination_register) => {
+                        if destination_register != class_ref_register {
+                            self.instructions.push(InstructionIR::Move(destination_register, class_ref_register));
+                        }
+                    }
+                    None => {
+                        self.instructions.push(InstructionIR::StoreFrameMemory(
+                            self.get_register_stack_offset(destination),
+                            class_ref_register
+                        ));
+                    }
+                }
+
+                temp_registers.done(&mut self.instructions);
+            }
+            InstructionMIRData::LoadField(class_type, field_name, destination, class_ref) if destination.value_type.is_struct() => {
+                let class = self.type_storage.get(class_type).unwrap().class.as_ref().unwrap();
+                let field = class.get_field(field_name).unwrap();
+
+                let mut temp_registers = TempRegisters::new(&self.register_allocation, instruction_index);
+                let [class_ref_register] = temp_registers.get_and_try_remove(
+                    &self.register_allocation,
+                    self.compilation_result,
+                    &mut self.instructions,
+                    [class_ref]
+                );
+
+                if self.can_be_null(instruction_index, class_ref) {
+                    self.instructions.push(InstructionIR::NullReferenceCheck(class_ref_register));
+                }
+
+                self.instructions.push(InstructionIR::LoadStructField(
+                    field.type_id().clone(),
+                    field.offset(),
+                    self.get_register_stack_offset(destination),
+                    class_ref_register
+                ));
+
+                temp_registers.done(&mut self.instructions);
+            }
             InstructionMIRData::LoadField(class_type, field_name, destination, class_ref) => {
                 let class = self.type_storage.get(class_type).unwrap().class.as_ref().unwrap();
                 let field = class.get_field(field_name).unwrap();
@@ -594,7 +1421,7 @@ impl<'a> AllocatedInstructionIRCompiler<'a> {
                 let mut temp_registers = TempRegisters::new(&self.register_allocation, instruction_index);
                 let [class_ref_register] = temp_registers.get_and_try_remove(
                     &self.register_allocation,
-                    self.function,
+                    self.compilation_result,
                     &mut self.instructions,
                     [class_ref]
                 );
@@ -607,7 +1434,7 @@ impl<'a> AllocatedInstructionIRCompiler<'a> {
                     Some(register) => register,
                     None => {
                         match field.type_id() {
-                            TypeId::Float32 => HardwareRegister::FloatSpill,
+                            TypeId::Float32 | TypeId::Float64 => HardwareRegister::FloatSpill,
                             _ => HardwareRegister::IntSpill
                         }
                     }
@@ -630,6 +1457,31 @@ impl<'a> AllocatedInstructionIRCompiler<'a> {
 
                 temp_registers.done(&mut self.instructions);
             }
+            InstructionMIRData::StoreField(class_type, field_name, class_ref, value) if value.value_type.is_struct() => {
+                let class = self.type_storage.get(class_type).unwrap().class.as_ref().unwrap();
+                let field = class.get_field(field_name).unwrap();
+
+                let mut temp_registers = TempRegisters::new(&self.register_allocation, instruction_index);
+                let [class_ref_register] = temp_registers.get_and_try_remove(
+                    &self.register_allocation,
+                    self.compilation_result,
+                    &mut self.instructions,
+                    [class_ref]
+                );
+
+                if self.can_be_null(instruction_index, class_ref) {
+                    self.instructions.push(InstructionIR::NullReferenceCheck(class_ref_register));
+                }
+
+                self.instructions.push(InstructionIR::StoreStructField(
+                    field.type_id().clone(),
+                    field.offset(),
+                    class_ref_register,
+                    self.get_register_stack_offset(value)
+                ));
+
+                temp_registers.done(&mut self.instructions);
+            }
             InstructionMIRData::StoreField(class_type, field_name, class_ref, value) => {
                 let class = self.type_storage.get(class_type).unwrap().class.as_ref().unwrap();
                 let field = class.get_field(field_name).unwrap();
@@ -637,7 +1489,7 @@ impl<'a> AllocatedInstructionIRCompiler<'a> {
                 let mut temp_registers = TempRegisters::new(&self.register_allocation, instruction_index);
                 let [class_ref_register, value_register] = temp_registers.get_and_try_remove(
                     &self.register_allocation,
-                    self.function,
+                    self.compilation_result,
                     &mut self.instructions,
                     [class_ref, value]
                 );
@@ -655,6 +1507,103 @@ impl<'a> AllocatedInstructionIRCompiler<'a> {
 
                 temp_registers.done(&mut self.instructions);
             }
+            InstructionMIRData::LoadStaticField(class_type, field_name, destination) if destination.value_type.is_struct() => {
+                self.instructions.push(InstructionIR::LoadStaticStructField(class_type.clone(), field_name.clone(), self.get_register_stack_offset(destination)));
+            }
+            InstructionMIRData::LoadStaticField(class_type, field_name, destination) => {
+                match self.register_allocation.get_register(destination).hardware_register() {
+                    Some(register) => {
+                        self.instructions.push(InstructionIR::LoadStaticField(class_type.clone(), field_name.clone(), register));
+                    }
+                    None => {
+                        let spill_register = match &destination.value_type {
+                            TypeId::Float32 | TypeId::Float64 => HardwareRegister::FloatSpill,
+                            _ => HardwareRegister::IntSpill
+                        };
+
+                        self.instructions.push(InstructionIR::LoadStaticField(class_type.clone(), field_name.clone(), spill_register));
+                        self.instructions.push(InstructionIR::StoreFrameMemory(self.get_register_stack_offset(destination), spill_register));
+                    }
+                }
+            }
+            InstructionMIRData::StoreStaticField(class_type, field_name, value) if value.value_type.is_struct() => {
+                self.instructions.push(InstructionIR::StoreStaticStructField(class_type.clone(), field_name.clone(), self.get_register_stack_offset(value)));
+            }
+            InstructionMIRData::StoreStaticField(class_type, field_name, value) => {
+                let mut temp_registers = TempRegisters::new(&self.register_allocation, instruction_index);
+                let [value_register] = temp_registers.get_and_try_remove(
+                    &self.register_allocation,
+                    self.compilation_result,
+                    &mut self.instructions,
+                    [value]
+                );
+
+                self.instructions.push(InstructionIR::StoreStaticField(class_type.clone(), field_name.clone(), value_register));
+
+                temp_registers.done(&mut self.instructions);
+            }
+            InstructionMIRData::NewStruct(struct_type, destination) => {
+                self.instructions.push(InstructionIR::ZeroFrameMemory(
+                    self.get_register_stack_offset(destination),
+                    self.type_storage.size_of(struct_type)
+                ));
+            }
+            InstructionMIRData::LoadStructField(struct_type, field_name, destination, struct_value) => {
+                let class = self.type_storage.get(struct_type).unwrap().class.as_ref().unwrap();
+                let field = class.get_field(field_name).unwrap();
+                let field_offset = self.get_register_stack_offset(struct_value) + field.offset() as i32;
+
+                if field.type_id().is_struct() {
+                    self.instructions.push(InstructionIR::CopyFrameMemory(self.get_register_stack_offset(destination), field_offset, field.size()));
+                } else {
+                    match self.register_allocation.get_register(destination).hardware_register() {
+                        Some(register) => {
+                            self.instructions.push(InstructionIR::LoadFrameField(field.type_id().clone(), register, field_offset));
+                        }
+                        None => {
+                            let spill_register = match field.type_id() {
+                                TypeId::Float32 | TypeId::Float64 => HardwareRegister::FloatSpill,
+                                _ => HardwareRegister::IntSpill
+                            };
+
+                            self.instructions.push(InstructionIR::LoadFrameField(field.type_id().clone(), spill_register, field_offset));
+                            self.instructions.push(InstructionIR::StoreFrameMemory(self.get_register_stack_offset(destination), spill_register));
+                        }
+                    }
+                }
+            }
+            InstructionMIRData::StoreStructField(struct_type, field_name, destination, struct_value, value) => {
+                let class = self.type_storage.get(struct_type).unwrap().class.as_ref().unwrap();
+                let field = class.get_field(field_name).unwrap();
+                let field_offset = self.get_register_stack_offset(destination) + field.offset() as i32;
+
+                if destination != struct_value {
+                    self.instructions.push(InstructionIR::CopyFrameMemory(
+                        self.get_register_stack_offset(destination),
+                        self.get_register_stack_offset(struct_value),
+                        class.memory_size()
+                    ));
+                }
+
+                if field.type_id().is_struct() {
+                    self.instructions.push(InstructionIR::CopyFrameMemory(field_offset, self.get_register_stack_offset(value), field.size()));
+                } else {
+                    match self.register_allocation.get_register(value).hardware_register() {
+                        Some(register) => {
+                            self.instructions.push(InstructionIR::StoreFrameField(field.type_id().clone(), field_offset, register));
+                        }
+                        None => {
+                            let spill_register = match field.type_id() {
+                                TypeId::Float32 | TypeId::Float64 => HardwareRegister::FloatSpill,
+                                _ => HardwareRegister::IntSpill
+                            };
+
+                            self.instructions.push(InstructionIR::LoadFrameMemory(spill_register, self.get_register_stack_offset(value)));
+                            self.instructions.push(InstructionIR::StoreFrameField(field.type_id().clone(), field_offset, spill_register));
+                        }
+                    }
+                }
+            }
             InstructionMIRData::CallInstance(signature, return_value, arguments) => {
                 let func_to_call = self.binder.get(signature).unwrap();
 
@@ -665,7 +1614,7 @@ impl<'a> AllocatedInstructionIRCompiler<'a> {
                     let mut temp_registers = TempRegisters::new(&self.register_allocation, instruction_index);
                     let [class_ref_register] = temp_registers.get_and_try_remove(
                         &self.register_allocation,
-                        self.function,
+                        self.compilation_result,
                         &mut self.instructions,
                         [class_ref]
                     );
@@ -677,13 +1626,23 @@ impl<'a> AllocatedInstructionIRCompiler<'a> {
                 }
 
                 let arguments_source = self.get_call_argument_sources(func_to_call, arguments);
-                self.instructions.push(InstructionIR::Call(signature.clone(), arguments_source, 0));
+                if func_to_call.is_virtual() {
+                    let class_type = self.type_storage.get(func_to_call.class().as_ref().unwrap()).unwrap();
+                    let virtual_index = class_type.virtual_function_index(signature).unwrap();
+                    if func_to_call.virtual_modifier() == &Some(VirtualModifier::Interface) {
+                        self.instructions.push(InstructionIR::CallInterface(signature.clone(), virtual_index, arguments_source, 0));
+                    } else {
+                        self.instructions.push(InstructionIR::CallVirtual(signature.clone(), virtual_index, arguments_source, 0));
+                    }
+                } else {
+                    self.instructions.push(InstructionIR::Call(signature.clone(), arguments_source, 0, instruction_index));
+                }
 
                 let return_register = if let Some(return_value) = return_value {
                     CallingConventions::new().handle_return_value(
                         self.function,
                         &match self.register_allocation.get_register(return_value).hardware_register() {
-                            Some(register) => Variable::Register(register.clone()),
+                            Some(register) => Variable::Register(register),
                             None => Variable::FrameMemory(self.get_register_stack_offset(return_value))
                         },
                         func_to_call,
@@ -730,6 +1689,36 @@ impl<'a> AllocatedInstructionIRCompiler<'a> {
 
                         false
                     }
+                    TypeId::Float64 => {
+                        self.binary_operator_f32(
+                            operand1,
+                            operand2,
+                            |instructions, op1, op2| {
+                                instructions.push(InstructionIR::Compare(TypeId::Float64, op1, op2));
+                            },
+                            |instructions, op1, op2| {
+                                instructions.push(InstructionIR::CompareFromFrameMemory(TypeId::Float64, op1, op2));
+                            }
+                        );
+
+                        false
+                    }
+                    TypeId::Int64 => {
+                        self.binary_operator(
+                            operand1,
+                            operand2,
+                            |instructions, op1, op2| {
+                                instructions.push(InstructionIR::Compare(TypeId::Int64, op1, op2));
+                            },
+                            |instructions, op1, op2| {
+                                instructions.push(InstructionIR::CompareFromFrameMemory(TypeId::Int64, op1, op2));
+                            },
+                            |instructions, op1, op2| {
+                                instructions.push(InstructionIR::CompareToFrameMemory(TypeId::Int64, op1, op2));
+                            }
+                        );
+                        true
+                    }
                     _ => {
                         self.binary_operator(
                             operand1,
@@ -761,7 +1750,7 @@ impl<'a> AllocatedInstructionIRCompiler<'a> {
 
                 let (destination_register, destination_is_stack, destination_alive) = temp_registers.get_with_status(
                     &self.register_allocation,
-                    self.function,
+                    self.compilation_result,
                     &mut self.instructions,
                     destination
                 );
@@ -784,6 +1773,36 @@ impl<'a> AllocatedInstructionIRCompiler<'a> {
 
                         false
                     }
+                    TypeId::Float64 => {
+                        self.binary_operator_f32(
+                            operand1,
+                            operand2,
+                            |instructions, op1, op2| {
+                                instructions.push(InstructionIR::Compare(TypeId::Float64, op1, op2));
+                            },
+                            |instructions, op1, op2| {
+                                instructions.push(InstructionIR::CompareFromFrameMemory(TypeId::Float64, op1, op2));
+                            }
+                        );
+
+                        false
+                    }
+                    TypeId::Int64 => {
+                        self.binary_operator(
+                            operand1,
+                            operand2,
+                            |instructions, op1, op2| {
+                                instructions.push(InstructionIR::Compare(TypeId::Int64, op1, op2));
+                            },
+                            |instructions, op1, op2| {
+                                instructions.push(InstructionIR::CompareFromFrameMemory(TypeId::Int64, op1, op2));
+                            },
+                            |instructions, op1, op2| {
+                                instructions.push(InstructionIR::CompareToFrameMemory(TypeId::Int64, op1, op2));
+                            }
+                        );
+                        true
+                    }
                     _ => {
                         self.binary_operator(
                             operand1,
@@ -818,6 +1837,20 @@ impl<'a> AllocatedInstructionIRCompiler<'a> {
 
                 temp_registers.clear();
             }
+            InstructionMIRData::Throw(exception) => {
+                let exception_register = match self.register_allocation.get_register(exception).hardware_register() {
+                    Some(register) => register,
+                    None => {
+                        self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::IntSpill, self.get_register_stack_offset(exception)));
+                        HardwareRegister::IntSpill
+                    }
+                };
+
+                self.instructions.push(InstructionIR::Throw(exception_register));
+            }
+            InstructionMIRData::ExceptionLanding(_) | InstructionMIRData::EndFinally(_) => {
+                panic!("Protected regions are not supported with register allocation.");
+            }
         }
     }
 
@@ -827,7 +1860,7 @@ impl<'a> AllocatedInstructionIRCompiler<'a> {
         self.pop_alive_registers(&alive_registers, None);
     }
 
-    fn get_call_argument_sources(&self, func_to_call: &FunctionDeclaration, arguments: &Vec<RegisterMIR>) -> Vec<Variable> {
+    fn get_call_argument_sources(&self, func_to_call: &FunctionDeclaration, arguments: &[RegisterMIR]) -> Vec<Variable> {
         let mut variables = Vec::new();
 
         let mut overwritten = HashSet::new();
@@ -836,8 +1869,8 @@ impl<'a> AllocatedInstructionIRCompiler<'a> {
                 AllocatedRegister::Hardware { register, .. } => {
                     // We might overwrite the register value when doing moves to the register arguments,
                     // so in that case, use cached version of the register on the stack that is created as part of the save register operation
-                    if !overwritten.contains(&register_mapping::get(register.clone(), DataSize::Bytes8)) {
-                        variables.push(Variable::Register(register.clone()));
+                    if !overwritten.contains(&register_mapping::get(*register, DataSize::Bytes8)) {
+                        variables.push(Variable::Register(*register));
                     } else {
                         variables.push(Variable::FrameMemory(self.get_register_stack_offset(argument)));
                     }
@@ -856,6 +1889,25 @@ impl<'a> AllocatedInstructionIRCompiler<'a> {
         variables
     }
 
+    /// Pushes the operands on the stack, where the array operations expect them. References are checked for null.
+    fn push_operands(&mut self, instruction_index: usize, operands: &[&RegisterMIR]) {
+        for operand in operands {
+            let register = match self.register_allocation.get_register(operand).hardware_register() {
+                Some(register) => register,
+                None => {
+                    self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::IntSpill, self.get_register_stack_offset(operand)));
+                    HardwareRegister::IntSpill
+                }
+            };
+
+            if operand.value_type.is_reference() && self.can_be_null(instruction_index, operand) {
+                self.instructions.push(InstructionIR::NullReferenceCheck(register));
+            }
+
+            self.instructions.push(InstructionIR::Push(register));
+        }
+    }
+
     fn can_be_null(&self, instruction_index: usize, register: &RegisterMIR) -> bool {
         assert!(register.value_type.is_reference());
         self.optimization_result.instructions_register_null_status[instruction_index].get(register).cloned().unwrap_or(true)
@@ -863,8 +1915,8 @@ impl<'a> AllocatedInstructionIRCompiler<'a> {
 }
 
 impl<'a> AllocatedCompilerHelpers for AllocatedInstructionIRCompiler<'a> {
-    fn function(&self) -> &Function {
-        &self.function
+    fn compilation_result(&self) -> &MIRCompilationResult {
+        self.compilation_result
     }
 
     fn register_allocation(&self) -> &RegisterAllocation {
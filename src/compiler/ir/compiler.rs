@@ -1,15 +1,15 @@
-use crate::analysis::OptimizationResult;
-use crate::compiler::calling_conventions::{CallingConventions, float_register_call_arguments, register_call_arguments};
+use iced_x86::Register;
+
+use crate::analysis::{OptimizationResult, VirtualRegister};
+use crate::compiler::calling_conventions::{CallingConventions, register_call_arguments};
 use crate::compiler::ir::{HardwareRegister, HardwareRegisterExplicit, InstructionIR, Variable};
 use crate::compiler::stack_layout;
 use crate::model::binder::Binder;
 use crate::mir::{InstructionMIR, RegisterMIR};
-use crate::mir::compiler::{InstructionMIRCompiler, MIRCompilationResult};
+use crate::mir::compiler::MIRCompilationResult;
 use crate::mir::InstructionMIRData;
-use crate::model::function::{Function, FunctionDeclaration, FunctionSignature};
-use crate::model::instruction::Instruction;
+use crate::model::function::{Function, FunctionDeclaration, VirtualModifier};
 use crate::model::typesystem::{TypeId, TypeStorage};
-use crate::model::verifier::Verifier;
 
 pub struct InstructionIRCompiler<'a> {
     binder: &'a Binder,
@@ -52,6 +52,8 @@ impl<'a> InstructionIRCompiler<'a> {
             self.instructions.push(InstructionIR::SubFromStackPointer(stack_size));
         }
 
+        self.instructions.push(InstructionIR::StackOverflowCheck);
+
         let argument_sources = CallingConventions::new().move_arguments_to_stack(self.function, &mut self.instructions);
         if let Some(this_reference) = self.compilation_result.member_this_register(self.function) {
             argument_sources[0].move_to_stack_frame(
@@ -63,10 +65,17 @@ impl<'a> InstructionIRCompiler<'a> {
         if !self.compilation_result.need_zero_initialize_registers.is_empty() {
             self.instructions.push(InstructionIR::LoadZeroToRegister(HardwareRegister::IntSpill));
             for register in &self.compilation_result.need_zero_initialize_registers {
-                self.instructions.push(InstructionIR::StoreFrameMemory(
-                    self.get_register_stack_offset(register),
-                    HardwareRegister::IntSpill
-                ));
+                if register.value_type.is_struct() {
+                    self.instructions.push(InstructionIR::ZeroFrameMemory(
+                        self.get_register_stack_offset(register),
+                        self.type_storage.size_of(&register.value_type)
+                    ));
+                } else {
+                    self.instructions.push(InstructionIR::StoreFrameMemory(
+                        self.get_register_stack_offset(register),
+                        HardwareRegister::IntSpill
+                    ));
+                }
             }
         }
     }
@@ -79,12 +88,30 @@ impl<'a> InstructionIRCompiler<'a> {
                 self.instructions.push(InstructionIR::MoveInt32ToFrameMemory(self.get_register_stack_offset(destination), *value));
             }
             InstructionMIRData::LoadFloat32(destination, value) => {
-                let value: i32 = unsafe { std::mem::transmute(*value) };
+                let value: i32 = value.to_bits() as i32;
                 self.instructions.push(InstructionIR::MoveInt32ToFrameMemory(self.get_register_stack_offset(destination), value));
             }
+            InstructionMIRData::LoadInt64(destination, value) => {
+                self.instructions.push(InstructionIR::MoveInt64ToRegister(HardwareRegister::IntSpill, *value));
+                self.instructions.push(InstructionIR::StoreFrameMemory(self.get_register_stack_offset(destination), HardwareRegister::IntSpill));
+            }
+            InstructionMIRData::LoadFloat64(destination, value) => {
+                let value: i64 = value.to_bits() as i64;
+                self.instructions.push(InstructionIR::MoveInt64ToRegister(HardwareRegister::IntSpill, value));
+                self.instructions.push(InstructionIR::StoreFrameMemory(self.get_register_stack_offset(destination), HardwareRegister::IntSpill));
+            }
             InstructionMIRData::LoadBool(destination, value) => {
                 self.instructions.push(InstructionIR::MoveInt32ToFrameMemory(self.get_register_stack_offset(destination), if *value {1} else {0}));
             }
+            InstructionMIRData::Move(destination, source) if destination.value_type.is_struct() => {
+                if destination != source {
+                    self.instructions.push(InstructionIR::CopyFrameMemory(
+                        self.get_register_stack_offset(destination),
+                        self.get_register_stack_offset(source),
+                        self.type_storage.size_of(&destination.value_type)
+                    ));
+                }
+            }
             InstructionMIRData::Move(destination, source) => {
                 self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(0), self.get_register_stack_offset(source)));
                 self.instructions.push(InstructionIR::StoreFrameMemory(self.get_register_stack_offset(destination), HardwareRegister::Int(0)));
@@ -123,6 +150,17 @@ impl<'a> InstructionIRCompiler<'a> {
                 self.instructions.push(InstructionIR::DivideInt32(HardwareRegister::Int(0), HardwareRegister::Int(1)));
                 self.instructions.push(InstructionIR::StoreFrameMemory(self.get_register_stack_offset(destination), HardwareRegister::Int(0)));
             }
+            InstructionMIRData::RemainderInt32(destination, operand1, operand2) => {
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(0), self.get_register_stack_offset(operand1)));
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(1), self.get_register_stack_offset(operand2)));
+                self.instructions.push(InstructionIR::RemainderInt32(HardwareRegister::Int(0), HardwareRegister::Int(1)));
+                self.instructions.push(InstructionIR::StoreFrameMemory(self.get_register_stack_offset(destination), HardwareRegister::Int(0)));
+            }
+            InstructionMIRData::NegateInt32(destination, operand) => {
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(0), self.get_register_stack_offset(operand)));
+                self.instructions.push(InstructionIR::NegateInt32(HardwareRegister::Int(0)));
+                self.instructions.push(InstructionIR::StoreFrameMemory(self.get_register_stack_offset(destination), HardwareRegister::Int(0)));
+            }
             InstructionMIRData::AddFloat32(destination, operand1, operand2) => {
                 self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Float(0), self.get_register_stack_offset(operand1)));
                 self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Float(1), self.get_register_stack_offset(operand2)));
@@ -147,6 +185,122 @@ impl<'a> InstructionIRCompiler<'a> {
                 self.instructions.push(InstructionIR::DivideFloat32(HardwareRegister::Float(0), HardwareRegister::Float(1)));
                 self.instructions.push(InstructionIR::StoreFrameMemory(self.get_register_stack_offset(destination), HardwareRegister::Float(0)));
             }
+            InstructionMIRData::MinFloat32(destination, operand1, operand2) => {
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Float(0), self.get_register_stack_offset(operand1)));
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Float(1), self.get_register_stack_offset(operand2)));
+                self.instructions.push(InstructionIR::MinFloat32(HardwareRegister::Float(0), HardwareRegister::Float(1)));
+                self.instructions.push(InstructionIR::StoreFrameMemory(self.get_register_stack_offset(destination), HardwareRegister::Float(0)));
+            }
+            InstructionMIRData::MaxFloat32(destination, operand1, operand2) => {
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Float(0), self.get_register_stack_offset(operand1)));
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Float(1), self.get_register_stack_offset(operand2)));
+                self.instructions.push(InstructionIR::MaxFloat32(HardwareRegister::Float(0), HardwareRegister::Float(1)));
+                self.instructions.push(InstructionIR::StoreFrameMemory(self.get_register_stack_offset(destination), HardwareRegister::Float(0)));
+            }
+            InstructionMIRData::SquareRootFloat32(destination, operand) => {
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Float(0), self.get_register_stack_offset(operand)));
+                self.instructions.push(InstructionIR::SquareRootFloat32(HardwareRegister::Float(1), HardwareRegister::Float(0)));
+                self.instructions.push(InstructionIR::StoreFrameMemory(self.get_register_stack_offset(destination), HardwareRegister::Float(1)));
+            }
+            InstructionMIRData::RoundFloat32(destination, operand, mode) => {
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Float(0), self.get_register_stack_offset(operand)));
+                self.instructions.push(InstructionIR::RoundFloat32(HardwareRegister::Float(1), HardwareRegister::Float(0), *mode));
+                self.instructions.push(InstructionIR::StoreFrameMemory(self.get_register_stack_offset(destination), HardwareRegister::Float(1)));
+            }
+            InstructionMIRData::AddInt64(destination, operand1, operand2) => {
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(0), self.get_register_stack_offset(operand1)));
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(1), self.get_register_stack_offset(operand2)));
+                self.instructions.push(InstructionIR::AddInt64(HardwareRegister::Int(0), HardwareRegister::Int(1)));
+                self.instructions.push(InstructionIR::StoreFrameMemory(self.get_register_stack_offset(destination), HardwareRegister::Int(0)));
+            }
+            InstructionMIRData::SubInt64(destination, operand1, operand2) => {
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(0), self.get_register_stack_offset(operand1)));
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(1), self.get_register_stack_offset(operand2)));
+                self.instructions.push(InstructionIR::SubInt64(HardwareRegister::Int(0), HardwareRegister::Int(1)));
+                self.instructions.push(InstructionIR::StoreFrameMemory(self.get_register_stack_offset(destination), HardwareRegister::Int(0)));
+            }
+            InstructionMIRData::MultiplyInt64(destination, operand1, operand2) => {
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(0), self.get_register_stack_offset(operand1)));
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(1), self.get_register_stack_offset(operand2)));
+                self.instructions.push(InstructionIR::MultiplyInt64(HardwareRegister::Int(0), HardwareRegister::Int(1)));
+                self.instructions.push(InstructionIR::StoreFrameMemory(self.get_register_stack_offset(destination), HardwareRegister::Int(0)));
+            }
+            InstructionMIRData::DivideInt64(destination, operand1, operand2) => {
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(0), self.get_register_stack_offset(operand1)));
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(1), self.get_register_stack_offset(operand2)));
+                self.instructions.push(InstructionIR::DivideInt64(HardwareRegister::Int(0), HardwareRegister::Int(1)));
+                self.instructions.push(InstructionIR::StoreFrameMemory(self.get_register_stack_offset(destination), HardwareRegister::Int(0)));
+            }
+            InstructionMIRData::RemainderInt64(destination, operand1, operand2) => {
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(0), self.get_register_stack_offset(operand1)));
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(1), self.get_register_stack_offset(operand2)));
+                self.instructions.push(InstructionIR::RemainderInt64(HardwareRegister::Int(0), HardwareRegister::Int(1)));
+                self.instructions.push(InstructionIR::StoreFrameMemory(self.get_register_stack_offset(destination), HardwareRegister::Int(0)));
+            }
+            InstructionMIRData::NegateInt64(destination, operand) => {
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(0), self.get_register_stack_offset(operand)));
+                self.instructions.push(InstructionIR::NegateInt64(HardwareRegister::Int(0)));
+                self.instructions.push(InstructionIR::StoreFrameMemory(self.get_register_stack_offset(destination), HardwareRegister::Int(0)));
+            }
+            InstructionMIRData::AndInt64(destination, operand1, operand2) => {
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(0), self.get_register_stack_offset(operand1)));
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(1), self.get_register_stack_offset(operand2)));
+                self.instructions.push(InstructionIR::AndInt64(HardwareRegister::Int(0), HardwareRegister::Int(1)));
+                self.instructions.push(InstructionIR::StoreFrameMemory(self.get_register_stack_offset(destination), HardwareRegister::Int(0)));
+            }
+            InstructionMIRData::OrInt64(destination, operand1, operand2) => {
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(0), self.get_register_stack_offset(operand1)));
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(1), self.get_register_stack_offset(operand2)));
+                self.instructions.push(InstructionIR::OrInt64(HardwareRegister::Int(0), HardwareRegister::Int(1)));
+                self.instructions.push(InstructionIR::StoreFrameMemory(self.get_register_stack_offset(destination), HardwareRegister::Int(0)));
+            }
+            InstructionMIRData::XorInt64(destination, operand1, operand2) => {
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(0), self.get_register_stack_offset(operand1)));
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(1), self.get_register_stack_offset(operand2)));
+                self.instructions.push(InstructionIR::XorInt64(HardwareRegister::Int(0), HardwareRegister::Int(1)));
+                self.instructions.push(InstructionIR::StoreFrameMemory(self.get_register_stack_offset(destination), HardwareRegister::Int(0)));
+            }
+            InstructionMIRData::NotInt64(destination, operand) => {
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(0), self.get_register_stack_offset(operand)));
+                self.instructions.push(InstructionIR::BitwiseNotInt64(HardwareRegister::Int(0)));
+                self.instructions.push(InstructionIR::StoreFrameMemory(self.get_register_stack_offset(destination), HardwareRegister::Int(0)));
+            }
+            InstructionMIRData::ShiftLeftInt64(destination, operand1, operand2) => {
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(0), self.get_register_stack_offset(operand1)));
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(1), self.get_register_stack_offset(operand2)));
+                self.instructions.push(InstructionIR::ShiftLeftInt64(HardwareRegister::Int(0), HardwareRegister::Int(1)));
+                self.instructions.push(InstructionIR::StoreFrameMemory(self.get_register_stack_offset(destination), HardwareRegister::Int(0)));
+            }
+            InstructionMIRData::ShiftRightInt64(destination, operand1, operand2) => {
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(0), self.get_register_stack_offset(operand1)));
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(1), self.get_register_stack_offset(operand2)));
+                self.instructions.push(InstructionIR::ShiftRightInt64(HardwareRegister::Int(0), HardwareRegister::Int(1)));
+                self.instructions.push(InstructionIR::StoreFrameMemory(self.get_register_stack_offset(destination), HardwareRegister::Int(0)));
+            }
+            InstructionMIRData::AddFloat64(destination, operand1, operand2) => {
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Float(0), self.get_register_stack_offset(operand1)));
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Float(1), self.get_register_stack_offset(operand2)));
+                self.instructions.push(InstructionIR::AddFloat64(HardwareRegister::Float(0), HardwareRegister::Float(1)));
+                self.instructions.push(InstructionIR::StoreFrameMemory(self.get_register_stack_offset(destination), HardwareRegister::Float(0)));
+            }
+            InstructionMIRData::SubFloat64(destination, operand1, operand2) => {
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Float(0), self.get_register_stack_offset(operand1)));
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Float(1), self.get_register_stack_offset(operand2)));
+                self.instructions.push(InstructionIR::SubFloat64(HardwareRegister::Float(0), HardwareRegister::Float(1)));
+                self.instructions.push(InstructionIR::StoreFrameMemory(self.get_register_stack_offset(destination), HardwareRegister::Float(0)));
+            }
+            InstructionMIRData::MultiplyFloat64(destination, operand1, operand2) => {
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Float(0), self.get_register_stack_offset(operand1)));
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Float(1), self.get_register_stack_offset(operand2)));
+                self.instructions.push(InstructionIR::MultiplyFloat64(HardwareRegister::Float(0), HardwareRegister::Float(1)));
+                self.instructions.push(InstructionIR::StoreFrameMemory(self.get_register_stack_offset(destination), HardwareRegister::Float(0)));
+            }
+            InstructionMIRData::DivideFloat64(destination, operand1, operand2) => {
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Float(0), self.get_register_stack_offset(operand1)));
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Float(1), self.get_register_stack_offset(operand2)));
+                self.instructions.push(InstructionIR::DivideFloat64(HardwareRegister::Float(0), HardwareRegister::Float(1)));
+                self.instructions.push(InstructionIR::StoreFrameMemory(self.get_register_stack_offset(destination), HardwareRegister::Float(0)));
+            }
             InstructionMIRData::AndBool(destination, operand1, operand2) => {
                 self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(0), self.get_register_stack_offset(operand1)));
                 self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(1), self.get_register_stack_offset(operand2)));
@@ -174,6 +328,86 @@ impl<'a> InstructionIRCompiler<'a> {
                 self.instructions.push(InstructionIR::NotInt32(HardwareRegister::Int(0)));
                 self.instructions.push(InstructionIR::StoreFrameMemory(self.get_register_stack_offset(destination), HardwareRegister::Int(0)));
             }
+            InstructionMIRData::AndInt32(destination, operand1, operand2) => {
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(0), self.get_register_stack_offset(operand1)));
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(1), self.get_register_stack_offset(operand2)));
+                self.instructions.push(InstructionIR::AndInt32(HardwareRegister::Int(0), HardwareRegister::Int(1)));
+                self.instructions.push(InstructionIR::StoreFrameMemory(self.get_register_stack_offset(destination), HardwareRegister::Int(0)));
+            }
+            InstructionMIRData::OrInt32(destination, operand1, operand2) => {
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(0), self.get_register_stack_offset(operand1)));
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(1), self.get_register_stack_offset(operand2)));
+                self.instructions.push(InstructionIR::OrInt32(HardwareRegister::Int(0), HardwareRegister::Int(1)));
+                self.instructions.push(InstructionIR::StoreFrameMemory(self.get_register_stack_offset(destination), HardwareRegister::Int(0)));
+            }
+            InstructionMIRData::XorInt32(destination, operand1, operand2) => {
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(0), self.get_register_stack_offset(operand1)));
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(1), self.get_register_stack_offset(operand2)));
+                self.instructions.push(InstructionIR::XorInt32(HardwareRegister::Int(0), HardwareRegister::Int(1)));
+                self.instructions.push(InstructionIR::StoreFrameMemory(self.get_register_stack_offset(destination), HardwareRegister::Int(0)));
+            }
+            InstructionMIRData::NotInt32(destination, operand) => {
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(0), self.get_register_stack_offset(operand)));
+                self.instructions.push(InstructionIR::BitwiseNotInt32(HardwareRegister::Int(0)));
+                self.instructions.push(InstructionIR::StoreFrameMemory(self.get_register_stack_offset(destination), HardwareRegister::Int(0)));
+            }
+            InstructionMIRData::ShiftLeftInt32(destination, operand1, operand2) => {
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(0), self.get_register_stack_offset(operand1)));
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(1), self.get_register_stack_offset(operand2)));
+                self.instructions.push(InstructionIR::ShiftLeftInt32(HardwareRegister::Int(0), HardwareRegister::Int(1)));
+                self.instructions.push(InstructionIR::StoreFrameMemory(self.get_register_stack_offset(destination), HardwareRegister::Int(0)));
+            }
+            InstructionMIRData::ShiftRightInt32(destination, operand1, operand2) => {
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(0), self.get_register_stack_offset(operand1)));
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(1), self.get_register_stack_offset(operand2)));
+                self.instructions.push(InstructionIR::ShiftRightInt32(HardwareRegister::Int(0), HardwareRegister::Int(1)));
+                self.instructions.push(InstructionIR::StoreFrameMemory(self.get_register_stack_offset(destination), HardwareRegister::Int(0)));
+            }
+            InstructionMIRData::ConvertInt32ToFloat32(destination, operand) => {
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(0), self.get_register_stack_offset(operand)));
+                self.instructions.push(InstructionIR::ConvertInt32ToFloat32(HardwareRegister::Float(1), HardwareRegister::Int(0)));
+                self.instructions.push(InstructionIR::StoreFrameMemory(self.get_register_stack_offset(destination), HardwareRegister::Float(1)));
+            }
+            InstructionMIRData::ConvertFloat32ToInt32(destination, operand) => {
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Float(0), self.get_register_stack_offset(operand)));
+                self.instructions.push(InstructionIR::ConvertFloat32ToInt32(HardwareRegister::Int(1), HardwareRegister::Float(0)));
+                self.instructions.push(InstructionIR::StoreFrameMemory(self.get_register_stack_offset(destination), HardwareRegister::Int(1)));
+            }
+            InstructionMIRData::ConvertBoolToInt32(destination, operand) => {
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(0), self.get_register_stack_offset(operand)));
+                self.instructions.push(InstructionIR::ConvertBoolToInt32(HardwareRegister::Int(1), HardwareRegister::Int(0)));
+                self.instructions.push(InstructionIR::StoreFrameMemory(self.get_register_stack_offset(destination), HardwareRegister::Int(1)));
+            }
+            InstructionMIRData::ConvertInt32ToInt64(destination, operand) => {
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(0), self.get_register_stack_offset(operand)));
+                self.instructions.push(InstructionIR::ConvertInt32ToInt64(HardwareRegister::Int(1), HardwareRegister::Int(0)));
+                self.instructions.push(InstructionIR::StoreFrameMemory(self.get_register_stack_offset(destination), HardwareRegister::Int(1)));
+            }
+            InstructionMIRData::ConvertInt64ToInt32(destination, operand) => {
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(0), self.get_register_stack_offset(operand)));
+                self.instructions.push(InstructionIR::ConvertInt64ToInt32(HardwareRegister::Int(1), HardwareRegister::Int(0)));
+                self.instructions.push(InstructionIR::StoreFrameMemory(self.get_register_stack_offset(destination), HardwareRegister::Int(1)));
+            }
+            InstructionMIRData::ConvertFloat32ToFloat64(destination, operand) => {
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Float(0), self.get_register_stack_offset(operand)));
+                self.instructions.push(InstructionIR::ConvertFloat32ToFloat64(HardwareRegister::Float(1), HardwareRegister::Float(0)));
+                self.instructions.push(InstructionIR::StoreFrameMemory(self.get_register_stack_offset(destination), HardwareRegister::Float(1)));
+            }
+            InstructionMIRData::ConvertFloat64ToFloat32(destination, operand) => {
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Float(0), self.get_register_stack_offset(operand)));
+                self.instructions.push(InstructionIR::ConvertFloat64ToFloat32(HardwareRegister::Float(1), HardwareRegister::Float(0)));
+                self.instructions.push(InstructionIR::StoreFrameMemory(self.get_register_stack_offset(destination), HardwareRegister::Float(1)));
+            }
+            InstructionMIRData::ConvertInt64ToFloat64(destination, operand) => {
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(0), self.get_register_stack_offset(operand)));
+                self.instructions.push(InstructionIR::ConvertInt64ToFloat64(HardwareRegister::Float(1), HardwareRegister::Int(0)));
+                self.instructions.push(InstructionIR::StoreFrameMemory(self.get_register_stack_offset(destination), HardwareRegister::Float(1)));
+            }
+            InstructionMIRData::ConvertFloat64ToInt64(destination, operand) => {
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Float(0), self.get_register_stack_offset(operand)));
+                self.instructions.push(InstructionIR::ConvertFloat64ToInt64(HardwareRegister::Int(1), HardwareRegister::Float(0)));
+                self.instructions.push(InstructionIR::StoreFrameMemory(self.get_register_stack_offset(destination), HardwareRegister::Int(1)));
+            }
             InstructionMIRData::Return(source) => {
                 if let Some(source) = source {
                     CallingConventions::new().make_return_value(
@@ -193,7 +427,7 @@ impl<'a> InstructionIRCompiler<'a> {
                     .map(|argument| Variable::FrameMemory(self.get_register_stack_offset(argument)))
                     .collect::<Vec<_>>();
 
-                self.instructions.push(InstructionIR::Call(signature.clone(), arguments_source, 0));
+                self.instructions.push(InstructionIR::Call(signature.clone(), arguments_source, 0, instruction_index));
 
                 if let Some(return_value) = return_value {
                     CallingConventions::new().handle_return_value(
@@ -204,6 +438,30 @@ impl<'a> InstructionIRCompiler<'a> {
                     );
                 }
             }
+            InstructionMIRData::LoadFunction(signature, destination) => {
+                self.instructions.push(InstructionIR::LoadFunction(signature.clone(), HardwareRegister::Int(0)));
+                self.instructions.push(InstructionIR::StoreFrameMemory(self.get_register_stack_offset(destination), HardwareRegister::Int(0)));
+            }
+            InstructionMIRData::CallIndirect(function_type, return_value, function_reference, arguments) => {
+                let func_to_call = FunctionDeclaration::with_function_reference(function_type).unwrap();
+
+                let arguments_source = arguments
+                    .iter()
+                    .map(|argument| Variable::FrameMemory(self.get_register_stack_offset(argument)))
+                    .collect::<Vec<_>>();
+
+                let function_source = Variable::FrameMemory(self.get_register_stack_offset(function_reference));
+                self.instructions.push(InstructionIR::CallIndirect(function_type.clone(), function_source, arguments_source, 0, instruction_index));
+
+                if let Some(return_value) = return_value {
+                    CallingConventions::new().handle_return_value(
+                        self.function,
+                        &Variable::FrameMemory(self.get_register_stack_offset(return_value)),
+                        &func_to_call,
+                        &mut self.instructions
+                    );
+                }
+            }
             InstructionMIRData::LoadArgument(argument_index, destination) => {
                 let argument_offset = stack_layout::argument_stack_offset(self.function, *argument_index);
                 let register_offset = self.get_register_stack_offset(destination);
@@ -214,14 +472,34 @@ impl<'a> InstructionIRCompiler<'a> {
             InstructionMIRData::LoadNull(destination) => {
                 self.instructions.push(InstructionIR::MoveInt32ToFrameMemory(self.get_register_stack_offset(destination), 0));
             }
+            InstructionMIRData::LoadString(destination, value) => {
+                self.instructions.push(InstructionIR::LoadString(HardwareRegister::Int(0), value.clone()));
+                self.instructions.push(InstructionIR::StoreFrameMemory(self.get_register_stack_offset(destination), HardwareRegister::Int(0)));
+            }
             InstructionMIRData::NewArray(element, destination, size) => {
                 self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(0), self.get_register_stack_offset(size)));
-                self.instructions.push(InstructionIR::NewArray(element.clone(), HardwareRegister::Int(0), 0));
+                self.instructions.push(InstructionIR::NewArray(element.clone(), HardwareRegister::Int(0), 0, instruction_index));
                 self.instructions.push(InstructionIR::StoreFrameMemoryExplicit(
                     self.get_register_stack_offset(destination),
                     HardwareRegisterExplicit(register_call_arguments::RETURN_VALUE)
                 ));
             }
+            InstructionMIRData::LoadElement(element, destination, array_ref, index) if element.is_struct() => {
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(1), self.get_register_stack_offset(index)));
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(0), self.get_register_stack_offset(array_ref)));
+
+                if self.can_be_null(instruction_index, array_ref) {
+                    self.instructions.push(InstructionIR::NullReferenceCheck(HardwareRegister::Int(0)));
+                }
+
+                self.instructions.push(InstructionIR::ArrayBoundsCheck(HardwareRegister::Int(0), HardwareRegister::Int(1)));
+                self.instructions.push(InstructionIR::LoadStructElement(
+                    element.clone(),
+                    self.get_register_stack_offset(destination),
+                    HardwareRegister::Int(0),
+                    HardwareRegister::Int(1)
+                ));
+            }
             InstructionMIRData::LoadElement(element, destination, array_ref, index) => {
                 self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(1), self.get_register_stack_offset(index)));
                 self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(0), self.get_register_stack_offset(array_ref)));
@@ -233,7 +511,7 @@ impl<'a> InstructionIRCompiler<'a> {
                 self.instructions.push(InstructionIR::ArrayBoundsCheck(HardwareRegister::Int(0), HardwareRegister::Int(1)));
 
                 let return_value = match element {
-                    TypeId::Float32 => HardwareRegister::Float(2),
+                    TypeId::Float32 | TypeId::Float64 => HardwareRegister::Float(2),
                     _ => HardwareRegister::Int(2)
                 };
 
@@ -246,11 +524,14 @@ impl<'a> InstructionIRCompiler<'a> {
             }
             InstructionMIRData::StoreElement(element, array_ref, index, value) => {
                 let value_register = match element {
-                    TypeId::Float32 => HardwareRegister::Float(2),
+                    TypeId::Float32 | TypeId::Float64 => HardwareRegister::Float(2),
                     _ => HardwareRegister::Int(2)
                 };
 
-                self.instructions.push(InstructionIR::LoadFrameMemory(value_register, self.get_register_stack_offset(value)));
+                if !element.is_struct() {
+                    self.instructions.push(InstructionIR::LoadFrameMemory(value_register, self.get_register_stack_offset(value)));
+                }
+
                 self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(1), self.get_register_stack_offset(index)));
                 self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(0), self.get_register_stack_offset(array_ref)));
 
@@ -260,33 +541,174 @@ impl<'a> InstructionIRCompiler<'a> {
 
                 self.instructions.push(InstructionIR::ArrayBoundsCheck(HardwareRegister::Int(0), HardwareRegister::Int(1)));
 
-                self.instructions.push(InstructionIR::StoreElement(
+                if element.is_struct() {
+                    self.instructions.push(InstructionIR::StoreStructElement(
+                        element.clone(),
+                        HardwareRegister::Int(0),
+                        HardwareRegister::Int(1),
+                        self.get_register_stack_offset(value)
+                    ));
+                } else {
+                    self.instructions.push(InstructionIR::StoreElement(
+                        element.clone(),
+                        HardwareRegister::Int(0),
+                        HardwareRegister::Int(1),
+                        value_register
+                    ));
+                }
+            }
+            InstructionMIRData::LoadArrayLength(destination, array_ref) => {
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(0), self.get_register_stack_offset(array_ref)));
+
+                if self.can_be_null(instruction_index, array_ref) {
+                    self.instructions.push(InstructionIR::NullReferenceCheck(HardwareRegister::Int(0)));
+                }
+
+                self.instructions.push(InstructionIR::LoadArrayLength(HardwareRegister::IntSpill, HardwareRegister::Int(0)));
+                self.instructions.push(InstructionIR::StoreFrameMemory(
+                    self.get_register_stack_offset(destination),
+                    HardwareRegister::IntSpill
+                ));
+            }
+            InstructionMIRData::ArrayCopy(element, source, source_index, destination, destination_index, count) => {
+                self.push_operands(instruction_index, &[source, source_index, destination, destination_index, count]);
+                self.instructions.push(InstructionIR::ArrayCopy(element.clone()));
+            }
+            InstructionMIRData::ArrayFill(element, array_ref, value) => {
+                self.push_operands(instruction_index, &[array_ref, value]);
+                self.instructions.push(InstructionIR::ArrayFill(element.clone()));
+            }
+            InstructionMIRData::ArrayEquals(element, destination, array_ref1, array_ref2) => {
+                self.push_operands(instruction_index, &[array_ref1, array_ref2]);
+                self.instructions.push(InstructionIR::ArrayEquals(element.clone(), HardwareRegister::Int(0)));
+                self.instructions.push(InstructionIR::StoreFrameMemory(
+                    self.get_register_stack_offset(destination),
+                    HardwareRegister::Int(0)
+                ));
+            }
+            InstructionMIRData::ArrayClone(element, destination, array_ref) => {
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(0), self.get_register_stack_offset(array_ref)));
+
+                if self.can_be_null(instruction_index, array_ref) {
+                    self.instructions.push(InstructionIR::NullReferenceCheck(HardwareRegister::Int(0)));
+                }
+
+                self.instructions.push(InstructionIR::LoadArrayLength(HardwareRegister::Int(0), HardwareRegister::Int(0)));
+                self.instructions.push(InstructionIR::NewArray(element.clone(), HardwareRegister::Int(0), 0, instruction_index));
+                self.instructions.push(InstructionIR::MoveExplicitToImplicit(
+                    HardwareRegister::Int(1),
+                    HardwareRegisterExplicit(register_call_arguments::RETURN_VALUE)
+                ));
+
+                // The allocation might have moved the source array
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(0), self.get_register_stack_offset(array_ref)));
+                self.instructions.push(InstructionIR::Push(HardwareRegister::Int(0)));
+                self.instructions.push(InstructionIR::PushInt32(0));
+                self.instructions.push(InstructionIR::Push(HardwareRegister::Int(1)));
+                self.instructions.push(InstructionIR::PushInt32(0));
+                self.instructions.push(InstructionIR::LoadArrayLength(HardwareRegister::Int(0), HardwareRegister::Int(1)));
+                self.instructions.push(InstructionIR::Push(HardwareRegister::Int(0)));
+                self.instructions.push(InstructionIR::ArrayCopy(element.clone()));
+
+                self.instructions.push(InstructionIR::StoreFrameMemory(
+                    self.get_register_stack_offset(destination),
+                    HardwareRegister::Int(1)
+                ));
+            }
+            InstructionMIRData::NewArray2D(element, destination, rows, columns) => {
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(0), self.get_register_stack_offset(rows)));
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(1), self.get_register_stack_offset(columns)));
+                self.instructions.push(InstructionIR::NewArray2D(element.clone(), HardwareRegister::Int(0), HardwareRegister::Int(1), 0, instruction_index));
+                self.instructions.push(InstructionIR::StoreFrameMemoryExplicit(
+                    self.get_register_stack_offset(destination),
+                    HardwareRegisterExplicit(register_call_arguments::RETURN_VALUE)
+                ));
+            }
+            InstructionMIRData::LoadElement2D(element, destination, array_ref, row, column) => {
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(2), self.get_register_stack_offset(column)));
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(1), self.get_register_stack_offset(row)));
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(0), self.get_register_stack_offset(array_ref)));
+
+                if self.can_be_null(instruction_index, array_ref) {
+                    self.instructions.push(InstructionIR::NullReferenceCheck(HardwareRegister::Int(0)));
+                }
+
+                self.instructions.push(InstructionIR::Array2DBoundsCheck(HardwareRegister::Int(0), HardwareRegister::Int(1), HardwareRegister::Int(2)));
+
+                let return_value = match element {
+                    TypeId::Float32 | TypeId::Float64 => HardwareRegister::FloatSpill,
+                    _ => HardwareRegister::IntSpill
+                };
+
+                self.instructions.push(InstructionIR::LoadElement2D(
+                    element.clone(),
+                    return_value,
+                    HardwareRegister::Int(0),
+                    HardwareRegister::Int(1),
+                    HardwareRegister::Int(2)
+                ));
+
+                self.instructions.push(InstructionIR::StoreFrameMemory(
+                    self.get_register_stack_offset(destination),
+                    return_value
+                ));
+            }
+            InstructionMIRData::StoreElement2D(element, array_ref, row, column, value) => {
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(2), self.get_register_stack_offset(column)));
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(1), self.get_register_stack_offset(row)));
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(0), self.get_register_stack_offset(array_ref)));
+
+                if self.can_be_null(instruction_index, array_ref) {
+                    self.instructions.push(InstructionIR::NullReferenceCheck(HardwareRegister::Int(0)));
+                }
+
+                self.instructions.push(InstructionIR::Array2DBoundsCheck(HardwareRegister::Int(0), HardwareRegister::Int(1), HardwareRegister::Int(2)));
+
+                // The bounds check uses the spill register, so the value is loaded after it
+                let value_register = match element {
+                    TypeId::Float32 | TypeId::Float64 => HardwareRegister::FloatSpill,
+                    _ => HardwareRegister::IntSpill
+                };
+
+                self.instructions.push(InstructionIR::LoadFrameMemory(value_register, self.get_register_stack_offset(value)));
+                self.instructions.push(InstructionIR::StoreElement2D(
                     element.clone(),
                     HardwareRegister::Int(0),
                     HardwareRegister::Int(1),
+                    HardwareRegister::Int(2),
                     value_register
                 ));
             }
-            InstructionMIRData::LoadArrayLength(destination, array_ref) => {
+            InstructionMIRData::LoadArrayDimension(dimension, destination, array_ref) => {
                 self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(0), self.get_register_stack_offset(array_ref)));
 
                 if self.can_be_null(instruction_index, array_ref) {
                     self.instructions.push(InstructionIR::NullReferenceCheck(HardwareRegister::Int(0)));
                 }
 
-                self.instructions.push(InstructionIR::LoadArrayLength(HardwareRegister::IntSpill, HardwareRegister::Int(0)));
+                self.instructions.push(InstructionIR::LoadArrayDimension(*dimension, HardwareRegister::IntSpill, HardwareRegister::Int(0)));
                 self.instructions.push(InstructionIR::StoreFrameMemory(
                     self.get_register_stack_offset(destination),
                     HardwareRegister::IntSpill
                 ));
             }
             InstructionMIRData::NewObject(class_type, destination) => {
-                self.instructions.push(InstructionIR::NewObject(class_type.clone()));
+                self.instructions.push(InstructionIR::NewObject(class_type.clone(), instruction_index));
                 self.instructions.push(InstructionIR::StoreFrameMemoryExplicit(
                     self.get_register_stack_offset(destination),
                     HardwareRegisterExplicit(register_call_arguments::RETURN_VALUE)
                 ));
             }
+            InstructionMIRData::IsInstance(class_type, destination, class_reference) => {
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(0), self.get_register_stack_offset(class_reference)));
+                self.instructions.push(InstructionIR::IsInstance(class_type.clone(), HardwareRegister::Int(1), HardwareRegister::Int(0)));
+                self.instructions.push(InstructionIR::StoreFrameMemory(self.get_register_stack_offset(destination), HardwareRegister::Int(1)));
+            }
+            InstructionMIRData::CastClass(class_type, destination, class_reference) => {
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(0), self.get_register_stack_offset(class_reference)));
+                self.instructions.push(InstructionIR::CastClass(class_type.clone(), HardwareRegister::Int(0)));
+                self.instructions.push(InstructionIR::StoreFrameMemory(self.get_register_stack_offset(destination), HardwareRegister::Int(0)));
+            }
             InstructionMIRData::LoadField(class_type, field_name, destination, class_reference) => {
                 let class = self.type_storage.get(class_type).unwrap().class.as_ref().unwrap();
                 let field = class.get_field(field_name).unwrap();
@@ -297,46 +719,139 @@ impl<'a> InstructionIRCompiler<'a> {
                     self.instructions.push(InstructionIR::NullReferenceCheck(HardwareRegister::Int(0)));
                 }
 
-                let return_value = match field.type_id() {
-                    TypeId::Float32 => HardwareRegister::Float(1),
-                    _ => HardwareRegister::Int(1)
-                };
-
-                self.instructions.push(InstructionIR::LoadField(
-                    field.type_id().clone(),
-                    field.offset(),
-                    return_value,
-                    HardwareRegister::Int(0)
-                ));
-
-                self.instructions.push(InstructionIR::StoreFrameMemory(
-                    self.get_register_stack_offset(destination),
-                    return_value
-                ));
+                if field.type_id().is_struct() {
+                    self.instructions.push(InstructionIR::LoadStructField(
+                        field.type_id().clone(),
+                        field.offset(),
+                        self.get_register_stack_offset(destination),
+                        HardwareRegister::Int(0)
+                    ));
+                } else {
+                    let return_value = match field.type_id() {
+                        TypeId::Float32 | TypeId::Float64 => HardwareRegister::Float(1),
+                        _ => HardwareRegister::Int(1)
+                    };
+
+                    self.instructions.push(InstructionIR::LoadField(
+                        field.type_id().clone(),
+                        field.offset(),
+                        return_value,
+                        HardwareRegister::Int(0)
+                    ));
+
+                    self.instructions.push(InstructionIR::StoreFrameMemory(
+                        self.get_register_stack_offset(destination),
+                        return_value
+                    ));
+                }
             }
             InstructionMIRData::StoreField(class_type, field_name, class_reference, value) => {
                 let class = self.type_storage.get(class_type).unwrap().class.as_ref().unwrap();
                 let field = class.get_field(field_name).unwrap();
 
                 let value_register = match field.type_id() {
-                    TypeId::Float32 => HardwareRegister::Float(1),
+                    TypeId::Float32 | TypeId::Float64 => HardwareRegister::Float(1),
                     _ => HardwareRegister::Int(1)
                 };
 
-                self.instructions.push(InstructionIR::LoadFrameMemory(value_register, self.get_register_stack_offset(value)));
+                if !field.type_id().is_struct() {
+                    self.instructions.push(InstructionIR::LoadFrameMemory(value_register, self.get_register_stack_offset(value)));
+                }
+
                 self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(0), self.get_register_stack_offset(class_reference)));
 
                 if self.can_be_null(instruction_index, class_reference) {
                     self.instructions.push(InstructionIR::NullReferenceCheck(HardwareRegister::Int(0)));
                 }
 
-                self.instructions.push(InstructionIR::StoreField(
-                    field.type_id().clone(),
-                    field.offset(),
-                    HardwareRegister::Int(0),
-                    value_register,
+                if field.type_id().is_struct() {
+                    self.instructions.push(InstructionIR::StoreStructField(
+                        field.type_id().clone(),
+                        field.offset(),
+                        HardwareRegister::Int(0),
+                        self.get_register_stack_offset(value)
+                    ));
+                } else {
+                    self.instructions.push(InstructionIR::StoreField(
+                        field.type_id().clone(),
+                        field.offset(),
+                        HardwareRegister::Int(0),
+                        value_register,
+                    ));
+                }
+            }
+            InstructionMIRData::LoadStaticField(class_type, field_name, destination) if destination.value_type.is_struct() => {
+                self.instructions.push(InstructionIR::LoadStaticStructField(class_type.clone(), field_name.clone(), self.get_register_stack_offset(destination)));
+            }
+            InstructionMIRData::LoadStaticField(class_type, field_name, destination) => {
+                let return_value = match &destination.value_type {
+                    TypeId::Float32 | TypeId::Float64 => HardwareRegister::Float(0),
+                    _ => HardwareRegister::Int(0)
+                };
+
+                self.instructions.push(InstructionIR::LoadStaticField(class_type.clone(), field_name.clone(), return_value));
+                self.instructions.push(InstructionIR::StoreFrameMemory(self.get_register_stack_offset(destination), return_value));
+            }
+            InstructionMIRData::StoreStaticField(class_type, field_name, value) if value.value_type.is_struct() => {
+                self.instructions.push(InstructionIR::StoreStaticStructField(class_type.clone(), field_name.clone(), self.get_register_stack_offset(value)));
+            }
+            InstructionMIRData::StoreStaticField(class_type, field_name, value) => {
+                let value_register = match &value.value_type {
+                    TypeId::Float32 | TypeId::Float64 => HardwareRegister::Float(0),
+                    _ => HardwareRegister::Int(0)
+                };
+
+                self.instructions.push(InstructionIR::LoadFrameMemory(value_register, self.get_register_stack_offset(value)));
+                self.instructions.push(InstructionIR::StoreStaticField(class_type.clone(), field_name.clone(), value_register));
+            }
+            InstructionMIRData::NewStruct(struct_type, destination) => {
+                self.instructions.push(InstructionIR::ZeroFrameMemory(
+                    self.get_register_stack_offset(destination),
+                    self.type_storage.size_of(struct_type)
                 ));
             }
+            InstructionMIRData::LoadStructField(struct_type, field_name, destination, struct_value) => {
+                let class = self.type_storage.get(struct_type).unwrap().class.as_ref().unwrap();
+                let field = class.get_field(field_name).unwrap();
+                let field_offset = self.get_register_stack_offset(struct_value) + field.offset() as i32;
+
+                if field.type_id().is_struct() {
+                    self.instructions.push(InstructionIR::CopyFrameMemory(self.get_register_stack_offset(destination), field_offset, field.size()));
+                } else {
+                    let return_value = match field.type_id() {
+                        TypeId::Float32 | TypeId::Float64 => HardwareRegister::Float(0),
+                        _ => HardwareRegister::Int(0)
+                    };
+
+                    self.instructions.push(InstructionIR::LoadFrameField(field.type_id().clone(), return_value, field_offset));
+                    self.instructions.push(InstructionIR::StoreFrameMemory(self.get_register_stack_offset(destination), return_value));
+                }
+            }
+            InstructionMIRData::StoreStructField(struct_type, field_name, destination, struct_value, value) => {
+                let class = self.type_storage.get(struct_type).unwrap().class.as_ref().unwrap();
+                let field = class.get_field(field_name).unwrap();
+                let field_offset = self.get_register_stack_offset(destination) + field.offset() as i32;
+
+                if destination != struct_value {
+                    self.instructions.push(InstructionIR::CopyFrameMemory(
+                        self.get_register_stack_offset(destination),
+                        self.get_register_stack_offset(struct_value),
+                        class.memory_size()
+                    ));
+                }
+
+                if field.type_id().is_struct() {
+                    self.instructions.push(InstructionIR::CopyFrameMemory(field_offset, self.get_register_stack_offset(value), field.size()));
+                } else {
+                    let value_register = match field.type_id() {
+                        TypeId::Float32 | TypeId::Float64 => HardwareRegister::Float(0),
+                        _ => HardwareRegister::Int(0)
+                    };
+
+                    self.instructions.push(InstructionIR::LoadFrameMemory(value_register, self.get_register_stack_offset(value)));
+                    self.instructions.push(InstructionIR::StoreFrameField(field.type_id().clone(), field_offset, value_register));
+                }
+            }
             InstructionMIRData::CallInstance(signature, return_value, arguments) => {
                 let func_to_call = self.binder.get(signature).unwrap();
 
@@ -351,7 +866,17 @@ impl<'a> InstructionIRCompiler<'a> {
                     self.instructions.push(InstructionIR::NullReferenceCheck(HardwareRegister::Int(0)));
                 }
 
-                self.instructions.push(InstructionIR::Call(signature.clone(), arguments_source, 0));
+                if func_to_call.is_virtual() {
+                    let class_type = self.type_storage.get(func_to_call.class().as_ref().unwrap()).unwrap();
+                    let virtual_index = class_type.virtual_function_index(signature).unwrap();
+                    if func_to_call.virtual_modifier() == &Some(VirtualModifier::Interface) {
+                        self.instructions.push(InstructionIR::CallInterface(signature.clone(), virtual_index, arguments_source, 0));
+                    } else {
+                        self.instructions.push(InstructionIR::CallVirtual(signature.clone(), virtual_index, arguments_source, 0));
+                    }
+                } else {
+                    self.instructions.push(InstructionIR::Call(signature.clone(), arguments_source, 0, instruction_index));
+                }
 
                 if let Some(return_value) = return_value {
                     CallingConventions::new().handle_return_value(
@@ -385,6 +910,18 @@ impl<'a> InstructionIRCompiler<'a> {
                         self.instructions.push(InstructionIR::Compare(TypeId::Float32, HardwareRegister::Float(0), HardwareRegister::Float(1)));
                         false
                     }
+                    TypeId::Float64 => {
+                        self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Float(0), self.get_register_stack_offset(operand1)));
+                        self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Float(1), self.get_register_stack_offset(operand2)));
+                        self.instructions.push(InstructionIR::Compare(TypeId::Float64, HardwareRegister::Float(0), HardwareRegister::Float(1)));
+                        false
+                    }
+                    TypeId::Int64 => {
+                        self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(0), self.get_register_stack_offset(operand1)));
+                        self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(1), self.get_register_stack_offset(operand2)));
+                        self.instructions.push(InstructionIR::Compare(TypeId::Int64, HardwareRegister::Int(0), HardwareRegister::Int(1)));
+                        true
+                    }
                     _ => {
                         self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(0), self.get_register_stack_offset(operand1)));
                         self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(1), self.get_register_stack_offset(operand2)));
@@ -410,6 +947,18 @@ impl<'a> InstructionIRCompiler<'a> {
                         self.instructions.push(InstructionIR::Compare(TypeId::Float32, HardwareRegister::Float(0), HardwareRegister::Float(1)));
                         false
                     }
+                    TypeId::Float64 => {
+                        self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Float(0), self.get_register_stack_offset(operand1)));
+                        self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Float(1), self.get_register_stack_offset(operand2)));
+                        self.instructions.push(InstructionIR::Compare(TypeId::Float64, HardwareRegister::Float(0), HardwareRegister::Float(1)));
+                        false
+                    }
+                    TypeId::Int64 => {
+                        self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(0), self.get_register_stack_offset(operand1)));
+                        self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(1), self.get_register_stack_offset(operand2)));
+                        self.instructions.push(InstructionIR::Compare(TypeId::Int64, HardwareRegister::Int(0), HardwareRegister::Int(1)));
+                        true
+                    }
                     _ => {
                         self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(0), self.get_register_stack_offset(operand1)));
                         self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(1), self.get_register_stack_offset(operand2)));
@@ -426,6 +975,33 @@ impl<'a> InstructionIRCompiler<'a> {
 
                 self.instructions.push(InstructionIR::StoreFrameMemory(self.get_register_stack_offset(destination), HardwareRegister::Int(2)));
             }
+            InstructionMIRData::ExceptionLanding(destination) => {
+                self.instructions.push(InstructionIR::StoreFrameMemoryExplicit(
+                    self.get_register_stack_offset(destination),
+                    HardwareRegisterExplicit(Register::RAX)
+                ));
+            }
+            InstructionMIRData::Throw(exception) => {
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(0), self.get_register_stack_offset(exception)));
+                self.instructions.push(InstructionIR::Throw(HardwareRegister::Int(0)));
+            }
+            InstructionMIRData::EndFinally(pending_exception) => {
+                self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(0), self.get_register_stack_offset(pending_exception)));
+                self.instructions.push(InstructionIR::ThrowIfNotNull(HardwareRegister::Int(0)));
+            }
+        }
+    }
+
+    /// Pushes the operands on the stack, where the array operations expect them. References are checked for null.
+    fn push_operands(&mut self, instruction_index: usize, operands: &[&RegisterMIR]) {
+        for operand in operands {
+            self.instructions.push(InstructionIR::LoadFrameMemory(HardwareRegister::Int(0), self.get_register_stack_offset(operand)));
+
+            if operand.value_type.is_reference() && self.can_be_null(instruction_index, operand) {
+                self.instructions.push(InstructionIR::NullReferenceCheck(HardwareRegister::Int(0)));
+            }
+
+            self.instructions.push(InstructionIR::Push(HardwareRegister::Int(0)));
         }
     }
 
@@ -435,7 +1011,7 @@ impl<'a> InstructionIRCompiler<'a> {
     }
 
     fn get_register_stack_offset(&self, register: &RegisterMIR) -> i32 {
-        stack_layout::virtual_register_stack_offset(self.function, register.number)
+        stack_layout::virtual_register_stack_offset(self.compilation_result, &VirtualRegister::from(register))
     }
 
     pub fn done(self) -> Vec<InstructionIR> {
@@ -1,14 +1,14 @@
 use std::collections::BTreeSet;
 
 use crate::mir::RegisterMIR;
-use crate::compiler::ir::{InstructionIR, HardwareRegister, HardwareRegisterExplicit, Variable};
+use crate::compiler::ir::{InstructionIR, HardwareRegister, Variable};
 use crate::optimization::register_allocation::{AllocatedRegister, RegisterAllocation};
 use crate::analysis::VirtualRegister;
 use crate::compiler::stack_layout;
-use crate::model::function::Function;
+use crate::mir::compiler::MIRCompilationResult;
 
 pub trait AllocatedCompilerHelpers {
-    fn function(&self) -> &Function;
+    fn compilation_result(&self) -> &MIRCompilationResult;
     fn register_allocation(&self) -> &RegisterAllocation;
     fn instructions(&mut self) -> &mut Vec<InstructionIR>;
 
@@ -16,26 +16,26 @@ pub trait AllocatedCompilerHelpers {
         let alive_registers = self.register_allocation().alive_registers_at(instruction_index);
         for (virtual_register, register) in &alive_registers {
             let destination_offset = self.get_virtual_register_stack_offset(virtual_register);
-            self.instructions().push(InstructionIR::StoreFrameMemory(destination_offset, register.clone()));
+            self.instructions().push(InstructionIR::StoreFrameMemory(destination_offset, *register));
         }
 
         alive_registers
     }
 
     fn pop_alive_registers(&mut self,
-                           alive_registers: &Vec<(VirtualRegister, HardwareRegister)>,
+                           alive_registers: &[(VirtualRegister, HardwareRegister)],
                            destination_register: Option<HardwareRegister>) {
         for (virtual_register, register) in alive_registers.iter().rev() {
             if let Some(destination_register) = destination_register.as_ref() {
                 if destination_register != register {
-                    let source_offset = self.get_virtual_register_stack_offset(&virtual_register);
-                    self.instructions().push(InstructionIR::LoadFrameMemory(register.clone(), source_offset));
+                    let source_offset = self.get_virtual_register_stack_offset(virtual_register);
+                    self.instructions().push(InstructionIR::LoadFrameMemory(*register, source_offset));
                 } else {
                     // The assign register will have the return value as value, so don't pop to a register.
                 }
             } else {
-                let source_offset = self.get_virtual_register_stack_offset(&virtual_register);
-                self.instructions().push(InstructionIR::LoadFrameMemory(register.clone(), source_offset));
+                let source_offset = self.get_virtual_register_stack_offset(virtual_register);
+                self.instructions().push(InstructionIR::LoadFrameMemory(*register, source_offset));
             }
         }
     }
@@ -184,17 +184,17 @@ pub trait AllocatedCompilerHelpers {
 
         match (operand1_allocation, operand2_allocation) {
             (Some(operand1_register), Some(operand2_register)) => {
-                reg_reg(&mut self.instructions(), operand1_register, operand2_register);
+                reg_reg(self.instructions(), operand1_register, operand2_register);
             }
             (Some(operand1_register), None) => {
-                reg_mem(&mut self.instructions(), operand1_register, operand2_offset);
+                reg_mem(self.instructions(), operand1_register, operand2_offset);
             }
             (None, Some(operand2_register)) => {
-                mem_reg(&mut self.instructions(), operand1_offset, operand2_register);
+                mem_reg(self.instructions(), operand1_offset, operand2_register);
             }
             (None, None) => {
                 self.instructions().push(InstructionIR::LoadFrameMemory(HardwareRegister::IntSpill, operand2_offset));
-                mem_reg(&mut self.instructions(), operand1_offset, HardwareRegister::IntSpill);
+                mem_reg(self.instructions(), operand1_offset, HardwareRegister::IntSpill);
             }
         }
     }
@@ -246,19 +246,19 @@ pub trait AllocatedCompilerHelpers {
 
         match (operand1_allocation, operand2_allocation) {
             (Some(operand1_register), Some(operand2_register)) => {
-                reg_reg(&mut self.instructions(), operand1_register, operand2_register);
+                reg_reg(self.instructions(), operand1_register, operand2_register);
             }
             (Some(operand1_register), None) => {
-                reg_mem(&mut self.instructions(), operand1_register, operand2_offset);
+                reg_mem(self.instructions(), operand1_register, operand2_offset);
             }
             (None, Some(operand2_register)) => {
                 self.instructions().push(InstructionIR::LoadFrameMemory(HardwareRegister::IntSpill, operand1_offset));
-                reg_reg(&mut self.instructions(), HardwareRegister::IntSpill, operand2_register);
+                reg_reg(self.instructions(), HardwareRegister::IntSpill, operand2_register);
                 self.instructions().push(InstructionIR::StoreFrameMemory(operand1_offset, HardwareRegister::IntSpill));
             }
             (None, None) => {
                 self.instructions().push(InstructionIR::LoadFrameMemory(HardwareRegister::IntSpill, operand1_offset));
-                reg_mem(&mut self.instructions(), HardwareRegister::IntSpill, operand2_offset);
+                reg_mem(self.instructions(), HardwareRegister::IntSpill, operand2_offset);
                 self.instructions().push(InstructionIR::StoreFrameMemory(operand1_offset, HardwareRegister::IntSpill));
             }
         }
@@ -290,14 +290,14 @@ pub trait AllocatedCompilerHelpers {
             let destination_allocation = self.register_allocation().get_register(destination).clone();
             let destination_offset = self.get_register_stack_offset(destination);
             handle(
-                &mut self.instructions(),
+                self.instructions(),
                 (destination_allocation.hardware_register(), destination_offset),
                 operand2
             );
         } else {
             self.move_to_hardware_register(HardwareRegister::IntSpill, operand1);
             handle(
-                &mut self.instructions(),
+                self.instructions(),
                 (Some(HardwareRegister::IntSpill), 0),
                 operand2
             );
@@ -387,11 +387,11 @@ pub trait AllocatedCompilerHelpers {
     }
 
     fn get_virtual_register_stack_offset(&self, register: &VirtualRegister) -> i32 {
-        stack_layout::virtual_register_stack_offset(self.function(), register.number)
+        stack_layout::virtual_register_stack_offset(self.compilation_result(), register)
     }
 
     fn get_register_stack_offset(&self, register: &RegisterMIR) -> i32 {
-        stack_layout::virtual_register_stack_offset(self.function(), register.number)
+        stack_layout::virtual_register_stack_offset(self.compilation_result(), &VirtualRegister::from(register))
     }
 }
 
@@ -435,19 +435,19 @@ impl TempRegisters {
 
     pub fn get(&mut self,
                register_allocation: &RegisterAllocation,
-               function: &Function,
+               compilation_result: &MIRCompilationResult,
                instructions: &mut Vec<InstructionIR>,
                register: &RegisterMIR) -> HardwareRegister {
-        self.get_with_status(register_allocation, function, instructions, register).0
+        self.get_with_status(register_allocation, compilation_result, instructions, register).0
     }
 
     pub fn get_with_status(&mut self,
                            register_allocation: &RegisterAllocation,
-                           function: &Function,
+                           compilation_result: &MIRCompilationResult,
                            instructions: &mut Vec<InstructionIR>,
                            register: &RegisterMIR) -> (HardwareRegister, bool, bool) {
         let (is_stack, hardware_register) = self.get_raw(register_allocation, register);
-        let alive = self.push_if_alive(function, instructions, register, &hardware_register, is_stack);
+        let alive = self.push_if_alive(compilation_result, instructions, register, &hardware_register, is_stack);
 
         if alive {
             self.saved_registers.push(hardware_register);
@@ -458,7 +458,7 @@ impl TempRegisters {
 
     pub fn get_and_try_remove<const N: usize>(&mut self,
                                               register_allocation: &RegisterAllocation,
-                                              function: &Function,
+                                              compilation_result: &MIRCompilationResult,
                                               instructions: &mut Vec<InstructionIR>,
                                               registers: [&RegisterMIR; N]) -> [HardwareRegister; N] {
         for register in &registers {
@@ -467,7 +467,7 @@ impl TempRegisters {
 
         let mut results: [HardwareRegister; N] = [HardwareRegister::Int(0); N];
         for (index, register) in registers.iter().enumerate() {
-            results[index] = self.get(register_allocation, function, instructions, register);
+            results[index] = self.get(register_allocation, compilation_result, instructions, register);
         }
 
         results
@@ -477,14 +477,14 @@ impl TempRegisters {
                register_allocation: &RegisterAllocation,
                register: &RegisterMIR) -> (bool, HardwareRegister) {
         match register_allocation.get_register(register).hardware_register() {
-            Some(register) => (false, register.clone()),
+            Some(register) => (false, register),
             None if register.value_type.is_float() => {
-                let register = self.float_registers.iter().rev().next().unwrap().clone();
+                let register = *self.float_registers.iter().next_back().unwrap();
                 self.float_registers.remove(&register);
                 (true, register)
             }
             None => {
-                let register = self.int_registers.iter().rev().next().unwrap().clone();
+                let register = *self.int_registers.iter().next_back().unwrap();
                 self.int_registers.remove(&register);
                 (true, register)
             }
@@ -492,21 +492,21 @@ impl TempRegisters {
     }
 
     fn push_if_alive(&mut self,
-                     function: &Function,
+                     compilation_result: &MIRCompilationResult,
                      instructions: &mut Vec<InstructionIR>,
                      register_ir: &RegisterMIR,
                      register: &HardwareRegister,
                      is_stack: bool) -> bool {
-        let alive = if is_stack && self.alive_registers.contains(&register) {
-            instructions.push(InstructionIR::Push(register.clone()));
+        let alive = if is_stack && self.alive_registers.contains(register) {
+            instructions.push(InstructionIR::Push(*register));
             true
         } else {
             false
         };
 
         if is_stack {
-            let source_offset = stack_layout::virtual_register_stack_offset(function, register_ir.number);
-            instructions.push(InstructionIR::LoadFrameMemory(register.clone(), source_offset));
+            let source_offset = stack_layout::virtual_register_stack_offset(compilation_result, &VirtualRegister::from(register_ir));
+            instructions.push(InstructionIR::LoadFrameMemory(*register, source_offset));
         }
 
         alive
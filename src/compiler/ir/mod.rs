@@ -129,18 +129,28 @@ pub enum Condition {
     GreaterThanOrEqual
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RoundingMode {
+    Floor,
+    Ceil
+}
+
 #[derive(Debug)]
 pub enum InstructionIR {
     Marker(usize, usize),
     InitializeFunction,
+    StackOverflowCheck,
     LoadZeroToRegister(HardwareRegister),
+    #[allow(dead_code)]
     AddToStackPointer(i32),
     SubFromStackPointer(i32),
 
     Push(HardwareRegister),
     Pop(HardwareRegister),
     PushExplicit(HardwareRegisterExplicit),
+    #[allow(dead_code)]
     PopExplicit(HardwareRegisterExplicit),
+    #[allow(dead_code)]
     PopEmpty,
     PushInt32(i32),
 
@@ -148,6 +158,10 @@ pub enum InstructionIR {
     StoreFrameMemory(i32, HardwareRegister),
     LoadFrameMemoryExplicit(HardwareRegisterExplicit, i32),
     StoreFrameMemoryExplicit(i32, HardwareRegisterExplicit),
+    CopyFrameMemory(i32, i32, usize),
+    ZeroFrameMemory(i32, usize),
+    LoadFrameField(TypeId, HardwareRegister, i32),
+    StoreFrameField(TypeId, i32, HardwareRegister),
 
     Move(HardwareRegister, HardwareRegister),
     MoveExplicit(HardwareRegisterExplicit, HardwareRegisterExplicit),
@@ -156,6 +170,8 @@ pub enum InstructionIR {
 
     MoveInt32ToFrameMemory(i32, i32),
     MoveInt32ToRegister(HardwareRegister, i32),
+    MoveInt64ToRegister(HardwareRegister, i64),
+    LoadString(HardwareRegister, String),
 
     AddInt32(HardwareRegister, HardwareRegister),
     AddInt32FromFrameMemory(HardwareRegister, i32),
@@ -173,8 +189,14 @@ pub enum InstructionIR {
     MultiplyInt32FromFrameMemory(HardwareRegister, i32),
 
     DivideInt32(HardwareRegister, HardwareRegister),
+    #[allow(dead_code)]
     DivideInt32FromFrameMemory(HardwareRegister, i32),
 
+    RemainderInt32(HardwareRegister, HardwareRegister),
+
+    NegateInt32(HardwareRegister),
+    NegateInt32FrameMemory(i32),
+
     AndInt32(HardwareRegister, HardwareRegister),
     AndInt32FromFrameMemory(HardwareRegister, i32),
     AndInt32ToFrameMemory(i32, HardwareRegister),
@@ -187,9 +209,29 @@ pub enum InstructionIR {
     OrInt32Constant(HardwareRegister, i32),
     OrInt32ConstantToFrameMemory(i32, i32),
 
+    XorInt32(HardwareRegister, HardwareRegister),
+    XorInt32FromFrameMemory(HardwareRegister, i32),
+    XorInt32ToFrameMemory(i32, HardwareRegister),
+
     NotInt32(HardwareRegister),
     NotInt32FrameMemory(i32),
 
+    BitwiseNotInt32(HardwareRegister),
+    BitwiseNotInt32FrameMemory(i32),
+
+    ShiftLeftInt32(HardwareRegister, HardwareRegister),
+    ShiftRightInt32(HardwareRegister, HardwareRegister),
+
+    ConvertInt32ToFloat32(HardwareRegister, HardwareRegister),
+    ConvertFloat32ToInt32(HardwareRegister, HardwareRegister),
+    ConvertBoolToInt32(HardwareRegister, HardwareRegister),
+    ConvertInt32ToInt64(HardwareRegister, HardwareRegister),
+    ConvertInt64ToInt32(HardwareRegister, HardwareRegister),
+    ConvertFloat32ToFloat64(HardwareRegister, HardwareRegister),
+    ConvertFloat64ToFloat32(HardwareRegister, HardwareRegister),
+    ConvertInt64ToFloat64(HardwareRegister, HardwareRegister),
+    ConvertFloat64ToInt64(HardwareRegister, HardwareRegister),
+
     AddFloat32(HardwareRegister, HardwareRegister),
     AddFloat32FromFrameMemory(HardwareRegister, i32),
 
@@ -201,21 +243,102 @@ pub enum InstructionIR {
 
     DivideFloat32(HardwareRegister, HardwareRegister),
     DivideFloat32FromFrameMemory(HardwareRegister, i32),
+    MinFloat32(HardwareRegister, HardwareRegister),
+    MinFloat32FromFrameMemory(HardwareRegister, i32),
+    MaxFloat32(HardwareRegister, HardwareRegister),
+    MaxFloat32FromFrameMemory(HardwareRegister, i32),
+    SquareRootFloat32(HardwareRegister, HardwareRegister),
+    RoundFloat32(HardwareRegister, HardwareRegister, RoundingMode),
+
+    AddInt64(HardwareRegister, HardwareRegister),
+    AddInt64FromFrameMemory(HardwareRegister, i32),
+    AddInt64ToFrameMemory(i32, HardwareRegister),
+
+    SubInt64(HardwareRegister, HardwareRegister),
+    SubInt64FromFrameMemory(HardwareRegister, i32),
+    SubInt64ToFrameMemory(i32, HardwareRegister),
+
+    MultiplyInt64(HardwareRegister, HardwareRegister),
+    MultiplyInt64FromFrameMemory(HardwareRegister, i32),
+
+    DivideInt64(HardwareRegister, HardwareRegister),
+    #[allow(dead_code)]
+    DivideInt64FromFrameMemory(HardwareRegister, i32),
 
-    Call(FunctionSignature, Vec<Variable>, usize),
+    RemainderInt64(HardwareRegister, HardwareRegister),
+
+    NegateInt64(HardwareRegister),
+    NegateInt64FrameMemory(i32),
+
+    AndInt64(HardwareRegister, HardwareRegister),
+    AndInt64FromFrameMemory(HardwareRegister, i32),
+    AndInt64ToFrameMemory(i32, HardwareRegister),
+
+    OrInt64(HardwareRegister, HardwareRegister),
+    OrInt64FromFrameMemory(HardwareRegister, i32),
+    OrInt64ToFrameMemory(i32, HardwareRegister),
+
+    XorInt64(HardwareRegister, HardwareRegister),
+    XorInt64FromFrameMemory(HardwareRegister, i32),
+    XorInt64ToFrameMemory(i32, HardwareRegister),
+
+    BitwiseNotInt64(HardwareRegister),
+    BitwiseNotInt64FrameMemory(i32),
+
+    ShiftLeftInt64(HardwareRegister, HardwareRegister),
+    ShiftRightInt64(HardwareRegister, HardwareRegister),
+
+    AddFloat64(HardwareRegister, HardwareRegister),
+    AddFloat64FromFrameMemory(HardwareRegister, i32),
+
+    SubFloat64(HardwareRegister, HardwareRegister),
+    SubFloat64FromFrameMemory(HardwareRegister, i32),
+
+    MultiplyFloat64(HardwareRegister, HardwareRegister),
+    MultiplyFloat64FromFrameMemory(HardwareRegister, i32),
+
+    DivideFloat64(HardwareRegister, HardwareRegister),
+    DivideFloat64FromFrameMemory(HardwareRegister, i32),
+
+    Call(FunctionSignature, Vec<Variable>, usize, usize),
+    CallVirtual(FunctionSignature, usize, Vec<Variable>, usize),
+    CallInterface(FunctionSignature, usize, Vec<Variable>, usize),
+    LoadFunction(FunctionSignature, HardwareRegister),
+    CallIndirect(TypeId, Variable, Vec<Variable>, usize, usize),
     Return,
 
     NullReferenceCheck(HardwareRegister),
+    Throw(HardwareRegister),
+    ThrowIfNotNull(HardwareRegister),
     ArrayBoundsCheck(HardwareRegister, HardwareRegister),
 
-    NewArray(TypeId, HardwareRegister, usize),
+    NewArray(TypeId, HardwareRegister, usize, usize),
     LoadElement(TypeId, HardwareRegister, HardwareRegister, HardwareRegister),
     StoreElement(TypeId, HardwareRegister, HardwareRegister, HardwareRegister),
     LoadArrayLength(HardwareRegister, HardwareRegister),
+    ArrayCopy(TypeId),
+    ArrayFill(TypeId),
+    ArrayEquals(TypeId, HardwareRegister),
+
+    Array2DBoundsCheck(HardwareRegister, HardwareRegister, HardwareRegister),
+    NewArray2D(TypeId, HardwareRegister, HardwareRegister, usize, usize),
+    LoadElement2D(TypeId, HardwareRegister, HardwareRegister, HardwareRegister, HardwareRegister),
+    StoreElement2D(TypeId, HardwareRegister, HardwareRegister, HardwareRegister, HardwareRegister),
+    LoadArrayDimension(u32, HardwareRegister, HardwareRegister),
 
-    NewObject(TypeId),
+    NewObject(TypeId, usize),
     LoadField(TypeId, usize, HardwareRegister, HardwareRegister),
     StoreField(TypeId, usize, HardwareRegister, HardwareRegister),
+    LoadStaticField(TypeId, String, HardwareRegister),
+    StoreStaticField(TypeId, String, HardwareRegister),
+    LoadStructElement(TypeId, i32, HardwareRegister, HardwareRegister),
+    StoreStructElement(TypeId, HardwareRegister, HardwareRegister, i32),
+    LoadStructField(TypeId, usize, i32, HardwareRegister),
+    StoreStructField(TypeId, usize, HardwareRegister, i32),
+    LoadStaticStructField(TypeId, String, i32),
+    StoreStaticStructField(TypeId, String, i32),
+    IsInstance(TypeId, HardwareRegister, HardwareRegister),
+    CastClass(TypeId, HardwareRegister),
 
     Compare(TypeId, HardwareRegister, HardwareRegister),
     CompareFromFrameMemory(TypeId, HardwareRegister, i32),
@@ -7,17 +7,21 @@ use crate::compiler::{FunctionCallType, FunctionCompilationData};
 use crate::compiler::allocator::ExecutableMemoryAllocator;
 use crate::compiler::code_generator::{CodeGenerator, CodeGeneratorResult};
 use crate::compiler::error_handling::ErrorHandling;
+use crate::compiler::invoker;
+use crate::compiler::invoker::InvokerFunction;
+use crate::compiler::native_stub;
 use crate::compiler::ir::allocated_compiler::AllocatedInstructionIRCompiler;
 use crate::compiler::ir::compiler::InstructionIRCompiler;
 use crate::compiler::ir::InstructionIR;
 use crate::mir;
-use crate::mir::branches;
 use crate::mir::compiler::{InstructionMIRCompiler, MIRCompilationResult};
 use crate::model::binder::Binder;
-use crate::model::function::{Function, FunctionDeclaration, FunctionSignature};
+use crate::model::function::{Function, FunctionAddress, FunctionDeclaration, FunctionSignature};
 use crate::model::typesystem::TypeStorage;
 use crate::optimization::{null_check_elision, peephole};
 use crate::optimization::register_allocation::RegisterAllocationSettings;
+use crate::runtime::memory::manager::MemoryManager;
+use crate::engine::execution::{StackLimit, RuntimeErrorManager};
 
 pub struct JitSettings {
     pub register_allocate: bool,
@@ -29,6 +33,9 @@ pub struct JitCompiler {
     memory_allocator: ExecutableMemoryAllocator,
     error_handling: ErrorHandling,
     functions_compilation_data: HashMap<FunctionSignature, FunctionCompilationData>,
+    unresolved_functions: Vec<FunctionSignature>,
+    #[allow(dead_code)]
+    invokers: HashMap<FunctionSignature, InvokerFunction>
 }
 
 impl JitCompiler {
@@ -41,12 +48,17 @@ impl JitCompiler {
             memory_allocator,
             error_handling,
             functions_compilation_data: HashMap::new(),
+            unresolved_functions: Vec::new(),
+            invokers: HashMap::new()
         }
     }
 
     pub fn compile_function(&mut self,
                             binder: &mut Binder,
                             type_storage: &mut TypeStorage,
+                            memory_manager: &mut MemoryManager,
+                            stack_limit: &StackLimit,
+                            runtime_error: &RuntimeErrorManager,
                             function: &mut Function) {
         if function.declaration().class().is_some() {
             println!("member {}", function.declaration());
@@ -61,12 +73,22 @@ impl JitCompiler {
         let generator_result = self.generate_code(
             binder,
             type_storage,
+            memory_manager,
+            stack_limit,
+            runtime_error,
             function,
             &mut compilation_data,
             &instructions_ir
         );
         compilation_data.instructions_offsets = generator_result.instructions_offsets;
 
+        for &(instruction_index, offset) in &compilation_data.instructions_offsets {
+            let instruction = &compilation_data.mir_compilation_result.instructions[instruction_index];
+            if let mir::InstructionMIRData::ExceptionLanding(_) = &instruction.data {
+                compilation_data.exception_handler_offsets.insert(instruction.index, offset);
+            }
+        }
+
         println!("}}");
         println!();
         let function_code_ptr = self.memory_allocator.allocate(generator_result.code_bytes.len());
@@ -79,27 +101,43 @@ impl JitCompiler {
         }
 
         self.functions_compilation_data.insert(function.declaration().signature(), compilation_data);
+        self.unresolved_functions.push(function.declaration().signature());
 
         binder.set_address(&function.declaration().signature(), function_code_ptr);
         function.declaration_mut().set_address(function_code_ptr);
     }
 
+    #[allow(dead_code)]
+    pub fn get_invoker(&mut self, function: &FunctionDeclaration) -> InvokerFunction {
+        let memory_allocator = &mut self.memory_allocator;
+        *self.invokers
+            .entry(function.signature())
+            .or_insert_with(|| invoker::generate_invoker(memory_allocator, function))
+    }
+
+    #[allow(dead_code)]
+    pub fn generate_native_stub(&mut self, slot_cell: *mut usize, slot: usize, trampoline: FunctionAddress) -> FunctionAddress {
+        native_stub::generate_native_stub(&mut self.memory_allocator, slot_cell, slot, trampoline)
+    }
+
     pub fn get_compilation_data(&self, signature: &FunctionSignature) -> Option<&FunctionCompilationData> {
         self.functions_compilation_data.get(signature)
     }
 
+    // Only functions compiled since the last resolve have anything to resolve
     pub fn resolve_calls_and_branches(&mut self, binder: &Binder) {
-        for (signature, compilation_data) in &mut self.functions_compilation_data {
+        for signature in std::mem::take(&mut self.unresolved_functions) {
+            let compilation_data = self.functions_compilation_data.get_mut(&signature).unwrap();
             if !compilation_data.unresolved_function_calls.is_empty() {
-                JitCompiler::resolve_calls(binder, binder.get(signature).unwrap(), compilation_data);
+                JitCompiler::resolve_calls(binder, binder.get(&signature).unwrap(), compilation_data);
             }
 
             if !compilation_data.unresolved_branches.is_empty() {
-                JitCompiler::resolve_branches(binder.get(signature).unwrap(), compilation_data);
+                JitCompiler::resolve_branches(binder.get(&signature).unwrap(), compilation_data);
             }
 
             if !compilation_data.unresolved_native_branches.is_empty() {
-                JitCompiler::resolve_native_branches(binder.get(signature).unwrap(), compilation_data);
+                JitCompiler::resolve_native_branches(binder.get(&signature).unwrap(), compilation_data);
             }
         }
     }
@@ -117,11 +155,15 @@ impl JitCompiler {
 
                     unsafe {
                         let function_code_ptr = function.address().unwrap().add(unresolved_function_call.call_offset + 1) as *mut i32;
-                        *function_code_ptr = target;
+                        function_code_ptr.write_unaligned(target);
                     }
                 }
                 FunctionCallType::Absolute => {
-                    unimplemented!();
+                    // The address is the immediate of a 64-bits move, which follows the prefix and opcode
+                    unsafe {
+                        let address_ptr = function.address().unwrap().add(unresolved_function_call.call_offset + 2) as *mut u64;
+                        address_ptr.write_unaligned(function_to_call.address().unwrap() as u64);
+                    }
                 }
             }
         }
@@ -138,7 +180,7 @@ impl JitCompiler {
 
             unsafe {
                 let code_ptr = function.address().unwrap().add(source_offset as usize) as *mut i32;
-                *code_ptr = target;
+                code_ptr.write_unaligned(target);
             }
         }
 
@@ -147,13 +189,15 @@ impl JitCompiler {
 
     fn resolve_native_branches(function: &FunctionDeclaration, compilation_data: &mut FunctionCompilationData) {
         let function_code_ptr = function.address().unwrap();
+        // Native branches are calls to error handlers
+        let call_instruction_size = 5;
         for (&source, &target) in &compilation_data.unresolved_native_branches {
-            let native_target = (target as isize - (function_code_ptr as u64 + source as u64) as isize - 6) as i32;
-            let source_offset = source + 6 - std::mem::size_of::<i32>();
+            let native_target = (target as isize - (function_code_ptr as u64 + source as u64) as isize - call_instruction_size) as i32;
+            let source_offset = source + call_instruction_size as usize - std::mem::size_of::<i32>();
 
             unsafe {
                 let code_ptr = function_code_ptr.add(source_offset as usize) as *mut i32;
-                *code_ptr = native_target;
+                code_ptr.write_unaligned(native_target);
             }
         }
 
@@ -164,17 +208,18 @@ impl JitCompiler {
                   binder: &Binder,
                   type_storage: &TypeStorage,
                   function: &Function) -> (MIRCompilationResult, Vec<InstructionIR>) {
-        let mut mir_compiler = InstructionMIRCompiler::new(&type_storage, &binder, &function);
+        let mut mir_compiler = InstructionMIRCompiler::new(type_storage, binder, function);
         mir_compiler.compile(function.instructions());
         let mut compilation_result = mir_compiler.done();
 
         let optimization_result = self.optimize_ir(function, &mut compilation_result);
 
-        let instructions_ir = if self.settings.register_allocate {
+        // The exception handlers expects all values to live in the stack frame
+        let instructions_ir = if self.settings.register_allocate && function.protected_regions().is_empty() {
             let mut ir_compiler = AllocatedInstructionIRCompiler::new(
-                &binder,
-                &type_storage,
-                &function,
+                binder,
+                type_storage,
+                function,
                 &compilation_result,
                 &optimization_result,
                 &self.settings.register_allocation
@@ -184,9 +229,9 @@ impl JitCompiler {
             ir_compiler.done()
         } else {
             let mut ir_compiler = InstructionIRCompiler::new(
-                &binder,
-                &type_storage,
-                &function,
+                binder,
+                type_storage,
+                function,
                 &compilation_result,
                 &optimization_result
             );
@@ -200,6 +245,13 @@ impl JitCompiler {
 
     fn optimize_ir(&self, function: &Function,
                    compilation_result: &mut MIRCompilationResult) -> OptimizationResult {
+        // The control flow into exception handlers is not visible to the analyses
+        if !function.protected_regions().is_empty() {
+            return OptimizationResult {
+                instructions_register_null_status: compilation_result.instructions.iter().map(|_| HashMap::new()).collect()
+            };
+        }
+
         let mut basic_blocks = BasicBlock::create_blocks(&compilation_result.instructions);
         peephole::optimize(compilation_result, &mut basic_blocks, &Default::default());
 
@@ -212,20 +264,24 @@ impl JitCompiler {
         OptimizationResult {
             instructions_register_null_status: null_check_elision::compute(
                 function,
-                &compilation_result,
+                compilation_result,
                 &basic_blocks,
                 &control_flow_graph
             )
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn generate_code(&self,
                      binder: &Binder,
                      type_storage: &mut TypeStorage,
+                     memory_manager: &mut MemoryManager,
+                     stack_limit: &StackLimit,
+                     runtime_error: &RuntimeErrorManager,
                      function: &Function,
                      compilation_data: &mut FunctionCompilationData,
                      instructions_ir: &Vec<InstructionIR>) -> CodeGeneratorResult {
-        let mut code_generator = CodeGenerator::new(binder, &self.error_handling, type_storage);
+        let mut code_generator = CodeGenerator::new(binder, &self.error_handling, type_storage, memory_manager, stack_limit, runtime_error);
         code_generator.generate(function, compilation_data, instructions_ir);
         code_generator.done()
     }
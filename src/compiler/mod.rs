@@ -1,9 +1,9 @@
 use std::collections::HashMap;
 
-use ir::{BranchLabel, HardwareRegisterExplicit, InstructionIR};
+use ir::BranchLabel;
 
 use crate::mir::compiler::MIRCompilationResult;
-use crate::model::function::{Function, FunctionSignature};
+use crate::model::function::FunctionSignature;
 
 pub mod code_generator;
 pub mod allocator;
@@ -11,6 +11,8 @@ pub mod stack_layout;
 pub mod jit;
 pub mod calling_conventions;
 pub mod error_handling;
+pub mod invoker;
+pub mod native_stub;
 pub mod ir;
 
 pub struct FunctionCompilationData {
@@ -19,7 +21,8 @@ pub struct FunctionCompilationData {
     pub unresolved_branches: HashMap<usize, (BranchLabel, usize)>,
     pub unresolved_native_branches: HashMap<usize, usize>,
     pub mir_compilation_result: MIRCompilationResult,
-    pub instructions_offsets: Vec<(usize, usize)>
+    pub instructions_offsets: Vec<(usize, usize)>,
+    pub exception_handler_offsets: HashMap<usize, usize>
 }
 
 impl FunctionCompilationData {
@@ -30,7 +33,8 @@ impl FunctionCompilationData {
             branch_targets: HashMap::new(),
             unresolved_native_branches: HashMap::new(),
             mir_compilation_result,
-            instructions_offsets: Vec::new()
+            instructions_offsets: Vec::new(),
+            exception_handler_offsets: HashMap::new()
         }
     }
 }
@@ -0,0 +1,33 @@
+use iced_x86::{Encoder, Code, Register, MemoryOperand};
+use iced_x86::Instruction as X86Instruction;
+
+use crate::compiler::allocator::ExecutableMemoryAllocator;
+use crate::model::function::FunctionAddress;
+
+/// Generates a stub that stores the slot into the given cell and then jumps to the trampoline, leaving the arguments untouched.
+#[allow(dead_code)]
+pub fn generate_native_stub(memory_allocator: &mut ExecutableMemoryAllocator,
+                            slot_cell: *mut usize,
+                            slot: usize,
+                            trampoline: FunctionAddress) -> FunctionAddress {
+    let mut encoder = Encoder::new(64);
+    let mut encode_x86_instruction = |instruction: X86Instruction| {
+        encoder.encode(&instruction, 0).unwrap();
+    };
+
+    // Neither RAX nor R11 are used for passing arguments
+    encode_x86_instruction(X86Instruction::try_with_reg_u64(Code::Mov_r64_imm64, Register::R11, slot_cell as u64).unwrap());
+    encode_x86_instruction(X86Instruction::try_with_reg_u64(Code::Mov_r64_imm64, Register::RAX, slot as u64).unwrap());
+    encode_x86_instruction(X86Instruction::with_mem_reg(Code::Mov_rm64_r64, MemoryOperand::with_base(Register::R11), Register::RAX));
+
+    encode_x86_instruction(X86Instruction::try_with_reg_u64(Code::Mov_r64_imm64, Register::R11, trampoline as u64).unwrap());
+    encode_x86_instruction(X86Instruction::with_reg(Code::Jmp_rm64, Register::R11));
+
+    let stub_buffer = encoder.take_buffer();
+    let stub_ptr = memory_allocator.allocate(stub_buffer.len());
+    unsafe {
+        stub_ptr.copy_from(stub_buffer.as_ptr() as *const _, stub_buffer.len());
+    }
+
+    stub_ptr
+}
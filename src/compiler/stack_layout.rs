@@ -1,5 +1,9 @@
+use std::collections::{BTreeSet, HashMap};
+
+use crate::analysis::{VirtualRegister, VirtualRegisterType};
 use crate::model::function::Function;
 use crate::mir::compiler::MIRCompilationResult;
+use crate::mir::RegisterMIR;
 
 pub const STACK_ENTRY_SIZE: i32 = 8;
 pub const STACK_OFFSET: u32 = 1;
@@ -10,7 +14,7 @@ pub fn stack_size(function: &Function, compilation_result: &MIRCompilationResult
 }
 
 pub fn needed_stack_size(function: &Function, compilation_result: &MIRCompilationResult) -> i32 {
-    (1 + function.declaration().parameters().len() + compilation_result.num_virtual_registers) as i32 * STACK_ENTRY_SIZE
+    (1 + function.declaration().parameters().len() + compilation_result.register_slots.num_slots() as usize) as i32 * STACK_ENTRY_SIZE
 }
 
 pub fn align_size(size: i32) -> i32 {
@@ -21,6 +25,48 @@ pub fn argument_stack_offset(_function: &Function, index: u32) -> i32 {
     (STACK_VALUE_OFFSET + index) as i32 * -STACK_ENTRY_SIZE
 }
 
-pub fn virtual_register_stack_offset(function: &Function, number: u32) -> i32 {
-    -STACK_ENTRY_SIZE * (STACK_VALUE_OFFSET + function.declaration().parameters().len() as u32 + number) as i32
+pub fn virtual_register_stack_offset(compilation_result: &MIRCompilationResult, register: &VirtualRegister) -> i32 {
+    -STACK_ENTRY_SIZE * (STACK_VALUE_OFFSET + compilation_result.register_slots.slot(register)) as i32
+}
+
+/// The slots of the virtual registers, which are placed after the arguments.
+/// Int, float and struct registers can share number while being alive, so a number gets a slot for each type it is used as.
+#[derive(Debug, Default)]
+pub struct RegisterSlots {
+    slots: HashMap<VirtualRegister, u32>,
+    num_slots: u32
+}
+
+impl RegisterSlots {
+    pub fn new<'a>(function: &Function, registers: impl Iterator<Item=&'a RegisterMIR>) -> RegisterSlots {
+        let registers = registers.map(VirtualRegister::from).collect::<BTreeSet<_>>();
+        let struct_size = (function.struct_slot_size() as u32).div_ceil(STACK_ENTRY_SIZE as u32);
+        let first_slot = function.declaration().parameters().len() as u32;
+
+        let mut slots = HashMap::new();
+        let mut num_slots = 0;
+        for register in registers {
+            let size = match register.register_type {
+                VirtualRegisterType::Struct => struct_size,
+                _ => 1
+            };
+
+            // A struct spans multiple slots, where the slot is the lowest address
+            num_slots += size;
+            slots.insert(register, first_slot + num_slots - 1);
+        }
+
+        RegisterSlots {
+            slots,
+            num_slots
+        }
+    }
+
+    pub fn num_slots(&self) -> u32 {
+        self.num_slots
+    }
+
+    pub fn slot(&self, register: &VirtualRegister) -> u32 {
+        self.slots[register]
+    }
 }
@@ -1,18 +1,20 @@
 use crate::compiler::jit::{JitCompiler, JitSettings};
-use crate::model::function::{Function, FunctionSignature, FunctionAddress, FunctionStorage};
-use crate::model::verifier::{Verifier, VerifyError};
-use crate::model::typesystem::TypeStorage;
+use crate::model::function::{Function, FunctionSignature, FunctionAddress, FunctionStorage, FunctionDeclaration, VirtualModifier};
+use crate::compiler::invoker::InvokerFunction;
+use crate::model::verifier::{Verifier, VerifyError, VerifyErrorMessage, VerifyResult};
+use crate::model::typesystem::{Type, TypeId, TypeStorage};
 use crate::model::binder::Binder;
+use crate::model::instruction::Instruction;
 use crate::vm::Execution;
-use crate::model::class::{Class};
 use crate::optimization::register_allocation::RegisterAllocationSettings;
+use crate::runtime::memory::manager::MemoryManager;
 
 #[derive(Debug, PartialEq, Eq)]
 pub enum ExecutionEngineError {
     Verify(VerifyError),
     NoMainFunction,
     NoMainFunctionCompiled,
-    Runtime(RuntimeError),
+    Runtime(RuntimeError, Vec<StackTraceEntry>),
     Other(String)
 }
 
@@ -20,7 +22,26 @@ pub enum ExecutionEngineError {
 pub enum RuntimeError {
     NullReference,
     ArrayCreate,
-    ArrayBounds
+    ArrayBounds,
+    OutOfMemory,
+    DivisionByZero,
+    DivisionOverflow,
+    InvalidCast,
+    StackOverflow,
+    UnhandledException(String),
+    NativePanic(String)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StackTraceEntry {
+    pub signature: FunctionSignature,
+    pub instruction_index: usize
+}
+
+impl std::fmt::Display for StackTraceEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} @ {}", self.signature, self.instruction_index)
+    }
 }
 
 pub type ExecutionEngineResult<T> = Result<T, ExecutionEngineError>;
@@ -28,7 +49,8 @@ pub type ExecutionEngineResult<T> = Result<T, ExecutionEngineError>;
 pub struct ExecutionEngine {
     compiler: JitCompiler,
     binder: Binder,
-    pub runtime_error: RuntimeErrorManager
+    pub runtime_error: RuntimeErrorManager,
+    pub stack_limit: StackLimit
 }
 
 impl ExecutionEngine {
@@ -38,6 +60,7 @@ impl ExecutionEngine {
             register_allocation: RegisterAllocationSettings { num_int_registers: 2, num_float_registers: 2 }
         };
 
+        #[allow(unused_mut)]
         let mut test_profile = std::env::var("TEST_PROFILE");
         // test_profile = Ok("2".to_owned());
 
@@ -68,37 +91,256 @@ impl ExecutionEngine {
         ExecutionEngine {
             compiler: JitCompiler::new(jit_settings),
             binder: Binder::new(),
-            runtime_error: RuntimeErrorManager::new()
+            runtime_error: RuntimeErrorManager::new(),
+            stack_limit: StackLimit::new(StackLimit::DEFAULT_MAX_STACK_SIZE)
         }
     }
 
     pub fn create_execution(&mut self,
                             type_storage: &mut TypeStorage,
-                            function_storage: &mut FunctionStorage) -> ExecutionEngineResult<Execution> {
-        self.compile_functions(type_storage, function_storage)?;
-        self.compiler.resolve_calls_and_branches(&self.binder);
+                            function_storage: &mut FunctionStorage,
+                            memory_manager: &mut MemoryManager) -> ExecutionEngineResult<Execution> {
+        self.prepare_execution(type_storage, function_storage, memory_manager)?;
 
         let address = self.get_entrypoint()?;
-        let entrypoint = unsafe { std::mem::transmute(address) };
+        let entrypoint = unsafe { std::mem::transmute::<*mut libc::c_void, extern "C" fn() -> i32>(address) };
         Ok(Execution::new(entrypoint))
     }
 
-    pub fn take_runtime_error(&mut self) -> Option<RuntimeError> {
-        self.runtime_error.has_error.take()
+    pub fn prepare_execution(&mut self,
+                             type_storage: &mut TypeStorage,
+                             function_storage: &mut FunctionStorage,
+                             memory_manager: &mut MemoryManager) -> ExecutionEngineResult<()> {
+        self.create_virtual_tables(type_storage)?;
+        self.compile_functions(type_storage, function_storage, memory_manager)?;
+        self.fill_virtual_tables(type_storage);
+        self.compiler.resolve_calls_and_branches(&self.binder);
+        Ok(())
+    }
+
+    #[allow(dead_code)]
+    pub fn get_invoker(&mut self, signature: &FunctionSignature) -> ExecutionEngineResult<(FunctionDeclaration, InvokerFunction)> {
+        let function = self.binder
+            .get(signature)
+            .ok_or_else(|| ExecutionEngineError::Other(format!("The function '{}' is not defined.", signature)))?
+            .clone();
+
+        if function.address().is_none() {
+            return Err(ExecutionEngineError::Other(format!("The function '{}' is not compiled.", signature)));
+        }
+
+        let invoker = self.compiler.get_invoker(&function);
+        Ok((function, invoker))
+    }
+
+    #[allow(dead_code)]
+    pub fn generate_native_stub(&mut self, slot_cell: *mut usize, slot: usize, trampoline: FunctionAddress) -> FunctionAddress {
+        self.compiler.generate_native_stub(slot_cell, slot, trampoline)
+    }
+
+    /// Returns the state that entering managed code overwrites, such that a nested call can restore it for its caller.
+    #[allow(dead_code)]
+    pub fn entry_state(&self) -> EntryState {
+        EntryState {
+            return_address: self.runtime_error.return_address,
+            base_pointer: self.runtime_error.base_pointer,
+            stack_pointer: self.runtime_error.stack_pointer,
+            stack_limit: self.stack_limit.limit(),
+            external_caller: *self.runtime_error.external_caller()
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn restore_entry_state(&mut self, state: EntryState) {
+        self.runtime_error.return_address = state.return_address;
+        self.runtime_error.base_pointer = state.base_pointer;
+        self.runtime_error.stack_pointer = state.stack_pointer;
+        self.runtime_error.set_external_caller(state.external_caller);
+        self.stack_limit.set_limit(state.stack_limit);
+    }
+
+    pub fn take_runtime_error(&mut self) -> Option<(RuntimeError, Vec<StackTraceEntry>)> {
+        let runtime_error = self.runtime_error.has_error.take()?;
+        Some((runtime_error, std::mem::take(&mut self.runtime_error.stack_trace)))
     }
 
     fn compile_functions(&mut self,
                          type_storage: &mut TypeStorage,
-                         function_storage: &mut FunctionStorage) -> ExecutionEngineResult<()> {
+                         function_storage: &mut FunctionStorage,
+                         memory_manager: &mut MemoryManager) -> ExecutionEngineResult<()> {
         for function in function_storage.functions_mut() {
+            // Already compiled by an earlier execution
+            if function.declaration().address().is_some() {
+                continue;
+            }
+
             let mut verifier = Verifier::new(&self.binder, type_storage, function);
-            verifier.verify().map_err(|err| ExecutionEngineError::Verify(err))?;
-            self.compiler.compile_function(&mut self.binder, type_storage, function);
+            verifier.verify().map_err(ExecutionEngineError::Verify)?;
+            ExecutionEngine::intern_strings(type_storage, memory_manager, function)?;
+            self.compiler.compile_function(&mut self.binder, type_storage, memory_manager, &self.stack_limit, &self.runtime_error, function);
+        }
+
+        Ok(())
+    }
+
+    fn intern_strings(type_storage: &mut TypeStorage, memory_manager: &mut MemoryManager, function: &Function) -> ExecutionEngineResult<()> {
+        let string_type = type_storage.entry(TypeId::String);
+        for instruction in function.instructions() {
+            if let Instruction::LoadString(value) = instruction {
+                memory_manager.intern_string(string_type, value)
+                    .ok_or(ExecutionEngineError::Runtime(RuntimeError::OutOfMemory, Vec::new()))?;
+            }
         }
 
         Ok(())
     }
 
+    fn create_virtual_tables(&self, type_storage: &mut TypeStorage) -> ExecutionEngineResult<()> {
+        let mut virtual_tables = Vec::new();
+        for class_type in type_storage.class_types() {
+            // The layout of a created table can't change, as compiled code refers to the slots
+            let class_type = type_storage.get(&class_type).unwrap();
+            if class_type.virtual_functions().is_none() {
+                let virtual_functions = self.virtual_functions(class_type).map_err(ExecutionEngineError::Verify)?;
+                virtual_tables.push((class_type.id.clone(), virtual_functions));
+            }
+        }
+
+        for (class_type, virtual_functions) in &virtual_tables {
+            type_storage.get_mut(class_type).unwrap().set_virtual_functions(virtual_functions.clone());
+        }
+
+        // The slots of the interfaces are needed to create the interface tables
+        let mut interface_tables = Vec::new();
+        for (class_type, _) in &virtual_tables {
+            let class_type = type_storage.get(class_type).unwrap();
+            if !class_type.is_interface() {
+                let interface_functions = self.interface_functions(type_storage, class_type).map_err(ExecutionEngineError::Verify)?;
+                interface_tables.push((class_type.id.clone(), interface_functions));
+            }
+        }
+
+        for (class_type, interface_functions) in interface_tables {
+            type_storage.get_mut(&class_type).unwrap().set_interface_functions(interface_functions);
+        }
+
+        Ok(())
+    }
+
+    fn interface_functions(&self, type_storage: &TypeStorage, class_type: &Type) -> VerifyResult<Vec<(TypeId, Vec<FunctionSignature>)>> {
+        let mut interface_functions = Vec::new();
+        for interface_type in class_type.supertypes().filter(|supertype| supertype.is_interface()) {
+            let mut functions = Vec::new();
+            for interface_signature in interface_type.virtual_functions().unwrap() {
+                let interface_function = self.binder.get(interface_signature).unwrap();
+                let signature = FunctionSignature::with_class(
+                    interface_signature.name.clone(),
+                    class_type.id.clone(),
+                    interface_signature.parameters.clone()
+                );
+
+                let function = self.binder.get_member(type_storage, &signature)
+                    .ok_or_else(|| VerifyError::new(VerifyErrorMessage::InterfaceFunctionNotImplemented(class_type.id.clone(), interface_signature.clone())))?;
+
+                if function.return_type() != interface_function.return_type() {
+                    return Err(VerifyError::new(VerifyErrorMessage::WrongType(
+                        interface_function.return_type().clone(),
+                        function.return_type().clone()
+                    )));
+                }
+
+                functions.push(function.signature());
+            }
+
+            interface_functions.push((interface_type.id.clone(), functions));
+        }
+
+        Ok(interface_functions)
+    }
+
+    // The slots of the base class come first, such that a function has the same slot in all derived classes
+    fn virtual_functions(&self, class_type: &Type) -> VerifyResult<Vec<FunctionSignature>> {
+        let mut virtual_functions = match class_type.base_type() {
+            Some(base_type) => {
+                match base_type.virtual_functions() {
+                    Some(virtual_functions) => virtual_functions.clone(),
+                    None => self.virtual_functions(base_type)?
+                }
+            }
+            None => Vec::new()
+        };
+
+        let mut functions = self.binder.functions()
+            .filter(|function| function.is_virtual() && function.class().as_ref() == Some(&class_type.id))
+            .collect::<Vec<_>>();
+        functions.sort_by_key(|function| function.signature().to_string());
+
+        for function in functions {
+            let signature = function.signature();
+            let base_index = virtual_functions
+                .iter()
+                .position(|base_signature| base_signature.name == signature.name && base_signature.parameters == signature.parameters);
+
+            match (function.virtual_modifier().as_ref().unwrap(), base_index) {
+                (VirtualModifier::Virtual, None) => {
+                    virtual_functions.push(signature);
+                }
+                (VirtualModifier::Virtual, Some(_)) => {
+                    return Err(VerifyError::new(VerifyErrorMessage::ExpectedOverride(signature)));
+                }
+                (VirtualModifier::Override, Some(base_index)) => {
+                    let base_function = self.binder.get(&virtual_functions[base_index]).unwrap();
+                    if base_function.return_type() != function.return_type() {
+                        return Err(VerifyError::new(VerifyErrorMessage::WrongType(
+                            base_function.return_type().clone(),
+                            function.return_type().clone()
+                        )));
+                    }
+
+                    virtual_functions[base_index] = signature;
+                }
+                (VirtualModifier::Override, None) => {
+                    return Err(VerifyError::new(VerifyErrorMessage::NoVirtualFunctionToOverride(signature)));
+                }
+                (VirtualModifier::Interface, _) => {
+                    virtual_functions.push(signature);
+                }
+            }
+        }
+
+        Ok(virtual_functions)
+    }
+
+    fn fill_virtual_tables(&self, type_storage: &mut TypeStorage) {
+        for class_type in type_storage.class_types() {
+            let class_type = type_storage.get_mut(&class_type).unwrap();
+            let addresses = class_type.virtual_functions().unwrap()
+                .iter()
+                .map(|signature| self.binder.get(signature).and_then(|function| function.address()).unwrap_or(std::ptr::null_mut()))
+                .collect::<Vec<_>>();
+
+            for (index, address) in addresses.into_iter().enumerate() {
+                class_type.set_virtual_function_address(index, address);
+            }
+
+            let interface_addresses = class_type.interface_functions()
+                .iter()
+                .map(|(_, functions)| {
+                    functions
+                        .iter()
+                        .map(|signature| self.binder.get(signature).and_then(|function| function.address()).unwrap_or(std::ptr::null_mut()))
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>();
+
+            for (interface_index, addresses) in interface_addresses.into_iter().enumerate() {
+                for (index, address) in addresses.into_iter().enumerate() {
+                    class_type.set_interface_function_address(interface_index, index, address);
+                }
+            }
+        }
+    }
+
     fn get_entrypoint(&self) -> ExecutionEngineResult<FunctionAddress> {
         self.binder
             .get(&FunctionSignature::new("main".to_owned(), Vec::new()))
@@ -118,18 +360,166 @@ impl ExecutionEngine {
 
 pub struct RuntimeErrorManager {
     pub has_error: Option<RuntimeError>,
+    pub stack_trace: Vec<StackTraceEntry>,
     pub return_address: u64,
     pub base_pointer: u64,
-    pub stack_pointer: u64
+    pub stack_pointer: u64,
+    native_error: Option<RuntimeError>,
+    native_error_flag: Box<u64>,
+    external_caller: Box<ExternalCallerFrame>
+}
+
+/// The managed frame that made the latest call to an external or native function, which is written by the generated code.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct ExternalCallerFrame {
+    pub base_pointer: u64,
+    pub function: *const Function,
+    pub instruction_index: usize
 }
 
 impl RuntimeErrorManager {
     pub fn new() -> RuntimeErrorManager {
         RuntimeErrorManager {
             has_error: None,
+            stack_trace: Vec::new(),
             return_address: 0,
             base_pointer: 0,
-            stack_pointer: 0
+            stack_pointer: 0,
+            native_error: None,
+            native_error_flag: Box::new(0),
+            external_caller: Box::new(ExternalCallerFrame {
+                base_pointer: 0,
+                function: std::ptr::null(),
+                instruction_index: 0
+            })
+        }
+    }
+
+    // External and native functions can't unwind into the generated code, so it checks this flag after calling one
+    pub fn native_error_flag_address(&self) -> *const u64 {
+        self.native_error_flag.as_ref() as *const u64
+    }
+
+    pub fn set_native_error(&mut self, error: RuntimeError) {
+        self.native_error = Some(error);
+        *self.native_error_flag = 1;
+    }
+
+    pub fn take_native_error(&mut self) -> Option<RuntimeError> {
+        *self.native_error_flag = 0;
+        self.native_error.take()
+    }
+
+    pub fn external_caller_address(&self) -> *const ExternalCallerFrame {
+        self.external_caller.as_ref() as *const ExternalCallerFrame
+    }
+
+    pub fn external_caller(&self) -> &ExternalCallerFrame {
+        &self.external_caller
+    }
+
+    #[allow(dead_code)]
+    pub fn set_external_caller(&mut self, frame: ExternalCallerFrame) {
+        *self.external_caller = frame;
+    }
+}
+
+/// Where an unhandled error in managed code returns to and how deep the stack may grow, as set up by the latest entry into managed code.
+pub struct EntryState {
+    return_address: u64,
+    base_pointer: u64,
+    stack_pointer: u64,
+    stack_limit: u64,
+    external_caller: ExternalCallerFrame
+}
+
+pub struct StackLimit {
+    max_stack_size: usize,
+    limit: Box<u64>
+}
+
+impl StackLimit {
+    pub const DEFAULT_MAX_STACK_SIZE: usize = 1024 * 1024;
+    // Space kept free below the limit for the runtime functions called while handling a stack overflow
+    pub const RESERVED_STACK_SIZE: usize = 64 * 1024;
+
+    pub fn new(max_stack_size: usize) -> StackLimit {
+        StackLimit {
+            max_stack_size,
+            limit: Box::new(0)
         }
     }
-}
\ No newline at end of file
+
+    #[allow(dead_code)]
+    pub fn max_stack_size(&self) -> usize {
+        self.max_stack_size
+    }
+
+    #[allow(dead_code)]
+    pub fn set_max_stack_size(&mut self, max_stack_size: usize) {
+        self.max_stack_size = max_stack_size;
+    }
+
+    // The limit is boxed such that the address embedded in the generated code is stable
+    pub fn limit_address(&self) -> *const u64 {
+        self.limit.as_ref() as *const u64
+    }
+
+    pub fn limit(&self) -> u64 {
+        *self.limit
+    }
+
+    pub fn set_limit(&mut self, limit: u64) {
+        *self.limit = limit;
+    }
+
+    pub fn set_stack_start(&mut self, stack_pointer: u64) {
+        let limit = stack_pointer.saturating_sub(self.max_stack_size as u64);
+        *self.limit = match thread_stack_bottom() {
+            Some(stack_bottom) => limit.max(stack_bottom + StackLimit::RESERVED_STACK_SIZE as u64),
+            None => limit
+        };
+    }
+}
+
+thread_local!(static THREAD_STACK_BOTTOM: std::cell::Cell<Option<u64>> = const { std::cell::Cell::new(None) });
+
+/// Returns the lowest usable address of the stack of the current thread (above the guard area), if it can be determined.
+fn thread_stack_bottom() -> Option<u64> {
+    THREAD_STACK_BOTTOM.with(|stack_bottom| {
+        if stack_bottom.get().is_none() {
+            stack_bottom.set(query_thread_stack_bottom());
+        }
+
+        stack_bottom.get()
+    })
+}
+
+#[cfg(target_os = "linux")]
+fn query_thread_stack_bottom() -> Option<u64> {
+    unsafe {
+        let mut attributes = std::mem::zeroed::<libc::pthread_attr_t>();
+        if libc::pthread_getattr_np(libc::pthread_self(), &mut attributes) != 0 {
+            return None;
+        }
+
+        let mut stack_address = std::ptr::null_mut();
+        let mut stack_size = 0;
+        let mut guard_size = 0;
+        let success = libc::pthread_attr_getstack(&attributes, &mut stack_address, &mut stack_size) == 0
+            && libc::pthread_attr_getguardsize(&attributes, &mut guard_size) == 0;
+        libc::pthread_attr_destroy(&mut attributes);
+
+        if success {
+            Some(stack_address as u64 + guard_size as u64)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn query_thread_stack_bottom() -> Option<u64> {
+    None
+}
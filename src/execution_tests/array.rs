@@ -5,7 +5,8 @@ use crate::model::instruction::Instruction;
 use crate::model::typesystem::TypeId;
 use crate::vm::{VirtualMachine, get_vm};
 use crate::runtime::array;
-use crate::engine::execution::{ExecutionEngineError, RuntimeError};
+use crate::engine::execution::{ExecutionEngineError, RuntimeError, StackTraceEntry};
+use crate::model::class::{Class, Field};
 
 thread_local!(static ARRAY_RESULT: RefCell<u64> = RefCell::new(0));
 thread_local!(static FLOAT_RESULT: RefCell<f32> = RefCell::new(0.0));
@@ -20,14 +21,14 @@ extern "C" fn print_array(ptr: u64) {
 extern "C" fn set_array(ptr: u64, index: i32, value: i32) {
     let ptr = (ptr + array::LENGTH_SIZE as u64) as *mut i32;
     unsafe {
-        *ptr.add(index as usize) = value;
+        ptr.add(index as usize).write_unaligned(value);
     }
 }
 
 extern "C" fn set_array_float(ptr: u64, index: i32, value: f32) {
     let ptr = (ptr + array::LENGTH_SIZE as u64) as *mut f32;
     unsafe {
-        *ptr.add(index as usize) = value;
+        ptr.add(index as usize).write_unaligned(value);
     }
 }
 
@@ -38,6 +39,38 @@ extern "C" fn print_float(x: f32) {
     });
 }
 
+fn create_int_array(local: u32, values: &[i32]) -> Vec<Instruction> {
+    let mut instructions = vec![
+        Instruction::LoadInt32(values.len() as i32),
+        Instruction::NewArray(TypeId::Int32),
+        Instruction::StoreLocal(local),
+    ];
+
+    for (index, value) in values.iter().enumerate() {
+        instructions.push(Instruction::LoadLocal(local));
+        instructions.push(Instruction::LoadInt32(index as i32));
+        instructions.push(Instruction::LoadInt32(*value));
+        instructions.push(Instruction::StoreElement(TypeId::Int32));
+    }
+
+    instructions
+}
+
+fn load_int_elements(local: u32, indices: &[i32]) -> Vec<Instruction> {
+    // Combines the elements as decimal digits
+    let mut instructions = vec![Instruction::LoadInt32(0)];
+    for index in indices {
+        instructions.push(Instruction::LoadInt32(10));
+        instructions.push(Instruction::Multiply);
+        instructions.push(Instruction::LoadLocal(local));
+        instructions.push(Instruction::LoadInt32(*index));
+        instructions.push(Instruction::LoadElement(TypeId::Int32));
+        instructions.push(Instruction::Add);
+    }
+
+    instructions
+}
+
 #[test]
 fn test_create1() {
     ARRAY_RESULT.with(|result| {
@@ -390,7 +423,7 @@ fn test_checks1() {
     )).unwrap();
 
     let execution_result = vm.execute();
-    assert_eq!(Err(ExecutionEngineError::Runtime(RuntimeError::NullReference)), execution_result);
+    assert!(matches!(execution_result, Err(ExecutionEngineError::Runtime(RuntimeError::NullReference, _))));
 }
 
 #[test]
@@ -420,7 +453,7 @@ fn test_checks2() {
     )).unwrap();
 
     let execution_result = vm.execute();
-    assert_eq!(Err(ExecutionEngineError::Runtime(RuntimeError::NullReference)), execution_result);
+    assert!(matches!(execution_result, Err(ExecutionEngineError::Runtime(RuntimeError::NullReference, _))));
 }
 
 #[test]
@@ -440,7 +473,7 @@ fn test_checks3() {
     )).unwrap();
 
     let execution_result = vm.execute();
-    assert_eq!(Err(ExecutionEngineError::Runtime(RuntimeError::ArrayBounds)), execution_result);
+    assert!(matches!(execution_result, Err(ExecutionEngineError::Runtime(RuntimeError::ArrayBounds, _))));
 }
 
 #[test]
@@ -460,7 +493,7 @@ fn test_check4() {
     )).unwrap();
 
     let execution_result = vm.execute();
-    assert_eq!(Err(ExecutionEngineError::Runtime(RuntimeError::ArrayBounds)), execution_result);
+    assert!(matches!(execution_result, Err(ExecutionEngineError::Runtime(RuntimeError::ArrayBounds, _))));
 }
 
 #[test]
@@ -482,7 +515,7 @@ fn test_check5() {
     )).unwrap();
 
     let execution_result = vm.execute();
-    assert_eq!(Err(ExecutionEngineError::Runtime(RuntimeError::ArrayBounds)), execution_result);
+    assert!(matches!(execution_result, Err(ExecutionEngineError::Runtime(RuntimeError::ArrayBounds, _))));
 }
 
 #[test]
@@ -502,7 +535,49 @@ fn test_check6() {
     )).unwrap();
 
     let execution_result = vm.execute();
-    assert_eq!(Err(ExecutionEngineError::Runtime(RuntimeError::ArrayCreate)), execution_result);
+    assert!(matches!(execution_result, Err(ExecutionEngineError::Runtime(RuntimeError::ArrayCreate, _))));
+}
+
+#[test]
+fn test_check_stack_trace1() {
+    let mut vm = VirtualMachine::new();
+
+    let array_type = TypeId::Array(Box::new(TypeId::Int32));
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("get".to_owned(), vec![array_type.clone(), TypeId::Int32], TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadArgument(0),
+            Instruction::LoadArgument(1),
+            Instruction::LoadElement(TypeId::Int32),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadInt32(10),
+            Instruction::NewArray(TypeId::Int32),
+            Instruction::LoadInt32(20),
+            Instruction::Call(FunctionSignature::new("get".to_owned(), vec![array_type.clone(), TypeId::Int32])),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute();
+    assert_eq!(
+        Err(ExecutionEngineError::Runtime(
+            RuntimeError::ArrayBounds,
+            vec![
+                StackTraceEntry { signature: FunctionSignature::new("get".to_owned(), vec![array_type.clone(), TypeId::Int32]), instruction_index: 2 },
+                StackTraceEntry { signature: FunctionSignature::new("main".to_owned(), Vec::new()), instruction_index: 3 }
+            ]
+        )),
+        execution_result
+    );
 }
 
 #[test]
@@ -524,3 +599,752 @@ fn test_check7() {
     let execution_result = vm.execute();
     assert_eq!(Ok(4711), execution_result);
 }
+
+#[test]
+fn test_copy1() {
+    let mut vm = VirtualMachine::new();
+
+    let mut instructions = create_int_array(0, &[1, 2, 3, 4, 5]);
+    instructions.extend(vec![
+        Instruction::LoadInt32(5),
+        Instruction::NewArray(TypeId::Int32),
+        Instruction::StoreLocal(1),
+
+        Instruction::LoadLocal(0),
+        Instruction::LoadInt32(1),
+        Instruction::LoadLocal(1),
+        Instruction::LoadInt32(2),
+        Instruction::LoadInt32(3),
+        Instruction::Call(FunctionSignature::new("std.array.copy".to_owned(), vec![TypeId::Array(Box::new(TypeId::Int32)), TypeId::Int32, TypeId::Array(Box::new(TypeId::Int32)), TypeId::Int32, TypeId::Int32])),
+    ]);
+    instructions.extend(load_int_elements(1, &[0, 1, 2, 3, 4]));
+    instructions.push(Instruction::Return);
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Array(Box::new(TypeId::Int32)), TypeId::Array(Box::new(TypeId::Int32))],
+        instructions
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(234, execution_result);
+}
+
+#[test]
+fn test_copy2() {
+    let mut vm = VirtualMachine::new();
+
+    let mut instructions = create_int_array(0, &[1, 2, 3, 4, 5]);
+    instructions.extend(vec![
+        Instruction::LoadLocal(0),
+        Instruction::LoadInt32(0),
+        Instruction::LoadLocal(0),
+        Instruction::LoadInt32(1),
+        Instruction::LoadInt32(4),
+        Instruction::Call(FunctionSignature::new("std.array.copy".to_owned(), vec![TypeId::Array(Box::new(TypeId::Int32)), TypeId::Int32, TypeId::Array(Box::new(TypeId::Int32)), TypeId::Int32, TypeId::Int32])),
+    ]);
+    instructions.extend(load_int_elements(0, &[0, 1, 2, 3, 4]));
+    instructions.push(Instruction::Return);
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Array(Box::new(TypeId::Int32))],
+        instructions
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(11234, execution_result);
+}
+
+#[test]
+fn test_copy3() {
+    let mut vm = VirtualMachine::new();
+
+    let mut instructions = create_int_array(0, &[1, 2, 3, 4, 5]);
+    instructions.extend(vec![
+        Instruction::LoadLocal(0),
+        Instruction::LoadInt32(1),
+        Instruction::LoadLocal(0),
+        Instruction::LoadInt32(0),
+        Instruction::LoadInt32(4),
+        Instruction::Call(FunctionSignature::new("std.array.copy".to_owned(), vec![TypeId::Array(Box::new(TypeId::Int32)), TypeId::Int32, TypeId::Array(Box::new(TypeId::Int32)), TypeId::Int32, TypeId::Int32])),
+    ]);
+    instructions.extend(load_int_elements(0, &[0, 1, 2, 3, 4]));
+    instructions.push(Instruction::Return);
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Array(Box::new(TypeId::Int32))],
+        instructions
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(23455, execution_result);
+}
+
+#[test]
+fn test_copy4() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Array(Box::new(TypeId::Bool))],
+        vec![
+            Instruction::LoadInt32(3),
+            Instruction::NewArray(TypeId::Bool),
+            Instruction::StoreLocal(0),
+
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(0),
+            Instruction::LoadTrue,
+            Instruction::StoreElement(TypeId::Bool),
+
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(0),
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(1),
+            Instruction::LoadInt32(2),
+            Instruction::Call(FunctionSignature::new("std.array.copy".to_owned(), vec![TypeId::Array(Box::new(TypeId::Bool)), TypeId::Int32, TypeId::Array(Box::new(TypeId::Bool)), TypeId::Int32, TypeId::Int32])),
+
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(2),
+            Instruction::LoadElement(TypeId::Bool),
+            Instruction::ConvertBoolToInt32,
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(1),
+            Instruction::LoadElement(TypeId::Bool),
+            Instruction::ConvertBoolToInt32,
+            Instruction::Add,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(1, execution_result);
+}
+
+#[test]
+fn test_copy_checks1() {
+    let ranges = [(0, 0, 6), (-1, 0, 2), (0, 4, 2), (0, 0, -1), (5, 0, 1)];
+
+    for (source_index, destination_index, count) in ranges {
+        let mut vm = VirtualMachine::new();
+
+        let mut instructions = create_int_array(0, &[1, 2, 3, 4, 5]);
+        instructions.extend(vec![
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(source_index),
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(destination_index),
+            Instruction::LoadInt32(count),
+            Instruction::Call(FunctionSignature::new("std.array.copy".to_owned(), vec![TypeId::Array(Box::new(TypeId::Int32)), TypeId::Int32, TypeId::Array(Box::new(TypeId::Int32)), TypeId::Int32, TypeId::Int32])),
+            Instruction::LoadInt32(0),
+            Instruction::Return,
+        ]);
+
+        vm.add_function(Function::new(
+            FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+            vec![TypeId::Array(Box::new(TypeId::Int32))],
+            instructions
+        )).unwrap();
+
+        let execution_result = vm.execute();
+        assert!(matches!(execution_result, Err(ExecutionEngineError::Runtime(RuntimeError::ArrayBounds, _))));
+    }
+}
+
+#[test]
+fn test_copy_checks2() {
+    let mut vm = VirtualMachine::new();
+
+    let mut instructions = create_int_array(0, &[1, 2, 3, 4, 5]);
+    instructions.extend(vec![
+        Instruction::LoadNull(TypeId::Array(Box::new(TypeId::Int32))),
+        Instruction::LoadInt32(0),
+        Instruction::LoadLocal(0),
+        Instruction::LoadInt32(0),
+        Instruction::LoadInt32(0),
+        Instruction::Call(FunctionSignature::new("std.array.copy".to_owned(), vec![TypeId::Array(Box::new(TypeId::Int32)), TypeId::Int32, TypeId::Array(Box::new(TypeId::Int32)), TypeId::Int32, TypeId::Int32])),
+        Instruction::LoadInt32(0),
+        Instruction::Return,
+    ]);
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Array(Box::new(TypeId::Int32))],
+        instructions
+    )).unwrap();
+
+    let execution_result = vm.execute();
+    assert!(matches!(execution_result, Err(ExecutionEngineError::Runtime(RuntimeError::NullReference, _))));
+}
+
+#[test]
+fn test_copy_checks3() {
+    let mut vm = VirtualMachine::new();
+
+    let mut instructions = create_int_array(0, &[1, 2, 3, 4, 5]);
+    instructions.extend(vec![
+        Instruction::LoadLocal(0),
+        Instruction::LoadInt32(5),
+        Instruction::LoadLocal(0),
+        Instruction::LoadInt32(0),
+        Instruction::LoadInt32(0),
+        Instruction::Call(FunctionSignature::new("std.array.copy".to_owned(), vec![TypeId::Array(Box::new(TypeId::Int32)), TypeId::Int32, TypeId::Array(Box::new(TypeId::Int32)), TypeId::Int32, TypeId::Int32])),
+    ]);
+    instructions.extend(load_int_elements(0, &[0, 1, 2, 3, 4]));
+    instructions.push(Instruction::Return);
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Array(Box::new(TypeId::Int32))],
+        instructions
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(12345, execution_result);
+}
+
+#[test]
+fn test_fill1() {
+    let mut vm = VirtualMachine::new();
+
+    let mut instructions = create_int_array(0, &[1, 2, 3, 4, 5]);
+    instructions.extend(vec![
+        Instruction::LoadLocal(0),
+        Instruction::LoadInt32(7),
+        Instruction::Call(FunctionSignature::new("std.array.fill".to_owned(), vec![TypeId::Array(Box::new(TypeId::Int32)), TypeId::Int32])),
+    ]);
+    instructions.extend(load_int_elements(0, &[0, 1, 2, 3, 4]));
+    instructions.push(Instruction::Return);
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Array(Box::new(TypeId::Int32))],
+        instructions
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(77777, execution_result);
+}
+
+#[test]
+fn test_fill2() {
+    FLOAT_RESULT.with(|result| {
+        *result.borrow_mut() = 0.0;
+    });
+
+    let mut vm = VirtualMachine::new();
+
+    vm.add_external_function(
+        FunctionDeclaration::with_external(
+            "print".to_owned(), vec![TypeId::Float32], TypeId::Void,
+            print_float as *mut std::ffi::c_void
+        )
+    );
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Array(Box::new(TypeId::Float32))],
+        vec![
+            Instruction::LoadInt32(1000),
+            Instruction::NewArray(TypeId::Float32),
+            Instruction::StoreLocal(0),
+
+            Instruction::LoadLocal(0),
+            Instruction::LoadFloat32(13.37),
+            Instruction::Call(FunctionSignature::new("std.array.fill".to_owned(), vec![TypeId::Array(Box::new(TypeId::Float32)), TypeId::Float32])),
+
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(999),
+            Instruction::LoadElement(TypeId::Float32),
+            Instruction::Call(FunctionSignature::new("print".to_owned(), vec![TypeId::Float32])),
+
+            Instruction::LoadInt32(0),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(0, execution_result);
+    assert_eq!(13.37, FLOAT_RESULT.with(|result| *result.borrow()));
+}
+
+#[test]
+fn test_fill3() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Array(Box::new(TypeId::Bool)), TypeId::Array(Box::new(TypeId::Int64))],
+        vec![
+            Instruction::LoadInt32(3),
+            Instruction::NewArray(TypeId::Bool),
+            Instruction::StoreLocal(0),
+
+            Instruction::LoadLocal(0),
+            Instruction::LoadTrue,
+            Instruction::Call(FunctionSignature::new("std.array.fill".to_owned(), vec![TypeId::Array(Box::new(TypeId::Bool)), TypeId::Bool])),
+
+            Instruction::LoadInt32(3),
+            Instruction::NewArray(TypeId::Int64),
+            Instruction::StoreLocal(1),
+
+            Instruction::LoadLocal(1),
+            Instruction::LoadInt64(1 << 40),
+            Instruction::Call(FunctionSignature::new("std.array.fill".to_owned(), vec![TypeId::Array(Box::new(TypeId::Int64)), TypeId::Int64])),
+
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(2),
+            Instruction::LoadElement(TypeId::Bool),
+            Instruction::ConvertBoolToInt32,
+
+            Instruction::LoadLocal(1),
+            Instruction::LoadInt32(2),
+            Instruction::LoadElement(TypeId::Int64),
+            Instruction::LoadInt64(1 << 40),
+            Instruction::CompareEqual,
+            Instruction::ConvertBoolToInt32,
+
+            Instruction::Add,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(2, execution_result);
+}
+
+#[test]
+fn test_fill_checks1() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![],
+        vec![
+            Instruction::LoadNull(TypeId::Array(Box::new(TypeId::Int32))),
+            Instruction::LoadInt32(7),
+            Instruction::Call(FunctionSignature::new("std.array.fill".to_owned(), vec![TypeId::Array(Box::new(TypeId::Int32)), TypeId::Int32])),
+            Instruction::LoadInt32(0),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute();
+    assert!(matches!(execution_result, Err(ExecutionEngineError::Runtime(RuntimeError::NullReference, _))));
+}
+
+#[test]
+fn test_clone1() {
+    let mut vm = VirtualMachine::new();
+
+    let mut instructions = create_int_array(0, &[1, 2, 3, 4, 5]);
+    instructions.extend(vec![
+        Instruction::LoadLocal(0),
+        Instruction::Call(FunctionSignature::new("std.array.clone".to_owned(), vec![TypeId::Array(Box::new(TypeId::Int32))])),
+        Instruction::StoreLocal(1),
+
+        Instruction::LoadLocal(0),
+        Instruction::LoadInt32(0),
+        Instruction::LoadInt32(9),
+        Instruction::StoreElement(TypeId::Int32),
+    ]);
+    instructions.extend(load_int_elements(1, &[0, 1, 2, 3, 4]));
+    instructions.push(Instruction::Return);
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Array(Box::new(TypeId::Int32)), TypeId::Array(Box::new(TypeId::Int32))],
+        instructions
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(12345, execution_result);
+}
+
+#[test]
+fn test_clone2() {
+    let mut vm = VirtualMachine::new();
+
+    // Cloning repeatedly triggers garbage collections that move the source array
+    let mut instructions = create_int_array(0, &[1, 2, 3, 4, 5]);
+    instructions.extend(vec![
+        Instruction::LoadInt32(0),
+        Instruction::StoreLocal(2),
+
+        Instruction::LoadLocal(0),
+        Instruction::Call(FunctionSignature::new("std.array.clone".to_owned(), vec![TypeId::Array(Box::new(TypeId::Int32))])),
+        Instruction::StoreLocal(1),
+
+        Instruction::LoadInt32(1000),
+        Instruction::NewArray(TypeId::Int32),
+        Instruction::Pop,
+
+        Instruction::LoadLocal(2),
+        Instruction::LoadInt32(1),
+        Instruction::Add,
+        Instruction::StoreLocal(2),
+
+        Instruction::LoadLocal(2),
+        Instruction::LoadInt32(1000),
+        Instruction::BranchLessThan(25),
+    ]);
+    instructions.extend(load_int_elements(1, &[0, 1, 2, 3, 4]));
+    instructions.push(Instruction::Return);
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Array(Box::new(TypeId::Int32)), TypeId::Array(Box::new(TypeId::Int32)), TypeId::Int32],
+        instructions
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(12345, execution_result);
+}
+
+#[test]
+fn test_equals1() {
+    let mut vm = VirtualMachine::new();
+
+    let mut instructions = create_int_array(0, &[1, 2, 3, 4, 5]);
+    instructions.extend(create_int_array(1, &[1, 2, 3, 4, 5]));
+    instructions.extend(create_int_array(2, &[1, 2, 3, 4, 6]));
+    instructions.extend(create_int_array(3, &[1, 2, 3, 4]));
+    instructions.extend(vec![
+        Instruction::LoadLocal(0),
+        Instruction::LoadLocal(1),
+        Instruction::Call(FunctionSignature::new("std.array.equals".to_owned(), vec![TypeId::Array(Box::new(TypeId::Int32)), TypeId::Array(Box::new(TypeId::Int32))])),
+        Instruction::ConvertBoolToInt32,
+        Instruction::LoadInt32(100),
+        Instruction::Multiply,
+
+        Instruction::LoadLocal(0),
+        Instruction::LoadLocal(2),
+        Instruction::Call(FunctionSignature::new("std.array.equals".to_owned(), vec![TypeId::Array(Box::new(TypeId::Int32)), TypeId::Array(Box::new(TypeId::Int32))])),
+        Instruction::ConvertBoolToInt32,
+        Instruction::LoadInt32(10),
+        Instruction::Multiply,
+        Instruction::Add,
+
+        Instruction::LoadLocal(0),
+        Instruction::LoadLocal(3),
+        Instruction::Call(FunctionSignature::new("std.array.equals".to_owned(), vec![TypeId::Array(Box::new(TypeId::Int32)), TypeId::Array(Box::new(TypeId::Int32))])),
+        Instruction::ConvertBoolToInt32,
+        Instruction::Add,
+
+        Instruction::Return,
+    ]);
+
+    let array_type = TypeId::Array(Box::new(TypeId::Int32));
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![array_type.clone(), array_type.clone(), array_type.clone(), array_type],
+        instructions
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(100, execution_result);
+}
+
+#[test]
+fn test_equals2() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Array(Box::new(TypeId::Float64)), TypeId::Array(Box::new(TypeId::Float64))],
+        vec![
+            Instruction::LoadInt32(0),
+            Instruction::NewArray(TypeId::Float64),
+            Instruction::StoreLocal(0),
+            Instruction::LoadInt32(0),
+            Instruction::NewArray(TypeId::Float64),
+            Instruction::StoreLocal(1),
+
+            Instruction::LoadLocal(0),
+            Instruction::LoadLocal(1),
+            Instruction::Call(FunctionSignature::new("std.array.equals".to_owned(), vec![TypeId::Array(Box::new(TypeId::Float64)), TypeId::Array(Box::new(TypeId::Float64))])),
+            Instruction::ConvertBoolToInt32,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(1, execution_result);
+}
+
+#[test]
+fn test_2d_load_store1() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Array2D(Box::new(TypeId::Int32))],
+        vec![
+            Instruction::LoadInt32(3),
+            Instruction::LoadInt32(4),
+            Instruction::NewArray2D(TypeId::Int32),
+            Instruction::StoreLocal(0),
+
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(2),
+            Instruction::LoadInt32(1),
+            Instruction::LoadInt32(4711),
+            Instruction::StoreElement2D(TypeId::Int32),
+
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(1),
+            Instruction::LoadInt32(2),
+            Instruction::LoadInt32(1000),
+            Instruction::StoreElement2D(TypeId::Int32),
+
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(2),
+            Instruction::LoadInt32(1),
+            Instruction::LoadElement2D(TypeId::Int32),
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(1),
+            Instruction::LoadInt32(2),
+            Instruction::LoadElement2D(TypeId::Int32),
+            Instruction::Add,
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(1),
+            Instruction::LoadInt32(1),
+            Instruction::LoadElement2D(TypeId::Int32),
+            Instruction::Add,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(5711, execution_result);
+}
+
+#[test]
+fn test_2d_load_store2() {
+    FLOAT_RESULT.with(|result| {
+        *result.borrow_mut() = 0.0;
+    });
+
+    let mut vm = VirtualMachine::new();
+
+    vm.add_external_function(
+        FunctionDeclaration::with_external(
+            "print".to_owned(), vec![TypeId::Float32], TypeId::Void,
+            print_float as *mut std::ffi::c_void
+        )
+    );
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Array2D(Box::new(TypeId::Float32))],
+        vec![
+            Instruction::LoadInt32(2),
+            Instruction::LoadInt32(5),
+            Instruction::NewArray2D(TypeId::Float32),
+            Instruction::StoreLocal(0),
+
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(1),
+            Instruction::LoadInt32(4),
+            Instruction::LoadFloat32(13.37),
+            Instruction::StoreElement2D(TypeId::Float32),
+
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(1),
+            Instruction::LoadInt32(4),
+            Instruction::LoadElement2D(TypeId::Float32),
+            Instruction::Call(FunctionSignature::new("print".to_owned(), vec![TypeId::Float32])),
+
+            Instruction::LoadInt32(0),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(0, execution_result);
+    assert_eq!(13.37, FLOAT_RESULT.with(|result| *result.borrow()));
+}
+
+#[test]
+fn test_2d_load_store3() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_class(Class::new(
+        "Point".to_owned(),
+        vec![
+            Field::new("x".to_owned(), TypeId::Int32),
+            Field::new("y".to_owned(), TypeId::Int32),
+        ]
+    )).unwrap();
+
+    let point_type = TypeId::Class("Point".to_owned());
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Array2D(Box::new(point_type.clone())), TypeId::Array(Box::new(TypeId::Int32))],
+        vec![
+            Instruction::LoadInt32(2),
+            Instruction::LoadInt32(2),
+            Instruction::NewArray2D(point_type.clone()),
+            Instruction::StoreLocal(0),
+
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(1),
+            Instruction::LoadInt32(0),
+            Instruction::NewObject("Point".to_owned()),
+            Instruction::StoreElement2D(point_type.clone()),
+
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(1),
+            Instruction::LoadInt32(0),
+            Instruction::LoadElement2D(point_type.clone()),
+            Instruction::LoadInt32(4711),
+            Instruction::StoreField("Point".to_owned(), "x".to_owned()),
+
+            Instruction::LoadInt32(1000),
+            Instruction::NewArray(TypeId::Int32),
+            Instruction::StoreLocal(1),
+            Instruction::LoadNull(TypeId::Array(Box::new(TypeId::Int32))),
+            Instruction::StoreLocal(1),
+            Instruction::Call(FunctionSignature::new("std.gc.collect".to_string(), vec![])),
+
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(1),
+            Instruction::LoadInt32(0),
+            Instruction::LoadElement2D(point_type),
+            Instruction::LoadField("Point".to_owned(), "x".to_owned()),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(4711, execution_result);
+
+    get_vm(|vm| {
+        assert_eq!(1, vm.memory_manager.garbage_collector.deleted_objects().len());
+        assert_eq!(TypeId::Array(Box::new(TypeId::Int32)), vm.memory_manager.garbage_collector.deleted_objects()[0].1);
+    });
+}
+
+#[test]
+fn test_2d_dimensions1() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Array2D(Box::new(TypeId::Int64))],
+        vec![
+            Instruction::LoadInt32(3),
+            Instruction::LoadInt32(4),
+            Instruction::NewArray2D(TypeId::Int64),
+            Instruction::StoreLocal(0),
+
+            Instruction::LoadLocal(0),
+            Instruction::LoadArrayDimension(0),
+            Instruction::LoadInt32(10),
+            Instruction::Multiply,
+            Instruction::LoadLocal(0),
+            Instruction::LoadArrayDimension(1),
+            Instruction::Add,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(34, execution_result);
+}
+
+#[test]
+fn test_2d_checks1() {
+    let indices = [(3, 0), (0, 4), (-1, 0), (0, -1), (1, 5)];
+
+    for (row, column) in indices {
+        let mut vm = VirtualMachine::new();
+
+        vm.add_function(Function::new(
+            FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+            vec![],
+            vec![
+                Instruction::LoadInt32(3),
+                Instruction::LoadInt32(4),
+                Instruction::NewArray2D(TypeId::Int32),
+                Instruction::LoadInt32(row),
+                Instruction::LoadInt32(column),
+                Instruction::LoadElement2D(TypeId::Int32),
+                Instruction::Return,
+            ]
+        )).unwrap();
+
+        let execution_result = vm.execute();
+        assert!(matches!(execution_result, Err(ExecutionEngineError::Runtime(RuntimeError::ArrayBounds, _))));
+    }
+}
+
+#[test]
+fn test_2d_checks2() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![],
+        vec![
+            Instruction::LoadInt32(3),
+            Instruction::LoadInt32(4),
+            Instruction::NewArray2D(TypeId::Int32),
+            Instruction::LoadInt32(3),
+            Instruction::LoadInt32(0),
+            Instruction::LoadInt32(4711),
+            Instruction::StoreElement2D(TypeId::Int32),
+            Instruction::LoadInt32(0),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute();
+    assert!(matches!(execution_result, Err(ExecutionEngineError::Runtime(RuntimeError::ArrayBounds, _))));
+}
+
+#[test]
+fn test_2d_checks3() {
+    for (rows, columns) in [(-1, 4), (3, -1)] {
+        let mut vm = VirtualMachine::new();
+
+        vm.add_function(Function::new(
+            FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+            vec![],
+            vec![
+                Instruction::LoadInt32(rows),
+                Instruction::LoadInt32(columns),
+                Instruction::NewArray2D(TypeId::Int32),
+                Instruction::Pop,
+                Instruction::LoadInt32(0),
+                Instruction::Return,
+            ]
+        )).unwrap();
+
+        let execution_result = vm.execute();
+        assert!(matches!(execution_result, Err(ExecutionEngineError::Runtime(RuntimeError::ArrayCreate, _))));
+    }
+}
+
+#[test]
+fn test_2d_checks4() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![],
+        vec![
+            Instruction::LoadInt32(i32::MAX),
+            Instruction::LoadInt32(i32::MAX),
+            Instruction::NewArray2D(TypeId::Int64),
+            Instruction::Pop,
+            Instruction::LoadInt32(0),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute();
+    assert!(matches!(execution_result, Err(ExecutionEngineError::Runtime(RuntimeError::OutOfMemory, _))));
+}
@@ -2,6 +2,7 @@ use crate::model::function::{Function, FunctionDeclaration, FunctionSignature};
 use crate::model::instruction::Instruction;
 use crate::model::typesystem::TypeId;
 use crate::vm::VirtualMachine;
+use crate::engine::execution::{ExecutionEngineError, RuntimeError};
 
 extern "C" fn sum(x: i32, y: i32) -> i32 {
     return x + y;
@@ -417,4 +418,543 @@ fn test_managed6() {
 
     let execution_result = vm.execute().unwrap();
     assert_eq!(1337 + 4711, execution_result);
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_stack_overflow1() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("recursive".to_owned(), vec![TypeId::Int32], TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadArgument(0),
+            Instruction::LoadInt32(1),
+            Instruction::Add,
+            Instruction::Call(FunctionSignature::new("recursive".to_owned(), vec![TypeId::Int32])),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadInt32(0),
+            Instruction::Call(FunctionSignature::new("recursive".to_owned(), vec![TypeId::Int32])),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute();
+    assert!(matches!(execution_result, Err(ExecutionEngineError::Runtime(RuntimeError::StackOverflow, _))));
+}
+
+#[test]
+fn test_stack_overflow2() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("count".to_owned(), vec![TypeId::Int32], TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadArgument(0),
+            Instruction::LoadInt32(0),
+            Instruction::BranchNotEqual(5),
+            Instruction::LoadInt32(0),
+            Instruction::Return,
+            Instruction::LoadArgument(0),
+            Instruction::LoadInt32(1),
+            Instruction::Sub,
+            Instruction::Call(FunctionSignature::new("count".to_owned(), vec![TypeId::Int32])),
+            Instruction::LoadInt32(1),
+            Instruction::Add,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadInt32(10000),
+            Instruction::Call(FunctionSignature::new("count".to_owned(), vec![TypeId::Int32])),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(10000, execution_result);
+}
+
+#[test]
+fn test_stack_overflow3() {
+    let mut vm = VirtualMachine::new();
+    vm.set_max_stack_size(16 * 1024);
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("count".to_owned(), vec![TypeId::Int32], TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadArgument(0),
+            Instruction::LoadInt32(0),
+            Instruction::BranchNotEqual(5),
+            Instruction::LoadInt32(0),
+            Instruction::Return,
+            Instruction::LoadArgument(0),
+            Instruction::LoadInt32(1),
+            Instruction::Sub,
+            Instruction::Call(FunctionSignature::new("count".to_owned(), vec![TypeId::Int32])),
+            Instruction::LoadInt32(1),
+            Instruction::Add,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadInt32(10000),
+            Instruction::Call(FunctionSignature::new("count".to_owned(), vec![TypeId::Int32])),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute();
+    assert!(matches!(execution_result, Err(ExecutionEngineError::Runtime(RuntimeError::StackOverflow, _))));
+}
+
+#[test]
+fn test_stack_overflow4() {
+    let mut vm = VirtualMachine::new();
+    vm.set_max_stack_size(1024 * 1024 * 1024);
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("count".to_owned(), vec![TypeId::Int32], TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadArgument(0),
+            Instruction::LoadInt32(0),
+            Instruction::BranchNotEqual(5),
+            Instruction::LoadInt32(0),
+            Instruction::Return,
+            Instruction::LoadArgument(0),
+            Instruction::LoadInt32(1),
+            Instruction::Sub,
+            Instruction::Call(FunctionSignature::new("count".to_owned(), vec![TypeId::Int32])),
+            Instruction::LoadInt32(1),
+            Instruction::Add,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadInt32(10000000),
+            Instruction::Call(FunctionSignature::new("count".to_owned(), vec![TypeId::Int32])),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute();
+    assert!(matches!(execution_result, Err(ExecutionEngineError::Runtime(RuntimeError::StackOverflow, _))));
+}
+
+#[test]
+fn test_discard_result1() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_external_function(
+        FunctionDeclaration::with_external(
+            "sum".to_owned(), vec![TypeId::Int32, TypeId::Int32], TypeId::Int32,
+            sum as *mut std::ffi::c_void
+        )
+    );
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadInt32(1000),
+            Instruction::LoadInt32(4711),
+            Instruction::LoadInt32(1337),
+            Instruction::Call(FunctionSignature::new("sum".to_owned(), vec![TypeId::Int32, TypeId::Int32])),
+            Instruction::Pop,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(1000, execution_result);
+}
+
+#[test]
+fn test_indirect1() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("add".to_owned(), vec![TypeId::Int32, TypeId::Int32], TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadArgument(0),
+            Instruction::LoadArgument(1),
+            Instruction::Add,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadInt32(4711),
+            Instruction::LoadInt32(1337),
+            Instruction::LoadFunction(FunctionSignature::new("add".to_owned(), vec![TypeId::Int32, TypeId::Int32])),
+            Instruction::CallIndirect,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(4711 + 1337, execution_result);
+}
+
+#[test]
+fn test_indirect2() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_external_function(
+        FunctionDeclaration::with_external(
+            "sum8_sub".to_owned(), vec![TypeId::Int32; 8], TypeId::Int32,
+            sum8_sub as *mut std::ffi::c_void
+        )
+    );
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadInt32(1),
+            Instruction::LoadInt32(2),
+            Instruction::LoadInt32(3),
+            Instruction::LoadInt32(4),
+            Instruction::LoadInt32(5),
+            Instruction::LoadInt32(6),
+            Instruction::LoadInt32(7),
+            Instruction::LoadInt32(8),
+            Instruction::LoadFunction(FunctionSignature::new("sum8_sub".to_owned(), vec![TypeId::Int32; 8])),
+            Instruction::CallIndirect,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(1 + 2 + 3 + 4 + 5 + 6 + 7 - 8, execution_result);
+}
+
+#[test]
+fn test_indirect3() {
+    let mut vm = VirtualMachine::new();
+
+    let function_type = TypeId::Function(vec![TypeId::Int32], Box::new(TypeId::Int32));
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("apply".to_owned(), vec![function_type.clone(), TypeId::Int32], TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadArgument(1),
+            Instruction::LoadArgument(0),
+            Instruction::CallIndirect,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("square".to_owned(), vec![TypeId::Int32], TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadArgument(0),
+            Instruction::LoadArgument(0),
+            Instruction::Multiply,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("negate".to_owned(), vec![TypeId::Int32], TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadArgument(0),
+            Instruction::Negate,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadFunction(FunctionSignature::new("square".to_owned(), vec![TypeId::Int32])),
+            Instruction::LoadInt32(12),
+            Instruction::Call(FunctionSignature::new("apply".to_owned(), vec![function_type.clone(), TypeId::Int32])),
+            Instruction::LoadFunction(FunctionSignature::new("negate".to_owned(), vec![TypeId::Int32])),
+            Instruction::LoadInt32(44),
+            Instruction::Call(FunctionSignature::new("apply".to_owned(), vec![function_type.clone(), TypeId::Int32])),
+            Instruction::Add,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(100, execution_result);
+}
+
+#[test]
+fn test_indirect4() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("scale".to_owned(), vec![TypeId::Float32, TypeId::Int32], TypeId::Float32),
+        Vec::new(),
+        vec![
+            Instruction::LoadArgument(0),
+            Instruction::LoadArgument(1),
+            Instruction::ConvertInt32ToFloat32,
+            Instruction::Multiply,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Function(vec![TypeId::Float32, TypeId::Int32], Box::new(TypeId::Float32))],
+        vec![
+            Instruction::LoadFunction(FunctionSignature::new("scale".to_owned(), vec![TypeId::Float32, TypeId::Int32])),
+            Instruction::StoreLocal(0),
+            Instruction::LoadFloat32(2.5),
+            Instruction::LoadInt32(4),
+            Instruction::LoadLocal(0),
+            Instruction::CallIndirect,
+            Instruction::ConvertFloat32ToInt32,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(10, execution_result);
+}
+
+#[test]
+fn test_indirect5() {
+    let mut vm = VirtualMachine::new();
+
+    let array_type = TypeId::Array(Box::new(TypeId::Int32));
+    let comparer_type = TypeId::Function(vec![TypeId::Int32, TypeId::Int32], Box::new(TypeId::Int32));
+
+    // Bubble sort, where the order is given by the comparer
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("sort".to_owned(), vec![array_type.clone(), comparer_type.clone()], TypeId::Void),
+        vec![TypeId::Int32, TypeId::Int32, TypeId::Int32],
+        vec![
+            Instruction::LoadInt32(0),
+            Instruction::StoreLocal(0),
+            Instruction::Branch(52),
+
+            Instruction::LoadInt32(0),
+            Instruction::StoreLocal(1),
+            Instruction::Branch(40),
+
+            Instruction::LoadArgument(0),
+            Instruction::LoadLocal(1),
+            Instruction::LoadElement(TypeId::Int32),
+            Instruction::LoadArgument(0),
+            Instruction::LoadLocal(1),
+            Instruction::LoadInt32(1),
+            Instruction::Add,
+            Instruction::LoadElement(TypeId::Int32),
+            Instruction::LoadArgument(1),
+            Instruction::CallIndirect,
+            Instruction::LoadInt32(0),
+            Instruction::BranchLessThanOrEqual(36),
+
+            Instruction::LoadArgument(0),
+            Instruction::LoadLocal(1),
+            Instruction::LoadElement(TypeId::Int32),
+            Instruction::StoreLocal(2),
+            Instruction::LoadArgument(0),
+            Instruction::LoadLocal(1),
+            Instruction::LoadArgument(0),
+            Instruction::LoadLocal(1),
+            Instruction::LoadInt32(1),
+            Instruction::Add,
+            Instruction::LoadElement(TypeId::Int32),
+            Instruction::StoreElement(TypeId::Int32),
+            Instruction::LoadArgument(0),
+            Instruction::LoadLocal(1),
+            Instruction::LoadInt32(1),
+            Instruction::Add,
+            Instruction::LoadLocal(2),
+            Instruction::StoreElement(TypeId::Int32),
+
+            Instruction::LoadLocal(1),
+            Instruction::LoadInt32(1),
+            Instruction::Add,
+            Instruction::StoreLocal(1),
+
+            Instruction::LoadLocal(1),
+            Instruction::LoadArgument(0),
+            Instruction::LoadArrayLength,
+            Instruction::LoadInt32(1),
+            Instruction::Sub,
+            Instruction::LoadLocal(0),
+            Instruction::Sub,
+            Instruction::BranchLessThan(6),
+
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(1),
+            Instruction::Add,
+            Instruction::StoreLocal(0),
+
+            Instruction::LoadLocal(0),
+            Instruction::LoadArgument(0),
+            Instruction::LoadArrayLength,
+            Instruction::BranchLessThan(3),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("ascending".to_owned(), vec![TypeId::Int32, TypeId::Int32], TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadArgument(0),
+            Instruction::LoadArgument(1),
+            Instruction::Sub,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("descending".to_owned(), vec![TypeId::Int32, TypeId::Int32], TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadArgument(1),
+            Instruction::LoadArgument(0),
+            Instruction::Sub,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let values = [3, 1, 4, 1, 5];
+    let mut instructions = vec![
+        Instruction::LoadInt32(values.len() as i32),
+        Instruction::NewArray(TypeId::Int32),
+        Instruction::StoreLocal(0),
+    ];
+
+    for (index, value) in values.iter().enumerate() {
+        instructions.push(Instruction::LoadLocal(0));
+        instructions.push(Instruction::LoadInt32(index as i32));
+        instructions.push(Instruction::LoadInt32(*value));
+        instructions.push(Instruction::StoreElement(TypeId::Int32));
+    }
+
+    for comparer in ["ascending", "descending"] {
+        instructions.push(Instruction::LoadLocal(0));
+        instructions.push(Instruction::LoadFunction(FunctionSignature::new(comparer.to_owned(), vec![TypeId::Int32, TypeId::Int32])));
+        instructions.push(Instruction::Call(FunctionSignature::new("sort".to_owned(), vec![array_type.clone(), comparer_type.clone()])));
+
+        // Combines the elements as decimal digits
+        instructions.push(Instruction::LoadInt32(0));
+        for index in 0..values.len() {
+            instructions.push(Instruction::LoadInt32(10));
+            instructions.push(Instruction::Multiply);
+            instructions.push(Instruction::LoadLocal(0));
+            instructions.push(Instruction::LoadInt32(index as i32));
+            instructions.push(Instruction::LoadElement(TypeId::Int32));
+            instructions.push(Instruction::Add);
+        }
+    }
+
+    instructions.push(Instruction::Sub);
+    instructions.push(Instruction::Return);
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![array_type.clone()],
+        instructions
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(11345 - 54311, execution_result);
+}
+
+#[test]
+fn test_indirect6() {
+    let mut vm = VirtualMachine::new();
+
+    let function_type = TypeId::Function(vec![TypeId::Int32], Box::new(TypeId::Int32));
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("collect_and_increment".to_owned(), vec![TypeId::Int32], TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::Call(FunctionSignature::new("std.gc.collect".to_owned(), vec![])),
+            Instruction::LoadArgument(0),
+            Instruction::LoadInt32(1),
+            Instruction::Add,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    // The array is kept on the operand stack during the call, where it's moved by the garbage collector
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Array(Box::new(function_type.clone()))],
+        vec![
+            Instruction::LoadInt32(2),
+            Instruction::NewArray(function_type.clone()),
+            Instruction::StoreLocal(0),
+
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(1),
+            Instruction::LoadFunction(FunctionSignature::new("collect_and_increment".to_owned(), vec![TypeId::Int32])),
+            Instruction::StoreElement(function_type.clone()),
+
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(4710),
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(1),
+            Instruction::LoadElement(function_type.clone()),
+            Instruction::CallIndirect,
+            Instruction::Swap,
+            Instruction::LoadArrayLength,
+            Instruction::Add,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(4711 + 2, execution_result);
+}
+
+#[test]
+fn test_indirect_checks1() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Function(Vec::new(), Box::new(TypeId::Int32))],
+        vec![
+            Instruction::LoadLocal(0),
+            Instruction::CallIndirect,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute();
+    assert!(matches!(execution_result, Err(ExecutionEngineError::Runtime(RuntimeError::NullReference, _))));
+}
@@ -1,12 +1,13 @@
 use std::cell::RefCell;
 
-use crate::model::function::{Function, FunctionDeclaration, FunctionSignature};
+use crate::model::function::{Function, FunctionDeclaration, FunctionSignature, VirtualModifier};
 use crate::model::instruction::Instruction;
 use crate::model::typesystem::TypeId;
 use crate::vm::{VirtualMachine, get_vm};
 use crate::runtime::array;
-use crate::engine::execution::{ExecutionEngineError, RuntimeError, ExecutionEngineResult};
+use crate::engine::execution::{ExecutionEngineError, RuntimeError, ExecutionEngineResult, StackTraceEntry};
 use crate::model::class::{Class, Field};
+use crate::model::verifier::{VerifyError, VerifyErrorMessage};
 
 thread_local!(static CLASS_RESULT: RefCell<u64> = RefCell::new(0));
 thread_local!(static FLOAT_RESULT: RefCell<f32> = RefCell::new(0.0));
@@ -21,13 +22,13 @@ extern "C" fn print_point(ptr: u64) {
 extern "C" fn set_point_x(ptr: u64, value: i32) {
     unsafe {
         let ptr = ptr as *mut i32;
-        *ptr = value;
+        ptr.write_unaligned(value);
     }
 }
 
 extern "C" fn print_array_element(ptr: u64, index: u64) {
     CLASS_RESULT.with(|result| {
-        let class_ptr = unsafe { *((ptr + array::LENGTH_SIZE as u64) as *const u64).offset(index as isize) };
+        let class_ptr = unsafe { ((ptr + array::LENGTH_SIZE as u64) as *const u64).offset(index as isize).read_unaligned() };
         println!("0x{:x}", class_ptr);
         *result.borrow_mut() = class_ptr;
     });
@@ -71,7 +72,7 @@ fn test_create1() {
             Field::new("x".to_owned(), TypeId::Int32),
             Field::new("y".to_owned(), TypeId::Int32),
         ]
-    ));
+    )).unwrap();
 
     let execution_result = vm.execute().unwrap();
     assert_eq!(0, execution_result);
@@ -97,7 +98,7 @@ fn test_load1() {
             Field::new("x".to_owned(), TypeId::Int32),
             Field::new("y".to_owned(), TypeId::Int32),
         ]
-    ));
+    )).unwrap();
 
     vm.add_function(Function::new(
         FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
@@ -130,7 +131,7 @@ fn test_store1() {
             Field::new("x".to_owned(), TypeId::Int32),
             Field::new("y".to_owned(), TypeId::Int32),
         ]
-    ));
+    )).unwrap();
 
     vm.add_function(Function::new(
         FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
@@ -167,7 +168,7 @@ fn test_store2() {
             Field::new("x".to_owned(), TypeId::Float32),
             Field::new("y".to_owned(), TypeId::Float32),
         ]
-    ));
+    )).unwrap();
 
     vm.add_external_function(
         FunctionDeclaration::with_external(
@@ -220,7 +221,7 @@ fn test_store3() {
             Field::new("x".to_owned(), TypeId::Int32),
             Field::new("y".to_owned(), TypeId::Int32),
         ]
-    ));
+    )).unwrap();
 
     vm.add_function(Function::new(
         FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
@@ -266,7 +267,7 @@ fn test_array1() {
             Field::new("x".to_owned(), TypeId::Int32),
             Field::new("y".to_owned(), TypeId::Int32),
         ]
-    ));
+    )).unwrap();
 
     vm.add_function(Function::new(
         FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
@@ -331,7 +332,7 @@ fn test_branch1() {
             Field::new("x".to_owned(), TypeId::Int32),
             Field::new("y".to_owned(), TypeId::Int32),
         ]
-    ));
+    )).unwrap();
 
     let execution_result = vm.execute().unwrap();
     assert_eq!(2000, execution_result);
@@ -370,7 +371,7 @@ fn test_branch2() {
             Field::new("x".to_owned(), TypeId::Int32),
             Field::new("y".to_owned(), TypeId::Int32),
         ]
-    ));
+    )).unwrap();
 
     let execution_result = vm.execute().unwrap();
     assert_eq!(1000, execution_result);
@@ -406,7 +407,7 @@ fn test_branch3() {
             Field::new("x".to_owned(), TypeId::Int32),
             Field::new("y".to_owned(), TypeId::Int32),
         ]
-    ));
+    )).unwrap();
 
     let execution_result = vm.execute().unwrap();
     assert_eq!(1000, execution_result);
@@ -422,7 +423,7 @@ fn test_call_member1() {
             Field::new("x".to_owned(), TypeId::Int32),
             Field::new("y".to_owned(), TypeId::Int32),
         ]
-    ));
+    )).unwrap();
 
     vm.add_function(Function::new(
         FunctionDeclaration::with_managed_member("sum".to_owned(), TypeId::Class("Point".to_owned()), Vec::new(), TypeId::Int32),
@@ -461,7 +462,7 @@ fn test_call_member2() {
             Field::new("x".to_owned(), TypeId::Int32),
             Field::new("y".to_owned(), TypeId::Int32),
         ]
-    ));
+    )).unwrap();
 
     vm.add_function(Function::new(
         FunctionDeclaration::with_managed_member("sum".to_owned(), TypeId::Class("Point".to_owned()), Vec::new(), TypeId::Int32),
@@ -511,7 +512,7 @@ fn test_call_member3() {
             Field::new("x".to_owned(), TypeId::Int32),
             Field::new("y".to_owned(), TypeId::Int32),
         ]
-    ));
+    )).unwrap();
 
     vm.add_function(Function::new(
         FunctionDeclaration::with_managed_member("sum".to_owned(), TypeId::Class("Point".to_owned()), Vec::new(), TypeId::Int32),
@@ -534,7 +535,10 @@ fn test_call_member3() {
 
     let execution_result = vm.execute();
     assert_eq!(
-        Err(ExecutionEngineError::Runtime(RuntimeError::NullReference)),
+        Err(ExecutionEngineError::Runtime(
+            RuntimeError::NullReference,
+            vec![StackTraceEntry { signature: FunctionSignature::new("main".to_owned(), Vec::new()), instruction_index: 1 }]
+        )),
         execution_result
     );
 }
@@ -549,7 +553,7 @@ fn test_call_member4() {
             Field::new("x".to_owned(), TypeId::Int32),
             Field::new("y".to_owned(), TypeId::Int32),
         ]
-    ));
+    )).unwrap();
 
     vm.add_function(Function::new(
         FunctionDeclaration::with_managed_member("sum".to_owned(), TypeId::Class("Point".to_owned()), Vec::new(), TypeId::Int32),
@@ -594,7 +598,7 @@ fn test_call_member5() {
             Field::new("x".to_owned(), TypeId::Int32),
             Field::new("y".to_owned(), TypeId::Int32),
         ]
-    ));
+    )).unwrap();
 
     vm.add_function(Function::new(
         FunctionDeclaration::with_managed_member("sum".to_owned(), TypeId::Class("Point".to_owned()), Vec::new(), TypeId::Int32),
@@ -648,7 +652,7 @@ fn test_call_member6() {
             Field::new("x".to_owned(), TypeId::Int32),
             Field::new("y".to_owned(), TypeId::Int32),
         ]
-    ));
+    )).unwrap();
 
     vm.add_function(Function::new(
         FunctionDeclaration::with_managed_member("sum".to_owned(), TypeId::Class("Point".to_owned()), vec![TypeId::Int32], TypeId::Int32),
@@ -691,4 +695,854 @@ fn test_call_member6() {
 
     let execution_result = vm.execute().unwrap();
     assert_eq!(4711 + 1314 + 1337, execution_result);
-}
\ No newline at end of file
+}
+fn add_shape_classes(vm: &mut VirtualMachine) {
+    let shape = Class::new("Shape".to_owned(), vec![Field::new("size".to_owned(), TypeId::Int32)]);
+    let circle = Class::with_base("Circle".to_owned(), &shape, Vec::new());
+    let square = Class::with_base("Square".to_owned(), &shape, vec![Field::new("scale".to_owned(), TypeId::Int32)]);
+    vm.add_class(shape).unwrap();
+    vm.add_class(circle).unwrap();
+    vm.add_class(square).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed_virtual_member("area".to_owned(), TypeId::Class("Shape".to_owned()), Vec::new(), TypeId::Int32, VirtualModifier::Virtual),
+        Vec::new(),
+        vec![
+            Instruction::LoadInt32(0),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed_virtual_member("area".to_owned(), TypeId::Class("Circle".to_owned()), Vec::new(), TypeId::Int32, VirtualModifier::Override),
+        Vec::new(),
+        vec![
+            Instruction::LoadArgument(0),
+            Instruction::LoadField("Shape".to_owned(), "size".to_owned()),
+            Instruction::LoadInt32(3),
+            Instruction::Multiply,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed_virtual_member("area".to_owned(), TypeId::Class("Square".to_owned()), Vec::new(), TypeId::Int32, VirtualModifier::Override),
+        Vec::new(),
+        vec![
+            Instruction::LoadArgument(0),
+            Instruction::LoadField("Square".to_owned(), "size".to_owned()),
+            Instruction::LoadArgument(0),
+            Instruction::LoadField("Square".to_owned(), "scale".to_owned()),
+            Instruction::Multiply,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("area_of".to_owned(), vec![TypeId::Class("Shape".to_owned())], TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadArgument(0),
+            Instruction::CallInstance(FunctionSignature::with_class("area".to_owned(), TypeId::Class("Shape".to_owned()), Vec::new())),
+            Instruction::Return,
+        ]
+    )).unwrap();
+}
+
+#[test]
+fn test_inheritance1() {
+    let mut vm = VirtualMachine::new();
+
+    let point = Class::new(
+        "Point".to_owned(),
+        vec![
+            Field::new("x".to_owned(), TypeId::Int32),
+            Field::new("y".to_owned(), TypeId::Int32),
+        ]
+    );
+    let point3 = Class::with_base("Point3".to_owned(), &point, vec![Field::new("z".to_owned(), TypeId::Int32)]);
+    vm.add_class(point).unwrap();
+    vm.add_class(point3).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed_member("sum".to_owned(), TypeId::Class("Point".to_owned()), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadArgument(0),
+            Instruction::LoadField("Point".to_owned(), "x".to_owned()),
+            Instruction::LoadArgument(0),
+            Instruction::LoadField("Point".to_owned(), "y".to_owned()),
+            Instruction::Add,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Class("Point3".to_owned())],
+        vec![
+            Instruction::NewObject("Point3".to_owned()),
+            Instruction::StoreLocal(0),
+
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(4711),
+            Instruction::StoreField("Point".to_owned(), "x".to_owned()),
+
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(1337),
+            Instruction::StoreField("Point3".to_owned(), "y".to_owned()),
+
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(1000),
+            Instruction::StoreField("Point3".to_owned(), "z".to_owned()),
+
+            Instruction::LoadLocal(0),
+            Instruction::CallInstance(FunctionSignature::with_class("sum".to_owned(), TypeId::Class("Point3".to_owned()), Vec::new())),
+            Instruction::LoadLocal(0),
+            Instruction::LoadField("Point3".to_owned(), "z".to_owned()),
+            Instruction::Add,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(4711 + 1337 + 1000, execution_result);
+}
+
+#[test]
+fn test_virtual1() {
+    let mut vm = VirtualMachine::new();
+    add_shape_classes(&mut vm);
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Class("Shape".to_owned()), TypeId::Class("Square".to_owned()), TypeId::Int32],
+        vec![
+            Instruction::NewObject("Circle".to_owned()),
+            Instruction::StoreLocal(0),
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(2),
+            Instruction::StoreField("Shape".to_owned(), "size".to_owned()),
+
+            Instruction::NewObject("Square".to_owned()),
+            Instruction::StoreLocal(1),
+            Instruction::LoadLocal(1),
+            Instruction::LoadInt32(5),
+            Instruction::StoreField("Square".to_owned(), "size".to_owned()),
+            Instruction::LoadLocal(1),
+            Instruction::LoadInt32(7),
+            Instruction::StoreField("Square".to_owned(), "scale".to_owned()),
+
+            Instruction::LoadLocal(0),
+            Instruction::Call(FunctionSignature::new("area_of".to_owned(), vec![TypeId::Class("Shape".to_owned())])),
+            Instruction::LoadInt32(10000),
+            Instruction::Multiply,
+            Instruction::StoreLocal(2),
+
+            Instruction::LoadLocal(1),
+            Instruction::Call(FunctionSignature::new("area_of".to_owned(), vec![TypeId::Class("Shape".to_owned())])),
+            Instruction::LoadInt32(100),
+            Instruction::Multiply,
+            Instruction::LoadLocal(2),
+            Instruction::Add,
+            Instruction::StoreLocal(2),
+
+            Instruction::NewObject("Shape".to_owned()),
+            Instruction::CallInstance(FunctionSignature::with_class("area".to_owned(), TypeId::Class("Shape".to_owned()), Vec::new())),
+            Instruction::LoadInt32(1),
+            Instruction::Add,
+            Instruction::LoadLocal(2),
+            Instruction::Add,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(6 * 10000 + 35 * 100 + 1, execution_result);
+}
+
+#[test]
+fn test_virtual2() {
+    let mut vm = VirtualMachine::new();
+
+    let base = Class::new("Base".to_owned(), vec![Field::new("value".to_owned(), TypeId::Int32)]);
+    let middle = Class::with_base("Middle".to_owned(), &base, Vec::new());
+    let derived = Class::with_base("Derived".to_owned(), &middle, Vec::new());
+    vm.add_class(base).unwrap();
+    vm.add_class(middle).unwrap();
+    vm.add_class(derived).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed_virtual_member("apply".to_owned(), TypeId::Class("Base".to_owned()), vec![TypeId::Int32], TypeId::Int32, VirtualModifier::Virtual),
+        Vec::new(),
+        vec![
+            Instruction::LoadArgument(1),
+            Instruction::LoadArgument(0),
+            Instruction::LoadField("Base".to_owned(), "value".to_owned()),
+            Instruction::Add,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed_virtual_member("apply".to_owned(), TypeId::Class("Middle".to_owned()), vec![TypeId::Int32], TypeId::Int32, VirtualModifier::Override),
+        Vec::new(),
+        vec![
+            Instruction::LoadArgument(1),
+            Instruction::LoadArgument(0),
+            Instruction::LoadField("Base".to_owned(), "value".to_owned()),
+            Instruction::Multiply,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed_virtual_member("scale".to_owned(), TypeId::Class("Derived".to_owned()), Vec::new(), TypeId::Int32, VirtualModifier::Virtual),
+        Vec::new(),
+        vec![
+            Instruction::LoadInt32(10),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Class("Derived".to_owned())],
+        vec![
+            Instruction::NewObject("Derived".to_owned()),
+            Instruction::StoreLocal(0),
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(3),
+            Instruction::StoreField("Base".to_owned(), "value".to_owned()),
+
+            // Dispatches to the function in the middle class
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(4),
+            Instruction::CallInstance(FunctionSignature::with_class("apply".to_owned(), TypeId::Class("Derived".to_owned()), vec![TypeId::Int32])),
+
+            Instruction::LoadLocal(0),
+            Instruction::CallInstance(FunctionSignature::with_class("scale".to_owned(), TypeId::Class("Derived".to_owned()), Vec::new())),
+            Instruction::Multiply,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(3 * 4 * 10, execution_result);
+}
+
+#[test]
+fn test_virtual3() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_class(Class::new("Point".to_owned(), Vec::new())).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed_virtual_member("sum".to_owned(), TypeId::Class("Point".to_owned()), Vec::new(), TypeId::Int32, VirtualModifier::Override),
+        Vec::new(),
+        vec![
+            Instruction::LoadInt32(0),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadInt32(0),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    assert_eq!(
+        Err(ExecutionEngineError::Verify(VerifyError::new(VerifyErrorMessage::NoVirtualFunctionToOverride(
+            FunctionSignature::with_class("sum".to_owned(), TypeId::Class("Point".to_owned()), Vec::new())
+        )))),
+        vm.execute()
+    );
+}
+
+#[test]
+fn test_virtual_null1() {
+    let mut vm = VirtualMachine::new();
+    add_shape_classes(&mut vm);
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadNull(TypeId::Class("Circle".to_owned())),
+            Instruction::Call(FunctionSignature::new("area_of".to_owned(), vec![TypeId::Class("Shape".to_owned())])),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute();
+    assert!(matches!(execution_result, Err(ExecutionEngineError::Runtime(RuntimeError::NullReference, _))));
+}
+
+fn add_shape_query_functions(vm: &mut VirtualMachine) {
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("is_square".to_owned(), vec![TypeId::Class("Shape".to_owned())], TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadArgument(0),
+            Instruction::IsInstance("Square".to_owned()),
+            Instruction::ConvertBoolToInt32,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("scale_of".to_owned(), vec![TypeId::Class("Shape".to_owned())], TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadArgument(0),
+            Instruction::CastClass("Square".to_owned()),
+            Instruction::LoadField("Square".to_owned(), "scale".to_owned()),
+            Instruction::Return,
+        ]
+    )).unwrap();
+}
+
+#[test]
+fn test_is_instance1() {
+    let mut vm = VirtualMachine::new();
+    add_shape_classes(&mut vm);
+    add_shape_query_functions(&mut vm);
+
+    let is_square = FunctionSignature::new("is_square".to_owned(), vec![TypeId::Class("Shape".to_owned())]);
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Int32],
+        vec![
+            Instruction::NewObject("Square".to_owned()),
+            Instruction::Call(is_square.clone()),
+            Instruction::LoadInt32(100),
+            Instruction::Multiply,
+            Instruction::StoreLocal(0),
+
+            Instruction::NewObject("Circle".to_owned()),
+            Instruction::Call(is_square.clone()),
+            Instruction::LoadInt32(10),
+            Instruction::Multiply,
+            Instruction::LoadLocal(0),
+            Instruction::Add,
+            Instruction::StoreLocal(0),
+
+            Instruction::LoadNull(TypeId::Class("Square".to_owned())),
+            Instruction::Call(is_square.clone()),
+            Instruction::LoadLocal(0),
+            Instruction::Add,
+            Instruction::StoreLocal(0),
+
+            // Every circle is a shape
+            Instruction::NewObject("Circle".to_owned()),
+            Instruction::IsInstance("Shape".to_owned()),
+            Instruction::ConvertBoolToInt32,
+            Instruction::LoadInt32(1000),
+            Instruction::Multiply,
+            Instruction::LoadLocal(0),
+            Instruction::Add,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(1100, execution_result);
+}
+
+#[test]
+fn test_cast1() {
+    let mut vm = VirtualMachine::new();
+    add_shape_classes(&mut vm);
+    add_shape_query_functions(&mut vm);
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Class("Square".to_owned())],
+        vec![
+            Instruction::NewObject("Square".to_owned()),
+            Instruction::StoreLocal(0),
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(7),
+            Instruction::StoreField("Square".to_owned(), "scale".to_owned()),
+            Instruction::LoadLocal(0),
+            Instruction::Call(FunctionSignature::new("scale_of".to_owned(), vec![TypeId::Class("Shape".to_owned())])),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(7, execution_result);
+}
+
+#[test]
+fn test_cast2() {
+    let mut vm = VirtualMachine::new();
+    add_shape_classes(&mut vm);
+    add_shape_query_functions(&mut vm);
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::NewObject("Circle".to_owned()),
+            Instruction::Call(FunctionSignature::new("scale_of".to_owned(), vec![TypeId::Class("Shape".to_owned())])),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute();
+    assert!(matches!(execution_result, Err(ExecutionEngineError::Runtime(RuntimeError::InvalidCast, _))));
+}
+
+#[test]
+fn test_cast3() {
+    let mut vm = VirtualMachine::new();
+    add_shape_classes(&mut vm);
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            // Null can be cast to any class
+            Instruction::LoadNull(TypeId::Class("Shape".to_owned())),
+            Instruction::CastClass("Square".to_owned()),
+            Instruction::LoadNull(TypeId::Class("Square".to_owned())),
+            Instruction::CompareEqual,
+            Instruction::ConvertBoolToInt32,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(1, execution_result);
+}
+
+fn add_measurable_classes(vm: &mut VirtualMachine) {
+    let measurable = Class::new_interface(
+        "Measurable".to_owned(),
+        vec![
+            FunctionDeclaration::with_interface_member("measure".to_owned(), TypeId::Class("Measurable".to_owned()), Vec::new(), TypeId::Int32),
+            FunctionDeclaration::with_interface_member("scaled".to_owned(), TypeId::Class("Measurable".to_owned()), vec![TypeId::Int32], TypeId::Int32),
+        ]
+    );
+    let named = Class::new_interface(
+        "Named".to_owned(),
+        vec![FunctionDeclaration::with_interface_member("id".to_owned(), TypeId::Class("Named".to_owned()), Vec::new(), TypeId::Int32)]
+    );
+
+    let mut line = Class::new("Line".to_owned(), vec![Field::new("length".to_owned(), TypeId::Int32)]);
+    line.add_interface(&named);
+    line.add_interface(&measurable);
+
+    let mut container = Class::new("Container".to_owned(), Vec::new());
+    container.add_interface(&measurable);
+    let cube = Class::with_base("Cube".to_owned(), &container, Vec::new());
+
+    vm.add_class(measurable).unwrap();
+    vm.add_class(named).unwrap();
+    vm.add_class(line).unwrap();
+    vm.add_class(container).unwrap();
+    vm.add_class(cube).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed_member("id".to_owned(), TypeId::Class("Line".to_owned()), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadInt32(4),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed_member("measure".to_owned(), TypeId::Class("Line".to_owned()), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadArgument(0),
+            Instruction::LoadField("Line".to_owned(), "length".to_owned()),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed_member("scaled".to_owned(), TypeId::Class("Line".to_owned()), vec![TypeId::Int32], TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadArgument(0),
+            Instruction::LoadField("Line".to_owned(), "length".to_owned()),
+            Instruction::LoadArgument(1),
+            Instruction::Multiply,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed_virtual_member("measure".to_owned(), TypeId::Class("Container".to_owned()), Vec::new(), TypeId::Int32, VirtualModifier::Virtual),
+        Vec::new(),
+        vec![
+            Instruction::LoadInt32(2),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed_member("scaled".to_owned(), TypeId::Class("Container".to_owned()), vec![TypeId::Int32], TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadArgument(1),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed_virtual_member("measure".to_owned(), TypeId::Class("Cube".to_owned()), Vec::new(), TypeId::Int32, VirtualModifier::Override),
+        Vec::new(),
+        vec![
+            Instruction::LoadInt32(8),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("measure_of".to_owned(), vec![TypeId::Class("Measurable".to_owned())], TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadArgument(0),
+            Instruction::CallInstance(FunctionSignature::with_class("measure".to_owned(), TypeId::Class("Measurable".to_owned()), Vec::new())),
+            Instruction::LoadArgument(0),
+            Instruction::LoadInt32(10),
+            Instruction::CallInstance(FunctionSignature::with_class("scaled".to_owned(), TypeId::Class("Measurable".to_owned()), vec![TypeId::Int32])),
+            Instruction::Add,
+            Instruction::Return,
+        ]
+    )).unwrap();
+}
+
+#[test]
+fn test_interface1() {
+    let mut vm = VirtualMachine::new();
+    add_measurable_classes(&mut vm);
+
+    let measure_of = FunctionSignature::new("measure_of".to_owned(), vec![TypeId::Class("Measurable".to_owned())]);
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Class("Line".to_owned()), TypeId::Int32],
+        vec![
+            Instruction::NewObject("Line".to_owned()),
+            Instruction::StoreLocal(0),
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(3),
+            Instruction::StoreField("Line".to_owned(), "length".to_owned()),
+
+            Instruction::LoadLocal(0),
+            Instruction::Call(measure_of.clone()),
+            Instruction::LoadInt32(10000),
+            Instruction::Multiply,
+            Instruction::StoreLocal(1),
+
+            // The implementation of the interface is inherited from the base class
+            Instruction::NewObject("Container".to_owned()),
+            Instruction::Call(measure_of.clone()),
+            Instruction::LoadInt32(100),
+            Instruction::Multiply,
+            Instruction::LoadLocal(1),
+            Instruction::Add,
+            Instruction::StoreLocal(1),
+
+            Instruction::NewObject("Cube".to_owned()),
+            Instruction::Call(measure_of.clone()),
+            Instruction::LoadLocal(1),
+            Instruction::Add,
+            Instruction::StoreLocal(1),
+
+            Instruction::LoadLocal(0),
+            Instruction::CallInstance(FunctionSignature::with_class("id".to_owned(), TypeId::Class("Named".to_owned()), Vec::new())),
+            Instruction::LoadInt32(1000000),
+            Instruction::Multiply,
+            Instruction::LoadLocal(1),
+            Instruction::Add,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(4 * 1000000 + 33 * 10000 + 12 * 100 + 18, execution_result);
+}
+
+#[test]
+fn test_interface2() {
+    let mut vm = VirtualMachine::new();
+
+    let measurable = Class::new_interface(
+        "Measurable".to_owned(),
+        vec![FunctionDeclaration::with_interface_member("measure".to_owned(), TypeId::Class("Measurable".to_owned()), Vec::new(), TypeId::Int32)]
+    );
+    let mut line = Class::new("Line".to_owned(), Vec::new());
+    line.add_interface(&measurable);
+    vm.add_class(measurable).unwrap();
+    vm.add_class(line).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadInt32(0),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    assert_eq!(
+        Err(ExecutionEngineError::Verify(VerifyError::new(VerifyErrorMessage::InterfaceFunctionNotImplemented(
+            TypeId::Class("Line".to_owned()),
+            FunctionSignature::with_class("measure".to_owned(), TypeId::Class("Measurable".to_owned()), Vec::new())
+        )))),
+        vm.execute()
+    );
+}
+
+#[test]
+fn test_interface_null1() {
+    let mut vm = VirtualMachine::new();
+    add_measurable_classes(&mut vm);
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadNull(TypeId::Class("Line".to_owned())),
+            Instruction::Call(FunctionSignature::new("measure_of".to_owned(), vec![TypeId::Class("Measurable".to_owned())])),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute();
+    assert!(matches!(execution_result, Err(ExecutionEngineError::Runtime(RuntimeError::NullReference, _))));
+}
+
+fn add_constructed_point_class(vm: &mut VirtualMachine) {
+    vm.add_class(Class::new(
+        "Point".to_owned(),
+        vec![
+            Field::new("x".to_owned(), TypeId::Int32),
+            Field::new("y".to_owned(), TypeId::Int32),
+        ]
+    )).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_constructor(TypeId::Class("Point".to_owned()), Vec::new()),
+        Vec::new(),
+        vec![
+            Instruction::LoadArgument(0),
+            Instruction::LoadInt32(-1),
+            Instruction::StoreField("Point".to_owned(), "x".to_owned()),
+            Instruction::LoadArgument(0),
+            Instruction::LoadInt32(-1),
+            Instruction::StoreField("Point".to_owned(), "y".to_owned()),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_constructor(TypeId::Class("Point".to_owned()), vec![TypeId::Int32, TypeId::Int32]),
+        Vec::new(),
+        vec![
+            Instruction::LoadArgument(0),
+            Instruction::LoadArgument(1),
+            Instruction::StoreField("Point".to_owned(), "x".to_owned()),
+            Instruction::LoadArgument(0),
+            Instruction::LoadArgument(2),
+            Instruction::StoreField("Point".to_owned(), "y".to_owned()),
+
+            // The object being constructed must survive a collection
+            Instruction::Call(FunctionSignature::new("std.gc.collect".to_owned(), Vec::new())),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed_member("sum".to_owned(), TypeId::Class("Point".to_owned()), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadArgument(0),
+            Instruction::LoadField("Point".to_owned(), "x".to_owned()),
+            Instruction::LoadInt32(10),
+            Instruction::Multiply,
+            Instruction::LoadArgument(0),
+            Instruction::LoadField("Point".to_owned(), "y".to_owned()),
+            Instruction::Add,
+            Instruction::Return,
+        ]
+    )).unwrap();
+}
+
+#[test]
+fn test_constructor1() {
+    let mut vm = VirtualMachine::new();
+    add_constructed_point_class(&mut vm);
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadInt32(3),
+            Instruction::LoadInt32(4),
+            Instruction::NewObjectWithConstructor("Point".to_owned(), vec![TypeId::Int32, TypeId::Int32]),
+            Instruction::CallInstance(FunctionSignature::with_class("sum".to_owned(), TypeId::Class("Point".to_owned()), Vec::new())),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(34, execution_result);
+}
+
+#[test]
+fn test_constructor2() {
+    let mut vm = VirtualMachine::new();
+    add_constructed_point_class(&mut vm);
+
+    let sum = FunctionSignature::with_class("sum".to_owned(), TypeId::Class("Point".to_owned()), Vec::new());
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Class("Point".to_owned()), TypeId::Class("Point".to_owned())],
+        vec![
+            // An object below the arguments is kept alive during the collection in the constructor
+            Instruction::NewObjectWithConstructor("Point".to_owned(), Vec::new()),
+            Instruction::LoadInt32(1),
+            Instruction::LoadInt32(2),
+            Instruction::NewObjectWithConstructor("Point".to_owned(), vec![TypeId::Int32, TypeId::Int32]),
+            Instruction::StoreLocal(1),
+            Instruction::StoreLocal(0),
+
+            Instruction::LoadLocal(0),
+            Instruction::CallInstance(sum.clone()),
+            Instruction::LoadInt32(1000),
+            Instruction::Multiply,
+            Instruction::LoadLocal(1),
+            Instruction::CallInstance(sum.clone()),
+            Instruction::Add,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(-11 * 1000 + 12, execution_result);
+}
+
+#[test]
+fn test_constructor3() {
+    let mut vm = VirtualMachine::new();
+    add_constructed_point_class(&mut vm);
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadFloat32(1.0),
+            Instruction::NewObjectWithConstructor("Point".to_owned(), vec![TypeId::Float32]),
+            Instruction::CallInstance(FunctionSignature::with_class("sum".to_owned(), TypeId::Class("Point".to_owned()), Vec::new())),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    assert_eq!(
+        Err(ExecutionEngineError::Verify(VerifyError::with_index(1, VerifyErrorMessage::FunctionNotDefined(
+            FunctionSignature::with_constructor(TypeId::Class("Point".to_owned()), vec![TypeId::Float32])
+        )))),
+        vm.execute()
+    );
+}
+
+fn add_counter_class(vm: &mut VirtualMachine) {
+    let mut class = Class::new("Counter".to_owned(), Vec::new());
+    class.add_static_field(Field::new("count".to_owned(), TypeId::Int32));
+    class.add_static_field(Field::new("scale".to_owned(), TypeId::Float32));
+    vm.add_class(class).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("increment".to_owned(), Vec::new(), TypeId::Void),
+        Vec::new(),
+        vec![
+            Instruction::LoadStaticField("Counter".to_owned(), "count".to_owned()),
+            Instruction::LoadInt32(1),
+            Instruction::Add,
+            Instruction::StoreStaticField("Counter".to_owned(), "count".to_owned()),
+            Instruction::Return,
+        ]
+    )).unwrap();
+}
+
+#[test]
+fn test_static_field1() {
+    let mut vm = VirtualMachine::new();
+    add_counter_class(&mut vm);
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::Call(FunctionSignature::new("increment".to_owned(), Vec::new())),
+            Instruction::Call(FunctionSignature::new("increment".to_owned(), Vec::new())),
+            Instruction::Call(FunctionSignature::new("increment".to_owned(), Vec::new())),
+            Instruction::LoadStaticField("Counter".to_owned(), "count".to_owned()),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(3, execution_result);
+}
+
+#[test]
+fn test_static_field2() {
+    let mut vm = VirtualMachine::new();
+    add_counter_class(&mut vm);
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadFloat32(2.5),
+            Instruction::StoreStaticField("Counter".to_owned(), "scale".to_owned()),
+            Instruction::Call(FunctionSignature::new("increment".to_owned(), Vec::new())),
+            Instruction::LoadStaticField("Counter".to_owned(), "scale".to_owned()),
+            Instruction::LoadFloat32(4.0),
+            Instruction::Multiply,
+            Instruction::ConvertFloat32ToInt32,
+            Instruction::LoadStaticField("Counter".to_owned(), "count".to_owned()),
+            Instruction::Add,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(11, execution_result);
+}
+
+#[test]
+fn test_static_field3() {
+    let mut vm = VirtualMachine::new();
+    add_counter_class(&mut vm);
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadStaticField("Counter".to_owned(), "value".to_owned()),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute();
+    assert_eq!(
+        Err(ExecutionEngineError::Verify(VerifyError::with_index(0, VerifyErrorMessage::FieldNotDefined("Counter".to_owned(), "value".to_owned())))),
+        execution_result
+    );
+}
+
@@ -0,0 +1,275 @@
+use std::cell::RefCell;
+
+use crate::model::function::{Function, FunctionDeclaration, FunctionSignature};
+use crate::model::instruction::Instruction;
+use crate::model::typesystem::TypeId;
+use crate::vm::{VirtualMachine, Value};
+
+thread_local!(static FLOAT_RESULT: RefCell<f32> = RefCell::new(0.0));
+
+extern "C" fn print_float(x: f32) {
+    println!("{}", x);
+    FLOAT_RESULT.with(|result| {
+        *result.borrow_mut() = x;
+    });
+}
+
+#[test]
+fn test_int_to_float1() {
+    FLOAT_RESULT.with(|result| {
+        *result.borrow_mut() = 0.0;
+    });
+
+    let mut vm = VirtualMachine::new();
+
+    vm.add_external_function(
+        FunctionDeclaration::with_external(
+            "print".to_owned(), vec![TypeId::Float32], TypeId::Void,
+            print_float as *mut std::ffi::c_void
+        )
+    );
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadInt32(-4711),
+            Instruction::ConvertInt32ToFloat32,
+            Instruction::LoadFloat32(0.5),
+            Instruction::Add,
+            Instruction::Call(FunctionSignature::new("print".to_owned(), vec![TypeId::Float32])),
+            Instruction::LoadInt32(0),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(0, execution_result);
+    assert_eq!(-4711.0 + 0.5, FLOAT_RESULT.with(|result| *result.borrow()));
+}
+
+#[test]
+fn test_float_to_int1() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadFloat32(47.99),
+            Instruction::ConvertFloat32ToInt32,
+            Instruction::LoadFloat32(-13.37),
+            Instruction::ConvertFloat32ToInt32,
+            Instruction::Add,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(47 - 13, execution_result);
+}
+
+#[test]
+fn test_float_to_int2() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Int32, TypeId::Float32],
+        vec![
+            Instruction::LoadInt32(1000),
+            Instruction::StoreLocal(0),
+            Instruction::LoadLocal(0),
+            Instruction::ConvertInt32ToFloat32,
+            Instruction::LoadFloat32(3.0),
+            Instruction::Divide,
+            Instruction::StoreLocal(1),
+            Instruction::LoadLocal(1),
+            Instruction::ConvertFloat32ToInt32,
+            Instruction::LoadLocal(0),
+            Instruction::Add,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(333 + 1000, execution_result);
+}
+
+#[test]
+fn test_bool_to_int1() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadInt32(4711),
+            Instruction::LoadInt32(1337),
+            Instruction::CompareGreaterThan,
+            Instruction::ConvertBoolToInt32,
+            Instruction::LoadFalse,
+            Instruction::ConvertBoolToInt32,
+            Instruction::LoadInt32(10),
+            Instruction::Add,
+            Instruction::Add,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(11, execution_result);
+}
+
+#[test]
+fn test_bool_to_int2() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Array(Box::new(TypeId::Bool))],
+        vec![
+            Instruction::LoadInt32(2),
+            Instruction::NewArray(TypeId::Bool),
+            Instruction::StoreLocal(0),
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(1),
+            Instruction::LoadTrue,
+            Instruction::StoreElement(TypeId::Bool),
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(1),
+            Instruction::LoadElement(TypeId::Bool),
+            Instruction::ConvertBoolToInt32,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(1, execution_result);
+}
+
+#[test]
+fn test_int32_to_int641() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("convert".to_owned(), vec![TypeId::Int32], TypeId::Int64),
+        Vec::new(),
+        vec![
+            Instruction::LoadArgument(0),
+            Instruction::ConvertInt32ToInt64,
+            Instruction::LoadInt64(1 << 40),
+            Instruction::Add,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let signature = FunctionSignature::new("convert".to_owned(), vec![TypeId::Int32]);
+    assert_eq!(Ok(Value::Int64((1 << 40) - 4711)), vm.call(&signature, &[Value::Int32(-4711)]));
+    assert_eq!(Ok(Value::Int64((1 << 40) + i32::MAX as i64)), vm.call(&signature, &[Value::Int32(i32::MAX)]));
+}
+
+#[test]
+fn test_int64_to_int321() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("convert".to_owned(), vec![TypeId::Int64], TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadArgument(0),
+            Instruction::ConvertInt64ToInt32,
+            Instruction::LoadInt32(1),
+            Instruction::Add,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let signature = FunctionSignature::new("convert".to_owned(), vec![TypeId::Int64]);
+    assert_eq!(Ok(Value::Int32(4711)), vm.call(&signature, &[Value::Int64((1 << 40) + 4710)]));
+    assert_eq!(Ok(Value::Int32(-4)), vm.call(&signature, &[Value::Int64(-5)]));
+}
+
+#[test]
+fn test_float32_to_float641() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("convert".to_owned(), vec![TypeId::Float32], TypeId::Float64),
+        Vec::new(),
+        vec![
+            Instruction::LoadArgument(0),
+            Instruction::ConvertFloat32ToFloat64,
+            Instruction::LoadFloat64(0.25),
+            Instruction::Add,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let signature = FunctionSignature::new("convert".to_owned(), vec![TypeId::Float32]);
+    assert_eq!(Ok(Value::Float64(1.75)), vm.call(&signature, &[Value::Float32(1.5)]));
+    assert_eq!(Ok(Value::Float64(-1e30f32 as f64 + 0.25)), vm.call(&signature, &[Value::Float32(-1e30)]));
+}
+
+#[test]
+fn test_float64_to_float321() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("convert".to_owned(), vec![TypeId::Float64], TypeId::Float32),
+        Vec::new(),
+        vec![
+            Instruction::LoadArgument(0),
+            Instruction::ConvertFloat64ToFloat32,
+            Instruction::LoadFloat32(0.5),
+            Instruction::Add,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let signature = FunctionSignature::new("convert".to_owned(), vec![TypeId::Float64]);
+    assert_eq!(Ok(Value::Float32(0.75)), vm.call(&signature, &[Value::Float64(0.25)]));
+    assert_eq!(Ok(Value::Float32(13.37f64 as f32 + 0.5)), vm.call(&signature, &[Value::Float64(13.37)]));
+}
+
+#[test]
+fn test_int64_to_float641() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("convert".to_owned(), vec![TypeId::Int64], TypeId::Float64),
+        Vec::new(),
+        vec![
+            Instruction::LoadArgument(0),
+            Instruction::ConvertInt64ToFloat64,
+            Instruction::LoadFloat64(0.5),
+            Instruction::Add,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let signature = FunctionSignature::new("convert".to_owned(), vec![TypeId::Int64]);
+    assert_eq!(Ok(Value::Float64(((1u64 << 40) + 1) as f64 + 0.5)), vm.call(&signature, &[Value::Int64((1 << 40) + 1)]));
+    assert_eq!(Ok(Value::Float64(-4710.5)), vm.call(&signature, &[Value::Int64(-4711)]));
+}
+
+#[test]
+fn test_float64_to_int641() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("convert".to_owned(), vec![TypeId::Float64], TypeId::Int64),
+        Vec::new(),
+        vec![
+            Instruction::LoadArgument(0),
+            Instruction::ConvertFloat64ToInt64,
+            Instruction::LoadInt64(1),
+            Instruction::Add,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let signature = FunctionSignature::new("convert".to_owned(), vec![TypeId::Float64]);
+    assert_eq!(Ok(Value::Int64(1_000_000_000_001)), vm.call(&signature, &[Value::Float64(1e12 + 0.75)]));
+    assert_eq!(Ok(Value::Int64(-46)), vm.call(&signature, &[Value::Float64(-47.99)]));
+}
@@ -0,0 +1,433 @@
+use crate::model::function::{Function, FunctionDeclaration, FunctionSignature};
+use crate::model::instruction::Instruction;
+use crate::model::typesystem::TypeId;
+use crate::model::class::Class;
+use crate::vm::VirtualMachine;
+use crate::engine::execution::{ExecutionEngineError, RuntimeError, StackTraceEntry};
+
+#[test]
+fn test_catch1() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_class(Class::new("MyException".to_owned(), vec![])).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Int32, TypeId::Class("MyException".to_owned())],
+        vec![
+            Instruction::LoadInt32(1),
+            Instruction::StoreLocal(0),
+            Instruction::BeginTry,
+            Instruction::NewObject("MyException".to_owned()),
+            Instruction::Throw,
+            Instruction::EndTry,
+            Instruction::BeginCatch("MyException".to_owned()),
+            Instruction::StoreLocal(1),
+            Instruction::LoadInt32(4711),
+            Instruction::StoreLocal(0),
+            Instruction::EndCatch,
+            Instruction::LoadLocal(0),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute();
+    assert_eq!(Ok(4711), execution_result);
+}
+
+#[test]
+fn test_catch2() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("length".to_owned(), vec![TypeId::Array(Box::new(TypeId::Int32))], TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadArgument(0),
+            Instruction::LoadArrayLength,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Int32, TypeId::Class("std.NullReferenceException".to_owned())],
+        vec![
+            Instruction::BeginTry,
+            Instruction::LoadNull(TypeId::Array(Box::new(TypeId::Int32))),
+            Instruction::Call(FunctionSignature::new("length".to_owned(), vec![TypeId::Array(Box::new(TypeId::Int32))])),
+            Instruction::StoreLocal(0),
+            Instruction::EndTry,
+            Instruction::BeginCatch("std.NullReferenceException".to_owned()),
+            Instruction::StoreLocal(1),
+            Instruction::LoadInt32(1337),
+            Instruction::StoreLocal(0),
+            Instruction::EndCatch,
+            Instruction::LoadLocal(0),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute();
+    assert_eq!(Ok(1337), execution_result);
+}
+
+#[test]
+fn test_catch3() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Int32, TypeId::Class("std.DivisionByZeroException".to_owned()), TypeId::Class("std.Exception".to_owned())],
+        vec![
+            Instruction::LoadInt32(0),
+            Instruction::StoreLocal(0),
+            Instruction::BeginTry,
+            Instruction::LoadInt32(4711),
+            Instruction::LoadLocal(0),
+            Instruction::Divide,
+            Instruction::StoreLocal(0),
+            Instruction::EndTry,
+            Instruction::BeginCatch("std.DivisionByZeroException".to_owned()),
+            Instruction::StoreLocal(1),
+            Instruction::LoadInt32(1),
+            Instruction::StoreLocal(0),
+            Instruction::EndCatch,
+            Instruction::BeginCatch("std.Exception".to_owned()),
+            Instruction::StoreLocal(2),
+            Instruction::LoadInt32(2),
+            Instruction::StoreLocal(0),
+            Instruction::EndCatch,
+            Instruction::LoadLocal(0),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute();
+    assert_eq!(Ok(1), execution_result);
+}
+
+#[test]
+fn test_catch4() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Int32, TypeId::Class("std.Exception".to_owned())],
+        vec![
+            Instruction::BeginTry,
+            Instruction::LoadInt32(4),
+            Instruction::NewArray(TypeId::Int32),
+            Instruction::LoadInt32(10),
+            Instruction::LoadElement(TypeId::Int32),
+            Instruction::StoreLocal(0),
+            Instruction::EndTry,
+            Instruction::BeginCatch("std.Exception".to_owned()),
+            Instruction::StoreLocal(1),
+            Instruction::LoadInt32(2),
+            Instruction::StoreLocal(0),
+            Instruction::EndCatch,
+            Instruction::LoadLocal(0),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute();
+    assert_eq!(Ok(2), execution_result);
+}
+
+#[test]
+fn test_catch5() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_class(Class::new("MyException".to_owned(), vec![])).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Int32, TypeId::Class("std.NullReferenceException".to_owned())],
+        vec![
+            Instruction::BeginTry,
+            Instruction::LoadNull(TypeId::Class("MyException".to_owned())),
+            Instruction::Throw,
+            Instruction::EndTry,
+            Instruction::BeginCatch("std.NullReferenceException".to_owned()),
+            Instruction::StoreLocal(1),
+            Instruction::LoadInt32(1337),
+            Instruction::StoreLocal(0),
+            Instruction::EndCatch,
+            Instruction::LoadLocal(0),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute();
+    assert_eq!(Ok(1337), execution_result);
+}
+
+#[test]
+fn test_catch6() {
+    let mut vm = VirtualMachine::new();
+
+    let base_error = Class::new("BaseError".to_owned(), vec![]);
+    let derived_error = Class::with_base("DerivedError".to_owned(), &base_error, vec![]);
+    vm.add_class(base_error).unwrap();
+    vm.add_class(derived_error).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Int32, TypeId::Class("BaseError".to_owned())],
+        vec![
+            Instruction::LoadInt32(1),
+            Instruction::StoreLocal(0),
+            Instruction::BeginTry,
+            Instruction::NewObject("DerivedError".to_owned()),
+            Instruction::Throw,
+            Instruction::EndTry,
+            Instruction::BeginCatch("BaseError".to_owned()),
+            Instruction::StoreLocal(1),
+            Instruction::LoadInt32(4711),
+            Instruction::StoreLocal(0),
+            Instruction::EndCatch,
+            Instruction::LoadLocal(0),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute();
+    assert_eq!(Ok(4711), execution_result);
+}
+
+#[test]
+fn test_catch7() {
+    let mut vm = VirtualMachine::new();
+
+    let base_error = Class::new("BaseError".to_owned(), vec![]);
+    let derived_error = Class::with_base("DerivedError".to_owned(), &base_error, vec![]);
+    vm.add_class(base_error).unwrap();
+    vm.add_class(derived_error).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Int32, TypeId::Class("DerivedError".to_owned())],
+        vec![
+            Instruction::LoadInt32(1),
+            Instruction::StoreLocal(0),
+            Instruction::BeginTry,
+            Instruction::NewObject("BaseError".to_owned()),
+            Instruction::Throw,
+            Instruction::EndTry,
+            Instruction::BeginCatch("DerivedError".to_owned()),
+            Instruction::StoreLocal(1),
+            Instruction::LoadInt32(4711),
+            Instruction::StoreLocal(0),
+            Instruction::EndCatch,
+            Instruction::LoadLocal(0),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute();
+    assert!(matches!(execution_result, Err(ExecutionEngineError::Runtime(RuntimeError::UnhandledException(class_name), _)) if class_name == "BaseError"));
+}
+
+#[test]
+fn test_catch_invalid_cast1() {
+    let mut vm = VirtualMachine::new();
+    vm.add_class(Class::new("Point".to_owned(), Vec::new())).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Int32, TypeId::Class("std.Exception".to_owned()), TypeId::Class("std.InvalidCastException".to_owned())],
+        vec![
+            Instruction::LoadInt32(0),
+            Instruction::StoreLocal(0),
+            Instruction::BeginTry,
+            Instruction::NewObject("std.Exception".to_owned()),
+            Instruction::CastClass("Point".to_owned()),
+            Instruction::Throw,
+            Instruction::EndTry,
+            Instruction::BeginCatch("std.InvalidCastException".to_owned()),
+            Instruction::StoreLocal(2),
+            Instruction::LoadInt32(4711),
+            Instruction::StoreLocal(0),
+            Instruction::EndCatch,
+            Instruction::LoadLocal(0),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute();
+    assert_eq!(Ok(4711), execution_result);
+}
+
+#[test]
+fn test_finally1() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Int32],
+        vec![
+            Instruction::BeginTry,
+            Instruction::LoadInt32(1),
+            Instruction::StoreLocal(0),
+            Instruction::EndTry,
+            Instruction::BeginFinally,
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(10),
+            Instruction::Add,
+            Instruction::StoreLocal(0),
+            Instruction::EndFinally,
+            Instruction::LoadLocal(0),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute();
+    assert_eq!(Ok(11), execution_result);
+}
+
+#[test]
+fn test_finally2() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_class(Class::new("MyException".to_owned(), vec![])).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("throw".to_owned(), Vec::new(), TypeId::Void),
+        Vec::new(),
+        vec![
+            Instruction::NewObject("MyException".to_owned()),
+            Instruction::Throw,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Int32, TypeId::Class("MyException".to_owned())],
+        vec![
+            Instruction::LoadInt32(1),
+            Instruction::StoreLocal(0),
+            Instruction::BeginTry,
+            Instruction::BeginTry,
+            Instruction::Call(FunctionSignature::new("throw".to_owned(), Vec::new())),
+            Instruction::EndTry,
+            Instruction::BeginFinally,
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(10),
+            Instruction::Add,
+            Instruction::StoreLocal(0),
+            Instruction::EndFinally,
+            Instruction::EndTry,
+            Instruction::BeginCatch("MyException".to_owned()),
+            Instruction::StoreLocal(1),
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(100),
+            Instruction::Add,
+            Instruction::StoreLocal(0),
+            Instruction::EndCatch,
+            Instruction::LoadLocal(0),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute();
+    assert_eq!(Ok(111), execution_result);
+}
+
+#[test]
+fn test_finally3() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_class(Class::new("MyException".to_owned(), vec![])).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Int32, TypeId::Class("MyException".to_owned())],
+        vec![
+            Instruction::BeginTry,
+            Instruction::NewObject("MyException".to_owned()),
+            Instruction::Throw,
+            Instruction::EndTry,
+            Instruction::BeginCatch("MyException".to_owned()),
+            Instruction::StoreLocal(1),
+            Instruction::LoadInt32(1),
+            Instruction::StoreLocal(0),
+            Instruction::EndCatch,
+            Instruction::BeginFinally,
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(2),
+            Instruction::Multiply,
+            Instruction::StoreLocal(0),
+            Instruction::EndFinally,
+            Instruction::LoadLocal(0),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute();
+    assert_eq!(Ok(2), execution_result);
+}
+
+#[test]
+fn test_unhandled1() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_class(Class::new("MyException".to_owned(), vec![])).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::NewObject("MyException".to_owned()),
+            Instruction::Throw,
+            Instruction::LoadInt32(0),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute();
+    assert_eq!(
+        Err(ExecutionEngineError::Runtime(
+            RuntimeError::UnhandledException("MyException".to_owned()),
+            vec![StackTraceEntry { signature: FunctionSignature::new("main".to_owned(), Vec::new()), instruction_index: 1 }]
+        )),
+        execution_result
+    );
+}
+
+#[test]
+fn test_unhandled2() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Int32, TypeId::Class("std.ArrayBoundsException".to_owned())],
+        vec![
+            Instruction::BeginTry,
+            Instruction::LoadNull(TypeId::Array(Box::new(TypeId::Int32))),
+            Instruction::LoadArrayLength,
+            Instruction::StoreLocal(0),
+            Instruction::EndTry,
+            Instruction::BeginCatch("std.ArrayBoundsException".to_owned()),
+            Instruction::StoreLocal(1),
+            Instruction::EndCatch,
+            Instruction::BeginFinally,
+            Instruction::LoadInt32(1),
+            Instruction::StoreLocal(0),
+            Instruction::EndFinally,
+            Instruction::LoadLocal(0),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute();
+    assert_eq!(
+        Err(ExecutionEngineError::Runtime(
+            RuntimeError::NullReference,
+            vec![StackTraceEntry { signature: FunctionSignature::new("main".to_owned(), Vec::new()), instruction_index: 11 }]
+        )),
+        execution_result
+    );
+}
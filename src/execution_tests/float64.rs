@@ -0,0 +1,267 @@
+use std::cell::RefCell;
+
+use crate::model::function::{Function, FunctionDeclaration, FunctionSignature};
+use crate::model::instruction::Instruction;
+use crate::model::typesystem::TypeId;
+use crate::model::class::{Class, Field};
+use crate::vm::VirtualMachine;
+
+thread_local!(static FLOAT64_RESULT: RefCell<f64> = RefCell::new(0.0));
+
+extern "C" fn print_float64(x: f64) {
+    println!("{}", x);
+    FLOAT64_RESULT.with(|result| {
+        *result.borrow_mut() = x;
+    });
+}
+
+extern "C" fn sum_float64(x0: f64, x1: f32, x2: f64, x3: f64, x4: f64, x5: f64, x6: f64, x7: f64, x8: f64, x9: f64) -> f64 {
+    x0 + x1 as f64 + x2 + x3 + x4 + x5 + x6 + x7 + x8 + x9
+}
+
+fn add_print_float64(vm: &mut VirtualMachine) {
+    FLOAT64_RESULT.with(|result| {
+        *result.borrow_mut() = 0.0;
+    });
+
+    vm.add_external_function(
+        FunctionDeclaration::with_external(
+            "print".to_owned(), vec![TypeId::Float64], TypeId::Void,
+            print_float64 as *mut std::ffi::c_void
+        )
+    );
+}
+
+fn print_float64_call() -> Instruction {
+    Instruction::Call(FunctionSignature::new("print".to_owned(), vec![TypeId::Float64]))
+}
+
+#[test]
+fn test_arithmetic1() {
+    let mut vm = VirtualMachine::new();
+    add_print_float64(&mut vm);
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadFloat64(13.37),
+            Instruction::LoadFloat64(47.11),
+            Instruction::Add,
+            Instruction::LoadFloat64(0.1),
+            Instruction::Sub,
+            print_float64_call(),
+            Instruction::LoadInt32(0),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(0, execution_result);
+    assert_eq!(13.37 + 47.11 - 0.1, FLOAT64_RESULT.with(|result| *result.borrow()));
+}
+
+#[test]
+fn test_arithmetic2() {
+    let mut vm = VirtualMachine::new();
+    add_print_float64(&mut vm);
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Float64],
+        vec![
+            Instruction::LoadFloat64(1.0e100),
+            Instruction::StoreLocal(0),
+            Instruction::LoadLocal(0),
+            Instruction::LoadFloat64(3.0),
+            Instruction::Multiply,
+            Instruction::LoadFloat64(7.0),
+            Instruction::Divide,
+            print_float64_call(),
+            Instruction::LoadInt32(0),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(0, execution_result);
+    assert_eq!(1.0e100 * 3.0 / 7.0, FLOAT64_RESULT.with(|result| *result.borrow()));
+}
+
+#[test]
+fn test_call1() {
+    let mut vm = VirtualMachine::new();
+    add_print_float64(&mut vm);
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadFloat64(0.1),
+            Instruction::LoadFloat64(0.2),
+            Instruction::Call(FunctionSignature::new("add".to_owned(), vec![TypeId::Float64, TypeId::Float64])),
+            print_float64_call(),
+            Instruction::LoadInt32(0),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("add".to_owned(), vec![TypeId::Float64, TypeId::Float64], TypeId::Float64),
+        Vec::new(),
+        vec![
+            Instruction::LoadArgument(0),
+            Instruction::LoadArgument(1),
+            Instruction::Add,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(0, execution_result);
+    assert_eq!(0.1 + 0.2, FLOAT64_RESULT.with(|result| *result.borrow()));
+}
+
+#[test]
+fn test_call2() {
+    let mut vm = VirtualMachine::new();
+    add_print_float64(&mut vm);
+
+    let parameters = vec![
+        TypeId::Float64, TypeId::Float32, TypeId::Float64, TypeId::Float64, TypeId::Float64,
+        TypeId::Float64, TypeId::Float64, TypeId::Float64, TypeId::Float64, TypeId::Float64
+    ];
+
+    vm.add_external_function(
+        FunctionDeclaration::with_external(
+            "sum".to_owned(), parameters.clone(), TypeId::Float64,
+            sum_float64 as *mut std::ffi::c_void
+        )
+    );
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadFloat64(1.1),
+            Instruction::LoadFloat32(2.5),
+            Instruction::LoadFloat64(3.3),
+            Instruction::LoadFloat64(4.4),
+            Instruction::LoadFloat64(5.5),
+            Instruction::LoadFloat64(6.6),
+            Instruction::LoadFloat64(7.7),
+            Instruction::LoadFloat64(8.8),
+            Instruction::LoadFloat64(9.9),
+            Instruction::LoadFloat64(10.1),
+            Instruction::Call(FunctionSignature::new("sum".to_owned(), parameters.clone())),
+            print_float64_call(),
+            Instruction::LoadInt32(0),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(0, execution_result);
+    assert_eq!(
+        sum_float64(1.1, 2.5, 3.3, 4.4, 5.5, 6.6, 7.7, 8.8, 9.9, 10.1),
+        FLOAT64_RESULT.with(|result| *result.borrow())
+    );
+}
+
+#[test]
+fn test_branches1() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadFloat64(1.0000000001),
+            Instruction::LoadFloat64(1.0),
+            Instruction::BranchGreaterThan(5),
+            Instruction::LoadInt32(1),
+            Instruction::Return,
+            Instruction::LoadInt32(2),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(2, execution_result);
+}
+
+#[test]
+fn test_array1() {
+    let mut vm = VirtualMachine::new();
+    add_print_float64(&mut vm);
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Array(Box::new(TypeId::Float64))],
+        vec![
+            Instruction::LoadInt32(3),
+            Instruction::NewArray(TypeId::Float64),
+            Instruction::StoreLocal(0),
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(1),
+            Instruction::LoadFloat64(0.1),
+            Instruction::StoreElement(TypeId::Float64),
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(2),
+            Instruction::LoadFloat64(0.7),
+            Instruction::StoreElement(TypeId::Float64),
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(1),
+            Instruction::LoadElement(TypeId::Float64),
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(2),
+            Instruction::LoadElement(TypeId::Float64),
+            Instruction::Add,
+            print_float64_call(),
+            Instruction::LoadInt32(0),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(0, execution_result);
+    assert_eq!(0.1 + 0.7, FLOAT64_RESULT.with(|result| *result.borrow()));
+}
+
+#[test]
+fn test_class1() {
+    let mut vm = VirtualMachine::new();
+    add_print_float64(&mut vm);
+
+    vm.add_class(Class::new(
+        "Point".to_owned(),
+        vec![
+            Field::new("x".to_owned(), TypeId::Float32),
+            Field::new("y".to_owned(), TypeId::Float64),
+        ]
+    )).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Class("Point".to_owned())],
+        vec![
+            Instruction::NewObject("Point".to_owned()),
+            Instruction::StoreLocal(0),
+            Instruction::LoadLocal(0),
+            Instruction::LoadFloat64(1.0e-300),
+            Instruction::StoreField("Point".to_owned(), "y".to_owned()),
+            Instruction::LoadLocal(0),
+            Instruction::LoadFloat32(47.11),
+            Instruction::StoreField("Point".to_owned(), "x".to_owned()),
+            Instruction::LoadLocal(0),
+            Instruction::LoadField("Point".to_owned(), "y".to_owned()),
+            print_float64_call(),
+            Instruction::LoadInt32(0),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(0, execution_result);
+    assert_eq!(1.0e-300, FLOAT64_RESULT.with(|result| *result.borrow()));
+}
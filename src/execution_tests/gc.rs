@@ -1,8 +1,10 @@
 use crate::model::function::{FunctionDeclaration, Function, FunctionSignature};
-use crate::vm::{VirtualMachine, get_vm};
+use crate::vm::{VirtualMachine, Value, get_vm};
 use crate::model::instruction::Instruction;
 use crate::model::typesystem::{TypeId, Type};
 use crate::model::class::{Class, Field};
+use crate::engine::execution::{ExecutionEngineError, RuntimeError};
+use crate::runtime::memory::heap::HeapSettings;
 
 #[test]
 fn test_stack_frame1() {
@@ -62,7 +64,7 @@ fn test_collect1() {
             Field::new("x".to_owned(), TypeId::Int32),
             Field::new("y".to_owned(), TypeId::Int32),
         ]
-    ));
+    )).unwrap();
 
     vm.add_function(Function::new(
         FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
@@ -108,7 +110,7 @@ fn test_collect2() {
             Field::new("x".to_owned(), TypeId::Int32),
             Field::new("y".to_owned(), TypeId::Int32),
         ]
-    ));
+    )).unwrap();
 
     let point_type = TypeId::Class("Point".to_owned());
 
@@ -159,7 +161,7 @@ fn test_collect3() {
             Field::new("x".to_owned(), TypeId::Int32),
             Field::new("y".to_owned(), TypeId::Int32),
         ]
-    ));
+    )).unwrap();
 
     vm.add_function(Function::new(
         FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
@@ -197,7 +199,7 @@ fn test_collect4() {
             Field::new("x".to_owned(), TypeId::Int32),
             Field::new("y".to_owned(), TypeId::Int32),
         ]
-    ));
+    )).unwrap();
 
     let point_type = TypeId::Class("Point".to_owned());
 
@@ -239,7 +241,7 @@ fn test_collect5() {
             Field::new("x".to_owned(), TypeId::Int32),
             Field::new("y".to_owned(), TypeId::Int32),
         ]
-    ));
+    )).unwrap();
 
     let point_type = TypeId::Class("Point".to_owned());
 
@@ -372,4 +374,614 @@ fn test_collect8() {
     get_vm(|vm| {
         assert_eq!(0, vm.memory_manager.garbage_collector.deleted_objects().len());
     });
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_automatic_collect1() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Int32, TypeId::Array(Box::new(TypeId::Int32))],
+        vec![
+            Instruction::LoadInt32(0),
+            Instruction::StoreLocal(0),
+
+            Instruction::LoadInt32(1000),
+            Instruction::NewArray(TypeId::Int32),
+            Instruction::StoreLocal(1),
+
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(1),
+            Instruction::Add,
+            Instruction::StoreLocal(0),
+
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(5000),
+            Instruction::BranchLessThan(2),
+
+            Instruction::LoadLocal(1),
+            Instruction::LoadArrayLength,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(1000, execution_result);
+
+    get_vm(|vm| {
+        assert!(!vm.memory_manager.garbage_collector.deleted_objects().is_empty());
+    });
+}
+
+#[test]
+fn test_automatic_collect2() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_class(Class::new(
+        "Point".to_owned(),
+        vec![
+            Field::new("x".to_owned(), TypeId::Int32),
+            Field::new("y".to_owned(), TypeId::Int32),
+        ]
+    )).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("create".to_owned(), vec![TypeId::Int32], TypeId::Class("Point".to_owned())),
+        vec![TypeId::Class("Point".to_owned())],
+        vec![
+            Instruction::NewObject("Point".to_owned()),
+            Instruction::StoreLocal(0),
+
+            Instruction::LoadLocal(0),
+            Instruction::LoadArgument(0),
+            Instruction::StoreField("Point".to_owned(), "x".to_owned()),
+
+            Instruction::LoadLocal(0),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Int32, TypeId::Class("Point".to_owned()), TypeId::Class("Point".to_owned())],
+        vec![
+            Instruction::LoadInt32(4711),
+            Instruction::Call(FunctionSignature::new("create".to_owned(), vec![TypeId::Int32])),
+            Instruction::StoreLocal(1),
+
+            Instruction::LoadInt32(0),
+            Instruction::StoreLocal(0),
+
+            Instruction::LoadLocal(0),
+            Instruction::Call(FunctionSignature::new("create".to_owned(), vec![TypeId::Int32])),
+            Instruction::StoreLocal(2),
+
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(1),
+            Instruction::Add,
+            Instruction::StoreLocal(0),
+
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(1000000),
+            Instruction::BranchLessThan(5),
+
+            Instruction::LoadLocal(1),
+            Instruction::LoadField("Point".to_owned(), "x".to_owned()),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(4711, execution_result);
+}
+
+#[test]
+fn test_out_of_memory1() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Array(Box::new(TypeId::Int32))],
+        vec![
+            Instruction::LoadInt32(128 * 1024 * 1024),
+            Instruction::NewArray(TypeId::Int32),
+            Instruction::StoreLocal(0),
+
+            Instruction::LoadInt32(0),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute();
+    assert!(matches!(execution_result, Err(ExecutionEngineError::Runtime(RuntimeError::OutOfMemory, _))));
+}
+
+#[test]
+fn test_out_of_memory2() {
+    let mut vm = VirtualMachine::with_heap_settings(HeapSettings {
+        max_size: 32 * 1024 * 1024,
+        ..Default::default()
+    }).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Int32, TypeId::Array(Box::new(TypeId::Array(Box::new(TypeId::Int32))))],
+        vec![
+            Instruction::LoadInt32(100),
+            Instruction::NewArray(TypeId::Array(Box::new(TypeId::Int32))),
+            Instruction::StoreLocal(1),
+
+            Instruction::LoadInt32(0),
+            Instruction::StoreLocal(0),
+
+            Instruction::LoadLocal(1),
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(256 * 1024),
+            Instruction::NewArray(TypeId::Int32),
+            Instruction::StoreElement(TypeId::Array(Box::new(TypeId::Int32))),
+
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(1),
+            Instruction::Add,
+            Instruction::StoreLocal(0),
+
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(100),
+            Instruction::BranchLessThan(5),
+
+            Instruction::LoadInt32(0),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute();
+    assert!(matches!(execution_result, Err(ExecutionEngineError::Runtime(RuntimeError::OutOfMemory, _))));
+}
+
+#[test]
+fn test_generational1() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_class(Class::new(
+        "Node".to_owned(),
+        vec![
+            Field::new("value".to_owned(), TypeId::Int32),
+            Field::new("next".to_owned(), TypeId::Class("Node".to_owned())),
+        ]
+    )).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Int32, TypeId::Class("Node".to_owned()), TypeId::Array(Box::new(TypeId::Int32))],
+        vec![
+            Instruction::NewObject("Node".to_owned()),
+            Instruction::StoreLocal(1),
+
+            // Allocate until the node has been promoted
+            Instruction::LoadInt32(0),
+            Instruction::StoreLocal(0),
+
+            Instruction::LoadInt32(1000),
+            Instruction::NewArray(TypeId::Int32),
+            Instruction::StoreLocal(2),
+
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(1),
+            Instruction::Add,
+            Instruction::StoreLocal(0),
+
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(1000),
+            Instruction::BranchLessThan(4),
+
+            // Old node now references young node
+            Instruction::LoadLocal(1),
+            Instruction::NewObject("Node".to_owned()),
+            Instruction::StoreField("Node".to_owned(), "next".to_owned()),
+
+            Instruction::LoadLocal(1),
+            Instruction::LoadField("Node".to_owned(), "next".to_owned()),
+            Instruction::LoadInt32(4711),
+            Instruction::StoreField("Node".to_owned(), "value".to_owned()),
+
+            // The young node is only reachable through the old node
+            Instruction::LoadInt32(0),
+            Instruction::StoreLocal(0),
+
+            Instruction::LoadInt32(1000),
+            Instruction::NewArray(TypeId::Int32),
+            Instruction::StoreLocal(2),
+
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(1),
+            Instruction::Add,
+            Instruction::StoreLocal(0),
+
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(1000),
+            Instruction::BranchLessThan(23),
+
+            Instruction::LoadLocal(1),
+            Instruction::LoadField("Node".to_owned(), "next".to_owned()),
+            Instruction::LoadField("Node".to_owned(), "value".to_owned()),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(4711, execution_result);
+
+    get_vm(|vm| {
+        assert!(vm.memory_manager.old_generation.used_size() > 0);
+    });
+}
+
+#[test]
+fn test_generational2() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_class(Class::new(
+        "Point".to_owned(),
+        vec![
+            Field::new("x".to_owned(), TypeId::Int32),
+            Field::new("y".to_owned(), TypeId::Int32),
+        ]
+    )).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Int32, TypeId::Array(Box::new(TypeId::Class("Point".to_owned()))), TypeId::Array(Box::new(TypeId::Int32))],
+        vec![
+            Instruction::LoadInt32(10),
+            Instruction::NewArray(TypeId::Class("Point".to_owned())),
+            Instruction::StoreLocal(1),
+
+            // Allocate until the array has been promoted
+            Instruction::LoadInt32(0),
+            Instruction::StoreLocal(0),
+
+            Instruction::LoadInt32(1000),
+            Instruction::NewArray(TypeId::Int32),
+            Instruction::StoreLocal(2),
+
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(1),
+            Instruction::Add,
+            Instruction::StoreLocal(0),
+
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(1000),
+            Instruction::BranchLessThan(5),
+
+            // Old array now references young point
+            Instruction::LoadLocal(1),
+            Instruction::LoadInt32(3),
+            Instruction::NewObject("Point".to_owned()),
+            Instruction::StoreElement(TypeId::Class("Point".to_owned())),
+
+            Instruction::LoadLocal(1),
+            Instruction::LoadInt32(3),
+            Instruction::LoadElement(TypeId::Class("Point".to_owned())),
+            Instruction::LoadInt32(1337),
+            Instruction::StoreField("Point".to_owned(), "y".to_owned()),
+
+            // The young point is only reachable through the old array
+            Instruction::LoadInt32(0),
+            Instruction::StoreLocal(0),
+
+            Instruction::LoadInt32(1000),
+            Instruction::NewArray(TypeId::Int32),
+            Instruction::StoreLocal(2),
+
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(1),
+            Instruction::Add,
+            Instruction::StoreLocal(0),
+
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(1000),
+            Instruction::BranchLessThan(26),
+
+            Instruction::LoadLocal(1),
+            Instruction::LoadInt32(3),
+            Instruction::LoadElement(TypeId::Class("Point".to_owned())),
+            Instruction::LoadField("Point".to_owned(), "y".to_owned()),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(1337, execution_result);
+}
+
+#[test]
+fn test_grow_heap1() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Int32, TypeId::Array(Box::new(TypeId::Array(Box::new(TypeId::Int32))))],
+        vec![
+            Instruction::LoadInt32(20),
+            Instruction::NewArray(TypeId::Array(Box::new(TypeId::Int32))),
+            Instruction::StoreLocal(1),
+
+            Instruction::LoadInt32(0),
+            Instruction::StoreLocal(0),
+
+            Instruction::LoadLocal(1),
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(256 * 1024),
+            Instruction::NewArray(TypeId::Int32),
+            Instruction::StoreElement(TypeId::Array(Box::new(TypeId::Int32))),
+
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(1),
+            Instruction::Add,
+            Instruction::StoreLocal(0),
+
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(20),
+            Instruction::BranchLessThan(5),
+
+            Instruction::LoadLocal(1),
+            Instruction::LoadInt32(19),
+            Instruction::LoadElement(TypeId::Array(Box::new(TypeId::Int32))),
+            Instruction::LoadArrayLength,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(256 * 1024, execution_result);
+
+    get_vm(|vm| {
+        assert!(vm.memory_manager.old_generation.segments().len() > 1);
+        assert!(vm.memory_manager.old_generation.used_size() > 20 * 1024 * 1024);
+    });
+}
+
+#[test]
+fn test_grow_heap2() {
+    let mut vm = VirtualMachine::with_heap_settings(HeapSettings {
+        young_generation_size: 64 * 1024,
+        initial_size: 64 * 1024,
+        max_size: 16 * 1024 * 1024,
+        growth_factor: 1.5
+    }).unwrap();
+
+    vm.add_class(Class::new(
+        "Node".to_owned(),
+        vec![
+            Field::new("value".to_owned(), TypeId::Int32),
+            Field::new("next".to_owned(), TypeId::Class("Node".to_owned())),
+        ]
+    )).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Int32, TypeId::Class("Node".to_owned()), TypeId::Class("Node".to_owned())],
+        vec![
+            Instruction::LoadInt32(0),
+            Instruction::StoreLocal(0),
+
+            // Create a linked list where each node points to the previous one
+            Instruction::NewObject("Node".to_owned()),
+            Instruction::StoreLocal(2),
+
+            Instruction::LoadLocal(2),
+            Instruction::LoadLocal(0),
+            Instruction::StoreField("Node".to_owned(), "value".to_owned()),
+
+            Instruction::LoadLocal(2),
+            Instruction::LoadLocal(1),
+            Instruction::StoreField("Node".to_owned(), "next".to_owned()),
+
+            Instruction::LoadLocal(2),
+            Instruction::StoreLocal(1),
+
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(1),
+            Instruction::Add,
+            Instruction::StoreLocal(0),
+
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(50000),
+            Instruction::BranchLessThan(2),
+
+            // Sum the values in the list
+            Instruction::LoadInt32(0),
+            Instruction::StoreLocal(0),
+
+            Instruction::LoadLocal(0),
+            Instruction::LoadLocal(1),
+            Instruction::LoadField("Node".to_owned(), "value".to_owned()),
+            Instruction::Add,
+            Instruction::StoreLocal(0),
+
+            Instruction::LoadLocal(1),
+            Instruction::LoadField("Node".to_owned(), "next".to_owned()),
+            Instruction::StoreLocal(1),
+
+            Instruction::LoadLocal(1),
+            Instruction::LoadNull(TypeId::Class("Node".to_owned())),
+            Instruction::BranchNotEqual(21),
+
+            Instruction::LoadLocal(0),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!((0..50000).sum::<i32>(), execution_result);
+
+    get_vm(|vm| {
+        assert!(vm.memory_manager.old_generation.segments().len() > 1);
+    });
+}
+
+#[test]
+fn test_pin1() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("create".to_owned(), vec![TypeId::Int32], TypeId::Array(Box::new(TypeId::Int32))),
+        Vec::new(),
+        vec![
+            Instruction::LoadArgument(0),
+            Instruction::NewArray(TypeId::Int32),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("allocate".to_owned(), Vec::new(), TypeId::Void),
+        vec![TypeId::Int32, TypeId::Array(Box::new(TypeId::Int32))],
+        vec![
+            Instruction::LoadInt32(0),
+            Instruction::StoreLocal(0),
+
+            Instruction::LoadInt32(1000),
+            Instruction::NewArray(TypeId::Int32),
+            Instruction::StoreLocal(1),
+
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(1),
+            Instruction::Add,
+            Instruction::StoreLocal(0),
+
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(1000),
+            Instruction::BranchLessThan(2),
+
+            Instruction::Call(FunctionSignature::new("std.gc.collect".to_owned(), Vec::new())),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let create = FunctionSignature::new("create".to_owned(), vec![TypeId::Int32]);
+    let allocate = FunctionSignature::new("allocate".to_owned(), Vec::new());
+
+    let array1 = vm.call(&create, &[Value::Int32(10)]).map(|array| vm.create_root(array).unwrap()).unwrap();
+    let array2 = vm.call(&create, &[Value::Int32(10)]).map(|array| vm.create_root(array).unwrap()).unwrap();
+    let array3 = vm.call(&create, &[Value::Int32(10)]).map(|array| vm.create_root(array).unwrap()).unwrap();
+    for i in 0..10 {
+        vm.memory_manager.set_element(&array2, i, Value::Int32(i as i32 * 3)).unwrap();
+        vm.memory_manager.set_element(&array3, i, Value::Int32(i as i32 * 7)).unwrap();
+    }
+
+    // The freed space before the pinned object can't be used by objects after it
+    vm.release_root(array1);
+    let pinned_array = vm.memory_manager.root_object(&array2);
+    let moved_array = vm.memory_manager.root_object(&array3);
+    vm.memory_manager.garbage_collector.pin(pinned_array);
+
+    assert_eq!(Ok(Value::Void), vm.call(&allocate, &[]));
+    assert_eq!(Ok(Value::Void), vm.call(&allocate, &[]));
+
+    assert_eq!(pinned_array, vm.memory_manager.root_object(&array2));
+    assert!(vm.memory_manager.young_generation.inside(pinned_array));
+    assert_ne!(moved_array, vm.memory_manager.root_object(&array3));
+
+    for i in 0..10 {
+        assert_eq!(Some(Value::Int32(i as i32 * 3)), vm.memory_manager.get_element(&array2, i));
+        assert_eq!(Some(Value::Int32(i as i32 * 7)), vm.memory_manager.get_element(&array3, i));
+    }
+
+    // Once unpinned, the object is promoted as usual
+    vm.memory_manager.garbage_collector.unpin(pinned_array);
+    assert_eq!(Ok(Value::Void), vm.call(&allocate, &[]));
+
+    assert!(vm.memory_manager.old_generation.inside(vm.memory_manager.root_object(&array2)));
+    for i in 0..10 {
+        assert_eq!(Some(Value::Int32(i as i32 * 3)), vm.memory_manager.get_element(&array2, i));
+    }
+}
+
+#[test]
+fn test_static_field1() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_class(Class::new(
+        "Node".to_owned(),
+        vec![
+            Field::new("value".to_owned(), TypeId::Int32),
+        ]
+    )).unwrap();
+
+    let mut class = Class::new("Globals".to_owned(), Vec::new());
+    class.add_static_field(Field::new("node".to_owned(), TypeId::Class("Node".to_owned())));
+    vm.add_class(class).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("create_node".to_owned(), Vec::new(), TypeId::Void),
+        Vec::new(),
+        vec![
+            Instruction::NewObject("Node".to_owned()),
+            Instruction::Duplicate,
+            Instruction::LoadInt32(4711),
+            Instruction::StoreField("Node".to_owned(), "value".to_owned()),
+            Instruction::StoreStaticField("Globals".to_owned(), "node".to_owned()),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Int32, TypeId::Array(Box::new(TypeId::Int32))],
+        vec![
+            Instruction::Call(FunctionSignature::new("create_node".to_owned(), Vec::new())),
+
+            // Allocate until there have been young collections, where the node is only reachable from the static field
+            Instruction::LoadInt32(0),
+            Instruction::StoreLocal(0),
+
+            Instruction::LoadInt32(1000),
+            Instruction::NewArray(TypeId::Int32),
+            Instruction::StoreLocal(1),
+
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(1),
+            Instruction::Add,
+            Instruction::StoreLocal(0),
+
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(1000),
+            Instruction::BranchLessThan(3),
+
+            Instruction::Call(FunctionSignature::new("std.gc.collect".to_string(), vec![])),
+
+            Instruction::LoadStaticField("Globals".to_owned(), "node".to_owned()),
+            Instruction::LoadField("Node".to_owned(), "value".to_owned()),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(4711, execution_result);
+
+    get_vm(|vm| {
+        assert!(vm.memory_manager.garbage_collector.deleted_objects().iter().all(|(_, object_type)| object_type != &TypeId::Class("Node".to_owned())));
+    });
+}
+
+
+#[test]
+fn test_heap_settings1() {
+    let invalid_settings = [
+        HeapSettings { young_generation_size: 0, ..Default::default() },
+        HeapSettings { initial_size: 0, ..Default::default() },
+        HeapSettings { max_size: 0, ..Default::default() },
+        HeapSettings { initial_size: 64 * 1024 * 1024, max_size: 32 * 1024 * 1024, ..Default::default() },
+        HeapSettings { young_generation_size: 16 * 1024 * 1024, initial_size: 1024 * 1024, max_size: 16 * 1024 * 1024, ..Default::default() },
+        HeapSettings { growth_factor: 0.5, ..Default::default() },
+        HeapSettings { growth_factor: f64::NAN, ..Default::default() },
+    ];
+
+    for settings in invalid_settings {
+        assert!(matches!(VirtualMachine::with_heap_settings(settings), Err(ExecutionEngineError::Other(_))));
+    }
+
+    assert!(VirtualMachine::with_heap_settings(HeapSettings::default()).is_ok());
+}
@@ -0,0 +1,186 @@
+use crate::model::class::{Class, Field};
+use crate::model::function::{Function, FunctionDeclaration, FunctionSignature};
+use crate::model::instruction::Instruction;
+use crate::model::typesystem::TypeId;
+use crate::vm::{VirtualMachine, Value};
+
+fn add_allocate_function(vm: &mut VirtualMachine, full_collection: bool) {
+    let mut instructions = vec![
+        // Allocate until the young generation has been collected several times
+        Instruction::LoadInt32(0),
+        Instruction::StoreLocal(0),
+
+        Instruction::LoadInt32(1000),
+        Instruction::NewArray(TypeId::Int32),
+        Instruction::StoreLocal(1),
+
+        Instruction::LoadLocal(0),
+        Instruction::LoadInt32(1),
+        Instruction::Add,
+        Instruction::StoreLocal(0),
+
+        Instruction::LoadLocal(0),
+        Instruction::LoadInt32(1000),
+        Instruction::BranchLessThan(2),
+    ];
+
+    if full_collection {
+        instructions.push(Instruction::Call(FunctionSignature::new("std.gc.collect".to_owned(), Vec::new())));
+    }
+
+    instructions.push(Instruction::Return);
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("allocate".to_owned(), Vec::new(), TypeId::Void),
+        vec![TypeId::Int32, TypeId::Array(Box::new(TypeId::Int32))],
+        instructions
+    )).unwrap();
+}
+
+fn add_node_class(vm: &mut VirtualMachine) {
+    vm.add_class(Class::new(
+        "Node".to_owned(),
+        vec![
+            Field::new("value".to_owned(), TypeId::Int32),
+            Field::new("weight".to_owned(), TypeId::Float32),
+            Field::new("next".to_owned(), TypeId::Class("Node".to_owned())),
+        ]
+    )).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("create_node".to_owned(), Vec::new(), TypeId::Class("Node".to_owned())),
+        Vec::new(),
+        vec![
+            Instruction::NewObject("Node".to_owned()),
+            Instruction::Return,
+        ]
+    )).unwrap();
+}
+
+#[test]
+fn test_root1() {
+    let mut vm = VirtualMachine::new();
+    add_allocate_function(&mut vm, true);
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("create".to_owned(), vec![TypeId::Int32], TypeId::Array(Box::new(TypeId::Int32))),
+        Vec::new(),
+        vec![
+            Instruction::LoadArgument(0),
+            Instruction::NewArray(TypeId::Int32),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("sum".to_owned(), vec![TypeId::Array(Box::new(TypeId::Int32))], TypeId::Int32),
+        vec![TypeId::Int32, TypeId::Int32, TypeId::Int32],
+        vec![
+            Instruction::LoadInt32(0),
+            Instruction::StoreLocal(0),
+            Instruction::LoadInt32(0),
+            Instruction::StoreLocal(1),
+            Instruction::LoadArgument(0),
+            Instruction::LoadArrayLength,
+            Instruction::StoreLocal(2),
+
+            Instruction::LoadLocal(1),
+            Instruction::LoadLocal(2),
+            Instruction::BranchGreaterThanOrEqual(21),
+
+            Instruction::LoadArgument(0),
+            Instruction::LoadLocal(1),
+            Instruction::LoadElement(TypeId::Int32),
+            Instruction::LoadLocal(0),
+            Instruction::Add,
+            Instruction::StoreLocal(0),
+
+            Instruction::LoadLocal(1),
+            Instruction::LoadInt32(1),
+            Instruction::Add,
+            Instruction::StoreLocal(1),
+            Instruction::Branch(7),
+
+            Instruction::LoadLocal(0),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let create = FunctionSignature::new("create".to_owned(), vec![TypeId::Int32]);
+    let allocate = FunctionSignature::new("allocate".to_owned(), Vec::new());
+    let sum = FunctionSignature::new("sum".to_owned(), vec![TypeId::Array(Box::new(TypeId::Int32))]);
+
+    let array = vm.call(&create, &[Value::Int32(10)]).unwrap();
+    let root = vm.create_root(array).unwrap();
+    for i in 0..10 {
+        assert_eq!(Some(()), vm.memory_manager.set_element(&root, i, Value::Int32(i as i32 * 10)));
+    }
+
+    assert_eq!(Ok(Value::Void), vm.call(&allocate, &[]));
+
+    // The array has been moved out of the young generation
+    assert_ne!(array, vm.root_value(&root));
+    assert!(vm.memory_manager.old_generation.inside(vm.memory_manager.root_object(&root)));
+
+    for i in 0..10 {
+        assert_eq!(Some(Value::Int32(i as i32 * 10)), vm.memory_manager.get_element(&root, i));
+    }
+
+    assert_eq!(Ok(Value::Int32(450)), vm.call(&sum, &[vm.root_value(&root)]));
+}
+
+#[test]
+fn test_access1() {
+    let mut vm = VirtualMachine::new();
+    add_node_class(&mut vm);
+
+    let create_node = FunctionSignature::new("create_node".to_owned(), Vec::new());
+    let node = vm.call(&create_node, &[]).unwrap();
+    let root = vm.create_root(node).unwrap();
+
+    assert_eq!(Some(()), vm.memory_manager.set_field(&root, "value", Value::Int32(4711)));
+    assert_eq!(Some(()), vm.memory_manager.set_field(&root, "weight", Value::Float32(13.37)));
+    assert_eq!(Some(()), vm.memory_manager.set_field(&root, "next", node));
+
+    assert_eq!(Some(Value::Int32(4711)), vm.memory_manager.get_field(&root, "value"));
+    assert_eq!(Some(Value::Float32(13.37)), vm.memory_manager.get_field(&root, "weight"));
+    assert_eq!(Some(node), vm.memory_manager.get_field(&root, "next"));
+
+    assert_eq!(None, vm.memory_manager.get_field(&root, "x"));
+    assert_eq!(None, vm.memory_manager.set_field(&root, "value", Value::Float32(1.0)));
+    assert_eq!(None, vm.memory_manager.set_field(&root, "next", Value::Int32(1)));
+    assert_eq!(None, vm.memory_manager.get_element(&root, 0));
+    assert_eq!(Some(Value::Int32(4711)), vm.memory_manager.get_field(&root, "value"));
+
+    assert_eq!(None, vm.create_root(Value::Int32(1)));
+
+    vm.release_root(root);
+    assert!(vm.memory_manager.handles.is_empty());
+}
+
+#[test]
+fn test_write_barrier1() {
+    let mut vm = VirtualMachine::new();
+    add_allocate_function(&mut vm, false);
+    add_node_class(&mut vm);
+
+    let create_node = FunctionSignature::new("create_node".to_owned(), Vec::new());
+    let allocate = FunctionSignature::new("allocate".to_owned(), Vec::new());
+
+    let root = vm.call(&create_node, &[]).map(|node| vm.create_root(node).unwrap()).unwrap();
+    assert_eq!(Ok(Value::Void), vm.call(&allocate, &[]));
+    assert!(vm.memory_manager.old_generation.inside(vm.memory_manager.root_object(&root)));
+
+    // The young node is only referenced by the old node
+    let young_node = vm.call(&create_node, &[]).unwrap();
+    let young_root = vm.create_root(young_node).unwrap();
+    assert_eq!(Some(()), vm.memory_manager.set_field(&young_root, "value", Value::Int32(1337)));
+    assert_eq!(Some(()), vm.memory_manager.set_field(&root, "next", young_node));
+    vm.release_root(young_root);
+
+    assert_eq!(Ok(Value::Void), vm.call(&allocate, &[]));
+
+    let next_root = vm.memory_manager.get_field(&root, "next").and_then(|next| vm.create_root(next)).unwrap();
+    assert_ne!(young_node, vm.root_value(&next_root));
+    assert_eq!(Some(Value::Int32(1337)), vm.memory_manager.get_field(&next_root, "value"));
+}
@@ -2,6 +2,7 @@ use crate::model::function::{Function, FunctionDeclaration};
 use crate::model::instruction::Instruction;
 use crate::model::typesystem::TypeId;
 use crate::vm::VirtualMachine;
+use crate::engine::execution::{ExecutionEngineError, RuntimeError};
 
 #[test]
 fn test1() {
@@ -100,3 +101,242 @@ fn test5() {
     assert_eq!(4711 / 1337, execution_result);
 }
 
+#[test]
+fn test_divide_checks1() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadInt32(4711),
+            Instruction::LoadInt32(0),
+            Instruction::Divide,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute();
+    assert!(matches!(execution_result, Err(ExecutionEngineError::Runtime(RuntimeError::DivisionByZero, _))));
+}
+
+#[test]
+fn test_divide_checks2() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Int32],
+        vec![
+            Instruction::LoadInt32(0),
+            Instruction::StoreLocal(0),
+            Instruction::LoadInt32(4711),
+            Instruction::LoadLocal(0),
+            Instruction::Divide,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute();
+    assert!(matches!(execution_result, Err(ExecutionEngineError::Runtime(RuntimeError::DivisionByZero, _))));
+}
+
+#[test]
+fn test_divide_checks3() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadInt32(i32::MIN),
+            Instruction::LoadInt32(-1),
+            Instruction::Divide,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute();
+    assert!(matches!(execution_result, Err(ExecutionEngineError::Runtime(RuntimeError::DivisionOverflow, _))));
+}
+
+#[test]
+fn test_divide_checks4() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadInt32(4711),
+            Instruction::LoadInt32(-1),
+            Instruction::Divide,
+            Instruction::LoadInt32(i32::MIN + 1),
+            Instruction::LoadInt32(-1),
+            Instruction::Divide,
+            Instruction::Add,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(-4711 + i32::MAX, execution_result);
+}
+
+#[test]
+fn test_remainder1() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Int32],
+        vec![
+            Instruction::LoadInt32(13),
+            Instruction::StoreLocal(0),
+            Instruction::LoadInt32(-4711),
+            Instruction::LoadLocal(0),
+            Instruction::Remainder,
+            Instruction::LoadInt32(1337),
+            Instruction::LoadInt32(100),
+            Instruction::Remainder,
+            Instruction::Add,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(-4711 % 13 + 1337 % 100, execution_result);
+}
+
+#[test]
+fn test_remainder2() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadInt32(4711),
+            Instruction::LoadInt32(0),
+            Instruction::Remainder,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute();
+    assert!(matches!(execution_result, Err(ExecutionEngineError::Runtime(RuntimeError::DivisionByZero, _))));
+}
+
+#[test]
+fn test_negate1() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Int32],
+        vec![
+            Instruction::LoadInt32(4711),
+            Instruction::StoreLocal(0),
+            Instruction::LoadLocal(0),
+            Instruction::Negate,
+            Instruction::LoadInt32(1337),
+            Instruction::Add,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(-4711 + 1337, execution_result);
+}
+
+#[test]
+fn test_bitwise1() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadInt32(0b1100),
+            Instruction::LoadInt32(0b1010),
+            Instruction::And,
+            Instruction::LoadInt32(0b0101_0000),
+            Instruction::Or,
+            Instruction::LoadInt32(0b1111_1111),
+            Instruction::Xor,
+            Instruction::Not,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(!(((0b1100 & 0b1010) | 0b0101_0000) ^ 0b1111_1111), execution_result);
+}
+
+#[test]
+fn test_shift1() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Int32],
+        vec![
+            Instruction::LoadInt32(3),
+            Instruction::StoreLocal(0),
+            Instruction::LoadInt32(4711),
+            Instruction::LoadLocal(0),
+            Instruction::ShiftLeft,
+            Instruction::LoadInt32(-1337),
+            Instruction::LoadLocal(0),
+            Instruction::ShiftRight,
+            Instruction::Add,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!((4711 << 3) + (-1337 >> 3), execution_result);
+}
+
+#[test]
+fn test_hash1() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Int32, TypeId::Int32],
+        vec![
+            Instruction::LoadInt32(17),
+            Instruction::StoreLocal(0),
+            Instruction::LoadInt32(0),
+            Instruction::StoreLocal(1),
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(5),
+            Instruction::ShiftLeft,
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(3),
+            Instruction::ShiftRight,
+            Instruction::Xor,
+            Instruction::LoadLocal(1),
+            Instruction::Xor,
+            Instruction::StoreLocal(0),
+            Instruction::LoadLocal(1),
+            Instruction::LoadInt32(1),
+            Instruction::Add,
+            Instruction::StoreLocal(1),
+            Instruction::LoadLocal(1),
+            Instruction::LoadInt32(10),
+            Instruction::BranchLessThan(4),
+            Instruction::LoadLocal(0),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let mut expected: i32 = 17;
+    for i in 0..10 {
+        expected = (expected.wrapping_shl(5) ^ (expected >> 3)) ^ i;
+    }
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(expected, execution_result);
+}
@@ -0,0 +1,552 @@
+use std::cell::RefCell;
+
+use crate::model::function::{Function, FunctionDeclaration, FunctionSignature};
+use crate::model::instruction::Instruction;
+use crate::model::typesystem::TypeId;
+use crate::model::class::{Class, Field};
+use crate::vm::{VirtualMachine, Value};
+use crate::engine::execution::{ExecutionEngineError, RuntimeError};
+
+thread_local!(static INT64_RESULT: RefCell<i64> = RefCell::new(0));
+
+extern "C" fn print_int64(x: i64) {
+    println!("{}", x);
+    INT64_RESULT.with(|result| {
+        *result.borrow_mut() = x;
+    });
+}
+
+fn add_print_int64(vm: &mut VirtualMachine) {
+    INT64_RESULT.with(|result| {
+        *result.borrow_mut() = 0;
+    });
+
+    vm.add_external_function(
+        FunctionDeclaration::with_external(
+            "print".to_owned(), vec![TypeId::Int64], TypeId::Void,
+            print_int64 as *mut std::ffi::c_void
+        )
+    );
+}
+
+fn print_int64_call() -> Instruction {
+    Instruction::Call(FunctionSignature::new("print".to_owned(), vec![TypeId::Int64]))
+}
+
+#[test]
+fn test_arithmetic1() {
+    let mut vm = VirtualMachine::new();
+    add_print_int64(&mut vm);
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadInt64(8_589_934_592),
+            Instruction::LoadInt64(4711),
+            Instruction::Add,
+            Instruction::LoadInt64(1337),
+            Instruction::Sub,
+            print_int64_call(),
+            Instruction::LoadInt32(0),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(0, execution_result);
+    assert_eq!(8_589_934_592 + 4711 - 1337, INT64_RESULT.with(|result| *result.borrow()));
+}
+
+#[test]
+fn test_arithmetic2() {
+    let mut vm = VirtualMachine::new();
+    add_print_int64(&mut vm);
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadInt64(3_000_000_000),
+            Instruction::LoadInt64(3),
+            Instruction::Multiply,
+            Instruction::LoadInt64(7),
+            Instruction::Divide,
+            print_int64_call(),
+            Instruction::LoadInt32(0),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(0, execution_result);
+    assert_eq!(3_000_000_000 * 3 / 7, INT64_RESULT.with(|result| *result.borrow()));
+}
+
+#[test]
+fn test_locals1() {
+    let mut vm = VirtualMachine::new();
+    add_print_int64(&mut vm);
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Int64, TypeId::Int64],
+        vec![
+            Instruction::LoadInt64(i64::MAX),
+            Instruction::StoreLocal(0),
+            Instruction::LoadInt64(-1),
+            Instruction::StoreLocal(1),
+            Instruction::LoadLocal(0),
+            Instruction::LoadLocal(1),
+            Instruction::Multiply,
+            print_int64_call(),
+            Instruction::LoadInt32(0),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(0, execution_result);
+    assert_eq!(-i64::MAX, INT64_RESULT.with(|result| *result.borrow()));
+}
+
+#[test]
+fn test_call1() {
+    let mut vm = VirtualMachine::new();
+    add_print_int64(&mut vm);
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadInt64(5_000_000_000),
+            Instruction::LoadInt32(1),
+            Instruction::LoadInt64(6_000_000_000),
+            Instruction::Call(FunctionSignature::new("sum".to_owned(), vec![TypeId::Int64, TypeId::Int32, TypeId::Int64])),
+            print_int64_call(),
+            Instruction::LoadInt32(0),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("sum".to_owned(), vec![TypeId::Int64, TypeId::Int32, TypeId::Int64], TypeId::Int64),
+        Vec::new(),
+        vec![
+            Instruction::LoadArgument(0),
+            Instruction::LoadArgument(2),
+            Instruction::Add,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(0, execution_result);
+    assert_eq!(11_000_000_000, INT64_RESULT.with(|result| *result.borrow()));
+}
+
+#[test]
+fn test_branches1() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadInt64(4_294_967_296),
+            Instruction::LoadInt64(1),
+            Instruction::BranchLessThan(5),
+            Instruction::LoadInt32(1),
+            Instruction::Return,
+            Instruction::LoadInt32(2),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(1, execution_result);
+}
+
+#[test]
+fn test_compare1() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadInt64(4_294_967_296),
+            Instruction::LoadInt64(0),
+            Instruction::CompareEqual,
+            Instruction::LoadTrue,
+            Instruction::BranchEqual(7),
+            Instruction::LoadInt32(1),
+            Instruction::Return,
+            Instruction::LoadInt32(2),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(1, execution_result);
+}
+
+#[test]
+fn test_array1() {
+    let mut vm = VirtualMachine::new();
+    add_print_int64(&mut vm);
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Array(Box::new(TypeId::Int64))],
+        vec![
+            Instruction::LoadInt32(3),
+            Instruction::NewArray(TypeId::Int64),
+            Instruction::StoreLocal(0),
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(1),
+            Instruction::LoadInt64(-8_589_934_592),
+            Instruction::StoreElement(TypeId::Int64),
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(2),
+            Instruction::LoadInt64(1),
+            Instruction::StoreElement(TypeId::Int64),
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(1),
+            Instruction::LoadElement(TypeId::Int64),
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(2),
+            Instruction::LoadElement(TypeId::Int64),
+            Instruction::Sub,
+            print_int64_call(),
+            Instruction::LoadInt32(0),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(0, execution_result);
+    assert_eq!(-8_589_934_593, INT64_RESULT.with(|result| *result.borrow()));
+}
+
+#[test]
+fn test_class1() {
+    let mut vm = VirtualMachine::new();
+    add_print_int64(&mut vm);
+
+    vm.add_class(Class::new(
+        "Point".to_owned(),
+        vec![
+            Field::new("x".to_owned(), TypeId::Int32),
+            Field::new("y".to_owned(), TypeId::Int64),
+        ]
+    )).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Class("Point".to_owned())],
+        vec![
+            Instruction::NewObject("Point".to_owned()),
+            Instruction::StoreLocal(0),
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt64(-4_294_967_296),
+            Instruction::StoreField("Point".to_owned(), "y".to_owned()),
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(4711),
+            Instruction::StoreField("Point".to_owned(), "x".to_owned()),
+            Instruction::LoadLocal(0),
+            Instruction::LoadField("Point".to_owned(), "y".to_owned()),
+            print_int64_call(),
+            Instruction::LoadInt32(0),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(0, execution_result);
+    assert_eq!(-4_294_967_296, INT64_RESULT.with(|result| *result.borrow()));
+}
+
+#[test]
+fn test_divide_checks1() {
+    let mut vm = VirtualMachine::new();
+    add_print_int64(&mut vm);
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadInt64(4711),
+            Instruction::LoadInt64(0),
+            Instruction::Divide,
+            print_int64_call(),
+            Instruction::LoadInt32(0),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute();
+    assert!(matches!(execution_result, Err(ExecutionEngineError::Runtime(RuntimeError::DivisionByZero, _))));
+}
+
+#[test]
+fn test_divide_checks2() {
+    let mut vm = VirtualMachine::new();
+    add_print_int64(&mut vm);
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadInt64(i64::MIN),
+            Instruction::LoadInt64(-1),
+            Instruction::Divide,
+            print_int64_call(),
+            Instruction::LoadInt32(0),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute();
+    assert!(matches!(execution_result, Err(ExecutionEngineError::Runtime(RuntimeError::DivisionOverflow, _))));
+}
+
+#[test]
+fn test_divide_checks3() {
+    let mut vm = VirtualMachine::new();
+    add_print_int64(&mut vm);
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadInt64(i64::MIN + 1),
+            Instruction::LoadInt64(-1),
+            Instruction::Divide,
+            Instruction::LoadInt64(i32::MIN as i64),
+            Instruction::LoadInt64(-1),
+            Instruction::Divide,
+            Instruction::Sub,
+            print_int64_call(),
+            Instruction::LoadInt32(0),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(0, execution_result);
+    assert_eq!(i64::MAX - (i32::MAX as i64 + 1), INT64_RESULT.with(|result| *result.borrow()));
+}
+
+#[test]
+fn test_remainder1() {
+    let mut vm = VirtualMachine::new();
+    add_print_int64(&mut vm);
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadInt64(8_589_934_592 + 17),
+            Instruction::LoadInt64(5),
+            Instruction::Remainder,
+            Instruction::LoadInt64(-8_589_934_599),
+            Instruction::LoadInt64(4_294_967_296),
+            Instruction::Remainder,
+            Instruction::Add,
+            print_int64_call(),
+            Instruction::LoadInt32(0),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(0, execution_result);
+    assert_eq!((8_589_934_592 + 17) % 5 + (-8_589_934_599 % 4_294_967_296), INT64_RESULT.with(|result| *result.borrow()));
+}
+
+#[test]
+fn test_remainder_checks1() {
+    let mut vm = VirtualMachine::new();
+    add_print_int64(&mut vm);
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadInt64(4711),
+            Instruction::LoadInt64(0),
+            Instruction::Remainder,
+            print_int64_call(),
+            Instruction::LoadInt32(0),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute();
+    assert!(matches!(execution_result, Err(ExecutionEngineError::Runtime(RuntimeError::DivisionByZero, _))));
+}
+
+#[test]
+fn test_remainder_checks2() {
+    let mut vm = VirtualMachine::new();
+    add_print_int64(&mut vm);
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadInt64(i64::MIN),
+            Instruction::LoadInt64(-1),
+            Instruction::Remainder,
+            print_int64_call(),
+            Instruction::LoadInt32(0),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute();
+    assert!(matches!(execution_result, Err(ExecutionEngineError::Runtime(RuntimeError::DivisionOverflow, _))));
+}
+
+#[test]
+fn test_negate1() {
+    let mut vm = VirtualMachine::new();
+    add_print_int64(&mut vm);
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadInt64(8_589_934_592 + 4711),
+            Instruction::Negate,
+            print_int64_call(),
+            Instruction::LoadInt32(0),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(0, execution_result);
+    assert_eq!(-8_589_934_592 - 4711, INT64_RESULT.with(|result| *result.borrow()));
+}
+
+#[test]
+fn test_bitwise1() {
+    let mut vm = VirtualMachine::new();
+    add_print_int64(&mut vm);
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadInt64(0x0F0F_0000_FFFF_0000),
+            Instruction::LoadInt64(0x00FF_0000_00FF_FF00),
+            Instruction::And,
+            Instruction::LoadInt64(0x1000_0000_0000_0001),
+            Instruction::Or,
+            Instruction::LoadInt64(0x0101_0101_0101_0101),
+            Instruction::Xor,
+            print_int64_call(),
+            Instruction::LoadInt32(0),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(0, execution_result);
+    assert_eq!(((0x0F0F_0000_FFFF_0000 & 0x00FF_0000_00FF_FF00) | 0x1000_0000_0000_0001) ^ 0x0101_0101_0101_0101, INT64_RESULT.with(|result| *result.borrow()));
+}
+
+#[test]
+fn test_bitwise2() {
+    let mut vm = VirtualMachine::new();
+    add_print_int64(&mut vm);
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadInt64(0x0F0F_0000_FFFF_0000),
+            Instruction::Not,
+            print_int64_call(),
+            Instruction::LoadInt32(0),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(0, execution_result);
+    assert_eq!(!0x0F0F_0000_FFFF_0000, INT64_RESULT.with(|result| *result.borrow()));
+}
+
+#[test]
+fn test_shift1() {
+    let mut vm = VirtualMachine::new();
+    add_print_int64(&mut vm);
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadInt64(3),
+            Instruction::LoadInt32(40),
+            Instruction::ShiftLeft,
+            Instruction::LoadInt64(-8_589_934_592),
+            Instruction::LoadInt32(4),
+            Instruction::ShiftRight,
+            Instruction::Add,
+            print_int64_call(),
+            Instruction::LoadInt32(0),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(0, execution_result);
+    assert_eq!((3i64 << 40) + (-8_589_934_592 >> 4), INT64_RESULT.with(|result| *result.borrow()));
+}
+
+#[test]
+fn test_bitwise3() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("mix".to_owned(), vec![TypeId::Int64, TypeId::Int64, TypeId::Int32], TypeId::Int64),
+        vec![TypeId::Int64],
+        vec![
+            Instruction::LoadArgument(0),
+            Instruction::LoadArgument(1),
+            Instruction::And,
+            Instruction::LoadArgument(0),
+            Instruction::LoadArgument(1),
+            Instruction::Xor,
+            Instruction::Or,
+            Instruction::StoreLocal(0),
+            Instruction::LoadLocal(0),
+            Instruction::LoadArgument(2),
+            Instruction::ShiftLeft,
+            Instruction::LoadLocal(0),
+            Instruction::Negate,
+            Instruction::LoadArgument(1),
+            Instruction::Not,
+            Instruction::LoadArgument(0),
+            Instruction::Remainder,
+            Instruction::Sub,
+            Instruction::Sub,
+            Instruction::LoadArgument(2),
+            Instruction::ShiftRight,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let mix = |a: i64, b: i64, shift: i32| {
+        let local = (a & b) | (a ^ b);
+        ((local << shift) - (-local - (!b % a))) >> shift
+    };
+
+    let signature = FunctionSignature::new("mix".to_owned(), vec![TypeId::Int64, TypeId::Int64, TypeId::Int32]);
+    for (a, b, shift) in [(8_589_934_599, -4_294_967_296, 3), (-1_000_000_000_007, 0x00FF_00FF_00FF_00FF, 17)] {
+        assert_eq!(Ok(Value::Int64(mix(a, b, shift))), vm.call(&signature, &[Value::Int64(a), Value::Int64(b), Value::Int32(shift)]));
+    }
+}
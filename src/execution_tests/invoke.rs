@@ -0,0 +1,363 @@
+use crate::model::function::{Function, FunctionDeclaration, FunctionSignature};
+use crate::model::instruction::Instruction;
+use crate::model::typesystem::TypeId;
+use crate::vm::{VirtualMachine, Value, get_vm};
+use crate::model::class::{Class, Field};
+use crate::engine::execution::{ExecutionEngineError, RuntimeError};
+use crate::runtime::object::ObjectPointer;
+
+#[test]
+fn test_invoke1() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("add".to_owned(), vec![TypeId::Int32, TypeId::Int32], TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadArgument(0),
+            Instruction::LoadArgument(1),
+            Instruction::Add,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let signature = FunctionSignature::new("add".to_owned(), vec![TypeId::Int32, TypeId::Int32]);
+    for i in 0..10 {
+        assert_eq!(Ok(Value::Int32(i + 4711)), vm.call(&signature, &[Value::Int32(i), Value::Int32(4711)]));
+    }
+}
+
+#[test]
+fn test_invoke2() {
+    let mut vm = VirtualMachine::new();
+
+    let mut parameters = vec![TypeId::Int32; 8];
+    parameters.extend(vec![TypeId::Float32; 9]);
+    parameters.push(TypeId::Bool);
+
+    let mut instructions = Vec::new();
+    instructions.push(Instruction::LoadInt32(0));
+    for i in 0..8 {
+        instructions.push(Instruction::LoadArgument(i));
+        instructions.push(Instruction::LoadInt32(i as i32 + 1));
+        instructions.push(Instruction::Multiply);
+        instructions.push(Instruction::Add);
+    }
+
+    instructions.push(Instruction::ConvertInt32ToFloat32);
+
+    for i in 8..17 {
+        instructions.push(Instruction::LoadArgument(i));
+        instructions.push(Instruction::LoadFloat32(i as f32 + 1.0));
+        instructions.push(Instruction::Multiply);
+        instructions.push(Instruction::Add);
+    }
+
+    instructions.push(Instruction::StoreLocal(0));
+
+    let negate_index = instructions.len() as u32 + 6;
+    instructions.push(Instruction::LoadArgument(17));
+    instructions.push(Instruction::ConvertBoolToInt32);
+    instructions.push(Instruction::LoadInt32(0));
+    instructions.push(Instruction::BranchEqual(negate_index));
+    instructions.push(Instruction::LoadLocal(0));
+    instructions.push(Instruction::Return);
+    instructions.push(Instruction::LoadFloat32(0.0));
+    instructions.push(Instruction::LoadLocal(0));
+    instructions.push(Instruction::Sub);
+    instructions.push(Instruction::Return);
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("sum".to_owned(), parameters.clone(), TypeId::Float32),
+        vec![TypeId::Float32],
+        instructions
+    )).unwrap();
+
+    let signature = FunctionSignature::new("sum".to_owned(), parameters);
+    let mut arguments = (1..=8).map(Value::Int32).collect::<Vec<_>>();
+    arguments.extend((9..=17).map(|i| Value::Float32(i as f32)));
+
+    let expected = (1..=17).map(|i| (i * i) as f32).sum::<f32>();
+
+    arguments.push(Value::Bool(true));
+    assert_eq!(Ok(Value::Float32(expected)), vm.call(&signature, &arguments));
+
+    *arguments.last_mut().unwrap() = Value::Bool(false);
+    assert_eq!(Ok(Value::Float32(-expected)), vm.call(&signature, &arguments));
+}
+
+#[test]
+fn test_invoke3() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("create".to_owned(), vec![TypeId::Int32], TypeId::Array(Box::new(TypeId::Int32))),
+        Vec::new(),
+        vec![
+            Instruction::LoadArgument(0),
+            Instruction::NewArray(TypeId::Int32),
+            Instruction::Duplicate,
+            Instruction::LoadInt32(0),
+            Instruction::LoadInt32(4711),
+            Instruction::StoreElement(TypeId::Int32),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("first".to_owned(), vec![TypeId::Array(Box::new(TypeId::Int32))], TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadArgument(0),
+            Instruction::LoadInt32(0),
+            Instruction::LoadElement(TypeId::Int32),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let create = FunctionSignature::new("create".to_owned(), vec![TypeId::Int32]);
+    let first = FunctionSignature::new("first".to_owned(), vec![TypeId::Array(Box::new(TypeId::Int32))]);
+
+    let array = vm.call(&create, &[Value::Int32(10)]).unwrap();
+    assert!(matches!(array, Value::Reference(_)));
+    assert_eq!(Ok(Value::Int32(4711)), vm.call(&first, &[array]));
+
+    assert!(matches!(
+        vm.call(&first, &[Value::Reference(std::ptr::null_mut())]),
+        Err(ExecutionEngineError::Runtime(RuntimeError::NullReference, _))
+    ));
+
+    assert!(matches!(
+        vm.call(&create, &[Value::Int32(-1)]),
+        Err(ExecutionEngineError::Runtime(RuntimeError::ArrayCreate, _))
+    ));
+
+    // The VM is still usable after an error
+    assert_eq!(Ok(Value::Int32(4711)), vm.call(&first, &[array]));
+}
+
+#[test]
+fn test_invoke4() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("allocate".to_owned(), vec![TypeId::Int32], TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadArgument(0),
+            Instruction::NewArray(TypeId::Int32),
+            Instruction::LoadArrayLength,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let signature = FunctionSignature::new("allocate".to_owned(), vec![TypeId::Int32]);
+    for _ in 0..1000 {
+        assert_eq!(Ok(Value::Int32(1000)), vm.call(&signature, &[Value::Int32(1000)]));
+    }
+
+    assert!(vm.memory_manager.young_generation.used_size() < 1000 * 1000 * 4);
+}
+
+#[test]
+fn test_invoke5() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("add".to_owned(), vec![TypeId::Int32, TypeId::Int32], TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadArgument(0),
+            Instruction::LoadArgument(1),
+            Instruction::Add,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let add = FunctionSignature::new("add".to_owned(), vec![TypeId::Int32, TypeId::Int32]);
+    assert_eq!(Ok(Value::Int32(3)), vm.call(&add, &[Value::Int32(1), Value::Int32(2)]));
+
+    // Functions added after a call are compiled by the next one
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("double".to_owned(), vec![TypeId::Int32], TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadArgument(0),
+            Instruction::LoadArgument(0),
+            Instruction::Call(add.clone()),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let double = FunctionSignature::new("double".to_owned(), vec![TypeId::Int32]);
+    assert_eq!(Ok(Value::Int32(8)), vm.call(&double, &[Value::Int32(4)]));
+    assert_eq!(Ok(Value::Int32(7)), vm.call(&add, &[Value::Int32(3), Value::Int32(4)]));
+}
+
+#[test]
+fn test_nested1() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("square".to_owned(), vec![TypeId::Int32], TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadArgument(0),
+            Instruction::LoadArgument(0),
+            Instruction::Multiply,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    vm.register_native("host.square_plus_one", |value: i32| -> i32 {
+        let square = FunctionSignature::new("square".to_owned(), vec![TypeId::Int32]);
+        match get_vm(|vm| vm.call(&square, &[Value::Int32(value)])) {
+            Ok(Value::Int32(result)) => result + 1,
+            _ => panic!("Failed to call square")
+        }
+    }).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("compute".to_owned(), vec![TypeId::Int32], TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadArgument(0),
+            Instruction::Call(FunctionSignature::new("host.square_plus_one".to_owned(), vec![TypeId::Int32])),
+            Instruction::LoadInt32(2),
+            Instruction::Multiply,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let compute = FunctionSignature::new("compute".to_owned(), vec![TypeId::Int32]);
+    assert_eq!(Ok(Value::Int32((5 * 5 + 1) * 2)), vm.call(&compute, &[Value::Int32(5)]));
+}
+
+#[test]
+fn test_nested2() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("square".to_owned(), vec![TypeId::Int32], TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadArgument(0),
+            Instruction::LoadArgument(0),
+            Instruction::Multiply,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    vm.register_native("host.square", |value: i32| -> i32 {
+        let square = FunctionSignature::new("square".to_owned(), vec![TypeId::Int32]);
+        match get_vm(|vm| vm.call(&square, &[Value::Int32(value)])) {
+            Ok(Value::Int32(result)) => result,
+            _ => panic!("Failed to call square")
+        }
+    }).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("compute".to_owned(), vec![TypeId::Int32], TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadArgument(0),
+            Instruction::Call(FunctionSignature::new("host.square".to_owned(), vec![TypeId::Int32])),
+            Instruction::LoadInt32(0),
+            Instruction::Divide,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let compute = FunctionSignature::new("compute".to_owned(), vec![TypeId::Int32]);
+    for _ in 0..2 {
+        let result = vm.call(&compute, &[Value::Int32(5)]);
+        assert!(matches!(result, Err(ExecutionEngineError::Runtime(RuntimeError::DivisionByZero, _))));
+    }
+}
+
+#[test]
+fn test_invalid1() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("negate".to_owned(), vec![TypeId::Float64], TypeId::Float64),
+        Vec::new(),
+        vec![
+            Instruction::LoadFloat64(0.0),
+            Instruction::LoadArgument(0),
+            Instruction::Sub,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let signature = FunctionSignature::new("negate".to_owned(), vec![TypeId::Float64]);
+    assert_eq!(Ok(Value::Float64(-13.37)), vm.call(&signature, &[Value::Float64(13.37)]));
+    assert!(vm.call(&signature, &[Value::Float32(13.37)]).is_err());
+    assert!(vm.call(&signature, &[]).is_err());
+    assert!(vm.call(&FunctionSignature::new("negate".to_owned(), vec![TypeId::Int32]), &[Value::Int32(1)]).is_err());
+}
+
+
+extern "C" fn origin() -> i64 {
+    0
+}
+
+#[test]
+fn test_invalid2() {
+    let mut vm = VirtualMachine::new();
+    vm.add_class(Class::new_struct("Point".to_owned(), vec![Field::new("x".to_owned(), TypeId::Int32)])).unwrap();
+
+    vm.add_external_function(
+        FunctionDeclaration::with_external("origin".to_owned(), Vec::new(), TypeId::Struct("Point".to_owned()), origin as *mut std::ffi::c_void)
+    );
+
+    assert!(matches!(
+        vm.call(&FunctionSignature::new("origin".to_owned(), Vec::new()), &[]),
+        Err(ExecutionEngineError::Other(message)) if message.starts_with("Structs")
+    ));
+}
+
+#[test]
+fn test_invalid3() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("create".to_owned(), vec![TypeId::Int32], TypeId::Array(Box::new(TypeId::Int32))),
+        Vec::new(),
+        vec![
+            Instruction::LoadArgument(0),
+            Instruction::NewArray(TypeId::Int32),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("length".to_owned(), vec![TypeId::Array(Box::new(TypeId::Int32))], TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadArgument(0),
+            Instruction::LoadArrayLength,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let create = FunctionSignature::new("create".to_owned(), vec![TypeId::Int32]);
+    let length = FunctionSignature::new("length".to_owned(), vec![TypeId::Array(Box::new(TypeId::Int32))]);
+
+    let array = match vm.call(&create, &[Value::Int32(10)]) {
+        Ok(Value::Reference(array)) => array,
+        result => panic!("Unexpected result: {:?}", result)
+    };
+
+    let host_value = Box::new([0u64; 4]);
+    let invalid_references = [
+        unsafe { (array as *mut u8).add(8) as ObjectPointer },
+        host_value.as_ptr() as ObjectPointer
+    ];
+
+    for reference in invalid_references.iter() {
+        assert!(matches!(vm.call(&length, &[Value::Reference(*reference)]), Err(ExecutionEngineError::Other(_))));
+    }
+
+    assert_eq!(Ok(Value::Int32(10)), vm.call(&length, &[Value::Reference(array)]));
+}
@@ -0,0 +1,143 @@
+use std::cell::RefCell;
+use std::io::{Write, Cursor};
+use std::rc::Rc;
+
+use crate::model::function::{Function, FunctionDeclaration, FunctionSignature};
+use crate::model::instruction::Instruction;
+use crate::model::typesystem::TypeId;
+use crate::vm::VirtualMachine;
+
+#[derive(Clone)]
+struct SharedOutput(Rc<RefCell<Vec<u8>>>);
+
+impl SharedOutput {
+    fn new() -> SharedOutput {
+        SharedOutput(Rc::new(RefCell::new(Vec::new())))
+    }
+
+    fn text(&self) -> String {
+        String::from_utf8(self.0.borrow().clone()).unwrap()
+    }
+}
+
+impl Write for SharedOutput {
+    fn write(&mut self, buffer: &[u8]) -> std::io::Result<usize> {
+        self.0.borrow_mut().write(buffer)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_print1() {
+    let mut vm = VirtualMachine::new();
+    let output = SharedOutput::new();
+    vm.set_output(Box::new(output.clone()));
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadInt32(4711),
+            Instruction::Call(FunctionSignature::new("std.io.print_int".to_owned(), vec![TypeId::Int32])),
+            Instruction::LoadInt32(32),
+            Instruction::Call(FunctionSignature::new("std.io.print_char".to_owned(), vec![TypeId::Int32])),
+            Instruction::LoadFloat32(13.37),
+            Instruction::Call(FunctionSignature::new("std.io.print_float".to_owned(), vec![TypeId::Float32])),
+            Instruction::LoadInt32(32),
+            Instruction::Call(FunctionSignature::new("std.io.print_char".to_owned(), vec![TypeId::Int32])),
+            Instruction::LoadTrue,
+            Instruction::Call(FunctionSignature::new("std.io.print_bool".to_owned(), vec![TypeId::Bool])),
+            Instruction::Call(FunctionSignature::new("std.io.println".to_owned(), Vec::new())),
+            Instruction::LoadInt32(0),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(0, execution_result);
+    assert_eq!("4711 13.37 true\n", output.text());
+}
+
+#[test]
+fn test_print2() {
+    let mut vm = VirtualMachine::new();
+    let output = SharedOutput::new();
+    vm.set_output(Box::new(output.clone()));
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Int32],
+        vec![
+            Instruction::LoadInt32(0),
+            Instruction::StoreLocal(0),
+
+            Instruction::LoadLocal(0),
+            Instruction::Call(FunctionSignature::new("std.io.print_int".to_owned(), vec![TypeId::Int32])),
+            Instruction::Call(FunctionSignature::new("std.io.println".to_owned(), Vec::new())),
+
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(1),
+            Instruction::Add,
+            Instruction::StoreLocal(0),
+
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(3),
+            Instruction::BranchLessThan(2),
+
+            Instruction::LoadLocal(0),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(3, execution_result);
+    assert_eq!("0\n1\n2\n", output.text());
+}
+
+#[test]
+fn test_print_string1() {
+    let mut vm = VirtualMachine::new();
+    let output = SharedOutput::new();
+    vm.set_output(Box::new(output.clone()));
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadString("Hello, World!".to_owned()),
+            Instruction::Call(FunctionSignature::new("std.io.print_string".to_owned(), vec![TypeId::String])),
+            Instruction::Call(FunctionSignature::new("std.io.println".to_owned(), Vec::new())),
+            Instruction::LoadInt32(0),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(0, execution_result);
+    assert_eq!("Hello, World!\n", output.text());
+}
+
+#[test]
+fn test_read1() {
+    let mut vm = VirtualMachine::new();
+    vm.set_input(Box::new(Cursor::new(b"1337\n4711\nabc\n".to_vec())));
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::Call(FunctionSignature::new("std.io.read_int".to_owned(), Vec::new())),
+            Instruction::Call(FunctionSignature::new("std.io.read_int".to_owned(), Vec::new())),
+            Instruction::Add,
+            Instruction::Call(FunctionSignature::new("std.io.read_int".to_owned(), Vec::new())),
+            Instruction::Add,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(1337 + 4711, execution_result);
+}
@@ -0,0 +1,218 @@
+use std::cell::RefCell;
+
+use crate::model::function::{Function, FunctionDeclaration, FunctionSignature};
+use crate::model::instruction::Instruction;
+use crate::model::typesystem::TypeId;
+use crate::vm::VirtualMachine;
+
+thread_local!(static FLOAT_RESULT: RefCell<f32> = RefCell::new(0.0));
+
+extern "C" fn print_float(x: f32) {
+    println!("{}", x);
+    FLOAT_RESULT.with(|result| {
+        *result.borrow_mut() = x;
+    });
+}
+
+fn create_vm() -> VirtualMachine {
+    FLOAT_RESULT.with(|result| {
+        *result.borrow_mut() = 0.0;
+    });
+
+    let mut vm = VirtualMachine::new();
+
+    vm.add_external_function(
+        FunctionDeclaration::with_external(
+            "print".to_owned(), vec![TypeId::Float32], TypeId::Void,
+            print_float as *mut std::ffi::c_void
+        )
+    );
+
+    vm
+}
+
+#[test]
+fn test_sqrt1() {
+    let mut vm = create_vm();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![],
+        vec![
+            Instruction::LoadFloat32(2.0),
+            Instruction::Call(FunctionSignature::new("std.math.sqrt".to_owned(), vec![TypeId::Float32])),
+            Instruction::Call(FunctionSignature::new("print".to_owned(), vec![TypeId::Float32])),
+            Instruction::LoadInt32(0),
+            Instruction::Return
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(0, execution_result);
+    assert_eq!(2.0f32.sqrt(), FLOAT_RESULT.with(|result| *result.borrow()));
+}
+
+#[test]
+fn test_sqrt2() {
+    let mut vm = create_vm();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Int32, TypeId::Float32],
+        vec![
+            Instruction::LoadInt32(0),
+            Instruction::StoreLocal(0),
+            Instruction::LoadFloat32(0.0),
+            Instruction::StoreLocal(1),
+
+            Instruction::LoadLocal(1),
+            Instruction::LoadLocal(0),
+            Instruction::ConvertInt32ToFloat32,
+            Instruction::Call(FunctionSignature::new("std.math.sqrt".to_owned(), vec![TypeId::Float32])),
+            Instruction::Add,
+            Instruction::StoreLocal(1),
+
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(1),
+            Instruction::Add,
+            Instruction::StoreLocal(0),
+
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(100),
+            Instruction::BranchLessThan(4),
+
+            Instruction::LoadLocal(1),
+            Instruction::Call(FunctionSignature::new("print".to_owned(), vec![TypeId::Float32])),
+            Instruction::LoadInt32(0),
+            Instruction::Return
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(0, execution_result);
+    assert_eq!((0..100).fold(0.0f32, |sum, i| sum + (i as f32).sqrt()), FLOAT_RESULT.with(|result| *result.borrow()));
+}
+
+#[test]
+fn test_min_max1() {
+    let mut vm = create_vm();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![],
+        vec![
+            Instruction::LoadFloat32(2.5),
+            Instruction::LoadFloat32(-1.0),
+            Instruction::Call(FunctionSignature::new("std.math.min".to_owned(), vec![TypeId::Float32, TypeId::Float32])),
+            Instruction::LoadFloat32(2.5),
+            Instruction::LoadFloat32(-1.0),
+            Instruction::Call(FunctionSignature::new("std.math.max".to_owned(), vec![TypeId::Float32, TypeId::Float32])),
+            Instruction::LoadFloat32(10.0),
+            Instruction::Multiply,
+            Instruction::Add,
+            Instruction::Call(FunctionSignature::new("print".to_owned(), vec![TypeId::Float32])),
+            Instruction::LoadInt32(0),
+            Instruction::Return
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(0, execution_result);
+    assert_eq!(-1.0 + 25.0, FLOAT_RESULT.with(|result| *result.borrow()));
+}
+
+#[test]
+fn test_round1() {
+    let mut vm = create_vm();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![],
+        vec![
+            Instruction::LoadFloat32(-1.5),
+            Instruction::Call(FunctionSignature::new("std.math.floor".to_owned(), vec![TypeId::Float32])),
+            Instruction::LoadFloat32(1.25),
+            Instruction::Call(FunctionSignature::new("std.math.ceil".to_owned(), vec![TypeId::Float32])),
+            Instruction::LoadFloat32(10.0),
+            Instruction::Multiply,
+            Instruction::Add,
+            Instruction::Call(FunctionSignature::new("print".to_owned(), vec![TypeId::Float32])),
+            Instruction::LoadInt32(0),
+            Instruction::Return
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(0, execution_result);
+    assert_eq!(-2.0 + 20.0, FLOAT_RESULT.with(|result| *result.borrow()));
+}
+
+#[test]
+fn test_external1() {
+    let mut vm = create_vm();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![],
+        vec![
+            Instruction::LoadFloat32(2.0),
+            Instruction::LoadFloat32(0.5),
+            Instruction::Call(FunctionSignature::new("std.math.pow".to_owned(), vec![TypeId::Float32, TypeId::Float32])),
+            Instruction::LoadFloat32(1.0),
+            Instruction::Call(FunctionSignature::new("std.math.sin".to_owned(), vec![TypeId::Float32])),
+            Instruction::Add,
+            Instruction::LoadFloat32(1.0),
+            Instruction::Call(FunctionSignature::new("std.math.cos".to_owned(), vec![TypeId::Float32])),
+            Instruction::Add,
+            Instruction::LoadFloat32(-4.0),
+            Instruction::Call(FunctionSignature::new("std.math.abs".to_owned(), vec![TypeId::Float32])),
+            Instruction::Add,
+            Instruction::Call(FunctionSignature::new("print".to_owned(), vec![TypeId::Float32])),
+            Instruction::LoadInt32(0),
+            Instruction::Return
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(0, execution_result);
+    assert_eq!(
+        2.0f32.powf(0.5) + 1.0f32.sin() + 1.0f32.cos() + 4.0,
+        FLOAT_RESULT.with(|result| *result.borrow())
+    );
+}
+
+#[test]
+fn test_int1() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![],
+        vec![
+            Instruction::LoadInt32(-5),
+            Instruction::Call(FunctionSignature::new("std.math.abs".to_owned(), vec![TypeId::Int32])),
+            Instruction::LoadInt32(3),
+            Instruction::LoadInt32(7),
+            Instruction::Call(FunctionSignature::new("std.math.min".to_owned(), vec![TypeId::Int32, TypeId::Int32])),
+            Instruction::LoadInt32(10),
+            Instruction::Multiply,
+            Instruction::Add,
+            Instruction::LoadInt32(3),
+            Instruction::LoadInt32(7),
+            Instruction::Call(FunctionSignature::new("std.math.max".to_owned(), vec![TypeId::Int32, TypeId::Int32])),
+            Instruction::LoadInt32(100),
+            Instruction::Multiply,
+            Instruction::Add,
+            Instruction::LoadInt32(2),
+            Instruction::LoadInt32(10),
+            Instruction::Call(FunctionSignature::new("std.math.pow".to_owned(), vec![TypeId::Int32, TypeId::Int32])),
+            Instruction::LoadInt32(1000),
+            Instruction::Multiply,
+            Instruction::Add,
+            Instruction::Return
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(5 + 30 + 700 + 1024000, execution_result);
+}
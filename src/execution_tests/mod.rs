@@ -1,10 +1,21 @@
 pub mod simple;
 pub mod int;
 pub mod float;
+pub mod int64;
+pub mod float64;
+pub mod conversion;
+pub mod string;
+pub mod io;
+pub mod math;
+pub mod native;
 pub mod bool;
 pub mod call;
+pub mod invoke;
+pub mod handles;
 pub mod array;
 pub mod class;
 pub mod branches;
 pub mod performance;
-pub mod gc;
\ No newline at end of file
+pub mod gc;
+pub mod exception;
+pub mod structs;
\ No newline at end of file
@@ -0,0 +1,410 @@
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use crate::model::class::{Class, Field};
+use crate::model::function::{Function, FunctionDeclaration, FunctionSignature};
+use crate::model::instruction::Instruction;
+use crate::model::typesystem::TypeId;
+use crate::vm::{VirtualMachine, get_vm};
+use crate::runtime::array::ArrayReference;
+use crate::runtime::native;
+use crate::runtime::native::{NativeArray, NativeClass, NativeObject, NativeString};
+use crate::runtime::object::ObjectPointer;
+use crate::engine::execution::{ExecutionEngineError, RuntimeError, StackTraceEntry};
+
+#[test]
+fn test_register1() {
+    let mut vm = VirtualMachine::new();
+
+    let offset = 1000;
+    vm.register_native("host.lookup", move |a: i32, b: f32| -> i32 { a * 10 + b as i32 + offset }).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadInt32(4),
+            Instruction::LoadFloat32(7.5),
+            Instruction::Call(FunctionSignature::new("host.lookup".to_owned(), vec![TypeId::Int32, TypeId::Float32])),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(4 * 10 + 7 + 1000, execution_result);
+}
+
+#[test]
+fn test_register2() {
+    let mut vm = VirtualMachine::new();
+
+    let values = Rc::new(RefCell::new(Vec::new()));
+    let values_clone = values.clone();
+    vm.register_native("host.record", move |value: i64, negate: bool| {
+        values_clone.borrow_mut().push(if negate { -value } else { value });
+    }).unwrap();
+
+    vm.register_native("host.half", |value: f64| -> i64 { (value / 2.0) as i64 }).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadInt64(4711),
+            Instruction::LoadFalse,
+            Instruction::Call(FunctionSignature::new("host.record".to_owned(), vec![TypeId::Int64, TypeId::Bool])),
+            Instruction::LoadInt64(1337),
+            Instruction::LoadTrue,
+            Instruction::Call(FunctionSignature::new("host.record".to_owned(), vec![TypeId::Int64, TypeId::Bool])),
+            Instruction::LoadFloat64(5.0),
+            Instruction::Call(FunctionSignature::new("host.half".to_owned(), vec![TypeId::Float64])),
+            Instruction::LoadFalse,
+            Instruction::Call(FunctionSignature::new("host.record".to_owned(), vec![TypeId::Int64, TypeId::Bool])),
+            Instruction::LoadInt32(0),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(0, execution_result);
+    assert_eq!(vec![4711, -1337, 2], *values.borrow());
+}
+
+#[test]
+fn test_register3() {
+    let mut vm = VirtualMachine::new();
+
+    vm.register_native(
+        "host.sum",
+        |a: i32, b: i32, c: i32, d: i32, e: i32, f: i32| -> i32 { a + 2 * b + 3 * c + 4 * d + 5 * e + 6 * f }
+    ).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadInt32(1),
+            Instruction::LoadInt32(2),
+            Instruction::LoadInt32(3),
+            Instruction::LoadInt32(4),
+            Instruction::LoadInt32(5),
+            Instruction::LoadInt32(6),
+            Instruction::Call(FunctionSignature::new("host.sum".to_owned(), vec![TypeId::Int32; 6])),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(1 + 4 + 9 + 16 + 25 + 36, execution_result);
+}
+
+#[test]
+fn test_signature1() {
+    let mut vm = VirtualMachine::new();
+
+    vm.register_native("host.value", || -> i32 { 1 }).unwrap();
+    assert!(vm.register_native("host.value", || -> i32 { 2 }).is_err());
+    assert!(vm.register_native("host.value", |x: i32| -> i32 { x }).is_ok());
+
+    let closure = |x: f32| -> f32 { x };
+    vm.register_native("host.identity", closure).unwrap();
+    assert!(vm.register_native("host.identity2", closure).is_ok());
+
+    assert!(vm.register_native("host.empty", |_array: NativeArray<()>| {}).is_err());
+}
+
+fn adder(offset: i32) -> impl Fn(i32) -> i32 {
+    move |value| value + offset
+}
+
+#[test]
+fn test_same_closure_type1() {
+    let mut vm = VirtualMachine::new();
+
+    vm.register_native("host.add1", adder(1)).unwrap();
+    vm.register_native("host.add1000", adder(1000)).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadInt32(10),
+            Instruction::Call(FunctionSignature::new("host.add1000".to_owned(), vec![TypeId::Int32])),
+            Instruction::Call(FunctionSignature::new("host.add1".to_owned(), vec![TypeId::Int32])),
+            Instruction::Call(FunctionSignature::new("host.add1000".to_owned(), vec![TypeId::Int32])),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(10 + 1000 + 1 + 1000, execution_result);
+}
+
+#[test]
+fn test_array1() {
+    let mut vm = VirtualMachine::new();
+
+    vm.register_native("host.reverse", |array: NativeArray<i32>| -> NativeArray<i32> {
+        let length = array.length();
+        let elements = (0..length).map(|index| array.get(index).unwrap()).collect::<Vec<_>>();
+        for (index, element) in elements.into_iter().rev().enumerate() {
+            array.set(index, element).unwrap();
+        }
+
+        assert_eq!(None, array.get(length));
+        assert_eq!(None, array.set(length, 0));
+        array
+    }).unwrap();
+
+    let array_type = TypeId::Array(Box::new(TypeId::Int32));
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![array_type.clone()],
+        vec![
+            Instruction::LoadInt32(3),
+            Instruction::NewArray(TypeId::Int32),
+            Instruction::StoreLocal(0),
+
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(0),
+            Instruction::LoadInt32(4711),
+            Instruction::StoreElement(TypeId::Int32),
+
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(2),
+            Instruction::LoadInt32(1337),
+            Instruction::StoreElement(TypeId::Int32),
+
+            Instruction::LoadLocal(0),
+            Instruction::Call(FunctionSignature::new("host.reverse".to_owned(), vec![array_type.clone()])),
+            Instruction::LoadInt32(0),
+            Instruction::LoadElement(TypeId::Int32),
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(2),
+            Instruction::LoadElement(TypeId::Int32),
+            Instruction::Sub,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(1337 - 4711, execution_result);
+}
+
+#[test]
+fn test_array2() {
+    let mut vm = VirtualMachine::new();
+
+    vm.register_native("host.count", |array: NativeArray<NativeArray<f64>>| -> i32 {
+        (0..array.length()).map(|index| array.get(index).unwrap()).filter(|inner| !inner.is_null()).count() as i32
+    }).unwrap();
+
+    let array_type = TypeId::Array(Box::new(TypeId::Array(Box::new(TypeId::Float64))));
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![array_type.clone()],
+        vec![
+            Instruction::LoadInt32(3),
+            Instruction::NewArray(TypeId::Array(Box::new(TypeId::Float64))),
+            Instruction::StoreLocal(0),
+
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(1),
+            Instruction::LoadInt32(2),
+            Instruction::NewArray(TypeId::Float64),
+            Instruction::StoreElement(TypeId::Array(Box::new(TypeId::Float64))),
+
+            Instruction::LoadLocal(0),
+            Instruction::Call(FunctionSignature::new("host.count".to_owned(), vec![array_type.clone()])),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(1, execution_result);
+}
+
+#[test]
+fn test_string1() {
+    let mut vm = VirtualMachine::new();
+
+    vm.register_native("host.count_words", |text: NativeString| -> i32 {
+        text.as_str().split_whitespace().count() as i32
+    }).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadString("hello native world".to_owned()),
+            Instruction::Call(FunctionSignature::new("host.count_words".to_owned(), vec![TypeId::String])),
+            Instruction::LoadNull(TypeId::String),
+            Instruction::Call(FunctionSignature::new("host.count_words".to_owned(), vec![TypeId::String])),
+            Instruction::Add,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(3, execution_result);
+}
+
+struct Point;
+
+impl NativeClass for Point {
+    fn class_name() -> &'static str {
+        "Point"
+    }
+}
+
+#[test]
+fn test_object1() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_class(Class::new(
+        "Point".to_owned(),
+        vec![
+            Field::new("x".to_owned(), TypeId::Int32),
+            Field::new("y".to_owned(), TypeId::Int32),
+        ]
+    )).unwrap();
+
+    vm.register_native("host.scale", |point: NativeObject<Point>, factor: i32| -> NativeObject<Point> {
+        let x = point.get_field::<i32>("x").unwrap();
+        let y = point.get_field::<i32>("y").unwrap();
+        point.set_field("x", x * factor).unwrap();
+        point.set_field("y", y * factor).unwrap();
+
+        assert_eq!(None, point.get_field::<f32>("x"));
+        assert_eq!(None, point.set_field("z", 0));
+        point
+    }).unwrap();
+
+    let point_type = TypeId::Class("Point".to_owned());
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![point_type.clone()],
+        vec![
+            Instruction::NewObject("Point".to_owned()),
+            Instruction::StoreLocal(0),
+
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(3),
+            Instruction::StoreField("Point".to_owned(), "x".to_owned()),
+
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(4),
+            Instruction::StoreField("Point".to_owned(), "y".to_owned()),
+
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(10),
+            Instruction::Call(FunctionSignature::new("host.scale".to_owned(), vec![point_type.clone(), TypeId::Int32])),
+            Instruction::LoadField("Point".to_owned(), "x".to_owned()),
+            Instruction::LoadLocal(0),
+            Instruction::LoadField("Point".to_owned(), "y".to_owned()),
+            Instruction::Add,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(30 + 40, execution_result);
+}
+
+#[test]
+fn test_panic1() {
+    let mut vm = VirtualMachine::new();
+
+    vm.register_native("host.check", |value: i32| -> i32 {
+        if value > 2 {
+            panic!("Value too large: {}", value);
+        }
+
+        value
+    }).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Int32],
+        vec![
+            Instruction::LoadInt32(0),
+            Instruction::StoreLocal(0),
+
+            Instruction::LoadLocal(0),
+            Instruction::Call(FunctionSignature::new("host.check".to_owned(), vec![TypeId::Int32])),
+            Instruction::LoadInt32(1),
+            Instruction::Add,
+            Instruction::StoreLocal(0),
+
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(10),
+            Instruction::BranchLessThan(2),
+
+            Instruction::LoadLocal(0),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute();
+    assert_eq!(
+        Err(ExecutionEngineError::Runtime(
+            RuntimeError::NativePanic("Value too large: 3".to_owned()),
+            vec![StackTraceEntry { signature: FunctionSignature::new("main".to_owned(), vec![]), instruction_index: 3 }]
+        )),
+        execution_result
+    );
+}
+
+thread_local!(static PINNED_ARRAY: Cell<ObjectPointer> = Cell::new(std::ptr::null_mut()));
+
+extern "C" fn pinned_sum(array: ObjectPointer) -> i32 {
+    PINNED_ARRAY.with(|pinned_array| pinned_array.set(array));
+
+    native::pin(array, |array| {
+        assert!(get_vm(|vm| vm.memory_manager.garbage_collector.is_pinned(array)));
+        let array_ref = ArrayReference::<i32>::new(array);
+        (0..array_ref.length()).map(|index| unsafe { (array_ref.get_raw(index) as *const i32).read_unaligned() }).sum()
+    })
+}
+
+#[test]
+fn test_pin1() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_external_function(FunctionDeclaration::with_external(
+        "host.pinned_sum".to_owned(), vec![TypeId::Array(Box::new(TypeId::Int32))], TypeId::Int32,
+        pinned_sum as *mut std::ffi::c_void
+    ));
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Array(Box::new(TypeId::Int32))],
+        vec![
+            Instruction::LoadInt32(3),
+            Instruction::NewArray(TypeId::Int32),
+            Instruction::StoreLocal(0),
+
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(1),
+            Instruction::LoadInt32(4711),
+            Instruction::StoreElement(TypeId::Int32),
+
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(2),
+            Instruction::LoadInt32(1337),
+            Instruction::StoreElement(TypeId::Int32),
+
+            Instruction::LoadLocal(0),
+            Instruction::Call(FunctionSignature::new("host.pinned_sum".to_owned(), vec![TypeId::Array(Box::new(TypeId::Int32))])),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(4711 + 1337, execution_result);
+
+    // The object is unpinned when the scope ends
+    get_vm(|vm| {
+        assert!(!vm.memory_manager.garbage_collector.is_pinned(PINNED_ARRAY.with(|pinned_array| pinned_array.get())));
+    });
+}
@@ -371,4 +371,89 @@ fn test_locals10() {
 
     let execution_result = vm.execute().unwrap();
     assert_eq!(1337 + 1337 + 1337, execution_result);
-}
\ No newline at end of file
+}
+#[test]
+fn test_duplicate1() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadInt32(1337),
+            Instruction::Duplicate,
+            Instruction::Multiply,
+            Instruction::Duplicate,
+            Instruction::Add,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(1337 * 1337 * 2, execution_result);
+}
+
+#[test]
+fn test_pop1() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadInt32(1337),
+            Instruction::LoadInt32(4711),
+            Instruction::Pop,
+            Instruction::LoadFloat32(13.37),
+            Instruction::Pop,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(1337, execution_result);
+}
+
+#[test]
+fn test_swap1() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Int32],
+        vec![
+            Instruction::LoadInt32(1337),
+            Instruction::StoreLocal(0),
+            Instruction::LoadInt32(4711),
+            Instruction::LoadLocal(0),
+            Instruction::Swap,
+            Instruction::Sub,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(1337 - 4711, execution_result);
+}
+
+#[test]
+fn test_swap2() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadInt32(1337),
+            Instruction::LoadFloat32(47.11),
+            Instruction::Swap,
+            Instruction::ConvertInt32ToFloat32,
+            Instruction::Add,
+            Instruction::ConvertFloat32ToInt32,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!((1337.0f32 + 47.11f32) as i32, execution_result);
+}
@@ -0,0 +1,276 @@
+use crate::model::function::{Function, FunctionDeclaration, FunctionSignature};
+use crate::model::instruction::Instruction;
+use crate::model::typesystem::TypeId;
+use crate::vm::{VirtualMachine, get_vm};
+use crate::engine::execution::{ExecutionEngineError, RuntimeError};
+use crate::runtime::memory::heap::HeapSettings;
+
+#[test]
+fn test_literal1() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadString("Hello, World!".to_owned()),
+            Instruction::Call(FunctionSignature::new("std.string.length".to_owned(), vec![TypeId::String])),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(13, execution_result);
+}
+
+#[test]
+fn test_literal2() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadString("Hallå".to_owned()),
+            Instruction::Call(FunctionSignature::new("std.string.length".to_owned(), vec![TypeId::String])),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(5, execution_result);
+}
+
+#[test]
+fn test_concat1() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadString("Hello, ".to_owned()),
+            Instruction::LoadString("World!".to_owned()),
+            Instruction::Call(FunctionSignature::new("std.string.concat".to_owned(), vec![TypeId::String, TypeId::String])),
+            Instruction::LoadString("Hello, World!".to_owned()),
+            Instruction::Call(FunctionSignature::new("std.string.compare".to_owned(), vec![TypeId::String, TypeId::String])),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(0, execution_result);
+}
+
+#[test]
+fn test_substring1() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadString("Hello, World!".to_owned()),
+            Instruction::LoadInt32(7),
+            Instruction::LoadInt32(5),
+            Instruction::Call(FunctionSignature::new("std.string.substring".to_owned(), vec![TypeId::String, TypeId::Int32, TypeId::Int32])),
+            Instruction::LoadString("World".to_owned()),
+            Instruction::Call(FunctionSignature::new("std.string.compare".to_owned(), vec![TypeId::String, TypeId::String])),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(0, execution_result);
+}
+
+#[test]
+fn test_compare1() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadString("abc".to_owned()),
+            Instruction::LoadString("abd".to_owned()),
+            Instruction::Call(FunctionSignature::new("std.string.compare".to_owned(), vec![TypeId::String, TypeId::String])),
+            Instruction::LoadInt32(10),
+            Instruction::Multiply,
+            Instruction::LoadString("b".to_owned()),
+            Instruction::LoadString("a".to_owned()),
+            Instruction::Call(FunctionSignature::new("std.string.compare".to_owned(), vec![TypeId::String, TypeId::String])),
+            Instruction::Add,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(-10 + 1, execution_result);
+}
+
+#[test]
+fn test_format1() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadInt32(-4711),
+            Instruction::Call(FunctionSignature::new("std.string.from_int".to_owned(), vec![TypeId::Int32])),
+            Instruction::LoadString(" ".to_owned()),
+            Instruction::Call(FunctionSignature::new("std.string.concat".to_owned(), vec![TypeId::String, TypeId::String])),
+            Instruction::LoadFloat32(13.37),
+            Instruction::Call(FunctionSignature::new("std.string.from_float".to_owned(), vec![TypeId::Float32])),
+            Instruction::Call(FunctionSignature::new("std.string.concat".to_owned(), vec![TypeId::String, TypeId::String])),
+            Instruction::LoadString("-4711 13.37".to_owned()),
+            Instruction::Call(FunctionSignature::new("std.string.compare".to_owned(), vec![TypeId::String, TypeId::String])),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(0, execution_result);
+}
+
+#[test]
+fn test_collect1() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Int32, TypeId::String],
+        vec![
+            Instruction::LoadString("Hello, ".to_owned()),
+            Instruction::LoadString("World!".to_owned()),
+            Instruction::Call(FunctionSignature::new("std.string.concat".to_owned(), vec![TypeId::String, TypeId::String])),
+            Instruction::StoreLocal(1),
+
+            // Allocate until the strings have been promoted
+            Instruction::LoadInt32(0),
+            Instruction::StoreLocal(0),
+
+            Instruction::LoadInt32(1000),
+            Instruction::NewArray(TypeId::Int32),
+            Instruction::Pop,
+
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(1),
+            Instruction::Add,
+            Instruction::StoreLocal(0),
+
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(1000),
+            Instruction::BranchLessThan(6),
+
+            Instruction::Call(FunctionSignature::new("std.gc.collect".to_owned(), Vec::new())),
+
+            Instruction::LoadLocal(1),
+            Instruction::LoadString("Hello, World!".to_owned()),
+            Instruction::Call(FunctionSignature::new("std.string.compare".to_owned(), vec![TypeId::String, TypeId::String])),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(0, execution_result);
+
+    get_vm(|vm| {
+        assert!(vm.memory_manager.old_generation.used_size() > 0);
+    });
+}
+
+#[test]
+fn test_concat_collect1() {
+    let mut vm = VirtualMachine::new();
+
+    let text = "x".repeat(1024);
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Int32, TypeId::String, TypeId::String],
+        vec![
+            Instruction::LoadString("kept".to_owned()),
+            Instruction::LoadString("".to_owned()),
+            Instruction::Call(FunctionSignature::new("std.string.concat".to_owned(), vec![TypeId::String, TypeId::String])),
+            Instruction::StoreLocal(1),
+
+            // Allocate more strings than fits in the young generation
+            Instruction::LoadInt32(0),
+            Instruction::StoreLocal(0),
+
+            Instruction::LoadString(text.clone()),
+            Instruction::LoadString(text.clone()),
+            Instruction::Call(FunctionSignature::new("std.string.concat".to_owned(), vec![TypeId::String, TypeId::String])),
+            Instruction::StoreLocal(2),
+
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(1),
+            Instruction::Add,
+            Instruction::StoreLocal(0),
+
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(2000),
+            Instruction::BranchLessThan(6),
+
+            Instruction::LoadLocal(1),
+            Instruction::Call(FunctionSignature::new("std.string.length".to_owned(), vec![TypeId::String])),
+            Instruction::LoadLocal(2),
+            Instruction::Call(FunctionSignature::new("std.string.length".to_owned(), vec![TypeId::String])),
+            Instruction::Add,
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(4 + 2048, execution_result);
+}
+
+#[test]
+fn test_concat_out_of_memory1() {
+    let mut vm = VirtualMachine::with_heap_settings(HeapSettings {
+        max_size: 32 * 1024 * 1024,
+        ..Default::default()
+    }).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::String],
+        vec![
+            Instruction::LoadString("x".repeat(1024)),
+            Instruction::StoreLocal(0),
+
+            // Doubles the string until the heap is exhausted
+            Instruction::LoadLocal(0),
+            Instruction::LoadLocal(0),
+            Instruction::Call(FunctionSignature::new("std.string.concat".to_owned(), vec![TypeId::String, TypeId::String])),
+            Instruction::StoreLocal(0),
+            Instruction::Branch(2),
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute();
+    assert!(matches!(execution_result, Err(ExecutionEngineError::Runtime(RuntimeError::OutOfMemory, _))));
+}
+
+#[test]
+fn test_literal_out_of_memory1() {
+    let mut vm = VirtualMachine::with_heap_settings(HeapSettings {
+        initial_size: 4 * 1024 * 1024,
+        max_size: 4 * 1024 * 1024,
+        ..Default::default()
+    }).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadString("x".repeat(8 * 1024 * 1024)),
+            Instruction::Call(FunctionSignature::new("std.string.length".to_owned(), vec![TypeId::String])),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute();
+    assert!(matches!(execution_result, Err(ExecutionEngineError::Runtime(RuntimeError::OutOfMemory, _))));
+}
@@ -0,0 +1,679 @@
+use crate::model::function::{Function, FunctionDeclaration, FunctionSignature};
+use crate::model::instruction::Instruction;
+use crate::model::typesystem::TypeId;
+use crate::model::class::{Class, Field};
+use crate::model::verifier::{VerifyError, VerifyErrorMessage};
+use crate::engine::execution::ExecutionEngineError;
+use crate::vm::{VirtualMachine, get_vm};
+
+fn point_struct() -> Class {
+    Class::new_struct(
+        "Point".to_owned(),
+        vec![
+            Field::new("x".to_owned(), TypeId::Int32),
+            Field::new("y".to_owned(), TypeId::Int32),
+        ]
+    )
+}
+
+fn entry_struct() -> Class {
+    Class::new_struct(
+        "Entry".to_owned(),
+        vec![
+            Field::new("key".to_owned(), TypeId::Int32),
+            Field::new("node".to_owned(), TypeId::Class("Node".to_owned())),
+        ]
+    )
+}
+
+fn node_class() -> Class {
+    Class::new(
+        "Node".to_owned(),
+        vec![
+            Field::new("value".to_owned(), TypeId::Int32),
+        ]
+    )
+}
+
+fn point(x: i32, y: i32) -> Vec<Instruction> {
+    vec![
+        Instruction::NewObject("Point".to_owned()),
+        Instruction::LoadInt32(x),
+        Instruction::StoreField("Point".to_owned(), "x".to_owned()),
+        Instruction::LoadInt32(y),
+        Instruction::StoreField("Point".to_owned(), "y".to_owned()),
+    ]
+}
+
+// Leaves an entry referencing a new node on the stack
+fn entry(key: i32, value: i32) -> Vec<Instruction> {
+    vec![
+        Instruction::NewObject("Entry".to_owned()),
+        Instruction::LoadInt32(key),
+        Instruction::StoreField("Entry".to_owned(), "key".to_owned()),
+        Instruction::NewObject("Node".to_owned()),
+        Instruction::Duplicate,
+        Instruction::LoadInt32(value),
+        Instruction::StoreField("Node".to_owned(), "value".to_owned()),
+        Instruction::StoreField("Entry".to_owned(), "node".to_owned()),
+    ]
+}
+
+// Allocates garbage until there have been collections
+fn allocate_garbage(counter_local: u32, start_index: u32) -> Vec<Instruction> {
+    vec![
+        Instruction::LoadInt32(0),
+        Instruction::StoreLocal(counter_local),
+
+        Instruction::LoadInt32(1000),
+        Instruction::NewArray(TypeId::Int32),
+        Instruction::Pop,
+
+        Instruction::LoadLocal(counter_local),
+        Instruction::LoadInt32(1),
+        Instruction::Add,
+        Instruction::StoreLocal(counter_local),
+
+        Instruction::LoadLocal(counter_local),
+        Instruction::LoadInt32(1000),
+        Instruction::BranchLessThan(start_index + 2),
+
+        Instruction::Call(FunctionSignature::new("std.gc.collect".to_string(), vec![])),
+    ]
+}
+
+fn assert_node_not_deleted() {
+    get_vm(|vm| {
+        assert!(vm.memory_manager.garbage_collector.deleted_objects().iter().all(|(_, object_type)| object_type != &TypeId::Class("Node".to_owned())));
+    });
+}
+
+#[test]
+fn test_local1() {
+    let mut vm = VirtualMachine::new();
+    vm.add_class(point_struct()).unwrap();
+
+    let mut instructions = point(4, 7);
+    instructions.extend(vec![
+        Instruction::StoreLocal(0),
+        Instruction::LoadLocal(0),
+        Instruction::LoadField("Point".to_owned(), "x".to_owned()),
+        Instruction::LoadInt32(10),
+        Instruction::Multiply,
+        Instruction::LoadLocal(0),
+        Instruction::LoadField("Point".to_owned(), "y".to_owned()),
+        Instruction::Add,
+        Instruction::Return,
+    ]);
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Struct("Point".to_owned())],
+        instructions
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(47, execution_result);
+}
+
+#[test]
+fn test_local2() {
+    let mut vm = VirtualMachine::new();
+    vm.add_class(point_struct()).unwrap();
+
+    // Structs are copied by value
+    let mut instructions = point(1, 2);
+    instructions.extend(vec![
+        Instruction::StoreLocal(0),
+        Instruction::LoadLocal(0),
+        Instruction::StoreLocal(1),
+
+        Instruction::LoadLocal(1),
+        Instruction::LoadInt32(5),
+        Instruction::StoreField("Point".to_owned(), "x".to_owned()),
+        Instruction::StoreLocal(1),
+
+        Instruction::LoadLocal(0),
+        Instruction::LoadField("Point".to_owned(), "x".to_owned()),
+        Instruction::LoadInt32(10),
+        Instruction::Multiply,
+        Instruction::LoadLocal(1),
+        Instruction::LoadField("Point".to_owned(), "x".to_owned()),
+        Instruction::Add,
+        Instruction::Return,
+    ]);
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Struct("Point".to_owned()), TypeId::Struct("Point".to_owned())],
+        instructions
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(15, execution_result);
+}
+
+#[test]
+fn test_local3() {
+    let mut vm = VirtualMachine::new();
+    vm.add_class(point_struct()).unwrap();
+
+    // Uninitialized struct locals are zero
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Struct("Point".to_owned())],
+        vec![
+            Instruction::LoadLocal(0),
+            Instruction::LoadField("Point".to_owned(), "y".to_owned()),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(0, execution_result);
+}
+
+#[test]
+fn test_stack1() {
+    let mut vm = VirtualMachine::new();
+    vm.add_class(point_struct()).unwrap();
+
+    let mut instructions = point(1, 2);
+    instructions.extend(point(3, 4));
+    instructions.extend(vec![
+        Instruction::Swap,
+        Instruction::Duplicate,
+        Instruction::Pop,
+        Instruction::LoadField("Point".to_owned(), "x".to_owned()),
+        Instruction::LoadInt32(10),
+        Instruction::Multiply,
+        Instruction::Swap,
+        Instruction::LoadField("Point".to_owned(), "y".to_owned()),
+        Instruction::Add,
+        Instruction::Return,
+    ]);
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        instructions
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(14, execution_result);
+}
+
+#[test]
+fn test_mixed_fields1() {
+    let mut vm = VirtualMachine::new();
+
+    vm.add_class(Class::new_struct(
+        "Mixed".to_owned(),
+        vec![
+            Field::new("flag".to_owned(), TypeId::Bool),
+            Field::new("value".to_owned(), TypeId::Float32),
+            Field::new("large".to_owned(), TypeId::Int64),
+            Field::new("precise".to_owned(), TypeId::Float64),
+        ]
+    )).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Struct("Mixed".to_owned())],
+        vec![
+            Instruction::NewObject("Mixed".to_owned()),
+            Instruction::LoadTrue,
+            Instruction::StoreField("Mixed".to_owned(), "flag".to_owned()),
+            Instruction::LoadFloat32(1.5),
+            Instruction::StoreField("Mixed".to_owned(), "value".to_owned()),
+            Instruction::LoadInt64(1 << 40),
+            Instruction::StoreField("Mixed".to_owned(), "large".to_owned()),
+            Instruction::LoadFloat64(2.25),
+            Instruction::StoreField("Mixed".to_owned(), "precise".to_owned()),
+            Instruction::StoreLocal(0),
+
+            Instruction::LoadLocal(0),
+            Instruction::LoadField("Mixed".to_owned(), "flag".to_owned()),
+            Instruction::LoadTrue,
+            Instruction::BranchNotEqual(28),
+            Instruction::LoadLocal(0),
+            Instruction::LoadField("Mixed".to_owned(), "large".to_owned()),
+            Instruction::LoadInt64(1 << 40),
+            Instruction::BranchNotEqual(28),
+            Instruction::LoadLocal(0),
+            Instruction::LoadField("Mixed".to_owned(), "precise".to_owned()),
+            Instruction::LoadFloat64(2.25),
+            Instruction::BranchNotEqual(28),
+            Instruction::LoadLocal(0),
+            Instruction::LoadField("Mixed".to_owned(), "value".to_owned()),
+            Instruction::LoadFloat32(2.0),
+            Instruction::Multiply,
+            Instruction::ConvertFloat32ToInt32,
+            Instruction::Return,
+            Instruction::LoadInt32(0),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(3, execution_result);
+}
+
+#[test]
+fn test_nested1() {
+    let mut vm = VirtualMachine::new();
+
+    let point_class = point_struct();
+    let rectangle = Class::new_struct(
+        "Rectangle".to_owned(),
+        vec![
+            Field::with_struct("min".to_owned(), &point_class),
+            Field::with_struct("max".to_owned(), &point_class),
+        ]
+    );
+    vm.add_class(point_class).unwrap();
+    vm.add_class(rectangle).unwrap();
+
+    let mut instructions = vec![
+        Instruction::NewObject("Rectangle".to_owned()),
+    ];
+    instructions.extend(point(1, 2));
+    instructions.push(Instruction::StoreField("Rectangle".to_owned(), "min".to_owned()));
+    instructions.extend(point(5, 9));
+    instructions.push(Instruction::StoreField("Rectangle".to_owned(), "max".to_owned()));
+    instructions.extend(vec![
+        Instruction::StoreLocal(0),
+
+        // (max.x - min.x) * (max.y - min.y)
+        Instruction::LoadLocal(0),
+        Instruction::LoadField("Rectangle".to_owned(), "max".to_owned()),
+        Instruction::LoadField("Point".to_owned(), "x".to_owned()),
+        Instruction::LoadLocal(0),
+        Instruction::LoadField("Rectangle".to_owned(), "min".to_owned()),
+        Instruction::LoadField("Point".to_owned(), "x".to_owned()),
+        Instruction::Sub,
+        Instruction::LoadLocal(0),
+        Instruction::LoadField("Rectangle".to_owned(), "max".to_owned()),
+        Instruction::LoadField("Point".to_owned(), "y".to_owned()),
+        Instruction::LoadLocal(0),
+        Instruction::LoadField("Rectangle".to_owned(), "min".to_owned()),
+        Instruction::LoadField("Point".to_owned(), "y".to_owned()),
+        Instruction::Sub,
+        Instruction::Multiply,
+        Instruction::Return,
+    ]);
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Struct("Rectangle".to_owned())],
+        instructions
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(28, execution_result);
+}
+
+#[test]
+fn test_array1() {
+    let mut vm = VirtualMachine::new();
+    vm.add_class(point_struct()).unwrap();
+
+    let point_array = TypeId::Array(Box::new(TypeId::Struct("Point".to_owned())));
+    let mut instructions = vec![
+        Instruction::LoadInt32(10),
+        Instruction::NewArray(TypeId::Struct("Point".to_owned())),
+        Instruction::StoreLocal(0),
+
+        Instruction::LoadLocal(0),
+        Instruction::LoadInt32(3),
+    ];
+    instructions.extend(point(4, 5));
+    instructions.extend(vec![
+        Instruction::StoreElement(TypeId::Struct("Point".to_owned())),
+
+        Instruction::LoadLocal(0),
+        Instruction::LoadInt32(3),
+        Instruction::LoadElement(TypeId::Struct("Point".to_owned())),
+        Instruction::StoreLocal(1),
+
+        Instruction::LoadLocal(1),
+        Instruction::LoadField("Point".to_owned(), "x".to_owned()),
+        Instruction::LoadLocal(1),
+        Instruction::LoadField("Point".to_owned(), "y".to_owned()),
+        Instruction::Multiply,
+
+        // The neighbouring elements are untouched
+        Instruction::LoadLocal(0),
+        Instruction::LoadInt32(2),
+        Instruction::LoadElement(TypeId::Struct("Point".to_owned())),
+        Instruction::LoadField("Point".to_owned(), "y".to_owned()),
+        Instruction::Add,
+        Instruction::LoadLocal(0),
+        Instruction::LoadInt32(4),
+        Instruction::LoadElement(TypeId::Struct("Point".to_owned())),
+        Instruction::LoadField("Point".to_owned(), "x".to_owned()),
+        Instruction::Add,
+        Instruction::Return,
+    ]);
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![point_array, TypeId::Struct("Point".to_owned())],
+        instructions
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(20, execution_result);
+}
+
+#[test]
+fn test_array2() {
+    let mut vm = VirtualMachine::new();
+    vm.add_class(point_struct()).unwrap();
+
+    // Sums x + y for points (i, 2 * i)
+    let point_array = TypeId::Array(Box::new(TypeId::Struct("Point".to_owned())));
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![point_array, TypeId::Int32, TypeId::Int32],
+        vec![
+            Instruction::LoadInt32(10),
+            Instruction::NewArray(TypeId::Struct("Point".to_owned())),
+            Instruction::StoreLocal(0),
+
+            Instruction::LoadLocal(0),
+            Instruction::LoadLocal(1),
+            Instruction::NewObject("Point".to_owned()),
+            Instruction::LoadLocal(1),
+            Instruction::StoreField("Point".to_owned(), "x".to_owned()),
+            Instruction::LoadLocal(1),
+            Instruction::LoadInt32(2),
+            Instruction::Multiply,
+            Instruction::StoreField("Point".to_owned(), "y".to_owned()),
+            Instruction::StoreElement(TypeId::Struct("Point".to_owned())),
+            Instruction::LoadLocal(1),
+            Instruction::LoadInt32(1),
+            Instruction::Add,
+            Instruction::StoreLocal(1),
+            Instruction::LoadLocal(1),
+            Instruction::LoadInt32(10),
+            Instruction::BranchLessThan(3),
+
+            Instruction::LoadInt32(0),
+            Instruction::StoreLocal(1),
+
+            Instruction::LoadLocal(2),
+            Instruction::LoadLocal(0),
+            Instruction::LoadLocal(1),
+            Instruction::LoadElement(TypeId::Struct("Point".to_owned())),
+            Instruction::LoadField("Point".to_owned(), "x".to_owned()),
+            Instruction::Add,
+            Instruction::LoadLocal(0),
+            Instruction::LoadLocal(1),
+            Instruction::LoadElement(TypeId::Struct("Point".to_owned())),
+            Instruction::LoadField("Point".to_owned(), "y".to_owned()),
+            Instruction::Add,
+            Instruction::StoreLocal(2),
+            Instruction::LoadLocal(1),
+            Instruction::LoadInt32(1),
+            Instruction::Add,
+            Instruction::StoreLocal(1),
+            Instruction::LoadLocal(1),
+            Instruction::LoadInt32(10),
+            Instruction::BranchLessThan(22),
+
+            Instruction::LoadLocal(2),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(135, execution_result);
+}
+
+#[test]
+fn test_class_field1() {
+    let mut vm = VirtualMachine::new();
+
+    let point_class = point_struct();
+    let line = Class::new(
+        "Line".to_owned(),
+        vec![
+            Field::with_struct("start".to_owned(), &point_class),
+            Field::new("width".to_owned(), TypeId::Int32),
+            Field::with_struct("end".to_owned(), &point_class),
+        ]
+    );
+    vm.add_class(point_class).unwrap();
+    vm.add_class(line).unwrap();
+
+    let mut instructions = vec![
+        Instruction::NewObject("Line".to_owned()),
+        Instruction::StoreLocal(0),
+
+        Instruction::LoadLocal(0),
+        Instruction::LoadInt32(3),
+        Instruction::StoreField("Line".to_owned(), "width".to_owned()),
+
+        Instruction::LoadLocal(0),
+    ];
+    instructions.extend(point(1, 2));
+    instructions.push(Instruction::StoreField("Line".to_owned(), "start".to_owned()));
+    instructions.push(Instruction::LoadLocal(0));
+    instructions.extend(point(6, 8));
+    instructions.push(Instruction::StoreField("Line".to_owned(), "end".to_owned()));
+    instructions.extend(vec![
+        Instruction::LoadLocal(0),
+        Instruction::LoadField("Line".to_owned(), "end".to_owned()),
+        Instruction::LoadField("Point".to_owned(), "y".to_owned()),
+        Instruction::LoadLocal(0),
+        Instruction::LoadField("Line".to_owned(), "start".to_owned()),
+        Instruction::LoadField("Point".to_owned(), "x".to_owned()),
+        Instruction::Sub,
+        Instruction::LoadLocal(0),
+        Instruction::LoadField("Line".to_owned(), "width".to_owned()),
+        Instruction::Multiply,
+        Instruction::Return,
+    ]);
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Class("Line".to_owned())],
+        instructions
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(21, execution_result);
+}
+
+#[test]
+fn test_static_field1() {
+    let mut vm = VirtualMachine::new();
+
+    let point_class = point_struct();
+    let mut class = Class::new("Globals".to_owned(), Vec::new());
+    class.add_static_field(Field::with_struct("origin".to_owned(), &point_class));
+    vm.add_class(point_class).unwrap();
+    vm.add_class(class).unwrap();
+
+    let mut instructions = point(3, 4);
+    instructions.extend(vec![
+        Instruction::StoreStaticField("Globals".to_owned(), "origin".to_owned()),
+        Instruction::LoadStaticField("Globals".to_owned(), "origin".to_owned()),
+        Instruction::LoadField("Point".to_owned(), "x".to_owned()),
+        Instruction::LoadStaticField("Globals".to_owned(), "origin".to_owned()),
+        Instruction::LoadField("Point".to_owned(), "y".to_owned()),
+        Instruction::Multiply,
+        Instruction::Return,
+    ]);
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        instructions
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(12, execution_result);
+}
+
+#[test]
+fn test_gc_local1() {
+    let mut vm = VirtualMachine::new();
+    vm.add_class(node_class()).unwrap();
+    vm.add_class(entry_struct()).unwrap();
+
+    let mut instructions = entry(1, 4711);
+    instructions.push(Instruction::StoreLocal(0));
+    instructions.extend(allocate_garbage(1, instructions.len() as u32));
+    instructions.extend(vec![
+        Instruction::LoadLocal(0),
+        Instruction::LoadField("Entry".to_owned(), "node".to_owned()),
+        Instruction::LoadField("Node".to_owned(), "value".to_owned()),
+        Instruction::Return,
+    ]);
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Struct("Entry".to_owned()), TypeId::Int32],
+        instructions
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(4711, execution_result);
+    assert_node_not_deleted();
+}
+
+#[test]
+fn test_gc_array1() {
+    let mut vm = VirtualMachine::new();
+    vm.add_class(node_class()).unwrap();
+    vm.add_class(entry_struct()).unwrap();
+
+    let entry_array = TypeId::Array(Box::new(TypeId::Struct("Entry".to_owned())));
+    let mut instructions = vec![
+        Instruction::LoadInt32(4),
+        Instruction::NewArray(TypeId::Struct("Entry".to_owned())),
+        Instruction::StoreLocal(0),
+
+        Instruction::LoadLocal(0),
+        Instruction::LoadInt32(2),
+    ];
+    instructions.extend(entry(1, 4711));
+    instructions.push(Instruction::StoreElement(TypeId::Struct("Entry".to_owned())));
+    instructions.extend(allocate_garbage(1, instructions.len() as u32));
+    instructions.extend(vec![
+        Instruction::LoadLocal(0),
+        Instruction::LoadInt32(2),
+        Instruction::LoadElement(TypeId::Struct("Entry".to_owned())),
+        Instruction::LoadField("Entry".to_owned(), "node".to_owned()),
+        Instruction::LoadField("Node".to_owned(), "value".to_owned()),
+        Instruction::Return,
+    ]);
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![entry_array, TypeId::Int32],
+        instructions
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(4711, execution_result);
+    assert_node_not_deleted();
+}
+
+#[test]
+fn test_gc_field1() {
+    let mut vm = VirtualMachine::new();
+    vm.add_class(node_class()).unwrap();
+
+    let entry = entry_struct();
+    let holder = Class::new(
+        "Holder".to_owned(),
+        vec![
+            Field::new("count".to_owned(), TypeId::Int32),
+            Field::with_struct("entry".to_owned(), &entry),
+        ]
+    );
+    vm.add_class(entry).unwrap();
+    vm.add_class(holder).unwrap();
+
+    let mut instructions = vec![
+        Instruction::NewObject("Holder".to_owned()),
+        Instruction::StoreLocal(0),
+        Instruction::LoadLocal(0),
+    ];
+    instructions.extend(self::entry(1, 4711));
+    instructions.push(Instruction::StoreField("Holder".to_owned(), "entry".to_owned()));
+    instructions.extend(allocate_garbage(1, instructions.len() as u32));
+    instructions.extend(vec![
+        Instruction::LoadLocal(0),
+        Instruction::LoadField("Holder".to_owned(), "entry".to_owned()),
+        Instruction::LoadField("Entry".to_owned(), "node".to_owned()),
+        Instruction::LoadField("Node".to_owned(), "value".to_owned()),
+        Instruction::Return,
+    ]);
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Class("Holder".to_owned()), TypeId::Int32],
+        instructions
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(4711, execution_result);
+    assert_node_not_deleted();
+}
+
+#[test]
+fn test_gc_static_field1() {
+    let mut vm = VirtualMachine::new();
+    vm.add_class(node_class()).unwrap();
+
+    let entry = entry_struct();
+    let mut class = Class::new("Globals".to_owned(), Vec::new());
+    class.add_static_field(Field::with_struct("entry".to_owned(), &entry));
+    vm.add_class(entry).unwrap();
+    vm.add_class(class).unwrap();
+
+    let mut instructions = self::entry(1, 4711);
+    instructions.push(Instruction::StoreStaticField("Globals".to_owned(), "entry".to_owned()));
+    instructions.extend(allocate_garbage(0, instructions.len() as u32));
+    instructions.extend(vec![
+        Instruction::LoadStaticField("Globals".to_owned(), "entry".to_owned()),
+        Instruction::LoadField("Entry".to_owned(), "node".to_owned()),
+        Instruction::LoadField("Node".to_owned(), "value".to_owned()),
+        Instruction::Return,
+    ]);
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Int32],
+        instructions
+    )).unwrap();
+
+    let execution_result = vm.execute().unwrap();
+    assert_eq!(4711, execution_result);
+    assert_node_not_deleted();
+}
+
+#[test]
+fn test_invalid1() {
+    let mut vm = VirtualMachine::new();
+    vm.add_class(point_struct()).unwrap();
+
+    vm.add_function(Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), vec![TypeId::Struct("Point".to_owned())], TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadInt32(0),
+            Instruction::Return,
+        ]
+    )).unwrap();
+
+    let execution_result = vm.execute();
+    assert_eq!(
+        Err(ExecutionEngineError::Verify(VerifyError::new(VerifyErrorMessage::InvalidStructUsage(TypeId::Struct("Point".to_owned()))))),
+        execution_result
+    );
+}
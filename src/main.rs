@@ -1,5 +1,3 @@
-#![allow(unused_imports)]
-
 #[macro_use]
 extern crate lazy_static;
 
@@ -12,7 +10,10 @@ mod runtime;
 mod engine;
 mod vm;
 mod parser;
+#[cfg(test)]
+#[allow(unused_imports, clippy::needless_return, clippy::missing_const_for_thread_local)]
 mod execution_tests;
+#[cfg(test)]
 mod integration_tests;
 
 use crate::vm::VirtualMachine;
@@ -33,7 +34,7 @@ pub fn main_execute(input_file: String) -> ExecutionEngineResult<i32> {
     }
 
     for class in classes {
-        vm.add_class(class);
+        vm.add_class(class)?;
     }
 
     vm.execute()
@@ -41,6 +42,20 @@ pub fn main_execute(input_file: String) -> ExecutionEngineResult<i32> {
 
 fn main() {
     let input_file = std::env::args().collect::<Vec<_>>().get(1).expect("Expected input file.").clone();
-    let result = main_execute(input_file).unwrap();
-    println!("{}", result);
+    match main_execute(input_file) {
+        Ok(result) => {
+            println!("{}", result);
+        }
+        Err(ExecutionEngineError::Runtime(error, stack_trace)) => {
+            println!("Runtime error: {:?}", error);
+            for entry in stack_trace {
+                println!("\tat {}", entry);
+            }
+
+            std::process::exit(1);
+        }
+        Err(err) => {
+            panic!("{:?}", err);
+        }
+    }
 }
@@ -1,8 +1,6 @@
 use std::collections::{HashMap, HashSet};
 
 use crate::compiler::ir::BranchLabel;
-use crate::mir::InstructionMIR;
-use crate::mir::InstructionMIRData;
 use crate::model::instruction;
 use crate::model::instruction::Instruction;
 
@@ -35,6 +33,24 @@ impl BranchManager {
         }
     }
 
+    pub fn add_branch_target(&mut self, target: instruction::BranchTarget) -> BranchLabel {
+        self.branch_targets.insert(target);
+
+        if let Some(label) = self.branch_labels.get(&target) {
+            *label
+        } else {
+            let label = self.create_label();
+            self.branch_labels.insert(target, label);
+            label
+        }
+    }
+
+    pub fn create_label(&mut self) -> BranchLabel {
+        let label = self.next_branch_label;
+        self.next_branch_label += 1;
+        label
+    }
+
     pub fn is_branch(&self, instruction_index: usize) -> Option<BranchLabel> {
         let branch_target = instruction_index as instruction::BranchTarget;
         if self.branch_targets.contains(&branch_target) {
@@ -1,28 +1,33 @@
 use std::collections::HashMap;
 
-use crate::optimization::null_check_elision::InstructionsRegisterNullStatus;
-use crate::analysis::VirtualRegister;
-use crate::compiler::ir::Condition;
-use crate::model::binder::Binder;
+use crate::compiler::ir::{BranchLabel, Condition, RoundingMode};
+use crate::compiler::stack_layout::RegisterSlots;
+use crate::model::binder::{Binder, ARRAY_FUNCTION_ELEMENTS};
 use crate::mir::{InstructionMIR, RegisterMIR};
 use crate::mir::branches::BranchManager;
 use crate::mir::InstructionMIRData;
-use crate::model::function::{Function, FunctionDeclaration, FunctionSignature};
-use crate::model::instruction::Instruction;
+use crate::model::function::{Function, FunctionSignature};
+use crate::model::instruction::{Instruction, BranchTarget};
+use crate::model::exception::{FinallyHandler, EXCEPTION_CLASS};
 use crate::model::typesystem::{TypeId, TypeStorage};
+
+#[cfg(test)]
+use crate::model::function::FunctionDeclaration;
+#[cfg(test)]
 use crate::model::verifier::Verifier;
 
 pub struct MIRCompilationResult {
     pub instructions: Vec<InstructionMIR>,
-    pub num_virtual_registers: usize,
     pub local_virtual_registers: Vec<RegisterMIR>,
     pub need_zero_initialize_registers: Vec<RegisterMIR>,
-    pub instructions_operand_stack: Vec<Vec<RegisterMIR>>
+    pub instructions_operand_stack: Vec<Vec<RegisterMIR>>,
+    pub struct_references: HashMap<TypeId, Vec<usize>>,
+    pub register_slots: RegisterSlots
 }
 
 impl MIRCompilationResult {
     pub fn member_this_register(&self, function: &Function) -> Option<&RegisterMIR> {
-        function.declaration().class().as_ref().map(|_| self.local_virtual_registers.last()).flatten()
+        function.declaration().class().as_ref().and_then(|_| self.local_virtual_registers.last())
     }
 }
 
@@ -35,8 +40,9 @@ pub struct InstructionMIRCompiler<'a> {
     local_virtual_registers: Vec<RegisterMIR>,
     need_zero_initialize_registers: Vec<RegisterMIR>,
     next_operand_virtual_register: u32,
-    max_num_virtual_register: usize,
     instructions_operands: Vec<Vec<RegisterMIR>>,
+    finally_handlers: HashMap<usize, (RegisterMIR, BranchLabel)>,
+    #[allow(clippy::type_complexity)]
     macros: HashMap<FunctionSignature, Box<dyn Fn(&mut InstructionMIRCompiler, usize, &Instruction) + 'a>>
 }
 
@@ -53,8 +59,8 @@ impl<'a> InstructionMIRCompiler<'a> {
             local_virtual_registers: Vec::new(),
             need_zero_initialize_registers: Vec::new(),
             next_operand_virtual_register: 0,
-            max_num_virtual_register: 0,
             instructions_operands: Vec::new(),
+            finally_handlers: HashMap::new(),
             macros: HashMap::new()
         };
 
@@ -78,6 +84,133 @@ impl<'a> InstructionMIRCompiler<'a> {
             }
         );
 
+        // Math functions with a direct x86 instruction are expanded instead of being called
+        compiler.make_macro(
+            FunctionSignature::new("std.math.sqrt".to_string(), vec![TypeId::Float32]),
+            |compiler: &mut InstructionMIRCompiler, instruction_index: usize, _instruction: &Instruction| {
+                let op_reg = compiler.use_stack_register(TypeId::Float32);
+                let assign_reg = compiler.assign_stack_register(TypeId::Float32);
+                compiler.instructions.push(InstructionMIR::new(
+                    instruction_index,
+                    InstructionMIRData::SquareRootFloat32(assign_reg, op_reg)
+                ));
+            }
+        );
+
+        compiler.make_macro(
+            FunctionSignature::new("std.math.min".to_string(), vec![TypeId::Float32, TypeId::Float32]),
+            |compiler: &mut InstructionMIRCompiler, instruction_index: usize, _instruction: &Instruction| {
+                let op2_reg = compiler.use_stack_register(TypeId::Float32);
+                let op1_reg = compiler.use_stack_register(TypeId::Float32);
+                let assign_reg = compiler.assign_stack_register(TypeId::Float32);
+                compiler.instructions.push(InstructionMIR::new(
+                    instruction_index,
+                    InstructionMIRData::MinFloat32(assign_reg, op1_reg, op2_reg)
+                ));
+            }
+        );
+
+        compiler.make_macro(
+            FunctionSignature::new("std.math.max".to_string(), vec![TypeId::Float32, TypeId::Float32]),
+            |compiler: &mut InstructionMIRCompiler, instruction_index: usize, _instruction: &Instruction| {
+                let op2_reg = compiler.use_stack_register(TypeId::Float32);
+                let op1_reg = compiler.use_stack_register(TypeId::Float32);
+                let assign_reg = compiler.assign_stack_register(TypeId::Float32);
+                compiler.instructions.push(InstructionMIR::new(
+                    instruction_index,
+                    InstructionMIRData::MaxFloat32(assign_reg, op1_reg, op2_reg)
+                ));
+            }
+        );
+
+        compiler.make_macro(
+            FunctionSignature::new("std.math.floor".to_string(), vec![TypeId::Float32]),
+            |compiler: &mut InstructionMIRCompiler, instruction_index: usize, _instruction: &Instruction| {
+                let op_reg = compiler.use_stack_register(TypeId::Float32);
+                let assign_reg = compiler.assign_stack_register(TypeId::Float32);
+                compiler.instructions.push(InstructionMIR::new(
+                    instruction_index,
+                    InstructionMIRData::RoundFloat32(assign_reg, op_reg, RoundingMode::Floor)
+                ));
+            }
+        );
+
+        compiler.make_macro(
+            FunctionSignature::new("std.math.ceil".to_string(), vec![TypeId::Float32]),
+            |compiler: &mut InstructionMIRCompiler, instruction_index: usize, _instruction: &Instruction| {
+                let op_reg = compiler.use_stack_register(TypeId::Float32);
+                let assign_reg = compiler.assign_stack_register(TypeId::Float32);
+                compiler.instructions.push(InstructionMIR::new(
+                    instruction_index,
+                    InstructionMIRData::RoundFloat32(assign_reg, op_reg, RoundingMode::Ceil)
+                ));
+            }
+        );
+
+        // The array functions operate on the whole array at once, which requires only a single range check
+        for element in &ARRAY_FUNCTION_ELEMENTS {
+            let array_type = TypeId::Array(Box::new(element.clone()));
+
+            let copy_element = element.clone();
+            compiler.make_macro(
+                FunctionSignature::new("std.array.copy".to_string(), vec![array_type.clone(), TypeId::Int32, array_type.clone(), TypeId::Int32, TypeId::Int32]),
+                move |compiler: &mut InstructionMIRCompiler, instruction_index: usize, _instruction: &Instruction| {
+                    let array_type = TypeId::Array(Box::new(copy_element.clone()));
+                    let count_reg = compiler.use_stack_register(TypeId::Int32);
+                    let destination_index_reg = compiler.use_stack_register(TypeId::Int32);
+                    let destination_reg = compiler.use_stack_register(array_type.clone());
+                    let source_index_reg = compiler.use_stack_register(TypeId::Int32);
+                    let source_reg = compiler.use_stack_register(array_type);
+                    compiler.instructions.push(InstructionMIR::new(
+                        instruction_index,
+                        InstructionMIRData::ArrayCopy(copy_element.clone(), source_reg, source_index_reg, destination_reg, destination_index_reg, count_reg)
+                    ));
+                }
+            );
+
+            let fill_element = element.clone();
+            compiler.make_macro(
+                FunctionSignature::new("std.array.fill".to_string(), vec![array_type.clone(), element.clone()]),
+                move |compiler: &mut InstructionMIRCompiler, instruction_index: usize, _instruction: &Instruction| {
+                    let value_reg = compiler.use_stack_register(fill_element.clone());
+                    let array_ref_reg = compiler.use_stack_register(TypeId::Array(Box::new(fill_element.clone())));
+                    compiler.instructions.push(InstructionMIR::new(
+                        instruction_index,
+                        InstructionMIRData::ArrayFill(fill_element.clone(), array_ref_reg, value_reg)
+                    ));
+                }
+            );
+
+            let clone_element = element.clone();
+            compiler.make_macro(
+                FunctionSignature::new("std.array.clone".to_string(), vec![array_type.clone()]),
+                move |compiler: &mut InstructionMIRCompiler, instruction_index: usize, _instruction: &Instruction| {
+                    let array_type = TypeId::Array(Box::new(clone_element.clone()));
+                    let array_ref_reg = compiler.use_stack_register(array_type.clone());
+                    let assign_reg = compiler.assign_stack_register(array_type);
+                    compiler.instructions.push(InstructionMIR::new(
+                        instruction_index,
+                        InstructionMIRData::ArrayClone(clone_element.clone(), assign_reg, array_ref_reg)
+                    ));
+                }
+            );
+
+            let equals_element = element.clone();
+            compiler.make_macro(
+                FunctionSignature::new("std.array.equals".to_string(), vec![array_type.clone(), array_type]),
+                move |compiler: &mut InstructionMIRCompiler, instruction_index: usize, _instruction: &Instruction| {
+                    let array_type = TypeId::Array(Box::new(equals_element.clone()));
+                    let op2_reg = compiler.use_stack_register(array_type.clone());
+                    let op1_reg = compiler.use_stack_register(array_type);
+                    let assign_reg = compiler.assign_stack_register(TypeId::Bool);
+                    compiler.instructions.push(InstructionMIR::new(
+                        instruction_index,
+                        InstructionMIRData::ArrayEquals(equals_element.clone(), assign_reg, op1_reg, op2_reg)
+                    ));
+                }
+            );
+        }
+
         compiler
     }
 
@@ -97,6 +230,21 @@ impl<'a> InstructionMIRCompiler<'a> {
             self.next_operand_virtual_register += 1;
         }
 
+        // Regions with a finally handler stores the exception being propagated in a hidden local
+        for (region_index, region) in self.function.protected_regions().iter().enumerate() {
+            self.branch_manager.add_branch_target((region.end() + 1) as BranchTarget);
+
+            if region.finally_handler.is_some() {
+                let pending_exception = RegisterMIR::new(self.next_operand_virtual_register, TypeId::Class(EXCEPTION_CLASS.to_owned()));
+                self.local_virtual_registers.push(pending_exception.clone());
+                self.need_zero_initialize_registers.push(pending_exception.clone());
+                self.next_operand_virtual_register += 1;
+
+                let finally_label = self.branch_manager.create_label();
+                self.finally_handlers.insert(region_index, (pending_exception, finally_label));
+            }
+        }
+
         if let Some(class) = self.function.declaration().class() {
             self.local_virtual_registers.push(
                 RegisterMIR::new(self.next_operand_virtual_register, class.clone())
@@ -104,8 +252,6 @@ impl<'a> InstructionMIRCompiler<'a> {
             self.next_operand_virtual_register += 1;
         }
 
-        self.max_num_virtual_register = self.local_virtual_registers.len();
-
         for (instruction_index, instruction) in instructions.iter().enumerate() {
             self.compile_instruction(instruction_index, instruction);
         }
@@ -136,6 +282,14 @@ impl<'a> InstructionMIRCompiler<'a> {
                 let assign_reg = self.assign_stack_register(TypeId::Float32);
                 self.instructions.push(InstructionMIR::new(instruction_index, InstructionMIRData::LoadFloat32(assign_reg, *value)));
             }
+            Instruction::LoadInt64(value) => {
+                let assign_reg = self.assign_stack_register(TypeId::Int64);
+                self.instructions.push(InstructionMIR::new(instruction_index, InstructionMIRData::LoadInt64(assign_reg, *value)));
+            }
+            Instruction::LoadFloat64(value) => {
+                let assign_reg = self.assign_stack_register(TypeId::Float64);
+                self.instructions.push(InstructionMIR::new(instruction_index, InstructionMIRData::LoadFloat64(assign_reg, *value)));
+            }
             Instruction::LoadTrue => {
                 let assign_reg = self.assign_stack_register(TypeId::Bool);
                 self.instructions.push(InstructionMIR::new(instruction_index, InstructionMIRData::LoadBool(assign_reg, true)));
@@ -151,11 +305,38 @@ impl<'a> InstructionMIRCompiler<'a> {
             }
             Instruction::StoreLocal(index) => {
                 let local_reg = self.local_virtual_registers[*index as usize].clone();
-                let value_reg = self.use_stack_register(local_reg.value_type.clone());
+                let value_reg = self.use_stack_register(operand_types.last().unwrap().clone());
                 self.instructions.push(InstructionMIR::new(instruction_index, InstructionMIRData::Move(local_reg, value_reg)));
             }
+            Instruction::Duplicate => {
+                let value_type = operand_types.last().unwrap().clone();
+                let value_reg = self.use_stack_register(value_type.clone());
+                self.assign_stack_register(value_type.clone());
+                let duplicate_reg = self.assign_stack_register(value_type);
+                self.instructions.push(InstructionMIR::new(instruction_index, InstructionMIRData::Move(duplicate_reg, value_reg)));
+            }
+            Instruction::Pop => {
+                let value_type = operand_types.last().unwrap().clone();
+                self.use_stack_register(value_type);
+            }
+            Instruction::Swap => {
+                let op1_type = operand_types[operand_types.len() - 2].clone();
+                let op2_type = operand_types[operand_types.len() - 1].clone();
+                let op2_reg = self.use_stack_register(op2_type.clone());
+                let op1_reg = self.use_stack_register(op1_type.clone());
+                let assign_op2_reg = self.assign_stack_register(op2_type);
+                let assign_op1_reg = self.assign_stack_register(op1_type.clone());
+
+                // The register above the top of the stack is used as a temporary
+                let temp_reg = self.assign_stack_register(op1_type.clone());
+                self.use_stack_register(op1_type);
+
+                self.instructions.push(InstructionMIR::new(instruction_index, InstructionMIRData::Move(temp_reg.clone(), op1_reg)));
+                self.instructions.push(InstructionMIR::new(instruction_index, InstructionMIRData::Move(assign_op2_reg, op2_reg)));
+                self.instructions.push(InstructionMIR::new(instruction_index, InstructionMIRData::Move(assign_op1_reg, temp_reg)));
+            }
             Instruction::Add => {
-                let value_type = &operand_types[0];
+                let value_type = operand_types.last().unwrap();
                 let op2_reg = self.use_stack_register(value_type.clone());
                 let op1_reg = self.use_stack_register(value_type.clone());
                 let assign_reg = self.assign_stack_register(value_type.clone());
@@ -167,11 +348,17 @@ impl<'a> InstructionMIRCompiler<'a> {
                     TypeId::Float32 => {
                         self.instructions.push(InstructionMIR::new(instruction_index, InstructionMIRData::AddFloat32(assign_reg, op1_reg, op2_reg)));
                     }
+                    TypeId::Int64 => {
+                        self.instructions.push(InstructionMIR::new(instruction_index, InstructionMIRData::AddInt64(assign_reg, op1_reg, op2_reg)));
+                    }
+                    TypeId::Float64 => {
+                        self.instructions.push(InstructionMIR::new(instruction_index, InstructionMIRData::AddFloat64(assign_reg, op1_reg, op2_reg)));
+                    }
                     _ => { panic!("unexpected."); }
                 }
             }
             Instruction::Sub => {
-                let value_type = &operand_types[0];
+                let value_type = operand_types.last().unwrap();
                 let op2_reg = self.use_stack_register(value_type.clone());
                 let op1_reg = self.use_stack_register(value_type.clone());
                 let assign_reg = self.assign_stack_register(value_type.clone());
@@ -183,11 +370,17 @@ impl<'a> InstructionMIRCompiler<'a> {
                     TypeId::Float32 => {
                         self.instructions.push(InstructionMIR::new(instruction_index, InstructionMIRData::SubFloat32(assign_reg, op1_reg, op2_reg)));
                     }
+                    TypeId::Int64 => {
+                        self.instructions.push(InstructionMIR::new(instruction_index, InstructionMIRData::SubInt64(assign_reg, op1_reg, op2_reg)));
+                    }
+                    TypeId::Float64 => {
+                        self.instructions.push(InstructionMIR::new(instruction_index, InstructionMIRData::SubFloat64(assign_reg, op1_reg, op2_reg)));
+                    }
                     _ => { panic!("unexpected."); }
                 }
             }
             Instruction::Multiply => {
-                let value_type = &operand_types[0];
+                let value_type = operand_types.last().unwrap();
                 let op2_reg = self.use_stack_register(value_type.clone());
                 let op1_reg = self.use_stack_register(value_type.clone());
                 let assign_reg = self.assign_stack_register(value_type.clone());
@@ -199,11 +392,17 @@ impl<'a> InstructionMIRCompiler<'a> {
                     TypeId::Float32 => {
                         self.instructions.push(InstructionMIR::new(instruction_index, InstructionMIRData::MultiplyFloat32(assign_reg, op1_reg, op2_reg)));
                     }
+                    TypeId::Int64 => {
+                        self.instructions.push(InstructionMIR::new(instruction_index, InstructionMIRData::MultiplyInt64(assign_reg, op1_reg, op2_reg)));
+                    }
+                    TypeId::Float64 => {
+                        self.instructions.push(InstructionMIR::new(instruction_index, InstructionMIRData::MultiplyFloat64(assign_reg, op1_reg, op2_reg)));
+                    }
                     _ => { panic!("unexpected."); }
                 }
             }
             Instruction::Divide => {
-                let value_type = &operand_types[0];
+                let value_type = operand_types.last().unwrap();
                 let op2_reg = self.use_stack_register(value_type.clone());
                 let op1_reg = self.use_stack_register(value_type.clone());
                 let assign_reg = self.assign_stack_register(value_type.clone());
@@ -215,11 +414,48 @@ impl<'a> InstructionMIRCompiler<'a> {
                     TypeId::Float32 => {
                         self.instructions.push(InstructionMIR::new(instruction_index, InstructionMIRData::DivideFloat32(assign_reg, op1_reg, op2_reg)));
                     }
+                    TypeId::Int64 => {
+                        self.instructions.push(InstructionMIR::new(instruction_index, InstructionMIRData::DivideInt64(assign_reg, op1_reg, op2_reg)));
+                    }
+                    TypeId::Float64 => {
+                        self.instructions.push(InstructionMIR::new(instruction_index, InstructionMIRData::DivideFloat64(assign_reg, op1_reg, op2_reg)));
+                    }
+                    _ => { panic!("unexpected."); }
+                }
+            }
+            Instruction::Remainder => {
+                let value_type = operand_types.last().unwrap();
+                let op2_reg = self.use_stack_register(value_type.clone());
+                let op1_reg = self.use_stack_register(value_type.clone());
+                let assign_reg = self.assign_stack_register(value_type.clone());
+
+                match value_type {
+                    TypeId::Int32 => {
+                        self.instructions.push(InstructionMIR::new(instruction_index, InstructionMIRData::RemainderInt32(assign_reg, op1_reg, op2_reg)));
+                    }
+                    TypeId::Int64 => {
+                        self.instructions.push(InstructionMIR::new(instruction_index, InstructionMIRData::RemainderInt64(assign_reg, op1_reg, op2_reg)));
+                    }
+                    _ => { panic!("unexpected."); }
+                }
+            }
+            Instruction::Negate => {
+                let value_type = operand_types.last().unwrap();
+                let op_reg = self.use_stack_register(value_type.clone());
+                let assign_reg = self.assign_stack_register(value_type.clone());
+
+                match value_type {
+                    TypeId::Int32 => {
+                        self.instructions.push(InstructionMIR::new(instruction_index, InstructionMIRData::NegateInt32(assign_reg, op_reg)));
+                    }
+                    TypeId::Int64 => {
+                        self.instructions.push(InstructionMIR::new(instruction_index, InstructionMIRData::NegateInt64(assign_reg, op_reg)));
+                    }
                     _ => { panic!("unexpected."); }
                 }
             }
             Instruction::And => {
-                let value_type = &operand_types[0];
+                let value_type = operand_types.last().unwrap();
                 let op2_reg = self.use_stack_register(value_type.clone());
                 let op1_reg = self.use_stack_register(value_type.clone());
                 let assign_reg = self.assign_stack_register(value_type.clone());
@@ -228,11 +464,17 @@ impl<'a> InstructionMIRCompiler<'a> {
                     TypeId::Bool => {
                         self.instructions.push(InstructionMIR::new(instruction_index, InstructionMIRData::AndBool(assign_reg, op1_reg, op2_reg)));
                     }
+                    TypeId::Int32 => {
+                        self.instructions.push(InstructionMIR::new(instruction_index, InstructionMIRData::AndInt32(assign_reg, op1_reg, op2_reg)));
+                    }
+                    TypeId::Int64 => {
+                        self.instructions.push(InstructionMIR::new(instruction_index, InstructionMIRData::AndInt64(assign_reg, op1_reg, op2_reg)));
+                    }
                     _ => { panic!("unexpected."); }
                 }
             }
             Instruction::Or => {
-                let value_type = &operand_types[0];
+                let value_type = operand_types.last().unwrap();
                 let op2_reg = self.use_stack_register(value_type.clone());
                 let op1_reg = self.use_stack_register(value_type.clone());
                 let assign_reg = self.assign_stack_register(value_type.clone());
@@ -241,11 +483,17 @@ impl<'a> InstructionMIRCompiler<'a> {
                     TypeId::Bool => {
                         self.instructions.push(InstructionMIR::new(instruction_index, InstructionMIRData::OrBool(assign_reg, op1_reg, op2_reg)));
                     }
+                    TypeId::Int32 => {
+                        self.instructions.push(InstructionMIR::new(instruction_index, InstructionMIRData::OrInt32(assign_reg, op1_reg, op2_reg)));
+                    }
+                    TypeId::Int64 => {
+                        self.instructions.push(InstructionMIR::new(instruction_index, InstructionMIRData::OrInt64(assign_reg, op1_reg, op2_reg)));
+                    }
                     _ => { panic!("unexpected."); }
                 }
             }
             Instruction::Not => {
-                let value_type = &operand_types[0];
+                let value_type = operand_types.last().unwrap();
                 let op_reg = self.use_stack_register(value_type.clone());
                 let assign_reg = self.assign_stack_register(value_type.clone());
 
@@ -253,12 +501,111 @@ impl<'a> InstructionMIRCompiler<'a> {
                     TypeId::Bool => {
                         self.instructions.push(InstructionMIR::new(instruction_index, InstructionMIRData::NotBool(assign_reg, op_reg)));
                     }
+                    TypeId::Int32 => {
+                        self.instructions.push(InstructionMIR::new(instruction_index, InstructionMIRData::NotInt32(assign_reg, op_reg)));
+                    }
+                    TypeId::Int64 => {
+                        self.instructions.push(InstructionMIR::new(instruction_index, InstructionMIRData::NotInt64(assign_reg, op_reg)));
+                    }
+                    _ => { panic!("unexpected."); }
+                }
+            }
+            Instruction::Xor => {
+                let value_type = operand_types.last().unwrap();
+                let op2_reg = self.use_stack_register(value_type.clone());
+                let op1_reg = self.use_stack_register(value_type.clone());
+                let assign_reg = self.assign_stack_register(value_type.clone());
+
+                match value_type {
+                    TypeId::Int32 => {
+                        self.instructions.push(InstructionMIR::new(instruction_index, InstructionMIRData::XorInt32(assign_reg, op1_reg, op2_reg)));
+                    }
+                    TypeId::Int64 => {
+                        self.instructions.push(InstructionMIR::new(instruction_index, InstructionMIRData::XorInt64(assign_reg, op1_reg, op2_reg)));
+                    }
+                    _ => { panic!("unexpected."); }
+                }
+            }
+            Instruction::ShiftLeft => {
+                let value_type = &operand_types[operand_types.len() - 2];
+                let op2_reg = self.use_stack_register(TypeId::Int32);
+                let op1_reg = self.use_stack_register(value_type.clone());
+                let assign_reg = self.assign_stack_register(value_type.clone());
+
+                match value_type {
+                    TypeId::Int32 => {
+                        self.instructions.push(InstructionMIR::new(instruction_index, InstructionMIRData::ShiftLeftInt32(assign_reg, op1_reg, op2_reg)));
+                    }
+                    TypeId::Int64 => {
+                        self.instructions.push(InstructionMIR::new(instruction_index, InstructionMIRData::ShiftLeftInt64(assign_reg, op1_reg, op2_reg)));
+                    }
+                    _ => { panic!("unexpected."); }
+                }
+            }
+            Instruction::ShiftRight => {
+                let value_type = &operand_types[operand_types.len() - 2];
+                let op2_reg = self.use_stack_register(TypeId::Int32);
+                let op1_reg = self.use_stack_register(value_type.clone());
+                let assign_reg = self.assign_stack_register(value_type.clone());
+
+                match value_type {
+                    TypeId::Int32 => {
+                        self.instructions.push(InstructionMIR::new(instruction_index, InstructionMIRData::ShiftRightInt32(assign_reg, op1_reg, op2_reg)));
+                    }
+                    TypeId::Int64 => {
+                        self.instructions.push(InstructionMIR::new(instruction_index, InstructionMIRData::ShiftRightInt64(assign_reg, op1_reg, op2_reg)));
+                    }
                     _ => { panic!("unexpected."); }
                 }
             }
+            Instruction::ConvertInt32ToFloat32 => {
+                let op_reg = self.use_stack_register(TypeId::Int32);
+                let assign_reg = self.assign_stack_register(TypeId::Float32);
+                self.instructions.push(InstructionMIR::new(instruction_index, InstructionMIRData::ConvertInt32ToFloat32(assign_reg, op_reg)));
+            }
+            Instruction::ConvertFloat32ToInt32 => {
+                let op_reg = self.use_stack_register(TypeId::Float32);
+                let assign_reg = self.assign_stack_register(TypeId::Int32);
+                self.instructions.push(InstructionMIR::new(instruction_index, InstructionMIRData::ConvertFloat32ToInt32(assign_reg, op_reg)));
+            }
+            Instruction::ConvertBoolToInt32 => {
+                let op_reg = self.use_stack_register(TypeId::Bool);
+                let assign_reg = self.assign_stack_register(TypeId::Int32);
+                self.instructions.push(InstructionMIR::new(instruction_index, InstructionMIRData::ConvertBoolToInt32(assign_reg, op_reg)));
+            }
+            Instruction::ConvertInt32ToInt64 => {
+                let op_reg = self.use_stack_register(TypeId::Int32);
+                let assign_reg = self.assign_stack_register(TypeId::Int64);
+                self.instructions.push(InstructionMIR::new(instruction_index, InstructionMIRData::ConvertInt32ToInt64(assign_reg, op_reg)));
+            }
+            Instruction::ConvertInt64ToInt32 => {
+                let op_reg = self.use_stack_register(TypeId::Int64);
+                let assign_reg = self.assign_stack_register(TypeId::Int32);
+                self.instructions.push(InstructionMIR::new(instruction_index, InstructionMIRData::ConvertInt64ToInt32(assign_reg, op_reg)));
+            }
+            Instruction::ConvertFloat32ToFloat64 => {
+                let op_reg = self.use_stack_register(TypeId::Float32);
+                let assign_reg = self.assign_stack_register(TypeId::Float64);
+                self.instructions.push(InstructionMIR::new(instruction_index, InstructionMIRData::ConvertFloat32ToFloat64(assign_reg, op_reg)));
+            }
+            Instruction::ConvertFloat64ToFloat32 => {
+                let op_reg = self.use_stack_register(TypeId::Float64);
+                let assign_reg = self.assign_stack_register(TypeId::Float32);
+                self.instructions.push(InstructionMIR::new(instruction_index, InstructionMIRData::ConvertFloat64ToFloat32(assign_reg, op_reg)));
+            }
+            Instruction::ConvertInt64ToFloat64 => {
+                let op_reg = self.use_stack_register(TypeId::Int64);
+                let assign_reg = self.assign_stack_register(TypeId::Float64);
+                self.instructions.push(InstructionMIR::new(instruction_index, InstructionMIRData::ConvertInt64ToFloat64(assign_reg, op_reg)));
+            }
+            Instruction::ConvertFloat64ToInt64 => {
+                let op_reg = self.use_stack_register(TypeId::Float64);
+                let assign_reg = self.assign_stack_register(TypeId::Int64);
+                self.instructions.push(InstructionMIR::new(instruction_index, InstructionMIRData::ConvertFloat64ToInt64(assign_reg, op_reg)));
+            }
             Instruction::Return => {
                 let return_value = if self.function.declaration().return_type() != &TypeId::Void {
-                    Some(self.use_stack_register(self.function.declaration().return_type().clone()))
+                    Some(self.use_stack_register(operand_types.last().unwrap().clone()))
                 } else {
                     None
                 };
@@ -273,12 +620,7 @@ impl<'a> InstructionMIRCompiler<'a> {
                     }
                     None => {
                         let func_to_call = self.binder.get(signature).unwrap();
-
-                        let mut arguments_regs = func_to_call.parameters()
-                            .iter().rev()
-                            .map(|parameter| self.use_stack_register(parameter.clone()))
-                            .collect::<Vec<_>>();
-                        arguments_regs.reverse();
+                        let arguments_regs = self.use_operand_registers(operand_types, func_to_call.parameters().len());
 
                         let return_value_reg = if func_to_call.return_type() != &TypeId::Void {
                             Some(self.assign_stack_register(func_to_call.return_type().clone()))
@@ -293,6 +635,32 @@ impl<'a> InstructionMIRCompiler<'a> {
                     }
                 }
             }
+            Instruction::LoadFunction(signature) => {
+                let func_to_load = self.binder.get(signature).unwrap();
+                let assign_reg = self.assign_stack_register(func_to_load.reference_type());
+                self.instructions.push(InstructionMIR::new(instruction_index, InstructionMIRData::LoadFunction(signature.clone(), assign_reg)));
+            }
+            Instruction::CallIndirect => {
+                let function_type = operand_types.last().unwrap().clone();
+                let (num_parameters, return_type) = match &function_type {
+                    TypeId::Function(parameters, return_type) => (parameters.len(), return_type.as_ref().clone()),
+                    _ => { panic!("unexpected."); }
+                };
+
+                let function_reg = self.use_stack_register(function_type.clone());
+                let arguments_regs = self.use_operand_registers(&operand_types[..(operand_types.len() - 1)], num_parameters);
+
+                let return_value_reg = if return_type != TypeId::Void {
+                    Some(self.assign_stack_register(return_type))
+                } else {
+                    None
+                };
+
+                self.instructions.push(InstructionMIR::new(
+                    instruction_index,
+                    InstructionMIRData::CallIndirect(function_type, return_value_reg, function_reg, arguments_regs)
+                ));
+            }
             Instruction::LoadArgument(argument_index) => {
                 if *argument_index == 0 && self.function.declaration().class().is_some() {
                     let assign_reg = self.assign_stack_register(self.function.declaration().class().as_ref().unwrap().clone());
@@ -305,6 +673,10 @@ impl<'a> InstructionMIRCompiler<'a> {
                     self.instructions.push(InstructionMIR::new(instruction_index, InstructionMIRData::LoadArgument(*argument_index, assign_reg)));
                 }
             }
+            Instruction::LoadString(value) => {
+                let assign_reg = self.assign_stack_register(TypeId::String);
+                self.instructions.push(InstructionMIR::new(instruction_index, InstructionMIRData::LoadString(assign_reg, value.clone())));
+            }
             Instruction::LoadNull(null_type) => {
                 let assign_reg = self.assign_stack_register(null_type.clone());
                 self.instructions.push(InstructionMIR::new(instruction_index, InstructionMIRData::LoadNull(assign_reg)));
@@ -327,7 +699,7 @@ impl<'a> InstructionMIRCompiler<'a> {
                 ));
             }
             Instruction::StoreElement(element) => {
-                let value_ref = self.use_stack_register(element.clone());
+                let value_ref = self.use_stack_register(operand_types.last().unwrap().clone());
                 let index_reg = self.use_stack_register(TypeId::Int32);
                 let array_ref_reg = self.use_stack_register(TypeId::Array(Box::new(element.clone())));
                 self.instructions.push(InstructionMIR::new(
@@ -336,47 +708,157 @@ impl<'a> InstructionMIRCompiler<'a> {
                 ));
             }
             Instruction::LoadArrayLength => {
-                let array_ref_reg = self.use_stack_register(operand_types[0].clone());
+                let array_ref_reg = self.use_stack_register(operand_types.last().unwrap().clone());
                 let assign_reg = self.assign_stack_register(TypeId::Int32);
                 self.instructions.push(InstructionMIR::new(instruction_index, InstructionMIRData::LoadArrayLength(assign_reg, array_ref_reg)));
             }
+            Instruction::NewArray2D(element) => {
+                let columns_reg = self.use_stack_register(TypeId::Int32);
+                let rows_reg = self.use_stack_register(TypeId::Int32);
+                let assign_reg = self.assign_stack_register(TypeId::Array2D(Box::new(element.clone())));
+                self.instructions.push(InstructionMIR::new(
+                    instruction_index,
+                    InstructionMIRData::NewArray2D(element.clone(), assign_reg, rows_reg, columns_reg)
+                ));
+            }
+            Instruction::LoadElement2D(element) => {
+                let column_reg = self.use_stack_register(TypeId::Int32);
+                let row_reg = self.use_stack_register(TypeId::Int32);
+                let array_ref_reg = self.use_stack_register(TypeId::Array2D(Box::new(element.clone())));
+                let assign_reg = self.assign_stack_register(element.clone());
+                self.instructions.push(InstructionMIR::new(
+                    instruction_index,
+                    InstructionMIRData::LoadElement2D(element.clone(), assign_reg, array_ref_reg, row_reg, column_reg)
+                ));
+            }
+            Instruction::StoreElement2D(element) => {
+                let value_ref = self.use_stack_register(operand_types.last().unwrap().clone());
+                let column_reg = self.use_stack_register(TypeId::Int32);
+                let row_reg = self.use_stack_register(TypeId::Int32);
+                let array_ref_reg = self.use_stack_register(TypeId::Array2D(Box::new(element.clone())));
+                self.instructions.push(InstructionMIR::new(
+                    instruction_index,
+                    InstructionMIRData::StoreElement2D(element.clone(), array_ref_reg, row_reg, column_reg, value_ref)
+                ));
+            }
+            Instruction::LoadArrayDimension(dimension) => {
+                let array_ref_reg = self.use_stack_register(operand_types.last().unwrap().clone());
+                let assign_reg = self.assign_stack_register(TypeId::Int32);
+                self.instructions.push(InstructionMIR::new(instruction_index, InstructionMIRData::LoadArrayDimension(*dimension, assign_reg, array_ref_reg)));
+            }
             Instruction::NewObject(class_type) => {
+                let class_type = self.type_storage.get_by_name(class_type).unwrap().id.clone();
+                let assign_reg = self.assign_stack_register(class_type.clone());
+                if class_type.is_struct() {
+                    self.instructions.push(InstructionMIR::new(instruction_index, InstructionMIRData::NewStruct(class_type, assign_reg)));
+                } else {
+                    self.instructions.push(InstructionMIR::new(instruction_index, InstructionMIRData::NewObject(class_type, assign_reg)));
+                }
+            }
+            Instruction::NewObjectWithConstructor(class_type, parameters) => {
+                let class_type = TypeId::Class(class_type.clone());
+
+                // The object is allocated above the arguments, such that it is kept alive while the constructor is called
+                let object_reg = self.assign_stack_register(class_type.clone());
+                self.instructions.push(InstructionMIR::new(instruction_index, InstructionMIRData::NewObject(class_type.clone(), object_reg.clone())));
+
+                let mut operand_stack = self.instructions_operands.last().unwrap().clone();
+                operand_stack.push(object_reg.clone());
+
+                let object_reg = self.use_stack_register(class_type.clone());
+                let mut arguments_regs = self.use_operand_registers(operand_types, parameters.len());
+                arguments_regs.insert(0, object_reg.clone());
+
+                self.instructions_operands.push(operand_stack.clone());
+                self.instructions.push(InstructionMIR::new(
+                    instruction_index,
+                    InstructionMIRData::CallInstance(FunctionSignature::with_constructor(class_type.clone(), parameters.clone()), None, arguments_regs)
+                ));
+
+                let assign_reg = self.assign_stack_register(class_type);
+                if assign_reg != object_reg {
+                    operand_stack.truncate(operand_stack.len() - parameters.len() - 1);
+                    operand_stack.push(object_reg.clone());
+                    self.instructions_operands.push(operand_stack);
+                    self.instructions.push(InstructionMIR::new(instruction_index, InstructionMIRData::Move(assign_reg, object_reg)));
+                }
+            }
+            Instruction::IsInstance(class_type) => {
+                let class_ref_reg = self.use_stack_register(operand_types.last().unwrap().clone());
+                let assign_reg = self.assign_stack_register(TypeId::Bool);
+                self.instructions.push(InstructionMIR::new(
+                    instruction_index,
+                    InstructionMIRData::IsInstance(TypeId::Class(class_type.clone()), assign_reg, class_ref_reg)
+                ));
+            }
+            Instruction::CastClass(class_type) => {
                 let class_type = TypeId::Class(class_type.clone());
+                let class_ref_reg = self.use_stack_register(operand_types.last().unwrap().clone());
                 let assign_reg = self.assign_stack_register(class_type.clone());
-                self.instructions.push(InstructionMIR::new(instruction_index, InstructionMIRData::NewObject(class_type, assign_reg)));
+                self.instructions.push(InstructionMIR::new(
+                    instruction_index,
+                    InstructionMIRData::CastClass(class_type, assign_reg, class_ref_reg)
+                ));
             }
             Instruction::LoadField(class_name, field_name) => {
+                let class_type = self.type_storage.get_by_name(class_name).unwrap();
+                let field = class_type.class.as_ref().unwrap().get_field(field_name).unwrap();
+                let class_type = class_type.id.clone();
+
+                let class_ref_reg = self.use_stack_register(operand_types.last().unwrap().clone());
+                let assign_reg = self.assign_stack_register(field.type_id().clone());
+                if class_type.is_struct() {
+                    self.instructions.push(InstructionMIR::new(
+                        instruction_index,
+                        InstructionMIRData::LoadStructField(class_type, field_name.clone(), assign_reg, class_ref_reg)
+                    ));
+                } else {
+                    self.instructions.push(InstructionMIR::new(
+                        instruction_index,
+                        InstructionMIRData::LoadField(class_type, field_name.clone(), assign_reg, class_ref_reg)
+                    ));
+                }
+            }
+            Instruction::StoreField(class_name, field_name) => {
+                let class_type = self.type_storage.get_by_name(class_name).unwrap().id.clone();
+                let value_reg = self.use_stack_register(operand_types[operand_types.len() - 1].clone());
+                let class_ref_reg = self.use_stack_register(operand_types[operand_types.len() - 2].clone());
+                if class_type.is_struct() {
+                    // The updated struct replaces the original on the stack
+                    let assign_reg = self.assign_stack_register(class_type.clone());
+                    self.instructions.push(InstructionMIR::new(
+                        instruction_index,
+                        InstructionMIRData::StoreStructField(class_type, field_name.clone(), assign_reg, class_ref_reg, value_reg)
+                    ));
+                } else {
+                    self.instructions.push(InstructionMIR::new(
+                        instruction_index,
+                        InstructionMIRData::StoreField(class_type, field_name.clone(), class_ref_reg, value_reg)
+                    ));
+                }
+            }
+            Instruction::LoadStaticField(class_name, field_name) => {
                 let class_type = TypeId::Class(class_name.clone());
                 let class = self.type_storage.get(&class_type).unwrap().class.as_ref().unwrap();
-                let field = class.get_field(field_name).unwrap();
+                let field = class.get_static_field(field_name).unwrap();
 
-                let class_ref_reg = self.use_stack_register(class_type.clone());
                 let assign_reg = self.assign_stack_register(field.type_id().clone());
                 self.instructions.push(InstructionMIR::new(
                     instruction_index,
-                    InstructionMIRData::LoadField(class_type, field_name.clone(), assign_reg, class_ref_reg)
+                    InstructionMIRData::LoadStaticField(class_type, field_name.clone(), assign_reg)
                 ));
             }
-            Instruction::StoreField(class_name, field_name) => {
+            Instruction::StoreStaticField(class_name, field_name) => {
                 let class_type = TypeId::Class(class_name.clone());
-                let class = self.type_storage.get(&class_type).unwrap().class.as_ref().unwrap();
-                let field = class.get_field(field_name).unwrap();
-
-                let value_reg = self.use_stack_register(field.type_id().clone());
-                let class_ref_reg = self.use_stack_register(class_type.clone());
+                let value_reg = self.use_stack_register(operand_types[operand_types.len() - 1].clone());
                 self.instructions.push(InstructionMIR::new(
                     instruction_index,
-                    InstructionMIRData::StoreField(class_type, field_name.clone(), class_ref_reg, value_reg)
+                    InstructionMIRData::StoreStaticField(class_type, field_name.clone(), value_reg)
                 ));
             }
             Instruction::CallInstance(signature) => {
-                let func_to_call = self.binder.get(signature).unwrap();
-
-                let mut arguments_regs = func_to_call.parameters()
-                    .iter().rev()
-                    .map(|parameter| self.use_stack_register(parameter.clone()))
-                    .collect::<Vec<_>>();
-                arguments_regs.reverse();
+                let func_to_call = self.binder.get_member(self.type_storage, signature).unwrap();
+                let arguments_regs = self.use_operand_registers(operand_types, func_to_call.parameters().len());
 
                 let return_value_reg = if func_to_call.return_type() != &TypeId::Void {
                     Some(self.assign_stack_register(func_to_call.return_type().clone()))
@@ -408,9 +890,9 @@ impl<'a> InstructionMIRCompiler<'a> {
                     _ => { panic!("unexpected."); }
                 };
 
-                let compare_type = operand_types[0].clone();
+                let compare_type = operand_types[operand_types.len() - 2].clone();
                 let label = self.branch_manager.get_label(*target).unwrap();
-                let op2_reg = self.use_stack_register(compare_type.clone());
+                let op2_reg = self.use_stack_register(operand_types[operand_types.len() - 1].clone());
                 let op1_reg = self.use_stack_register(compare_type.clone());
                 self.instructions.push(InstructionMIR::new(instruction_index, InstructionMIRData::BranchCondition(
                     condition,
@@ -436,8 +918,8 @@ impl<'a> InstructionMIRCompiler<'a> {
                     _ => { panic!("unexpected."); }
                 };
 
-                let compare_type = operand_types[0].clone();
-                let op2_reg = self.use_stack_register(compare_type.clone());
+                let compare_type = operand_types[operand_types.len() - 2].clone();
+                let op2_reg = self.use_stack_register(operand_types[operand_types.len() - 1].clone());
                 let op1_reg = self.use_stack_register(compare_type.clone());
                 let assign_reg = self.assign_stack_register(TypeId::Bool);
 
@@ -449,9 +931,69 @@ impl<'a> InstructionMIRCompiler<'a> {
                     op2_reg
                 )));
             }
+            Instruction::BeginTry => {
+                self.instructions_operands.pop();
+            }
+            Instruction::EndTry | Instruction::EndCatch => {
+                let region_index = self.function.protected_regions()
+                    .iter()
+                    .position(|region| region.try_end == instruction_index || region.catch_handlers.iter().any(|handler| handler.end == instruction_index))
+                    .unwrap();
+
+                match self.finally_handlers.get(&region_index).cloned() {
+                    Some((pending_exception, finally_label)) => {
+                        self.instructions.push(InstructionMIR::new(instruction_index, InstructionMIRData::LoadNull(pending_exception)));
+                        self.instructions_operands.push(Vec::new());
+                        self.instructions.push(InstructionMIR::new(instruction_index, InstructionMIRData::Branch(finally_label)));
+                    }
+                    None => {
+                        let region_end = self.function.protected_regions()[region_index].end();
+                        let label = self.branch_manager.get_label((region_end + 1) as BranchTarget).unwrap();
+                        self.instructions.push(InstructionMIR::new(instruction_index, InstructionMIRData::Branch(label)));
+                    }
+                }
+            }
+            Instruction::BeginCatch(class_name) => {
+                let assign_reg = self.assign_stack_register(TypeId::Class(class_name.clone()));
+                self.instructions.push(InstructionMIR::new(instruction_index, InstructionMIRData::ExceptionLanding(assign_reg)));
+            }
+            Instruction::BeginFinally => {
+                let (pending_exception, finally_label) = self.finally_handler(instruction_index, |handler| handler.start);
+                self.instructions.push(InstructionMIR::new(instruction_index, InstructionMIRData::ExceptionLanding(pending_exception)));
+                self.instructions_operands.push(Vec::new());
+                self.instructions.push(InstructionMIR::new(instruction_index, InstructionMIRData::BranchLabel(finally_label)));
+            }
+            Instruction::EndFinally => {
+                let (pending_exception, _) = self.finally_handler(instruction_index, |handler| handler.end);
+                self.instructions.push(InstructionMIR::new(instruction_index, InstructionMIRData::EndFinally(pending_exception)));
+            }
+            Instruction::Throw => {
+                let exception_reg = self.use_stack_register(operand_types.last().unwrap().clone());
+                self.instructions.push(InstructionMIR::new(instruction_index, InstructionMIRData::Throw(exception_reg)));
+            }
         }
     }
 
+    fn finally_handler<F: Fn(&FinallyHandler) -> usize>(&self, instruction_index: usize, handler_index: F) -> (RegisterMIR, BranchLabel) {
+        let region_index = self.function.protected_regions()
+            .iter()
+            .position(|region| region.finally_handler.as_ref().map(&handler_index) == Some(instruction_index))
+            .unwrap();
+
+        self.finally_handlers[&region_index].clone()
+    }
+
+    // Uses the registers of the given number of top operands, where the registers have the type of the actual operand
+    // rather than the expected type, as a derived class can be used where a base class is expected.
+    fn use_operand_registers(&mut self, operand_types: &[TypeId], count: usize) -> Vec<RegisterMIR> {
+        let mut operand_regs = operand_types[(operand_types.len() - count)..]
+            .iter().rev()
+            .map(|operand_type| self.use_stack_register(operand_type.clone()))
+            .collect::<Vec<_>>();
+        operand_regs.reverse();
+        operand_regs
+    }
+
     fn use_stack_register(&mut self, value_type: TypeId) -> RegisterMIR {
         if self.next_operand_virtual_register == 0 {
             panic!("Invalid stack virtual register.");
@@ -465,17 +1007,40 @@ impl<'a> InstructionMIRCompiler<'a> {
     fn assign_stack_register(&mut self, value_type: TypeId) -> RegisterMIR {
         let number = self.next_operand_virtual_register;
         self.next_operand_virtual_register += 1;
-        self.max_num_virtual_register = self.max_num_virtual_register.max(self.next_operand_virtual_register as usize);
         RegisterMIR::new(number, value_type)
     }
 
     pub fn done(self) -> MIRCompilationResult {
+        // The references within structs are needed to find them on the stack
+        let mut struct_references = HashMap::new();
+        let registers = self.local_virtual_registers
+            .iter()
+            .cloned()
+            .chain(self.instructions.iter().flat_map(|instruction| instruction.data.assign_register()));
+
+        for register in registers {
+            if register.value_type.is_struct() && !struct_references.contains_key(&register.value_type) {
+                struct_references.insert(register.value_type.clone(), self.type_storage.reference_offsets(&register.value_type));
+            }
+        }
+
+        // The optimizations only remove or reuse registers, so the slots stay valid
+        let used_registers = self.instructions.iter().flat_map(|instruction| instruction.data.use_registers());
+        let assigned_registers = self.instructions.iter().flat_map(|instruction| instruction.data.assign_register());
+        let registers = used_registers
+            .chain(assigned_registers)
+            .chain(self.local_virtual_registers.iter().cloned())
+            .chain(self.instructions_operands.iter().flatten().cloned())
+            .collect::<Vec<_>>();
+        let register_slots = RegisterSlots::new(self.function, registers.iter());
+
         MIRCompilationResult {
             instructions: self.instructions,
-            num_virtual_registers: self.max_num_virtual_register,
             local_virtual_registers: self.local_virtual_registers,
             need_zero_initialize_registers: self.need_zero_initialize_registers,
-            instructions_operand_stack: self.instructions_operands
+            instructions_operand_stack: self.instructions_operands,
+            struct_references,
+            register_slots
         }
     }
 }
@@ -613,9 +1178,75 @@ fn test_simple5() {
     println_vec(function.instructions(), &compiler.done().instructions);
 }
 
-fn println_vec(original: &Vec<Instruction>, irs: &Vec<InstructionMIR>) {
+#[test]
+fn test_intrinsic1() {
+    let mut function = Function::new(
+        FunctionDeclaration::with_managed("test".to_owned(), vec![], TypeId::Float32),
+        vec![],
+        vec![
+            Instruction::LoadFloat32(2.0),
+            Instruction::Call(FunctionSignature::new("std.math.sqrt".to_owned(), vec![TypeId::Float32])),
+            Instruction::LoadFloat32(1.5),
+            Instruction::Call(FunctionSignature::new("std.math.floor".to_owned(), vec![TypeId::Float32])),
+            Instruction::Call(FunctionSignature::new("std.math.max".to_owned(), vec![TypeId::Float32, TypeId::Float32])),
+            Instruction::Return
+        ]
+    );
+
+    let binder = Binder::new();
+    let type_storage = TypeStorage::new();
+    Verifier::new(&binder, &type_storage, &mut function).verify().unwrap();
+
+    let mut compiler = InstructionMIRCompiler::new(&type_storage, &binder, &function);
+    compiler.compile(function.instructions());
+    let instructions = compiler.done().instructions;
+
+    println_vec(function.instructions(), &instructions);
+    assert!(instructions.iter().all(|instruction| !matches!(instruction.data, InstructionMIRData::Call(_, _, _))));
+}
+
+#[allow(dead_code)]
+fn println_vec(original: &[Instruction], irs: &Vec<InstructionMIR>) {
     for ir in irs {
         println!("{:?}", original[ir.index]);
         println!("\t{:?}", ir.data);
     }
-}
\ No newline at end of file
+}
+#[test]
+fn test_register_slots1() {
+    use crate::analysis::{VirtualRegister, VirtualRegisterType};
+
+    let mut function = Function::new(
+        FunctionDeclaration::with_managed("test".to_owned(), vec![TypeId::Int32], TypeId::Int32),
+        vec![TypeId::Float32],
+        vec![
+            Instruction::LoadArgument(0),
+            Instruction::LoadInt32(2),
+            Instruction::Add,
+            Instruction::ConvertInt32ToFloat32,
+            Instruction::StoreLocal(0),
+            Instruction::LoadInt32(0),
+            Instruction::Return,
+        ]
+    );
+
+    let binder = Binder::new();
+    let type_storage = TypeStorage::new();
+    Verifier::new(&binder, &type_storage, &mut function).verify().unwrap();
+
+    let mut compiler = InstructionMIRCompiler::new(&type_storage, &binder, &function);
+    compiler.compile(function.instructions());
+    let compilation_result = compiler.done();
+
+    // Only the types a register is used as gets a slot
+    let register_slots = &compilation_result.register_slots;
+    assert_eq!(4, register_slots.num_slots());
+
+    let slots = [
+        VirtualRegister { number: 0, register_type: VirtualRegisterType::Float },
+        VirtualRegister { number: 1, register_type: VirtualRegisterType::Int },
+        VirtualRegister { number: 1, register_type: VirtualRegisterType::Float },
+        VirtualRegister { number: 2, register_type: VirtualRegisterType::Int },
+    ].iter().map(|register| register_slots.slot(register)).collect::<Vec<_>>();
+    assert_eq!(vec![1, 2, 3, 4], slots);
+}
@@ -4,7 +4,7 @@ pub mod compiler;
 pub mod branches;
 
 use crate::analysis::VirtualRegister;
-use crate::compiler::ir::{BranchLabel, Condition};
+use crate::compiler::ir::{BranchLabel, Condition, RoundingMode};
 use crate::model::function::FunctionSignature;
 use crate::model::typesystem::TypeId;
 
@@ -43,6 +43,7 @@ impl InstructionMIR {
         }
     }
 
+    #[allow(dead_code)]
     pub fn name(&self) -> String {
         self.data.name()
     }
@@ -64,32 +65,93 @@ pub enum InstructionMIRData {
     SubFloat32(RegisterMIR, RegisterMIR, RegisterMIR),
     MultiplyFloat32(RegisterMIR, RegisterMIR, RegisterMIR),
     DivideFloat32(RegisterMIR, RegisterMIR, RegisterMIR),
+    MinFloat32(RegisterMIR, RegisterMIR, RegisterMIR),
+    MaxFloat32(RegisterMIR, RegisterMIR, RegisterMIR),
+    SquareRootFloat32(RegisterMIR, RegisterMIR),
+    RoundFloat32(RegisterMIR, RegisterMIR, RoundingMode),
+    LoadInt64(RegisterMIR, i64),
+    LoadFloat64(RegisterMIR, f64),
+    AddInt64(RegisterMIR, RegisterMIR, RegisterMIR),
+    SubInt64(RegisterMIR, RegisterMIR, RegisterMIR),
+    MultiplyInt64(RegisterMIR, RegisterMIR, RegisterMIR),
+    DivideInt64(RegisterMIR, RegisterMIR, RegisterMIR),
+    AddFloat64(RegisterMIR, RegisterMIR, RegisterMIR),
+    SubFloat64(RegisterMIR, RegisterMIR, RegisterMIR),
+    MultiplyFloat64(RegisterMIR, RegisterMIR, RegisterMIR),
+    DivideFloat64(RegisterMIR, RegisterMIR, RegisterMIR),
     AndBool(RegisterMIR, RegisterMIR, RegisterMIR),
     AndBoolConstant(RegisterMIR, RegisterMIR, bool),
     OrBool(RegisterMIR, RegisterMIR, RegisterMIR),
     OrBoolConstant(RegisterMIR, RegisterMIR, bool),
     NotBool(RegisterMIR, RegisterMIR),
+    RemainderInt32(RegisterMIR, RegisterMIR, RegisterMIR),
+    NegateInt32(RegisterMIR, RegisterMIR),
+    AndInt32(RegisterMIR, RegisterMIR, RegisterMIR),
+    OrInt32(RegisterMIR, RegisterMIR, RegisterMIR),
+    XorInt32(RegisterMIR, RegisterMIR, RegisterMIR),
+    NotInt32(RegisterMIR, RegisterMIR),
+    ShiftLeftInt32(RegisterMIR, RegisterMIR, RegisterMIR),
+    ShiftRightInt32(RegisterMIR, RegisterMIR, RegisterMIR),
+    RemainderInt64(RegisterMIR, RegisterMIR, RegisterMIR),
+    NegateInt64(RegisterMIR, RegisterMIR),
+    AndInt64(RegisterMIR, RegisterMIR, RegisterMIR),
+    OrInt64(RegisterMIR, RegisterMIR, RegisterMIR),
+    XorInt64(RegisterMIR, RegisterMIR, RegisterMIR),
+    NotInt64(RegisterMIR, RegisterMIR),
+    ShiftLeftInt64(RegisterMIR, RegisterMIR, RegisterMIR),
+    ShiftRightInt64(RegisterMIR, RegisterMIR, RegisterMIR),
+    ConvertInt32ToFloat32(RegisterMIR, RegisterMIR),
+    ConvertFloat32ToInt32(RegisterMIR, RegisterMIR),
+    ConvertBoolToInt32(RegisterMIR, RegisterMIR),
+    ConvertInt32ToInt64(RegisterMIR, RegisterMIR),
+    ConvertInt64ToInt32(RegisterMIR, RegisterMIR),
+    ConvertFloat32ToFloat64(RegisterMIR, RegisterMIR),
+    ConvertFloat64ToFloat32(RegisterMIR, RegisterMIR),
+    ConvertInt64ToFloat64(RegisterMIR, RegisterMIR),
+    ConvertFloat64ToInt64(RegisterMIR, RegisterMIR),
     Return(Option<RegisterMIR>),
     Call(FunctionSignature, Option<RegisterMIR>, Vec<RegisterMIR>),
+    LoadFunction(FunctionSignature, RegisterMIR),
+    CallIndirect(TypeId, Option<RegisterMIR>, RegisterMIR, Vec<RegisterMIR>),
     LoadArgument(u32, RegisterMIR),
     LoadNull(RegisterMIR),
+    LoadString(RegisterMIR, String),
     NewArray(TypeId, RegisterMIR, RegisterMIR),
     LoadElement(TypeId, RegisterMIR, RegisterMIR, RegisterMIR),
     StoreElement(TypeId, RegisterMIR, RegisterMIR, RegisterMIR),
     LoadArrayLength(RegisterMIR, RegisterMIR),
+    ArrayCopy(TypeId, RegisterMIR, RegisterMIR, RegisterMIR, RegisterMIR, RegisterMIR),
+    ArrayFill(TypeId, RegisterMIR, RegisterMIR),
+    ArrayClone(TypeId, RegisterMIR, RegisterMIR),
+    ArrayEquals(TypeId, RegisterMIR, RegisterMIR, RegisterMIR),
+    NewArray2D(TypeId, RegisterMIR, RegisterMIR, RegisterMIR),
+    LoadElement2D(TypeId, RegisterMIR, RegisterMIR, RegisterMIR, RegisterMIR),
+    StoreElement2D(TypeId, RegisterMIR, RegisterMIR, RegisterMIR, RegisterMIR),
+    LoadArrayDimension(u32, RegisterMIR, RegisterMIR),
     NewObject(TypeId, RegisterMIR),
     LoadField(TypeId, String, RegisterMIR, RegisterMIR),
     StoreField(TypeId, String, RegisterMIR, RegisterMIR),
+    LoadStaticField(TypeId, String, RegisterMIR),
+    StoreStaticField(TypeId, String, RegisterMIR),
+    NewStruct(TypeId, RegisterMIR),
+    LoadStructField(TypeId, String, RegisterMIR, RegisterMIR),
+    StoreStructField(TypeId, String, RegisterMIR, RegisterMIR, RegisterMIR),
     CallInstance(FunctionSignature, Option<RegisterMIR>, Vec<RegisterMIR>),
+    IsInstance(TypeId, RegisterMIR, RegisterMIR),
+    CastClass(TypeId, RegisterMIR, RegisterMIR),
     GarbageCollect,
     PrintStackFrame,
     BranchLabel(BranchLabel),
     Branch(BranchLabel),
     BranchCondition(Condition, TypeId, BranchLabel, RegisterMIR, RegisterMIR),
-    Compare(Condition, TypeId, RegisterMIR, RegisterMIR, RegisterMIR)
+    Compare(Condition, TypeId, RegisterMIR, RegisterMIR, RegisterMIR),
+    ExceptionLanding(RegisterMIR),
+    Throw(RegisterMIR),
+    EndFinally(RegisterMIR)
 }
 
 impl InstructionMIRData {
+    #[allow(dead_code)]
     pub fn name(&self) -> String {
         match self {
             InstructionMIRData::LoadInt32(_, _) => "LoadInt32".to_owned(),
@@ -106,29 +168,89 @@ impl InstructionMIRData {
             InstructionMIRData::SubFloat32(_, _, _) => "SubFloat32".to_owned(),
             InstructionMIRData::MultiplyFloat32(_, _, _) => "MultiplyFloat32".to_owned(),
             InstructionMIRData::DivideFloat32(_, _, _) => "DivideFloat32".to_owned(),
+            InstructionMIRData::MinFloat32(_, _, _) => "MinFloat32".to_owned(),
+            InstructionMIRData::MaxFloat32(_, _, _) => "MaxFloat32".to_owned(),
+            InstructionMIRData::SquareRootFloat32(_, _) => "SquareRootFloat32".to_owned(),
+            InstructionMIRData::RoundFloat32(_, _, _) => "RoundFloat32".to_owned(),
+            InstructionMIRData::LoadInt64(_, _) => "LoadInt64".to_owned(),
+            InstructionMIRData::LoadFloat64(_, _) => "LoadFloat64".to_owned(),
+            InstructionMIRData::AddInt64(_, _, _) => "AddInt64".to_owned(),
+            InstructionMIRData::SubInt64(_, _, _) => "SubInt64".to_owned(),
+            InstructionMIRData::MultiplyInt64(_, _, _) => "MultiplyInt64".to_owned(),
+            InstructionMIRData::DivideInt64(_, _, _) => "DivideInt64".to_owned(),
+            InstructionMIRData::AddFloat64(_, _, _) => "AddFloat64".to_owned(),
+            InstructionMIRData::SubFloat64(_, _, _) => "SubFloat64".to_owned(),
+            InstructionMIRData::MultiplyFloat64(_, _, _) => "MultiplyFloat64".to_owned(),
+            InstructionMIRData::DivideFloat64(_, _, _) => "DivideFloat64".to_owned(),
             InstructionMIRData::AndBool(_, _, _) => "AndBool".to_owned(),
             InstructionMIRData::AndBoolConstant(_, _, _) => "AndBoolConstant".to_owned(),
             InstructionMIRData::OrBool(_, _, _) => "OrBool".to_owned(),
             InstructionMIRData::OrBoolConstant(_, _, _) => "OrBoolConstant".to_owned(),
             InstructionMIRData::NotBool(_, _) => "NotBool".to_owned(),
+            InstructionMIRData::RemainderInt32(_, _, _) => "RemainderInt32".to_owned(),
+            InstructionMIRData::NegateInt32(_, _) => "NegateInt32".to_owned(),
+            InstructionMIRData::AndInt32(_, _, _) => "AndInt32".to_owned(),
+            InstructionMIRData::OrInt32(_, _, _) => "OrInt32".to_owned(),
+            InstructionMIRData::XorInt32(_, _, _) => "XorInt32".to_owned(),
+            InstructionMIRData::NotInt32(_, _) => "NotInt32".to_owned(),
+            InstructionMIRData::ShiftLeftInt32(_, _, _) => "ShiftLeftInt32".to_owned(),
+            InstructionMIRData::ShiftRightInt32(_, _, _) => "ShiftRightInt32".to_owned(),
+            InstructionMIRData::RemainderInt64(_, _, _) => "RemainderInt64".to_owned(),
+            InstructionMIRData::NegateInt64(_, _) => "NegateInt64".to_owned(),
+            InstructionMIRData::AndInt64(_, _, _) => "AndInt64".to_owned(),
+            InstructionMIRData::OrInt64(_, _, _) => "OrInt64".to_owned(),
+            InstructionMIRData::XorInt64(_, _, _) => "XorInt64".to_owned(),
+            InstructionMIRData::NotInt64(_, _) => "NotInt64".to_owned(),
+            InstructionMIRData::ShiftLeftInt64(_, _, _) => "ShiftLeftInt64".to_owned(),
+            InstructionMIRData::ShiftRightInt64(_, _, _) => "ShiftRightInt64".to_owned(),
+            InstructionMIRData::ConvertInt32ToFloat32(_, _) => "ConvertInt32ToFloat32".to_owned(),
+            InstructionMIRData::ConvertFloat32ToInt32(_, _) => "ConvertFloat32ToInt32".to_owned(),
+            InstructionMIRData::ConvertBoolToInt32(_, _) => "ConvertBoolToInt32".to_owned(),
+            InstructionMIRData::ConvertInt32ToInt64(_, _) => "ConvertInt32ToInt64".to_owned(),
+            InstructionMIRData::ConvertInt64ToInt32(_, _) => "ConvertInt64ToInt32".to_owned(),
+            InstructionMIRData::ConvertFloat32ToFloat64(_, _) => "ConvertFloat32ToFloat64".to_owned(),
+            InstructionMIRData::ConvertFloat64ToFloat32(_, _) => "ConvertFloat64ToFloat32".to_owned(),
+            InstructionMIRData::ConvertInt64ToFloat64(_, _) => "ConvertInt64ToFloat64".to_owned(),
+            InstructionMIRData::ConvertFloat64ToInt64(_, _) => "ConvertFloat64ToInt64".to_owned(),
             InstructionMIRData::Return(_) => "Return".to_owned(),
             InstructionMIRData::Call(_, _, _) => "Call".to_owned(),
+            InstructionMIRData::LoadFunction(_, _) => "LoadFunction".to_owned(),
+            InstructionMIRData::CallIndirect(_, _, _, _) => "CallIndirect".to_owned(),
             InstructionMIRData::LoadArgument(_, _) => "LoadArgument".to_owned(),
             InstructionMIRData::LoadNull(_) => "LoadNull".to_owned(),
+            InstructionMIRData::LoadString(_, _) => "LoadString".to_owned(),
             InstructionMIRData::NewArray(_, _, _) => "NewArray".to_owned(),
             InstructionMIRData::LoadElement(_, _, _, _) => "LoadElement".to_owned(),
             InstructionMIRData::StoreElement(_, _, _, _) => "StoreElement".to_owned(),
             InstructionMIRData::LoadArrayLength(_, _) => "LoadArrayLength".to_owned(),
+            InstructionMIRData::ArrayCopy(_, _, _, _, _, _) => "ArrayCopy".to_owned(),
+            InstructionMIRData::ArrayFill(_, _, _) => "ArrayFill".to_owned(),
+            InstructionMIRData::ArrayClone(_, _, _) => "ArrayClone".to_owned(),
+            InstructionMIRData::ArrayEquals(_, _, _, _) => "ArrayEquals".to_owned(),
+            InstructionMIRData::NewArray2D(_, _, _, _) => "NewArray2D".to_owned(),
+            InstructionMIRData::LoadElement2D(_, _, _, _, _) => "LoadElement2D".to_owned(),
+            InstructionMIRData::StoreElement2D(_, _, _, _, _) => "StoreElement2D".to_owned(),
+            InstructionMIRData::LoadArrayDimension(_, _, _) => "LoadArrayDimension".to_owned(),
             InstructionMIRData::NewObject(_, _) => "NewObject".to_owned(),
+            InstructionMIRData::IsInstance(_, _, _) => "IsInstance".to_owned(),
+            InstructionMIRData::CastClass(_, _, _) => "CastClass".to_owned(),
             InstructionMIRData::GarbageCollect => "GarbageCollect".to_owned(),
             InstructionMIRData::PrintStackFrame => "PrintStackFrame".to_owned(),
             InstructionMIRData::LoadField(_, _, _, _) => "LoadField".to_owned(),
             InstructionMIRData::StoreField(_, _, _, _) => "StoreField".to_owned(),
+            InstructionMIRData::LoadStaticField(_, _, _) => "LoadStaticField".to_owned(),
+            InstructionMIRData::StoreStaticField(_, _, _) => "StoreStaticField".to_owned(),
+            InstructionMIRData::NewStruct(_, _) => "NewStruct".to_owned(),
+            InstructionMIRData::LoadStructField(_, _, _, _) => "LoadStructField".to_owned(),
+            InstructionMIRData::StoreStructField(_, _, _, _, _) => "StoreStructField".to_owned(),
             InstructionMIRData::CallInstance(_, _, _) => "CallInstance".to_owned(),
             InstructionMIRData::BranchLabel(_) => "BranchLabel".to_owned(),
             InstructionMIRData::Branch(_) => "Branch".to_owned(),
             InstructionMIRData::BranchCondition(_, _, _, _, _) => "BranchCondition".to_owned(),
-            InstructionMIRData::Compare(_, _, _, _, _) => "Compare".to_owned()
+            InstructionMIRData::Compare(_, _, _, _, _) => "Compare".to_owned(),
+            InstructionMIRData::ExceptionLanding(_) => "ExceptionLanding".to_owned(),
+            InstructionMIRData::Throw(_) => "Throw".to_owned(),
+            InstructionMIRData::EndFinally(_) => "EndFinally".to_owned()
         }
     }
 
@@ -148,29 +270,89 @@ impl InstructionMIRData {
             InstructionMIRData::SubFloat32(register, _, _) => Some(register.clone()),
             InstructionMIRData::MultiplyFloat32(register, _, _) => Some(register.clone()),
             InstructionMIRData::DivideFloat32(register, _, _) => Some(register.clone()),
+            InstructionMIRData::MinFloat32(register, _, _) => Some(register.clone()),
+            InstructionMIRData::MaxFloat32(register, _, _) => Some(register.clone()),
+            InstructionMIRData::SquareRootFloat32(register, _) => Some(register.clone()),
+            InstructionMIRData::RoundFloat32(register, _, _) => Some(register.clone()),
+            InstructionMIRData::LoadInt64(register, _) => Some(register.clone()),
+            InstructionMIRData::LoadFloat64(register, _) => Some(register.clone()),
+            InstructionMIRData::AddInt64(register, _, _) => Some(register.clone()),
+            InstructionMIRData::SubInt64(register, _, _) => Some(register.clone()),
+            InstructionMIRData::MultiplyInt64(register, _, _) => Some(register.clone()),
+            InstructionMIRData::DivideInt64(register, _, _) => Some(register.clone()),
+            InstructionMIRData::AddFloat64(register, _, _) => Some(register.clone()),
+            InstructionMIRData::SubFloat64(register, _, _) => Some(register.clone()),
+            InstructionMIRData::MultiplyFloat64(register, _, _) => Some(register.clone()),
+            InstructionMIRData::DivideFloat64(register, _, _) => Some(register.clone()),
             InstructionMIRData::AndBool(register, _, _) => Some(register.clone()),
             InstructionMIRData::AndBoolConstant(register, _, _) => Some(register.clone()),
             InstructionMIRData::OrBool(register, _, _) => Some(register.clone()),
             InstructionMIRData::OrBoolConstant(register, _, _) => Some(register.clone()),
             InstructionMIRData::NotBool(register, _) => Some(register.clone()),
+            InstructionMIRData::RemainderInt32(register, _, _) => Some(register.clone()),
+            InstructionMIRData::NegateInt32(register, _) => Some(register.clone()),
+            InstructionMIRData::AndInt32(register, _, _) => Some(register.clone()),
+            InstructionMIRData::OrInt32(register, _, _) => Some(register.clone()),
+            InstructionMIRData::XorInt32(register, _, _) => Some(register.clone()),
+            InstructionMIRData::NotInt32(register, _) => Some(register.clone()),
+            InstructionMIRData::ShiftLeftInt32(register, _, _) => Some(register.clone()),
+            InstructionMIRData::ShiftRightInt32(register, _, _) => Some(register.clone()),
+            InstructionMIRData::RemainderInt64(register, _, _) => Some(register.clone()),
+            InstructionMIRData::NegateInt64(register, _) => Some(register.clone()),
+            InstructionMIRData::AndInt64(register, _, _) => Some(register.clone()),
+            InstructionMIRData::OrInt64(register, _, _) => Some(register.clone()),
+            InstructionMIRData::XorInt64(register, _, _) => Some(register.clone()),
+            InstructionMIRData::NotInt64(register, _) => Some(register.clone()),
+            InstructionMIRData::ShiftLeftInt64(register, _, _) => Some(register.clone()),
+            InstructionMIRData::ShiftRightInt64(register, _, _) => Some(register.clone()),
+            InstructionMIRData::ConvertInt32ToFloat32(register, _) => Some(register.clone()),
+            InstructionMIRData::ConvertFloat32ToInt32(register, _) => Some(register.clone()),
+            InstructionMIRData::ConvertBoolToInt32(register, _) => Some(register.clone()),
+            InstructionMIRData::ConvertInt32ToInt64(register, _) => Some(register.clone()),
+            InstructionMIRData::ConvertInt64ToInt32(register, _) => Some(register.clone()),
+            InstructionMIRData::ConvertFloat32ToFloat64(register, _) => Some(register.clone()),
+            InstructionMIRData::ConvertFloat64ToFloat32(register, _) => Some(register.clone()),
+            InstructionMIRData::ConvertInt64ToFloat64(register, _) => Some(register.clone()),
+            InstructionMIRData::ConvertFloat64ToInt64(register, _) => Some(register.clone()),
             InstructionMIRData::Return(_) => None,
             InstructionMIRData::Call(_, register, _) => register.clone(),
+            InstructionMIRData::LoadFunction(_, register) => Some(register.clone()),
+            InstructionMIRData::CallIndirect(_, register, _, _) => register.clone(),
             InstructionMIRData::LoadArgument(_, register) => Some(register.clone()),
             InstructionMIRData::LoadNull(register) => Some(register.clone()),
+            InstructionMIRData::LoadString(register, _) => Some(register.clone()),
             InstructionMIRData::NewArray(_, register, _) => Some(register.clone()),
             InstructionMIRData::LoadElement(_, register, _, _) => Some(register.clone()),
             InstructionMIRData::NewObject(_, register) => Some(register.clone()),
+            InstructionMIRData::IsInstance(_, register, _) => Some(register.clone()),
+            InstructionMIRData::CastClass(_, register, _) => Some(register.clone()),
             InstructionMIRData::GarbageCollect => None,
             InstructionMIRData::PrintStackFrame => None,
             InstructionMIRData::LoadField(_, _, register, _) => Some(register.clone()),
             InstructionMIRData::StoreField(_, _, _, _) => None,
+            InstructionMIRData::LoadStaticField(_, _, register) => Some(register.clone()),
+            InstructionMIRData::StoreStaticField(_, _, _) => None,
+            InstructionMIRData::NewStruct(_, register) => Some(register.clone()),
+            InstructionMIRData::LoadStructField(_, _, register, _) => Some(register.clone()),
+            InstructionMIRData::StoreStructField(_, _, register, _, _) => Some(register.clone()),
             InstructionMIRData::CallInstance(_, register, _) => register.clone(),
             InstructionMIRData::StoreElement(_, _, _, _) => None,
-            InstructionMIRData::LoadArrayLength(_, register) => Some(register.clone()),
+            InstructionMIRData::LoadArrayLength(register, _) => Some(register.clone()),
+            InstructionMIRData::ArrayCopy(_, _, _, _, _, _) => None,
+            InstructionMIRData::ArrayFill(_, _, _) => None,
+            InstructionMIRData::ArrayClone(_, register, _) => Some(register.clone()),
+            InstructionMIRData::ArrayEquals(_, register, _, _) => Some(register.clone()),
+            InstructionMIRData::NewArray2D(_, register, _, _) => Some(register.clone()),
+            InstructionMIRData::LoadElement2D(_, register, _, _, _) => Some(register.clone()),
+            InstructionMIRData::StoreElement2D(_, _, _, _, _) => None,
+            InstructionMIRData::LoadArrayDimension(_, register, _) => Some(register.clone()),
             InstructionMIRData::BranchLabel(_) => None,
             InstructionMIRData::Branch(_) => None,
             InstructionMIRData::BranchCondition(_, _, _, _, _) => None,
-            InstructionMIRData::Compare(_, _, destination, _, _) => Some(destination.clone())
+            InstructionMIRData::Compare(_, _, destination, _, _) => Some(destination.clone()),
+            InstructionMIRData::ExceptionLanding(register) => Some(register.clone()),
+            InstructionMIRData::Throw(_) => None,
+            InstructionMIRData::EndFinally(_) => None
         }
     }
 
@@ -190,29 +372,89 @@ impl InstructionMIRData {
             InstructionMIRData::SubFloat32(register, _, _) => Some(register),
             InstructionMIRData::MultiplyFloat32(register, _, _) => Some(register),
             InstructionMIRData::DivideFloat32(register, _, _) => Some(register),
+            InstructionMIRData::MinFloat32(register, _, _) => Some(register),
+            InstructionMIRData::MaxFloat32(register, _, _) => Some(register),
+            InstructionMIRData::SquareRootFloat32(register, _) => Some(register),
+            InstructionMIRData::RoundFloat32(register, _, _) => Some(register),
+            InstructionMIRData::LoadInt64(register, _) => Some(register),
+            InstructionMIRData::LoadFloat64(register, _) => Some(register),
+            InstructionMIRData::AddInt64(register, _, _) => Some(register),
+            InstructionMIRData::SubInt64(register, _, _) => Some(register),
+            InstructionMIRData::MultiplyInt64(register, _, _) => Some(register),
+            InstructionMIRData::DivideInt64(register, _, _) => Some(register),
+            InstructionMIRData::AddFloat64(register, _, _) => Some(register),
+            InstructionMIRData::SubFloat64(register, _, _) => Some(register),
+            InstructionMIRData::MultiplyFloat64(register, _, _) => Some(register),
+            InstructionMIRData::DivideFloat64(register, _, _) => Some(register),
             InstructionMIRData::AndBool(register, _, _) => Some(register),
             InstructionMIRData::AndBoolConstant(register, _, _) => Some(register),
             InstructionMIRData::OrBool(register, _, _) => Some(register),
             InstructionMIRData::OrBoolConstant(register, _, _) => Some(register),
             InstructionMIRData::NotBool(register, _) => Some(register),
+            InstructionMIRData::RemainderInt32(register, _, _) => Some(register),
+            InstructionMIRData::NegateInt32(register, _) => Some(register),
+            InstructionMIRData::AndInt32(register, _, _) => Some(register),
+            InstructionMIRData::OrInt32(register, _, _) => Some(register),
+            InstructionMIRData::XorInt32(register, _, _) => Some(register),
+            InstructionMIRData::NotInt32(register, _) => Some(register),
+            InstructionMIRData::ShiftLeftInt32(register, _, _) => Some(register),
+            InstructionMIRData::ShiftRightInt32(register, _, _) => Some(register),
+            InstructionMIRData::RemainderInt64(register, _, _) => Some(register),
+            InstructionMIRData::NegateInt64(register, _) => Some(register),
+            InstructionMIRData::AndInt64(register, _, _) => Some(register),
+            InstructionMIRData::OrInt64(register, _, _) => Some(register),
+            InstructionMIRData::XorInt64(register, _, _) => Some(register),
+            InstructionMIRData::NotInt64(register, _) => Some(register),
+            InstructionMIRData::ShiftLeftInt64(register, _, _) => Some(register),
+            InstructionMIRData::ShiftRightInt64(register, _, _) => Some(register),
+            InstructionMIRData::ConvertInt32ToFloat32(register, _) => Some(register),
+            InstructionMIRData::ConvertFloat32ToInt32(register, _) => Some(register),
+            InstructionMIRData::ConvertBoolToInt32(register, _) => Some(register),
+            InstructionMIRData::ConvertInt32ToInt64(register, _) => Some(register),
+            InstructionMIRData::ConvertInt64ToInt32(register, _) => Some(register),
+            InstructionMIRData::ConvertFloat32ToFloat64(register, _) => Some(register),
+            InstructionMIRData::ConvertFloat64ToFloat32(register, _) => Some(register),
+            InstructionMIRData::ConvertInt64ToFloat64(register, _) => Some(register),
+            InstructionMIRData::ConvertFloat64ToInt64(register, _) => Some(register),
             InstructionMIRData::Return(_) => None,
             InstructionMIRData::Call(_, register, _) => register.as_mut(),
+            InstructionMIRData::LoadFunction(_, register) => Some(register),
+            InstructionMIRData::CallIndirect(_, register, _, _) => register.as_mut(),
             InstructionMIRData::LoadArgument(_, register) => Some(register),
             InstructionMIRData::LoadNull(register) => Some(register),
+            InstructionMIRData::LoadString(register, _) => Some(register),
             InstructionMIRData::NewArray(_, register, _) => Some(register),
             InstructionMIRData::LoadElement(_, register, _, _) => Some(register),
             InstructionMIRData::NewObject(_, register) => Some(register),
+            InstructionMIRData::IsInstance(_, register, _) => Some(register),
+            InstructionMIRData::CastClass(_, register, _) => Some(register),
             InstructionMIRData::GarbageCollect => None,
             InstructionMIRData::PrintStackFrame => None,
             InstructionMIRData::LoadField(_, _, register, _) => Some(register),
             InstructionMIRData::StoreField(_, _, _, _) => None,
+            InstructionMIRData::LoadStaticField(_, _, register) => Some(register),
+            InstructionMIRData::StoreStaticField(_, _, _) => None,
+            InstructionMIRData::NewStruct(_, register) => Some(register),
+            InstructionMIRData::LoadStructField(_, _, register, _) => Some(register),
+            InstructionMIRData::StoreStructField(_, _, register, _, _) => Some(register),
             InstructionMIRData::CallInstance(_, register, _) => register.as_mut(),
             InstructionMIRData::StoreElement(_, _, _, _) => None,
-            InstructionMIRData::LoadArrayLength(_, register) => Some(register),
+            InstructionMIRData::LoadArrayLength(register, _) => Some(register),
+            InstructionMIRData::ArrayCopy(_, _, _, _, _, _) => None,
+            InstructionMIRData::ArrayFill(_, _, _) => None,
+            InstructionMIRData::ArrayClone(_, register, _) => Some(register),
+            InstructionMIRData::ArrayEquals(_, register, _, _) => Some(register),
+            InstructionMIRData::NewArray2D(_, register, _, _) => Some(register),
+            InstructionMIRData::LoadElement2D(_, register, _, _, _) => Some(register),
+            InstructionMIRData::StoreElement2D(_, _, _, _, _) => None,
+            InstructionMIRData::LoadArrayDimension(_, register, _) => Some(register),
             InstructionMIRData::BranchLabel(_) => None,
             InstructionMIRData::Branch(_) => None,
             InstructionMIRData::BranchCondition(_, _, _, _, _) => None,
-            InstructionMIRData::Compare(_, _, destination, _, _) => Some(destination)
+            InstructionMIRData::Compare(_, _, destination, _, _) => Some(destination),
+            InstructionMIRData::ExceptionLanding(register) => Some(register),
+            InstructionMIRData::Throw(_) => None,
+            InstructionMIRData::EndFinally(_) => None
         }
     }
 
@@ -236,29 +478,89 @@ impl InstructionMIRData {
             InstructionMIRData::SubFloat32(_, op1, op2) => vec![op1.clone(), op2.clone()],
             InstructionMIRData::MultiplyFloat32(_, op1, op2) => vec![op1.clone(), op2.clone()],
             InstructionMIRData::DivideFloat32(_, op1, op2) => vec![op1.clone(), op2.clone()],
+            InstructionMIRData::MinFloat32(_, op1, op2) => vec![op1.clone(), op2.clone()],
+            InstructionMIRData::MaxFloat32(_, op1, op2) => vec![op1.clone(), op2.clone()],
+            InstructionMIRData::SquareRootFloat32(_, op1) => vec![op1.clone()],
+            InstructionMIRData::RoundFloat32(_, op1, _) => vec![op1.clone()],
+            InstructionMIRData::LoadInt64(_, _) => Vec::new(),
+            InstructionMIRData::LoadFloat64(_, _) => Vec::new(),
+            InstructionMIRData::AddInt64(_, op1, op2) => vec![op1.clone(), op2.clone()],
+            InstructionMIRData::SubInt64(_, op1, op2) => vec![op1.clone(), op2.clone()],
+            InstructionMIRData::MultiplyInt64(_, op1, op2) => vec![op1.clone(), op2.clone()],
+            InstructionMIRData::DivideInt64(_, op1, op2) => vec![op1.clone(), op2.clone()],
+            InstructionMIRData::AddFloat64(_, op1, op2) => vec![op1.clone(), op2.clone()],
+            InstructionMIRData::SubFloat64(_, op1, op2) => vec![op1.clone(), op2.clone()],
+            InstructionMIRData::MultiplyFloat64(_, op1, op2) => vec![op1.clone(), op2.clone()],
+            InstructionMIRData::DivideFloat64(_, op1, op2) => vec![op1.clone(), op2.clone()],
             InstructionMIRData::AndBool(_, op1, op2) => vec![op1.clone(), op2.clone()],
             InstructionMIRData::AndBoolConstant(_, op1, _) => vec![op1.clone()],
             InstructionMIRData::OrBool(_, op1, op2) => vec![op1.clone(), op2.clone()],
             InstructionMIRData::OrBoolConstant(_, op1, _) => vec![op1.clone()],
             InstructionMIRData::NotBool(_, op1) => vec![op1.clone()],
+            InstructionMIRData::RemainderInt32(_, op1, op2) => vec![op1.clone(), op2.clone()],
+            InstructionMIRData::NegateInt32(_, op1) => vec![op1.clone()],
+            InstructionMIRData::AndInt32(_, op1, op2) => vec![op1.clone(), op2.clone()],
+            InstructionMIRData::OrInt32(_, op1, op2) => vec![op1.clone(), op2.clone()],
+            InstructionMIRData::XorInt32(_, op1, op2) => vec![op1.clone(), op2.clone()],
+            InstructionMIRData::NotInt32(_, op1) => vec![op1.clone()],
+            InstructionMIRData::ShiftLeftInt32(_, op1, op2) => vec![op1.clone(), op2.clone()],
+            InstructionMIRData::ShiftRightInt32(_, op1, op2) => vec![op1.clone(), op2.clone()],
+            InstructionMIRData::RemainderInt64(_, op1, op2) => vec![op1.clone(), op2.clone()],
+            InstructionMIRData::NegateInt64(_, op1) => vec![op1.clone()],
+            InstructionMIRData::AndInt64(_, op1, op2) => vec![op1.clone(), op2.clone()],
+            InstructionMIRData::OrInt64(_, op1, op2) => vec![op1.clone(), op2.clone()],
+            InstructionMIRData::XorInt64(_, op1, op2) => vec![op1.clone(), op2.clone()],
+            InstructionMIRData::NotInt64(_, op1) => vec![op1.clone()],
+            InstructionMIRData::ShiftLeftInt64(_, op1, op2) => vec![op1.clone(), op2.clone()],
+            InstructionMIRData::ShiftRightInt64(_, op1, op2) => vec![op1.clone(), op2.clone()],
+            InstructionMIRData::ConvertInt32ToFloat32(_, op1) => vec![op1.clone()],
+            InstructionMIRData::ConvertFloat32ToInt32(_, op1) => vec![op1.clone()],
+            InstructionMIRData::ConvertBoolToInt32(_, op1) => vec![op1.clone()],
+            InstructionMIRData::ConvertInt32ToInt64(_, op1) => vec![op1.clone()],
+            InstructionMIRData::ConvertInt64ToInt32(_, op1) => vec![op1.clone()],
+            InstructionMIRData::ConvertFloat32ToFloat64(_, op1) => vec![op1.clone()],
+            InstructionMIRData::ConvertFloat64ToFloat32(_, op1) => vec![op1.clone()],
+            InstructionMIRData::ConvertInt64ToFloat64(_, op1) => vec![op1.clone()],
+            InstructionMIRData::ConvertFloat64ToInt64(_, op1) => vec![op1.clone()],
             InstructionMIRData::Return(register) => Vec::from_iter(register.iter().cloned()),
             InstructionMIRData::Call(_, _, arguments) => arguments.clone(),
+            InstructionMIRData::LoadFunction(_, _) => Vec::new(),
+            InstructionMIRData::CallIndirect(_, _, function, arguments) => arguments.iter().chain(std::iter::once(function)).cloned().collect(),
             InstructionMIRData::LoadArgument(_, _) => Vec::new(),
             InstructionMIRData::LoadNull(_) => Vec::new(),
+            InstructionMIRData::LoadString(_, _) => Vec::new(),
             InstructionMIRData::NewArray(_, _, op) => vec![op.clone()],
             InstructionMIRData::LoadElement(_, _, op1, op2) => vec![op1.clone(), op2.clone()],
             InstructionMIRData::StoreElement(_, op1, op2, op3) => vec![op1.clone(), op2.clone(), op3.clone()],
-            InstructionMIRData::LoadArrayLength(_, _) => Vec::new(),
+            InstructionMIRData::LoadArrayLength(_, op) => vec![op.clone()],
+            InstructionMIRData::ArrayCopy(_, op1, op2, op3, op4, op5) => vec![op1.clone(), op2.clone(), op3.clone(), op4.clone(), op5.clone()],
+            InstructionMIRData::ArrayFill(_, op1, op2) => vec![op1.clone(), op2.clone()],
+            InstructionMIRData::ArrayClone(_, _, op) => vec![op.clone()],
+            InstructionMIRData::ArrayEquals(_, _, op1, op2) => vec![op1.clone(), op2.clone()],
+            InstructionMIRData::NewArray2D(_, _, op1, op2) => vec![op1.clone(), op2.clone()],
+            InstructionMIRData::LoadElement2D(_, _, op1, op2, op3) => vec![op1.clone(), op2.clone(), op3.clone()],
+            InstructionMIRData::StoreElement2D(_, op1, op2, op3, op4) => vec![op1.clone(), op2.clone(), op3.clone(), op4.clone()],
+            InstructionMIRData::LoadArrayDimension(_, _, op) => vec![op.clone()],
             InstructionMIRData::NewObject(_, _) => Vec::new(),
+            InstructionMIRData::IsInstance(_, _, op) => vec![op.clone()],
+            InstructionMIRData::CastClass(_, _, op) => vec![op.clone()],
             InstructionMIRData::LoadField(_, _, _, op) => vec![op.clone()],
             InstructionMIRData::StoreField(_, _, op1, op2) => vec![op1.clone(), op2.clone()],
+            InstructionMIRData::LoadStaticField(_, _, _) => Vec::new(),
+            InstructionMIRData::StoreStaticField(_, _, op) => vec![op.clone()],
+            InstructionMIRData::NewStruct(_, _) => Vec::new(),
+            InstructionMIRData::LoadStructField(_, _, _, op) => vec![op.clone()],
+            InstructionMIRData::StoreStructField(_, _, _, op1, op2) => vec![op1.clone(), op2.clone()],
             InstructionMIRData::CallInstance(_, _, arguments) => arguments.clone(),
             InstructionMIRData::GarbageCollect => Vec::new(),
             InstructionMIRData::PrintStackFrame => Vec::new(),
             InstructionMIRData::BranchLabel(_) => Vec::new(),
             InstructionMIRData::Branch(_) => Vec::new(),
             InstructionMIRData::BranchCondition(_, _, _, op1, op2) => vec![op1.clone(), op2.clone()],
-            InstructionMIRData::Compare(_, _, _, op1, op2) => vec![op1.clone(), op2.clone()]
+            InstructionMIRData::Compare(_, _, _, op1, op2) => vec![op1.clone(), op2.clone()],
+            InstructionMIRData::ExceptionLanding(_) => Vec::new(),
+            InstructionMIRData::Throw(op) => vec![op.clone()],
+            InstructionMIRData::EndFinally(op) => vec![op.clone()]
         }
     }
 
@@ -278,33 +580,93 @@ impl InstructionMIRData {
             InstructionMIRData::SubFloat32(_, op1, op2) => vec![op1, op2],
             InstructionMIRData::MultiplyFloat32(_, op1, op2) => vec![op1, op2],
             InstructionMIRData::DivideFloat32(_, op1, op2) => vec![op1, op2],
+            InstructionMIRData::MinFloat32(_, op1, op2) => vec![op1, op2],
+            InstructionMIRData::MaxFloat32(_, op1, op2) => vec![op1, op2],
+            InstructionMIRData::SquareRootFloat32(_, op1) => vec![op1],
+            InstructionMIRData::RoundFloat32(_, op1, _) => vec![op1],
+            InstructionMIRData::LoadInt64(_, _) => Vec::new(),
+            InstructionMIRData::LoadFloat64(_, _) => Vec::new(),
+            InstructionMIRData::AddInt64(_, op1, op2) => vec![op1, op2],
+            InstructionMIRData::SubInt64(_, op1, op2) => vec![op1, op2],
+            InstructionMIRData::MultiplyInt64(_, op1, op2) => vec![op1, op2],
+            InstructionMIRData::DivideInt64(_, op1, op2) => vec![op1, op2],
+            InstructionMIRData::AddFloat64(_, op1, op2) => vec![op1, op2],
+            InstructionMIRData::SubFloat64(_, op1, op2) => vec![op1, op2],
+            InstructionMIRData::MultiplyFloat64(_, op1, op2) => vec![op1, op2],
+            InstructionMIRData::DivideFloat64(_, op1, op2) => vec![op1, op2],
             InstructionMIRData::AndBool(_, op1, op2) => vec![op1, op2],
             InstructionMIRData::AndBoolConstant(_, op1, _) => vec![op1],
             InstructionMIRData::OrBool(_, op1, op2) => vec![op1, op2],
             InstructionMIRData::OrBoolConstant(_, op1, _) => vec![op1],
             InstructionMIRData::NotBool(_, op1) => vec![op1],
-            InstructionMIRData::Return(register) => register.as_mut().map(|r| vec![r]).unwrap_or_else(|| Vec::new()),
-            InstructionMIRData::Call(_, _, arguments) => arguments.iter_mut().map(|r| r).collect(),
+            InstructionMIRData::RemainderInt32(_, op1, op2) => vec![op1, op2],
+            InstructionMIRData::NegateInt32(_, op1) => vec![op1],
+            InstructionMIRData::AndInt32(_, op1, op2) => vec![op1, op2],
+            InstructionMIRData::OrInt32(_, op1, op2) => vec![op1, op2],
+            InstructionMIRData::XorInt32(_, op1, op2) => vec![op1, op2],
+            InstructionMIRData::NotInt32(_, op1) => vec![op1],
+            InstructionMIRData::ShiftLeftInt32(_, op1, op2) => vec![op1, op2],
+            InstructionMIRData::ShiftRightInt32(_, op1, op2) => vec![op1, op2],
+            InstructionMIRData::RemainderInt64(_, op1, op2) => vec![op1, op2],
+            InstructionMIRData::NegateInt64(_, op1) => vec![op1],
+            InstructionMIRData::AndInt64(_, op1, op2) => vec![op1, op2],
+            InstructionMIRData::OrInt64(_, op1, op2) => vec![op1, op2],
+            InstructionMIRData::XorInt64(_, op1, op2) => vec![op1, op2],
+            InstructionMIRData::NotInt64(_, op1) => vec![op1],
+            InstructionMIRData::ShiftLeftInt64(_, op1, op2) => vec![op1, op2],
+            InstructionMIRData::ShiftRightInt64(_, op1, op2) => vec![op1, op2],
+            InstructionMIRData::ConvertInt32ToFloat32(_, op1) => vec![op1],
+            InstructionMIRData::ConvertFloat32ToInt32(_, op1) => vec![op1],
+            InstructionMIRData::ConvertBoolToInt32(_, op1) => vec![op1],
+            InstructionMIRData::ConvertInt32ToInt64(_, op1) => vec![op1],
+            InstructionMIRData::ConvertInt64ToInt32(_, op1) => vec![op1],
+            InstructionMIRData::ConvertFloat32ToFloat64(_, op1) => vec![op1],
+            InstructionMIRData::ConvertFloat64ToFloat32(_, op1) => vec![op1],
+            InstructionMIRData::ConvertInt64ToFloat64(_, op1) => vec![op1],
+            InstructionMIRData::ConvertFloat64ToInt64(_, op1) => vec![op1],
+            InstructionMIRData::Return(register) => register.as_mut().map(|r| vec![r]).unwrap_or_else(Vec::new),
+            InstructionMIRData::Call(_, _, arguments) => arguments.iter_mut().collect(),
+            InstructionMIRData::LoadFunction(_, _) => Vec::new(),
+            InstructionMIRData::CallIndirect(_, _, function, arguments) => arguments.iter_mut().chain(std::iter::once(function)).collect(),
             InstructionMIRData::LoadArgument(_, _) => Vec::new(),
             InstructionMIRData::LoadNull(_) => Vec::new(),
+            InstructionMIRData::LoadString(_, _) => Vec::new(),
             InstructionMIRData::NewArray(_, _, op) => vec![op],
             InstructionMIRData::LoadElement(_, _, op1, op2) => vec![op1, op2],
             InstructionMIRData::StoreElement(_, op1, op2, op3) => vec![op1, op2, op3],
-            InstructionMIRData::LoadArrayLength(_, _) => Vec::new(),
+            InstructionMIRData::LoadArrayLength(_, op) => vec![op],
+            InstructionMIRData::ArrayCopy(_, op1, op2, op3, op4, op5) => vec![op1, op2, op3, op4, op5],
+            InstructionMIRData::ArrayFill(_, op1, op2) => vec![op1, op2],
+            InstructionMIRData::ArrayClone(_, _, op) => vec![op],
+            InstructionMIRData::ArrayEquals(_, _, op1, op2) => vec![op1, op2],
+            InstructionMIRData::NewArray2D(_, _, op1, op2) => vec![op1, op2],
+            InstructionMIRData::LoadElement2D(_, _, op1, op2, op3) => vec![op1, op2, op3],
+            InstructionMIRData::StoreElement2D(_, op1, op2, op3, op4) => vec![op1, op2, op3, op4],
+            InstructionMIRData::LoadArrayDimension(_, _, op) => vec![op],
             InstructionMIRData::NewObject(_, _) => Vec::new(),
+            InstructionMIRData::IsInstance(_, _, op) => vec![op],
+            InstructionMIRData::CastClass(_, _, op) => vec![op],
             InstructionMIRData::LoadField(_, _, _, op) => vec![op],
             InstructionMIRData::StoreField(_, _, op1, op2) => vec![op1, op2],
-            InstructionMIRData::CallInstance(_, _, arguments) => arguments.iter_mut().map(|r| r).collect(),
+            InstructionMIRData::LoadStaticField(_, _, _) => Vec::new(),
+            InstructionMIRData::StoreStaticField(_, _, op) => vec![op],
+            InstructionMIRData::NewStruct(_, _) => Vec::new(),
+            InstructionMIRData::LoadStructField(_, _, _, op) => vec![op],
+            InstructionMIRData::StoreStructField(_, _, _, op1, op2) => vec![op1, op2],
+            InstructionMIRData::CallInstance(_, _, arguments) => arguments.iter_mut().collect(),
             InstructionMIRData::GarbageCollect => Vec::new(),
             InstructionMIRData::PrintStackFrame => Vec::new(),
             InstructionMIRData::BranchLabel(_) => Vec::new(),
             InstructionMIRData::Branch(_) => Vec::new(),
             InstructionMIRData::BranchCondition(_, _, _, op1, op2) => vec![op1, op2],
-            InstructionMIRData::Compare(_, _, _, op1, op2) => vec![op1, op2]
+            InstructionMIRData::Compare(_, _, _, op1, op2) => vec![op1, op2],
+            InstructionMIRData::ExceptionLanding(_) => Vec::new(),
+            InstructionMIRData::Throw(op) => vec![op],
+            InstructionMIRData::EndFinally(op) => vec![op]
         }
     }
 
     pub fn use_virtual_registers(&self) -> Vec<VirtualRegister> {
-        self.use_registers().iter().map(|register| VirtualRegister::from(register)).collect()
+        self.use_registers().iter().map(VirtualRegister::from).collect()
     }
 }
@@ -1,7 +1,11 @@
 use std::collections::HashMap;
 
 use crate::model::function::{FunctionDeclaration, FunctionAddress, FunctionSignature};
-use crate::model::typesystem::TypeId;
+use crate::model::typesystem::{TypeId, TypeStorage};
+use crate::runtime::{string, io, math};
+
+/// The element types of the arrays that the std.array functions are defined for.
+pub const ARRAY_FUNCTION_ELEMENTS: [TypeId; 5] = [TypeId::Int32, TypeId::Int64, TypeId::Float32, TypeId::Float64, TypeId::Bool];
 
 pub struct Binder {
     functions: HashMap<FunctionSignature, FunctionDeclaration>
@@ -27,6 +31,221 @@ impl Binder {
             std::ptr::null_mut()
         ));
 
+        binder.define(FunctionDeclaration::with_external(
+            "std.string.length".to_owned(),
+            vec![TypeId::String],
+            TypeId::Int32,
+            string::length as *mut std::ffi::c_void
+        ));
+
+        binder.define(FunctionDeclaration::with_external(
+            "std.string.concat".to_owned(),
+            vec![TypeId::String, TypeId::String],
+            TypeId::String,
+            string::concat as *mut std::ffi::c_void
+        ));
+
+        binder.define(FunctionDeclaration::with_external(
+            "std.string.substring".to_owned(),
+            vec![TypeId::String, TypeId::Int32, TypeId::Int32],
+            TypeId::String,
+            string::substring as *mut std::ffi::c_void
+        ));
+
+        binder.define(FunctionDeclaration::with_external(
+            "std.string.compare".to_owned(),
+            vec![TypeId::String, TypeId::String],
+            TypeId::Int32,
+            string::compare as *mut std::ffi::c_void
+        ));
+
+        binder.define(FunctionDeclaration::with_external(
+            "std.string.from_int".to_owned(),
+            vec![TypeId::Int32],
+            TypeId::String,
+            string::from_int as *mut std::ffi::c_void
+        ));
+
+        binder.define(FunctionDeclaration::with_external(
+            "std.string.from_float".to_owned(),
+            vec![TypeId::Float32],
+            TypeId::String,
+            string::from_float as *mut std::ffi::c_void
+        ));
+
+        binder.define(FunctionDeclaration::with_external(
+            "std.io.print_int".to_owned(),
+            vec![TypeId::Int32],
+            TypeId::Void,
+            io::print_int as *mut std::ffi::c_void
+        ));
+
+        binder.define(FunctionDeclaration::with_external(
+            "std.io.print_float".to_owned(),
+            vec![TypeId::Float32],
+            TypeId::Void,
+            io::print_float as *mut std::ffi::c_void
+        ));
+
+        binder.define(FunctionDeclaration::with_external(
+            "std.io.print_bool".to_owned(),
+            vec![TypeId::Bool],
+            TypeId::Void,
+            io::print_bool as *mut std::ffi::c_void
+        ));
+
+        binder.define(FunctionDeclaration::with_external(
+            "std.io.print_char".to_owned(),
+            vec![TypeId::Int32],
+            TypeId::Void,
+            io::print_char as *mut std::ffi::c_void
+        ));
+
+        binder.define(FunctionDeclaration::with_external(
+            "std.io.print_string".to_owned(),
+            vec![TypeId::String],
+            TypeId::Void,
+            io::print_string as *mut std::ffi::c_void
+        ));
+
+        binder.define(FunctionDeclaration::with_external(
+            "std.io.println".to_owned(),
+            Vec::new(),
+            TypeId::Void,
+            io::println as *mut std::ffi::c_void
+        ));
+
+        binder.define(FunctionDeclaration::with_external(
+            "std.io.read_int".to_owned(),
+            Vec::new(),
+            TypeId::Int32,
+            io::read_int as *mut std::ffi::c_void
+        ));
+
+        binder.define(FunctionDeclaration::with_external(
+            "std.math.sqrt".to_owned(),
+            vec![TypeId::Float32],
+            TypeId::Float32,
+            math::sqrt_float as *mut std::ffi::c_void
+        ));
+
+        binder.define(FunctionDeclaration::with_external(
+            "std.math.abs".to_owned(),
+            vec![TypeId::Float32],
+            TypeId::Float32,
+            math::abs_float as *mut std::ffi::c_void
+        ));
+
+        binder.define(FunctionDeclaration::with_external(
+            "std.math.min".to_owned(),
+            vec![TypeId::Float32, TypeId::Float32],
+            TypeId::Float32,
+            math::min_float as *mut std::ffi::c_void
+        ));
+
+        binder.define(FunctionDeclaration::with_external(
+            "std.math.max".to_owned(),
+            vec![TypeId::Float32, TypeId::Float32],
+            TypeId::Float32,
+            math::max_float as *mut std::ffi::c_void
+        ));
+
+        binder.define(FunctionDeclaration::with_external(
+            "std.math.floor".to_owned(),
+            vec![TypeId::Float32],
+            TypeId::Float32,
+            math::floor_float as *mut std::ffi::c_void
+        ));
+
+        binder.define(FunctionDeclaration::with_external(
+            "std.math.ceil".to_owned(),
+            vec![TypeId::Float32],
+            TypeId::Float32,
+            math::ceil_float as *mut std::ffi::c_void
+        ));
+
+        binder.define(FunctionDeclaration::with_external(
+            "std.math.pow".to_owned(),
+            vec![TypeId::Float32, TypeId::Float32],
+            TypeId::Float32,
+            math::pow_float as *mut std::ffi::c_void
+        ));
+
+        binder.define(FunctionDeclaration::with_external(
+            "std.math.sin".to_owned(),
+            vec![TypeId::Float32],
+            TypeId::Float32,
+            math::sin_float as *mut std::ffi::c_void
+        ));
+
+        binder.define(FunctionDeclaration::with_external(
+            "std.math.cos".to_owned(),
+            vec![TypeId::Float32],
+            TypeId::Float32,
+            math::cos_float as *mut std::ffi::c_void
+        ));
+
+        binder.define(FunctionDeclaration::with_external(
+            "std.math.abs".to_owned(),
+            vec![TypeId::Int32],
+            TypeId::Int32,
+            math::abs_int as *mut std::ffi::c_void
+        ));
+
+        binder.define(FunctionDeclaration::with_external(
+            "std.math.min".to_owned(),
+            vec![TypeId::Int32, TypeId::Int32],
+            TypeId::Int32,
+            math::min_int as *mut std::ffi::c_void
+        ));
+
+        binder.define(FunctionDeclaration::with_external(
+            "std.math.max".to_owned(),
+            vec![TypeId::Int32, TypeId::Int32],
+            TypeId::Int32,
+            math::max_int as *mut std::ffi::c_void
+        ));
+
+        binder.define(FunctionDeclaration::with_external(
+            "std.math.pow".to_owned(),
+            vec![TypeId::Int32, TypeId::Int32],
+            TypeId::Int32,
+            math::pow_int as *mut std::ffi::c_void
+        ));
+
+        // The array functions are always expanded by the compiler, so they have no address
+        for element in &ARRAY_FUNCTION_ELEMENTS {
+            let array_type = TypeId::Array(Box::new(element.clone()));
+
+            binder.define(FunctionDeclaration::with_external(
+                "std.array.copy".to_owned(),
+                vec![array_type.clone(), TypeId::Int32, array_type.clone(), TypeId::Int32, TypeId::Int32],
+                TypeId::Void,
+                std::ptr::null_mut()
+            ));
+
+            binder.define(FunctionDeclaration::with_external(
+                "std.array.fill".to_owned(),
+                vec![array_type.clone(), element.clone()],
+                TypeId::Void,
+                std::ptr::null_mut()
+            ));
+
+            binder.define(FunctionDeclaration::with_external(
+                "std.array.clone".to_owned(),
+                vec![array_type.clone()],
+                array_type.clone(),
+                std::ptr::null_mut()
+            ));
+
+            binder.define(FunctionDeclaration::with_external(
+                "std.array.equals".to_owned(),
+                vec![array_type.clone(), array_type.clone()],
+                TypeId::Bool,
+                std::ptr::null_mut()
+            ));
+        }
+
         binder
     }
 
@@ -38,6 +257,25 @@ impl Binder {
         self.functions.get(signature)
     }
 
+    /// Returns the member function with the given signature, which is either defined in the class or inherited from a base class.
+    pub fn get_member(&self, type_storage: &TypeStorage, signature: &FunctionSignature) -> Option<&FunctionDeclaration> {
+        let mut current_type = type_storage.get(signature.class.as_ref()?);
+        while let Some(class_type) = current_type {
+            let member_signature = FunctionSignature::with_class(signature.name.clone(), class_type.id.clone(), signature.parameters.clone());
+            if let Some(function) = self.functions.get(&member_signature) {
+                return Some(function);
+            }
+
+            current_type = class_type.base_type();
+        }
+
+        None
+    }
+
+    pub fn functions(&self) -> impl Iterator<Item=&FunctionDeclaration> {
+        self.functions.values()
+    }
+
     pub fn set_address(&mut self, signature: &FunctionSignature, address: FunctionAddress) {
         self.functions.get_mut(signature).unwrap().set_address(address)
     }
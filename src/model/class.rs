@@ -2,23 +2,42 @@ use std::collections::HashMap;
 use std::iter::FromIterator;
 
 use crate::model::typesystem::TypeId;
+use crate::model::function::FunctionDeclaration;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Field {
     name: String,
     field_type: TypeId,
-    offset: usize
+    offset: usize,
+    size: usize,
+    reference_offsets: Vec<usize>
 }
 
 impl Field {
     pub fn new(name: String, field_type: TypeId) -> Field {
         Field {
             name,
+            size: field_type.size(),
+            reference_offsets: if field_type.is_reference() { vec![0] } else { Vec::new() },
             field_type,
             offset: 0
         }
     }
 
+    /// Creates a field that stores the given struct inline.
+    pub fn with_struct(name: String, struct_class: &Class) -> Field {
+        assert!(struct_class.is_struct());
+
+        Field {
+            name,
+            field_type: TypeId::Struct(struct_class.name.clone()),
+            offset: 0,
+            size: struct_class.memory_size,
+            reference_offsets: struct_class.reference_offsets.clone()
+        }
+    }
+
+    #[allow(dead_code)]
     pub fn name(&self) -> &str {
         &self.name
     }
@@ -30,32 +49,94 @@ impl Field {
     pub fn offset(&self) -> usize {
         self.offset
     }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// The offsets of the references within the field, relative to the field.
+    pub fn reference_offsets(&self) -> &Vec<usize> {
+        &self.reference_offsets
+    }
 }
 
 pub struct Class {
     name: String,
+    base: Option<String>,
+    interfaces: Vec<String>,
+    interface_functions: Option<Vec<FunctionDeclaration>>,
     fields: Vec<Field>,
     fields_mapping: HashMap<String, usize>,
-    memory_size: usize
+    memory_size: usize,
+    reference_offsets: Vec<usize>,
+    is_struct: bool,
+    static_fields: Vec<Field>,
+    static_fields_mapping: HashMap<String, usize>,
+    static_memory_size: usize
 }
 
 impl Class {
-    pub fn new(name: String, mut fields: Vec<Field>) -> Class {
-        let mut offset = 0;
+    pub fn new(name: String, fields: Vec<Field>) -> Class {
+        Class::create(name, None, fields)
+    }
+
+    /// Creates a class derived from the given base class, where the fields of the base class prefix the fields of the class.
+    pub fn with_base(name: String, base: &Class, fields: Vec<Field>) -> Class {
+        Class::create(name, Some(base), fields)
+    }
+
+    /// Creates a struct, which is a value type that is stored inline in locals, fields and arrays rather than on the heap.
+    /// The size is padded to whole words, such that structs can be copied a word at a time.
+    pub fn new_struct(name: String, fields: Vec<Field>) -> Class {
+        let mut class = Class::create(name, None, fields);
+        class.memory_size = class.memory_size.div_ceil(8) * 8;
+        class.is_struct = true;
+        class
+    }
+
+    /// Creates an interface, which has no fields and consists of the given functions that classes implementing it must define.
+    pub fn new_interface(name: String, functions: Vec<FunctionDeclaration>) -> Class {
+        let mut class = Class::create(name, None, Vec::new());
+        class.interface_functions = Some(functions);
+        class
+    }
+
+    fn create(name: String, base: Option<&Class>, mut fields: Vec<Field>) -> Class {
+        let mut offset = base.map(|base| base.memory_size).unwrap_or(0);
         for field in &mut fields {
             field.offset = offset;
-            offset += field.field_type.size();
+            offset += field.size;
         }
 
+        let fields = base
+            .map(|base| base.fields.clone())
+            .unwrap_or_default()
+            .into_iter()
+            .chain(fields)
+            .collect::<Vec<_>>();
+
         let fields_mapping = HashMap::from_iter(
             fields.iter().enumerate().map(|(index, field)| (field.name.clone(), index))
         );
 
+        let reference_offsets = fields
+            .iter()
+            .flat_map(|field| field.reference_offsets.iter().map(move |offset| field.offset + offset))
+            .collect();
+
         Class {
             name,
+            base: base.map(|base| base.name.clone()),
+            interfaces: Vec::new(),
+            interface_functions: None,
             fields,
             fields_mapping,
-            memory_size: offset
+            memory_size: offset,
+            reference_offsets,
+            is_struct: false,
+            static_fields: Vec::new(),
+            static_fields_mapping: HashMap::new(),
+            static_memory_size: 0
         }
     }
 
@@ -63,6 +144,32 @@ impl Class {
         &self.name
     }
 
+    pub fn base(&self) -> Option<&str> {
+        self.base.as_deref()
+    }
+
+    pub fn is_interface(&self) -> bool {
+        self.interface_functions.is_some()
+    }
+
+    pub fn is_struct(&self) -> bool {
+        self.is_struct
+    }
+
+    pub fn interface_functions(&self) -> Option<&Vec<FunctionDeclaration>> {
+        self.interface_functions.as_ref()
+    }
+
+    pub fn interfaces(&self) -> &Vec<String> {
+        &self.interfaces
+    }
+
+    pub fn add_interface(&mut self, interface: &Class) {
+        assert!(interface.is_interface());
+        self.interfaces.push(interface.name.clone());
+    }
+
+    #[allow(dead_code)]
     pub fn fields(&self) -> &Vec<Field> {
         &self.fields
     }
@@ -75,4 +182,118 @@ impl Class {
     pub fn memory_size(&self) -> usize {
         self.memory_size
     }
-}
\ No newline at end of file
+
+    /// The offsets of all references within the objects of the class, including those embedded in struct fields.
+    pub fn reference_offsets(&self) -> &Vec<usize> {
+        &self.reference_offsets
+    }
+
+    /// Adds a field that belongs to the class rather than its objects. Static fields are not inherited.
+    pub fn add_static_field(&mut self, mut field: Field) {
+        field.offset = self.static_memory_size;
+        self.static_memory_size += field.size;
+        self.static_fields_mapping.insert(field.name.clone(), self.static_fields.len());
+        self.static_fields.push(field);
+    }
+
+    pub fn static_fields(&self) -> &Vec<Field> {
+        &self.static_fields
+    }
+
+    pub fn get_static_field(&self, name: &str) -> Option<&Field> {
+        let field_index = self.static_fields_mapping.get(name)?;
+        self.static_fields.get(*field_index)
+    }
+
+    pub fn static_memory_size(&self) -> usize {
+        self.static_memory_size
+    }
+}
+
+#[test]
+fn test_base1() {
+    let base = Class::new(
+        "Shape".to_owned(),
+        vec![
+            Field::new("x".to_owned(), TypeId::Int32),
+            Field::new("next".to_owned(), TypeId::Class("Shape".to_owned()))
+        ]
+    );
+
+    let class = Class::with_base(
+        "Circle".to_owned(),
+        &base,
+        vec![Field::new("radius".to_owned(), TypeId::Float32)]
+    );
+
+    assert_eq!(Some("Shape"), class.base());
+    assert_eq!(3, class.fields().len());
+    assert_eq!(0, class.get_field("x").unwrap().offset());
+    assert_eq!(4, class.get_field("next").unwrap().offset());
+    assert_eq!(12, class.get_field("radius").unwrap().offset());
+    assert_eq!(16, class.memory_size());
+}
+
+#[test]
+fn test_static_fields1() {
+    let mut class = Class::new(
+        "Point".to_owned(),
+        vec![Field::new("x".to_owned(), TypeId::Int32)]
+    );
+
+    class.add_static_field(Field::new("count".to_owned(), TypeId::Int32));
+    class.add_static_field(Field::new("origin".to_owned(), TypeId::Class("Point".to_owned())));
+
+    assert_eq!(0, class.get_static_field("count").unwrap().offset());
+    assert_eq!(4, class.get_static_field("origin").unwrap().offset());
+    assert_eq!(12, class.static_memory_size());
+    assert!(class.get_static_field("x").is_none());
+    assert!(class.get_field("count").is_none());
+    assert_eq!(4, class.memory_size());
+}
+
+#[test]
+fn test_struct1() {
+    let point = Class::new_struct(
+        "Point".to_owned(),
+        vec![
+            Field::new("x".to_owned(), TypeId::Int32),
+            Field::new("y".to_owned(), TypeId::Int32),
+            Field::new("visible".to_owned(), TypeId::Bool)
+        ]
+    );
+
+    let line = Class::new_struct(
+        "Line".to_owned(),
+        vec![
+            Field::with_struct("start".to_owned(), &point),
+            Field::new("name".to_owned(), TypeId::String),
+            Field::with_struct("end".to_owned(), &point)
+        ]
+    );
+
+    let shape = Class::new(
+        "Shape".to_owned(),
+        vec![
+            Field::new("id".to_owned(), TypeId::Int32),
+            Field::with_struct("outline".to_owned(), &line),
+            Field::new("next".to_owned(), TypeId::Class("Shape".to_owned()))
+        ]
+    );
+
+    assert!(point.is_struct());
+    assert_eq!(16, point.memory_size());
+    assert!(point.reference_offsets().is_empty());
+
+    assert_eq!(TypeId::Struct("Point".to_owned()), line.get_field("start").unwrap().type_id().clone());
+    assert_eq!(16, line.get_field("name").unwrap().offset());
+    assert_eq!(24, line.get_field("end").unwrap().offset());
+    assert_eq!(40, line.memory_size());
+    assert_eq!(&vec![16], line.reference_offsets());
+
+    assert!(!shape.is_struct());
+    assert_eq!(4, shape.get_field("outline").unwrap().offset());
+    assert_eq!(44, shape.get_field("next").unwrap().offset());
+    assert_eq!(52, shape.memory_size());
+    assert_eq!(&vec![20, 44], shape.reference_offsets());
+}
@@ -0,0 +1,123 @@
+use crate::model::class::Class;
+use crate::model::typesystem::{TypeId, TypeStorage};
+
+pub const EXCEPTION_CLASS: &str = "std.Exception";
+pub const NULL_REFERENCE_EXCEPTION_CLASS: &str = "std.NullReferenceException";
+pub const ARRAY_CREATE_EXCEPTION_CLASS: &str = "std.ArrayCreateException";
+pub const ARRAY_BOUNDS_EXCEPTION_CLASS: &str = "std.ArrayBoundsException";
+pub const DIVISION_BY_ZERO_EXCEPTION_CLASS: &str = "std.DivisionByZeroException";
+pub const DIVISION_OVERFLOW_EXCEPTION_CLASS: &str = "std.DivisionOverflowException";
+pub const INVALID_CAST_EXCEPTION_CLASS: &str = "std.InvalidCastException";
+
+pub fn add_builtin_exceptions(type_storage: &mut TypeStorage) {
+    let exception_class = Class::new(EXCEPTION_CLASS.to_owned(), Vec::new());
+
+    let classes = [
+        NULL_REFERENCE_EXCEPTION_CLASS,
+        ARRAY_CREATE_EXCEPTION_CLASS,
+        ARRAY_BOUNDS_EXCEPTION_CLASS,
+        DIVISION_BY_ZERO_EXCEPTION_CLASS,
+        DIVISION_OVERFLOW_EXCEPTION_CLASS,
+        INVALID_CAST_EXCEPTION_CLASS
+    ];
+
+    let derived_classes = classes
+        .iter()
+        .map(|class_name| Class::with_base((*class_name).to_owned(), &exception_class, Vec::new()))
+        .collect::<Vec<_>>();
+
+    type_storage.add_class(exception_class).unwrap();
+    for class in derived_classes {
+        type_storage.add_class(class).unwrap();
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CatchHandler {
+    pub exception_type: TypeId,
+    pub start: usize,
+    pub end: usize
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FinallyHandler {
+    pub start: usize,
+    pub end: usize
+}
+
+/// A try block together with its handlers. The indices refer to the marker instructions of the function.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProtectedRegion {
+    pub try_start: usize,
+    pub try_end: usize,
+    pub catch_handlers: Vec<CatchHandler>,
+    pub finally_handler: Option<FinallyHandler>
+}
+
+impl ProtectedRegion {
+    pub fn new(try_start: usize) -> ProtectedRegion {
+        ProtectedRegion {
+            try_start,
+            try_end: try_start,
+            catch_handlers: Vec::new(),
+            finally_handler: None
+        }
+    }
+
+    pub fn end(&self) -> usize {
+        match &self.finally_handler {
+            Some(finally_handler) => finally_handler.end,
+            None => self.catch_handlers.last().map(|handler| handler.end).unwrap_or(self.try_end)
+        }
+    }
+
+    pub fn in_try(&self, instruction_index: usize) -> bool {
+        instruction_index > self.try_start && instruction_index < self.try_end
+    }
+
+    pub fn in_catch(&self, instruction_index: usize) -> bool {
+        self.catch_handlers.iter().any(|handler| instruction_index > handler.start && instruction_index < handler.end)
+    }
+
+    /// Returns the instruction index of the handler that an exception thrown at the given instruction lands at.
+    /// A catch handler is chosen if the exception is of the caught type or derived from it.
+    pub fn find_handler(&self, instruction_index: usize, type_storage: &TypeStorage, exception_type: &TypeId) -> Option<usize> {
+        if self.in_try(instruction_index) {
+            let exception_type = type_storage.get(exception_type);
+            let catch_handler = self.catch_handlers
+                .iter()
+                .find(|handler| exception_type.map(|exception_type| exception_type.is_subtype_of(&handler.exception_type)).unwrap_or(false));
+
+            if let Some(catch_handler) = catch_handler {
+                return Some(catch_handler.start);
+            }
+        }
+
+        if self.in_try(instruction_index) || self.in_catch(instruction_index) {
+            return self.finally_handler.as_ref().map(|handler| handler.start);
+        }
+
+        None
+    }
+}
+
+#[test]
+fn test_find_handler1() {
+    let mut type_storage = TypeStorage::new();
+    add_builtin_exceptions(&mut type_storage);
+
+    let region = ProtectedRegion {
+        try_start: 0,
+        try_end: 3,
+        catch_handlers: vec![
+            CatchHandler { exception_type: TypeId::Class(NULL_REFERENCE_EXCEPTION_CLASS.to_owned()), start: 4, end: 6 }
+        ],
+        finally_handler: Some(FinallyHandler { start: 7, end: 9 })
+    };
+
+    assert_eq!(Some(4), region.find_handler(1, &type_storage, &TypeId::Class(NULL_REFERENCE_EXCEPTION_CLASS.to_owned())));
+    assert_eq!(Some(7), region.find_handler(2, &type_storage, &TypeId::Class(ARRAY_BOUNDS_EXCEPTION_CLASS.to_owned())));
+    assert_eq!(Some(7), region.find_handler(5, &type_storage, &TypeId::Class(NULL_REFERENCE_EXCEPTION_CLASS.to_owned())));
+    assert_eq!(None, region.find_handler(8, &type_storage, &TypeId::Class(NULL_REFERENCE_EXCEPTION_CLASS.to_owned())));
+    assert_eq!(9, region.end());
+}
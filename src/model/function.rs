@@ -1,15 +1,25 @@
 use crate::model::typesystem::TypeId;
 use crate::model::instruction::Instruction;
-use crate::model::class::Class;
+use crate::model::exception::ProtectedRegion;
 
 pub type FunctionAddress = *mut std::ffi::c_void;
 
+pub const CONSTRUCTOR_NAME: &str = ".constructor";
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum FunctionType {
     External,
+    Native,
     Managed
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VirtualModifier {
+    Virtual,
+    Override,
+    Interface
+}
+
 #[derive(Debug, Clone)]
 pub struct FunctionDeclaration {
     function_type: FunctionType,
@@ -18,6 +28,7 @@ pub struct FunctionDeclaration {
     parameters: Vec<TypeId>,
     return_type: TypeId,
     address: Option<FunctionAddress>,
+    virtual_modifier: Option<VirtualModifier>
 }
 
 impl FunctionDeclaration {
@@ -28,7 +39,21 @@ impl FunctionDeclaration {
             class: None,
             parameters,
             return_type,
-            address: Some(address)
+            address: Some(address),
+            virtual_modifier: None
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn with_native(name: String, parameters: Vec<TypeId>, return_type: TypeId, address: FunctionAddress) -> FunctionDeclaration {
+        FunctionDeclaration {
+            function_type: FunctionType::Native,
+            name,
+            class: None,
+            parameters,
+            return_type,
+            address: Some(address),
+            virtual_modifier: None
         }
     }
 
@@ -39,7 +64,8 @@ impl FunctionDeclaration {
             class: None,
             parameters,
             return_type,
-            address: None
+            address: None,
+            virtual_modifier: None
         }
     }
 
@@ -51,7 +77,40 @@ impl FunctionDeclaration {
             class: Some(class),
             parameters,
             return_type,
-            address: None
+            address: None,
+            virtual_modifier: None
+        }
+    }
+
+    /// Creates a member function that is called through the virtual table of the object.
+    pub fn with_managed_virtual_member(name: String,
+                                       class: TypeId,
+                                       parameters: Vec<TypeId>,
+                                       return_type: TypeId,
+                                       virtual_modifier: VirtualModifier) -> FunctionDeclaration {
+        let mut declaration = FunctionDeclaration::with_managed_member(name, class, parameters, return_type);
+        declaration.virtual_modifier = Some(virtual_modifier);
+        declaration
+    }
+
+    /// Creates a constructor of the given class, which is called on newly allocated objects.
+    #[allow(dead_code)]
+    pub fn with_constructor(class: TypeId, parameters: Vec<TypeId>) -> FunctionDeclaration {
+        FunctionDeclaration::with_managed_member(CONSTRUCTOR_NAME.to_owned(), class, parameters, TypeId::Void)
+    }
+
+    /// Creates a function of an interface, which has no body and is called through the interface table of the object.
+    pub fn with_interface_member(name: String, interface: TypeId, parameters: Vec<TypeId>, return_type: TypeId) -> FunctionDeclaration {
+        FunctionDeclaration::with_managed_virtual_member(name, interface, parameters, return_type, VirtualModifier::Interface)
+    }
+
+    /// Creates the declaration used for calling a function through a reference of the given function type.
+    pub fn with_function_reference(function_type: &TypeId) -> Option<FunctionDeclaration> {
+        match function_type {
+            TypeId::Function(parameters, return_type) => {
+                Some(FunctionDeclaration::with_managed(String::new(), parameters.clone(), return_type.as_ref().clone()))
+            }
+            _ => None
         }
     }
 
@@ -75,6 +134,18 @@ impl FunctionDeclaration {
         &self.return_type
     }
 
+    pub fn virtual_modifier(&self) -> &Option<VirtualModifier> {
+        &self.virtual_modifier
+    }
+
+    pub fn is_virtual(&self) -> bool {
+        self.virtual_modifier.is_some()
+    }
+
+    pub fn is_constructor(&self) -> bool {
+        self.class.is_some() && self.name == CONSTRUCTOR_NAME
+    }
+
     pub fn address(&self) -> Option<FunctionAddress> {
         self.address
     }
@@ -92,12 +163,17 @@ impl FunctionDeclaration {
         }
     }
 
+    /// The type of a reference to the function.
+    pub fn reference_type(&self) -> TypeId {
+        TypeId::Function(self.parameters.clone(), Box::new(self.return_type.clone()))
+    }
+
     pub fn set_address(&mut self, address: FunctionAddress) {
         self.address = Some(address);
     }
 
     pub fn is_entry_point(&self) -> bool {
-        &self.function_type == &FunctionType::Managed
+        self.function_type == FunctionType::Managed
         && self.name() == "main"
         && self.parameters().is_empty()
         && self.return_type() == &TypeId::Int32
@@ -115,7 +191,9 @@ pub struct Function {
     locals: Vec<TypeId>,
     instructions: Vec<Instruction>,
     instruction_operand_types: Vec<Vec<TypeId>>,
-    operand_stack_size: usize
+    operand_stack_size: usize,
+    struct_slot_size: usize,
+    protected_regions: Vec<ProtectedRegion>
 }
 
 impl Function {
@@ -128,7 +206,9 @@ impl Function {
             locals,
             instructions,
             instruction_operand_types: (0..num_instructions).map(|_| Vec::new()).collect(),
-            operand_stack_size: 0
+            operand_stack_size: 0,
+            struct_slot_size: 0,
+            protected_regions: Vec::new()
         }
     }
 
@@ -144,6 +224,7 @@ impl Function {
         &self.locals
     }
 
+    #[allow(dead_code)]
     pub fn operand_stack_size(&self) -> usize {
         self.operand_stack_size
     }
@@ -152,6 +233,15 @@ impl Function {
         self.operand_stack_size = value;
     }
 
+    /// The size of the stack slot reserved for each virtual register that holds a struct, which fits the largest struct used.
+    pub fn struct_slot_size(&self) -> usize {
+        self.struct_slot_size
+    }
+
+    pub fn set_struct_slot_size(&mut self, value: usize) {
+        self.struct_slot_size = value;
+    }
+
     pub fn instructions(&self) -> &Vec<Instruction> {
         &self.instructions
     }
@@ -163,6 +253,14 @@ impl Function {
     pub fn instruction_operand_types_mut(&mut self, index: usize) -> &mut Vec<TypeId> {
         &mut self.instruction_operand_types[index]
     }
+
+    pub fn protected_regions(&self) -> &Vec<ProtectedRegion> {
+        &self.protected_regions
+    }
+
+    pub fn set_protected_regions(&mut self, regions: Vec<ProtectedRegion>) {
+        self.protected_regions = regions;
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -188,6 +286,14 @@ impl FunctionSignature {
             parameters
         }
     }
+
+    pub fn with_constructor(class: TypeId, parameters: Vec<TypeId>) -> FunctionSignature {
+        FunctionSignature::with_class(CONSTRUCTOR_NAME.to_owned(), class, parameters)
+    }
+
+    pub fn is_constructor(&self) -> bool {
+        self.class.is_some() && self.name == CONSTRUCTOR_NAME
+    }
 }
 
 impl std::fmt::Display for FunctionSignature {
@@ -204,6 +310,7 @@ impl std::fmt::Display for FunctionSignature {
 }
 
 pub struct FunctionStorage {
+    #[allow(clippy::vec_box)]
     functions: Vec<Box<Function>>
 }
 
@@ -218,12 +325,14 @@ impl FunctionStorage {
         self.functions.push(Box::new(function));
     }
 
+    #[allow(dead_code)]
     pub fn get_function(&self, signature: &FunctionSignature) -> Option<&Function> {
         self.functions.iter()
             .find(|function| &function.declaration().signature() == signature)
             .map(|function| function.as_ref())
     }
 
+    #[allow(clippy::vec_box)]
     pub fn functions_mut(&mut self) -> &mut Vec<Box<Function>> {
         &mut self.functions
     }
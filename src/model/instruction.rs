@@ -6,30 +6,64 @@ pub type BranchTarget = u32;
 #[derive(Debug, Clone, PartialEq)]
 pub enum Instruction {
     LoadInt32(i32),
+    LoadInt64(i64),
     LoadFloat32(f32),
+    LoadFloat64(f64),
+    #[allow(dead_code)]
     LoadTrue,
+    #[allow(dead_code)]
     LoadFalse,
     LoadNull(TypeId),
+    LoadString(String),
     LoadLocal(u32),
     StoreLocal(u32),
+    Duplicate,
+    Pop,
+    Swap,
     Add,
     Sub,
     Multiply,
     Divide,
+    Remainder,
+    Negate,
     And,
     Or,
+    Xor,
     Not,
+    ShiftLeft,
+    ShiftRight,
+    ConvertInt32ToFloat32,
+    ConvertFloat32ToInt32,
+    ConvertBoolToInt32,
+    ConvertInt32ToInt64,
+    ConvertInt64ToInt32,
+    ConvertFloat32ToFloat64,
+    ConvertFloat64ToFloat32,
+    ConvertInt64ToFloat64,
+    ConvertFloat64ToInt64,
     Call(FunctionSignature),
+    LoadFunction(FunctionSignature),
+    CallIndirect,
     LoadArgument(u32),
     Return,
     NewArray(TypeId),
     LoadElement(TypeId),
     StoreElement(TypeId),
     LoadArrayLength,
+    NewArray2D(TypeId),
+    LoadElement2D(TypeId),
+    StoreElement2D(TypeId),
+    LoadArrayDimension(u32),
     NewObject(String),
+    NewObjectWithConstructor(String, Vec<TypeId>),
     LoadField(String, String),
     StoreField(String, String),
+    LoadStaticField(String, String),
+    StoreStaticField(String, String),
+    #[allow(dead_code)]
     CallInstance(FunctionSignature),
+    IsInstance(String),
+    CastClass(String),
     Branch(BranchTarget),
     BranchEqual(BranchTarget),
     BranchNotEqual(BranchTarget),
@@ -42,7 +76,14 @@ pub enum Instruction {
     CompareGreaterThan,
     CompareGreaterThanOrEqual,
     CompareLessThan,
-    CompareLessThanOrEqual
+    CompareLessThanOrEqual,
+    BeginTry,
+    EndTry,
+    BeginCatch(String),
+    EndCatch,
+    BeginFinally,
+    EndFinally,
+    Throw
 }
 
 impl Instruction {
@@ -68,9 +109,15 @@ impl std::fmt::Display for Instruction {
             Instruction::LoadInt32(value) => {
                 write!(f, "LoadInt32 {}", value)
             }
+            Instruction::LoadInt64(value) => {
+                write!(f, "LoadInt64 {}", value)
+            }
             Instruction::LoadFloat32(value) => {
                 write!(f, "LoadFloat32 {}", value)
             }
+            Instruction::LoadFloat64(value) => {
+                write!(f, "LoadFloat64 {}", value)
+            }
             Instruction::LoadTrue => {
                 write!(f, "LoadTrue")
             }
@@ -80,12 +127,24 @@ impl std::fmt::Display for Instruction {
             Instruction::LoadNull(null_type) => {
                 write!(f, "LoadNull {}", null_type)
             }
+            Instruction::LoadString(value) => {
+                write!(f, "LoadString {:?}", value)
+            }
             Instruction::LoadLocal(index) => {
                 write!(f, "LoadLocal {}", index)
             }
             Instruction::StoreLocal(index) => {
                 write!(f, "StoreLocal {}", index)
             }
+            Instruction::Duplicate => {
+                write!(f, "Duplicate")
+            }
+            Instruction::Pop => {
+                write!(f, "Pop")
+            }
+            Instruction::Swap => {
+                write!(f, "Swap")
+            }
             Instruction::Add => {
                 write!(f, "Add")
             }
@@ -98,18 +157,66 @@ impl std::fmt::Display for Instruction {
             Instruction::Divide => {
                 write!(f, "Divide")
             }
+            Instruction::Remainder => {
+                write!(f, "Remainder")
+            }
+            Instruction::Negate => {
+                write!(f, "Negate")
+            }
             Instruction::And => {
                 write!(f, "And")
             }
             Instruction::Or => {
                 write!(f, "Or")
             }
+            Instruction::Xor => {
+                write!(f, "Xor")
+            }
             Instruction::Not => {
                 write!(f, "Not")
             }
+            Instruction::ShiftLeft => {
+                write!(f, "ShiftLeft")
+            }
+            Instruction::ShiftRight => {
+                write!(f, "ShiftRight")
+            }
+            Instruction::ConvertInt32ToFloat32 => {
+                write!(f, "ConvertInt32ToFloat32")
+            }
+            Instruction::ConvertFloat32ToInt32 => {
+                write!(f, "ConvertFloat32ToInt32")
+            }
+            Instruction::ConvertBoolToInt32 => {
+                write!(f, "ConvertBoolToInt32")
+            }
+            Instruction::ConvertInt32ToInt64 => {
+                write!(f, "ConvertInt32ToInt64")
+            }
+            Instruction::ConvertInt64ToInt32 => {
+                write!(f, "ConvertInt64ToInt32")
+            }
+            Instruction::ConvertFloat32ToFloat64 => {
+                write!(f, "ConvertFloat32ToFloat64")
+            }
+            Instruction::ConvertFloat64ToFloat32 => {
+                write!(f, "ConvertFloat64ToFloat32")
+            }
+            Instruction::ConvertInt64ToFloat64 => {
+                write!(f, "ConvertInt64ToFloat64")
+            }
+            Instruction::ConvertFloat64ToInt64 => {
+                write!(f, "ConvertFloat64ToInt64")
+            }
             Instruction::Call(signature) => {
                 write!(f, "Call {}", signature)
             }
+            Instruction::LoadFunction(signature) => {
+                write!(f, "LoadFunction {}", signature)
+            }
+            Instruction::CallIndirect => {
+                write!(f, "CallIndirect")
+            }
             Instruction::LoadArgument(argument) => {
                 write!(f, "LoadArgument {}", argument)
             }
@@ -128,6 +235,9 @@ impl std::fmt::Display for Instruction {
             Instruction::NewObject(class_type) => {
                 write!(f, "NewObject {}", class_type)
             }
+            Instruction::NewObjectWithConstructor(class_type, parameters) => {
+                write!(f, "NewObject {}({})", class_type, parameters.iter().map(|p| p.to_string()).collect::<Vec<_>>().join(" "))
+            }
             Instruction::CallInstance(signature) => {
                 write!(f, "CallInstance {}", signature)
             }
@@ -137,9 +247,33 @@ impl std::fmt::Display for Instruction {
             Instruction::StoreField(class_type, field) => {
                 write!(f, "StoreField {}::{}", class_type, field)
             }
+            Instruction::LoadStaticField(class_type, field) => {
+                write!(f, "LoadStaticField {}::{}", class_type, field)
+            }
+            Instruction::StoreStaticField(class_type, field) => {
+                write!(f, "StoreStaticField {}::{}", class_type, field)
+            }
             Instruction::LoadArrayLength => {
                 write!(f, "LoadArrayLength")
             }
+            Instruction::NewArray2D(element) => {
+                write!(f, "NewArray2D {}", element)
+            }
+            Instruction::LoadElement2D(element) => {
+                write!(f, "LoadElement2D {}", element)
+            }
+            Instruction::StoreElement2D(element) => {
+                write!(f, "StoreElement2D {}", element)
+            }
+            Instruction::LoadArrayDimension(dimension) => {
+                write!(f, "LoadArrayDimension {}", dimension)
+            }
+            Instruction::IsInstance(class_type) => {
+                write!(f, "IsInstance {}", class_type)
+            }
+            Instruction::CastClass(class_type) => {
+                write!(f, "CastClass {}", class_type)
+            }
             Instruction::Branch(target) => {
                 write!(f, "Branch {}", target)
             }
@@ -179,6 +313,27 @@ impl std::fmt::Display for Instruction {
             Instruction::CompareLessThanOrEqual => {
                 write!(f, "CompareLessThanOrEqual")
             }
+            Instruction::BeginTry => {
+                write!(f, "BeginTry")
+            }
+            Instruction::EndTry => {
+                write!(f, "EndTry")
+            }
+            Instruction::BeginCatch(class_type) => {
+                write!(f, "BeginCatch {}", class_type)
+            }
+            Instruction::EndCatch => {
+                write!(f, "EndCatch")
+            }
+            Instruction::BeginFinally => {
+                write!(f, "BeginFinally")
+            }
+            Instruction::EndFinally => {
+                write!(f, "EndFinally")
+            }
+            Instruction::Throw => {
+                write!(f, "Throw")
+            }
         }
     }
 }
\ No newline at end of file
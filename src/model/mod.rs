@@ -4,3 +4,4 @@ pub mod class;
 pub mod typesystem;
 pub mod verifier;
 pub mod binder;
+pub mod exception;
@@ -3,15 +3,23 @@ use std::ops::Deref;
 use std::iter::FromIterator;
 
 use crate::model::class::Class;
+use crate::model::function::{FunctionAddress, FunctionSignature};
+use crate::model::verifier::{VerifyError, VerifyErrorMessage, VerifyResult};
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum TypeId {
     Void,
     Int32,
+    Int64,
     Float32,
+    Float64,
     Bool,
+    String,
     Array(Box<TypeId>),
-    Class(String)
+    Array2D(Box<TypeId>),
+    Class(String),
+    Struct(String),
+    Function(Vec<TypeId>, Box<TypeId>)
 }
 
 impl TypeId {
@@ -19,56 +27,63 @@ impl TypeId {
         match self {
             TypeId::Void => 0,
             TypeId::Int32 => 4,
+            TypeId::Int64 => 8,
             TypeId::Float32 => 4,
+            TypeId::Float64 => 8,
             TypeId::Bool => 1,
+            TypeId::String => 8,
             TypeId::Array(_) => 8,
-            TypeId::Class(_) => 8
+            TypeId::Array2D(_) => 8,
+            TypeId::Class(_) => 8,
+            TypeId::Struct(_) => panic!("The size of a struct is given by its class, see TypeStorage::size_of."),
+            TypeId::Function(_, _) => 8
         }
     }
 
     pub fn element_type(&self) -> Option<&TypeId> {
-        if let TypeId::Array(element) = self {
-            Some(element.deref())
-        } else {
-            None
+        match self {
+            TypeId::Array(element) | TypeId::Array2D(element) => Some(element.deref()),
+            _ => None
         }
     }
 
     pub fn class_name(&self) -> Option<&str> {
-        if let TypeId::Class(class) = self {
-            Some(class)
-        } else {
-            None
+        match self {
+            TypeId::Class(class) | TypeId::Struct(class) => Some(class),
+            _ => None
         }
     }
 
     pub fn is_reference(&self) -> bool {
-        match self {
-            TypeId::Array(_) => true,
-            TypeId::Class(_) => true,
-            _ => false,
-        }
+        matches!(self, TypeId::String | TypeId::Array(_) | TypeId::Array2D(_) | TypeId::Class(_))
     }
 
     pub fn is_array(&self) -> bool {
-        match self {
-            TypeId::Array(_) => true,
-            _ => false,
-        }
+        matches!(self, TypeId::Array(_))
+    }
+
+    pub fn is_array2d(&self) -> bool {
+        matches!(self, TypeId::Array2D(_))
+    }
+
+    pub fn is_string(&self) -> bool {
+        matches!(self, TypeId::String)
     }
 
     pub fn is_class(&self) -> bool {
-        match self {
-            TypeId::Class(_) => true,
-            _ => false,
-        }
+        matches!(self, TypeId::Class(_))
+    }
+
+    pub fn is_struct(&self) -> bool {
+        matches!(self, TypeId::Struct(_))
+    }
+
+    pub fn is_function(&self) -> bool {
+        matches!(self, TypeId::Function(_, _))
     }
 
     pub fn is_float(&self) -> bool {
-        match self {
-            TypeId::Float32 => true,
-            _ => false,
-        }
+        matches!(self, TypeId::Float32 | TypeId::Float64)
     }
 
     pub fn is_same_type(&self, other: &TypeId) -> bool {
@@ -86,30 +101,91 @@ impl TypeId {
 
         let void_chars = TypeId::Void.to_string().chars().collect::<Vec<_>>();
         let int_chars = TypeId::Int32.to_string().chars().collect::<Vec<_>>();
+        let int64_chars = TypeId::Int64.to_string().chars().collect::<Vec<_>>();
         let float_chars = TypeId::Float32.to_string().chars().collect::<Vec<_>>();
+        let float64_chars = TypeId::Float64.to_string().chars().collect::<Vec<_>>();
         let bool_chars = TypeId::Bool.to_string().chars().collect::<Vec<_>>();
+        let string_chars = TypeId::String.to_string().chars().collect::<Vec<_>>();
 
         let ref_array_chars = "Ref.Array[".chars().collect::<Vec<_>>();
+        let ref_array2d_chars = "Ref.Array2D[".chars().collect::<Vec<_>>();
         let ref_chars = "Ref.".chars().collect::<Vec<_>>();
+        let struct_chars = "Struct.".chars().collect::<Vec<_>>();
+        let function_chars = "Func[".chars().collect::<Vec<_>>();
 
         if text.starts_with(&void_chars[..]) {
             Some(TypeId::Void)
+        } else if text.starts_with(&int64_chars[..]) {
+            Some(TypeId::Int64)
+        } else if text.starts_with(&float64_chars[..]) {
+            Some(TypeId::Float64)
         } else if text.starts_with(&int_chars[..]) {
             Some(TypeId::Int32)
         } else if text.starts_with(&float_chars[..]) {
             Some(TypeId::Float32)
         } else if text.starts_with(&bool_chars[..]) {
             Some(TypeId::Bool)
+        } else if text.starts_with(&string_chars[..]) && !text[string_chars.len()..].iter().any(|c| c.is_alphanumeric() || c == &'_' || c == &'.') {
+            Some(TypeId::String)
         } else if text.starts_with(&ref_array_chars[..]) {
             let element_type = TypeId::parse_type(&text[ref_array_chars.len()..])?;
             Some(TypeId::Array(Box::new(element_type)))
+        } else if text.starts_with(&ref_array2d_chars[..]) {
+            let element_type = TypeId::parse_type(&text[ref_array2d_chars.len()..])?;
+            Some(TypeId::Array2D(Box::new(element_type)))
+        } else if text.starts_with(&function_chars[..]) {
+            let (parameters, parameters_end) = TypeId::split_parameter_types(&text[function_chars.len()..])?;
+            let parameters = parameters.into_iter().map(TypeId::parse_type).collect::<Option<Vec<_>>>()?;
+
+            let return_type_text = &text[(function_chars.len() + parameters_end + 1)..];
+            if return_type_text.first() != Some(&'[') {
+                return None;
+            }
+
+            let return_type = TypeId::parse_type(&return_type_text[1..])?;
+            Some(TypeId::Function(parameters, Box::new(return_type)))
         } else if text.starts_with(&ref_chars[..]) {
             let end = text.iter().position(|c| c == &']').unwrap_or(text.len());
             Some(TypeId::Class(String::from_iter(&text[ref_chars.len()..end])))
+        } else if text.starts_with(&struct_chars[..]) {
+            let end = text.iter().position(|c| c == &']').unwrap_or(text.len());
+            Some(TypeId::Struct(String::from_iter(&text[struct_chars.len()..end])))
         } else {
             None
         }
     }
+
+    // Splits the comma separated types up to the closing bracket, which is also returned
+    fn split_parameter_types(text: &[char]) -> Option<(Vec<&[char]>, usize)> {
+        let mut parameters = Vec::new();
+        let mut parameter_start = 0;
+        let mut depth = 0;
+
+        for (index, current) in text.iter().enumerate() {
+            match current {
+                '[' => {
+                    depth += 1;
+                }
+                ']' if depth > 0 => {
+                    depth -= 1;
+                }
+                ']' => {
+                    if index > parameter_start {
+                        parameters.push(&text[parameter_start..index]);
+                    }
+
+                    return Some((parameters, index));
+                }
+                ',' if depth == 0 => {
+                    parameters.push(&text[parameter_start..index]);
+                    parameter_start = index + 1;
+                }
+                _ => {}
+            }
+        }
+
+        None
+    }
 }
 
 impl std::fmt::Display for TypeId {
@@ -121,18 +197,37 @@ impl std::fmt::Display for TypeId {
             TypeId::Int32 => {
                 write!(f, "Int")
             }
+            TypeId::Int64 => {
+                write!(f, "Int64")
+            }
             TypeId::Float32 => {
                 write!(f, "Float")
             }
+            TypeId::Float64 => {
+                write!(f, "Float64")
+            }
             TypeId::Bool => {
                 write!(f, "Bool")
             }
+            TypeId::String => {
+                write!(f, "Ref.String")
+            }
             TypeId::Array(element) => {
                 write!(f, "Ref.Array[{}]", element)
             }
+            TypeId::Array2D(element) => {
+                write!(f, "Ref.Array2D[{}]", element)
+            }
             TypeId::Class(name) => {
                 write!(f, "Ref.{}", name)
             }
+            TypeId::Struct(name) => {
+                write!(f, "Struct.{}", name)
+            }
+            TypeId::Function(parameters, return_type) => {
+                let parameters = parameters.iter().map(|parameter| parameter.to_string()).collect::<Vec<_>>();
+                write!(f, "Func[{}][{}]", parameters.join(","), return_type)
+            }
         }
     }
 }
@@ -143,12 +238,17 @@ fn test_parse1() {
     assert_eq!(Some(TypeId::Float32), TypeId::from_str("Float"));
     assert_eq!(Some(TypeId::Void), TypeId::from_str("Void"));
     assert_eq!(Some(TypeId::Bool), TypeId::from_str("Bool"));
+    assert_eq!(Some(TypeId::String), TypeId::from_str("Ref.String"));
+    assert_eq!(Some(TypeId::Class("StringBuilder".to_owned())), TypeId::from_str("Ref.StringBuilder"));
+    assert_eq!(Some(TypeId::Int64), TypeId::from_str("Int64"));
+    assert_eq!(Some(TypeId::Float64), TypeId::from_str("Float64"));
 }
 
 #[test]
 fn test_parse2() {
     assert_eq!(Some(TypeId::Array(Box::new(TypeId::Int32))), TypeId::from_str("Ref.Array[Int]"));
     assert_eq!(Some(TypeId::Array(Box::new(TypeId::Array(Box::new(TypeId::Int32))))), TypeId::from_str("Ref.Array[Ref.Array[Int]]"));
+    assert_eq!(Some(TypeId::Array(Box::new(TypeId::String))), TypeId::from_str("Ref.Array[Ref.String]"));
 }
 
 #[test]
@@ -158,9 +258,199 @@ fn test_parse3() {
     assert_eq!(Some(TypeId::Array(Box::new(TypeId::Array(Box::new(TypeId::Class("Point".to_owned())))))), TypeId::from_str("Ref.Array[Ref.Array[Ref.Point]]"));
 }
 
+#[test]
+fn test_parse4() {
+    assert_eq!(Some(TypeId::Struct("Point".to_owned())), TypeId::from_str("Struct.Point"));
+    assert_eq!(Some(TypeId::Array(Box::new(TypeId::Struct("Point".to_owned())))), TypeId::from_str("Ref.Array[Struct.Point]"));
+    assert_eq!("Ref.Array[Struct.Point]", TypeId::Array(Box::new(TypeId::Struct("Point".to_owned()))).to_string());
+}
+
+#[test]
+fn test_parse5() {
+    assert_eq!(Some(TypeId::Array2D(Box::new(TypeId::Int32))), TypeId::from_str("Ref.Array2D[Int]"));
+    assert_eq!(Some(TypeId::Array2D(Box::new(TypeId::Array(Box::new(TypeId::Float32))))), TypeId::from_str("Ref.Array2D[Ref.Array[Float]]"));
+    assert_eq!(Some(TypeId::Array(Box::new(TypeId::Array2D(Box::new(TypeId::Int32))))), TypeId::from_str("Ref.Array[Ref.Array2D[Int]]"));
+    assert_eq!("Ref.Array2D[Ref.String]", TypeId::Array2D(Box::new(TypeId::String)).to_string());
+}
+
+#[test]
+fn test_parse6() {
+    assert_eq!(
+        Some(TypeId::Function(vec![TypeId::Int32, TypeId::Float32], Box::new(TypeId::Int32))),
+        TypeId::from_str("Func[Int,Float][Int]")
+    );
+    assert_eq!(Some(TypeId::Function(vec![], Box::new(TypeId::Void))), TypeId::from_str("Func[][Void]"));
+
+    let nested = TypeId::Function(
+        vec![TypeId::Array(Box::new(TypeId::Class("Point".to_owned()))), TypeId::Function(vec![TypeId::Int32], Box::new(TypeId::Bool))],
+        Box::new(TypeId::String)
+    );
+    assert_eq!("Func[Ref.Array[Ref.Point],Func[Int][Bool]][Ref.String]", nested.to_string());
+    assert_eq!(Some(nested.clone()), TypeId::from_str(&nested.to_string()));
+    assert_eq!(Some(TypeId::Array(Box::new(nested.clone()))), TypeId::from_str(&format!("Ref.Array[{}]", nested)));
+
+    assert_eq!(None, TypeId::from_str("Func[Int"));
+    assert_eq!(None, TypeId::from_str("Func[Int]"));
+}
+
+/// The table of an interface implemented by a type, which holds the addresses of the implementing functions.
+#[repr(C)]
+pub struct InterfaceTable {
+    interface_type: *const Type,
+    functions: *const FunctionAddress
+}
+
+pub const VIRTUAL_TABLE_OFFSET: i32 = 0;
+pub const SUPERTYPES_OFFSET: i32 = 8;
+pub const INTERFACE_TABLES_OFFSET: i32 = 16;
+pub const INTERFACE_TABLE_SIZE: i32 = 16;
+pub const INTERFACE_TABLE_FUNCTIONS_OFFSET: i32 = 8;
+
+/// The runtime metadata of a type. The generated code reads the pointers to the tables, which must be the first fields.
+#[repr(C)]
 pub struct Type {
+    virtual_table_ptr: *const FunctionAddress,
+    supertypes_ptr: *const *const Type,
+    interface_tables_ptr: *const InterfaceTable,
     pub id: TypeId,
-    pub class: Option<Class>
+    pub class: Option<Class>,
+    base_type: *const Type,
+    supertypes: Vec<*const Type>,
+    virtual_functions: Option<Vec<FunctionSignature>>,
+    virtual_table: Vec<FunctionAddress>,
+    interface_functions: Vec<(TypeId, Vec<FunctionSignature>)>,
+    interface_addresses: Vec<Vec<FunctionAddress>>,
+    interface_tables: Vec<InterfaceTable>,
+    element_size: usize,
+    element_references: Vec<usize>
+}
+
+impl Type {
+    pub fn new(id: TypeId, class: Option<Class>, base_type: Option<&Type>) -> Type {
+        let supertypes = vec![std::ptr::null()];
+        Type {
+            virtual_table_ptr: std::ptr::null(),
+            supertypes_ptr: supertypes.as_ptr(),
+            interface_tables_ptr: std::ptr::null(),
+            id,
+            class,
+            base_type: base_type.map(|base_type| base_type as *const Type).unwrap_or(std::ptr::null()),
+            supertypes,
+            virtual_functions: None,
+            virtual_table: Vec::new(),
+            interface_functions: Vec::new(),
+            interface_addresses: Vec::new(),
+            interface_tables: Vec::new(),
+            element_size: 0,
+            element_references: Vec::new()
+        }
+    }
+
+    pub fn base_type(&self) -> Option<&Type> {
+        unsafe { self.base_type.as_ref() }
+    }
+
+    pub fn is_interface(&self) -> bool {
+        self.class.as_ref().map(|class| class.is_interface()).unwrap_or(false)
+    }
+
+    /// Returns the types that this type can be used as, which are the type itself, its base types and the implemented interfaces.
+    pub fn supertypes(&self) -> impl Iterator<Item=&Type> {
+        self.supertypes
+            .iter()
+            .take_while(|supertype| !supertype.is_null())
+            .map(|supertype| unsafe { &**supertype })
+    }
+
+    /// Sets the supertypes, which the generated code searches through as a null terminated array.
+    pub fn set_supertypes(&mut self, mut supertypes: Vec<*const Type>) {
+        supertypes.push(std::ptr::null());
+        self.supertypes = supertypes;
+        self.supertypes_ptr = self.supertypes.as_ptr();
+    }
+
+    /// Indicates if this type is the given type, derived from it or implements it.
+    pub fn is_subtype_of(&self, type_id: &TypeId) -> bool {
+        self.id.is_same_type(type_id) || self.supertypes().any(|supertype| supertype.id.is_same_type(type_id))
+    }
+
+    pub fn virtual_functions(&self) -> Option<&Vec<FunctionSignature>> {
+        self.virtual_functions.as_ref()
+    }
+
+    /// Returns the slot in the virtual table of the function with the same name and parameters as the given function.
+    pub fn virtual_function_index(&self, signature: &FunctionSignature) -> Option<usize> {
+        self.virtual_functions.as_ref()?
+            .iter()
+            .position(|function| function.name == signature.name && function.parameters == signature.parameters)
+    }
+
+    /// Sets the layout of the virtual table. The table is allocated once, such that the pointer to it stays valid.
+    pub fn set_virtual_functions(&mut self, functions: Vec<FunctionSignature>) {
+        self.virtual_table = vec![std::ptr::null_mut(); functions.len()];
+        self.virtual_table_ptr = self.virtual_table.as_ptr();
+        self.virtual_functions = Some(functions);
+    }
+
+    pub fn set_virtual_function_address(&mut self, index: usize, address: FunctionAddress) {
+        self.virtual_table[index] = address;
+    }
+
+    #[allow(dead_code)]
+    pub fn virtual_table(&self) -> &Vec<FunctionAddress> {
+        &self.virtual_table
+    }
+
+    /// Returns the implementing functions of each interface, in the order of the slots of the interface.
+    pub fn interface_functions(&self) -> &Vec<(TypeId, Vec<FunctionSignature>)> {
+        &self.interface_functions
+    }
+
+    /// Sets the layout of the interface tables, where the interfaces must be supertypes of this type.
+    pub fn set_interface_functions(&mut self, interface_functions: Vec<(TypeId, Vec<FunctionSignature>)>) {
+        self.interface_addresses = interface_functions
+            .iter()
+            .map(|(_, functions)| vec![std::ptr::null_mut(); functions.len()])
+            .collect();
+
+        let interface_types = interface_functions
+            .iter()
+            .map(|(interface, _)| {
+                self.supertypes()
+                    .find(|supertype| &supertype.id == interface)
+                    .map(|supertype| supertype as *const Type)
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        self.interface_tables = interface_types
+            .into_iter()
+            .zip(self.interface_addresses.iter())
+            .map(|(interface_type, addresses)| InterfaceTable { interface_type, functions: addresses.as_ptr() })
+            .collect();
+
+        self.interface_tables_ptr = self.interface_tables.as_ptr();
+        self.interface_functions = interface_functions;
+    }
+
+    pub fn set_interface_function_address(&mut self, interface_index: usize, index: usize, address: FunctionAddress) {
+        self.interface_addresses[interface_index][index] = address;
+    }
+
+    #[allow(dead_code)]
+    pub fn interface_table(&self, interface_index: usize) -> &Vec<FunctionAddress> {
+        &self.interface_addresses[interface_index]
+    }
+
+    /// The size of the elements of an array type.
+    pub fn element_size(&self) -> usize {
+        self.element_size
+    }
+
+    /// The offsets of the references within each element of an array type.
+    pub fn element_references(&self) -> &Vec<usize> {
+        &self.element_references
+    }
 }
 
 pub struct TypeStorage {
@@ -174,33 +464,189 @@ impl TypeStorage {
         }
     }
 
-    pub fn add_class(&mut self, class: Class) {
-        let type_id = TypeId::Class(class.name().to_owned());
+    /// Adds the given class, where the base class and the implemented interfaces must already have been added.
+    pub fn add_class(&mut self, class: Class) -> VerifyResult<()> {
+        let type_id = if class.is_struct() {
+            TypeId::Struct(class.name().to_owned())
+        } else {
+            TypeId::Class(class.name().to_owned())
+        };
+
+        if self.types.contains_key(&type_id) {
+            return Ok(());
+        }
+
+        let base_type = match class.base() {
+            Some(base) => Some(self.class_type_pointer(base)?),
+            None => None
+        };
+
+        let mut direct_supertypes = base_type.into_iter().collect::<Vec<_>>();
+        for interface in class.interfaces() {
+            direct_supertypes.push(self.class_type_pointer(interface)?);
+        }
+
+        let mut supertypes = Vec::new();
 
-        self.types.entry(type_id.clone()).or_insert_with(|| {
-            Box::new(
-                Type {
-                    id: type_id,
-                    class: Some(class)
+        for direct_supertype in direct_supertypes {
+            for supertype in unsafe { &*direct_supertype }.supertypes() {
+                let supertype = supertype as *const Type;
+                if !supertypes.contains(&supertype) {
+                    supertypes.push(supertype);
                 }
-            )
-        });
+            }
+        }
+
+        let mut class_type = Box::new(Type::new(type_id.clone(), Some(class), base_type.map(|base_type| unsafe { &*base_type })));
+        supertypes.insert(0, class_type.as_ref() as *const Type);
+        class_type.set_supertypes(supertypes);
+        self.types.insert(type_id, class_type);
+        Ok(())
+    }
+
+    fn class_type_pointer(&self, name: &str) -> VerifyResult<*const Type> {
+        self.types.get(&TypeId::Class(name.to_owned()))
+            .map(|class_type| class_type.as_ref() as *const Type)
+            .ok_or_else(|| VerifyError::new(VerifyErrorMessage::ClassTypeNotDefined(name.to_owned())))
     }
 
     pub fn get(&self, type_id: &TypeId) -> Option<&Type> {
         self.types.get(type_id).map(|t| t.as_ref())
     }
 
+    pub fn get_mut(&mut self, type_id: &TypeId) -> Option<&mut Type> {
+        self.types.get_mut(type_id).map(|t| t.as_mut())
+    }
+
+    /// Returns the class or struct type with the given name.
+    pub fn get_by_name(&self, name: &str) -> Option<&Type> {
+        self.get(&TypeId::Class(name.to_owned())).or_else(|| self.get(&TypeId::Struct(name.to_owned())))
+    }
+
     pub fn entry(&mut self, type_id: TypeId) -> &Type {
-        self.types.entry(type_id.clone()).or_insert_with(|| {
-            assert!(!type_id.is_class());
+        if !self.types.contains_key(&type_id) {
+            assert!(!type_id.is_class() && !type_id.is_struct());
+            let mut new_type = Box::new(Type::new(type_id.clone(), None, None));
+            if let Some(element) = type_id.element_type() {
+                new_type.element_size = self.size_of(element);
+                new_type.element_references = self.reference_offsets(element);
+            }
 
-            Box::new(
-                Type {
-                    id: type_id,
-                    class: None
-                }
-            )
-        })
+            self.types.insert(type_id.clone(), new_type);
+        }
+
+        &self.types[&type_id]
+    }
+
+    /// Returns the size of a value of the given type, where structs are stored inline.
+    pub fn size_of(&self, type_id: &TypeId) -> usize {
+        match type_id {
+            TypeId::Struct(_) => self.get(type_id).unwrap().class.as_ref().unwrap().memory_size(),
+            _ => type_id.size()
+        }
+    }
+
+    /// Returns the offsets of the references within a value of the given type.
+    pub fn reference_offsets(&self, type_id: &TypeId) -> Vec<usize> {
+        match type_id {
+            TypeId::Struct(_) => self.get(type_id).unwrap().class.as_ref().unwrap().reference_offsets().clone(),
+            _ if type_id.is_reference() => vec![0],
+            _ => Vec::new()
+        }
+    }
+
+    pub fn class_types(&self) -> Vec<TypeId> {
+        self.types.values()
+            .filter(|current_type| current_type.id.is_class())
+            .map(|current_type| current_type.id.clone())
+            .collect()
+    }
+
+    /// Indicates if a value of the actual type can be used where the expected type is expected, which holds for derived classes and implemented interfaces.
+    pub fn is_assignable(&self, expected: &TypeId, actual: &TypeId) -> bool {
+        if expected.is_same_type(actual) {
+            return true;
+        }
+
+        match (expected, actual) {
+            (TypeId::Class(_), TypeId::Class(_)) => {
+                self.get(actual).map(|actual_type| actual_type.is_subtype_of(expected)).unwrap_or(false)
+            }
+            _ => false
+        }
     }
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_assignable1() {
+    use crate::model::class::Field;
+
+    let mut type_storage = TypeStorage::new();
+    let base = Class::new("Shape".to_owned(), vec![Field::new("x".to_owned(), TypeId::Int32)]);
+    let derived = Class::with_base("Circle".to_owned(), &base, Vec::new());
+    type_storage.add_class(base).unwrap();
+    type_storage.add_class(derived).unwrap();
+    type_storage.add_class(Class::new("Point".to_owned(), Vec::new())).unwrap();
+
+    let shape = TypeId::Class("Shape".to_owned());
+    let circle = TypeId::Class("Circle".to_owned());
+    let point = TypeId::Class("Point".to_owned());
+
+    assert!(type_storage.is_assignable(&shape, &shape));
+    assert!(type_storage.is_assignable(&shape, &circle));
+    assert!(!type_storage.is_assignable(&circle, &shape));
+    assert!(!type_storage.is_assignable(&shape, &point));
+    assert!(!type_storage.is_assignable(&TypeId::Array(Box::new(shape.clone())), &TypeId::Array(Box::new(circle.clone()))));
+}
+
+#[test]
+fn test_interfaces1() {
+    let mut type_storage = TypeStorage::new();
+    let shape = Class::new_interface("Shape".to_owned(), Vec::new());
+    let named = Class::new_interface("Named".to_owned(), Vec::new());
+    let mut base = Class::new("Polygon".to_owned(), Vec::new());
+    base.add_interface(&shape);
+    let mut derived = Class::with_base("Square".to_owned(), &base, Vec::new());
+    derived.add_interface(&named);
+    derived.add_interface(&shape);
+    type_storage.add_class(shape).unwrap();
+    type_storage.add_class(named).unwrap();
+    type_storage.add_class(base).unwrap();
+    type_storage.add_class(derived).unwrap();
+
+    let shape = TypeId::Class("Shape".to_owned());
+    let named = TypeId::Class("Named".to_owned());
+    let polygon = TypeId::Class("Polygon".to_owned());
+    let square = TypeId::Class("Square".to_owned());
+
+    let supertypes = type_storage.get(&square).unwrap().supertypes().map(|supertype| supertype.id.clone()).collect::<Vec<_>>();
+    assert_eq!(vec![square.clone(), polygon.clone(), shape.clone(), named.clone()], supertypes);
+
+    assert!(type_storage.is_assignable(&shape, &polygon));
+    assert!(type_storage.is_assignable(&shape, &square));
+    assert!(type_storage.is_assignable(&named, &square));
+    assert!(!type_storage.is_assignable(&named, &polygon));
+    assert!(!type_storage.is_assignable(&polygon, &shape));
+}
+
+#[test]
+fn test_undefined_supertype1() {
+    let mut type_storage = TypeStorage::new();
+    let shape = Class::new_interface("Shape".to_owned(), Vec::new());
+    let base = Class::new("Polygon".to_owned(), Vec::new());
+    let derived = Class::with_base("Square".to_owned(), &base, Vec::new());
+    let mut circle = Class::new("Circle".to_owned(), Vec::new());
+    circle.add_interface(&shape);
+
+    assert_eq!(
+        Err(VerifyError::new(VerifyErrorMessage::ClassTypeNotDefined("Polygon".to_owned()))),
+        type_storage.add_class(derived)
+    );
+    assert_eq!(
+        Err(VerifyError::new(VerifyErrorMessage::ClassTypeNotDefined("Shape".to_owned()))),
+        type_storage.add_class(circle)
+    );
+
+    assert!(type_storage.get(&TypeId::Class("Square".to_owned())).is_none());
+    assert!(type_storage.get(&TypeId::Class("Circle".to_owned())).is_none());
+}
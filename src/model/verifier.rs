@@ -1,27 +1,33 @@
-use crate::model::function::{Function, FunctionSignature, FunctionDeclaration};
+use crate::model::function::{Function, FunctionSignature, FunctionType};
 use crate::model::typesystem::{TypeId, TypeStorage};
 use crate::model::instruction::Instruction;
 use crate::model::binder::Binder;
-use crate::model::class::{Class, Field};
+use crate::model::class::Field;
+use crate::model::exception::{ProtectedRegion, CatchHandler, FinallyHandler};
+
+#[cfg(test)]
+use crate::model::function::FunctionDeclaration;
+#[cfg(test)]
+use crate::model::class::Class;
 
 #[derive(Debug, PartialEq, Eq)]
 pub struct VerifyError {
     pub index: Option<usize>,
-    pub message: VerifyErrorMessage
+    pub message: Box<VerifyErrorMessage>
 }
 
 impl VerifyError {
     pub fn new(message: VerifyErrorMessage) -> VerifyError {
         VerifyError {
             index: None,
-            message
+            message: Box::new(message)
         }
     }
 
     pub fn with_index(index: usize, message: VerifyErrorMessage) -> VerifyError {
         VerifyError {
             index: Some(index),
-            message
+            message: Box::new(message)
         }
     }
 }
@@ -45,6 +51,20 @@ pub enum VerifyErrorMessage {
     ExpectedArrayReference,
     ClassTypeNotDefined(String),
     FieldNotDefined(String, String),
+    ExpectedEmptyOperandStack,
+    ExpectedClassReference,
+    InvalidProtectedRegion,
+    BranchCrossesProtectedRegion,
+    ReturnInsideFinallyRegion,
+    ExpectedOverride(FunctionSignature),
+    NoVirtualFunctionToOverride(FunctionSignature),
+    InterfaceFunctionNotImplemented(TypeId, FunctionSignature),
+    CannotCreateInterface(String),
+    VirtualConstructor,
+    InvalidStructUsage(TypeId),
+    InvalidArrayDimension(u32),
+    InvalidFunctionReference(FunctionSignature),
+    ExpectedFunctionReference,
 }
 
 pub type VerifyResult<T> = Result<T, VerifyError>;
@@ -54,7 +74,30 @@ pub struct Verifier<'a> {
     binder: &'a Binder,
     type_storage: &'a TypeStorage,
     operand_stack: Vec<TypeId>,
-    branches: Vec<(usize, usize, Vec<TypeId>)>
+    branches: Vec<(usize, usize, Vec<TypeId>)>,
+    returns: Vec<usize>,
+    open_regions: Vec<RegionBuilder>,
+    protected_regions: Vec<ProtectedRegion>,
+    struct_slot_size: usize
+}
+
+struct RegionBuilder {
+    region: ProtectedRegion,
+    try_closed: bool,
+    open_handler: Option<OpenHandler>
+}
+
+enum OpenHandler {
+    Catch(TypeId, usize),
+    Finally(usize)
+}
+
+#[derive(PartialEq, Eq)]
+enum Block {
+    Function,
+    Try(usize),
+    Catch(usize, usize),
+    Finally(usize)
 }
 
 impl<'a> Verifier<'a> {
@@ -66,7 +109,11 @@ impl<'a> Verifier<'a> {
             binder,
             type_storage,
             operand_stack: Vec::new(),
-            branches: Vec::new()
+            branches: Vec::new(),
+            returns: Vec::new(),
+            open_regions: Vec::new(),
+            protected_regions: Vec::new(),
+            struct_slot_size: 0
         }
     }
 
@@ -75,16 +122,33 @@ impl<'a> Verifier<'a> {
             if parameter == &TypeId::Void {
                 return Err(VerifyError::new(VerifyErrorMessage::ParameterCannotBeVoid));
             }
+
+            if parameter.is_struct() {
+                return Err(VerifyError::new(VerifyErrorMessage::InvalidStructUsage(parameter.clone())));
+            }
+        }
+
+        // Structs are only passed by value inside a function
+        let return_type = self.function.declaration().return_type();
+        if return_type.is_struct() {
+            return Err(VerifyError::new(VerifyErrorMessage::InvalidStructUsage(return_type.clone())));
+        }
+
+        if self.function.declaration().is_constructor() {
+            self.verify_constructor()?;
         }
 
         self.verify_instructions()?;
         self.verify_locals()?;
         self.verify_branches()?;
+        self.verify_protected_regions()?;
 
         if !self.operand_stack.is_empty() {
             return Err(VerifyError::new(VerifyErrorMessage::NonEmptyOperandStackOnReturn));
         }
 
+        self.function.set_struct_slot_size(self.struct_slot_size);
+
         Ok(())
     }
 
@@ -96,13 +160,24 @@ impl<'a> Verifier<'a> {
             *self.function.instruction_operand_types_mut(instruction_index) = self.operand_stack.clone();
             max_stack_size = max_stack_size.max(self.operand_stack.len());
 
+            match instruction {
+                Instruction::BeginCatch(_) | Instruction::BeginFinally => {}
+                _ => { self.try_close_region(instruction_index)?; }
+            }
+
             match instruction {
                 Instruction::LoadInt32(_) => {
                     self.push_operand_stack(TypeId::Int32);
                 }
+                Instruction::LoadInt64(_) => {
+                    self.push_operand_stack(TypeId::Int64);
+                }
                 Instruction::LoadFloat32(_) => {
                     self.push_operand_stack(TypeId::Float32);
                 }
+                Instruction::LoadFloat64(_) => {
+                    self.push_operand_stack(TypeId::Float64);
+                }
                 Instruction::LoadTrue => {
                     self.push_operand_stack(TypeId::Bool);
                 }
@@ -110,8 +185,15 @@ impl<'a> Verifier<'a> {
                     self.push_operand_stack(TypeId::Bool);
                 }
                 Instruction::LoadNull(null_type) => {
+                    if null_type.is_struct() {
+                        return Err(VerifyError::with_index(instruction_index, VerifyErrorMessage::InvalidStructUsage(null_type.clone())));
+                    }
+
                     self.push_operand_stack(null_type.clone());
                 }
+                Instruction::LoadString(_) => {
+                    self.push_operand_stack(TypeId::String);
+                }
                 Instruction::LoadLocal(index) => {
                     let local_type = self.function.locals().get(*index as usize)
                         .ok_or(VerifyError::with_index(instruction_index, VerifyErrorMessage::LocalIndexOutOfRange))?
@@ -125,7 +207,21 @@ impl<'a> Verifier<'a> {
                         .ok_or(VerifyError::with_index(instruction_index, VerifyErrorMessage::LocalIndexOutOfRange))?
                         .clone();
 
-                    self.same_type(instruction_index, &local_type, &operand)?;
+                    self.assignable_type(instruction_index, &local_type, &operand)?;
+                }
+                Instruction::Duplicate => {
+                    let operand = self.pop_operand_stack(instruction_index)?;
+                    self.push_operand_stack(operand.clone());
+                    self.push_operand_stack(operand);
+                }
+                Instruction::Pop => {
+                    self.pop_operand_stack(instruction_index)?;
+                }
+                Instruction::Swap => {
+                    let op2 = self.pop_operand_stack(instruction_index)?;
+                    let op1 = self.pop_operand_stack(instruction_index)?;
+                    self.push_operand_stack(op2);
+                    self.push_operand_stack(op1);
                 }
                 Instruction::Add | Instruction::Sub | Instruction::Multiply | Instruction::Divide => {
                     let op2 = self.pop_operand_stack(instruction_index)?;
@@ -134,9 +230,62 @@ impl<'a> Verifier<'a> {
                         (TypeId::Int32, TypeId::Int32) => {
                             self.push_operand_stack(op1);
                         }
+                        (TypeId::Int64, TypeId::Int64) => {
+                            self.push_operand_stack(op1);
+                        }
                         (TypeId::Float32, TypeId::Float32) => {
                             self.push_operand_stack(op1);
                         }
+                        (TypeId::Float64, TypeId::Float64) => {
+                            self.push_operand_stack(op1);
+                        }
+                        _ => {
+                            return Err(VerifyError::with_index(instruction_index, VerifyErrorMessage::WrongArithmeticOperands));
+                        }
+                    }
+                }
+                Instruction::Remainder => {
+                    let op2 = self.pop_operand_stack(instruction_index)?;
+                    let op1 = self.pop_operand_stack(instruction_index)?;
+                    match (&op1, &op2) {
+                        (TypeId::Int32, TypeId::Int32) => {
+                            self.push_operand_stack(op1);
+                        }
+                        (TypeId::Int64, TypeId::Int64) => {
+                            self.push_operand_stack(op1);
+                        }
+                        _ => {
+                            return Err(VerifyError::with_index(instruction_index, VerifyErrorMessage::WrongArithmeticOperands));
+                        }
+                    }
+                }
+                Instruction::ShiftLeft | Instruction::ShiftRight => {
+                    let op2 = self.pop_operand_stack(instruction_index)?;
+                    let op1 = self.pop_operand_stack(instruction_index)?;
+
+                    // The shift amount is always an Int32
+                    match (&op1, &op2) {
+                        (TypeId::Int32, TypeId::Int32) => {
+                            self.push_operand_stack(op1);
+                        }
+                        (TypeId::Int64, TypeId::Int32) => {
+                            self.push_operand_stack(op1);
+                        }
+                        _ => {
+                            return Err(VerifyError::with_index(instruction_index, VerifyErrorMessage::WrongArithmeticOperands));
+                        }
+                    }
+                }
+                Instruction::Negate => {
+                    let op = self.pop_operand_stack(instruction_index)?;
+
+                    match op {
+                        TypeId::Int32 => {
+                            self.push_operand_stack(op);
+                        }
+                        TypeId::Int64 => {
+                            self.push_operand_stack(op);
+                        }
                         _ => {
                             return Err(VerifyError::with_index(instruction_index, VerifyErrorMessage::WrongArithmeticOperands));
                         }
@@ -149,6 +298,27 @@ impl<'a> Verifier<'a> {
                         (TypeId::Bool, TypeId::Bool) => {
                             self.push_operand_stack(op1);
                         }
+                        (TypeId::Int32, TypeId::Int32) => {
+                            self.push_operand_stack(op1);
+                        }
+                        (TypeId::Int64, TypeId::Int64) => {
+                            self.push_operand_stack(op1);
+                        }
+                        _ => {
+                            return Err(VerifyError::with_index(instruction_index, VerifyErrorMessage::WrongLogicalOperands));
+                        }
+                    }
+                }
+                Instruction::Xor => {
+                    let op2 = self.pop_operand_stack(instruction_index)?;
+                    let op1 = self.pop_operand_stack(instruction_index)?;
+                    match (&op1, &op2) {
+                        (TypeId::Int32, TypeId::Int32) => {
+                            self.push_operand_stack(op1);
+                        }
+                        (TypeId::Int64, TypeId::Int64) => {
+                            self.push_operand_stack(op1);
+                        }
                         _ => {
                             return Err(VerifyError::with_index(instruction_index, VerifyErrorMessage::WrongLogicalOperands));
                         }
@@ -161,11 +331,62 @@ impl<'a> Verifier<'a> {
                         TypeId::Bool => {
                             self.push_operand_stack(op);
                         }
+                        TypeId::Int32 => {
+                            self.push_operand_stack(op);
+                        }
+                        TypeId::Int64 => {
+                            self.push_operand_stack(op);
+                        }
                         _ => {
                             return Err(VerifyError::with_index(instruction_index, VerifyErrorMessage::WrongLogicalOperands));
                         }
                     }
                 }
+                Instruction::ConvertInt32ToFloat32 => {
+                    let op = self.pop_operand_stack(instruction_index)?;
+                    self.assignable_type(instruction_index, &TypeId::Int32, &op)?;
+                    self.push_operand_stack(TypeId::Float32);
+                }
+                Instruction::ConvertFloat32ToInt32 => {
+                    let op = self.pop_operand_stack(instruction_index)?;
+                    self.assignable_type(instruction_index, &TypeId::Float32, &op)?;
+                    self.push_operand_stack(TypeId::Int32);
+                }
+                Instruction::ConvertBoolToInt32 => {
+                    let op = self.pop_operand_stack(instruction_index)?;
+                    self.assignable_type(instruction_index, &TypeId::Bool, &op)?;
+                    self.push_operand_stack(TypeId::Int32);
+                }
+                Instruction::ConvertInt32ToInt64 => {
+                    let op = self.pop_operand_stack(instruction_index)?;
+                    self.assignable_type(instruction_index, &TypeId::Int32, &op)?;
+                    self.push_operand_stack(TypeId::Int64);
+                }
+                Instruction::ConvertInt64ToInt32 => {
+                    let op = self.pop_operand_stack(instruction_index)?;
+                    self.assignable_type(instruction_index, &TypeId::Int64, &op)?;
+                    self.push_operand_stack(TypeId::Int32);
+                }
+                Instruction::ConvertFloat32ToFloat64 => {
+                    let op = self.pop_operand_stack(instruction_index)?;
+                    self.assignable_type(instruction_index, &TypeId::Float32, &op)?;
+                    self.push_operand_stack(TypeId::Float64);
+                }
+                Instruction::ConvertFloat64ToFloat32 => {
+                    let op = self.pop_operand_stack(instruction_index)?;
+                    self.assignable_type(instruction_index, &TypeId::Float64, &op)?;
+                    self.push_operand_stack(TypeId::Float32);
+                }
+                Instruction::ConvertInt64ToFloat64 => {
+                    let op = self.pop_operand_stack(instruction_index)?;
+                    self.assignable_type(instruction_index, &TypeId::Int64, &op)?;
+                    self.push_operand_stack(TypeId::Float64);
+                }
+                Instruction::ConvertFloat64ToInt64 => {
+                    let op = self.pop_operand_stack(instruction_index)?;
+                    self.assignable_type(instruction_index, &TypeId::Float64, &op)?;
+                    self.push_operand_stack(TypeId::Int64);
+                }
                 Instruction::Call(signature) => {
                     let func_to_call = self.binder.get(signature)
                         .ok_or(VerifyError::with_index(instruction_index, VerifyErrorMessage::FunctionNotDefined(signature.clone())))?;
@@ -179,13 +400,52 @@ impl<'a> Verifier<'a> {
 
                     for parameter in func_to_call.parameters().iter().rev() {
                         let operand = self.pop_operand_stack(instruction_index)?;
-                        self.same_type(instruction_index, parameter, &operand)?;
+                        self.assignable_type(instruction_index, parameter, &operand)?;
                     }
 
                     if func_to_call.return_type() != &TypeId::Void {
                         self.push_operand_stack(func_to_call.return_type().clone());
                     }
                 }
+                Instruction::LoadFunction(signature) => {
+                    let func_to_load = self.binder.get(signature)
+                        .ok_or(VerifyError::with_index(instruction_index, VerifyErrorMessage::FunctionNotDefined(signature.clone())))?;
+
+                    // Member functions require an object, native functions a managed caller and intrinsics have no code to reference
+                    let has_code = match func_to_load.function_type() {
+                        FunctionType::Managed => true,
+                        FunctionType::External => !func_to_load.address().unwrap().is_null(),
+                        FunctionType::Native => false
+                    };
+
+                    if func_to_load.class().is_some() || !has_code {
+                        return Err(VerifyError::with_index(instruction_index, VerifyErrorMessage::InvalidFunctionReference(signature.clone())));
+                    }
+
+                    self.push_operand_stack(func_to_load.reference_type());
+                }
+                Instruction::CallIndirect => {
+                    let (parameters, return_type) = match self.pop_operand_stack(instruction_index)? {
+                        TypeId::Function(parameters, return_type) => (parameters, return_type),
+                        _ => { return Err(VerifyError::with_index(instruction_index, VerifyErrorMessage::ExpectedFunctionReference)); }
+                    };
+
+                    if self.operand_stack.len() < parameters.len() {
+                        return Err(VerifyError::with_index(
+                            instruction_index,
+                            VerifyErrorMessage::ExpectedNumberOfOperands(parameters.len())
+                        ));
+                    }
+
+                    for parameter in parameters.iter().rev() {
+                        let operand = self.pop_operand_stack(instruction_index)?;
+                        self.assignable_type(instruction_index, parameter, &operand)?;
+                    }
+
+                    if *return_type != TypeId::Void {
+                        self.push_operand_stack(*return_type);
+                    }
+                }
                 Instruction::LoadArgument(index) => {
                     let argument_type = self.function.declaration().parameters().get(*index as usize)
                         .ok_or(VerifyError::with_index(instruction_index, VerifyErrorMessage::ArgumentIndexOutOfRange))?
@@ -194,14 +454,20 @@ impl<'a> Verifier<'a> {
                     self.push_operand_stack(argument_type);
                 }
                 Instruction::Return => {
+                    self.returns.push(instruction_index);
+
                     if self.function.declaration().return_type() != &TypeId::Void {
                         let operand = self.pop_operand_stack(instruction_index)?;
-                        self.same_type(instruction_index, self.function.declaration().return_type(), &operand)?;
+                        self.assignable_type(instruction_index, self.function.declaration().return_type(), &operand)?;
                     }
                 }
                 Instruction::NewArray(element) => {
+                    if element.is_struct() && self.type_storage.get(element).is_none() {
+                        return Err(VerifyError::with_index(instruction_index, VerifyErrorMessage::ClassTypeNotDefined(element.class_name().unwrap().to_owned())));
+                    }
+
                     let length = self.pop_operand_stack(instruction_index)?;
-                    self.same_type(instruction_index, &TypeId::Int32, &length)?;
+                    self.assignable_type(instruction_index, &TypeId::Int32, &length)?;
                     self.push_operand_stack(TypeId::Array(Box::new(element.clone())));
                 }
                 Instruction::LoadElement(element) => {
@@ -209,8 +475,8 @@ impl<'a> Verifier<'a> {
                     let array_reference = self.pop_operand_stack(instruction_index)?;
                     let array_reference_type = TypeId::Array(Box::new(element.clone()));
 
-                    self.same_type(instruction_index, &TypeId::Int32, &array_index)?;
-                    self.same_type(instruction_index, &array_reference_type, &array_reference)?;
+                    self.assignable_type(instruction_index, &TypeId::Int32, &array_index)?;
+                    self.assignable_type(instruction_index, &array_reference_type, &array_reference)?;
 
                     self.push_operand_stack(element.clone());
                 }
@@ -220,9 +486,9 @@ impl<'a> Verifier<'a> {
                     let array_reference = self.pop_operand_stack(instruction_index)?;
                     let array_reference_type = TypeId::Array(Box::new(element.clone()));
 
-                    self.same_type(instruction_index, &TypeId::Int32, &array_index)?;
-                    self.same_type(instruction_index, &array_reference_type, &array_reference)?;
-                    self.same_type(instruction_index, &array_value, &element)?;
+                    self.assignable_type(instruction_index, &TypeId::Int32, &array_index)?;
+                    self.assignable_type(instruction_index, &array_reference_type, &array_reference)?;
+                    self.assignable_type(instruction_index, &array_value, element)?;
                 }
                 Instruction::LoadArrayLength => {
                     let array_reference = self.pop_operand_stack(instruction_index)?;
@@ -233,40 +499,140 @@ impl<'a> Verifier<'a> {
 
                     self.push_operand_stack(TypeId::Int32);
                 }
+                Instruction::NewArray2D(element) => {
+                    self.verify_array2d_element(instruction_index, element)?;
+
+                    let columns = self.pop_operand_stack(instruction_index)?;
+                    let rows = self.pop_operand_stack(instruction_index)?;
+                    self.assignable_type(instruction_index, &TypeId::Int32, &columns)?;
+                    self.assignable_type(instruction_index, &TypeId::Int32, &rows)?;
+                    self.push_operand_stack(TypeId::Array2D(Box::new(element.clone())));
+                }
+                Instruction::LoadElement2D(element) => {
+                    self.verify_array2d_element(instruction_index, element)?;
+
+                    let column = self.pop_operand_stack(instruction_index)?;
+                    let row = self.pop_operand_stack(instruction_index)?;
+                    let array_reference = self.pop_operand_stack(instruction_index)?;
+                    let array_reference_type = TypeId::Array2D(Box::new(element.clone()));
+
+                    self.assignable_type(instruction_index, &TypeId::Int32, &column)?;
+                    self.assignable_type(instruction_index, &TypeId::Int32, &row)?;
+                    self.assignable_type(instruction_index, &array_reference_type, &array_reference)?;
+
+                    self.push_operand_stack(element.clone());
+                }
+                Instruction::StoreElement2D(element) => {
+                    self.verify_array2d_element(instruction_index, element)?;
+
+                    let array_value = self.pop_operand_stack(instruction_index)?;
+                    let column = self.pop_operand_stack(instruction_index)?;
+                    let row = self.pop_operand_stack(instruction_index)?;
+                    let array_reference = self.pop_operand_stack(instruction_index)?;
+                    let array_reference_type = TypeId::Array2D(Box::new(element.clone()));
+
+                    self.assignable_type(instruction_index, &TypeId::Int32, &column)?;
+                    self.assignable_type(instruction_index, &TypeId::Int32, &row)?;
+                    self.assignable_type(instruction_index, &array_reference_type, &array_reference)?;
+                    self.assignable_type(instruction_index, element, &array_value)?;
+                }
+                Instruction::LoadArrayDimension(dimension) => {
+                    if *dimension > 1 {
+                        return Err(VerifyError::with_index(instruction_index, VerifyErrorMessage::InvalidArrayDimension(*dimension)));
+                    }
+
+                    let array_reference = self.pop_operand_stack(instruction_index)?;
+                    if !array_reference.is_array2d() {
+                        return Err(VerifyError::with_index(instruction_index, VerifyErrorMessage::ExpectedArrayReference));
+                    }
+
+                    self.push_operand_stack(TypeId::Int32);
+                }
                 Instruction::NewObject(class_name) => {
+                    let object_type = self.type_storage.get_by_name(class_name)
+                        .ok_or_else(|| VerifyError::with_index(instruction_index, VerifyErrorMessage::ClassTypeNotDefined(class_name.clone())))?;
+
+                    if object_type.is_interface() {
+                        return Err(VerifyError::with_index(instruction_index, VerifyErrorMessage::CannotCreateInterface(class_name.clone())));
+                    }
+
+                    self.push_operand_stack(object_type.id.clone());
+                }
+                Instruction::NewObjectWithConstructor(class_name, parameters) => {
+                    let struct_type = TypeId::Struct(class_name.clone());
+                    if self.type_storage.get(&struct_type).is_some() {
+                        return Err(VerifyError::with_index(instruction_index, VerifyErrorMessage::InvalidStructUsage(struct_type)));
+                    }
+
                     let class_type = TypeId::Class(class_name.clone());
-                    self.type_storage.get(&class_type)
+                    let object_type = self.type_storage.get(&class_type)
                         .ok_or_else(|| VerifyError::with_index(instruction_index, VerifyErrorMessage::ClassTypeNotDefined(class_name.clone())))?;
 
+                    if object_type.is_interface() {
+                        return Err(VerifyError::with_index(instruction_index, VerifyErrorMessage::CannotCreateInterface(class_name.clone())));
+                    }
+
+                    // Constructors are not inherited
+                    let signature = FunctionSignature::with_constructor(class_type.clone(), parameters.clone());
+                    self.binder.get(&signature)
+                        .ok_or_else(|| VerifyError::with_index(instruction_index, VerifyErrorMessage::FunctionNotDefined(signature.clone())))?;
+
+                    if self.operand_stack.len() < parameters.len() {
+                        return Err(VerifyError::with_index(instruction_index, VerifyErrorMessage::ExpectedNumberOfOperands(parameters.len())));
+                    }
+
+                    for parameter in parameters.iter().rev() {
+                        let operand = self.pop_operand_stack(instruction_index)?;
+                        self.assignable_type(instruction_index, parameter, &operand)?;
+                    }
+
                     self.push_operand_stack(class_type);
                 }
-                Instruction::LoadField(class_name, field_name) => {
+                Instruction::IsInstance(class_name) | Instruction::CastClass(class_name) => {
                     let class_type = TypeId::Class(class_name.clone());
-                    let class = self.type_storage.get(&class_type)
-                        .ok_or_else(|| VerifyError::with_index(instruction_index, VerifyErrorMessage::ClassTypeNotDefined(class_name.clone())))?
-                        .class.as_ref().unwrap();
+                    self.type_storage.get(&class_type)
+                        .ok_or_else(|| VerifyError::with_index(instruction_index, VerifyErrorMessage::ClassTypeNotDefined(class_name.clone())))?;
 
-                    let field = class.get_field(field_name)
-                        .ok_or_else(|| VerifyError::with_index(instruction_index, VerifyErrorMessage::FieldNotDefined(class_name.clone(), field_name.clone())))?;
+                    let class_reference = self.pop_operand_stack(instruction_index)?;
+                    if !class_reference.is_class() {
+                        return Err(VerifyError::with_index(instruction_index, VerifyErrorMessage::ExpectedClassReference));
+                    }
+
+                    if let Instruction::IsInstance(_) = instruction {
+                        self.push_operand_stack(TypeId::Bool);
+                    } else {
+                        self.push_operand_stack(class_type);
+                    }
+                }
+                Instruction::LoadField(class_name, field_name) => {
+                    let (class_type, field) = self.get_field(instruction_index, class_name, field_name)?;
 
                     let class_reference = self.pop_operand_stack(instruction_index)?;
-                    self.same_type(instruction_index, &TypeId::Class(class.name().to_owned()), &class_reference)?;
+                    self.assignable_type(instruction_index, &class_type, &class_reference)?;
                     self.push_operand_stack(field.type_id().clone());
                 }
                 Instruction::StoreField(class_name, field_name) => {
-                    let class_type = TypeId::Class(class_name.clone());
-                    let class = self.type_storage.get(&class_type)
-                        .ok_or_else(|| VerifyError::with_index(instruction_index, VerifyErrorMessage::ClassTypeNotDefined(class_name.clone())))?
-                        .class.as_ref().unwrap();
-
-                    let field = class.get_field(field_name)
-                        .ok_or_else(|| VerifyError::with_index(instruction_index, VerifyErrorMessage::FieldNotDefined(class_name.clone(), field_name.clone())))?;
+                    let (class_type, field) = self.get_field(instruction_index, class_name, field_name)?;
 
                     let value_type = self.pop_operand_stack(instruction_index)?;
                     let class_reference = self.pop_operand_stack(instruction_index)?;
 
-                    self.same_type(instruction_index, &TypeId::Class(class.name().to_owned()), &class_reference)?;
-                    self.same_type(instruction_index, field.type_id(), &value_type)?;
+                    self.assignable_type(instruction_index, &class_type, &class_reference)?;
+                    self.assignable_type(instruction_index, field.type_id(), &value_type)?;
+
+                    // Structs are values, so the updated struct replaces the original
+                    if class_type.is_struct() {
+                        self.push_operand_stack(class_type);
+                    }
+                }
+                Instruction::LoadStaticField(class_name, field_name) => {
+                    let field = self.get_static_field(instruction_index, class_name, field_name)?;
+                    self.push_operand_stack(field.type_id().clone());
+                }
+                Instruction::StoreStaticField(class_name, field_name) => {
+                    let field = self.get_static_field(instruction_index, class_name, field_name)?;
+                    let value_type = self.pop_operand_stack(instruction_index)?;
+                    self.assignable_type(instruction_index, field.type_id(), &value_type)?;
                 }
                 Instruction::CallInstance(signature) => {
                     let class_type = signature.class.as_ref().unwrap();
@@ -274,7 +640,7 @@ impl<'a> Verifier<'a> {
                         .ok_or_else(|| VerifyError::with_index(instruction_index, VerifyErrorMessage::ClassTypeNotDefined(class_type.class_name().unwrap().to_owned())))?
                         .class.as_ref().unwrap();
 
-                    let func_to_call = self.binder.get(signature)
+                    let func_to_call = self.binder.get_member(self.type_storage, signature)
                         .ok_or(VerifyError::with_index(instruction_index, VerifyErrorMessage::FunctionNotDefined(signature.clone())))?;
 
                     if self.operand_stack.len() < func_to_call.parameters().len() {
@@ -286,7 +652,7 @@ impl<'a> Verifier<'a> {
 
                     for parameter in func_to_call.parameters().iter().rev() {
                         let operand = self.pop_operand_stack(instruction_index)?;
-                        self.same_type(instruction_index, parameter, &operand)?;
+                        self.assignable_type(instruction_index, parameter, &operand)?;
                     }
 
                     if func_to_call.return_type() != &TypeId::Void {
@@ -304,7 +670,11 @@ impl<'a> Verifier<'a> {
                     let op2 = self.pop_operand_stack(instruction_index)?;
                     let op1 = self.pop_operand_stack(instruction_index)?;
 
-                    self.same_type(instruction_index, &op1, &op2)?;
+                    self.comparable_types(instruction_index, &op1, &op2)?;
+                    if op1.is_struct() {
+                        return Err(VerifyError::with_index(instruction_index, VerifyErrorMessage::InvalidStructUsage(op1)));
+                    }
+
                     if *target >= self.function.instructions().len() as u32 {
                         return Err(VerifyError::with_index(instruction_index, VerifyErrorMessage::InvalidBranchTarget));
                     }
@@ -324,7 +694,7 @@ impl<'a> Verifier<'a> {
                     }
 
                     match op1 {
-                        TypeId::Int32 | TypeId::Float32 => {}
+                        TypeId::Int32 | TypeId::Int64 | TypeId::Float32 | TypeId::Float64 => {}
                         _ => {
                             return Err(VerifyError::with_index(instruction_index, VerifyErrorMessage::ExpectedComparableType));
                         }
@@ -336,7 +706,10 @@ impl<'a> Verifier<'a> {
                     let op2 = self.pop_operand_stack(instruction_index)?;
                     let op1 = self.pop_operand_stack(instruction_index)?;
 
-                    self.same_type(instruction_index, &op1, &op2)?;
+                    self.comparable_types(instruction_index, &op1, &op2)?;
+                    if op1.is_struct() {
+                        return Err(VerifyError::with_index(instruction_index, VerifyErrorMessage::InvalidStructUsage(op1)));
+                    }
 
                     self.push_operand_stack(TypeId::Bool);
                 }
@@ -350,7 +723,7 @@ impl<'a> Verifier<'a> {
                     self.same_type(instruction_index, &op1, &op2)?;
 
                     match op1 {
-                        TypeId::Int32 | TypeId::Float32 => {}
+                        TypeId::Int32 | TypeId::Int64 | TypeId::Float32 | TypeId::Float64 => {}
                         _ => {
                             return Err(VerifyError::with_index(instruction_index, VerifyErrorMessage::ExpectedComparableType));
                         }
@@ -358,19 +731,148 @@ impl<'a> Verifier<'a> {
 
                     self.push_operand_stack(TypeId::Bool);
                 }
+                Instruction::BeginTry => {
+                    self.expect_empty_operand_stack(instruction_index)?;
+
+                    self.open_regions.push(RegionBuilder {
+                        region: ProtectedRegion::new(instruction_index),
+                        try_closed: false,
+                        open_handler: None
+                    });
+                }
+                Instruction::EndTry => {
+                    self.expect_empty_operand_stack(instruction_index)?;
+
+                    let region = self.open_regions.last_mut()
+                        .filter(|region| !region.try_closed)
+                        .ok_or(VerifyError::with_index(instruction_index, VerifyErrorMessage::InvalidProtectedRegion))?;
+
+                    region.region.try_end = instruction_index;
+                    region.try_closed = true;
+                }
+                Instruction::BeginCatch(class_name) => {
+                    self.expect_empty_operand_stack(instruction_index)?;
+
+                    let class_type = TypeId::Class(class_name.clone());
+                    self.type_storage.get(&class_type)
+                        .ok_or_else(|| VerifyError::with_index(instruction_index, VerifyErrorMessage::ClassTypeNotDefined(class_name.clone())))?;
+
+                    let region = self.awaiting_handler_region(instruction_index)?;
+                    region.open_handler = Some(OpenHandler::Catch(class_type.clone(), instruction_index));
+
+                    self.push_operand_stack(class_type);
+                }
+                Instruction::EndCatch => {
+                    self.expect_empty_operand_stack(instruction_index)?;
+
+                    let region = self.open_regions.last_mut()
+                        .ok_or(VerifyError::with_index(instruction_index, VerifyErrorMessage::InvalidProtectedRegion))?;
+
+                    match region.open_handler.take() {
+                        Some(OpenHandler::Catch(exception_type, start)) => {
+                            region.region.catch_handlers.push(CatchHandler { exception_type, start, end: instruction_index });
+                        }
+                        _ => {
+                            return Err(VerifyError::with_index(instruction_index, VerifyErrorMessage::InvalidProtectedRegion));
+                        }
+                    }
+                }
+                Instruction::BeginFinally => {
+                    self.expect_empty_operand_stack(instruction_index)?;
+
+                    let region = self.awaiting_handler_region(instruction_index)?;
+                    region.open_handler = Some(OpenHandler::Finally(instruction_index));
+                }
+                Instruction::EndFinally => {
+                    self.expect_empty_operand_stack(instruction_index)?;
+
+                    let mut region = self.open_regions.pop()
+                        .ok_or(VerifyError::with_index(instruction_index, VerifyErrorMessage::InvalidProtectedRegion))?;
+
+                    match region.open_handler.take() {
+                        Some(OpenHandler::Finally(start)) => {
+                            region.region.finally_handler = Some(FinallyHandler { start, end: instruction_index });
+                            self.protected_regions.push(region.region);
+                        }
+                        _ => {
+                            return Err(VerifyError::with_index(instruction_index, VerifyErrorMessage::InvalidProtectedRegion));
+                        }
+                    }
+                }
+                Instruction::Throw => {
+                    let exception = self.pop_operand_stack(instruction_index)?;
+
+                    if !exception.is_class() {
+                        return Err(VerifyError::with_index(instruction_index, VerifyErrorMessage::ExpectedClassReference));
+                    }
+                }
             }
         }
 
+        self.try_close_region(instructions.len())?;
+        if !self.open_regions.is_empty() {
+            return Err(VerifyError::new(VerifyErrorMessage::InvalidProtectedRegion));
+        }
+
         self.function.set_operand_stack_size(max_stack_size);
 
         Ok(())
     }
 
+    fn get_field(&self, instruction_index: usize, class_name: &str, field_name: &str) -> VerifyResult<(TypeId, &'a Field)> {
+        let class_type = self.type_storage.get_by_name(class_name)
+            .ok_or_else(|| VerifyError::with_index(instruction_index, VerifyErrorMessage::ClassTypeNotDefined(class_name.to_owned())))?;
+
+        let field = class_type.class.as_ref().unwrap().get_field(field_name)
+            .ok_or_else(|| VerifyError::with_index(instruction_index, VerifyErrorMessage::FieldNotDefined(class_name.to_owned(), field_name.to_owned())))?;
+
+        Ok((class_type.id.clone(), field))
+    }
+
+    fn verify_array2d_element(&self, instruction_index: usize, element: &TypeId) -> VerifyResult<()> {
+        if element.is_struct() {
+            return Err(VerifyError::with_index(instruction_index, VerifyErrorMessage::InvalidStructUsage(element.clone())));
+        }
+
+        Ok(())
+    }
+
+    fn get_static_field(&self, instruction_index: usize, class_name: &str, field_name: &str) -> VerifyResult<&'a Field> {
+        let class_type = TypeId::Class(class_name.to_owned());
+        let class = self.type_storage.get(&class_type)
+            .ok_or_else(|| VerifyError::with_index(instruction_index, VerifyErrorMessage::ClassTypeNotDefined(class_name.to_owned())))?
+            .class.as_ref().unwrap();
+
+        class.get_static_field(field_name)
+            .ok_or_else(|| VerifyError::with_index(instruction_index, VerifyErrorMessage::FieldNotDefined(class_name.to_owned(), field_name.to_owned())))
+    }
+
+    fn verify_constructor(&self) -> VerifyResult<()> {
+        let declaration = self.function.declaration();
+        if declaration.return_type() != &TypeId::Void {
+            return Err(VerifyError::new(VerifyErrorMessage::WrongType(TypeId::Void, declaration.return_type().clone())));
+        }
+
+        if declaration.is_virtual() {
+            return Err(VerifyError::new(VerifyErrorMessage::VirtualConstructor));
+        }
+
+        Ok(())
+    }
+
     fn verify_locals(&mut self) -> VerifyResult<()> {
         for local in self.function.locals() {
             if local == &TypeId::Void {
                 return Err(VerifyError::new(VerifyErrorMessage::LocalCannotBeVoid));
             }
+
+            if local.is_struct() {
+                if self.type_storage.get(local).is_none() {
+                    return Err(VerifyError::new(VerifyErrorMessage::ClassTypeNotDefined(local.class_name().unwrap().to_owned())));
+                }
+
+                self.struct_slot_size = self.struct_slot_size.max(self.type_storage.size_of(local));
+            }
         }
 
         Ok(())
@@ -397,9 +899,104 @@ impl<'a> Verifier<'a> {
         Ok(())
     }
 
-    fn push_operand_stack(&mut self, value_type: TypeId) {
-        self.operand_stack.push(value_type);
-    }
+    fn verify_protected_regions(&mut self) -> VerifyResult<()> {
+        let num_instructions = self.function.instructions().len();
+
+        for region in &self.protected_regions {
+            // The instruction after the region is where normal execution continues
+            if region.end() + 1 >= num_instructions {
+                return Err(VerifyError::with_index(region.end(), VerifyErrorMessage::InvalidProtectedRegion));
+            }
+        }
+
+        for (branch_source, branch_target, _) in &self.branches {
+            match &self.function.instructions()[*branch_target] {
+                Instruction::BeginCatch(_) | Instruction::BeginFinally => {
+                    return Err(VerifyError::with_index(*branch_source, VerifyErrorMessage::BranchCrossesProtectedRegion));
+                }
+                _ => {}
+            }
+
+            if self.block_of(*branch_source) != self.block_of(*branch_target) {
+                return Err(VerifyError::with_index(*branch_source, VerifyErrorMessage::BranchCrossesProtectedRegion));
+            }
+        }
+
+        for return_index in &self.returns {
+            let inside_finally_region = self.protected_regions
+                .iter()
+                .any(|region| region.finally_handler.is_some() && *return_index > region.try_start && *return_index <= region.end());
+
+            if inside_finally_region {
+                return Err(VerifyError::with_index(*return_index, VerifyErrorMessage::ReturnInsideFinallyRegion));
+            }
+        }
+
+        self.function.set_protected_regions(std::mem::take(&mut self.protected_regions));
+        Ok(())
+    }
+
+    fn block_of(&self, instruction_index: usize) -> Block {
+        let mut block = (Block::Function, 0);
+        let mut update_block = |new_block: Block, start: usize, end: usize| {
+            if instruction_index > start && instruction_index <= end && start >= block.1 {
+                block = (new_block, start);
+            }
+        };
+
+        for (region_index, region) in self.protected_regions.iter().enumerate() {
+            update_block(Block::Try(region_index), region.try_start, region.try_end);
+
+            for (handler_index, handler) in region.catch_handlers.iter().enumerate() {
+                update_block(Block::Catch(region_index, handler_index), handler.start, handler.end);
+            }
+
+            if let Some(handler) = &region.finally_handler {
+                update_block(Block::Finally(region_index), handler.start, handler.end);
+            }
+        }
+
+        block.0
+    }
+
+    fn awaiting_handler_region(&mut self, instruction_index: usize) -> VerifyResult<&mut RegionBuilder> {
+        self.open_regions.last_mut()
+            .filter(|region| region.try_closed && region.open_handler.is_none() && region.region.finally_handler.is_none())
+            .ok_or(VerifyError::with_index(instruction_index, VerifyErrorMessage::InvalidProtectedRegion))
+    }
+
+    fn try_close_region(&mut self, instruction_index: usize) -> VerifyResult<()> {
+        let awaiting_handler = self.open_regions.last()
+            .map(|region| region.try_closed && region.open_handler.is_none())
+            .unwrap_or(false);
+
+        if awaiting_handler {
+            let region = self.open_regions.pop().unwrap();
+            if region.region.catch_handlers.is_empty() {
+                return Err(VerifyError::with_index(instruction_index, VerifyErrorMessage::InvalidProtectedRegion));
+            }
+
+            self.protected_regions.push(region.region);
+        }
+
+        Ok(())
+    }
+
+    fn expect_empty_operand_stack(&self, instruction_index: usize) -> VerifyResult<()> {
+        if !self.operand_stack.is_empty() {
+            return Err(VerifyError::with_index(instruction_index, VerifyErrorMessage::ExpectedEmptyOperandStack));
+        }
+
+        Ok(())
+    }
+
+    fn push_operand_stack(&mut self, value_type: TypeId) {
+        if value_type.is_struct() {
+            self.struct_slot_size = self.struct_slot_size.max(self.type_storage.size_of(&value_type));
+        }
+
+        self.operand_stack.push(value_type);
+    }
 
     fn pop_operand_stack(&mut self, instruction_index: usize) -> VerifyResult<TypeId> {
         self.operand_stack.pop()
@@ -407,9 +1004,29 @@ impl<'a> Verifier<'a> {
     }
 
     fn clone_operand_stack(&self) -> Vec<TypeId> {
-        self.operand_stack.iter().map(|o| o.clone()).collect()
+        self.operand_stack.to_vec()
     }
 
+    fn assignable_type(&self, instruction_index: usize, expected: &TypeId, actual: &TypeId) -> VerifyResult<()> {
+        if !self.type_storage.is_assignable(expected, actual) {
+            Err(VerifyError::with_index(
+                instruction_index,
+                VerifyErrorMessage::WrongType(expected.clone(), actual.clone())
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn comparable_types(&self, instruction_index: usize, op1: &TypeId, op2: &TypeId) -> VerifyResult<()> {
+        if self.type_storage.is_assignable(op2, op1) {
+            Ok(())
+        } else {
+            self.assignable_type(instruction_index, op1, op2)
+        }
+    }
+
+    // The operand stacks where branches meet must match exactly, as the stack slots have a single type
     fn same_type(&self, instruction_index: usize, expected: &TypeId, actual: &TypeId) -> VerifyResult<()> {
         if !expected.is_same_type(actual) {
             Err(VerifyError::with_index(
@@ -480,14 +1097,14 @@ fn test_simple3() {
 }
 
 #[test]
-fn test_return1() {
+fn test_simple4() {
     let mut function = Function::new(
-        FunctionDeclaration::with_managed("test".to_owned(), Vec::new(), TypeId::Void),
+        FunctionDeclaration::with_managed("test".to_owned(), Vec::new(), TypeId::Int64),
         Vec::new(),
         vec![
-            Instruction::LoadInt32(4711),
-            Instruction::LoadInt32(1337),
-            Instruction::Add,
+            Instruction::LoadInt64(4711),
+            Instruction::LoadInt64(1337),
+            Instruction::Multiply,
             Instruction::Return,
         ]
     );
@@ -495,17 +1112,19 @@ fn test_return1() {
     let binder = Binder::new();
     let type_storage = TypeStorage::new();
     let mut verifier = Verifier::new(&binder, &type_storage, &mut function);
-    assert_eq!(Err(VerifyError::new(VerifyErrorMessage::NonEmptyOperandStackOnReturn)), verifier.verify());
+    assert_eq!(Ok(()), verifier.verify());
+    assert_eq!(&vec![TypeId::Int64, TypeId::Int64], function.instruction_operand_types(2));
 }
 
 #[test]
-fn test_return2() {
+fn test_simple5() {
     let mut function = Function::new(
-        FunctionDeclaration::with_managed("test".to_owned(), Vec::new(), TypeId::Void),
+        FunctionDeclaration::with_managed("test".to_owned(), Vec::new(), TypeId::Float64),
         Vec::new(),
         vec![
-            Instruction::LoadInt32(4711),
-            Instruction::LoadInt32(1337),
+            Instruction::LoadFloat64(47.11),
+            Instruction::LoadFloat32(13.37),
+            Instruction::Add,
             Instruction::Return,
         ]
     );
@@ -513,19 +1132,20 @@ fn test_return2() {
     let binder = Binder::new();
     let type_storage = TypeStorage::new();
     let mut verifier = Verifier::new(&binder, &type_storage, &mut function);
-    assert_eq!(Err(VerifyError::new(VerifyErrorMessage::NonEmptyOperandStackOnReturn)), verifier.verify());
+    assert_eq!(
+        Err(VerifyError::with_index(2, VerifyErrorMessage::WrongArithmeticOperands)),
+        verifier.verify()
+    );
 }
 
-
 #[test]
-fn test_return3() {
+fn test_convert1() {
     let mut function = Function::new(
         FunctionDeclaration::with_managed("test".to_owned(), Vec::new(), TypeId::Float32),
         Vec::new(),
         vec![
             Instruction::LoadInt32(4711),
-            Instruction::LoadInt32(1337),
-            Instruction::Add,
+            Instruction::ConvertInt32ToFloat32,
             Instruction::Return,
         ]
     );
@@ -533,22 +1153,17 @@ fn test_return3() {
     let binder = Binder::new();
     let type_storage = TypeStorage::new();
     let mut verifier = Verifier::new(&binder, &type_storage, &mut function);
-    assert_eq!(
-        Err(VerifyError::with_index(3, VerifyErrorMessage::WrongType(TypeId::Float32, TypeId::Int32))),
-        verifier.verify()
-    );
+    assert_eq!(Ok(()), verifier.verify());
 }
 
-
 #[test]
-fn test_local1() {
+fn test_convert2() {
     let mut function = Function::new(
         FunctionDeclaration::with_managed("test".to_owned(), Vec::new(), TypeId::Int32),
-        vec![TypeId::Int32],
+        Vec::new(),
         vec![
             Instruction::LoadInt32(4711),
-            Instruction::StoreLocal(0),
-            Instruction::LoadLocal(0),
+            Instruction::ConvertFloat32ToInt32,
             Instruction::Return,
         ]
     );
@@ -556,18 +1171,22 @@ fn test_local1() {
     let binder = Binder::new();
     let type_storage = TypeStorage::new();
     let mut verifier = Verifier::new(&binder, &type_storage, &mut function);
-    assert_eq!(Ok(()), verifier.verify());
+    assert_eq!(
+        Err(VerifyError::with_index(1, VerifyErrorMessage::WrongType(TypeId::Float32, TypeId::Int32))),
+        verifier.verify()
+    );
 }
 
 #[test]
-fn test_local2() {
+fn test_convert3() {
     let mut function = Function::new(
-        FunctionDeclaration::with_managed("test".to_owned(), Vec::new(), TypeId::Int32),
-        vec![],
+        FunctionDeclaration::with_managed("test".to_owned(), Vec::new(), TypeId::Float32),
+        Vec::new(),
         vec![
             Instruction::LoadInt32(4711),
-            Instruction::StoreLocal(0),
-            Instruction::LoadLocal(0),
+            Instruction::ConvertInt32ToInt64,
+            Instruction::ConvertInt64ToFloat64,
+            Instruction::ConvertFloat64ToFloat32,
             Instruction::Return,
         ]
     );
@@ -575,19 +1194,17 @@ fn test_local2() {
     let binder = Binder::new();
     let type_storage = TypeStorage::new();
     let mut verifier = Verifier::new(&binder, &type_storage, &mut function);
-    assert_eq!(
-        Err(VerifyError::with_index(1, VerifyErrorMessage::LocalIndexOutOfRange)),
-        verifier.verify()
-    );
+    assert_eq!(Ok(()), verifier.verify());
 }
 
 #[test]
-fn test_call1() {
+fn test_convert4() {
     let mut function = Function::new(
-        FunctionDeclaration::with_managed("test".to_owned(), vec![TypeId::Int32], TypeId::Int32),
-        vec![],
+        FunctionDeclaration::with_managed("test".to_owned(), Vec::new(), TypeId::Int64),
+        Vec::new(),
         vec![
-            Instruction::LoadArgument(0),
+            Instruction::LoadFloat32(13.37),
+            Instruction::ConvertFloat64ToInt64,
             Instruction::Return,
         ]
     );
@@ -595,96 +1212,85 @@ fn test_call1() {
     let binder = Binder::new();
     let type_storage = TypeStorage::new();
     let mut verifier = Verifier::new(&binder, &type_storage, &mut function);
-    assert_eq!(Ok(()), verifier.verify());
-    assert_eq!(&vec![TypeId::Int32], function.instruction_operand_types(1));
+    assert_eq!(
+        Err(VerifyError::with_index(1, VerifyErrorMessage::WrongType(TypeId::Float64, TypeId::Float32))),
+        verifier.verify()
+    );
 }
 
 #[test]
-fn test_call2() {
+fn test_stack1() {
     let mut function = Function::new(
-        FunctionDeclaration::with_managed("test".to_owned(), vec![TypeId::Int32], TypeId::Int32),
-        vec![],
+        FunctionDeclaration::with_managed("test".to_owned(), Vec::new(), TypeId::Float32),
+        Vec::new(),
         vec![
-            Instruction::LoadInt32(1),
-            Instruction::LoadInt32(2),
-            Instruction::LoadFloat32(3.0),
-            Instruction::LoadInt32(4),
-            Instruction::LoadFloat32(5.0),
-            Instruction::Call(FunctionSignature::new("test_call".to_owned(), vec![TypeId::Int32, TypeId::Int32, TypeId::Float32, TypeId::Int32, TypeId::Float32])),
+            Instruction::LoadInt32(4711),
+            Instruction::LoadFloat32(13.37),
+            Instruction::Swap,
+            Instruction::Duplicate,
+            Instruction::Pop,
+            Instruction::Pop,
             Instruction::Return,
         ]
     );
 
-    let mut binder = Binder::new();
+    let binder = Binder::new();
     let type_storage = TypeStorage::new();
-    binder.define(FunctionDeclaration::with_managed("test_call".to_owned(), vec![TypeId::Int32, TypeId::Int32, TypeId::Float32, TypeId::Int32, TypeId::Float32], TypeId::Int32));
-
     let mut verifier = Verifier::new(&binder, &type_storage, &mut function);
     assert_eq!(Ok(()), verifier.verify());
+    assert_eq!(&vec![TypeId::Float32, TypeId::Int32], function.instruction_operand_types(3));
+    assert_eq!(&vec![TypeId::Float32, TypeId::Int32, TypeId::Int32], function.instruction_operand_types(4));
 }
 
 #[test]
-fn test_call3() {
+fn test_stack2() {
     let mut function = Function::new(
-        FunctionDeclaration::with_managed("test".to_owned(), vec![TypeId::Int32], TypeId::Int32),
-        vec![],
+        FunctionDeclaration::with_managed("test".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
         vec![
-            Instruction::LoadInt32(1),
-            Instruction::LoadInt32(2),
-            Instruction::LoadFloat32(5.0),
-            Instruction::Call(FunctionSignature::new("test_call".to_owned(), vec![TypeId::Int32, TypeId::Int32, TypeId::Float32, TypeId::Int32, TypeId::Float32])),
+            Instruction::LoadInt32(4711),
+            Instruction::Swap,
             Instruction::Return,
         ]
     );
 
-    let mut binder = Binder::new();
+    let binder = Binder::new();
     let type_storage = TypeStorage::new();
-    binder.define(FunctionDeclaration::with_managed("test_call".to_owned(), vec![TypeId::Int32, TypeId::Int32, TypeId::Float32, TypeId::Int32, TypeId::Float32], TypeId::Int32));
-
     let mut verifier = Verifier::new(&binder, &type_storage, &mut function);
-    assert_eq!(
-        Err(VerifyError::with_index(3, VerifyErrorMessage::ExpectedNumberOfOperands(5))),
-        verifier.verify()
-    );
+    assert_eq!(Err(VerifyError::with_index(1, VerifyErrorMessage::EmptyOperandStack)), verifier.verify());
 }
 
 #[test]
-fn test_call4() {
+fn test_bitwise1() {
     let mut function = Function::new(
-        FunctionDeclaration::with_managed("test".to_owned(), vec![TypeId::Int32], TypeId::Int32),
-        vec![],
+        FunctionDeclaration::with_managed("test".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
         vec![
-            Instruction::LoadInt32(1),
-            Instruction::LoadInt32(2),
+            Instruction::LoadInt32(4711),
+            Instruction::LoadInt32(1337),
+            Instruction::Xor,
             Instruction::LoadInt32(3),
-            Instruction::LoadInt32(4),
-            Instruction::LoadFloat32(5.0),
-            Instruction::Call(FunctionSignature::new("test_call".to_owned(), vec![TypeId::Int32, TypeId::Int32, TypeId::Float32, TypeId::Int32, TypeId::Float32])),
+            Instruction::ShiftLeft,
+            Instruction::Not,
             Instruction::Return,
         ]
     );
 
-    let mut binder = Binder::new();
+    let binder = Binder::new();
     let type_storage = TypeStorage::new();
-    binder.define(FunctionDeclaration::with_managed("test_call".to_owned(), vec![TypeId::Int32, TypeId::Int32, TypeId::Float32, TypeId::Int32, TypeId::Float32], TypeId::Int32));
-
     let mut verifier = Verifier::new(&binder, &type_storage, &mut function);
-    assert_eq!(
-        Err(VerifyError::with_index(5, VerifyErrorMessage::WrongType(TypeId::Float32, TypeId::Int32))),
-        verifier.verify()
-    );
+    assert_eq!(Ok(()), verifier.verify());
 }
 
 #[test]
-fn test_array1() {
+fn test_bitwise2() {
     let mut function = Function::new(
-        FunctionDeclaration::with_managed("test".to_owned(), Vec::new(), TypeId::Int32),
+        FunctionDeclaration::with_managed("test".to_owned(), Vec::new(), TypeId::Bool),
         Vec::new(),
         vec![
-            Instruction::LoadInt32(4711),
-            Instruction::NewArray(TypeId::Int32),
-            Instruction::LoadInt32(1000),
-            Instruction::LoadElement(TypeId::Int32),
+            Instruction::LoadTrue,
+            Instruction::LoadFalse,
+            Instruction::Xor,
             Instruction::Return,
         ]
     );
@@ -692,20 +1298,22 @@ fn test_array1() {
     let binder = Binder::new();
     let type_storage = TypeStorage::new();
     let mut verifier = Verifier::new(&binder, &type_storage, &mut function);
-    assert_eq!(Ok(()), verifier.verify());
+    assert_eq!(Err(VerifyError::with_index(2, VerifyErrorMessage::WrongLogicalOperands)), verifier.verify());
 }
 
 #[test]
-fn test_array2() {
+fn test_bitwise3() {
     let mut function = Function::new(
-        FunctionDeclaration::with_managed("test".to_owned(), Vec::new(), TypeId::Void),
+        FunctionDeclaration::with_managed("test".to_owned(), Vec::new(), TypeId::Int64),
         Vec::new(),
         vec![
-            Instruction::LoadInt32(4711),
-            Instruction::NewArray(TypeId::Float32),
-            Instruction::LoadInt32(1000),
-            Instruction::LoadFloat32(47.11),
-            Instruction::StoreElement(TypeId::Float32),
+            Instruction::LoadInt64(4711),
+            Instruction::LoadInt64(1337),
+            Instruction::Xor,
+            Instruction::LoadInt32(3),
+            Instruction::ShiftLeft,
+            Instruction::Not,
+            Instruction::Negate,
             Instruction::Return,
         ]
     );
@@ -717,16 +1325,14 @@ fn test_array2() {
 }
 
 #[test]
-fn test_array3() {
+fn test_bitwise4() {
     let mut function = Function::new(
-        FunctionDeclaration::with_managed("test".to_owned(), Vec::new(), TypeId::Void),
+        FunctionDeclaration::with_managed("test".to_owned(), Vec::new(), TypeId::Int64),
         Vec::new(),
         vec![
-            Instruction::LoadInt32(4711),
-            Instruction::NewArray(TypeId::Int32),
-            Instruction::LoadInt32(1000),
-            Instruction::LoadFloat32(47.11),
-            Instruction::StoreElement(TypeId::Float32),
+            Instruction::LoadInt64(4711),
+            Instruction::LoadInt64(3),
+            Instruction::ShiftRight,
             Instruction::Return,
         ]
     );
@@ -735,21 +1341,20 @@ fn test_array3() {
     let type_storage = TypeStorage::new();
     let mut verifier = Verifier::new(&binder, &type_storage, &mut function);
     assert_eq!(
-        Err(VerifyError::with_index(4, VerifyErrorMessage::WrongType(TypeId::Array(Box::new(TypeId::Float32)), TypeId::Array(Box::new(TypeId::Int32))))),
+        Err(VerifyError::with_index(2, VerifyErrorMessage::WrongArithmeticOperands)),
         verifier.verify()
     );
 }
 
 #[test]
-fn test_array4() {
+fn test_remainder1() {
     let mut function = Function::new(
-        FunctionDeclaration::with_managed("test".to_owned(), Vec::new(), TypeId::Int32),
+        FunctionDeclaration::with_managed("test".to_owned(), Vec::new(), TypeId::Float32),
         Vec::new(),
         vec![
-            Instruction::LoadInt32(4711),
-            Instruction::LoadInt32(5411),
-            Instruction::LoadInt32(1000),
-            Instruction::LoadElement(TypeId::Int32),
+            Instruction::LoadFloat32(4711.0),
+            Instruction::LoadFloat32(1337.0),
+            Instruction::Remainder,
             Instruction::Return,
         ]
     );
@@ -757,21 +1362,18 @@ fn test_array4() {
     let binder = Binder::new();
     let type_storage = TypeStorage::new();
     let mut verifier = Verifier::new(&binder, &type_storage, &mut function);
-    assert_eq!(
-        Err(VerifyError::with_index(3, VerifyErrorMessage::WrongType(TypeId::Array(Box::new(TypeId::Int32)), TypeId::Int32))),
-        verifier.verify()
-    );
+    assert_eq!(Err(VerifyError::with_index(2, VerifyErrorMessage::WrongArithmeticOperands)), verifier.verify());
 }
 
 #[test]
-fn test_array5() {
+fn test_return1() {
     let mut function = Function::new(
-        FunctionDeclaration::with_managed("test".to_owned(), Vec::new(), TypeId::Int32),
+        FunctionDeclaration::with_managed("test".to_owned(), Vec::new(), TypeId::Void),
         Vec::new(),
         vec![
             Instruction::LoadInt32(4711),
-            Instruction::NewArray(TypeId::Float32),
-            Instruction::LoadArrayLength,
+            Instruction::LoadInt32(1337),
+            Instruction::Add,
             Instruction::Return,
         ]
     );
@@ -779,20 +1381,17 @@ fn test_array5() {
     let binder = Binder::new();
     let type_storage = TypeStorage::new();
     let mut verifier = Verifier::new(&binder, &type_storage, &mut function);
-    assert_eq!(Ok(()), verifier.verify());
+    assert_eq!(Err(VerifyError::new(VerifyErrorMessage::NonEmptyOperandStackOnReturn)), verifier.verify());
 }
 
 #[test]
-fn test_branches1() {
+fn test_return2() {
     let mut function = Function::new(
         FunctionDeclaration::with_managed("test".to_owned(), Vec::new(), TypeId::Void),
-        vec![TypeId::Int32],
+        Vec::new(),
         vec![
-            Instruction::LoadInt32(1),
-            Instruction::LoadInt32(2),
-            Instruction::BranchNotEqual(5),
-            Instruction::LoadInt32(3),
-            Instruction::StoreLocal(0),
+            Instruction::LoadInt32(4711),
+            Instruction::LoadInt32(1337),
             Instruction::Return,
         ]
     );
@@ -800,24 +1399,19 @@ fn test_branches1() {
     let binder = Binder::new();
     let type_storage = TypeStorage::new();
     let mut verifier = Verifier::new(&binder, &type_storage, &mut function);
-    assert_eq!(Ok(()), verifier.verify());
+    assert_eq!(Err(VerifyError::new(VerifyErrorMessage::NonEmptyOperandStackOnReturn)), verifier.verify());
 }
 
+
 #[test]
-fn test_branches2() {
+fn test_return3() {
     let mut function = Function::new(
-        FunctionDeclaration::with_managed("test".to_owned(), Vec::new(), TypeId::Int32),
-        vec![TypeId::Int32],
+        FunctionDeclaration::with_managed("test".to_owned(), Vec::new(), TypeId::Float32),
+        Vec::new(),
         vec![
-            Instruction::LoadInt32(1),
-            Instruction::LoadInt32(2),
-            Instruction::BranchNotEqual(6),
-            Instruction::LoadInt32(1337),
-            Instruction::StoreLocal(0),
-            Instruction::Branch(8),
             Instruction::LoadInt32(4711),
-            Instruction::StoreLocal(0),
-            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(1337),
+            Instruction::Add,
             Instruction::Return,
         ]
     );
@@ -825,141 +1419,1186 @@ fn test_branches2() {
     let binder = Binder::new();
     let type_storage = TypeStorage::new();
     let mut verifier = Verifier::new(&binder, &type_storage, &mut function);
-    assert_eq!(Ok(()), verifier.verify());
+    assert_eq!(
+        Err(VerifyError::with_index(3, VerifyErrorMessage::WrongType(TypeId::Float32, TypeId::Int32))),
+        verifier.verify()
+    );
+}
+
+
+#[test]
+fn test_local1() {
+    let mut function = Function::new(
+        FunctionDeclaration::with_managed("test".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Int32],
+        vec![
+            Instruction::LoadInt32(4711),
+            Instruction::StoreLocal(0),
+            Instruction::LoadLocal(0),
+            Instruction::Return,
+        ]
+    );
+
+    let binder = Binder::new();
+    let type_storage = TypeStorage::new();
+    let mut verifier = Verifier::new(&binder, &type_storage, &mut function);
+    assert_eq!(Ok(()), verifier.verify());
+}
+
+#[test]
+fn test_local2() {
+    let mut function = Function::new(
+        FunctionDeclaration::with_managed("test".to_owned(), Vec::new(), TypeId::Int32),
+        vec![],
+        vec![
+            Instruction::LoadInt32(4711),
+            Instruction::StoreLocal(0),
+            Instruction::LoadLocal(0),
+            Instruction::Return,
+        ]
+    );
+
+    let binder = Binder::new();
+    let type_storage = TypeStorage::new();
+    let mut verifier = Verifier::new(&binder, &type_storage, &mut function);
+    assert_eq!(
+        Err(VerifyError::with_index(1, VerifyErrorMessage::LocalIndexOutOfRange)),
+        verifier.verify()
+    );
+}
+
+#[test]
+fn test_call1() {
+    let mut function = Function::new(
+        FunctionDeclaration::with_managed("test".to_owned(), vec![TypeId::Int32], TypeId::Int32),
+        vec![],
+        vec![
+            Instruction::LoadArgument(0),
+            Instruction::Return,
+        ]
+    );
+
+    let binder = Binder::new();
+    let type_storage = TypeStorage::new();
+    let mut verifier = Verifier::new(&binder, &type_storage, &mut function);
+    assert_eq!(Ok(()), verifier.verify());
+    assert_eq!(&vec![TypeId::Int32], function.instruction_operand_types(1));
+}
+
+#[test]
+fn test_call2() {
+    let mut function = Function::new(
+        FunctionDeclaration::with_managed("test".to_owned(), vec![TypeId::Int32], TypeId::Int32),
+        vec![],
+        vec![
+            Instruction::LoadInt32(1),
+            Instruction::LoadInt32(2),
+            Instruction::LoadFloat32(3.0),
+            Instruction::LoadInt32(4),
+            Instruction::LoadFloat32(5.0),
+            Instruction::Call(FunctionSignature::new("test_call".to_owned(), vec![TypeId::Int32, TypeId::Int32, TypeId::Float32, TypeId::Int32, TypeId::Float32])),
+            Instruction::Return,
+        ]
+    );
+
+    let mut binder = Binder::new();
+    let type_storage = TypeStorage::new();
+    binder.define(FunctionDeclaration::with_managed("test_call".to_owned(), vec![TypeId::Int32, TypeId::Int32, TypeId::Float32, TypeId::Int32, TypeId::Float32], TypeId::Int32));
+
+    let mut verifier = Verifier::new(&binder, &type_storage, &mut function);
+    assert_eq!(Ok(()), verifier.verify());
+}
+
+#[test]
+fn test_call3() {
+    let mut function = Function::new(
+        FunctionDeclaration::with_managed("test".to_owned(), vec![TypeId::Int32], TypeId::Int32),
+        vec![],
+        vec![
+            Instruction::LoadInt32(1),
+            Instruction::LoadInt32(2),
+            Instruction::LoadFloat32(5.0),
+            Instruction::Call(FunctionSignature::new("test_call".to_owned(), vec![TypeId::Int32, TypeId::Int32, TypeId::Float32, TypeId::Int32, TypeId::Float32])),
+            Instruction::Return,
+        ]
+    );
+
+    let mut binder = Binder::new();
+    let type_storage = TypeStorage::new();
+    binder.define(FunctionDeclaration::with_managed("test_call".to_owned(), vec![TypeId::Int32, TypeId::Int32, TypeId::Float32, TypeId::Int32, TypeId::Float32], TypeId::Int32));
+
+    let mut verifier = Verifier::new(&binder, &type_storage, &mut function);
+    assert_eq!(
+        Err(VerifyError::with_index(3, VerifyErrorMessage::ExpectedNumberOfOperands(5))),
+        verifier.verify()
+    );
+}
+
+#[test]
+fn test_call4() {
+    let mut function = Function::new(
+        FunctionDeclaration::with_managed("test".to_owned(), vec![TypeId::Int32], TypeId::Int32),
+        vec![],
+        vec![
+            Instruction::LoadInt32(1),
+            Instruction::LoadInt32(2),
+            Instruction::LoadInt32(3),
+            Instruction::LoadInt32(4),
+            Instruction::LoadFloat32(5.0),
+            Instruction::Call(FunctionSignature::new("test_call".to_owned(), vec![TypeId::Int32, TypeId::Int32, TypeId::Float32, TypeId::Int32, TypeId::Float32])),
+            Instruction::Return,
+        ]
+    );
+
+    let mut binder = Binder::new();
+    let type_storage = TypeStorage::new();
+    binder.define(FunctionDeclaration::with_managed("test_call".to_owned(), vec![TypeId::Int32, TypeId::Int32, TypeId::Float32, TypeId::Int32, TypeId::Float32], TypeId::Int32));
+
+    let mut verifier = Verifier::new(&binder, &type_storage, &mut function);
+    assert_eq!(
+        Err(VerifyError::with_index(5, VerifyErrorMessage::WrongType(TypeId::Float32, TypeId::Int32))),
+        verifier.verify()
+    );
+}
+
+#[test]
+fn test_array1() {
+    let mut function = Function::new(
+        FunctionDeclaration::with_managed("test".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadInt32(4711),
+            Instruction::NewArray(TypeId::Int32),
+            Instruction::LoadInt32(1000),
+            Instruction::LoadElement(TypeId::Int32),
+            Instruction::Return,
+        ]
+    );
+
+    let binder = Binder::new();
+    let type_storage = TypeStorage::new();
+    let mut verifier = Verifier::new(&binder, &type_storage, &mut function);
+    assert_eq!(Ok(()), verifier.verify());
+}
+
+#[test]
+fn test_array2() {
+    let mut function = Function::new(
+        FunctionDeclaration::with_managed("test".to_owned(), Vec::new(), TypeId::Void),
+        Vec::new(),
+        vec![
+            Instruction::LoadInt32(4711),
+            Instruction::NewArray(TypeId::Float32),
+            Instruction::LoadInt32(1000),
+            Instruction::LoadFloat32(47.11),
+            Instruction::StoreElement(TypeId::Float32),
+            Instruction::Return,
+        ]
+    );
+
+    let binder = Binder::new();
+    let type_storage = TypeStorage::new();
+    let mut verifier = Verifier::new(&binder, &type_storage, &mut function);
+    assert_eq!(Ok(()), verifier.verify());
+}
+
+#[test]
+fn test_array3() {
+    let mut function = Function::new(
+        FunctionDeclaration::with_managed("test".to_owned(), Vec::new(), TypeId::Void),
+        Vec::new(),
+        vec![
+            Instruction::LoadInt32(4711),
+            Instruction::NewArray(TypeId::Int32),
+            Instruction::LoadInt32(1000),
+            Instruction::LoadFloat32(47.11),
+            Instruction::StoreElement(TypeId::Float32),
+            Instruction::Return,
+        ]
+    );
+
+    let binder = Binder::new();
+    let type_storage = TypeStorage::new();
+    let mut verifier = Verifier::new(&binder, &type_storage, &mut function);
+    assert_eq!(
+        Err(VerifyError::with_index(4, VerifyErrorMessage::WrongType(TypeId::Array(Box::new(TypeId::Float32)), TypeId::Array(Box::new(TypeId::Int32))))),
+        verifier.verify()
+    );
+}
+
+#[test]
+fn test_array4() {
+    let mut function = Function::new(
+        FunctionDeclaration::with_managed("test".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadInt32(4711),
+            Instruction::LoadInt32(5411),
+            Instruction::LoadInt32(1000),
+            Instruction::LoadElement(TypeId::Int32),
+            Instruction::Return,
+        ]
+    );
+
+    let binder = Binder::new();
+    let type_storage = TypeStorage::new();
+    let mut verifier = Verifier::new(&binder, &type_storage, &mut function);
+    assert_eq!(
+        Err(VerifyError::with_index(3, VerifyErrorMessage::WrongType(TypeId::Array(Box::new(TypeId::Int32)), TypeId::Int32))),
+        verifier.verify()
+    );
+}
+
+#[test]
+fn test_array5() {
+    let mut function = Function::new(
+        FunctionDeclaration::with_managed("test".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadInt32(4711),
+            Instruction::NewArray(TypeId::Float32),
+            Instruction::LoadArrayLength,
+            Instruction::Return,
+        ]
+    );
+
+    let binder = Binder::new();
+    let type_storage = TypeStorage::new();
+    let mut verifier = Verifier::new(&binder, &type_storage, &mut function);
+    assert_eq!(Ok(()), verifier.verify());
+}
+
+#[test]
+fn test_array2d1() {
+    let mut function = Function::new(
+        FunctionDeclaration::with_managed("test".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Array2D(Box::new(TypeId::Int32))],
+        vec![
+            Instruction::LoadInt32(2),
+            Instruction::LoadInt32(3),
+            Instruction::NewArray2D(TypeId::Int32),
+            Instruction::StoreLocal(0),
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(1),
+            Instruction::LoadInt32(2),
+            Instruction::LoadInt32(4711),
+            Instruction::StoreElement2D(TypeId::Int32),
+            Instruction::LoadLocal(0),
+            Instruction::LoadInt32(1),
+            Instruction::LoadInt32(2),
+            Instruction::LoadElement2D(TypeId::Int32),
+            Instruction::LoadLocal(0),
+            Instruction::LoadArrayDimension(1),
+            Instruction::Add,
+            Instruction::Return,
+        ]
+    );
+
+    let binder = Binder::new();
+    let type_storage = TypeStorage::new();
+    let mut verifier = Verifier::new(&binder, &type_storage, &mut function);
+    assert_eq!(Ok(()), verifier.verify());
+}
+
+#[test]
+fn test_array2d2() {
+    let mut function = Function::new(
+        FunctionDeclaration::with_managed("test".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadInt32(2),
+            Instruction::NewArray(TypeId::Int32),
+            Instruction::LoadInt32(0),
+            Instruction::LoadInt32(1),
+            Instruction::LoadElement2D(TypeId::Int32),
+            Instruction::Return,
+        ]
+    );
+
+    let binder = Binder::new();
+    let type_storage = TypeStorage::new();
+    let mut verifier = Verifier::new(&binder, &type_storage, &mut function);
+    assert_eq!(
+        Err(VerifyError::with_index(4, VerifyErrorMessage::WrongType(TypeId::Array2D(Box::new(TypeId::Int32)), TypeId::Array(Box::new(TypeId::Int32))))),
+        verifier.verify()
+    );
+}
+
+#[test]
+fn test_array2d3() {
+    let mut function = Function::new(
+        FunctionDeclaration::with_managed("test".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadInt32(2),
+            Instruction::LoadInt32(2),
+            Instruction::NewArray2D(TypeId::Int32),
+            Instruction::LoadArrayDimension(2),
+            Instruction::Return,
+        ]
+    );
+
+    let binder = Binder::new();
+    let type_storage = TypeStorage::new();
+    let mut verifier = Verifier::new(&binder, &type_storage, &mut function);
+    assert_eq!(
+        Err(VerifyError::with_index(3, VerifyErrorMessage::InvalidArrayDimension(2))),
+        verifier.verify()
+    );
+}
+
+#[test]
+fn test_array_functions1() {
+    let mut function = Function::new(
+        FunctionDeclaration::with_managed("test".to_owned(), Vec::new(), TypeId::Void),
+        Vec::new(),
+        vec![
+            Instruction::LoadInt32(2),
+            Instruction::NewArray(TypeId::Int32),
+            Instruction::LoadFloat32(47.11),
+            Instruction::Call(FunctionSignature::new("std.array.fill".to_owned(), vec![TypeId::Array(Box::new(TypeId::Int32)), TypeId::Float32])),
+            Instruction::Return,
+        ]
+    );
+
+    let binder = Binder::new();
+    let type_storage = TypeStorage::new();
+    let mut verifier = Verifier::new(&binder, &type_storage, &mut function);
+    assert_eq!(
+        Err(VerifyError::with_index(3, VerifyErrorMessage::FunctionNotDefined(FunctionSignature::new("std.array.fill".to_owned(), vec![TypeId::Array(Box::new(TypeId::Int32)), TypeId::Float32])))),
+        verifier.verify()
+    );
+}
+
+#[test]
+fn test_function_reference1() {
+    let mut function = Function::new(
+        FunctionDeclaration::with_managed("test".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadInt32(1),
+            Instruction::LoadInt32(2),
+            Instruction::LoadFunction(FunctionSignature::new("add".to_owned(), vec![TypeId::Int32, TypeId::Int32])),
+            Instruction::CallIndirect,
+            Instruction::Return,
+        ]
+    );
+
+    let mut binder = Binder::new();
+    binder.define(FunctionDeclaration::with_managed("add".to_owned(), vec![TypeId::Int32, TypeId::Int32], TypeId::Int32));
+
+    let type_storage = TypeStorage::new();
+    let mut verifier = Verifier::new(&binder, &type_storage, &mut function);
+    assert_eq!(Ok(()), verifier.verify());
+    assert_eq!(
+        &vec![TypeId::Int32, TypeId::Int32, TypeId::Function(vec![TypeId::Int32, TypeId::Int32], Box::new(TypeId::Int32))],
+        function.instruction_operand_types(3)
+    );
+}
+
+#[test]
+fn test_function_reference2() {
+    let mut function = Function::new(
+        FunctionDeclaration::with_managed("test".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadInt32(1),
+            Instruction::LoadFloat32(2.0),
+            Instruction::LoadFunction(FunctionSignature::new("add".to_owned(), vec![TypeId::Int32, TypeId::Int32])),
+            Instruction::CallIndirect,
+            Instruction::Return,
+        ]
+    );
+
+    let mut binder = Binder::new();
+    binder.define(FunctionDeclaration::with_managed("add".to_owned(), vec![TypeId::Int32, TypeId::Int32], TypeId::Int32));
+
+    let type_storage = TypeStorage::new();
+    let mut verifier = Verifier::new(&binder, &type_storage, &mut function);
+    assert_eq!(
+        Err(VerifyError::with_index(3, VerifyErrorMessage::WrongType(TypeId::Int32, TypeId::Float32))),
+        verifier.verify()
+    );
+}
+
+#[test]
+fn test_function_reference3() {
+    let mut function = Function::new(
+        FunctionDeclaration::with_managed("test".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadInt32(1),
+            Instruction::LoadInt32(2),
+            Instruction::CallIndirect,
+            Instruction::Return,
+        ]
+    );
+
+    let binder = Binder::new();
+    let type_storage = TypeStorage::new();
+    let mut verifier = Verifier::new(&binder, &type_storage, &mut function);
+    assert_eq!(
+        Err(VerifyError::with_index(2, VerifyErrorMessage::ExpectedFunctionReference)),
+        verifier.verify()
+    );
+}
+
+#[test]
+fn test_function_reference4() {
+    let signatures = vec![
+        FunctionSignature::new("std.gc.collect".to_owned(), Vec::new()),
+        FunctionSignature::with_class("get".to_owned(), TypeId::Class("Point".to_owned()), Vec::new()),
+    ];
+
+    for signature in signatures {
+        let mut function = Function::new(
+            FunctionDeclaration::with_managed("test".to_owned(), Vec::new(), TypeId::Void),
+            Vec::new(),
+            vec![
+                Instruction::LoadFunction(signature.clone()),
+                Instruction::Return,
+            ]
+        );
+
+        let mut binder = Binder::new();
+        binder.define(FunctionDeclaration::with_managed_member("get".to_owned(), TypeId::Class("Point".to_owned()), Vec::new(), TypeId::Int32));
+
+        let type_storage = TypeStorage::new();
+        let mut verifier = Verifier::new(&binder, &type_storage, &mut function);
+        assert_eq!(
+            Err(VerifyError::with_index(0, VerifyErrorMessage::InvalidFunctionReference(signature))),
+            verifier.verify()
+        );
+    }
+}
+
+#[test]
+fn test_branches1() {
+    let mut function = Function::new(
+        FunctionDeclaration::with_managed("test".to_owned(), Vec::new(), TypeId::Void),
+        vec![TypeId::Int32],
+        vec![
+            Instruction::LoadInt32(1),
+            Instruction::LoadInt32(2),
+            Instruction::BranchNotEqual(5),
+            Instruction::LoadInt32(3),
+            Instruction::StoreLocal(0),
+            Instruction::Return,
+        ]
+    );
+
+    let binder = Binder::new();
+    let type_storage = TypeStorage::new();
+    let mut verifier = Verifier::new(&binder, &type_storage, &mut function);
+    assert_eq!(Ok(()), verifier.verify());
+}
+
+#[test]
+fn test_branches2() {
+    let mut function = Function::new(
+        FunctionDeclaration::with_managed("test".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Int32],
+        vec![
+            Instruction::LoadInt32(1),
+            Instruction::LoadInt32(2),
+            Instruction::BranchNotEqual(6),
+            Instruction::LoadInt32(1337),
+            Instruction::StoreLocal(0),
+            Instruction::Branch(8),
+            Instruction::LoadInt32(4711),
+            Instruction::StoreLocal(0),
+            Instruction::LoadLocal(0),
+            Instruction::Return,
+        ]
+    );
+
+    let binder = Binder::new();
+    let type_storage = TypeStorage::new();
+    let mut verifier = Verifier::new(&binder, &type_storage, &mut function);
+    assert_eq!(Ok(()), verifier.verify());
+}
+
+#[test]
+fn test_branches3() {
+    let mut function = Function::new(
+        FunctionDeclaration::with_managed("test".to_owned(), Vec::new(), TypeId::Void),
+        Vec::new(),
+        vec![
+            Instruction::LoadInt32(1),
+            Instruction::LoadInt32(2),
+            Instruction::LoadInt32(3),
+            Instruction::BranchNotEqual(6),
+            Instruction::LoadInt32(4),
+            Instruction::LoadInt32(5),
+            Instruction::Return,
+        ]
+    );
+
+    let binder = Binder::new();
+    let type_storage = TypeStorage::new();
+    let mut verifier = Verifier::new(&binder, &type_storage, &mut function);
+    assert_eq!(
+        Err(VerifyError::with_index(3, VerifyErrorMessage::BranchDifferentNumberOfOperands(1, 3))),
+        verifier.verify()
+    );
+}
+
+#[test]
+fn test_branches4() {
+    let mut function = Function::new(
+        FunctionDeclaration::with_managed("test".to_owned(), Vec::new(), TypeId::Void),
+        vec![TypeId::Int32],
+        vec![
+            Instruction::LoadInt32(1),
+            Instruction::LoadInt32(2),
+            Instruction::LoadInt32(3),
+            Instruction::BranchNotEqual(5),
+            Instruction::LoadInt32(4),
+            Instruction::Return,
+        ]
+    );
+
+    let binder = Binder::new();
+    let type_storage = TypeStorage::new();
+    let mut verifier = Verifier::new(&binder, &type_storage, &mut function);
+    assert_eq!(
+        Err(VerifyError::with_index(3, VerifyErrorMessage::BranchDifferentNumberOfOperands(1, 2))),
+        verifier.verify()
+    );
+}
+
+#[test]
+fn test_branches5() {
+    let mut function = Function::new(
+        FunctionDeclaration::with_managed("test".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Int32],
+        vec![
+            Instruction::LoadInt32(1),
+            Instruction::LoadInt32(2),
+            Instruction::BranchLessThanOrEqual(6),
+            Instruction::LoadInt32(1337),
+            Instruction::StoreLocal(0),
+            Instruction::Branch(8),
+            Instruction::LoadInt32(4711),
+            Instruction::StoreLocal(0),
+            Instruction::LoadLocal(0),
+            Instruction::Return,
+        ]
+    );
+
+    let binder = Binder::new();
+    let type_storage = TypeStorage::new();
+    let mut verifier = Verifier::new(&binder, &type_storage, &mut function);
+    assert_eq!(Ok(()), verifier.verify());
+}
+
+#[test]
+fn test_branches6() {
+    let mut function = Function::new(
+        FunctionDeclaration::with_managed("test".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Array(Box::new(TypeId::Int32))],
+        vec![
+            Instruction::LoadLocal(0),
+            Instruction::LoadLocal(0),
+            Instruction::BranchLessThanOrEqual(6),
+            Instruction::LoadInt32(1337),
+            Instruction::StoreLocal(0),
+            Instruction::Branch(8),
+            Instruction::LoadInt32(4711),
+            Instruction::StoreLocal(0),
+            Instruction::LoadLocal(0),
+            Instruction::Return,
+        ]
+    );
+
+    let binder = Binder::new();
+    let type_storage = TypeStorage::new();
+    let mut verifier = Verifier::new(&binder, &type_storage, &mut function);
+    assert_eq!(
+        Err(VerifyError::with_index(2, VerifyErrorMessage::ExpectedComparableType)),
+        verifier.verify()
+    );
+}
+
+#[test]
+fn test_null1() {
+    let mut function = Function::new(
+        FunctionDeclaration::with_managed("test".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Array(Box::new(TypeId::Int32))],
+        vec![
+            Instruction::LoadNull(TypeId::Array(Box::new(TypeId::Int32))),
+            Instruction::StoreLocal(0),
+            Instruction::LoadInt32(4711),
+            Instruction::Return,
+        ]
+    );
+
+    let binder = Binder::new();
+    let type_storage = TypeStorage::new();
+    let mut verifier = Verifier::new(&binder, &type_storage, &mut function);
+    assert_eq!(Ok(()), verifier.verify());
+
+    assert!(function.instruction_operand_types(1)[0].is_reference());
+}
+
+#[test]
+fn test_null2() {
+    let mut function = Function::new(
+        FunctionDeclaration::with_managed("test".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadNull(TypeId::Array(Box::new(TypeId::Int32))),
+            Instruction::LoadInt32(1000),
+            Instruction::LoadElement(TypeId::Int32),
+            Instruction::Return,
+        ]
+    );
+
+    let binder = Binder::new();
+    let type_storage = TypeStorage::new();
+    let mut verifier = Verifier::new(&binder, &type_storage, &mut function);
+    assert_eq!(Ok(()), verifier.verify());
+
+    assert!(function.instruction_operand_types(2)[0].is_reference());
+}
+
+#[test]
+fn test_null3() {
+    let mut function = Function::new(
+        FunctionDeclaration::with_managed("test".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadNull(TypeId::Array(Box::new(TypeId::Int32))),
+            Instruction::LoadInt32(1000),
+            Instruction::LoadInt32(4711),
+            Instruction::StoreElement(TypeId::Int32),
+            Instruction::LoadInt32(0),
+            Instruction::Return,
+        ]
+    );
+
+    let binder = Binder::new();
+    let type_storage = TypeStorage::new();
+    let mut verifier = Verifier::new(&binder, &type_storage, &mut function);
+    assert_eq!(Ok(()), verifier.verify());
+
+    assert!(function.instruction_operand_types(3)[0].is_reference());
+}
+
+#[test]
+fn test_class1() {
+    let mut function = Function::new(
+        FunctionDeclaration::with_managed("test".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::NewObject("Point".to_owned()),
+            Instruction::LoadField("Point".to_owned(), "x".to_owned()),
+            Instruction::Return,
+        ]
+    );
+
+    let binder = Binder::new();
+    let mut type_storage = TypeStorage::new();
+    type_storage.add_class(Class::new(
+        "Point".to_owned(),
+        vec![
+            Field::new("x".to_owned(), TypeId::Int32),
+            Field::new("y".to_owned(), TypeId::Int32)
+        ]
+    )).unwrap();
+
+    let mut verifier = Verifier::new(&binder, &type_storage, &mut function);
+    assert_eq!(Ok(()), verifier.verify());
+}
+
+#[test]
+fn test_class2() {
+    let mut function = Function::new(
+        FunctionDeclaration::with_managed("test".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::NewObject("Point".to_owned()),
+            Instruction::LoadInt32(1337),
+            Instruction::StoreField("Point".to_owned(), "x".to_owned()),
+            Instruction::LoadInt32(0),
+            Instruction::Return,
+        ]
+    );
+
+    let binder = Binder::new();
+    let mut type_storage = TypeStorage::new();
+    type_storage.add_class(Class::new(
+        "Point".to_owned(),
+        vec![
+            Field::new("x".to_owned(), TypeId::Int32),
+            Field::new("y".to_owned(), TypeId::Int32)
+        ]
+    )).unwrap();
+
+    let mut verifier = Verifier::new(&binder, &type_storage, &mut function);
+    assert_eq!(Ok(()), verifier.verify());
+}
+
+#[test]
+fn test_class3() {
+    let mut function = Function::new(
+        FunctionDeclaration::with_managed("test".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::NewObject("Point".to_owned()),
+            Instruction::LoadField("Point".to_owned(), "z".to_owned()),
+            Instruction::Return,
+        ]
+    );
+
+    let binder = Binder::new();
+    let mut type_storage = TypeStorage::new();
+    type_storage.add_class(Class::new(
+        "Point".to_owned(),
+        vec![
+            Field::new("x".to_owned(), TypeId::Int32),
+            Field::new("y".to_owned(), TypeId::Int32)
+        ]
+    )).unwrap();
+
+    let mut verifier = Verifier::new(&binder, &type_storage, &mut function);
+    assert_eq!(
+        Err(VerifyError::with_index(1, VerifyErrorMessage::FieldNotDefined("Point".to_owned(), "z".to_owned()))),
+        verifier.verify()
+    );
+}
+
+#[test]
+fn test_class_member_function1() {
+    let mut function = Function::new(
+        FunctionDeclaration::with_managed_member("sum".to_owned(), TypeId::Class("Point".to_owned()), Vec::new(), TypeId::Int32),
+        vec![],
+        vec![
+            Instruction::LoadArgument(0),
+            Instruction::LoadField("Point".to_owned(), "x".to_owned()),
+            Instruction::LoadArgument(0),
+            Instruction::LoadField("Point".to_owned(), "y".to_owned()),
+            Instruction::Add,
+            Instruction::Return,
+        ]
+    );
+
+    let binder = Binder::new();
+    let mut type_storage = TypeStorage::new();
+    type_storage.add_class(Class::new(
+        "Point".to_owned(),
+        vec![
+            Field::new("x".to_owned(), TypeId::Int32),
+            Field::new("y".to_owned(), TypeId::Int32)
+        ]
+    )).unwrap();
+
+    let mut verifier = Verifier::new(&binder, &type_storage, &mut function);
+    assert_eq!(Ok(()), verifier.verify());
+}
+
+#[test]
+fn test_class_member_function2() {
+    let mut function = Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Class("Point".to_owned())],
+        vec![
+            Instruction::NewObject("Point".to_owned()),
+            Instruction::CallInstance(FunctionSignature::with_class("sum".to_owned(), TypeId::Class("Point".to_owned()), Vec::new())),
+            Instruction::Return,
+        ]
+    );
+
+    let mut binder = Binder::new();
+    binder.define(FunctionDeclaration::with_managed_member(
+        "sum".to_owned(),
+        TypeId::Class("Point".to_owned()),
+        vec![],
+        TypeId::Int32
+    ));
+
+    let mut type_storage = TypeStorage::new();
+    type_storage.add_class(Class::new(
+        "Point".to_owned(),
+        vec![
+            Field::new("x".to_owned(), TypeId::Int32),
+            Field::new("y".to_owned(), TypeId::Int32)
+        ]
+    )).unwrap();
+
+    let mut verifier = Verifier::new(&binder, &type_storage, &mut function);
+    assert_eq!(Ok(()), verifier.verify());
+}
+
+#[test]
+fn test_class_member_function3() {
+    let mut function = Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Class("Point".to_owned())],
+        vec![
+            Instruction::NewObject("Point".to_owned()),
+            Instruction::CallInstance(FunctionSignature::with_class("sum".to_owned(), TypeId::Class("Point3".to_owned()), Vec::new())),
+            Instruction::Return,
+        ]
+    );
+
+    let mut binder = Binder::new();
+    binder.define(FunctionDeclaration::with_managed_member(
+        "sum".to_owned(),
+        TypeId::Class("Point".to_owned()),
+        vec![],
+        TypeId::Int32
+    ));
+
+    binder.define(FunctionDeclaration::with_managed_member(
+        "sum".to_owned(),
+        TypeId::Class("Point3".to_owned()),
+        vec![],
+        TypeId::Int32
+    ));
+
+    let mut type_storage = TypeStorage::new();
+    type_storage.add_class(Class::new(
+        "Point".to_owned(),
+        vec![
+            Field::new("x".to_owned(), TypeId::Int32),
+            Field::new("y".to_owned(), TypeId::Int32)
+        ]
+    )).unwrap();
+
+    type_storage.add_class(Class::new(
+        "Point3".to_owned(),
+        vec![
+            Field::new("x".to_owned(), TypeId::Int32),
+            Field::new("y".to_owned(), TypeId::Int32),
+            Field::new("z".to_owned(), TypeId::Int32)
+        ]
+    )).unwrap();
+
+    let mut verifier = Verifier::new(&binder, &type_storage, &mut function);
+    assert_eq!(
+        Err(VerifyError::with_index(1, VerifyErrorMessage::WrongType(TypeId::Class("Point3".to_owned()), TypeId::Class("Point".to_owned())))),
+        verifier.verify()
+    );
+}
+
+#[test]
+fn test_inheritance1() {
+    let mut function = Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Class("Point".to_owned())],
+        vec![
+            Instruction::NewObject("Point3".to_owned()),
+            Instruction::StoreLocal(0),
+            Instruction::LoadLocal(0),
+            Instruction::LoadField("Point".to_owned(), "x".to_owned()),
+            Instruction::NewObject("Point3".to_owned()),
+            Instruction::CallInstance(FunctionSignature::with_class("sum".to_owned(), TypeId::Class("Point3".to_owned()), Vec::new())),
+            Instruction::Add,
+            Instruction::Return,
+        ]
+    );
+
+    let mut binder = Binder::new();
+    binder.define(FunctionDeclaration::with_managed_member(
+        "sum".to_owned(),
+        TypeId::Class("Point".to_owned()),
+        vec![],
+        TypeId::Int32
+    ));
+
+    let point = Class::new(
+        "Point".to_owned(),
+        vec![
+            Field::new("x".to_owned(), TypeId::Int32),
+            Field::new("y".to_owned(), TypeId::Int32)
+        ]
+    );
+    let point3 = Class::with_base("Point3".to_owned(), &point, vec![Field::new("z".to_owned(), TypeId::Int32)]);
+
+    let mut type_storage = TypeStorage::new();
+    type_storage.add_class(point).unwrap();
+    type_storage.add_class(point3).unwrap();
+
+    let mut verifier = Verifier::new(&binder, &type_storage, &mut function);
+    assert_eq!(Ok(()), verifier.verify());
+}
+
+#[test]
+fn test_inheritance2() {
+    let mut function = Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Class("Point3".to_owned())],
+        vec![
+            Instruction::NewObject("Point".to_owned()),
+            Instruction::StoreLocal(0),
+            Instruction::LoadInt32(0),
+            Instruction::Return,
+        ]
+    );
+
+    let binder = Binder::new();
+    let point = Class::new("Point".to_owned(), vec![Field::new("x".to_owned(), TypeId::Int32)]);
+    let point3 = Class::with_base("Point3".to_owned(), &point, Vec::new());
+
+    let mut type_storage = TypeStorage::new();
+    type_storage.add_class(point).unwrap();
+    type_storage.add_class(point3).unwrap();
+
+    let mut verifier = Verifier::new(&binder, &type_storage, &mut function);
+    assert_eq!(
+        Err(VerifyError::with_index(1, VerifyErrorMessage::WrongType(TypeId::Class("Point3".to_owned()), TypeId::Class("Point".to_owned())))),
+        verifier.verify()
+    );
+}
+
+#[test]
+fn test_cast1() {
+    let mut function = Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), vec![TypeId::Class("Point".to_owned())], TypeId::Int32),
+        vec![TypeId::Class("Point3".to_owned()), TypeId::Bool],
+        vec![
+            Instruction::LoadArgument(0),
+            Instruction::CastClass("Point3".to_owned()),
+            Instruction::StoreLocal(0),
+            Instruction::LoadArgument(0),
+            Instruction::IsInstance("Point3".to_owned()),
+            Instruction::StoreLocal(1),
+            Instruction::LoadInt32(0),
+            Instruction::Return,
+        ]
+    );
+
+    let binder = Binder::new();
+    let point = Class::new("Point".to_owned(), vec![Field::new("x".to_owned(), TypeId::Int32)]);
+    let point3 = Class::with_base("Point3".to_owned(), &point, Vec::new());
+
+    let mut type_storage = TypeStorage::new();
+    type_storage.add_class(point).unwrap();
+    type_storage.add_class(point3).unwrap();
+
+    let mut verifier = Verifier::new(&binder, &type_storage, &mut function);
+    assert_eq!(Ok(()), verifier.verify());
+}
+
+#[test]
+fn test_cast2() {
+    let mut function = Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
+        vec![
+            Instruction::LoadInt32(0),
+            Instruction::CastClass("Point".to_owned()),
+            Instruction::Pop,
+            Instruction::LoadInt32(0),
+            Instruction::Return,
+        ]
+    );
+
+    let binder = Binder::new();
+    let mut type_storage = TypeStorage::new();
+    type_storage.add_class(Class::new("Point".to_owned(), Vec::new())).unwrap();
+
+    let mut verifier = Verifier::new(&binder, &type_storage, &mut function);
+    assert_eq!(Err(VerifyError::with_index(1, VerifyErrorMessage::ExpectedClassReference)), verifier.verify());
+}
+
+#[test]
+fn test_interface1() {
+    let mut function = Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Class("Shape".to_owned())],
+        vec![
+            Instruction::NewObject("Circle".to_owned()),
+            Instruction::StoreLocal(0),
+            Instruction::LoadLocal(0),
+            Instruction::CallInstance(FunctionSignature::with_class("area".to_owned(), TypeId::Class("Shape".to_owned()), Vec::new())),
+            Instruction::Return,
+        ]
+    );
+
+    let mut binder = Binder::new();
+    let shape = Class::new_interface(
+        "Shape".to_owned(),
+        vec![FunctionDeclaration::with_interface_member("area".to_owned(), TypeId::Class("Shape".to_owned()), Vec::new(), TypeId::Int32)]
+    );
+    binder.define(shape.interface_functions().unwrap()[0].clone());
+
+    let mut circle = Class::new("Circle".to_owned(), Vec::new());
+    circle.add_interface(&shape);
+
+    let mut type_storage = TypeStorage::new();
+    type_storage.add_class(shape).unwrap();
+    type_storage.add_class(circle).unwrap();
+
+    let mut verifier = Verifier::new(&binder, &type_storage, &mut function);
+    assert_eq!(Ok(()), verifier.verify());
+}
+
+#[test]
+fn test_interface2() {
+    let mut function = Function::new(
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Class("Shape".to_owned())],
+        vec![
+            Instruction::NewObject("Shape".to_owned()),
+            Instruction::StoreLocal(0),
+            Instruction::LoadInt32(0),
+            Instruction::Return,
+        ]
+    );
+
+    let binder = Binder::new();
+    let mut type_storage = TypeStorage::new();
+    type_storage.add_class(Class::new_interface("Shape".to_owned(), Vec::new())).unwrap();
+
+    let mut verifier = Verifier::new(&binder, &type_storage, &mut function);
+    assert_eq!(
+        Err(VerifyError::with_index(0, VerifyErrorMessage::CannotCreateInterface("Shape".to_owned()))),
+        verifier.verify()
+    );
 }
 
 #[test]
-fn test_branches3() {
+fn test_constructor1() {
     let mut function = Function::new(
-        FunctionDeclaration::with_managed("test".to_owned(), Vec::new(), TypeId::Void),
-        Vec::new(),
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Class("Point".to_owned())],
         vec![
             Instruction::LoadInt32(1),
-            Instruction::LoadInt32(2),
-            Instruction::LoadInt32(3),
-            Instruction::BranchNotEqual(6),
-            Instruction::LoadInt32(4),
-            Instruction::LoadInt32(5),
+            Instruction::LoadFloat32(2.0),
+            Instruction::NewObjectWithConstructor("Point".to_owned(), vec![TypeId::Int32, TypeId::Int32]),
+            Instruction::StoreLocal(0),
+            Instruction::LoadInt32(0),
             Instruction::Return,
         ]
     );
 
-    let binder = Binder::new();
-    let type_storage = TypeStorage::new();
+    let mut binder = Binder::new();
+    binder.define(FunctionDeclaration::with_constructor(TypeId::Class("Point".to_owned()), vec![TypeId::Int32, TypeId::Int32]));
+
+    let mut type_storage = TypeStorage::new();
+    type_storage.add_class(Class::new("Point".to_owned(), Vec::new())).unwrap();
+
     let mut verifier = Verifier::new(&binder, &type_storage, &mut function);
     assert_eq!(
-        Err(VerifyError::with_index(3, VerifyErrorMessage::BranchDifferentNumberOfOperands(1, 3))),
+        Err(VerifyError::with_index(2, VerifyErrorMessage::WrongType(TypeId::Int32, TypeId::Float32))),
         verifier.verify()
     );
 }
 
 #[test]
-fn test_branches4() {
+fn test_constructor2() {
     let mut function = Function::new(
-        FunctionDeclaration::with_managed("test".to_owned(), Vec::new(), TypeId::Void),
-        vec![TypeId::Int32],
+        FunctionDeclaration::with_managed_member(crate::model::function::CONSTRUCTOR_NAME.to_owned(), TypeId::Class("Point".to_owned()), Vec::new(), TypeId::Int32),
+        Vec::new(),
         vec![
-            Instruction::LoadInt32(1),
-            Instruction::LoadInt32(2),
-            Instruction::LoadInt32(3),
-            Instruction::BranchNotEqual(5),
-            Instruction::LoadInt32(4),
+            Instruction::LoadInt32(0),
             Instruction::Return,
         ]
     );
 
     let binder = Binder::new();
-    let type_storage = TypeStorage::new();
+    let mut type_storage = TypeStorage::new();
+    type_storage.add_class(Class::new("Point".to_owned(), Vec::new())).unwrap();
+
     let mut verifier = Verifier::new(&binder, &type_storage, &mut function);
     assert_eq!(
-        Err(VerifyError::with_index(3, VerifyErrorMessage::BranchDifferentNumberOfOperands(1, 2))),
+        Err(VerifyError::new(VerifyErrorMessage::WrongType(TypeId::Void, TypeId::Int32))),
         verifier.verify()
     );
 }
 
 #[test]
-fn test_branches5() {
+fn test_static_field1() {
     let mut function = Function::new(
-        FunctionDeclaration::with_managed("test".to_owned(), Vec::new(), TypeId::Int32),
-        vec![TypeId::Int32],
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
         vec![
-            Instruction::LoadInt32(1),
-            Instruction::LoadInt32(2),
-            Instruction::BranchLessThanOrEqual(6),
-            Instruction::LoadInt32(1337),
-            Instruction::StoreLocal(0),
-            Instruction::Branch(8),
-            Instruction::LoadInt32(4711),
-            Instruction::StoreLocal(0),
-            Instruction::LoadLocal(0),
+            Instruction::LoadFloat32(1.0),
+            Instruction::StoreStaticField("Counter".to_owned(), "count".to_owned()),
+            Instruction::LoadInt32(0),
             Instruction::Return,
         ]
     );
 
     let binder = Binder::new();
-    let type_storage = TypeStorage::new();
+    let mut type_storage = TypeStorage::new();
+    let mut class = Class::new("Counter".to_owned(), Vec::new());
+    class.add_static_field(Field::new("count".to_owned(), TypeId::Int32));
+    type_storage.add_class(class).unwrap();
+
     let mut verifier = Verifier::new(&binder, &type_storage, &mut function);
-    assert_eq!(Ok(()), verifier.verify());
+    assert_eq!(
+        Err(VerifyError::with_index(1, VerifyErrorMessage::WrongType(TypeId::Int32, TypeId::Float32))),
+        verifier.verify()
+    );
 }
 
 #[test]
-fn test_branches6() {
+fn test_static_field2() {
     let mut function = Function::new(
-        FunctionDeclaration::with_managed("test".to_owned(), Vec::new(), TypeId::Int32),
-        vec![TypeId::Array(Box::new(TypeId::Int32))],
+        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
         vec![
-            Instruction::LoadLocal(0),
-            Instruction::LoadLocal(0),
-            Instruction::BranchLessThanOrEqual(6),
-            Instruction::LoadInt32(1337),
-            Instruction::StoreLocal(0),
-            Instruction::Branch(8),
-            Instruction::LoadInt32(4711),
-            Instruction::StoreLocal(0),
-            Instruction::LoadLocal(0),
+            Instruction::LoadStaticField("Counter".to_owned(), "count".to_owned()),
             Instruction::Return,
         ]
     );
 
     let binder = Binder::new();
-    let type_storage = TypeStorage::new();
+    let mut type_storage = TypeStorage::new();
+    type_storage.add_class(Class::new("Counter".to_owned(), vec![Field::new("count".to_owned(), TypeId::Int32)])).unwrap();
+
     let mut verifier = Verifier::new(&binder, &type_storage, &mut function);
     assert_eq!(
-        Err(VerifyError::with_index(2, VerifyErrorMessage::ExpectedComparableType)),
+        Err(VerifyError::with_index(0, VerifyErrorMessage::FieldNotDefined("Counter".to_owned(), "count".to_owned()))),
         verifier.verify()
     );
 }
 
 #[test]
-fn test_null1() {
+fn test_exception1() {
     let mut function = Function::new(
         FunctionDeclaration::with_managed("test".to_owned(), Vec::new(), TypeId::Int32),
-        vec![TypeId::Array(Box::new(TypeId::Int32))],
+        vec![TypeId::Class("std.Exception".to_owned())],
         vec![
-            Instruction::LoadNull(TypeId::Array(Box::new(TypeId::Int32))),
+            Instruction::BeginTry,
+            Instruction::LoadNull(TypeId::Class("std.Exception".to_owned())),
+            Instruction::Throw,
+            Instruction::EndTry,
+            Instruction::BeginCatch("std.Exception".to_owned()),
             Instruction::StoreLocal(0),
-            Instruction::LoadInt32(4711),
+            Instruction::EndCatch,
+            Instruction::BeginFinally,
+            Instruction::EndFinally,
+            Instruction::LoadInt32(0),
             Instruction::Return,
         ]
     );
 
     let binder = Binder::new();
-    let type_storage = TypeStorage::new();
+    let mut type_storage = TypeStorage::new();
+    type_storage.add_class(Class::new("std.Exception".to_owned(), vec![])).unwrap();
+
     let mut verifier = Verifier::new(&binder, &type_storage, &mut function);
     assert_eq!(Ok(()), verifier.verify());
-
-    assert!(function.instruction_operand_types(1)[0].is_reference());
+    assert_eq!(&vec![TypeId::Class("std.Exception".to_owned())], function.instruction_operand_types(5));
+    assert_eq!(1, function.protected_regions().len());
+    assert_eq!(8, function.protected_regions()[0].end());
 }
 
 #[test]
-fn test_null2() {
+fn test_exception2() {
     let mut function = Function::new(
         FunctionDeclaration::with_managed("test".to_owned(), Vec::new(), TypeId::Int32),
         Vec::new(),
         vec![
-            Instruction::LoadNull(TypeId::Array(Box::new(TypeId::Int32))),
-            Instruction::LoadInt32(1000),
-            Instruction::LoadElement(TypeId::Int32),
+            Instruction::LoadInt32(1),
+            Instruction::BeginTry,
+            Instruction::EndTry,
+            Instruction::BeginFinally,
+            Instruction::EndFinally,
             Instruction::Return,
         ]
     );
@@ -967,21 +2606,23 @@ fn test_null2() {
     let binder = Binder::new();
     let type_storage = TypeStorage::new();
     let mut verifier = Verifier::new(&binder, &type_storage, &mut function);
-    assert_eq!(Ok(()), verifier.verify());
-
-    assert!(function.instruction_operand_types(2)[0].is_reference());
+    assert_eq!(
+        Err(VerifyError::with_index(1, VerifyErrorMessage::ExpectedEmptyOperandStack)),
+        verifier.verify()
+    );
 }
 
 #[test]
-fn test_null3() {
+fn test_exception3() {
     let mut function = Function::new(
         FunctionDeclaration::with_managed("test".to_owned(), Vec::new(), TypeId::Int32),
         Vec::new(),
         vec![
-            Instruction::LoadNull(TypeId::Array(Box::new(TypeId::Int32))),
-            Instruction::LoadInt32(1000),
-            Instruction::LoadInt32(4711),
-            Instruction::StoreElement(TypeId::Int32),
+            Instruction::BeginTry,
+            Instruction::Branch(5),
+            Instruction::EndTry,
+            Instruction::BeginFinally,
+            Instruction::EndFinally,
             Instruction::LoadInt32(0),
             Instruction::Return,
         ]
@@ -990,204 +2631,158 @@ fn test_null3() {
     let binder = Binder::new();
     let type_storage = TypeStorage::new();
     let mut verifier = Verifier::new(&binder, &type_storage, &mut function);
-    assert_eq!(Ok(()), verifier.verify());
-
-    assert!(function.instruction_operand_types(3)[0].is_reference());
+    assert_eq!(
+        Err(VerifyError::with_index(1, VerifyErrorMessage::BranchCrossesProtectedRegion)),
+        verifier.verify()
+    );
 }
 
 #[test]
-fn test_class1() {
+fn test_exception4() {
     let mut function = Function::new(
         FunctionDeclaration::with_managed("test".to_owned(), Vec::new(), TypeId::Int32),
         Vec::new(),
         vec![
-            Instruction::NewObject("Point".to_owned()),
-            Instruction::LoadField("Point".to_owned(), "x".to_owned()),
+            Instruction::BeginTry,
+            Instruction::LoadInt32(0),
+            Instruction::Return,
+            Instruction::EndTry,
+            Instruction::BeginFinally,
+            Instruction::EndFinally,
+            Instruction::LoadInt32(0),
             Instruction::Return,
         ]
     );
 
     let binder = Binder::new();
-    let mut type_storage = TypeStorage::new();
-    type_storage.add_class(Class::new(
-        "Point".to_owned(),
-        vec![
-            Field::new("x".to_owned(), TypeId::Int32),
-            Field::new("y".to_owned(), TypeId::Int32)
-        ]
-    ));
-
+    let type_storage = TypeStorage::new();
     let mut verifier = Verifier::new(&binder, &type_storage, &mut function);
-    assert_eq!(Ok(()), verifier.verify());
+    assert_eq!(
+        Err(VerifyError::with_index(2, VerifyErrorMessage::ReturnInsideFinallyRegion)),
+        verifier.verify()
+    );
 }
 
 #[test]
-fn test_class2() {
+fn test_exception5() {
     let mut function = Function::new(
         FunctionDeclaration::with_managed("test".to_owned(), Vec::new(), TypeId::Int32),
         Vec::new(),
         vec![
-            Instruction::NewObject("Point".to_owned()),
-            Instruction::LoadInt32(1337),
-            Instruction::StoreField("Point".to_owned(), "x".to_owned()),
+            Instruction::LoadInt32(0),
+            Instruction::Throw,
             Instruction::LoadInt32(0),
             Instruction::Return,
         ]
     );
 
     let binder = Binder::new();
-    let mut type_storage = TypeStorage::new();
-    type_storage.add_class(Class::new(
-        "Point".to_owned(),
-        vec![
-            Field::new("x".to_owned(), TypeId::Int32),
-            Field::new("y".to_owned(), TypeId::Int32)
-        ]
-    ));
-
+    let type_storage = TypeStorage::new();
     let mut verifier = Verifier::new(&binder, &type_storage, &mut function);
-    assert_eq!(Ok(()), verifier.verify());
+    assert_eq!(
+        Err(VerifyError::with_index(1, VerifyErrorMessage::ExpectedClassReference)),
+        verifier.verify()
+    );
 }
 
 #[test]
-fn test_class3() {
+fn test_exception6() {
     let mut function = Function::new(
         FunctionDeclaration::with_managed("test".to_owned(), Vec::new(), TypeId::Int32),
         Vec::new(),
         vec![
-            Instruction::NewObject("Point".to_owned()),
-            Instruction::LoadField("Point".to_owned(), "z".to_owned()),
+            Instruction::BeginTry,
+            Instruction::EndTry,
+            Instruction::LoadInt32(0),
             Instruction::Return,
         ]
     );
 
     let binder = Binder::new();
-    let mut type_storage = TypeStorage::new();
-    type_storage.add_class(Class::new(
-        "Point".to_owned(),
-        vec![
-            Field::new("x".to_owned(), TypeId::Int32),
-            Field::new("y".to_owned(), TypeId::Int32)
-        ]
-    ));
-
+    let type_storage = TypeStorage::new();
     let mut verifier = Verifier::new(&binder, &type_storage, &mut function);
     assert_eq!(
-        Err(VerifyError::with_index(1, VerifyErrorMessage::FieldNotDefined("Point".to_owned(), "z".to_owned()))),
+        Err(VerifyError::with_index(2, VerifyErrorMessage::InvalidProtectedRegion)),
         verifier.verify()
     );
 }
 
 #[test]
-fn test_class_member_function1() {
+fn test_struct1() {
     let mut function = Function::new(
-        FunctionDeclaration::with_managed_member("sum".to_owned(), TypeId::Class("Point".to_owned()), Vec::new(), TypeId::Int32),
-        vec![],
+        FunctionDeclaration::with_managed("test".to_owned(), Vec::new(), TypeId::Int32),
+        vec![TypeId::Struct("Point".to_owned())],
         vec![
-            Instruction::LoadArgument(0),
+            Instruction::NewObject("Point".to_owned()),
+            Instruction::LoadInt32(1),
+            Instruction::StoreField("Point".to_owned(), "x".to_owned()),
+            Instruction::StoreLocal(0),
+            Instruction::LoadLocal(0),
             Instruction::LoadField("Point".to_owned(), "x".to_owned()),
-            Instruction::LoadArgument(0),
-            Instruction::LoadField("Point".to_owned(), "y".to_owned()),
-            Instruction::Add,
             Instruction::Return,
         ]
     );
 
     let binder = Binder::new();
     let mut type_storage = TypeStorage::new();
-    type_storage.add_class(Class::new(
+    type_storage.add_class(Class::new_struct(
         "Point".to_owned(),
         vec![
             Field::new("x".to_owned(), TypeId::Int32),
-            Field::new("y".to_owned(), TypeId::Int32)
+            Field::new("y".to_owned(), TypeId::Float64)
         ]
-    ));
+    )).unwrap();
 
     let mut verifier = Verifier::new(&binder, &type_storage, &mut function);
     assert_eq!(Ok(()), verifier.verify());
+    assert_eq!(16, function.struct_slot_size());
+    assert_eq!(&vec![TypeId::Struct("Point".to_owned())], function.instruction_operand_types(3));
 }
 
 #[test]
-fn test_class_member_function2() {
+fn test_struct2() {
     let mut function = Function::new(
-        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
-        vec![TypeId::Class("Point".to_owned())],
+        FunctionDeclaration::with_managed("test".to_owned(), Vec::new(), TypeId::Int32),
+        Vec::new(),
         vec![
             Instruction::NewObject("Point".to_owned()),
-            Instruction::CallInstance(FunctionSignature::with_class("sum".to_owned(), TypeId::Class("Point".to_owned()), Vec::new())),
+            Instruction::LoadNull(TypeId::Struct("Point".to_owned())),
+            Instruction::CompareEqual,
+            Instruction::Pop,
+            Instruction::LoadInt32(0),
             Instruction::Return,
         ]
     );
 
-    let mut binder = Binder::new();
-    binder.define(FunctionDeclaration::with_managed_member(
-        "sum".to_owned(),
-        TypeId::Class("Point".to_owned()),
-        vec![],
-        TypeId::Int32
-    ));
-
+    let binder = Binder::new();
     let mut type_storage = TypeStorage::new();
-    type_storage.add_class(Class::new(
-        "Point".to_owned(),
-        vec![
-            Field::new("x".to_owned(), TypeId::Int32),
-            Field::new("y".to_owned(), TypeId::Int32)
-        ]
-    ));
+    type_storage.add_class(Class::new_struct("Point".to_owned(), vec![Field::new("x".to_owned(), TypeId::Int32)])).unwrap();
 
     let mut verifier = Verifier::new(&binder, &type_storage, &mut function);
-    assert_eq!(Ok(()), verifier.verify());
+    assert_eq!(
+        Err(VerifyError::with_index(1, VerifyErrorMessage::InvalidStructUsage(TypeId::Struct("Point".to_owned())))),
+        verifier.verify()
+    );
 }
 
 #[test]
-fn test_class_member_function3() {
+fn test_struct3() {
     let mut function = Function::new(
-        FunctionDeclaration::with_managed("main".to_owned(), Vec::new(), TypeId::Int32),
-        vec![TypeId::Class("Point".to_owned())],
+        FunctionDeclaration::with_managed("test".to_owned(), Vec::new(), TypeId::Struct("Point".to_owned())),
+        Vec::new(),
         vec![
             Instruction::NewObject("Point".to_owned()),
-            Instruction::CallInstance(FunctionSignature::with_class("sum".to_owned(), TypeId::Class("Point3".to_owned()), Vec::new())),
             Instruction::Return,
         ]
     );
 
-    let mut binder = Binder::new();
-    binder.define(FunctionDeclaration::with_managed_member(
-        "sum".to_owned(),
-        TypeId::Class("Point".to_owned()),
-        vec![],
-        TypeId::Int32
-    ));
-
-    binder.define(FunctionDeclaration::with_managed_member(
-        "sum".to_owned(),
-        TypeId::Class("Point3".to_owned()),
-        vec![],
-        TypeId::Int32
-    ));
-
+    let binder = Binder::new();
     let mut type_storage = TypeStorage::new();
-    type_storage.add_class(Class::new(
-        "Point".to_owned(),
-        vec![
-            Field::new("x".to_owned(), TypeId::Int32),
-            Field::new("y".to_owned(), TypeId::Int32)
-        ]
-    ));
-
-    type_storage.add_class(Class::new(
-        "Point3".to_owned(),
-        vec![
-            Field::new("x".to_owned(), TypeId::Int32),
-            Field::new("y".to_owned(), TypeId::Int32),
-            Field::new("z".to_owned(), TypeId::Int32)
-        ]
-    ));
+    type_storage.add_class(Class::new_struct("Point".to_owned(), vec![Field::new("x".to_owned(), TypeId::Int32)])).unwrap();
 
     let mut verifier = Verifier::new(&binder, &type_storage, &mut function);
     assert_eq!(
-        Err(VerifyError::with_index(1, VerifyErrorMessage::WrongType(TypeId::Class("Point3".to_owned()), TypeId::Class("Point".to_owned())))),
+        Err(VerifyError::new(VerifyErrorMessage::InvalidStructUsage(TypeId::Struct("Point".to_owned())))),
         verifier.verify()
     );
-}
\ No newline at end of file
+}
@@ -1,14 +1,26 @@
-use std::collections::{HashMap, HashSet};
+#![cfg_attr(test, allow(clippy::bool_assert_comparison))]
+
+use std::collections::HashMap;
 
 use crate::analysis::basic_block::BasicBlock;
 use crate::analysis::control_flow_graph::ControlFlowGraph;
+use crate::mir::{InstructionMIRData, RegisterMIR};
+use crate::mir::compiler::MIRCompilationResult;
+use crate::model::function::Function;
+
+#[cfg(test)]
 use crate::model::binder::Binder;
-use crate::mir::{branches, InstructionMIR, InstructionMIRData, RegisterMIR};
-use crate::mir::compiler::{InstructionMIRCompiler, MIRCompilationResult};
-use crate::model::function::{Function, FunctionDeclaration};
+#[cfg(test)]
+use crate::mir::compiler::InstructionMIRCompiler;
+#[cfg(test)]
+use crate::model::function::FunctionDeclaration;
+#[cfg(test)]
 use crate::model::instruction::Instruction;
+#[cfg(test)]
 use crate::model::typesystem::{TypeId, TypeStorage};
+#[cfg(test)]
 use crate::model::verifier::Verifier;
+#[cfg(test)]
 use crate::model::class::{Field, Class};
 
 pub type RegisterNullStatus = HashMap<RegisterMIR, bool>;
@@ -16,7 +28,7 @@ pub type InstructionsRegisterNullStatus = Vec<RegisterNullStatus>;
 
 pub fn compute(function: &Function,
                compilation_result: &MIRCompilationResult,
-               basic_blocks: &Vec<BasicBlock>,
+               basic_blocks: &[BasicBlock],
                control_flow_graph: &ControlFlowGraph) -> InstructionsRegisterNullStatus {
     // return compilation_result.instructions.iter().map(|_| HashMap::new()).collect();
 
@@ -119,19 +131,59 @@ fn compute_null_check_elision_for_block_internal(function: &Function,
             InstructionMIRData::SubFloat32(_, _, _) => {}
             InstructionMIRData::MultiplyFloat32(_, _, _) => {}
             InstructionMIRData::DivideFloat32(_, _, _) => {}
+            InstructionMIRData::MinFloat32(_, _, _) => {}
+            InstructionMIRData::MaxFloat32(_, _, _) => {}
+            InstructionMIRData::SquareRootFloat32(_, _) => {}
+            InstructionMIRData::RoundFloat32(_, _, _) => {}
+            InstructionMIRData::LoadInt64(_, _) => {}
+            InstructionMIRData::LoadFloat64(_, _) => {}
+            InstructionMIRData::AddInt64(_, _, _) => {}
+            InstructionMIRData::SubInt64(_, _, _) => {}
+            InstructionMIRData::MultiplyInt64(_, _, _) => {}
+            InstructionMIRData::DivideInt64(_, _, _) => {}
+            InstructionMIRData::AddFloat64(_, _, _) => {}
+            InstructionMIRData::SubFloat64(_, _, _) => {}
+            InstructionMIRData::MultiplyFloat64(_, _, _) => {}
+            InstructionMIRData::DivideFloat64(_, _, _) => {}
             InstructionMIRData::AndBoolConstant(_, _, _) => {}
             InstructionMIRData::AndBool(_, _, _) => {}
             InstructionMIRData::OrBool(_, _, _) => {}
             InstructionMIRData::OrBoolConstant(_, _, _) => {}
             InstructionMIRData::NotBool(_, _) => {}
+            InstructionMIRData::RemainderInt32(_, _, _) => {}
+            InstructionMIRData::NegateInt32(_, _) => {}
+            InstructionMIRData::AndInt32(_, _, _) => {}
+            InstructionMIRData::OrInt32(_, _, _) => {}
+            InstructionMIRData::XorInt32(_, _, _) => {}
+            InstructionMIRData::NotInt32(_, _) => {}
+            InstructionMIRData::ShiftLeftInt32(_, _, _) => {}
+            InstructionMIRData::ShiftRightInt32(_, _, _) => {}
+            InstructionMIRData::RemainderInt64(_, _, _) => {}
+            InstructionMIRData::NegateInt64(_, _) => {}
+            InstructionMIRData::AndInt64(_, _, _) => {}
+            InstructionMIRData::OrInt64(_, _, _) => {}
+            InstructionMIRData::XorInt64(_, _, _) => {}
+            InstructionMIRData::NotInt64(_, _) => {}
+            InstructionMIRData::ShiftLeftInt64(_, _, _) => {}
+            InstructionMIRData::ShiftRightInt64(_, _, _) => {}
+            InstructionMIRData::ConvertInt32ToFloat32(_, _) => {}
+            InstructionMIRData::ConvertFloat32ToInt32(_, _) => {}
+            InstructionMIRData::ConvertBoolToInt32(_, _) => {}
+            InstructionMIRData::ConvertInt32ToInt64(_, _) => {}
+            InstructionMIRData::ConvertInt64ToInt32(_, _) => {}
+            InstructionMIRData::ConvertFloat32ToFloat64(_, _) => {}
+            InstructionMIRData::ConvertFloat64ToFloat32(_, _) => {}
+            InstructionMIRData::ConvertInt64ToFloat64(_, _) => {}
+            InstructionMIRData::ConvertFloat64ToInt64(_, _) => {}
             InstructionMIRData::Return(_) => {}
-            InstructionMIRData::Call(_, destination, _) => {
+            InstructionMIRData::Call(_, destination, _) | InstructionMIRData::CallIndirect(_, destination, _, _) => {
                 if let Some(destination) = destination {
                     if destination.value_type.is_reference() {
                         register_is_null.insert(destination.clone(), true);
                     }
                 }
             }
+            InstructionMIRData::LoadFunction(_, _) => {}
             InstructionMIRData::LoadArgument(index, destination) => {
                 if function.declaration().parameters()[*index as usize].is_reference() {
                     if function.declaration().class().is_some() && *index == 0 {
@@ -144,6 +196,9 @@ fn compute_null_check_elision_for_block_internal(function: &Function,
             InstructionMIRData::LoadNull(destination) => {
                 register_is_null.insert(destination.clone(), true);
             }
+            InstructionMIRData::LoadString(destination, _) => {
+                register_is_null.insert(destination.clone(), false);
+            }
             InstructionMIRData::NewArray(_, destination, _) => {
                 register_is_null.insert(destination.clone(), false);
             }
@@ -154,15 +209,48 @@ fn compute_null_check_elision_for_block_internal(function: &Function,
             }
             InstructionMIRData::StoreElement(_, _, _, _) => {}
             InstructionMIRData::LoadArrayLength(_, _) => {}
+            InstructionMIRData::ArrayCopy(_, _, _, _, _, _) => {}
+            InstructionMIRData::ArrayFill(_, _, _) => {}
+            InstructionMIRData::ArrayClone(_, destination, _) => {
+                register_is_null.insert(destination.clone(), false);
+            }
+            InstructionMIRData::ArrayEquals(_, _, _, _) => {}
+            InstructionMIRData::NewArray2D(_, destination, _, _) => {
+                register_is_null.insert(destination.clone(), false);
+            }
+            InstructionMIRData::LoadElement2D(_, destination, _, _, _) => {
+                if destination.value_type.is_reference() {
+                    register_is_null.insert(destination.clone(), true);
+                }
+            }
+            InstructionMIRData::StoreElement2D(_, _, _, _, _) => {}
+            InstructionMIRData::LoadArrayDimension(_, _, _) => {}
             InstructionMIRData::NewObject(_, destination) => {
                 register_is_null.insert(destination.clone(), false);
             }
+            InstructionMIRData::IsInstance(_, _, _) => {}
+            InstructionMIRData::CastClass(_, destination, _) => {
+                register_is_null.insert(destination.clone(), true);
+            }
             InstructionMIRData::LoadField(_, _, destination, _) => {
                 if destination.value_type.is_reference() {
                     register_is_null.insert(destination.clone(), true);
                 }
             }
             InstructionMIRData::StoreField(_, _, _, _) => {}
+            InstructionMIRData::LoadStaticField(_, _, destination) => {
+                if destination.value_type.is_reference() {
+                    register_is_null.insert(destination.clone(), true);
+                }
+            }
+            InstructionMIRData::StoreStaticField(_, _, _) => {}
+            InstructionMIRData::NewStruct(_, _) => {}
+            InstructionMIRData::LoadStructField(_, _, destination, _) => {
+                if destination.value_type.is_reference() {
+                    register_is_null.insert(destination.clone(), true);
+                }
+            }
+            InstructionMIRData::StoreStructField(_, _, _, _, _) => {}
             InstructionMIRData::CallInstance(_, destination, _) => {
                 if let Some(destination) = destination {
                     if destination.value_type.is_reference() {
@@ -176,6 +264,11 @@ fn compute_null_check_elision_for_block_internal(function: &Function,
             InstructionMIRData::Branch(_) => {}
             InstructionMIRData::BranchCondition(_, _, _, _, _) => {}
             InstructionMIRData::Compare(_, _, _, _, _) => {}
+            InstructionMIRData::ExceptionLanding(destination) => {
+                register_is_null.insert(destination.clone(), true);
+            }
+            InstructionMIRData::Throw(_) => {}
+            InstructionMIRData::EndFinally(_) => {}
         }
     }
 
@@ -200,7 +293,7 @@ fn merge_results(mut potentials_instructions: Vec<InstructionsRegisterNullStatus
     let mut final_registers = potentials_register.remove(0);
     for potential_result in potentials_register {
         for (register, &is_null) in &potential_result {
-            let current_is_null = final_registers.get(&register).cloned().unwrap_or(false);
+            let current_is_null = final_registers.get(register).cloned().unwrap_or(false);
             final_registers.insert(
                 register.clone(),
                 is_null || current_is_null
@@ -547,7 +640,7 @@ fn test_member_function1() {
             Field::new("x".to_owned(), TypeId::Int32),
             Field::new("y".to_owned(), TypeId::Int32),
         ]
-    ));
+    )).unwrap();
 
     Verifier::new(&binder, &type_storage, &mut function).verify().unwrap();
 
@@ -574,6 +667,52 @@ fn test_member_function1() {
     assert_eq!(false, result[2][&RegisterMIR::new(1, TypeId::Class("Point".to_owned()))]);
 }
 
+#[test]
+fn test_constructor1() {
+    let mut function = Function::new(
+        FunctionDeclaration::with_managed("test".to_owned(), vec![], TypeId::Int32),
+        vec![TypeId::Class("Point".to_owned())],
+        vec![
+            Instruction::LoadInt32(1000),
+            Instruction::NewObjectWithConstructor("Point".to_owned(), vec![TypeId::Int32]),
+            Instruction::StoreLocal(0),
+            Instruction::LoadLocal(0),
+            Instruction::LoadField("Point".to_owned(), "x".to_owned()),
+            Instruction::Return,
+        ]
+    );
+
+    let mut binder = Binder::new();
+    binder.define(FunctionDeclaration::with_constructor(TypeId::Class("Point".to_owned()), vec![TypeId::Int32]));
+
+    let mut type_storage = TypeStorage::new();
+    type_storage.add_class(Class::new(
+        "Point".to_owned(),
+        vec![
+            Field::new("x".to_owned(), TypeId::Int32)
+        ]
+    )).unwrap();
+
+    Verifier::new(&binder, &type_storage, &mut function).verify().unwrap();
+
+    let mut compiler = InstructionMIRCompiler::new(&type_storage, &binder, &function);
+    compiler.compile(function.instructions());
+    let compilation_result = compiler.done();
+    let basic_blocks = BasicBlock::create_blocks(&compilation_result.instructions);
+
+    let (result, _) = compute_null_check_elision_for_block(&function, &compilation_result, &basic_blocks[0]);
+
+    for instruction in &result {
+        println!("{:?}", instruction);
+    }
+
+    // The constructor is called on the newly allocated object
+    assert_eq!(false, result[2][&RegisterMIR::new(2, TypeId::Class("Point".to_owned()))]);
+
+    assert_eq!(false, result[6][&RegisterMIR::new(0, TypeId::Class("Point".to_owned()))]);
+    assert_eq!(false, result[6][&RegisterMIR::new(1, TypeId::Class("Point".to_owned()))]);
+}
+
 #[test]
 fn test_branches1() {
     let mut function = Function::new(
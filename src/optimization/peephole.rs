@@ -1,15 +1,25 @@
 use std::collections::{HashMap, HashSet};
 use std::iter::FromIterator;
 
+use crate::mir::compiler::MIRCompilationResult;
+use crate::analysis::basic_block::BasicBlock;
+use crate::mir::{InstructionMIRData, RegisterMIR};
+use crate::analysis::determine_instructions_operand_stack;
+
+#[cfg(test)]
 use crate::model::function::{Function, FunctionDeclaration};
+#[cfg(test)]
 use crate::model::instruction::Instruction;
+#[cfg(test)]
 use crate::model::typesystem::{TypeId, TypeStorage};
+#[cfg(test)]
 use crate::model::binder::Binder;
-use crate::mir::compiler::{InstructionMIRCompiler, MIRCompilationResult};
-use crate::analysis::basic_block::BasicBlock;
+#[cfg(test)]
+use crate::mir::compiler::InstructionMIRCompiler;
+#[cfg(test)]
 use crate::model::verifier::Verifier;
-use crate::mir::{InstructionMIR, InstructionMIRData, RegisterMIR};
-use crate::analysis::determine_instructions_operand_stack;
+#[cfg(test)]
+use crate::mir::InstructionMIR;
 
 pub struct PeepholeSettings {
     pub remove_load_local: bool,
@@ -28,6 +38,7 @@ impl Default for PeepholeSettings {
 }
 
 impl PeepholeSettings {
+    #[allow(dead_code)]
     pub fn remove_only_load_local() -> PeepholeSettings {
         PeepholeSettings {
             remove_load_local: true,
@@ -36,6 +47,7 @@ impl PeepholeSettings {
         }
     }
 
+    #[allow(dead_code)]
     pub fn remove_only_store_local() -> PeepholeSettings {
         PeepholeSettings {
             remove_load_local: false,
@@ -44,6 +56,7 @@ impl PeepholeSettings {
         }
     }
 
+    #[allow(dead_code)]
     pub fn remove_only_constants() -> PeepholeSettings {
         PeepholeSettings {
             remove_load_local: false,
@@ -62,7 +75,7 @@ pub fn optimize(compilation_result: &mut MIRCompilationResult,
         remove_unnecessary_load_constant_for_block(compilation_result, &local_registers, block, settings);
     }
 
-    let valid_instructions = HashSet::<usize>::from_iter(BasicBlock::linearize(basic_blocks).into_iter());
+    let valid_instructions = HashSet::<usize>::from_iter(BasicBlock::linearize(basic_blocks));
 
     let mut index = 0;
     compilation_result.instructions.retain(|_| {
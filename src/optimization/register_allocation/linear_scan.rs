@@ -1,26 +1,36 @@
+#![cfg_attr(test, allow(clippy::needless_borrow))]
+
 use std::cmp::Ordering;
 use std::collections::{BTreeSet, HashMap};
 use std::iter::FromIterator;
 
-use crate::analysis::{VirtualRegister, VirtualRegisterType, liveness};
+use crate::analysis::{VirtualRegisterType, liveness};
 use crate::analysis::basic_block::BasicBlock;
 use crate::analysis::control_flow_graph::ControlFlowGraph;
 use crate::analysis::liveness::{LiveInterval};
+use crate::mir::InstructionMIR;
+use crate::mir::compiler::MIRCompilationResult;
+use crate::optimization::register_allocation::{RegisterAllocation, RegisterAllocationSettings};
+
+#[cfg(test)]
 use crate::model::binder::Binder;
-use crate::mir::{branches, InstructionMIR, RegisterMIR};
-use crate::mir::compiler::{InstructionMIRCompiler, MIRCompilationResult};
+#[cfg(test)]
+use crate::mir::compiler::InstructionMIRCompiler;
+#[cfg(test)]
 use crate::model::function::{Function, FunctionDeclaration, FunctionSignature};
+#[cfg(test)]
 use crate::model::instruction::Instruction;
+#[cfg(test)]
 use crate::model::typesystem::{TypeId, TypeStorage};
+#[cfg(test)]
 use crate::model::verifier::Verifier;
-use crate::optimization::register_allocation::{AllocatedRegister, RegisterAllocation, RegisterAllocationSettings};
 
-pub fn allocate(live_intervals: &Vec<LiveInterval>, settings: &RegisterAllocationSettings) -> RegisterAllocation {
+pub fn allocate(live_intervals: &[LiveInterval], settings: &RegisterAllocationSettings) -> RegisterAllocation {
     let mut allocated_registers = HashMap::new();
     let mut spilled_registers = Vec::new();
     let mut free_registers = FreeRegisters::new(settings);
 
-    let mut live_intervals = live_intervals.clone();
+    let mut live_intervals = live_intervals.to_owned();
     live_intervals.sort_by_key(|interval| interval.start);
 
     let mut active = BTreeSet::<LiveIntervalByEndPoint>::new();
@@ -34,7 +44,7 @@ pub fn allocate(live_intervals: &Vec<LiveInterval>, settings: &RegisterAllocatio
 
         let active_of_same_type = active
             .iter()
-            .filter(|register| &register.0.register.register_type == &interval.register.register_type)
+            .filter(|register| register.0.register.register_type == interval.register.register_type)
             .count();
 
         if active_of_same_type == free_registers.max_for_type(&interval.register.register_type) {
@@ -64,9 +74,9 @@ struct FreeRegisters {
 impl FreeRegisters {
     pub fn new(settings: &RegisterAllocationSettings) -> FreeRegisters {
         FreeRegisters {
-            int_registers: BTreeSet::from_iter(0 as u32..settings.num_int_registers as u32),
+            int_registers: BTreeSet::from_iter(0_u32..settings.num_int_registers as u32),
             max_int: settings.num_int_registers,
-            float_registers: BTreeSet::from_iter(0 as u32..settings.num_float_registers as u32),
+            float_registers: BTreeSet::from_iter(0_u32..settings.num_float_registers as u32),
             max_float: settings.num_float_registers
         }
     }
@@ -74,21 +84,26 @@ impl FreeRegisters {
     pub fn max_for_type(&self, register_type: &VirtualRegisterType) -> usize {
         match register_type {
             VirtualRegisterType::Int => self.max_int,
-            VirtualRegisterType::Float => self.max_float
+            VirtualRegisterType::Float => self.max_float,
+            // Structs don't fit in registers, so they are always on the stack
+            VirtualRegisterType::Struct => 0
         }
     }
 
+    #[allow(dead_code)]
     pub fn for_type(&self, register_type: &VirtualRegisterType) -> &BTreeSet<u32> {
         match register_type {
             VirtualRegisterType::Int => &self.int_registers,
-            VirtualRegisterType::Float => &self.float_registers
+            VirtualRegisterType::Float => &self.float_registers,
+            VirtualRegisterType::Struct => unreachable!()
         }
     }
 
     pub fn for_type_mut(&mut self, register_type: &VirtualRegisterType) -> &mut BTreeSet<u32> {
         match register_type {
             VirtualRegisterType::Int => &mut self.int_registers,
-            VirtualRegisterType::Float => &mut self.float_registers
+            VirtualRegisterType::Float => &mut self.float_registers,
+            VirtualRegisterType::Struct => unreachable!()
         }
     }
 
@@ -126,9 +141,7 @@ fn split_at_interval(allocated_registers: &mut HashMap<LiveInterval, u32>,
                      spilled_registers: &mut Vec<LiveInterval>,
                      active: &mut BTreeSet<LiveIntervalByEndPoint>,
                      current_interval: &LiveInterval) {
-    let spill = active.iter()
-        .filter(|register| &register.0.register.register_type == &current_interval.register.register_type)
-        .last();
+    let spill = active.iter().rfind(|register| register.0.register.register_type == current_interval.register.register_type);
 
     if spill.is_none() {
         spilled_registers.push(current_interval.clone());
@@ -138,7 +151,7 @@ fn split_at_interval(allocated_registers: &mut HashMap<LiveInterval, u32>,
     let spill = spill.unwrap().clone();
 
     if spill.0.end > current_interval.end {
-        allocated_registers.insert(current_interval.clone(), allocated_registers[&spill.0].clone());
+        allocated_registers.insert(current_interval.clone(), allocated_registers[&spill.0]);
 
         spilled_registers.push(spill.0.clone());
         allocated_registers.remove(&spill.0);
@@ -166,25 +179,19 @@ impl Eq for LiveIntervalByEndPoint {}
 
 impl PartialOrd for LiveIntervalByEndPoint {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        let mut result = self.0.end.partial_cmp(&other.0.end)?;
-        if result == Ordering::Equal {
-            result = self.0.start.partial_cmp(&other.0.start)?;
-
-            if result == Ordering::Equal {
-                return self.0.register.partial_cmp(&other.0.register);
-            }
-        }
-
-        Some(result)
+        Some(self.cmp(other))
     }
 }
 
 impl Ord for LiveIntervalByEndPoint {
     fn cmp(&self, other: &Self) -> Ordering {
-        self.partial_cmp(&other).unwrap()
+        self.0.end.cmp(&other.0.end)
+            .then_with(|| self.0.start.cmp(&other.0.start))
+            .then_with(|| self.0.register.cmp(&other.0.register))
     }
 }
 
+#[allow(dead_code)]
 fn analyze(compilation_result: &MIRCompilationResult) -> (Vec<BasicBlock>, ControlFlowGraph, Vec<LiveInterval>) {
     let blocks = BasicBlock::create_blocks(&compilation_result.instructions);
     let control_flow_graph = ControlFlowGraph::new(&compilation_result.instructions, &blocks);
@@ -192,7 +199,8 @@ fn analyze(compilation_result: &MIRCompilationResult) -> (Vec<BasicBlock>, Contr
     (blocks, control_flow_graph, live_intervals)
 }
 
-fn print_allocation(instructions: &Vec<InstructionMIR>, live_intervals: &Vec<LiveInterval>, allocation: &RegisterAllocation) {
+#[allow(dead_code)]
+fn print_allocation(instructions: &[InstructionMIR], live_intervals: &Vec<LiveInterval>, allocation: &RegisterAllocation) {
     for (index, instruction) in instructions.iter().enumerate() {
         println!("{}: {:?}", index, instruction);
     }
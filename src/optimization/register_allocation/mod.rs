@@ -4,7 +4,6 @@ use crate::analysis::{VirtualRegister, VirtualRegisterType};
 use crate::analysis::liveness::LiveInterval;
 use crate::compiler::ir::HardwareRegister;
 use crate::mir::RegisterMIR;
-use crate::model::typesystem::TypeId;
 
 pub mod linear_scan;
 
@@ -36,7 +35,7 @@ impl AllocatedRegister {
 
     pub fn hardware_register(&self) -> Option<HardwareRegister> {
         match self {
-            AllocatedRegister::Hardware { register, .. } => Some(register.clone()),
+            AllocatedRegister::Hardware { register, .. } => Some(*register),
             AllocatedRegister::Stack { .. } => None
         }
     }
@@ -52,7 +51,8 @@ impl RegisterAllocation {
         for (live_interval, register_number) in allocated {
             let register = match &live_interval.register.register_type {
                 VirtualRegisterType::Int => HardwareRegister::Int(register_number),
-                VirtualRegisterType::Float => HardwareRegister::Float(register_number)
+                VirtualRegisterType::Float => HardwareRegister::Float(register_number),
+                VirtualRegisterType::Struct => unreachable!()
             };
 
             registers.insert(live_interval.register.clone(), AllocatedRegister::Hardware { register, live_interval });
@@ -67,10 +67,12 @@ impl RegisterAllocation {
         }
     }
 
+    #[allow(dead_code)]
     pub fn num_allocated_registers(&self) -> usize {
         self.registers.values().filter(|register| register.hardware_register().is_some()).count()
     }
 
+    #[allow(dead_code)]
     pub fn num_spilled_registers(&self) -> usize {
         self.registers.values().filter(|register| register.hardware_register().is_none()).count()
     }
@@ -96,6 +98,6 @@ impl RegisterAllocation {
     }
 
     pub fn alive_hardware_registers_at(&self, instruction_index: usize) -> Vec<HardwareRegister> {
-        self.alive_registers_at(instruction_index).iter().map(|(_, register)| register.clone()).collect::<Vec<_>>()
+        self.alive_registers_at(instruction_index).iter().map(|(_, register)| *register).collect::<Vec<_>>()
     }
 }
\ No newline at end of file
@@ -1,6 +1,7 @@
 use std::str::FromStr;
+use std::convert::TryFrom;
 
-use crate::model::function::{Function, FunctionDeclaration, FunctionSignature};
+use crate::model::function::{Function, FunctionDeclaration, FunctionSignature, VirtualModifier, CONSTRUCTOR_NAME};
 use crate::model::typesystem::TypeId;
 use crate::model::instruction::Instruction;
 use crate::model::class::{Class, Field};
@@ -11,18 +12,22 @@ pub enum Token {
     RightCurlyParentheses,
     LeftParentheses,
     RightParentheses,
-    Int32(i32),
-    Float32(f32),
+    Int(i64),
+    Float(f64),
+    String(String),
     Identifier(String),
     DefineNumberOfLocals,
     DefineLocal,
     Function,
     MemberFunction,
     Class,
+    Interface,
+    Struct,
     Colon,
     End
 }
 
+#[allow(dead_code)]
 #[derive(Debug)]
 pub enum ParserError {
     FloatConvertError,
@@ -32,7 +37,12 @@ pub enum ParserError {
     ExpectedFunctionOrClass,
     ExpectedIdentifier,
     ExpectedInt32,
+    ExpectedInt64,
     ExpectedFloat32,
+    ExpectedFloat64,
+    ExpectedString,
+    UnterminatedString,
+    InvalidEscapeCharacter(char),
     ExpectedLeftParentheses,
     ExpectedRightParentheses,
     ExpectedLeftCurlyParentheses,
@@ -41,7 +51,8 @@ pub enum ParserError {
     NotDefinedInstruction(String),
     UndefinedModifier,
     UntypedLocal(u32),
-    ExpectedColon
+    ExpectedColon,
+    MultipleBaseClasses(String)
 }
 
 pub type ParserResult<T> = Result<T, ParserError>;
@@ -57,7 +68,7 @@ pub fn tokenize(text: &str) -> ParserResult<Vec<Token>> {
 
             loop {
                 match char_iterator.peek() {
-                    Some(next) if next.is_alphanumeric() || next == &'_' || next == &'.' || next == &'[' || next == &']' => {
+                    Some(next) if next.is_alphanumeric() || next == &'_' || next == &'.' || next == &'[' || next == &']' || next == &',' => {
                         identifier.push(char_iterator.next().unwrap());
                     }
                     _ => {
@@ -72,6 +83,10 @@ pub fn tokenize(text: &str) -> ParserResult<Vec<Token>> {
                 tokens.push(Token::MemberFunction);
             } else if identifier == "class" {
                 tokens.push(Token::Class);
+            } else if identifier == "interface" {
+                tokens.push(Token::Interface);
+            } else if identifier == "struct" {
+                tokens.push(Token::Struct);
             }  else {
                 tokens.push(Token::Identifier(identifier));
             }
@@ -100,9 +115,9 @@ pub fn tokenize(text: &str) -> ParserResult<Vec<Token>> {
             }
 
             if has_dot {
-                tokens.push(Token::Float32(f32::from_str(&number).map_err(|_err| ParserError::FloatConvertError)?));
+                tokens.push(Token::Float(f64::from_str(&number).map_err(|_err| ParserError::FloatConvertError)?));
             } else {
-                tokens.push(Token::Int32(i32::from_str(&number).map_err(|_err| ParserError::IntConvertError)?));
+                tokens.push(Token::Int(i64::from_str(&number).map_err(|_err| ParserError::IntConvertError)?));
             }
         } else if current == '.' {
             let mut identifier = String::new();
@@ -123,9 +138,39 @@ pub fn tokenize(text: &str) -> ParserResult<Vec<Token>> {
                 tokens.push(Token::DefineNumberOfLocals);
             } else if identifier == ".local" {
                 tokens.push(Token::DefineLocal);
+            } else if identifier == CONSTRUCTOR_NAME {
+                tokens.push(Token::Identifier(identifier));
             } else {
                 return Err(ParserError::UndefinedModifier);
             }
+        } else if current == '"' {
+            let mut string = String::new();
+
+            loop {
+                match char_iterator.next() {
+                    Some('"') => {
+                        break;
+                    }
+                    Some('\\') => {
+                        match char_iterator.next() {
+                            Some('n') => string.push('\n'),
+                            Some('t') => string.push('\t'),
+                            Some('"') => string.push('"'),
+                            Some('\\') => string.push('\\'),
+                            Some(other) => { return Err(ParserError::InvalidEscapeCharacter(other)); }
+                            None => { return Err(ParserError::UnterminatedString); }
+                        }
+                    }
+                    Some(other) => {
+                        string.push(other);
+                    }
+                    None => {
+                        return Err(ParserError::UnterminatedString);
+                    }
+                }
+            }
+
+            tokens.push(Token::String(string));
         } else if current == '(' {
             tokens.push(Token::LeftParentheses);
         } else if current == ')' {
@@ -195,13 +240,33 @@ impl Parser {
                 self.classes.push(class);
                 Ok(())
             }
-            _ => { return Err(ParserError::ExpectedFunctionOrClass); }
+            Token::Interface => {
+                let interface = self.parse_interface()?;
+                self.classes.push(interface);
+                Ok(())
+            }
+            Token::Struct => {
+                let struct_class = self.parse_struct()?;
+                self.classes.push(struct_class);
+                Ok(())
+            }
+            _ => { Err(ParserError::ExpectedFunctionOrClass)}
         }
     }
 
     fn parse_function(&mut self, is_member: bool) -> ParserResult<Function> {
         self.next()?;
 
+        let virtual_modifier = match self.current() {
+            Token::Identifier(modifier) if is_member && modifier == "virtual" => Some(VirtualModifier::Virtual),
+            Token::Identifier(modifier) if is_member && modifier == "override" => Some(VirtualModifier::Override),
+            _ => None
+        };
+
+        if virtual_modifier.is_some() {
+            self.next()?;
+        }
+
         let class_name = if is_member {
             let class_name = self.next_identifier()?;
             self.next_double_colon()?;
@@ -211,27 +276,7 @@ impl Parser {
         };
 
         let name = self.next_identifier()?;
-
-        match self.current() {
-            Token::LeftParentheses => { self.next()?; }
-            _ => { return Err(ParserError::ExpectedLeftParentheses); }
-        }
-
-        let mut parameters = Vec::new();
-        loop {
-            match self.current() {
-                Token::RightParentheses => {
-                    self.next()?;
-                    break;
-                }
-                Token::Identifier(identifier) => {
-                    parameters.push(parse_type(identifier)?);
-                    self.next()?;
-                }
-                _ => { return Err(ParserError::ExpectedRightParentheses); }
-            }
-        }
-
+        let parameters = self.parse_parameters()?;
         let return_type = self.next_type_id()?;
 
         match self.current() {
@@ -278,8 +323,17 @@ impl Parser {
             }
         }
 
-        let function_declaration = match class_name {
-            Some(class_name) => {
+        let function_declaration = match (class_name, virtual_modifier) {
+            (Some(class_name), Some(virtual_modifier)) => {
+                FunctionDeclaration::with_managed_virtual_member(
+                    name,
+                    TypeId::Class(class_name),
+                    parameters,
+                    return_type,
+                    virtual_modifier
+                )
+            }
+            (Some(class_name), None) => {
                 FunctionDeclaration::with_managed_member(
                     name,
                     TypeId::Class(class_name),
@@ -287,7 +341,7 @@ impl Parser {
                     return_type
                 )
             }
-            None => {
+            (None, _) => {
                 FunctionDeclaration::with_managed(
                     name,
                     parameters,
@@ -311,6 +365,10 @@ impl Parser {
                 let reference_type = self.next_type_id()?;
                 Ok(Instruction::LoadNull(reference_type))
             }
+            "ldstr" => {
+                let value = self.next_string()?;
+                Ok(Instruction::LoadString(value))
+            }
             "ldint" => {
                 let value = self.next_i32()?;
                 Ok(Instruction::LoadInt32(value))
@@ -319,6 +377,14 @@ impl Parser {
                 let value = self.next_f32()?;
                 Ok(Instruction::LoadFloat32(value))
             }
+            "ldint64" => {
+                let value = self.next_i64()?;
+                Ok(Instruction::LoadInt64(value))
+            }
+            "ldfloat64" => {
+                let value = self.next_f64()?;
+                Ok(Instruction::LoadFloat64(value))
+            }
             "ldloc" => {
                 let value = self.next_i32()?;
                 Ok(Instruction::LoadLocal(value as u32))
@@ -327,6 +393,9 @@ impl Parser {
                 let value = self.next_i32()?;
                 Ok(Instruction::StoreLocal(value as u32))
             }
+            "dup" => { Ok(Instruction::Duplicate) }
+            "pop" => { Ok(Instruction::Pop) }
+            "swap" => { Ok(Instruction::Swap) }
             "newarr" => {
                 let element_type = self.next_type_id()?;
                 Ok(Instruction::NewArray(element_type))
@@ -340,13 +409,43 @@ impl Parser {
                 Ok(Instruction::StoreElement(element_type))
             }
             "ldlen" => { Ok(Instruction::LoadArrayLength) }
+            "newarr2d" => {
+                let element_type = self.next_type_id()?;
+                Ok(Instruction::NewArray2D(element_type))
+            }
+            "ldelem2d" => {
+                let element_type = self.next_type_id()?;
+                Ok(Instruction::LoadElement2D(element_type))
+            }
+            "stelem2d" => {
+                let element_type = self.next_type_id()?;
+                Ok(Instruction::StoreElement2D(element_type))
+            }
+            "lddim" => {
+                let dimension = self.next_i32()?;
+                Ok(Instruction::LoadArrayDimension(dimension as u32))
+            }
             "add" => { Ok(Instruction::Add) }
             "sub" => { Ok(Instruction::Sub) }
             "mul" => { Ok(Instruction::Multiply) }
             "div" => { Ok(Instruction::Divide) }
+            "rem" => { Ok(Instruction::Remainder) }
+            "neg" => { Ok(Instruction::Negate) }
             "and" => { Ok(Instruction::And) }
             "or" => { Ok(Instruction::Or) }
+            "xor" => { Ok(Instruction::Xor) }
             "not" => { Ok(Instruction::Not) }
+            "shl" => { Ok(Instruction::ShiftLeft) }
+            "shr" => { Ok(Instruction::ShiftRight) }
+            "convinttofloat" => { Ok(Instruction::ConvertInt32ToFloat32) }
+            "convfloattoint" => { Ok(Instruction::ConvertFloat32ToInt32) }
+            "convbooltoint" => { Ok(Instruction::ConvertBoolToInt32) }
+            "convinttoint64" => { Ok(Instruction::ConvertInt32ToInt64) }
+            "convint64toint" => { Ok(Instruction::ConvertInt64ToInt32) }
+            "convfloattofloat64" => { Ok(Instruction::ConvertFloat32ToFloat64) }
+            "convfloat64tofloat" => { Ok(Instruction::ConvertFloat64ToFloat32) }
+            "convint64tofloat64" => { Ok(Instruction::ConvertInt64ToFloat64) }
+            "convfloat64toint64" => { Ok(Instruction::ConvertFloat64ToInt64) }
             "ldarg" => {
                 let argument = self.next_i32()?;
                 Ok(Instruction::LoadArgument(argument as u32))
@@ -381,10 +480,29 @@ impl Parser {
 
                 Ok(Instruction::Call(FunctionSignature::new(call_name, arguments)))
             }
+            "ldfunc" => {
+                let function_name = self.next_identifier()?;
+                let parameters = self.parse_parameters()?;
+                Ok(Instruction::LoadFunction(FunctionSignature::new(function_name, parameters)))
+            }
+            "calli" => { Ok(Instruction::CallIndirect) }
             "ret" => { Ok(Instruction::Return) }
             "newobj" => {
                 let class_type = self.next_identifier()?;
-                Ok(Instruction::NewObject(class_type))
+                if self.current() == &Token::LeftParentheses {
+                    let parameters = self.parse_parameters()?;
+                    Ok(Instruction::NewObjectWithConstructor(class_type, parameters))
+                } else {
+                    Ok(Instruction::NewObject(class_type))
+                }
+            }
+            "isinst" => {
+                let class_type = self.next_identifier()?;
+                Ok(Instruction::IsInstance(class_type))
+            }
+            "castclass" => {
+                let class_type = self.next_identifier()?;
+                Ok(Instruction::CastClass(class_type))
             }
             "ldfield" => {
                 let class_name = self.next_identifier()?;
@@ -402,6 +520,22 @@ impl Parser {
                 let field_name = self.next_identifier()?;
                 Ok(Instruction::StoreField(class_name, field_name))
             }
+            "ldsfield" => {
+                let class_name = self.next_identifier()?;
+
+                self.next_double_colon()?;
+
+                let field_name = self.next_identifier()?;
+                Ok(Instruction::LoadStaticField(class_name, field_name))
+            }
+            "stsfield" => {
+                let class_name = self.next_identifier()?;
+
+                self.next_double_colon()?;
+
+                let field_name = self.next_identifier()?;
+                Ok(Instruction::StoreStaticField(class_name, field_name))
+            }
             "br" => {
                 let target = self.next_i32()? as u32;
                 Ok(Instruction::Branch(target))
@@ -436,14 +570,158 @@ impl Parser {
             "cmpge" => { Ok(Instruction::CompareGreaterThanOrEqual) }
             "cmplt" => { Ok(Instruction::CompareLessThan) }
             "cmple" => { Ok(Instruction::CompareLessThanOrEqual) }
-            _ => { return Err(ParserError::NotDefinedInstruction(identifier.to_owned())); }
+            "try" => { Ok(Instruction::BeginTry) }
+            "endtry" => { Ok(Instruction::EndTry) }
+            "catch" => {
+                let class_type = self.next_identifier()?;
+                Ok(Instruction::BeginCatch(class_type))
+            }
+            "endcatch" => { Ok(Instruction::EndCatch) }
+            "finally" => { Ok(Instruction::BeginFinally) }
+            "endfinally" => { Ok(Instruction::EndFinally) }
+            "throw" => { Ok(Instruction::Throw) }
+            _ => { Err(ParserError::NotDefinedInstruction(identifier.to_owned()))}
         }
     }
 
+    fn parse_parameters(&mut self) -> ParserResult<Vec<TypeId>> {
+        match self.current() {
+            Token::LeftParentheses => { self.next()?; }
+            _ => { return Err(ParserError::ExpectedLeftParentheses); }
+        }
+
+        let mut parameters = Vec::new();
+        loop {
+            match self.current() {
+                Token::RightParentheses => {
+                    self.next()?;
+                    break;
+                }
+                Token::Identifier(identifier) => {
+                    parameters.push(parse_type(identifier)?);
+                    self.next()?;
+                }
+                _ => { return Err(ParserError::ExpectedRightParentheses); }
+            }
+        }
+
+        Ok(parameters)
+    }
+
     fn parse_class(&mut self) -> ParserResult<Class> {
         self.next()?;
         let name = self.next_identifier()?;
 
+        // The base class and the implemented interfaces can be given in any order
+        let mut base_name = None;
+        let mut interface_names = Vec::new();
+        if self.current() == &Token::Colon {
+            self.next()?;
+
+            while let Token::Identifier(_) = self.current() {
+                let supertype_name = self.next_identifier()?;
+                let supertype = self.get_class(&supertype_name)?;
+
+                if supertype.is_interface() {
+                    interface_names.push(supertype_name);
+                } else if base_name.is_none() {
+                    base_name = Some(supertype_name);
+                } else {
+                    return Err(ParserError::MultipleBaseClasses(name));
+                }
+            }
+        }
+
+        match self.current() {
+            Token::LeftCurlyParentheses => { self.next()?; }
+            _ => { return Err(ParserError::ExpectedLeftCurlyParentheses); }
+        }
+
+        let mut fields = Vec::new();
+        let mut static_fields = Vec::new();
+
+        loop {
+            let current = self.current().clone();
+            match current {
+                Token::RightCurlyParentheses => {
+                    self.next()?;
+                    break;
+                }
+                Token::Identifier(modifier) if modifier == "static" => {
+                    self.next()?;
+
+                    let field_name = self.next_identifier()?;
+                    let field_type = self.next_type_id()?;
+                    static_fields.push(self.create_field(field_name, field_type)?);
+                }
+                Token::Identifier(field_name) => {
+                    self.next()?;
+
+                    let field_type = self.next_type_id()?;
+                    fields.push(self.create_field(field_name, field_type)?);
+                }
+                _ => { return Err(ParserError::ExpectedIdentifier); }
+            }
+        }
+
+        let mut class = match base_name {
+            Some(base_name) => Class::with_base(name, self.get_class(&base_name)?, fields),
+            None => Class::new(name, fields)
+        };
+
+        for interface_name in interface_names {
+            let interface = self.get_class(&interface_name)?;
+            class.add_interface(interface);
+        }
+
+        for field in static_fields {
+            class.add_static_field(field);
+        }
+
+        Ok(class)
+    }
+
+    fn parse_interface(&mut self) -> ParserResult<Class> {
+        self.next()?;
+        let name = self.next_identifier()?;
+
+        match self.current() {
+            Token::LeftCurlyParentheses => { self.next()?; }
+            _ => { return Err(ParserError::ExpectedLeftCurlyParentheses); }
+        }
+
+        let mut functions = Vec::new();
+
+        loop {
+            let current = self.current().clone();
+            match current {
+                Token::RightCurlyParentheses => {
+                    self.next()?;
+                    break;
+                }
+                Token::Identifier(function_name) => {
+                    self.next()?;
+
+                    let parameters = self.parse_parameters()?;
+                    let return_type = self.next_type_id()?;
+                    functions.push(FunctionDeclaration::with_interface_member(
+                        function_name,
+                        TypeId::Class(name.clone()),
+                        parameters,
+                        return_type
+                    ));
+                }
+                _ => { return Err(ParserError::ExpectedIdentifier); }
+            }
+        }
+
+        Ok(Class::new_interface(name, functions))
+    }
+
+    fn parse_struct(&mut self) -> ParserResult<Class> {
+        self.next()?;
+        let name = self.next_identifier()?;
+
         match self.current() {
             Token::LeftCurlyParentheses => { self.next()?; }
             _ => { return Err(ParserError::ExpectedLeftCurlyParentheses); }
@@ -462,13 +740,34 @@ impl Parser {
                     self.next()?;
 
                     let field_type = self.next_type_id()?;
-                    fields.push(Field::new(field_name, field_type));
+                    fields.push(self.create_field(field_name, field_type)?);
                 }
                 _ => { return Err(ParserError::ExpectedIdentifier); }
             }
         }
 
-        Ok(Class::new(name, fields))
+        Ok(Class::new_struct(name, fields))
+    }
+
+    fn create_field(&self, name: String, field_type: TypeId) -> ParserResult<Field> {
+        // The layout of a struct field depends on the struct, which must be defined before
+        if let TypeId::Struct(struct_name) = &field_type {
+            let struct_class = self.get_class(struct_name)?;
+            if !struct_class.is_struct() {
+                return Err(ParserError::NotDefinedType(field_type.to_string()));
+            }
+
+            Ok(Field::with_struct(name, struct_class))
+        } else {
+            Ok(Field::new(name, field_type))
+        }
+    }
+
+    fn get_class(&self, name: &str) -> ParserResult<&Class> {
+        self.classes
+            .iter()
+            .find(|class| class.name() == name)
+            .ok_or_else(|| ParserError::NotDefinedType(name.to_owned()))
     }
 
     fn next_type_id(&mut self) -> ParserResult<TypeId> {
@@ -481,27 +780,58 @@ impl Parser {
                 self.next()?;
                 Ok(identifier.clone())
             }
-            _ => { return Err(ParserError::ExpectedIdentifier); }
+            _ => { Err(ParserError::ExpectedIdentifier)}
         }
     }
 
     fn next_i32(&mut self) -> ParserResult<i32> {
         match self.current().clone() {
-            Token::Int32(value) => {
+            Token::Int(value) => {
+                let value = i32::try_from(value).map_err(|_err| ParserError::IntConvertError)?;
+                self.next()?;
+                Ok(value)
+            }
+            _ => { Err(ParserError::ExpectedInt32)}
+        }
+    }
+
+    fn next_i64(&mut self) -> ParserResult<i64> {
+        match self.current().clone() {
+            Token::Int(value) => {
                 self.next()?;
                 Ok(value)
             }
-            _ => { return Err(ParserError::ExpectedInt32); }
+            _ => { Err(ParserError::ExpectedInt64)}
         }
     }
 
     fn next_f32(&mut self) -> ParserResult<f32> {
         match self.current().clone() {
-            Token::Float32(value) => {
+            Token::Float(value) => {
+                self.next()?;
+                Ok(value as f32)
+            }
+            _ => { Err(ParserError::ExpectedFloat32)}
+        }
+    }
+
+    fn next_f64(&mut self) -> ParserResult<f64> {
+        match self.current().clone() {
+            Token::Float(value) => {
+                self.next()?;
+                Ok(value)
+            }
+            _ => { Err(ParserError::ExpectedFloat64)}
+        }
+    }
+
+    fn next_string(&mut self) -> ParserResult<String> {
+        match self.current().clone() {
+            Token::String(value) => {
                 self.next()?;
                 Ok(value)
             }
-            _ => { return Err(ParserError::ExpectedFloat32); }
+            _ => { Err(ParserError::ExpectedString)}
         }
     }
 
@@ -824,6 +1154,182 @@ fn test_parse_function9() {
     assert_eq!(Instruction::Return, function.instructions()[2]);
 }
 
+#[test]
+fn test_parse_function10() {
+    let text = r"
+    func test() Int
+    {
+        .locals 1
+        .local 0 Ref.std.Exception
+        TRY
+        LDNULL Ref.std.Exception
+        THROW
+        ENDTRY
+        CATCH std.Exception
+        STLOC 0
+        ENDCATCH
+        FINALLY
+        ENDFINALLY
+        LDINT 0
+        RET
+    }
+    ";
+
+    let mut parser = Parser::new(tokenize(text).unwrap());
+    let (functions, _) = parser.parse().unwrap();
+
+    assert_eq!(1, functions.len());
+
+    let function = &functions[0];
+    assert_eq!(Instruction::BeginTry, function.instructions()[0]);
+    assert_eq!(Instruction::Throw, function.instructions()[2]);
+    assert_eq!(Instruction::EndTry, function.instructions()[3]);
+    assert_eq!(Instruction::BeginCatch("std.Exception".to_owned()), function.instructions()[4]);
+    assert_eq!(Instruction::EndCatch, function.instructions()[6]);
+    assert_eq!(Instruction::BeginFinally, function.instructions()[7]);
+    assert_eq!(Instruction::EndFinally, function.instructions()[8]);
+}
+
+#[test]
+fn test_parse_function11() {
+    let text = r"
+    func test(Int64 Float64) Float64
+    {
+        LDINT64 8589934592
+        LDFLOAT64 0.1
+        LDARG 1
+        ADD
+        RET
+    }
+    ";
+
+    let mut parser = Parser::new(tokenize(text).unwrap());
+    let (functions, _) = parser.parse().unwrap();
+
+    assert_eq!(1, functions.len());
+
+    let function = &functions[0];
+    assert_eq!(&vec![TypeId::Int64, TypeId::Float64], function.declaration().parameters());
+    assert_eq!(&TypeId::Float64, function.declaration().return_type());
+
+    assert_eq!(Instruction::LoadInt64(8589934592), function.instructions()[0]);
+    assert_eq!(Instruction::LoadFloat64(0.1), function.instructions()[1]);
+}
+
+#[test]
+fn test_parse_function12() {
+    let text = r"
+    func test() Int
+    {
+        LDINT 8589934592
+        RET
+    }
+    ";
+
+    let mut parser = Parser::new(tokenize(text).unwrap());
+    assert!(parser.parse().is_err());
+}
+
+#[test]
+fn test_parse_function13() {
+    let text = r#"
+    func test() Ref.String
+    {
+        LDSTR "Hello, \"World\"\n"
+        RET
+    }
+    "#;
+
+    let mut parser = Parser::new(tokenize(text).unwrap());
+    let (functions, _) = parser.parse().unwrap();
+
+    assert_eq!(1, functions.len());
+
+    let function = &functions[0];
+    assert_eq!(&TypeId::String, function.declaration().return_type());
+    assert_eq!(Instruction::LoadString("Hello, \"World\"\n".to_owned()), function.instructions()[0]);
+}
+
+#[test]
+fn test_parse_function14() {
+    let text = r#"
+    func test() Ref.String
+    {
+        LDSTR "Hello
+    }
+    "#;
+
+    assert!(tokenize(text).is_err());
+}
+
+#[test]
+fn test_parse_function15() {
+    let text = r"
+    func test() Int
+    {
+        LDINT 2
+        LDINT 3
+        NEWARR2D Int
+        DUP
+        LDINT 1
+        LDINT 2
+        LDINT 4711
+        STELEM2D Int
+        LDDIM 1
+        RET
+    }
+    ";
+
+    let mut parser = Parser::new(tokenize(text).unwrap());
+    let (functions, _) = parser.parse().unwrap();
+
+    assert_eq!(1, functions.len());
+
+    let function = &functions[0];
+    assert_eq!(Instruction::NewArray2D(TypeId::Int32), function.instructions()[2]);
+    assert_eq!(Instruction::StoreElement2D(TypeId::Int32), function.instructions()[7]);
+    assert_eq!(Instruction::LoadArrayDimension(1), function.instructions()[8]);
+}
+
+#[test]
+fn test_parse_function16() {
+    let text = r"
+    func apply(Func[Int,Int][Int] Int) Int
+    {
+        LDINT 2
+        LDARG 1
+        LDARG 0
+        CALLI
+        RET
+    }
+
+    func main() Int
+    {
+        LDFUNC add(Int Int)
+        LDINT 4711
+        CALL apply(Func[Int,Int][Int] Int)
+        RET
+    }
+    ";
+
+    let mut parser = Parser::new(tokenize(text).unwrap());
+    let (functions, _) = parser.parse().unwrap();
+
+    assert_eq!(2, functions.len());
+
+    let function_type = TypeId::Function(vec![TypeId::Int32, TypeId::Int32], Box::new(TypeId::Int32));
+    assert_eq!(&vec![function_type.clone(), TypeId::Int32], functions[0].declaration().parameters());
+    assert_eq!(Instruction::CallIndirect, functions[0].instructions()[3]);
+    assert_eq!(
+        Instruction::LoadFunction(FunctionSignature::new("add".to_owned(), vec![TypeId::Int32, TypeId::Int32])),
+        functions[1].instructions()[0]
+    );
+    assert_eq!(
+        Instruction::Call(FunctionSignature::new("apply".to_owned(), vec![function_type, TypeId::Int32])),
+        functions[1].instructions()[2]
+    );
+}
+
 #[test]
 fn test_parse_classes1() {
     let text = r"
@@ -897,6 +1403,249 @@ fn test_parse_member_function1() {
     assert_eq!(&TypeId::Float32, class.fields()[1].type_id());
 }
 
+#[test]
+fn test_parse_inheritance1() {
+    let text = r"
+    class Shape
+    {
+        x Int
+    }
+
+    class Circle : Shape
+    {
+        radius Float
+    }
+
+    member virtual Shape::area() Float
+    {
+        LDFLOAT 0.0
+        RET
+    }
+
+    member override Circle::area() Float
+    {
+        LDARG 0
+        LDFIELD Circle::radius
+        RET
+    }
+    ";
+
+    let mut parser = Parser::new(tokenize(text).unwrap());
+    let (functions, classes) = parser.parse().unwrap();
+
+    assert_eq!(2, functions.len());
+    assert_eq!(&Some(VirtualModifier::Virtual), functions[0].declaration().virtual_modifier());
+    assert_eq!(&Some(TypeId::Class("Shape".to_owned())), functions[0].declaration().class());
+    assert_eq!(&Some(VirtualModifier::Override), functions[1].declaration().virtual_modifier());
+    assert_eq!(&Some(TypeId::Class("Circle".to_owned())), functions[1].declaration().class());
+
+    assert_eq!(2, classes.len());
+
+    let class = &classes[1];
+    assert_eq!("Circle", class.name());
+    assert_eq!(Some("Shape"), class.base());
+    assert_eq!("x", class.fields()[0].name());
+    assert_eq!("radius", class.fields()[1].name());
+    assert_eq!(4, class.fields()[1].offset());
+}
+
+#[test]
+fn test_parse_inheritance2() {
+    let text = r"
+    class Circle : Shape
+    {
+        radius Float
+    }
+    ";
+
+    let mut parser = Parser::new(tokenize(text).unwrap());
+    assert!(parser.parse().is_err());
+}
+
+#[test]
+fn test_parse_interface1() {
+    let text = r"
+    interface Shape
+    {
+        area() Int
+        scale(Int Float) Float
+    }
+
+    class Base
+    {
+
+    }
+
+    interface Named
+    {
+        name() Ref.String
+    }
+
+    class Circle : Shape Base Named
+    {
+        radius Int
+    }
+    ";
+
+    let mut parser = Parser::new(tokenize(text).unwrap());
+    let (_, classes) = parser.parse().unwrap();
+
+    assert_eq!(4, classes.len());
+
+    let interface = &classes[0];
+    assert!(interface.is_interface());
+    let functions = interface.interface_functions().unwrap();
+    assert_eq!(2, functions.len());
+    assert_eq!(
+        FunctionSignature::with_class("scale".to_owned(), TypeId::Class("Shape".to_owned()), vec![TypeId::Int32, TypeId::Float32]),
+        functions[1].signature()
+    );
+    assert_eq!(&TypeId::Float32, functions[1].return_type());
+    assert_eq!(&Some(VirtualModifier::Interface), functions[1].virtual_modifier());
+
+    let class = &classes[3];
+    assert!(!class.is_interface());
+    assert_eq!(Some("Base"), class.base());
+    assert_eq!(&vec!["Shape".to_owned(), "Named".to_owned()], class.interfaces());
+}
+
+#[test]
+fn test_parse_interface2() {
+    let text = r"
+    class Shape
+    {
+
+    }
+
+    class Base
+    {
+
+    }
+
+    class Circle : Shape Base
+    {
+
+    }
+    ";
+
+    let mut parser = Parser::new(tokenize(text).unwrap());
+    assert!(matches!(parser.parse(), Err(ParserError::MultipleBaseClasses(_))));
+}
+
+#[test]
+fn test_parse_constructor1() {
+    let text = r"
+    class Point
+    {
+        x Int
+    }
+
+    member Point::.constructor(Int) Void
+    {
+        LDARG 0
+        LDARG 1
+        STFIELD Point::x
+        RET
+    }
+
+    func main() Int
+    {
+        LDINT 4711
+        NEWOBJ Point(Int)
+        LDFIELD Point::x
+        RET
+    }
+    ";
+
+    let mut parser = Parser::new(tokenize(text).unwrap());
+    let (functions, _) = parser.parse().unwrap();
+
+    assert_eq!(2, functions.len());
+    assert!(functions[0].declaration().is_constructor());
+    assert_eq!(
+        FunctionSignature::with_constructor(TypeId::Class("Point".to_owned()), vec![TypeId::Int32]),
+        functions[0].declaration().signature()
+    );
+
+    assert_eq!(
+        Instruction::NewObjectWithConstructor("Point".to_owned(), vec![TypeId::Int32]),
+        functions[1].instructions()[1]
+    );
+}
+
+#[test]
+fn test_parse_static_field1() {
+    let text = r"
+    class Counter
+    {
+        value Int
+        static count Int
+    }
+
+    func main() Int
+    {
+        LDSFIELD Counter::count
+        LDINT 1
+        ADD
+        STSFIELD Counter::count
+        LDINT 0
+        RET
+    }
+    ";
+
+    let mut parser = Parser::new(tokenize(text).unwrap());
+    let (functions, classes) = parser.parse().unwrap();
+
+    let class = &classes[0];
+    assert_eq!(1, class.fields().len());
+    assert_eq!(1, class.static_fields().len());
+    assert_eq!(&TypeId::Int32, class.get_static_field("count").unwrap().type_id());
+
+    assert_eq!(Instruction::LoadStaticField("Counter".to_owned(), "count".to_owned()), functions[0].instructions()[0]);
+    assert_eq!(Instruction::StoreStaticField("Counter".to_owned(), "count".to_owned()), functions[0].instructions()[3]);
+}
+
+#[test]
+fn test_parse_struct1() {
+    let text = r"
+    struct Point
+    {
+        x Int
+        y Int
+    }
+
+    class Line
+    {
+        start Struct.Point
+        end Struct.Point
+    }
+
+    func main() Int
+    {
+        .locals 1
+        .local 0 Struct.Point
+        NEWOBJ Point
+        STLOC 0
+        LDINT 0
+        RET
+    }
+    ";
+
+    let mut parser = Parser::new(tokenize(text).unwrap());
+    let (functions, classes) = parser.parse().unwrap();
+
+    assert!(classes[0].is_struct());
+    assert_eq!(8, classes[0].memory_size());
+
+    let line = &classes[1];
+    assert!(!line.is_struct());
+    assert_eq!(&TypeId::Struct("Point".to_owned()), line.fields()[0].type_id());
+    assert_eq!(8, line.get_field("end").unwrap().offset());
+    assert_eq!(16, line.memory_size());
+
+    assert_eq!(&vec![TypeId::Struct("Point".to_owned())], functions[0].locals());
+}
+
 #[test]
 fn test_parse1() {
     let text = r"
@@ -2,14 +2,31 @@ use crate::runtime::object::ObjectPointer;
 
 pub const LENGTH_SIZE: usize = 4;
 
+/// 2D arrays store the number of rows and columns before the elements, which are stored row by row.
+pub const ROWS_OFFSET: usize = 0;
+pub const COLUMNS_OFFSET: usize = 4;
+pub const DIMENSIONS_SIZE: usize = 8;
+
 pub fn get_length(ptr: ObjectPointer) -> usize {
-    (unsafe { *(ptr as *const i32) }) as usize
+    (unsafe { (ptr as *const i32).read_unaligned() }) as usize
 }
 
 pub fn get_elements<T>(ptr: ObjectPointer) -> *const T {
     unsafe { (ptr.add(LENGTH_SIZE)) as *const T }
 }
 
+pub fn get_rows(ptr: ObjectPointer) -> usize {
+    (unsafe { (ptr.add(ROWS_OFFSET) as *const i32).read_unaligned() }) as usize
+}
+
+pub fn get_columns(ptr: ObjectPointer) -> usize {
+    (unsafe { (ptr.add(COLUMNS_OFFSET) as *const i32).read_unaligned() }) as usize
+}
+
+pub fn get_elements_2d<T>(ptr: ObjectPointer) -> *const T {
+    unsafe { (ptr.add(DIMENSIONS_SIZE)) as *const T }
+}
+
 pub struct ArrayReference<T> {
     elements_ptr: *const T,
     length: usize
@@ -23,6 +40,13 @@ impl<T> ArrayReference<T> {
         }
     }
 
+    pub fn new_2d(ptr: ObjectPointer) -> ArrayReference<T> {
+        ArrayReference {
+            elements_ptr: get_elements_2d(ptr),
+            length: get_rows(ptr) * get_columns(ptr)
+        }
+    }
+
     pub fn length(&self) -> usize {
         self.length
     }
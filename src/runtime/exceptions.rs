@@ -0,0 +1,70 @@
+use crate::compiler::jit::JitCompiler;
+use crate::compiler::stack_layout;
+use crate::engine::execution::RuntimeError;
+use crate::model::exception;
+use crate::model::typesystem::{TypeId, TypeStorage};
+use crate::runtime::stack_walker::StackFrame;
+
+pub struct ExceptionHandlerTarget {
+    pub handler_address: u64,
+    pub base_pointer: u64,
+    pub stack_pointer: u64
+}
+
+pub fn find_handler(compiler: &JitCompiler,
+                    type_storage: &TypeStorage,
+                    base_pointer: u64,
+                    error_address: u64,
+                    exception_type: &TypeId) -> Option<ExceptionHandlerTarget> {
+    let mut current_frame = StackFrame::from_native_address(compiler, base_pointer, error_address);
+
+    while let Some(frame) = current_frame.take() {
+        let compilation_data = frame.compilation_data();
+        let instruction_index = compilation_data.mir_compilation_result.instructions[frame.instruction_index()].index;
+
+        // The regions are ordered such that inner regions comes first
+        for region in frame.function().protected_regions() {
+            if let Some(handler_index) = region.find_handler(instruction_index, type_storage, exception_type) {
+                let function_code_ptr = frame.function().declaration().address().unwrap() as u64;
+                let stack_size = stack_layout::stack_size(frame.function(), &compilation_data.mir_compilation_result);
+
+                return Some(
+                    ExceptionHandlerTarget {
+                        handler_address: function_code_ptr + compilation_data.exception_handler_offsets[&handler_index] as u64,
+                        base_pointer: frame.base_pointer(),
+                        stack_pointer: frame.base_pointer() - stack_size as u64
+                    }
+                );
+            }
+        }
+
+        current_frame = frame.parent(compiler);
+    }
+
+    None
+}
+
+pub fn exception_class(runtime_error: &RuntimeError) -> Option<&'static str> {
+    match runtime_error {
+        RuntimeError::NullReference => Some(exception::NULL_REFERENCE_EXCEPTION_CLASS),
+        RuntimeError::ArrayCreate => Some(exception::ARRAY_CREATE_EXCEPTION_CLASS),
+        RuntimeError::ArrayBounds => Some(exception::ARRAY_BOUNDS_EXCEPTION_CLASS),
+        RuntimeError::DivisionByZero => Some(exception::DIVISION_BY_ZERO_EXCEPTION_CLASS),
+        RuntimeError::DivisionOverflow => Some(exception::DIVISION_OVERFLOW_EXCEPTION_CLASS),
+        RuntimeError::InvalidCast => Some(exception::INVALID_CAST_EXCEPTION_CLASS),
+        _ => None
+    }
+}
+
+pub fn unhandled_exception_error(exception_type: &TypeId) -> RuntimeError {
+    let class_name = exception_type.class_name().unwrap();
+    match class_name {
+        exception::NULL_REFERENCE_EXCEPTION_CLASS => RuntimeError::NullReference,
+        exception::ARRAY_CREATE_EXCEPTION_CLASS => RuntimeError::ArrayCreate,
+        exception::ARRAY_BOUNDS_EXCEPTION_CLASS => RuntimeError::ArrayBounds,
+        exception::DIVISION_BY_ZERO_EXCEPTION_CLASS => RuntimeError::DivisionByZero,
+        exception::DIVISION_OVERFLOW_EXCEPTION_CLASS => RuntimeError::DivisionOverflow,
+        exception::INVALID_CAST_EXCEPTION_CLASS => RuntimeError::InvalidCast,
+        _ => RuntimeError::UnhandledException(class_name.to_owned())
+    }
+}
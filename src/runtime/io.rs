@@ -0,0 +1,70 @@
+use std::io::{BufRead, Write, BufReader};
+
+use crate::runtime::object::ObjectPointer;
+use crate::runtime::string;
+use crate::vm::get_vm;
+
+/// The input and output used by the std.io functions, which can be replaced by the embedder.
+pub struct Console {
+    pub output: Box<dyn Write>,
+    pub input: Box<dyn BufRead>
+}
+
+impl Console {
+    pub fn new(output: Box<dyn Write>, input: Box<dyn BufRead>) -> Console {
+        Console {
+            output,
+            input
+        }
+    }
+}
+
+impl Default for Console {
+    fn default() -> Self {
+        Console::new(Box::new(std::io::stdout()), Box::new(BufReader::new(std::io::stdin())))
+    }
+}
+
+fn write_output(text: &str) {
+    get_vm(|vm| {
+        // Errors can't be signaled from external functions, so failed writes are ignored
+        let _ = vm.console.output.write_all(text.as_bytes());
+        let _ = vm.console.output.flush();
+    });
+}
+
+pub extern "C" fn print_int(value: i32) {
+    write_output(&value.to_string());
+}
+
+pub extern "C" fn print_float(value: f32) {
+    write_output(&value.to_string());
+}
+
+pub extern "C" fn print_bool(value: bool) {
+    write_output(&value.to_string());
+}
+
+pub extern "C" fn print_char(value: i32) {
+    let character = std::char::from_u32(value as u32).unwrap_or(std::char::REPLACEMENT_CHARACTER);
+    write_output(&character.to_string());
+}
+
+pub extern "C" fn print_string(value: ObjectPointer) {
+    write_output(string::as_str(value));
+}
+
+pub extern "C" fn println() {
+    write_output("\n");
+}
+
+pub extern "C" fn read_int() -> i32 {
+    get_vm(|vm| {
+        // Invalid input is read as zero
+        let mut line = String::new();
+        match vm.console.input.read_line(&mut line) {
+            Ok(_) => line.trim().parse::<i32>().unwrap_or(0),
+            Err(_) => 0
+        }
+    })
+}
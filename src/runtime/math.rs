@@ -0,0 +1,60 @@
+pub extern "C" fn sqrt_float(value: f32) -> f32 {
+    value.sqrt()
+}
+
+pub extern "C" fn abs_float(value: f32) -> f32 {
+    value.abs()
+}
+
+pub extern "C" fn min_float(x: f32, y: f32) -> f32 {
+    x.min(y)
+}
+
+pub extern "C" fn max_float(x: f32, y: f32) -> f32 {
+    x.max(y)
+}
+
+pub extern "C" fn floor_float(value: f32) -> f32 {
+    value.floor()
+}
+
+pub extern "C" fn ceil_float(value: f32) -> f32 {
+    value.ceil()
+}
+
+pub extern "C" fn pow_float(x: f32, y: f32) -> f32 {
+    x.powf(y)
+}
+
+pub extern "C" fn sin_float(value: f32) -> f32 {
+    value.sin()
+}
+
+pub extern "C" fn cos_float(value: f32) -> f32 {
+    value.cos()
+}
+
+pub extern "C" fn abs_int(value: i32) -> i32 {
+    value.wrapping_abs()
+}
+
+pub extern "C" fn min_int(x: i32, y: i32) -> i32 {
+    x.min(y)
+}
+
+pub extern "C" fn max_int(x: i32, y: i32) -> i32 {
+    x.max(y)
+}
+
+pub extern "C" fn pow_int(x: i32, y: i32) -> i32 {
+    // Negative exponents are truncated towards zero, like integer division
+    if y < 0 {
+        return match x {
+            1 => 1,
+            -1 => if y % 2 == 0 { 1 } else { -1 },
+            _ => 0
+        };
+    }
+
+    x.wrapping_pow(y as u32)
+}
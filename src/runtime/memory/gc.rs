@@ -1,36 +1,82 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use crate::runtime::memory::heap::{Heap, HeapObjectsIterator};
-use crate::runtime::stack_walker::{StackFrame, FrameValue};
+use crate::runtime::stack_walker::StackFrame;
 use crate::compiler::jit::JitCompiler;
-use crate::model::binder::Binder;
 use crate::model::typesystem::TypeId;
-use crate::runtime::object::ObjectReference;
-use crate::runtime::{array, object};
+use crate::runtime::object::{ObjectHeader, ObjectReference};
+use crate::runtime::object;
 use crate::runtime::array::ArrayReference;
 use crate::runtime::object::ObjectPointer;
 
 pub struct GarbageCollector {
-    deleted_objects: Vec<(u64, TypeId)>
+    deleted_objects: Vec<(u64, TypeId)>,
+    remembered_set: HashSet<ObjectPointer>,
+    roots: Vec<*mut ObjectPointer>,
+    pinned_objects: HashMap<ObjectPointer, usize>
 }
 
+// The number of young collections an object must survive before being promoted to the old generation
+pub const PROMOTION_SURVIVAL_COUNT: u8 = 2;
+
 impl GarbageCollector {
     pub fn new() -> GarbageCollector {
         GarbageCollector {
-            deleted_objects: Vec::new()
+            deleted_objects: Vec::new(),
+            remembered_set: HashSet::new(),
+            roots: Vec::new(),
+            pinned_objects: HashMap::new()
         }
     }
 
+    #[allow(dead_code)]
     pub fn deleted_objects(&self) -> &Vec<(u64, TypeId)> {
         &self.deleted_objects
     }
 
+    #[allow(dead_code)]
+    pub fn remembered_set(&self) -> &HashSet<ObjectPointer> {
+        &self.remembered_set
+    }
+
+    pub fn remember(&mut self, object: ObjectPointer) {
+        self.remembered_set.insert(object);
+    }
+
+    /// Adds a slot outside of the stack and the heap that holds a reference. The slot must stay valid for the lifetime of the collector.
+    pub fn add_root(&mut self, root: *mut ObjectPointer) {
+        self.roots.push(root);
+    }
+
+    /// Pins the object such that it is not moved by the collector. An object pinned multiple times must be unpinned as many times.
+    #[allow(dead_code)]
+    pub fn pin(&mut self, object: ObjectPointer) {
+        if !object.is_null() {
+            *self.pinned_objects.entry(object).or_insert(0) += 1;
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn unpin(&mut self, object: ObjectPointer) {
+        if let Some(count) = self.pinned_objects.get_mut(&object) {
+            *count -= 1;
+            if *count == 0 {
+                self.pinned_objects.remove(&object);
+            }
+        }
+    }
+
+    pub fn is_pinned(&self, object: ObjectPointer) -> bool {
+        self.pinned_objects.contains_key(&object)
+    }
+
     pub fn collect(&mut self,
                    compiler: &JitCompiler,
-                   heap: &mut Heap,
+                   young_generation: &mut Heap,
+                   old_generation: &mut Heap,
                    stack_frame: StackFrame) {
         let print_objects = |heap: &Heap| {
-            for object_ref in HeapObjectsIterator::new(&heap) {
+            for object_ref in HeapObjectsIterator::new(heap) {
                 println!(
                     "0x{:0x} - type: {}, size: {}, marked: {}, dead: {}",
                     object_ref.ptr() as u64,
@@ -55,20 +101,79 @@ impl GarbageCollector {
 
         println!();
         println!("Before heap objects:");
-        print_objects(heap);
+        print_objects(young_generation);
+        print_objects(old_generation);
         println!();
 
         self.mark_objects(compiler, &stack_frame);
-        // self.sweep_objects(heap);
-        self.compact_objects(compiler, heap, &stack_frame);
+        self.mark_reachable_objects(self.root_values(), |_| true);
+        self.compact_objects(compiler, young_generation, old_generation, &stack_frame);
+        self.rebuild_remembered_set(young_generation, old_generation);
 
         println!();
         println!("After heap objects:");
-        print_objects(heap);
+        print_objects(young_generation);
+        print_objects(old_generation);
 
         println!("--------------------------------------------");
     }
 
+    pub fn collect_young(&mut self,
+                         compiler: &JitCompiler,
+                         young_generation: &mut Heap,
+                         old_generation: &mut Heap,
+                         stack_frame: StackFrame) {
+        self.mark_young_objects(compiler, young_generation, &stack_frame);
+
+        let (next_segment_offsets, new_locations, promoted_objects, free_young_ranges) = self.compute_young_new_locations(
+            young_generation,
+            old_generation
+        );
+
+        self.update_stack_references(compiler, &stack_frame, &new_locations);
+        self.update_root_references(&new_locations);
+        self.update_heap_references(young_generation, &new_locations);
+        for &object in &self.remembered_set {
+            self.update_object_references(&ObjectReference::from_ptr(object).unwrap(), &new_locations);
+        }
+
+        let num_deleted_objects = self.deleted_objects.len();
+        self.move_young_objects(young_generation, &new_locations);
+        free_ranges(free_young_ranges);
+
+        println!(
+            "Young collection: deleted {} objects, promoted {} objects, decreased young generation by {} bytes",
+            self.deleted_objects.len() - num_deleted_objects,
+            promoted_objects.len(),
+            young_generation.used_size() as isize - next_segment_offsets.iter().sum::<usize>() as isize
+        );
+        young_generation.set_segment_offsets(next_segment_offsets);
+
+        // Old objects not referencing any young objects do not need to be remembered anymore
+        let mut remembered_set = std::mem::take(&mut self.remembered_set);
+        remembered_set.extend(promoted_objects);
+        self.remembered_set = remembered_set
+            .into_iter()
+            .filter(|&object| references_young_objects(young_generation, &ObjectReference::from_ptr(object).unwrap()))
+            .collect();
+    }
+
+    fn root_values(&self) -> Vec<ObjectPointer> {
+        // Pinned objects are in use by native code, so they are kept alive
+        self.roots
+            .iter()
+            .map(|&root| unsafe { root.read_unaligned() })
+            .chain(self.pinned_objects.keys().cloned())
+            .collect()
+    }
+
+    fn update_root_references(&self, new_locations: &HashMap<ObjectPointer, ObjectPointer>) {
+        for &root in &self.roots {
+            self.update_reference(new_locations, root);
+        }
+    }
+
+    #[allow(dead_code)]
     fn sweep_objects(&mut self, heap: &Heap) {
         for mut object_ref in HeapObjectsIterator::new(heap) {
             if !object_ref.header().is_marked() {
@@ -83,32 +188,50 @@ impl GarbageCollector {
 
     fn compact_objects(&mut self,
                        compiler: &JitCompiler,
-                       heap: &mut Heap,
+                       young_generation: &mut Heap,
+                       old_generation: &mut Heap,
                        stack_frame: &StackFrame) {
-        let (next_object_offset, new_locations) = self.compute_new_locations(heap);
-        self.update_references(compiler, heap, stack_frame, &new_locations);
-        self.move_objects(heap, &new_locations);
+        let (next_young_segment_offsets, mut new_locations, young_free_ranges) = self.compute_new_locations(young_generation);
+        let (next_old_segment_offsets, old_new_locations, old_free_ranges) = self.compute_new_locations(old_generation);
+        new_locations.extend(old_new_locations);
+
+        self.update_stack_references(compiler, stack_frame, &new_locations);
+        self.update_root_references(&new_locations);
+        self.update_heap_references(young_generation, &new_locations);
+        self.update_heap_references(old_generation, &new_locations);
 
-        println!("Decreased heap by {} bytes", heap.offset() as isize - next_object_offset as isize);
-        heap.set_offset(next_object_offset);
+        self.move_objects(young_generation, &new_locations);
+        self.move_objects(old_generation, &new_locations);
+        free_ranges(young_free_ranges);
+        free_ranges(old_free_ranges);
+
+        println!(
+            "Decreased heap by {} bytes",
+            (young_generation.used_size() + old_generation.used_size()) as isize
+            - (next_young_segment_offsets.iter().sum::<usize>() + next_old_segment_offsets.iter().sum::<usize>()) as isize
+        );
+        young_generation.set_segment_offsets(next_young_segment_offsets);
+        old_generation.set_segment_offsets(next_old_segment_offsets);
     }
 
-    fn compute_new_locations(&self, heap: &Heap) -> (usize, HashMap<ObjectPointer, ObjectPointer>) {
-        let mut object_offset = 0;
+    #[allow(clippy::type_complexity)]
+    fn compute_new_locations(&self, heap: &Heap) -> (Vec<usize>, HashMap<ObjectPointer, ObjectPointer>, Vec<(ObjectPointer, usize)>) {
+        let mut compacted_heap = CompactedHeap::new(heap);
         let mut new_locations = HashMap::new();
 
         for object_ref in HeapObjectsIterator::new(heap) {
             if object_ref.header().is_marked() {
-                new_locations.insert(
-                    object_ref.full_ptr(),
-                    unsafe { heap.data().as_ptr().add(object_offset) } as ObjectPointer
-                );
+                let new_address = if self.is_pinned(object_ref.ptr()) {
+                    compacted_heap.allocate_pinned(&object_ref)
+                } else {
+                    compacted_heap.allocate(object_ref.full_size())
+                };
 
-                object_offset += object_ref.full_size();
+                new_locations.insert(object_ref.full_ptr(), new_address);
             }
         }
 
-        (object_offset, new_locations)
+        (compacted_heap.segment_offsets, new_locations, compacted_heap.free_ranges)
     }
 
     fn move_objects(&mut self,
@@ -128,6 +251,209 @@ impl GarbageCollector {
             }
         }
     }
+
+    fn rebuild_remembered_set(&mut self, young_generation: &Heap, old_generation: &Heap) {
+        self.remembered_set.clear();
+
+        for object_ref in HeapObjectsIterator::new(old_generation) {
+            if references_young_objects(young_generation, &object_ref) {
+                self.remembered_set.insert(object_ref.ptr());
+            }
+        }
+    }
+
+    fn mark_young_objects(&mut self,
+                          compiler: &JitCompiler,
+                          young_generation: &Heap,
+                          stack_frame: &StackFrame) {
+        let mut to_visit = Vec::new();
+
+        stack_frame.walk(
+            compiler,
+            |frame| {
+                frame.visit_references(|value_ptr| {
+                    to_visit.push(unsafe { value_ptr.read_unaligned() });
+                });
+            }
+        );
+
+        to_visit.extend(self.root_values());
+
+        // Old objects referencing young objects are also roots
+        for &object in &self.remembered_set {
+            visit_reference_fields(&ObjectReference::from_ptr(object).unwrap(), |field_ptr| {
+                to_visit.push(unsafe { field_ptr.read_unaligned() });
+            });
+        }
+
+        self.mark_reachable_objects(to_visit, |object| young_generation.inside(object));
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn compute_young_new_locations(&self,
+                                   young_generation: &Heap,
+                                   old_generation: &mut Heap) -> (Vec<usize>, HashMap<ObjectPointer, ObjectPointer>, Vec<ObjectPointer>, Vec<(ObjectPointer, usize)>) {
+        let mut compacted_heap = CompactedHeap::new(young_generation);
+        let mut new_locations = HashMap::new();
+        let mut promoted_objects = Vec::new();
+
+        for object_ref in HeapObjectsIterator::new(young_generation) {
+            if object_ref.header().is_marked() && self.is_pinned(object_ref.ptr()) {
+                // Pinned objects are not promoted, as that would move them
+                new_locations.insert(object_ref.full_ptr(), compacted_heap.allocate_pinned(&object_ref));
+            } else if object_ref.header().is_marked() {
+                // Objects are also promoted if too many survive, else the young generation would be left without free space.
+                // If the old generation is full, the object stays in the young generation.
+                let survived_enough = object_ref.header().survival_count() + 1 >= PROMOTION_SURVIVAL_COUNT;
+                let young_overflow = compacted_heap.used_size() + object_ref.full_size() > young_generation.size() / 2;
+
+                let promoted_address = if survived_enough || young_overflow {
+                    old_generation.allocate(object_ref.full_size())
+                } else {
+                    None
+                };
+
+                match promoted_address {
+                    Some(promoted_address) => {
+                        new_locations.insert(object_ref.full_ptr(), promoted_address);
+                        promoted_objects.push(unsafe { promoted_address.add(object::HEADER_SIZE) });
+                    }
+                    None => {
+                        new_locations.insert(
+                            object_ref.full_ptr(),
+                            compacted_heap.allocate(object_ref.full_size())
+                        );
+                    }
+                }
+            }
+        }
+
+        (compacted_heap.segment_offsets, new_locations, promoted_objects, compacted_heap.free_ranges)
+    }
+
+    fn move_young_objects(&mut self,
+                          young_generation: &mut Heap,
+                          new_locations: &HashMap<ObjectPointer, ObjectPointer>) {
+        for mut object_ref in HeapObjectsIterator::new(young_generation) {
+            if object_ref.header().is_marked() {
+                object_ref.header_mut().unmark();
+                if object_ref.header().survival_count() < PROMOTION_SURVIVAL_COUNT {
+                    object_ref.header_mut().increase_survival_count();
+                }
+
+                let new_address = new_locations[&object_ref.full_ptr()];
+
+                unsafe {
+                    object_ref.full_ptr().copy_to(new_address, object_ref.full_size());
+                }
+            } else {
+                self.deleted_objects.push((object_ref.ptr() as u64, object_ref.object_type().id.clone()));
+            }
+        }
+    }
+}
+
+// Computes where objects are placed when compacted. As objects are placed in the same order as they are in the heap,
+// an object is never placed after its current location.
+struct CompactedHeap<'a> {
+    heap: &'a Heap,
+    segment_index: usize,
+    segment_offsets: Vec<usize>,
+    free_ranges: Vec<(ObjectPointer, usize)>
+}
+
+impl<'a> CompactedHeap<'a> {
+    fn new(heap: &'a Heap) -> CompactedHeap<'a> {
+        CompactedHeap {
+            heap,
+            segment_index: 0,
+            segment_offsets: vec![0; heap.segments().len()],
+            free_ranges: Vec::new()
+        }
+    }
+
+    fn used_size(&self) -> usize {
+        self.segment_offsets.iter().sum()
+    }
+
+    fn allocate(&mut self, size: usize) -> ObjectPointer {
+        while self.segment_offsets[self.segment_index] + size > self.heap.segments()[self.segment_index].size() {
+            self.segment_index += 1;
+        }
+
+        let segment = &self.heap.segments()[self.segment_index];
+        let address = unsafe { segment.data().as_ptr().add(self.segment_offsets[self.segment_index]) } as ObjectPointer;
+        self.segment_offsets[self.segment_index] += size;
+        address
+    }
+
+    // Pinned objects keep their location, which means that the following objects are placed after it.
+    // The space before is free, as it is made up of whole objects not placed there.
+    fn allocate_pinned(&mut self, object_ref: &ObjectReference) -> ObjectPointer {
+        let address = object_ref.full_ptr();
+        self.segment_index = self.heap.segments().iter().position(|segment| segment.inside(address)).unwrap();
+
+        let segment = &self.heap.segments()[self.segment_index];
+        let offset = address as usize - segment.data().as_ptr() as usize;
+        let free_offset = self.segment_offsets[self.segment_index];
+        if offset > free_offset {
+            let free_address = unsafe { segment.data().as_ptr().add(free_offset) } as ObjectPointer;
+            self.free_ranges.push((free_address, offset - free_offset));
+        }
+
+        self.segment_offsets[self.segment_index] = offset + object_ref.full_size();
+        address
+    }
+}
+
+// Marks the space between compacted objects and pinned objects as deleted, such that the heap can still be iterated
+fn free_ranges(ranges: Vec<(ObjectPointer, usize)>) {
+    for (address, size) in ranges {
+        unsafe {
+            (*(address as *mut ObjectHeader)).delete(size as u64);
+        }
+    }
+}
+
+fn visit_reference_fields<F: FnMut(*mut ObjectPointer)>(object_ref: &ObjectReference, mut apply: F) {
+    match &object_ref.object_type().id {
+        TypeId::Array(_) | TypeId::Array2D(_) => {
+            let element_size = object_ref.object_type().element_size();
+            let element_references = object_ref.object_type().element_references();
+            if !element_references.is_empty() {
+                let array_ref = if object_ref.object_type().id.is_array2d() {
+                    ArrayReference::<u8>::new_2d(object_ref.ptr())
+                } else {
+                    ArrayReference::<u8>::new(object_ref.ptr())
+                };
+
+                for index in 0..array_ref.length() {
+                    let element_ptr = array_ref.get_raw(index * element_size);
+                    for reference_offset in element_references {
+                        apply(unsafe { element_ptr.add(*reference_offset) } as *mut ObjectPointer);
+                    }
+                }
+            }
+        }
+        TypeId::Class(_) => {
+            for reference_offset in object_ref.object_type().class.as_ref().unwrap().reference_offsets() {
+                apply(unsafe { object_ref.ptr().add(*reference_offset) as *mut u8 } as *mut ObjectPointer);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn references_young_objects(young_generation: &Heap, object_ref: &ObjectReference) -> bool {
+    let mut references_young = false;
+    visit_reference_fields(object_ref, |field_ptr| {
+        let field_value = unsafe { field_ptr.read_unaligned() };
+        if !field_value.is_null() && young_generation.inside(field_value) {
+            references_young = true;
+        }
+    });
+
+    references_young
 }
 
 impl MarkObjects for GarbageCollector {}
@@ -137,68 +463,47 @@ pub trait MarkObjects {
     fn mark_objects(&mut self,
                     compiler: &JitCompiler,
                     stack_frame: &StackFrame) {
+        let mut to_visit = Vec::new();
+
         stack_frame.walk(
             compiler,
             |frame| {
-                frame.visit_values(|value| {
-                    self.mark_value(value);
+                frame.visit_references(|value_ptr| {
+                    to_visit.push(unsafe { value_ptr.read_unaligned() });
                 });
             }
         );
+
+        self.mark_reachable_objects(to_visit, |_| true);
     }
 
-    fn mark_value(&mut self, value: FrameValue) {
-        if value.value_type.is_reference() {
-            if value.value_ptr() != std::ptr::null_mut() {
-                self.mark_object(ObjectReference::from_ptr(value.value_ptr()).unwrap());
+    // Uses an explicit stack, as deep object graphs (such as long linked lists) would overflow the native stack
+    fn mark_reachable_objects<F: Fn(ObjectPointer) -> bool>(&mut self, mut to_visit: Vec<ObjectPointer>, should_mark: F) {
+        while let Some(object) = to_visit.pop() {
+            if object.is_null() || !should_mark(object) {
+                continue;
             }
-        }
-    }
 
-    fn mark_object(&mut self, mut object_ref: ObjectReference) {
-        if !object_ref.header().is_marked() {
-            object_ref.header_mut().mark();
-
-            match &object_ref.object_type().id {
-                TypeId::Array(element) => {
-                    if element.is_reference() {
-                        let array_ref = ArrayReference::<u64>::new(object_ref.ptr());
-                        for index in 0..array_ref.length() {
-                            self.mark_value(
-                                FrameValue::new_value(
-                                    element.as_ref(),
-                                    array_ref.get_raw(index)
-                                )
-                            );
-                        }
-                    }
-                }
-                TypeId::Class(_) => {
-                    for field in object_ref.object_type().class.as_ref().unwrap().fields() {
-                        if field.type_id().is_reference() {
-                            self.mark_value(
-                                FrameValue::new_value(
-                                    field.type_id(),
-                                    unsafe { object_ref.ptr().add(field.offset()) as *mut u8 }
-                                )
-                            );
-                        }
-                    }
-                }
-                _ => {}
+            let mut object_ref = ObjectReference::from_ptr(object).unwrap();
+            if !object_ref.header().is_marked() {
+                object_ref.header_mut().mark();
+                visit_reference_fields(&object_ref, |field_ptr| {
+                    to_visit.push(unsafe { field_ptr.read_unaligned() });
+                });
             }
         }
     }
 }
 
 pub trait UpdateReferences {
+    #[allow(dead_code)]
     fn update_references(&self,
                          compiler: &JitCompiler,
                          heap: &Heap,
                          stack_frame: &StackFrame,
                          new_locations: &HashMap<ObjectPointer, ObjectPointer>) {
-        self.update_stack_references(compiler, stack_frame, &new_locations);
-        self.update_heap_references(heap, &new_locations);
+        self.update_stack_references(compiler, stack_frame, new_locations);
+        self.update_heap_references(heap, new_locations);
     }
 
     fn update_stack_references(&self,
@@ -208,10 +513,8 @@ pub trait UpdateReferences {
         stack_frame.walk(
             compiler,
             |frame| {
-                frame.visit_values(|value| {
-                    if value.value_type.is_reference() {
-                        self.update_reference(new_locations, value.ptr_mut() as *mut ObjectPointer);
-                    }
+                frame.visit_references(|value_ptr| {
+                    self.update_reference(new_locations, value_ptr);
                 })
             }
         );
@@ -222,40 +525,30 @@ pub trait UpdateReferences {
                               new_locations: &HashMap<ObjectPointer, ObjectPointer>) {
         for object_ref in HeapObjectsIterator::new(heap) {
             if object_ref.header().is_marked() {
-                match &object_ref.object_type().id {
-                    TypeId::Array(element) => {
-                        if element.is_reference() {
-                            let array_ref = ArrayReference::<u64>::new(object_ref.ptr());
-                            for index in 0..array_ref.length() {
-                                self.update_reference(new_locations, array_ref.get_raw(index) as *mut ObjectPointer);
-                            }
-                        }
-                    }
-                    TypeId::Class(_) => {
-                        for field in object_ref.object_type().class.as_ref().unwrap().fields() {
-                            if field.type_id().is_reference() {
-                                self.update_reference(
-                                    new_locations,
-                                    unsafe { object_ref.ptr().add(field.offset()) as *mut u8 } as *mut ObjectPointer
-                                );
-                            }
-                        }
-                    }
-                    _ => {}
-                }
+                self.update_object_references(&object_ref, new_locations);
             }
         }
     }
 
+    fn update_object_references(&self,
+                                object_ref: &ObjectReference,
+                                new_locations: &HashMap<ObjectPointer, ObjectPointer>) {
+        visit_reference_fields(object_ref, |field_ptr| {
+            self.update_reference(new_locations, field_ptr);
+        });
+    }
+
     fn update_reference(&self,
                         new_locations: &HashMap<ObjectPointer, ObjectPointer>,
                         object_ref_ptr: *mut ObjectPointer) {
         unsafe {
-            let object_ref = *object_ref_ptr as ObjectPointer;
-            if object_ref != std::ptr::null_mut() {
+            let object_ref = object_ref_ptr.read_unaligned();
+            if !object_ref.is_null() {
+                // Objects not being moved (such as old objects in a young collection) are not part of the new locations
                 let old_address = object_ref.sub(object::HEADER_SIZE);
-                let new_address = new_locations[&old_address];
-                *object_ref_ptr = new_address.add(object::HEADER_SIZE);
+                if let Some(new_address) = new_locations.get(&old_address) {
+                    object_ref_ptr.write_unaligned(new_address.add(object::HEADER_SIZE));
+                }
             }
         }
     }
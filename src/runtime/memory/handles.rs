@@ -0,0 +1,125 @@
+use crate::model::typesystem::TypeId;
+use crate::runtime::array;
+use crate::runtime::memory::gc::GarbageCollector;
+use crate::runtime::object::{ObjectPointer, ObjectReference};
+use crate::vm::Value;
+
+/// A reference to a managed object held by the host. The object is kept alive and the reference is updated when the object is moved.
+#[allow(dead_code)]
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct GcRoot {
+    index: usize
+}
+
+pub struct HandleTable {
+    #[allow(dead_code, clippy::vec_box)]
+    slots: Vec<Box<ObjectPointer>>,
+    #[allow(dead_code)]
+    free_slots: Vec<usize>
+}
+
+impl HandleTable {
+    pub fn new() -> HandleTable {
+        HandleTable {
+            slots: Vec::new(),
+            free_slots: Vec::new()
+        }
+    }
+
+    /// Creates a root for the given object. Slots are registered in the collector once and reused after being released.
+    #[allow(dead_code)]
+    pub fn create(&mut self, garbage_collector: &mut GarbageCollector, object: ObjectPointer) -> GcRoot {
+        let index = match self.free_slots.pop() {
+            Some(index) => index,
+            None => {
+                let mut slot: Box<ObjectPointer> = Box::new(std::ptr::null_mut());
+                garbage_collector.add_root(slot.as_mut() as *mut ObjectPointer);
+                self.slots.push(slot);
+                self.slots.len() - 1
+            }
+        };
+
+        *self.slots[index] = object;
+        GcRoot { index }
+    }
+
+    #[allow(dead_code)]
+    pub fn release(&mut self, root: GcRoot) {
+        // Null roots are ignored by the collector
+        *self.slots[root.index] = std::ptr::null_mut();
+        self.free_slots.push(root.index);
+    }
+
+    #[allow(dead_code)]
+    pub fn get(&self, root: &GcRoot) -> ObjectPointer {
+        *self.slots[root.index]
+    }
+
+    #[allow(dead_code)]
+    pub fn set(&mut self, root: &GcRoot, object: ObjectPointer) {
+        *self.slots[root.index] = object;
+    }
+
+    #[allow(dead_code)]
+    pub fn len(&self) -> usize {
+        self.slots.len() - self.free_slots.len()
+    }
+
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Default for HandleTable {
+    fn default() -> Self {
+        HandleTable::new()
+    }
+}
+
+#[allow(dead_code)]
+pub fn element_location(object: ObjectPointer, index: usize) -> Option<(*mut u8, TypeId)> {
+    if object.is_null() {
+        return None;
+    }
+
+    let object_ref = ObjectReference::from_ptr(object).ok()?;
+    match &object_ref.object_type().id {
+        // Structs can't be represented as a single value
+        TypeId::Array(element) if !element.is_struct() && index < array::get_length(object) => {
+            let element_ptr = unsafe { array::get_elements::<u8>(object).add(index * element.size()) as *mut u8 };
+            Some((element_ptr, element.as_ref().clone()))
+        }
+        _ => None
+    }
+}
+
+#[allow(dead_code)]
+pub fn field_location(object: ObjectPointer, name: &str) -> Option<(*mut u8, TypeId)> {
+    if object.is_null() {
+        return None;
+    }
+
+    let object_ref = ObjectReference::from_ptr(object).ok()?;
+    let field = object_ref.object_type().class.as_ref()?.get_field(name).filter(|field| !field.type_id().is_struct())?;
+    let field_ptr = unsafe { (object as *mut u8).add(field.offset()) };
+    Some((field_ptr, field.type_id().clone()))
+}
+
+#[allow(dead_code)]
+pub fn read_value(ptr: *const u8, value_type: &TypeId) -> Value {
+    let mut raw = [0u8; 8];
+    unsafe {
+        std::ptr::copy_nonoverlapping(ptr, raw.as_mut_ptr(), value_type.size());
+    }
+
+    Value::from_raw(value_type, u64::from_le_bytes(raw))
+}
+
+#[allow(dead_code)]
+pub fn write_value(ptr: *mut u8, value_type: &TypeId, value: Value) {
+    let raw = value.to_raw().to_le_bytes();
+    unsafe {
+        std::ptr::copy_nonoverlapping(raw.as_ptr(), ptr, value_type.size());
+    }
+}
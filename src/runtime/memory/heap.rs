@@ -1,17 +1,56 @@
-use crate::model::typesystem::{TypeId, TypeStorage};
-use crate::runtime::array;
-use crate::runtime::object;
 use crate::runtime::object::ObjectReference;
 use crate::runtime::object::ObjectPointer;
 
-pub struct Heap {
+pub struct HeapSettings {
+    pub young_generation_size: usize,
+    pub initial_size: usize,
+    pub max_size: usize,
+    pub growth_factor: f64
+}
+
+impl Default for HeapSettings {
+    fn default() -> Self {
+        HeapSettings {
+            young_generation_size: 1024 * 1024,
+            initial_size: 8 * 1024 * 1024,
+            max_size: 256 * 1024 * 1024,
+            growth_factor: 2.0
+        }
+    }
+}
+
+impl HeapSettings {
+    /// Returns an error message if a heap can't be created from the settings.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.young_generation_size == 0 || self.initial_size == 0 || self.max_size == 0 {
+            return Err("The sizes of the heap must be positive.".to_owned());
+        }
+
+        if self.initial_size > self.max_size {
+            return Err("The initial size of the heap can't be larger than the max size.".to_owned());
+        }
+
+        // Objects that survive a young collection are promoted to the old generation
+        if self.young_generation_size >= self.max_size {
+            return Err("The young generation must be smaller than the max size of the heap.".to_owned());
+        }
+
+        if !self.growth_factor.is_finite() || self.growth_factor < 1.0 {
+            return Err("The growth factor of the heap must be at least 1.".to_owned());
+        }
+
+        Ok(())
+    }
+}
+
+pub struct HeapSegment {
     data: Vec<u8>,
     offset: usize
 }
 
-impl Heap {
-    pub fn new(size: usize) -> Heap {
-        Heap {
+impl HeapSegment {
+    pub fn new(size: usize) -> HeapSegment {
+        HeapSegment {
             data: vec![0; size],
             offset: 0
         }
@@ -21,6 +60,10 @@ impl Heap {
         &self.data
     }
 
+    pub fn size(&self) -> usize {
+        self.data.len()
+    }
+
     pub fn offset(&self) -> usize {
         self.offset
     }
@@ -45,8 +88,80 @@ impl Heap {
     }
 }
 
+pub struct Heap {
+    segments: Vec<HeapSegment>,
+    max_size: usize,
+    growth_factor: f64
+}
+
+impl Heap {
+    pub fn new(size: usize) -> Heap {
+        Heap::with_growth(size, size, 1.0)
+    }
+
+    pub fn with_growth(initial_size: usize, max_size: usize, growth_factor: f64) -> Heap {
+        Heap {
+            segments: vec![HeapSegment::new(initial_size)],
+            max_size,
+            growth_factor
+        }
+    }
+
+    pub fn segments(&self) -> &Vec<HeapSegment> {
+        &self.segments
+    }
+
+    #[allow(dead_code)]
+    pub fn segments_mut(&mut self) -> &mut Vec<HeapSegment> {
+        &mut self.segments
+    }
+
+    pub fn size(&self) -> usize {
+        self.segments.iter().map(|segment| segment.size()).sum()
+    }
+
+    pub fn used_size(&self) -> usize {
+        self.segments.iter().map(|segment| segment.offset()).sum()
+    }
+
+    pub fn set_segment_offsets(&mut self, offsets: Vec<usize>) {
+        for (segment, offset) in self.segments.iter_mut().zip(offsets) {
+            segment.set_offset(offset);
+        }
+    }
+
+    pub fn allocate(&mut self, size: usize) -> Option<*mut std::ffi::c_void> {
+        for segment in &mut self.segments {
+            if let Some(ptr) = segment.allocate(size) {
+                return Some(ptr);
+            }
+        }
+
+        None
+    }
+
+    pub fn inside(&self, address: *const std::ffi::c_void) -> bool {
+        self.segments.iter().any(|segment| segment.inside(address))
+    }
+
+    // Grows the heap by adding a new segment that can hold at least the given size
+    pub fn grow(&mut self, min_size: usize) -> bool {
+        let current_size = self.size();
+        let grow_size = ((current_size as f64 * self.growth_factor) as usize).saturating_sub(current_size).max(min_size);
+
+        if current_size + grow_size <= self.max_size {
+            println!("Grew heap by {} bytes", grow_size);
+            self.segments.push(HeapSegment::new(grow_size));
+            true
+        } else {
+            false
+        }
+    }
+}
+
 pub struct HeapObjectsIterator<'a> {
     heap: &'a Heap,
+    current_segment_index: usize,
     current_object_offset: usize
 }
 
@@ -54,6 +169,7 @@ impl<'a> HeapObjectsIterator<'a> {
     pub fn new(heap: &'a Heap) -> HeapObjectsIterator<'a> {
         HeapObjectsIterator {
             heap,
+            current_segment_index: 0,
             current_object_offset: 0
         }
     }
@@ -63,22 +179,23 @@ impl<'a> Iterator for HeapObjectsIterator<'a> {
     type Item = ObjectReference<'a>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.current_object_offset >= self.heap.offset() {
-            return None;
-        }
-
-        while self.current_object_offset < self.heap.offset() {
-            match ObjectReference::from_full_ptr(unsafe { self.heap.data().as_ptr().add(self.current_object_offset) as ObjectPointer }) {
-                Ok(object_ref) => {
-                    self.current_object_offset += object_ref.full_size();
-                    return Some(object_ref)
-                }
-                Err(deleted_size) => {
-                    self.current_object_offset += deleted_size;
+        while let Some(segment) = self.heap.segments().get(self.current_segment_index) {
+            while self.current_object_offset < segment.offset() {
+                match ObjectReference::from_full_ptr(unsafe { segment.data().as_ptr().add(self.current_object_offset) as ObjectPointer }) {
+                    Ok(object_ref) => {
+                        self.current_object_offset += object_ref.full_size();
+                        return Some(object_ref)
+                    }
+                    Err(deleted_size) => {
+                        self.current_object_offset += deleted_size;
+                    }
                 }
             }
+
+            self.current_segment_index += 1;
+            self.current_object_offset = 0;
         }
 
-        return None;
+        None
     }
-}
\ No newline at end of file
+}
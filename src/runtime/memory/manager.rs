@@ -1,55 +1,274 @@
+use std::collections::HashMap;
+
 use crate::model::class::Class;
-use crate::model::typesystem::{Type, TypeId, TypeStorage};
-use crate::runtime::array;
+use crate::model::typesystem::{Type, TypeId};
+use crate::runtime::{array, string};
 use crate::runtime::memory::gc::GarbageCollector;
-use crate::runtime::memory::heap::Heap;
-use crate::runtime::object::{ObjectHeader, ObjectPointer, ObjectReference};
+use crate::runtime::memory::handles::{HandleTable, GcRoot};
+use crate::runtime::memory::handles;
+use crate::runtime::memory::heap::{Heap, HeapSettings, HeapObjectsIterator};
+use crate::runtime::memory::statics::StaticsArea;
+use crate::runtime::object::{ObjectHeader, ObjectPointer};
 use crate::runtime::object;
+use crate::vm::Value;
 
 pub struct MemoryManager {
-    pub heap: Heap,
-    pub garbage_collector: GarbageCollector
+    pub young_generation: Heap,
+    pub old_generation: Heap,
+    pub garbage_collector: GarbageCollector,
+    #[allow(dead_code)]
+    pub handles: HandleTable,
+    pub statics: StaticsArea,
+    interned_strings: HashMap<String, Box<ObjectPointer>>
 }
 
 impl MemoryManager {
-    pub fn new() -> MemoryManager {
+    pub fn new(settings: &HeapSettings) -> MemoryManager {
         MemoryManager {
-            heap: Heap::new(8 * 1024 * 1024),
-            garbage_collector: GarbageCollector::new()
+            young_generation: Heap::new(settings.young_generation_size),
+            old_generation: Heap::with_growth(settings.initial_size, settings.max_size, settings.growth_factor),
+            garbage_collector: GarbageCollector::new(),
+            handles: HandleTable::new(),
+            statics: StaticsArea::new(),
+            interned_strings: HashMap::new()
         }
     }
 
+    #[allow(dead_code)]
     pub fn is_owned(&self, address: *const std::ffi::c_void) -> bool {
-        self.heap.inside(address)
+        self.young_generation.inside(address) || self.old_generation.inside(address)
+    }
+
+    /// Indicates if the given pointer is a live object in one of the heaps.
+    pub fn is_object(&self, object: ObjectPointer) -> bool {
+        [&self.young_generation, &self.old_generation]
+            .iter()
+            .filter(|heap| heap.inside(object))
+            .any(|heap| HeapObjectsIterator::new(heap).any(|object_ref| object_ref.ptr() == object))
+    }
+
+    /// Same as Value::is_of_type, but a reference given by the host is only dereferenced if it is an object in the heap.
+    pub fn is_value_of_type(&self, value: &Value, value_type: &TypeId) -> bool {
+        match value {
+            Value::Reference(object) if !object.is_null() && !self.is_object(*object) => false,
+            _ => value.is_of_type(value_type)
+        }
     }
 
-    pub fn new_array(&mut self, type_instance: &Type, length: i32) -> ObjectPointer {
-        let element_type = type_instance.id.element_type().expect("unexpected type");
+    pub fn array_size(type_instance: &Type, length: i32) -> usize {
+        assert!(type_instance.id.is_array());
+        array::LENGTH_SIZE + length as usize * type_instance.element_size()
+    }
 
-        let array_size = array::LENGTH_SIZE + length as usize * element_type.size();
-        let obj_ptr = self.new_object(type_instance, array_size);
+    /// Returns None if the number of elements does not fit in the length of an array.
+    pub fn array2d_size(type_instance: &Type, rows: i32, columns: i32) -> Option<usize> {
+        assert!(type_instance.id.is_array2d());
+        let length = rows.checked_mul(columns)?;
+        Some(array::DIMENSIONS_SIZE + length as usize * type_instance.element_size())
+    }
+
+    pub fn class_size(type_instance: &Type) -> usize {
+        type_instance.class.as_ref().unwrap().memory_size()
+    }
+
+    pub fn string_size(text: &str) -> usize {
+        string::LENGTH_SIZE + text.len()
+    }
+
+    pub fn new_array(&mut self, type_instance: &Type, length: i32) -> Option<ObjectPointer> {
+        let array_size = MemoryManager::array_size(type_instance, length);
+        let obj_ptr = self.new_object(type_instance, array_size)?;
 
         unsafe {
-            *(obj_ptr as *mut i32) = length;
+            (obj_ptr as *mut i32).write_unaligned(length);
         }
 
         println!("Allocated array (type: {}, length: {}, size: {}): 0x{:x}", type_instance.id, length, array_size, obj_ptr as u64);
-        obj_ptr
+        Some(obj_ptr)
+    }
+
+    pub fn new_array2d(&mut self, type_instance: &Type, rows: i32, columns: i32) -> Option<ObjectPointer> {
+        let array_size = MemoryManager::array2d_size(type_instance, rows, columns)?;
+        let obj_ptr = self.new_object(type_instance, array_size)?;
+
+        unsafe {
+            (obj_ptr.add(array::ROWS_OFFSET) as *mut i32).write_unaligned(rows);
+            (obj_ptr.add(array::COLUMNS_OFFSET) as *mut i32).write_unaligned(columns);
+        }
+
+        println!("Allocated 2D array (type: {}, rows: {}, columns: {}, size: {}): 0x{:x}", type_instance.id, rows, columns, array_size, obj_ptr as u64);
+        Some(obj_ptr)
     }
 
-    pub fn new_class(&mut self, type_instance: &Type) -> ObjectPointer {
-        let obj_size = type_instance.class.as_ref().unwrap().memory_size();
-        let obj_ptr = self.new_object(type_instance, obj_size);
+    pub fn new_class(&mut self, type_instance: &Type) -> Option<ObjectPointer> {
+        let obj_size = MemoryManager::class_size(type_instance);
+        let obj_ptr = self.new_object(type_instance, obj_size)?;
         println!("Allocated class (type: {}, size: {}): 0x{:x}", type_instance.id, obj_size, obj_ptr as u64);
-        obj_ptr
+        Some(obj_ptr)
+    }
+
+    /// Allocates a class without being able to collect garbage, used when the stack frames can't be walked.
+    pub fn new_class_without_collection(&mut self, type_instance: &Type) -> Option<ObjectPointer> {
+        if let Some(obj_ptr) = self.new_class(type_instance) {
+            return Some(obj_ptr);
+        }
+
+        self.new_old_object_without_collection(type_instance, MemoryManager::class_size(type_instance))
+    }
+
+    pub fn new_string(&mut self, type_instance: &Type, text: &str) -> Option<ObjectPointer> {
+        let string_size = MemoryManager::string_size(text);
+        let obj_ptr = self.new_object(type_instance, string_size)?;
+        string::initialize(obj_ptr, text);
+        println!("Allocated string (length: {}, size: {}): 0x{:x}", text.len(), string_size, obj_ptr as u64);
+        Some(obj_ptr)
+    }
+
+    /// Allocates a string without being able to collect garbage, used when the stack frames can't be walked.
+    pub fn new_string_without_collection(&mut self, type_instance: &Type, text: &str) -> Option<ObjectPointer> {
+        if let Some(obj_ptr) = self.new_string(type_instance, text) {
+            return Some(obj_ptr);
+        }
+
+        let obj_ptr = self.new_old_object_without_collection(type_instance, MemoryManager::string_size(text))?;
+        string::initialize(obj_ptr, text);
+        Some(obj_ptr)
+    }
+
+    /// Returns the address of a slot containing the interned string. The slot is a root, which is updated if the string is moved.
+    pub fn intern_string(&mut self, type_instance: &Type, text: &str) -> Option<*const ObjectPointer> {
+        if let Some(slot) = self.interned_strings.get(text) {
+            return Some(slot.as_ref() as *const ObjectPointer);
+        }
+
+        let mut slot = Box::new(self.new_string_without_collection(type_instance, text)?);
+        let slot_ptr = slot.as_mut() as *mut ObjectPointer;
+        self.garbage_collector.add_root(slot_ptr);
+        self.interned_strings.insert(text.to_owned(), slot);
+        Some(slot_ptr)
+    }
+
+    pub fn interned_string(&self, text: &str) -> Option<*const ObjectPointer> {
+        self.interned_strings.get(text).map(|slot| slot.as_ref() as *const ObjectPointer)
+    }
+
+    /// Returns the address of the static field, which stays the same for the lifetime of the memory manager.
+    pub fn static_field_address(&mut self, class: &Class, field_name: &str) -> Option<*mut u8> {
+        self.statics.field_address(&mut self.garbage_collector, class, field_name)
     }
 
-    fn new_object(&mut self, type_instance: &Type, size: usize) -> ObjectPointer {
-        let obj_ptr = self.heap.allocate(size + object::HEADER_SIZE).unwrap();
+    /// Creates a root that keeps the object alive until released, even if not referenced by any managed code.
+    #[allow(dead_code)]
+    pub fn create_root(&mut self, object: ObjectPointer) -> GcRoot {
+        self.handles.create(&mut self.garbage_collector, object)
+    }
+
+    #[allow(dead_code)]
+    pub fn release_root(&mut self, root: GcRoot) {
+        self.handles.release(root);
+    }
+
+    /// Returns the current address of the rooted object, which is only valid until the next collection.
+    #[allow(dead_code)]
+    pub fn root_object(&self, root: &GcRoot) -> ObjectPointer {
+        self.handles.get(root)
+    }
+
+    /// Returns None if the rooted object is not an array or the index is out of bounds.
+    #[allow(dead_code)]
+    pub fn get_element(&self, root: &GcRoot, index: usize) -> Option<Value> {
+        self.get_object_element(self.handles.get(root), index)
+    }
+
+    /// Returns None if the rooted object is not an array, the index is out of bounds or the value is of the wrong type.
+    #[allow(dead_code)]
+    pub fn set_element(&mut self, root: &GcRoot, index: usize, value: Value) -> Option<()> {
+        self.set_object_element(self.handles.get(root), index, value)
+    }
+
+    /// Returns None if the rooted object is not a class or does not have the field.
+    #[allow(dead_code)]
+    pub fn get_field(&self, root: &GcRoot, name: &str) -> Option<Value> {
+        self.get_object_field(self.handles.get(root), name)
+    }
+
+    /// Returns None if the rooted object is not a class, does not have the field or the value is of the wrong type.
+    #[allow(dead_code)]
+    pub fn set_field(&mut self, root: &GcRoot, name: &str, value: Value) -> Option<()> {
+        self.set_object_field(self.handles.get(root), name, value)
+    }
+
+    /// Same as get_element, but for an object that is not rooted.
+    #[allow(dead_code)]
+    pub fn get_object_element(&self, object: ObjectPointer, index: usize) -> Option<Value> {
+        let (element_ptr, element_type) = handles::element_location(object, index)?;
+        Some(handles::read_value(element_ptr, &element_type))
+    }
+
+    #[allow(dead_code)]
+    pub fn set_object_element(&mut self, object: ObjectPointer, index: usize, value: Value) -> Option<()> {
+        let (element_ptr, element_type) = handles::element_location(object, index)?;
+        self.store_value(object, element_ptr, &element_type, value)
+    }
+
+    #[allow(dead_code)]
+    pub fn get_object_field(&self, object: ObjectPointer, name: &str) -> Option<Value> {
+        let (field_ptr, field_type) = handles::field_location(object, name)?;
+        Some(handles::read_value(field_ptr, &field_type))
+    }
+
+    #[allow(dead_code)]
+    pub fn set_object_field(&mut self, object: ObjectPointer, name: &str, value: Value) -> Option<()> {
+        let (field_ptr, field_type) = handles::field_location(object, name)?;
+        self.store_value(object, field_ptr, &field_type, value)
+    }
+
+    #[allow(dead_code)]
+    fn store_value(&mut self, object: ObjectPointer, value_ptr: *mut u8, value_type: &TypeId, value: Value) -> Option<()> {
+        if !self.is_value_of_type(&value, value_type) {
+            return None;
+        }
+
+        // Same as the write barrier in the generated code
+        if let Value::Reference(reference) = value {
+            if !reference.is_null() && self.young_generation.inside(reference) && !self.young_generation.inside(object) {
+                self.garbage_collector.remember(object);
+            }
+        }
+
+        handles::write_value(value_ptr, value_type, value);
+        Some(())
+    }
+
+    fn new_old_object_without_collection(&mut self, type_instance: &Type, size: usize) -> Option<ObjectPointer> {
+        let full_size = size + object::HEADER_SIZE;
+        loop {
+            if let Some(obj_ptr) = self.old_generation.allocate(full_size) {
+                return Some(MemoryManager::initialize_object(obj_ptr, type_instance, size));
+            }
+
+            if !self.old_generation.grow(full_size) {
+                return None;
+            }
+        }
+    }
+
+    fn new_object(&mut self, type_instance: &Type, size: usize) -> Option<ObjectPointer> {
+        // Large objects are placed directly in the old generation to avoid copying them
+        let full_size = size + object::HEADER_SIZE;
+        let obj_ptr = if full_size <= self.young_generation.size() / 4 {
+            self.young_generation.allocate(full_size)?
+        } else {
+            self.old_generation.allocate(full_size)?
+        };
+
+        Some(MemoryManager::initialize_object(obj_ptr, type_instance, size))
+    }
 
+    fn initialize_object(obj_ptr: ObjectPointer, type_instance: &Type, size: usize) -> ObjectPointer {
         unsafe {
             let obj_ptr = obj_ptr as *mut u8;
-            for i in 0..size as isize {
+            for i in 0..(size + object::HEADER_SIZE) as isize {
                 *obj_ptr.offset(i) = 0;
             }
 
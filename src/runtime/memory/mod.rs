@@ -1,3 +1,5 @@
 pub mod manager;
 pub mod heap;
 pub mod gc;
+pub mod handles;
+pub mod statics;
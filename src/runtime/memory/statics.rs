@@ -0,0 +1,42 @@
+use std::collections::HashMap;
+
+use crate::model::class::Class;
+use crate::runtime::memory::gc::GarbageCollector;
+use crate::runtime::object::ObjectPointer;
+
+/// Holds the static fields of classes. The fields of a class are stored in memory that is never moved, such that the addresses can be embedded in code.
+pub struct StaticsArea {
+    classes: HashMap<String, Box<[u8]>>
+}
+
+impl StaticsArea {
+    pub fn new() -> StaticsArea {
+        StaticsArea {
+            classes: HashMap::new()
+        }
+    }
+
+    /// Returns the address of the given static field. The fields of a class are zero initialized when first used, where reference fields become roots.
+    pub fn field_address(&mut self, garbage_collector: &mut GarbageCollector, class: &Class, field_name: &str) -> Option<*mut u8> {
+        let field = class.get_static_field(field_name)?;
+
+        let fields = self.classes.entry(class.name().to_owned()).or_insert_with(|| {
+            let mut fields = vec![0u8; class.static_memory_size()].into_boxed_slice();
+            for field in class.static_fields() {
+                for reference_offset in field.reference_offsets() {
+                    garbage_collector.add_root(unsafe { fields.as_mut_ptr().add(field.offset() + reference_offset) } as *mut ObjectPointer);
+                }
+            }
+
+            fields
+        });
+
+        Some(unsafe { fields.as_mut_ptr().add(field.offset()) })
+    }
+}
+
+impl Default for StaticsArea {
+    fn default() -> Self {
+        StaticsArea::new()
+    }
+}
@@ -1,5 +1,10 @@
 pub mod runtime_interface;
 pub mod memory;
 pub mod array;
+pub mod string;
+pub mod io;
+pub mod math;
+pub mod native;
 pub mod stack_walker;
 pub mod object;
+pub mod exceptions;
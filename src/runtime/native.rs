@@ -0,0 +1,461 @@
+use std::any::Any;
+use std::cell::Cell;
+use std::marker::PhantomData;
+use std::panic::AssertUnwindSafe;
+use std::rc::Rc;
+
+use crate::model::function::FunctionAddress;
+use crate::model::typesystem::TypeId;
+use crate::engine::execution::RuntimeError;
+use crate::runtime::{array, string};
+use crate::runtime::object::ObjectPointer;
+use crate::vm::{get_vm, Value};
+
+/// A Rust type that can be passed to and returned from native functions.
+/// Structs can't be passed, as they are stored inline and not behind a reference.
+#[allow(dead_code)]
+pub trait NativeType: Copy + Default + 'static {
+    fn type_id() -> TypeId;
+    fn to_value(self) -> Value;
+    fn from_value(value: Value) -> Option<Self>;
+}
+
+impl NativeType for () {
+    fn type_id() -> TypeId {
+        TypeId::Void
+    }
+
+    fn to_value(self) -> Value {
+        Value::Void
+    }
+
+    fn from_value(value: Value) -> Option<Self> {
+        match value {
+            Value::Void => Some(()),
+            _ => None
+        }
+    }
+}
+
+impl NativeType for i32 {
+    fn type_id() -> TypeId {
+        TypeId::Int32
+    }
+
+    fn to_value(self) -> Value {
+        Value::Int32(self)
+    }
+
+    fn from_value(value: Value) -> Option<Self> {
+        match value {
+            Value::Int32(value) => Some(value),
+            _ => None
+        }
+    }
+}
+
+impl NativeType for f32 {
+    fn type_id() -> TypeId {
+        TypeId::Float32
+    }
+
+    fn to_value(self) -> Value {
+        Value::Float32(self)
+    }
+
+    fn from_value(value: Value) -> Option<Self> {
+        match value {
+            Value::Float32(value) => Some(value),
+            _ => None
+        }
+    }
+}
+
+impl NativeType for i64 {
+    fn type_id() -> TypeId {
+        TypeId::Int64
+    }
+
+    fn to_value(self) -> Value {
+        Value::Int64(self)
+    }
+
+    fn from_value(value: Value) -> Option<Self> {
+        match value {
+            Value::Int64(value) => Some(value),
+            _ => None
+        }
+    }
+}
+
+impl NativeType for f64 {
+    fn type_id() -> TypeId {
+        TypeId::Float64
+    }
+
+    fn to_value(self) -> Value {
+        Value::Float64(self)
+    }
+
+    fn from_value(value: Value) -> Option<Self> {
+        match value {
+            Value::Float64(value) => Some(value),
+            _ => None
+        }
+    }
+}
+
+impl NativeType for bool {
+    fn type_id() -> TypeId {
+        TypeId::Bool
+    }
+
+    fn to_value(self) -> Value {
+        Value::Bool(self)
+    }
+
+    fn from_value(value: Value) -> Option<Self> {
+        match value {
+            Value::Bool(value) => Some(value),
+            _ => None
+        }
+    }
+}
+
+/// A reference to a managed array, which is null by default.
+/// The garbage collector can move the array if managed code is called, so it must be pinned to be used after that.
+#[allow(dead_code)]
+#[repr(transparent)]
+#[derive(Clone, Copy)]
+pub struct NativeArray<T: NativeType> {
+    object: ObjectPointer,
+    element: PhantomData<T>
+}
+
+impl<T: NativeType> NativeArray<T> {
+    #[allow(dead_code)]
+    pub fn from_ptr(object: ObjectPointer) -> NativeArray<T> {
+        NativeArray {
+            object,
+            element: PhantomData
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn as_ptr(&self) -> ObjectPointer {
+        self.object
+    }
+
+    #[allow(dead_code)]
+    pub fn is_null(&self) -> bool {
+        self.object.is_null()
+    }
+
+    #[allow(dead_code)]
+    pub fn length(&self) -> usize {
+        if self.object.is_null() {
+            return 0;
+        }
+
+        array::get_length(self.object)
+    }
+
+    /// Returns None if the array is null or the index is out of bounds.
+    #[allow(dead_code)]
+    pub fn get(&self, index: usize) -> Option<T> {
+        let value = get_vm(|vm| vm.memory_manager.get_object_element(self.object, index))?;
+        if !value.is_of_type(&T::type_id()) {
+            return None;
+        }
+
+        T::from_value(value)
+    }
+
+    /// Returns None if the array is null, the index is out of bounds or the element is of the wrong type.
+    #[allow(dead_code)]
+    pub fn set(&self, index: usize, value: T) -> Option<()> {
+        get_vm(|vm| vm.memory_manager.set_object_element(self.object, index, value.to_value()))
+    }
+}
+
+impl<T: NativeType> Default for NativeArray<T> {
+    fn default() -> Self {
+        NativeArray::from_ptr(std::ptr::null_mut())
+    }
+}
+
+impl<T: NativeType> NativeType for NativeArray<T> {
+    fn type_id() -> TypeId {
+        TypeId::Array(Box::new(T::type_id()))
+    }
+
+    fn to_value(self) -> Value {
+        Value::Reference(self.object)
+    }
+
+    fn from_value(value: Value) -> Option<Self> {
+        match value {
+            Value::Reference(object) => Some(NativeArray::from_ptr(object)),
+            _ => None
+        }
+    }
+}
+
+/// A reference to a managed string, which is null by default. Null is treated as the empty string.
+#[allow(dead_code)]
+#[repr(transparent)]
+#[derive(Clone, Copy)]
+pub struct NativeString {
+    object: ObjectPointer
+}
+
+impl NativeString {
+    #[allow(dead_code)]
+    pub fn from_ptr(object: ObjectPointer) -> NativeString {
+        NativeString {
+            object
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn as_ptr(&self) -> ObjectPointer {
+        self.object
+    }
+
+    /// The text is only valid until managed code is called, as the string can be moved.
+    #[allow(dead_code)]
+    pub fn as_str(&self) -> &str {
+        string::as_str(self.object)
+    }
+}
+
+impl Default for NativeString {
+    fn default() -> Self {
+        NativeString::from_ptr(std::ptr::null_mut())
+    }
+}
+
+impl NativeType for NativeString {
+    fn type_id() -> TypeId {
+        TypeId::String
+    }
+
+    fn to_value(self) -> Value {
+        Value::Reference(self.object)
+    }
+
+    fn from_value(value: Value) -> Option<Self> {
+        match value {
+            Value::Reference(object) => Some(NativeString::from_ptr(object)),
+            _ => None
+        }
+    }
+}
+
+/// A class that native functions can reference, given by its name as the Rust type does not define the class.
+#[allow(dead_code)]
+pub trait NativeClass: 'static {
+    fn class_name() -> &'static str;
+}
+
+/// A reference to an instance of a managed class, which is null by default.
+/// The garbage collector can move the object if managed code is called, so it must be pinned to be used after that.
+#[allow(dead_code)]
+#[repr(transparent)]
+pub struct NativeObject<C: NativeClass> {
+    object: ObjectPointer,
+    class: PhantomData<C>
+}
+
+impl<C: NativeClass> NativeObject<C> {
+    #[allow(dead_code)]
+    pub fn from_ptr(object: ObjectPointer) -> NativeObject<C> {
+        NativeObject {
+            object,
+            class: PhantomData
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn as_ptr(&self) -> ObjectPointer {
+        self.object
+    }
+
+    #[allow(dead_code)]
+    pub fn is_null(&self) -> bool {
+        self.object.is_null()
+    }
+
+    /// Returns None if the object is null or does not have a field of the given type.
+    #[allow(dead_code)]
+    pub fn get_field<T: NativeType>(&self, name: &str) -> Option<T> {
+        let value = get_vm(|vm| vm.memory_manager.get_object_field(self.object, name))?;
+        if !value.is_of_type(&T::type_id()) {
+            return None;
+        }
+
+        T::from_value(value)
+    }
+
+    /// Returns None if the object is null or does not have a field of the given type.
+    #[allow(dead_code)]
+    pub fn set_field<T: NativeType>(&self, name: &str, value: T) -> Option<()> {
+        get_vm(|vm| vm.memory_manager.set_object_field(self.object, name, value.to_value()))
+    }
+}
+
+impl<C: NativeClass> Clone for NativeObject<C> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<C: NativeClass> Copy for NativeObject<C> {}
+
+impl<C: NativeClass> Default for NativeObject<C> {
+    fn default() -> Self {
+        NativeObject::from_ptr(std::ptr::null_mut())
+    }
+}
+
+impl<C: NativeClass> NativeType for NativeObject<C> {
+    fn type_id() -> TypeId {
+        TypeId::Class(C::class_name().to_owned())
+    }
+
+    fn to_value(self) -> Value {
+        Value::Reference(self.object)
+    }
+
+    fn from_value(value: Value) -> Option<Self> {
+        match value {
+            Value::Reference(object) => Some(NativeObject::from_ptr(object)),
+            _ => None
+        }
+    }
+}
+
+/// A Rust closure that can be called from managed code.
+#[allow(dead_code)]
+pub trait NativeFunction<Args>: 'static {
+    fn parameters() -> Vec<TypeId>;
+    fn return_type() -> TypeId;
+    fn trampoline() -> FunctionAddress;
+}
+
+// Closures of the same type share a trampoline, so each registration gets a stub that selects its slot before jumping to it
+pub struct NativeFunctions {
+    #[allow(dead_code)]
+    functions: Vec<Rc<dyn Any>>,
+    #[allow(dead_code)]
+    current_slot: Box<Cell<usize>>
+}
+
+impl NativeFunctions {
+    pub fn new() -> NativeFunctions {
+        NativeFunctions {
+            functions: Vec::new(),
+            current_slot: Box::new(Cell::new(0))
+        }
+    }
+
+    /// Returns the slot of the added function.
+    #[allow(dead_code)]
+    pub fn add<F: 'static>(&mut self, function: F) -> usize {
+        self.functions.push(Rc::new(function));
+        self.functions.len() - 1
+    }
+
+    #[allow(dead_code)]
+    pub fn current_slot_address(&self) -> *mut usize {
+        self.current_slot.as_ptr()
+    }
+
+    #[allow(dead_code)]
+    fn current<F: 'static>(&self) -> Rc<F> {
+        self.functions[self.current_slot.get()].clone().downcast::<F>().unwrap()
+    }
+}
+
+/// Pins the object while the given function is called, such that the garbage collector does not move it.
+#[allow(dead_code)]
+pub fn pin<F: FnOnce(ObjectPointer) -> R, R>(object: ObjectPointer, function: F) -> R {
+    let pinned = PinnedObject::new(object);
+    function(pinned.object)
+}
+
+// The object is unpinned when dropped, which also happens if the function panics
+#[allow(dead_code)]
+struct PinnedObject {
+    object: ObjectPointer
+}
+
+impl PinnedObject {
+    #[allow(dead_code)]
+    fn new(object: ObjectPointer) -> PinnedObject {
+        get_vm(|vm| vm.memory_manager.garbage_collector.pin(object));
+        PinnedObject { object }
+    }
+}
+
+impl Drop for PinnedObject {
+    fn drop(&mut self) {
+        get_vm(|vm| vm.memory_manager.garbage_collector.unpin(self.object));
+    }
+}
+
+#[allow(dead_code)]
+fn call_native<F: 'static, R: NativeType, C: FnOnce(&F) -> R>(call: C) -> R {
+    // The slot must be read before anything else, as calling managed code can change it.
+    // The closure is called without holding the VM, as it might use it.
+    let function = get_vm(|vm| vm.native_functions.current::<F>());
+
+    match std::panic::catch_unwind(AssertUnwindSafe(|| call(&function))) {
+        Ok(result) => result,
+        Err(panic) => {
+            let message = if let Some(message) = panic.downcast_ref::<&str>() {
+                message.to_string()
+            } else if let Some(message) = panic.downcast_ref::<String>() {
+                message.clone()
+            } else {
+                "unknown".to_owned()
+            };
+
+            get_vm(|vm| vm.engine.runtime_error.set_native_error(RuntimeError::NativePanic(message.clone())));
+            R::default()
+        }
+    }
+}
+
+macro_rules! native_function {
+    ($trampoline:ident, $($argument:ident: $argument_type:ident),*) => {
+        #[allow(dead_code)]
+        extern "C" fn $trampoline<F, $($argument_type,)* R>($($argument: $argument_type),*) -> R
+            where F: Fn($($argument_type),*) -> R + 'static, $($argument_type: NativeType,)* R: NativeType {
+            call_native::<F, R, _>(|function| function($($argument),*))
+        }
+
+        impl<F, $($argument_type,)* R> NativeFunction<($($argument_type,)*)> for F
+            where F: Fn($($argument_type),*) -> R + 'static, $($argument_type: NativeType,)* R: NativeType {
+            fn parameters() -> Vec<TypeId> {
+                vec![$($argument_type::type_id()),*]
+            }
+
+            fn return_type() -> TypeId {
+                R::type_id()
+            }
+
+            fn trampoline() -> FunctionAddress {
+                $trampoline::<F, $($argument_type,)* R> as *mut std::ffi::c_void
+            }
+        }
+    };
+}
+
+native_function!(trampoline0,);
+native_function!(trampoline1, argument1: T1);
+native_function!(trampoline2, argument1: T1, argument2: T2);
+native_function!(trampoline3, argument1: T1, argument2: T2, argument3: T3);
+native_function!(trampoline4, argument1: T1, argument2: T2, argument3: T3, argument4: T4);
+native_function!(trampoline5, argument1: T1, argument2: T2, argument3: T3, argument4: T4, argument5: T5);
+native_function!(trampoline6, argument1: T1, argument2: T2, argument3: T3, argument4: T4, argument5: T5, argument6: T6);
@@ -1,5 +1,5 @@
-use crate::model::typesystem::{Type, TypeId, TypeStorage};
-use crate::runtime::array;
+use crate::model::typesystem::{Type, TypeId};
+use crate::runtime::{array, string};
 
 pub type ObjectPointer = *mut std::ffi::c_void;
 
@@ -32,10 +32,10 @@ impl ObjectHeader {
         self.get_gc_info().1
     }
 
-    fn delete(&mut self, size: u64) {
+    pub fn delete(&mut self, size: u64) {
         unsafe {
-            let object_type: &u64 = std::mem::transmute(&self.object_type);
-            *(object_type as *const u64 as *mut u64) = size;
+            let object_type = std::ptr::addr_of_mut!(self.object_type) as *mut u64;
+            object_type.write_unaligned(size);
         }
         self.gc_info = 0xFF;
     }
@@ -82,8 +82,15 @@ impl<'a> ObjectReference<'a> {
         let object_ptr = unsafe { ptr.add(HEADER_SIZE) };
 
         let object_size = match &type_metadata.id {
-            TypeId::Array(element) => {
-                array::LENGTH_SIZE + element.size() * array::get_length(object_ptr as ObjectPointer)
+            TypeId::String => {
+                string::LENGTH_SIZE + string::get_length(object_ptr as ObjectPointer)
+            }
+            TypeId::Array(_) => {
+                array::LENGTH_SIZE + type_metadata.element_size() * array::get_length(object_ptr as ObjectPointer)
+            }
+            TypeId::Array2D(_) => {
+                let object_ptr = object_ptr as ObjectPointer;
+                array::DIMENSIONS_SIZE + type_metadata.element_size() * array::get_rows(object_ptr) * array::get_columns(object_ptr)
             }
             TypeId::Class(_) => {
                 type_metadata.class.as_ref().unwrap().memory_size()
@@ -113,7 +120,7 @@ impl<'a> ObjectReference<'a> {
     }
 
     pub fn object_type(&self) -> &Type {
-        &self.object_type
+        self.object_type
     }
 
     pub fn size(&self) -> usize {
@@ -132,6 +139,7 @@ impl<'a> ObjectReference<'a> {
         unsafe { (self.full_ptr() as *mut ObjectHeader).as_mut() }.unwrap()
     }
 
+    #[allow(dead_code)]
     pub fn delete(&mut self) {
         let full_size = self.full_size();
         self.header_mut().delete(full_size as u64);
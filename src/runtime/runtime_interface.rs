@@ -1,57 +1,221 @@
 use crate::vm::get_vm;
 use crate::model::typesystem::{TypeId, Type};
 use crate::engine::execution::RuntimeError;
-use crate::model::function::{FunctionSignature, Function};
-use crate::compiler::stack_layout;
+use crate::model::function::Function;
 use crate::runtime::stack_walker::StackFrame;
-use crate::runtime::object::ObjectPointer;
+use crate::runtime::object::{ObjectPointer, ObjectReference};
+use crate::runtime::exceptions;
+use crate::runtime::exceptions::ExceptionHandlerTarget;
+use crate::runtime::object;
+use crate::vm::VirtualMachine;
+use crate::runtime::memory::manager::MemoryManager;
 
 pub extern "C" fn set_error_return(return_address: u64, base_pointer: u64, stack_pointer: u64) {
     get_vm(|vm| {
         vm.engine.runtime_error.return_address = return_address;
         vm.engine.runtime_error.base_pointer = base_pointer;
         vm.engine.runtime_error.stack_pointer = stack_pointer;
+        vm.engine.stack_limit.set_stack_start(stack_pointer);
     })
 }
 
-pub extern "C" fn new_array(type_ptr: *const Type, length: i32) -> ObjectPointer {
+pub extern "C" fn new_array(type_ptr: *const Type,
+                            length: i32,
+                            base_pointer: u64,
+                            function_ptr: *const Function,
+                            instruction_index: usize) -> ObjectPointer {
     get_vm(|vm| {
         let type_metadata = unsafe { type_ptr.as_ref() }.unwrap();
-        vm.memory_manager.new_array(type_metadata, length)
+        allocate_object(
+            vm,
+            base_pointer,
+            function_ptr,
+            instruction_index,
+            MemoryManager::array_size(type_metadata, length),
+            |memory_manager| memory_manager.new_array(type_metadata, length)
+        )
     })
 }
 
-pub extern "C" fn new_class(type_ptr: *const Type) -> ObjectPointer {
+pub extern "C" fn new_array2d(type_ptr: *const Type,
+                              rows: i32,
+                              columns: i32,
+                              base_pointer: u64,
+                              function_ptr: *const Function,
+                              instruction_index: usize) -> ObjectPointer {
     get_vm(|vm| {
         let type_metadata = unsafe { type_ptr.as_ref() }.unwrap();
-        vm.memory_manager.new_class(type_metadata)
+
+        // Too many elements is treated as being out of memory
+        let array_size = match MemoryManager::array2d_size(type_metadata, rows, columns) {
+            Some(array_size) => array_size,
+            None => { return std::ptr::null_mut(); }
+        };
+
+        allocate_object(
+            vm,
+            base_pointer,
+            function_ptr,
+            instruction_index,
+            array_size,
+            |memory_manager| memory_manager.new_array2d(type_metadata, rows, columns)
+        )
     })
 }
 
-pub extern "C" fn null_error(result_ptr: *mut u64) {
-    runtime_error(result_ptr, RuntimeError::NullReference)
+pub extern "C" fn new_class(type_ptr: *const Type,
+                            base_pointer: u64,
+                            function_ptr: *const Function,
+                            instruction_index: usize) -> ObjectPointer {
+    get_vm(|vm| {
+        let type_metadata = unsafe { type_ptr.as_ref() }.unwrap();
+        allocate_object(
+            vm,
+            base_pointer,
+            function_ptr,
+            instruction_index,
+            MemoryManager::class_size(type_metadata),
+            |memory_manager| memory_manager.new_class(type_metadata)
+        )
+    })
 }
 
-pub extern "C" fn array_create_error(result_ptr: *mut u64) {
-    runtime_error(result_ptr, RuntimeError::ArrayCreate)
+pub fn allocate_object<F: FnMut(&mut MemoryManager) -> Option<ObjectPointer>>(vm: &mut VirtualMachine,
+                                                                            base_pointer: u64,
+                                                                            function_ptr: *const Function,
+                                                                            instruction_index: usize,
+                                                                            size: usize,
+                                                                            mut allocate: F) -> ObjectPointer {
+    if let Some(obj_ptr) = allocate(&mut vm.memory_manager) {
+        return obj_ptr;
+    }
+
+    // Out of memory, try to free some and then try again. Start with only the young generation as it is the cheapest.
+    collect_garbage(vm, base_pointer, function_ptr, instruction_index, false);
+    if let Some(obj_ptr) = allocate(&mut vm.memory_manager) {
+        return obj_ptr;
+    }
+
+    collect_garbage(vm, base_pointer, function_ptr, instruction_index, true);
+    if let Some(obj_ptr) = allocate(&mut vm.memory_manager) {
+        return obj_ptr;
+    }
+
+    // Still not enough memory, grow the heap. A young collection is done to be able to promote objects to the new space.
+    while vm.memory_manager.old_generation.grow(size + object::HEADER_SIZE) {
+        collect_garbage(vm, base_pointer, function_ptr, instruction_index, false);
+        if let Some(obj_ptr) = allocate(&mut vm.memory_manager) {
+            return obj_ptr;
+        }
+    }
+
+    // A null pointer signals that we are out of memory
+    std::ptr::null_mut()
 }
 
-pub extern "C" fn array_bounds_error(result_ptr: *mut u64) {
-    runtime_error(result_ptr, RuntimeError::ArrayBounds)
+pub extern "C" fn write_barrier(object_ptr: ObjectPointer) {
+    get_vm(|vm| {
+        vm.memory_manager.garbage_collector.remember(object_ptr);
+    });
 }
 
-fn runtime_error(result_ptr: *mut u64, runtime_error: RuntimeError) {
+pub extern "C" fn null_error(result_ptr: *mut u64, error_address: u64, base_pointer: u64) {
+    runtime_error(result_ptr, error_address, base_pointer, RuntimeError::NullReference)
+}
+
+pub extern "C" fn array_create_error(result_ptr: *mut u64, error_address: u64, base_pointer: u64) {
+    runtime_error(result_ptr, error_address, base_pointer, RuntimeError::ArrayCreate)
+}
+
+pub extern "C" fn array_bounds_error(result_ptr: *mut u64, error_address: u64, base_pointer: u64) {
+    runtime_error(result_ptr, error_address, base_pointer, RuntimeError::ArrayBounds)
+}
+
+pub extern "C" fn out_of_memory_error(result_ptr: *mut u64, error_address: u64, base_pointer: u64) {
+    runtime_error(result_ptr, error_address, base_pointer, RuntimeError::OutOfMemory)
+}
+
+pub extern "C" fn division_by_zero_error(result_ptr: *mut u64, error_address: u64, base_pointer: u64) {
+    runtime_error(result_ptr, error_address, base_pointer, RuntimeError::DivisionByZero)
+}
+
+pub extern "C" fn division_overflow_error(result_ptr: *mut u64, error_address: u64, base_pointer: u64) {
+    runtime_error(result_ptr, error_address, base_pointer, RuntimeError::DivisionOverflow)
+}
+
+pub extern "C" fn invalid_cast_error(result_ptr: *mut u64, error_address: u64, base_pointer: u64) {
+    runtime_error(result_ptr, error_address, base_pointer, RuntimeError::InvalidCast)
+}
+
+pub extern "C" fn stack_overflow_error(result_ptr: *mut u64, error_address: u64, base_pointer: u64) {
+    runtime_error(result_ptr, error_address, base_pointer, RuntimeError::StackOverflow)
+}
+
+pub extern "C" fn native_error(result_ptr: *mut u64, error_address: u64, base_pointer: u64) {
+    let error = get_vm(|vm| vm.engine.runtime_error.take_native_error()).unwrap_or(RuntimeError::NativePanic(String::new()));
+    runtime_error(result_ptr, error_address, base_pointer, error)
+}
+
+pub extern "C" fn throw_exception(result_ptr: *mut u64, error_address: u64, base_pointer: u64, exception: ObjectPointer) {
+    if exception.is_null() {
+        return runtime_error(result_ptr, error_address, base_pointer, RuntimeError::NullReference);
+    }
+
     get_vm(|vm| {
-        vm.engine.runtime_error.has_error = Some(runtime_error.clone());
+        let exception_type = ObjectReference::from_ptr(exception).unwrap().object_type().id.clone();
+
+        match exceptions::find_handler(vm.engine.compiler(), &vm.type_storage, base_pointer, error_address, &exception_type) {
+            Some(handler) => {
+                continue_at_handler(result_ptr, &handler, exception);
+            }
+            None => {
+                unhandled_error(vm, result_ptr, error_address, base_pointer, exceptions::unhandled_exception_error(&exception_type));
+            }
+        }
+    });
+}
 
-        unsafe {
-            *result_ptr = vm.engine.runtime_error.return_address;
-            *result_ptr.add(1) = vm.engine.runtime_error.base_pointer;
-            *result_ptr.add(2) = vm.engine.runtime_error.stack_pointer;
+fn runtime_error(result_ptr: *mut u64, error_address: u64, base_pointer: u64, runtime_error: RuntimeError) {
+    get_vm(|vm| {
+        if let Some(class_name) = exceptions::exception_class(&runtime_error) {
+            let exception_type = TypeId::Class(class_name.to_owned());
+            if let Some(handler) = exceptions::find_handler(vm.engine.compiler(), &vm.type_storage, base_pointer, error_address, &exception_type) {
+                let type_metadata = vm.type_storage.get(&exception_type).unwrap();
+                if let Some(exception) = vm.memory_manager.new_class_without_collection(type_metadata) {
+                    continue_at_handler(result_ptr, &handler, exception);
+                    return;
+                }
+            }
         }
+
+        unhandled_error(vm, result_ptr, error_address, base_pointer, runtime_error.clone());
     });
 }
 
+fn continue_at_handler(result_ptr: *mut u64, handler: &ExceptionHandlerTarget, exception: ObjectPointer) {
+    unsafe {
+        *result_ptr = handler.handler_address;
+        *result_ptr.add(1) = handler.base_pointer;
+        *result_ptr.add(2) = handler.stack_pointer;
+        *result_ptr.add(3) = exception as u64;
+    }
+}
+
+fn unhandled_error(vm: &mut VirtualMachine, result_ptr: *mut u64, error_address: u64, base_pointer: u64, runtime_error: RuntimeError) {
+    vm.engine.runtime_error.has_error = Some(runtime_error);
+    vm.engine.runtime_error.stack_trace = StackFrame::from_native_address(vm.engine.compiler(), base_pointer, error_address)
+        .map(|stack_frame| stack_frame.stack_trace(vm.engine.compiler()))
+        .unwrap_or_default();
+
+    // Return from the entrypoint
+    unsafe {
+        *result_ptr = vm.engine.runtime_error.return_address;
+        *result_ptr.add(1) = vm.engine.runtime_error.base_pointer;
+        *result_ptr.add(2) = vm.engine.runtime_error.stack_pointer + 8;
+        *result_ptr.add(3) = 0;
+    }
+}
+
 pub extern "C" fn print_stack_frame(base_pointer: u64, function_ptr: *const Function, instruction_index: usize) {
     get_vm(|vm| {
         let function = unsafe { function_ptr.as_ref().unwrap() };
@@ -77,16 +241,31 @@ pub extern "C" fn print_stack_frame(base_pointer: u64, function_ptr: *const Func
 
 pub extern "C" fn garbage_collect(base_pointer: u64, function_ptr: *const Function, instruction_index: usize) {
     get_vm(|vm| {
-        let function = unsafe { function_ptr.as_ref().unwrap() };
-        let compilation_data = vm.engine.compiler()
-            .get_compilation_data(&function.declaration().signature())
-            .unwrap();
+        collect_garbage(vm, base_pointer, function_ptr, instruction_index, true);
+    });
+}
 
-        let stack_frame = StackFrame::new(base_pointer, instruction_index, function, compilation_data);
-        vm.memory_manager.garbage_collector.collect(
+fn collect_garbage(vm: &mut VirtualMachine, base_pointer: u64, function_ptr: *const Function, instruction_index: usize, full: bool) {
+    let function = unsafe { function_ptr.as_ref().unwrap() };
+    let compilation_data = vm.engine.compiler()
+        .get_compilation_data(&function.declaration().signature())
+        .unwrap();
+
+    let stack_frame = StackFrame::new(base_pointer, instruction_index, function, compilation_data);
+    let memory_manager = &mut vm.memory_manager;
+    if full {
+        memory_manager.garbage_collector.collect(
             vm.engine.compiler(),
-            &mut vm.memory_manager.heap,
+            &mut memory_manager.young_generation,
+            &mut memory_manager.old_generation,
             stack_frame
         );
-    });
+    } else {
+        memory_manager.garbage_collector.collect_young(
+            vm.engine.compiler(),
+            &mut memory_manager.young_generation,
+            &mut memory_manager.old_generation,
+            stack_frame
+        );
+    }
 }
\ No newline at end of file
@@ -1,10 +1,11 @@
 use crate::compiler::{FunctionCompilationData, stack_layout};
 use crate::model::function::Function;
 use crate::compiler::jit::JitCompiler;
-use crate::model::binder::Binder;
 use crate::mir::RegisterMIR;
+use crate::analysis::VirtualRegister;
 use crate::model::typesystem::TypeId;
 use crate::runtime::object::ObjectPointer;
+use crate::engine::execution::StackTraceEntry;
 
 pub struct StackFrame<'a> {
     base_pointer: u64,
@@ -26,6 +27,35 @@ impl<'a> StackFrame<'a> {
         }
     }
 
+    pub fn from_native_address(compiler: &'a JitCompiler, base_pointer: u64, native_address: u64) -> Option<StackFrame<'a>> {
+        let function_address = unsafe { *((base_pointer as isize - 8) as *const u64) };
+        let function = unsafe { (function_address as *const Function).as_ref() }?;
+        let compilation_data = compiler.get_compilation_data(&function.declaration().signature())?;
+
+        let function_code_ptr = function.declaration().address()?;
+        let offset = (native_address as isize - function_code_ptr as isize) as usize;
+        let instruction_index = instruction_index_from_offset(compilation_data, offset)
+            .or_else(|| compilation_data.instructions_offsets.first().map(|(index, _)| *index))?;
+
+        Some(StackFrame::new(base_pointer, instruction_index, function, compilation_data))
+    }
+
+    pub fn base_pointer(&self) -> u64 {
+        self.base_pointer
+    }
+
+    pub fn instruction_index(&self) -> usize {
+        self.instruction_index
+    }
+
+    pub fn function(&self) -> &'a Function {
+        self.function
+    }
+
+    pub fn compilation_data(&self) -> &'a FunctionCompilationData {
+        self.compilation_data
+    }
+
     pub fn parent(&self, compiler: &'a JitCompiler) -> Option<StackFrame<'a>> {
         if self.function.declaration().name() == "main" {
             return None;
@@ -33,7 +63,7 @@ impl<'a> StackFrame<'a> {
 
         let parent_base_pointer = unsafe { *(self.base_pointer as *const u64) };
         let parent_function_address = unsafe { *((parent_base_pointer as isize - 8) as *const u64) };
-        let parent_function = unsafe { (parent_function_address as *const Function).as_ref() }.unwrap();
+        let parent_function = unsafe { (parent_function_address as *const Function).as_ref() }?;
         let parent_signature = parent_function.declaration().signature();
         let parent_compilation_data = compiler
             .get_compilation_data(&parent_signature)
@@ -67,15 +97,27 @@ impl<'a> StackFrame<'a> {
         }
     }
 
-    pub fn arguments(&'a self) -> StackFrameArgumentsIterator {
+    pub fn stack_trace(&self, compiler: &'a JitCompiler) -> Vec<StackTraceEntry> {
+        let mut stack_trace = Vec::new();
+        self.walk(compiler, |frame| {
+            stack_trace.push(StackTraceEntry {
+                signature: frame.function.declaration().signature(),
+                instruction_index: frame.compilation_data.mir_compilation_result.instructions[frame.instruction_index].index
+            });
+        });
+
+        stack_trace
+    }
+
+    pub fn arguments(&'a self) -> StackFrameArgumentsIterator<'a> {
         StackFrameArgumentsIterator::new(self)
     }
 
-    pub fn locals(&'a self) -> StackFrameLocalsIterator {
+    pub fn locals(&'a self) -> StackFrameLocalsIterator<'a> {
         StackFrameLocalsIterator::new(self)
     }
 
-    pub fn operands(&'a self) -> StackFrameOperandsIterator {
+    pub fn operands(&'a self) -> StackFrameOperandsIterator<'a> {
         StackFrameOperandsIterator::new(self)
     }
 
@@ -93,6 +135,20 @@ impl<'a> StackFrame<'a> {
         }
     }
 
+    /// Visits the references of the frame, including the ones embedded in structs.
+    pub fn visit_references<F: FnMut(*mut ObjectPointer)>(&self, mut apply: F) {
+        let struct_references = &self.compilation_data.mir_compilation_result.struct_references;
+        self.visit_values(|value| {
+            if value.value_type.is_reference() {
+                apply(value.ptr_mut() as *mut ObjectPointer);
+            } else if value.value_type.is_struct() {
+                for reference_offset in &struct_references[value.value_type] {
+                    apply(unsafe { value.ptr_mut().add(*reference_offset) } as *mut ObjectPointer);
+                }
+            }
+        });
+    }
+
     pub fn print_frame(&self) {
         println!("{} @ {}", self.function.declaration(), self.instruction_index);
 
@@ -131,7 +187,7 @@ impl<'a> StackFrameArgumentsIterator<'a> {
     }
 
     fn arguments(&self) -> &'a Vec<TypeId> {
-        &self.stack_frame.function.declaration().parameters()
+        self.stack_frame.function.declaration().parameters()
     }
 }
 
@@ -179,7 +235,7 @@ impl<'a> Iterator for StackFrameLocalsIterator<'a> {
         }
 
         let register = &self.locals()[self.index];
-        let value_offset = stack_layout::virtual_register_stack_offset(self.stack_frame.function, register.number);
+        let value_offset = stack_layout::virtual_register_stack_offset(&self.stack_frame.compilation_data.mir_compilation_result, &VirtualRegister::from(register));
         let value_ptr = (self.stack_frame.base_pointer as isize + value_offset as isize) as *mut u8;
 
         self.index += 1;
@@ -214,7 +270,7 @@ impl<'a> Iterator for StackFrameOperandsIterator<'a> {
         }
 
         let register = &self.operand_registers()[self.index];
-        let value_offset = stack_layout::virtual_register_stack_offset(self.stack_frame.function, register.number);
+        let value_offset = stack_layout::virtual_register_stack_offset(&self.stack_frame.compilation_data.mir_compilation_result, &VirtualRegister::from(register));
         let value_ptr = (self.stack_frame.base_pointer as isize + value_offset as isize) as *mut u8;
 
         self.index += 1;
@@ -245,6 +301,7 @@ impl<'a> FrameValue<'a> {
         }
     }
 
+    #[allow(dead_code)]
     pub fn ptr(&self) -> *const u8 {
         self.value_ptr as *const u8
     }
@@ -257,10 +314,19 @@ impl<'a> FrameValue<'a> {
         unsafe { *(self.value_ptr as *const u64) }
     }
 
+    pub fn value_i64(&self) -> i64 {
+        unsafe { *(self.value_ptr as *const i64) }
+    }
+
+    pub fn value_f64(&self) -> f64 {
+        unsafe { *(self.value_ptr as *const f64) }
+    }
+
     pub fn value_bool(&self) -> bool {
         self.value_u64() != 0
     }
 
+    #[allow(dead_code)]
     pub fn value_ptr(&self) -> ObjectPointer {
         unsafe { *(self.value_ptr as *const ObjectPointer) }
     }
@@ -283,9 +349,15 @@ impl<'a> std::fmt::Display for FrameValue<'a> {
             TypeId::Int32 => {
                 write!(f, "{}", self.value_u64())
             }
+            TypeId::Int64 => {
+                write!(f, "{}", self.value_i64())
+            }
             TypeId::Float32 => {
                 write!(f, "{}", self.value_u64())
             }
+            TypeId::Float64 => {
+                write!(f, "{}", self.value_f64())
+            }
             TypeId::Bool => {
                 if self.value_bool() {
                     write!(f, "true")
@@ -293,12 +365,21 @@ impl<'a> std::fmt::Display for FrameValue<'a> {
                     write!(f, "false")
                 }
             }
-            TypeId::Array(_) => {
+            TypeId::String => {
+                write!(f, "0x{:0x}", self.value_u64())
+            }
+            TypeId::Array(_) | TypeId::Array2D(_) => {
                 write!(f, "0x{:0x}", self.value_u64())
             }
             TypeId::Class(_) => {
                 write!(f, "0x{:0x}", self.value_u64())
             }
+            TypeId::Struct(_) => {
+                write!(f, "struct @ 0x{:0x}", self.value_ptr as u64)
+            }
+            TypeId::Function(_, _) => {
+                write!(f, "function @ 0x{:0x}", self.value_u64())
+            }
         }
     }
 }
@@ -309,8 +390,10 @@ fn instruction_index_from_offset(compilation_data: &FunctionCompilationData, off
             if offset >= compilation_data.instructions_offsets[index].1 && offset <= compilation_data.instructions_offsets[index + 1].1 {
                 return Some(compilation_data.instructions_offsets[index].0);
             }
+        } else if offset >= compilation_data.instructions_offsets[index].1 {
+            return Some(compilation_data.instructions_offsets[index].0);
         }
     }
 
-    return None;
+    None
 }
\ No newline at end of file
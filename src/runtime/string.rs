@@ -0,0 +1,95 @@
+use std::cmp::Ordering;
+
+use crate::engine::execution::RuntimeError;
+use crate::model::typesystem::{Type, TypeId};
+use crate::runtime::memory::manager::MemoryManager;
+use crate::runtime::object::ObjectPointer;
+use crate::runtime::runtime_interface;
+use crate::vm::get_vm;
+
+// Strings are stored as UTF-8 bytes, prefixed by the number of bytes
+pub const LENGTH_SIZE: usize = 4;
+
+pub fn get_length(ptr: ObjectPointer) -> usize {
+    (unsafe { (ptr as *const i32).read_unaligned() }) as usize
+}
+
+pub fn initialize(ptr: ObjectPointer, text: &str) {
+    unsafe {
+        (ptr as *mut i32).write_unaligned(text.len() as i32);
+        std::ptr::copy_nonoverlapping(text.as_ptr(), ptr.add(LENGTH_SIZE) as *mut u8, text.len());
+    }
+}
+
+pub fn as_str<'a>(ptr: ObjectPointer) -> &'a str {
+    // Null is treated as the empty string
+    if ptr.is_null() {
+        return "";
+    }
+
+    unsafe {
+        let bytes = std::slice::from_raw_parts(ptr.add(LENGTH_SIZE) as *const u8, get_length(ptr));
+        std::str::from_utf8_unchecked(bytes)
+    }
+}
+
+fn new_string(text: &str) -> ObjectPointer {
+    get_vm(|vm| {
+        let string_type = vm.type_storage.entry(TypeId::String) as *const Type;
+        let caller = vm.engine.runtime_error.external_caller();
+        let (base_pointer, function_ptr, instruction_index) = (caller.base_pointer, caller.function, caller.instruction_index);
+
+        let obj_ptr = runtime_interface::allocate_object(
+            vm,
+            base_pointer,
+            function_ptr,
+            instruction_index,
+            MemoryManager::string_size(text),
+            |memory_manager| memory_manager.new_string(unsafe { &*string_type }, text)
+        );
+
+        // The generated code checks for the error after the call returns
+        if obj_ptr.is_null() {
+            vm.engine.runtime_error.set_native_error(RuntimeError::OutOfMemory);
+        }
+
+        obj_ptr
+    })
+}
+
+pub extern "C" fn length(string: ObjectPointer) -> i32 {
+    as_str(string).chars().count() as i32
+}
+
+pub extern "C" fn concat(string1: ObjectPointer, string2: ObjectPointer) -> ObjectPointer {
+    let mut result = as_str(string1).to_owned();
+    result.push_str(as_str(string2));
+    new_string(&result)
+}
+
+pub extern "C" fn substring(string: ObjectPointer, start: i32, length: i32) -> ObjectPointer {
+    // The range is clamped to the string
+    let result = as_str(string)
+        .chars()
+        .skip(start.max(0) as usize)
+        .take(length.max(0) as usize)
+        .collect::<String>();
+
+    new_string(&result)
+}
+
+pub extern "C" fn compare(string1: ObjectPointer, string2: ObjectPointer) -> i32 {
+    match as_str(string1).cmp(as_str(string2)) {
+        Ordering::Less => -1,
+        Ordering::Equal => 0,
+        Ordering::Greater => 1
+    }
+}
+
+pub extern "C" fn from_int(value: i32) -> ObjectPointer {
+    new_string(&value.to_string())
+}
+
+pub extern "C" fn from_float(value: f32) -> ObjectPointer {
+    new_string(&value.to_string())
+}
@@ -1,49 +1,269 @@
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 
 use crate::engine::ExecutionEngine;
-use crate::model::typesystem::{TypeStorage};
+use crate::model::typesystem::{TypeStorage, TypeId};
 use crate::engine::execution::{ExecutionEngineResult, ExecutionEngineError};
 use crate::runtime::memory::manager::MemoryManager;
-use crate::model::function::{Function, FunctionStorage, FunctionDeclaration};
+use crate::runtime::memory::heap::HeapSettings;
+use crate::runtime::memory::handles::GcRoot;
+use crate::model::function::{Function, FunctionStorage, FunctionDeclaration, FunctionSignature};
 use crate::model::class::Class;
+use crate::model::exception;
+use crate::runtime::io::Console;
+use crate::runtime::native::{NativeFunction, NativeFunctions};
+use crate::runtime::object::{ObjectPointer, ObjectReference};
 
 pub struct VirtualMachine {
-    type_storage: TypeStorage,
+    pub type_storage: TypeStorage,
     function_storage: FunctionStorage,
     pub engine: ExecutionEngine,
-    pub memory_manager: MemoryManager
+    pub memory_manager: MemoryManager,
+    pub console: Console,
+    #[allow(dead_code)]
+    pub native_functions: NativeFunctions,
+    // Set when functions or classes are added that have not been compiled yet
+    needs_preparation: bool
 }
 
 impl VirtualMachine {
     pub fn new() -> VirtualMachine {
+        VirtualMachine::create(HeapSettings::default())
+    }
+
+    #[allow(dead_code)]
+    pub fn with_heap_settings(heap_settings: HeapSettings) -> ExecutionEngineResult<VirtualMachine> {
+        heap_settings.validate().map_err(ExecutionEngineError::Other)?;
+        Ok(VirtualMachine::create(heap_settings))
+    }
+
+    fn create(heap_settings: HeapSettings) -> VirtualMachine {
+        let mut type_storage = TypeStorage::new();
+        exception::add_builtin_exceptions(&mut type_storage);
+
         VirtualMachine {
-            type_storage: TypeStorage::new(),
+            type_storage,
             function_storage: FunctionStorage::new(),
             engine: ExecutionEngine::new(),
-            memory_manager: MemoryManager::new()
+            memory_manager: MemoryManager::new(&heap_settings),
+            console: Console::default(),
+            native_functions: NativeFunctions::new(),
+            needs_preparation: true
         }
     }
 
+    #[allow(dead_code)]
+    pub fn set_max_stack_size(&mut self, max_stack_size: usize) {
+        self.engine.stack_limit.set_max_stack_size(max_stack_size);
+    }
+
+    #[allow(dead_code)]
+    pub fn set_output(&mut self, output: Box<dyn std::io::Write>) {
+        self.console.output = output;
+    }
+
+    #[allow(dead_code)]
+    pub fn set_input(&mut self, input: Box<dyn std::io::BufRead>) {
+        self.console.input = input;
+    }
+
     pub fn add_function(&mut self, function: Function) -> ExecutionEngineResult<()> {
         self.engine.binder_mut().define(function.declaration().clone());
         self.function_storage.add_function(function);
+        self.needs_preparation = true;
         Ok(())
     }
 
+    #[allow(dead_code)]
     pub fn add_external_function(&mut self, declaration: FunctionDeclaration) {
         self.engine.binder_mut().define(declaration);
+        self.needs_preparation = true;
+    }
+
+    #[allow(dead_code)]
+    pub fn register_native<F: NativeFunction<Args>, Args>(&mut self, name: &str, function: F) -> ExecutionEngineResult<()> {
+        let mut declaration = FunctionDeclaration::with_native(
+            name.to_owned(),
+            F::parameters(),
+            F::return_type(),
+            F::trampoline()
+        );
+
+        if declaration.parameters().iter().any(|parameter| parameter == &TypeId::Void || has_void_element(parameter))
+            || has_void_element(declaration.return_type()) {
+            return Err(ExecutionEngineError::Other(format!("The function '{}' has a void parameter or element type.", declaration)));
+        }
+
+        if self.engine.binder_mut().get(&declaration.signature()).is_some() {
+            return Err(ExecutionEngineError::Other(format!("The function '{}' is already defined.", declaration)));
+        }
+
+        let slot = self.native_functions.add(function);
+        let stub = self.engine.generate_native_stub(self.native_functions.current_slot_address(), slot, F::trampoline());
+        declaration.set_address(stub);
+        self.engine.binder_mut().define(declaration);
+        self.needs_preparation = true;
+        Ok(())
     }
 
-    pub fn add_class(&mut self, class: Class) {
-        self.type_storage.add_class(class);
+    pub fn add_class(&mut self, class: Class) -> ExecutionEngineResult<()> {
+        let interface_functions = class.interface_functions().cloned().unwrap_or_default();
+        self.type_storage.add_class(class).map_err(ExecutionEngineError::Verify)?;
+
+        for function in interface_functions {
+            self.engine.binder_mut().define(function);
+        }
+
+        self.needs_preparation = true;
+        Ok(())
     }
 
     pub fn execute(mut self) -> ExecutionEngineResult<i32> {
         self.create_execution()?.execute(self)
     }
 
+    #[allow(dead_code)]
+    pub fn call(&mut self, signature: &FunctionSignature, arguments: &[Value]) -> ExecutionEngineResult<Value> {
+        if self.needs_preparation {
+            self.engine.prepare_execution(&mut self.type_storage, &mut self.function_storage, &mut self.memory_manager)?;
+            self.needs_preparation = false;
+        }
+
+        let (function, invoker) = self.engine.get_invoker(signature)?;
+
+        if function.parameters().len() != arguments.len() {
+            return Err(ExecutionEngineError::Other(format!(
+                "Expected {} arguments but got {} when calling '{}'.",
+                function.parameters().len(),
+                arguments.len(),
+                signature
+            )));
+        }
+
+        if function.parameters().iter().any(|parameter| parameter.is_struct()) || function.return_type().is_struct() {
+            return Err(ExecutionEngineError::Other(format!("Structs can't be passed to or returned from '{}' when calling it from the host.", signature)));
+        }
+
+        for (argument, parameter) in arguments.iter().zip(function.parameters()) {
+            if !self.memory_manager.is_value_of_type(argument, parameter) {
+                return Err(ExecutionEngineError::Other(format!(
+                    "Expected argument of type {} but got {:?} when calling '{}'.",
+                    parameter,
+                    argument,
+                    signature
+                )));
+            }
+        }
+
+        let raw_arguments = arguments.iter().map(|argument| argument.to_raw()).collect::<Vec<_>>();
+
+        let entry_state = self.engine.entry_state();
+
+        // Managed code reaches the VM through this pointer, so it is used instead of self until the call returns
+        let vm_ptr: *mut VirtualMachine = self;
+        let previous_vm = ACTIVE_VIRTUAL_MACHINE.with(|vm| vm.replace(vm_ptr));
+        let raw_result = invoker(raw_arguments.as_ptr());
+        ACTIVE_VIRTUAL_MACHINE.with(|vm| vm.set(previous_vm));
+
+        // A call made from a native function must leave the state of the managed code that called the native function intact
+        let vm = unsafe { &mut *vm_ptr };
+        vm.engine.restore_entry_state(entry_state);
+        if let Some((err, stack_trace)) = vm.engine.take_runtime_error() {
+            return Err(ExecutionEngineError::Runtime(err, stack_trace));
+        }
+
+        Ok(Value::from_raw(function.return_type(), raw_result))
+    }
+
+    /// Roots a reference returned from managed code, such that it stays valid across collections. Returns None for non-references.
+    #[allow(dead_code)]
+    pub fn create_root(&mut self, value: Value) -> Option<GcRoot> {
+        match value {
+            Value::Reference(object) => Some(self.memory_manager.create_root(object)),
+            _ => None
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn release_root(&mut self, root: GcRoot) {
+        self.memory_manager.release_root(root);
+    }
+
+    /// Returns the rooted reference as a value that can be passed to managed code.
+    #[allow(dead_code)]
+    pub fn root_value(&self, root: &GcRoot) -> Value {
+        Value::Reference(self.memory_manager.root_object(root))
+    }
+
     pub fn create_execution(&mut self) -> ExecutionEngineResult<Execution> {
-        self.engine.create_execution(&mut self.type_storage, &mut self.function_storage)
+        let execution = self.engine.create_execution(&mut self.type_storage, &mut self.function_storage, &mut self.memory_manager)?;
+        self.needs_preparation = false;
+        Ok(execution)
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Value {
+    Void,
+    Int32(i32),
+    Float32(f32),
+    Int64(i64),
+    Float64(f64),
+    Bool(bool),
+    Reference(ObjectPointer)
+}
+
+impl Value {
+    #[allow(dead_code)]
+    /// A non-null reference must point to an object, see MemoryManager::is_value_of_type for values given by the host.
+    pub fn is_of_type(&self, value_type: &TypeId) -> bool {
+        match (self, value_type) {
+            (Value::Void, TypeId::Void) => true,
+            (Value::Int32(_), TypeId::Int32) => true,
+            (Value::Float32(_), TypeId::Float32) => true,
+            (Value::Int64(_), TypeId::Int64) => true,
+            (Value::Float64(_), TypeId::Float64) => true,
+            (Value::Bool(_), TypeId::Bool) => true,
+            (Value::Reference(ptr), value_type) if value_type.is_reference() => {
+                ptr.is_null() || ObjectReference::from_ptr(*ptr).map(|object| object.object_type().is_subtype_of(value_type)).unwrap_or(false)
+            }
+            _ => false
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn to_raw(self) -> u64 {
+        match self {
+            Value::Void => 0,
+            Value::Int32(value) => value as u32 as u64,
+            Value::Float32(value) => value.to_bits() as u64,
+            Value::Int64(value) => value as u64,
+            Value::Float64(value) => value.to_bits(),
+            Value::Bool(value) => value as u64,
+            Value::Reference(value) => value as u64
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn from_raw(value_type: &TypeId, raw: u64) -> Value {
+        match value_type {
+            TypeId::Void => Value::Void,
+            TypeId::Int32 => Value::Int32(raw as u32 as i32),
+            TypeId::Float32 => Value::Float32(f32::from_bits(raw as u32)),
+            TypeId::Int64 => Value::Int64(raw as i64),
+            TypeId::Float64 => Value::Float64(f64::from_bits(raw)),
+            // Only the lowest byte of a bool is defined
+            TypeId::Bool => Value::Bool(raw as u8 != 0),
+            TypeId::String | TypeId::Array(_) | TypeId::Array2D(_) | TypeId::Class(_) | TypeId::Function(_, _) => Value::Reference(raw as ObjectPointer),
+            TypeId::Struct(_) => panic!("Structs are not returned as raw values.")
+        }
+    }
+}
+
+fn has_void_element(type_id: &TypeId) -> bool {
+    match type_id {
+        TypeId::Array(element) => element.as_ref() == &TypeId::Void || has_void_element(element),
+        _ => false
     }
 }
 
@@ -63,15 +283,14 @@ impl Execution {
     pub fn execute(&mut self, virtual_machine: VirtualMachine) -> ExecutionEngineResult<i32> {
         assign_vm(virtual_machine);
         let execution_result = (self.entrypoint)();
-        let result = get_vm(|vm| {
-            if let Some(err) = vm.engine.take_runtime_error() {
-                Err(ExecutionEngineError::Runtime(err))
+
+        get_vm(|vm| {
+            if let Some((err, stack_trace)) = vm.engine.take_runtime_error() {
+                Err(ExecutionEngineError::Runtime(err, stack_trace))
             } else {
                 Ok(execution_result)
             }
-        });
-
-        result
+        })
     }
 }
 
@@ -82,15 +301,23 @@ pub fn assign_vm(virtual_machine: VirtualMachine) {
 }
 
 pub fn get_vm<F: FnMut(&mut VirtualMachine) -> R, R>(mut f: F) -> R {
+    // The caller of VirtualMachine::call does not use the VM until the call returns
+    let active_vm = ACTIVE_VIRTUAL_MACHINE.with(|vm| vm.get());
+    if let Some(active_vm) = unsafe { active_vm.as_mut() } {
+        return f(active_vm);
+    }
+
     VIRTUAL_MACHINE_INSTANCE.with(|vm| {
         f(vm.borrow_mut().as_mut().unwrap())
     })
 }
 
+#[allow(dead_code)]
 pub fn clear_vm() {
     VIRTUAL_MACHINE_INSTANCE.with(|vm_ref| {
         *vm_ref.borrow_mut() = None;
     });
 }
 
-thread_local!(static VIRTUAL_MACHINE_INSTANCE: RefCell<Option<VirtualMachine>> = RefCell::new(None));
+thread_local!(static VIRTUAL_MACHINE_INSTANCE: RefCell<Option<VirtualMachine>> = const { RefCell::new(None) });
+thread_local!(static ACTIVE_VIRTUAL_MACHINE: Cell<*mut VirtualMachine> = const { Cell::new(std::ptr::null_mut()) });